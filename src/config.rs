@@ -0,0 +1,35 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::path::PathBuf;
+
+/*
+ * Settings that belong to the installation rather than to a celestial system are stored in the user's configuration directory.
+ */
+fn config_path(file_name: &str) -> Option<PathBuf> {
+    let config_dir = std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("APPDATA").map(PathBuf::from))
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+    Some(config_dir.join("elenath").join(file_name))
+}
+
+/*
+ * Falls back to the default if nothing was saved yet, or the saved settings cannot be read.
+ */
+pub(crate) fn load<T: DeserializeOwned + Default>(file_name: &str) -> T {
+    config_path(file_name)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+pub(crate) fn save<T: Serialize>(file_name: &str, settings: &T) -> Result<(), std::io::Error> {
+    let path = config_path(file_name).ok_or(std::io::Error::new(
+        std::io::ErrorKind::NotFound,
+        "No configuration directory found.",
+    ))?;
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let content = serde_json::to_string_pretty(settings)?;
+    std::fs::write(path, content)
+}
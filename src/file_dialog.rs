@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 fn current_path() -> PathBuf {
     match std::env::current_dir() {
@@ -7,17 +7,116 @@ fn current_path() -> PathBuf {
     }
 }
 
-pub(crate) fn new() -> Option<PathBuf> {
+/*
+ * Dialogs start in the project folder, so that everything belonging to a project ends up next to its save file.
+ */
+fn start_directory(project_folder: Option<&Path>) -> PathBuf {
+    match project_folder {
+        Some(folder) => folder.to_path_buf(),
+        None => current_path(),
+    }
+}
+
+pub(crate) fn new(project_folder: Option<&Path>) -> Option<PathBuf> {
     rfd::FileDialog::new()
         .set_file_name("celestial_system.json")
-        .set_directory(current_path())
+        .set_directory(start_directory(project_folder))
         .save_file()
 }
 
-pub(crate) fn open() -> Option<PathBuf> {
+pub(crate) fn open(project_folder: Option<&Path>) -> Option<PathBuf> {
     rfd::FileDialog::new()
         .add_filter("Celestial System (.json)", &["json"])
         .add_filter("Any", &["*"])
-        .set_directory(current_path())
+        .set_directory(start_directory(project_folder))
+        .pick_file()
+}
+
+pub(crate) fn open_gaia_export(project_folder: Option<&Path>) -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("Gaia Archive Export (.csv)", &["csv"])
+        .add_filter("Any", &["*"])
+        .set_directory(start_directory(project_folder))
+        .pick_file()
+}
+
+pub(crate) fn open_space_engine_script(project_folder: Option<&Path>) -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("SpaceEngine Script (.sc)", &["sc"])
+        .add_filter("Any", &["*"])
+        .set_directory(start_directory(project_folder))
+        .pick_file()
+}
+
+pub(crate) fn save_stars(project_folder: Option<&Path>) -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("Stars (.json)", &["json"])
+        .add_filter("VOTable (.vot)", &["vot", "xml"])
+        .set_file_name("stars.json")
+        .set_directory(start_directory(project_folder))
+        .save_file()
+}
+
+pub(crate) fn save_table(project_folder: Option<&Path>) -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("Comma-Separated Values (.csv)", &["csv"])
+        .add_filter("Tab-Separated Values (.tsv)", &["tsv"])
+        .set_file_name("table.csv")
+        .set_directory(start_directory(project_folder))
+        .save_file()
+}
+
+pub(crate) fn save_sky_calendar(project_folder: Option<&Path>) -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("Web Page (.html)", &["html"])
+        .add_filter("Markdown (.md)", &["md"])
+        .set_file_name("sky_calendar.html")
+        .set_directory(start_directory(project_folder))
+        .save_file()
+}
+
+pub(crate) fn open_background_image(project_folder: Option<&Path>) -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("Image (.png, .jpg, .ppm)", &["png", "jpg", "jpeg", "ppm"])
+        .set_directory(start_directory(project_folder))
+        .pick_file()
+}
+
+pub(crate) fn save_screenshot(project_folder: Option<&Path>) -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("PNG image (.png)", &["png"])
+        .add_filter("Portable Pixmap (.ppm)", &["ppm"])
+        .set_file_name("screenshot.png")
+        .set_directory(start_directory(project_folder))
+        .save_file()
+}
+
+pub(crate) fn save_command_log(project_folder: Option<&Path>) -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("Command Log (.json)", &["json"])
+        .set_file_name("command_log.json")
+        .set_directory(start_directory(project_folder))
+        .save_file()
+}
+
+pub(crate) fn open_command_log(project_folder: Option<&Path>) -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("Command Log (.json)", &["json"])
+        .set_directory(start_directory(project_folder))
+        .pick_file()
+}
+
+pub(crate) fn save_view_configuration(project_folder: Option<&Path>) -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("View Configuration (.json)", &["json"])
+        .set_file_name("view_configuration.json")
+        .set_directory(start_directory(project_folder))
+        .save_file()
+}
+
+pub(crate) fn open_view_configuration(project_folder: Option<&Path>) -> Option<PathBuf> {
+    rfd::FileDialog::new()
+        .add_filter("View Configuration (.json)", &["json"])
+        .set_directory(start_directory(project_folder))
         .pick_file()
 }
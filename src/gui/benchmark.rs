@@ -0,0 +1,126 @@
+use astro_coords::cartesian::Cartesian;
+use astro_utils::{
+    planets::random_planets::generate_random_planet,
+    real_data::stars::{all::get_many_stars, sun},
+    stars::data::StarData,
+};
+use iced::Size;
+use std::time::Instant;
+use uom::si::{
+    f64::{Length, Time},
+    length::light_year,
+    time::year,
+};
+
+use super::{gui_widget::group_digits, surface_view::widget::SurfaceViewState};
+use crate::model::{
+    celestial_system::CelestialSystem, galactic_motion::standard_normal,
+    star_generation::SplitMix64, star_provenance::StarOrigin,
+};
+
+const STAR_COUNT: usize = 200_000;
+const PLANET_COUNT: usize = 50;
+const MAX_DISTANCE_IN_LIGHT_YEARS: f64 = 5000.;
+const SEED: u64 = 42;
+const CANVAS_SIZE: Size = Size::new(1920., 1080.);
+
+/*
+ * Measures the operations that slow down with the size of a system, on a synthetic system far larger than real use.
+ * The stars are real stars moved to random places, so that every release benchmarks the same system.
+ * Drawing the surface view is measured without the rasterisation, which needs a window.
+ * The report is meant to be compared between releases on the same machine.
+ */
+pub(crate) fn run() -> String {
+    let mut report = vec![format!(
+        "Benchmark with {} stars and {} planets",
+        group_digits(STAR_COUNT),
+        PLANET_COUNT
+    )];
+
+    let mut system = timed(&mut report, "Generating the system", synthetic_system);
+    timed(&mut report, "Advancing the time by 1000 years", || {
+        system.set_time_since_epoch(Time::new::<year>(1000.))
+    });
+    let Some(planet) = system.get_planets().into_iter().next() else {
+        report.push("The system has no planet to observe from.".to_string());
+        return report.join("\n");
+    };
+    let surface_view = SurfaceViewState::new();
+    let projected = timed(&mut report, "Projecting the surface view", || {
+        surface_view.project_stars(&planet, &system, CANVAS_SIZE)
+    });
+    report.push(format!("  {} stars in view", group_digits(projected)));
+
+    let path = std::env::temp_dir().join("elenath_benchmark.json");
+    let saved = timed(&mut report, "Saving", || system.write_to_file(path.clone()));
+    let loaded = timed(&mut report, "Loading", || {
+        CelestialSystem::read_from_file(path.clone())
+    });
+    if let Ok(metadata) = std::fs::metadata(&path) {
+        report.push(format!(
+            "  {} MB on disk",
+            group_digits((metadata.len() / 1_000_000) as usize)
+        ));
+    }
+    if let Err(e) = saved.and(loaded) {
+        report.push(format!("Saving and loading failed: {}", e));
+    }
+    let _ = std::fs::remove_file(path);
+    report.join("\n")
+}
+
+fn timed<T>(report: &mut Vec<String>, label: &str, operation: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = operation();
+    report.push(format!(
+        "{:<36}{:>10.3} s",
+        label,
+        start.elapsed().as_secs_f64()
+    ));
+    result
+}
+
+fn synthetic_system() -> CelestialSystem {
+    let mut system = CelestialSystem::empty();
+    system.overwrite_star_data(None, sun().to_star_data());
+    for i in 0..PLANET_COUNT {
+        let mut planet = generate_random_planet();
+        planet.set_name(format!("Benchmark {}", i));
+        let _ = system.add_planet_data(planet);
+    }
+    let templates: Vec<StarData> = get_many_stars()
+        .iter()
+        .map(|star| star.to_star_data())
+        .collect();
+    let mut random = SplitMix64(SEED);
+    let stars = (0..STAR_COUNT)
+        .map(|i| {
+            let mut star = templates[i % templates.len()].clone();
+            star.set_name(format!("Benchmark {}", i));
+            star.set_pos_at_epoch(random_position(&mut random));
+            star
+        })
+        .collect();
+    system.add_stars_from_data(stars, StarOrigin::Random);
+    system
+}
+
+/*
+ * Uniformly distributed within a sphere.
+ */
+fn random_position(random: &mut SplitMix64) -> Cartesian {
+    let direction = [(); 3].map(|_| standard_normal(random));
+    let norm = direction
+        .iter()
+        .map(|x| x * x)
+        .sum::<f64>()
+        .sqrt()
+        .max(f64::EPSILON);
+    let distance = MAX_DISTANCE_IN_LIGHT_YEARS * random.next_f64().cbrt();
+    let component = |x: f64| Length::new::<light_year>(distance * x / norm);
+    Cartesian::new(
+        component(direction[0]),
+        component(direction[1]),
+        component(direction[2]),
+    )
+}
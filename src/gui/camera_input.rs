@@ -0,0 +1,191 @@
+use iced::{
+    keyboard::{self, key::Named, Key},
+    mouse::{self, Cursor, ScrollDelta},
+    widget::canvas,
+    Point, Rectangle, Vector,
+};
+use uom::si::{angle::degree, f64::Angle};
+
+use super::touch::TouchGesture;
+
+const KEY_PAN_IN_PIXELS: f32 = 20.;
+const KEY_ZOOM: f32 = 1.25;
+const WHEEL_ZOOM_PER_LINE: f32 = 1.1;
+const PIXELS_PER_WHEEL_LINE: f32 = 50.;
+const WALK_STEP_IN_DEGREES: f64 = 1.;
+
+/*
+ * What the mouse or the keyboard asks of the camera.
+ * Turning and zooming are the same gestures as two fingers on a touch screen, so the views handle them alike.
+ * Walking moves the observer across the surface of the planet.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum CameraCommand {
+    Gesture(TouchGesture),
+    Walk(Angle, Angle),
+}
+
+/*
+ * Dragging with the right mouse button turns the view, because the left button already selects regions and planets.
+ * The wheel zooms, the arrow keys or WASD turn the view, plus and minus zoom, and with Shift the arrow keys walk.
+ * Keys only count while the cursor is over the canvas, so that typing into a text field does not move the camera.
+ */
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CameraInput {
+    drag_position: Option<Point>,
+}
+
+impl CameraInput {
+    pub(crate) fn handle(
+        &mut self,
+        event: &canvas::Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> Option<CameraCommand> {
+        match event {
+            canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right)) => {
+                self.drag_position = Some(cursor.position_over(bounds)?);
+                None
+            }
+            canvas::Event::Mouse(mouse::Event::CursorMoved { position }) => {
+                let previous = self.drag_position?;
+                self.drag_position = Some(*position);
+                Some(CameraCommand::Gesture(pan(*position - previous)))
+            }
+            canvas::Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Right)) => {
+                self.drag_position = None;
+                None
+            }
+            canvas::Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                cursor.position_over(bounds)?;
+                let lines = match *delta {
+                    ScrollDelta::Lines { y, .. } => y,
+                    ScrollDelta::Pixels { y, .. } => y / PIXELS_PER_WHEEL_LINE,
+                };
+                Some(CameraCommand::Gesture(zoom(
+                    WHEEL_ZOOM_PER_LINE.powf(lines),
+                )))
+            }
+            canvas::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => {
+                cursor.position_over(bounds)?;
+                key_command(key, modifiers.shift())
+            }
+            _ => None,
+        }
+    }
+}
+
+fn pan(pan: Vector) -> TouchGesture {
+    TouchGesture { zoom: 1., pan }
+}
+
+fn zoom(zoom: f32) -> TouchGesture {
+    TouchGesture {
+        zoom,
+        pan: Vector::ZERO,
+    }
+}
+
+/*
+ * Looking to the right moves the sky to the left, as if it had been dragged there.
+ */
+fn key_command(key: &Key, shift: bool) -> Option<CameraCommand> {
+    let (right, up) = match key.as_ref() {
+        Key::Named(Named::ArrowLeft) => (-1., 0.),
+        Key::Named(Named::ArrowRight) => (1., 0.),
+        Key::Named(Named::ArrowUp) => (0., 1.),
+        Key::Named(Named::ArrowDown) => (0., -1.),
+        Key::Character(c) => match c.to_lowercase().as_str() {
+            "a" => (-1., 0.),
+            "d" => (1., 0.),
+            "w" => (0., 1.),
+            "s" => (0., -1.),
+            "+" | "=" => return Some(CameraCommand::Gesture(zoom(KEY_ZOOM))),
+            "-" => return Some(CameraCommand::Gesture(zoom(1. / KEY_ZOOM))),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    if shift {
+        let step = |direction: f64| Angle::new::<degree>(direction * WALK_STEP_IN_DEGREES);
+        return Some(CameraCommand::Walk(step(right), step(up)));
+    }
+    Some(CameraCommand::Gesture(pan(Vector::new(
+        -right as f32 * KEY_PAN_IN_PIXELS,
+        up as f32 * KEY_PAN_IN_PIXELS,
+    ))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOUNDS: Rectangle = Rectangle {
+        x: 0.,
+        y: 0.,
+        width: 100.,
+        height: 100.,
+    };
+
+    fn mouse_event(input: &mut CameraInput, event: mouse::Event) -> Option<CameraCommand> {
+        let cursor = Cursor::Available(Point::new(50., 50.));
+        input.handle(&canvas::Event::Mouse(event), BOUNDS, cursor)
+    }
+
+    #[test]
+    fn right_drag_pans_until_released() {
+        let mut input = CameraInput::default();
+        let moved = |position| mouse::Event::CursorMoved { position };
+        assert!(mouse_event(&mut input, moved(Point::new(10., 10.))).is_none());
+        mouse_event(
+            &mut input,
+            mouse::Event::ButtonPressed(mouse::Button::Right),
+        );
+        let command = mouse_event(&mut input, moved(Point::new(60., 45.)));
+        assert_eq!(
+            command,
+            Some(CameraCommand::Gesture(pan(Vector::new(10., -5.))))
+        );
+        mouse_event(
+            &mut input,
+            mouse::Event::ButtonReleased(mouse::Button::Right),
+        );
+        assert!(mouse_event(&mut input, moved(Point::new(70., 45.))).is_none());
+    }
+
+    #[test]
+    fn scrolling_up_zooms_in() {
+        let mut input = CameraInput::default();
+        let scrolled = || mouse::Event::WheelScrolled {
+            delta: ScrollDelta::Lines { x: 0., y: 1. },
+        };
+        let Some(CameraCommand::Gesture(gesture)) = mouse_event(&mut input, scrolled()) else {
+            panic!("Scrolling should zoom");
+        };
+        assert!(gesture.zoom > 1.);
+        let outside = Cursor::Available(Point::new(150., 50.));
+        let event = canvas::Event::Mouse(scrolled());
+        assert!(input.handle(&event, BOUNDS, outside).is_none());
+    }
+
+    #[test]
+    fn keys_turn_the_view_and_walk_with_shift() {
+        let up = Key::Named(Named::ArrowUp);
+        assert_eq!(
+            key_command(&up, false),
+            Some(CameraCommand::Gesture(pan(Vector::new(
+                0.,
+                KEY_PAN_IN_PIXELS
+            ))))
+        );
+        let d = Key::Character("D".into());
+        assert_eq!(
+            key_command(&d, true),
+            Some(CameraCommand::Walk(
+                Angle::new::<degree>(WALK_STEP_IN_DEGREES),
+                Angle::new::<degree>(0.)
+            ))
+        );
+        assert!(key_command(&Key::Character("x".into()), false).is_none());
+    }
+}
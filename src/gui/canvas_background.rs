@@ -0,0 +1,287 @@
+use iced::{
+    widget::{
+        canvas::{self, Path},
+        image::Handle,
+    },
+    Color, Point, Rectangle, Size,
+};
+use image::{ImageReader, Limits, RgbaImage};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::Display,
+    io::Cursor,
+    path::{Path as FilePath, PathBuf},
+    sync::Arc,
+};
+
+use crate::{config, error::ElenathError, model::star_generation::SplitMix64, project_folder};
+
+const FILE_NAME: &str = "canvas_background.json";
+const DEEP_BLUE: Color = Color::from_rgb(0.01, 0.02, 0.09);
+const NOISE_BASE: Color = Color::from_rgb(0.015, 0.015, 0.02);
+const NOISE_SEED: u64 = 0x5EED;
+// Every speck covers this many square pixels of canvas on average.
+const PIXELS_PER_NOISE_SPECK: f32 = 40.;
+const MAX_NOISE_SPECKS: usize = 60_000;
+// Larger images are refused, because they take a lot of memory for no visible gain.
+const MAX_IMAGE_SIDE: u32 = 16_384;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub(crate) enum BackgroundStyle {
+    #[default]
+    Black,
+    DeepBlue,
+    Noise,
+    Image(PathBuf),
+}
+
+/*
+ * The entries of the background picker. Choosing an image opens a file dialog rather than selecting a style directly.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum BackgroundChoice {
+    Black,
+    DeepBlue,
+    Noise,
+    Image,
+}
+
+impl BackgroundChoice {
+    pub(crate) const ALL: [BackgroundChoice; 4] = [
+        BackgroundChoice::Black,
+        BackgroundChoice::DeepBlue,
+        BackgroundChoice::Noise,
+        BackgroundChoice::Image,
+    ];
+}
+
+impl Display for BackgroundChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BackgroundChoice::Black => write!(f, "Black"),
+            BackgroundChoice::DeepBlue => write!(f, "Deep Blue"),
+            BackgroundChoice::Noise => write!(f, "Noise"),
+            BackgroundChoice::Image => write!(f, "Image..."),
+        }
+    }
+}
+
+impl BackgroundStyle {
+    pub(crate) fn choice(&self) -> BackgroundChoice {
+        match self {
+            BackgroundStyle::Black => BackgroundChoice::Black,
+            BackgroundStyle::DeepBlue => BackgroundChoice::DeepBlue,
+            BackgroundStyle::Noise => BackgroundChoice::Noise,
+            BackgroundStyle::Image(_) => BackgroundChoice::Image,
+        }
+    }
+
+    pub(crate) fn relative_to(self, folder: &FilePath) -> Self {
+        match self {
+            BackgroundStyle::Image(path) => {
+                BackgroundStyle::Image(project_folder::to_relative(&path, folder))
+            }
+            style => style,
+        }
+    }
+
+    pub(crate) fn resolved_in(self, folder: &FilePath) -> Self {
+        match self {
+            BackgroundStyle::Image(path) => {
+                BackgroundStyle::Image(project_folder::to_absolute(&path, folder))
+            }
+            style => style,
+        }
+    }
+}
+
+/*
+ * A user supplied backdrop, decoded once when it is chosen so that a broken file is reported right away.
+ * PNG, JPEG and the Netpbm colour format (.ppm) are supported.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BackgroundImage {
+    width: u32,
+    height: u32,
+    handle: Handle,
+}
+
+impl BackgroundImage {
+    pub(crate) fn read(path: &FilePath) -> Result<Self, ElenathError> {
+        Self::from_bytes(&std::fs::read(path)?)
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Self, ElenathError> {
+        let pixels = decode(bytes)?;
+        let (width, height) = pixels.dimensions();
+        Ok(BackgroundImage {
+            width,
+            height,
+            handle: Handle::from_rgba(width, height, pixels.into_raw()),
+        })
+    }
+
+    /*
+     * Scales the image to cover the whole canvas, cropping whatever sticks out on either side.
+     */
+    pub(crate) fn draw(&self, bounds: Rectangle, frame: &mut canvas::Frame, opacity: f32) {
+        let scale = (bounds.width / self.width as f32).max(bounds.height / self.height as f32);
+        let size = Size::new(self.width as f32 * scale, self.height as f32 * scale);
+        let top_left = Point::new(
+            (bounds.width - size.width) / 2.,
+            (bounds.height - size.height) / 2.,
+        );
+        frame.draw_image(
+            Rectangle::new(top_left, size),
+            canvas::Image::new(self.handle.clone()).opacity(opacity),
+        );
+    }
+}
+
+/*
+ * The header of an image states its size, so without limits a few bytes could ask for any amount of memory.
+ */
+fn decode(bytes: &[u8]) -> Result<RgbaImage, ElenathError> {
+    let error = |e: image::ImageError| ElenathError::Generic(format!("Invalid image: {}", e));
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_IMAGE_SIDE);
+    limits.max_image_height = Some(MAX_IMAGE_SIDE);
+    let mut reader = ImageReader::new(Cursor::new(bytes)).with_guessed_format()?;
+    reader.limits(limits);
+    Ok(reader.decode().map_err(error)?.to_rgba8())
+}
+
+/*
+ * What the surface and top view draw behind the bodies.
+ * The style is remembered across sessions, the image is read again from its path on startup.
+ */
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CanvasBackground {
+    style: BackgroundStyle,
+    image: Option<Arc<BackgroundImage>>,
+}
+
+impl CanvasBackground {
+    pub(crate) const BLACK: CanvasBackground = CanvasBackground {
+        style: BackgroundStyle::Black,
+        image: None,
+    };
+
+    /*
+     * An image that cannot be read anymore is not worth interrupting the startup for, the background is merely black instead.
+     */
+    pub(crate) fn load() -> CanvasBackground {
+        let style: BackgroundStyle = config::load(FILE_NAME);
+        let mut background = CanvasBackground::default();
+        if let Err(e) = background.set_style(style) {
+            println!("Failed to load the canvas background: {}", e);
+        }
+        background
+    }
+
+    pub(crate) fn save(&self) -> Result<(), std::io::Error> {
+        config::save(FILE_NAME, &self.style)
+    }
+
+    pub(crate) fn get_style(&self) -> &BackgroundStyle {
+        &self.style
+    }
+
+    /*
+     * Leaves the background unchanged if the image cannot be read.
+     */
+    pub(crate) fn set_style(&mut self, style: BackgroundStyle) -> Result<(), ElenathError> {
+        self.image = match &style {
+            BackgroundStyle::Image(path) => Some(Arc::new(BackgroundImage::read(path)?)),
+            _ => None,
+        };
+        self.style = style;
+        Ok(())
+    }
+
+    pub(crate) fn draw(&self, bounds: Rectangle, frame: &mut canvas::Frame) {
+        let fill = |frame: &mut canvas::Frame, color: Color| {
+            let background = Path::rectangle(Point::ORIGIN, bounds.size());
+            frame.fill(&background, color);
+        };
+        match (&self.style, &self.image) {
+            (BackgroundStyle::DeepBlue, _) => fill(frame, DEEP_BLUE),
+            (BackgroundStyle::Noise, _) => {
+                fill(frame, NOISE_BASE);
+                draw_noise(bounds, frame);
+            }
+            (BackgroundStyle::Image(_), Some(image)) => {
+                fill(frame, Color::BLACK);
+                image.draw(bounds, frame, 1.);
+            }
+            _ => fill(frame, Color::BLACK),
+        }
+    }
+}
+
+/*
+ * Faint specks at fixed random positions, so that the texture does not flicker when the canvas is resized.
+ */
+fn draw_noise(bounds: Rectangle, frame: &mut canvas::Frame) {
+    let mut rng = SplitMix64(NOISE_SEED);
+    let count =
+        ((bounds.width * bounds.height / PIXELS_PER_NOISE_SPECK) as usize).min(MAX_NOISE_SPECKS);
+    for _ in 0..count {
+        let x = rng.next_f64() as f32 * bounds.width;
+        let y = rng.next_f64() as f32 * bounds.height;
+        let brightness = 0.03 + 0.07 * rng.next_f64() as f32;
+        let speck = Path::rectangle(Point::new(x, y), Size::new(1., 1.));
+        frame.fill(
+            &speck,
+            Color::from_rgb(brightness, brightness, brightness * 1.1),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_and_plain_ppm_images_are_equivalent() {
+        let mut binary = b"P6\n# a comment\n2 1\n255\n".to_vec();
+        binary.extend_from_slice(&[255, 0, 0, 0, 0, 255]);
+        let plain = b"P3 2 1 255\n255 0 0\n0 0 255\n";
+        let binary = decode(&binary).unwrap();
+        assert_eq!(binary, decode(plain).unwrap());
+        assert_eq!(binary.dimensions(), (2, 1));
+        assert_eq!(binary.into_raw(), vec![255, 0, 0, 255, 0, 0, 255, 255]);
+    }
+
+    #[test]
+    fn png_images_are_supported() {
+        let mut png = Vec::new();
+        RgbaImage::from_pixel(3, 2, image::Rgba([10, 20, 30, 255]))
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let image = BackgroundImage::from_bytes(&png).unwrap();
+        assert_eq!((image.width, image.height), (3, 2));
+    }
+
+    #[test]
+    fn truncated_or_unsupported_images_are_rejected() {
+        assert!(BackgroundImage::from_bytes(b"").is_err());
+        assert!(BackgroundImage::from_bytes(b"not an image").is_err());
+        assert!(BackgroundImage::from_bytes(b"P6 2 2 255\n\0\0\0").is_err());
+    }
+
+    #[test]
+    fn oversized_images_are_rejected_before_allocating() {
+        assert!(BackgroundImage::from_bytes(b"P6 4294967295 4294967295 255\n\0").is_err());
+        assert!(BackgroundImage::from_bytes(b"P6 16385 1 255\n\0").is_err());
+    }
+
+    #[test]
+    fn a_missing_image_leaves_the_background_unchanged() {
+        let mut background = CanvasBackground::default();
+        background.set_style(BackgroundStyle::Noise).unwrap();
+        let missing = BackgroundStyle::Image(PathBuf::from("does/not/exist.ppm"));
+        assert!(background.set_style(missing).is_err());
+        assert_eq!(background.get_style(), &BackgroundStyle::Noise);
+    }
+}
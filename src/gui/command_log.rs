@@ -0,0 +1,216 @@
+use super::{message::GuiMessage, Gui};
+use crate::{
+    error::ElenathError,
+    model::celestial_system::command_log::{LoggedCommand, SystemCommand},
+};
+
+impl Gui {
+    /*
+     * The command by which the message changes the system, if it does.
+     * Adding a system changes the universe rather than the system, so it is not logged.
+     * Messages that draw random numbers or ask for a file are logged by their handlers, which know what was drawn or chosen.
+     */
+    pub(super) fn command_for(&self, message: &GuiMessage) -> Option<SystemCommand> {
+        let command = match message {
+            GuiMessage::NewPlanet(planet, notes, precession_period, rotation_angle) => {
+                SystemCommand::NewPlanet(
+                    planet.clone(),
+                    notes.clone(),
+                    *precession_period,
+                    *rotation_angle,
+                )
+            }
+            GuiMessage::PlanetEdited(id, planet, notes, precession_period, rotation_angle) => {
+                SystemCommand::EditPlanet(
+                    *id,
+                    planet.clone(),
+                    notes.clone(),
+                    *precession_period,
+                    *rotation_angle,
+                )
+            }
+            GuiMessage::NewStar(star, notes, radial_velocity, appearance_override) => {
+                SystemCommand::NewStar(
+                    star.clone(),
+                    notes.clone(),
+                    *radial_velocity,
+                    appearance_override.clone(),
+                )
+            }
+            GuiMessage::StarEdited(
+                id,
+                star,
+                notes,
+                radial_velocity,
+                appearance_override,
+                linked_system,
+            ) => SystemCommand::EditStar(
+                *id,
+                star.clone(),
+                notes.clone(),
+                *radial_velocity,
+                appearance_override.clone(),
+                *linked_system,
+            ),
+            GuiMessage::CentralBodyEdited(star, notes, metadata) => {
+                SystemCommand::EditCentralBody(star.clone(), notes.clone(), metadata.clone())
+            }
+            GuiMessage::MetadataEdited(metadata) => SystemCommand::EditMetadata(metadata.clone()),
+            GuiMessage::RedefineEpoch(description) => {
+                SystemCommand::RedefineEpoch(description.clone())
+            }
+            GuiMessage::SaveObserverLocation => SystemCommand::AddObserverLocation(
+                self.selected_planet?,
+                self.surface_view_state.current_observer_location(),
+            ),
+            GuiMessage::SetGalacticMotion(motion) => SystemCommand::SetGalacticMotion(*motion),
+            GuiMessage::SetStarFormingRegions(regions) => {
+                SystemCommand::SetStarFormingRegions(regions.clone())
+            }
+            GuiMessage::SetMigrationSchedule(schedule) => {
+                SystemCommand::SetMigrationSchedule(schedule.clone())
+            }
+            GuiMessage::SetBinaryPlanet(primary, secondary, separation) => {
+                SystemCommand::SetBinaryPlanet(*primary, *secondary, *separation)
+            }
+            GuiMessage::SeparateBinaryPlanet(id) => SystemCommand::SeparateBinaryPlanet(*id),
+            GuiMessage::AddCoOrbitalBody(host, placement, name, mass, radius) => {
+                SystemCommand::AddCoOrbitalBody(*host, *placement, name.clone(), *mass, *radius)
+            }
+            GuiMessage::SetCalendar(calendar) => SystemCommand::SetCalendar(calendar.clone()),
+            GuiMessage::LoadRealPlanets => SystemCommand::LoadRealPlanets,
+            GuiMessage::LoadStars(data_type, mode, filter) => {
+                SystemCommand::LoadStars(*data_type, *mode, *filter)
+            }
+            GuiMessage::RemoveDuplicateStars => SystemCommand::RemoveDuplicateStars,
+            GuiMessage::TrimFaintStars(magnitude) => SystemCommand::TrimFaintStars(*magnitude),
+            GuiMessage::JitterStars(jitter) => SystemCommand::JitterStars(*jitter),
+            _ => return None,
+        };
+        Some(command)
+    }
+
+    /*
+     * Replays go through the same messages as the original changes, so they are logged again.
+     * Observer locations are the exception, because the message takes them from the surface view,
+     * and so are the commands whose messages would draw new random numbers or ask for a file again.
+     */
+    fn replay_command(&mut self, command: SystemCommand) -> Result<(), ElenathError> {
+        let message = match command {
+            SystemCommand::NewPlanet(planet, notes, precession_period, rotation_angle) => {
+                GuiMessage::NewPlanet(planet, notes, precession_period, rotation_angle)
+            }
+            SystemCommand::EditPlanet(id, planet, notes, precession_period, rotation_angle) => {
+                GuiMessage::PlanetEdited(id, planet, notes, precession_period, rotation_angle)
+            }
+            SystemCommand::NewStar(star, notes, radial_velocity, appearance_override) => {
+                GuiMessage::NewStar(star, notes, radial_velocity, appearance_override)
+            }
+            SystemCommand::EditStar(
+                id,
+                star,
+                notes,
+                radial_velocity,
+                appearance_override,
+                linked_system,
+            ) => GuiMessage::StarEdited(
+                id,
+                star,
+                notes,
+                radial_velocity,
+                appearance_override,
+                linked_system,
+            ),
+            SystemCommand::EditCentralBody(star, notes, metadata) => {
+                GuiMessage::CentralBodyEdited(star, notes, metadata)
+            }
+            SystemCommand::EditMetadata(metadata) => GuiMessage::MetadataEdited(metadata),
+            SystemCommand::RedefineEpoch(description) => GuiMessage::RedefineEpoch(description),
+            SystemCommand::AddObserverLocation(planet, location) => {
+                self.get_system()?
+                    .add_observer_location(planet, location.clone());
+                return self.log_replayed(SystemCommand::AddObserverLocation(planet, location));
+            }
+            SystemCommand::SetGalacticMotion(motion) => GuiMessage::SetGalacticMotion(motion),
+            SystemCommand::SetStarFormingRegions(regions) => {
+                GuiMessage::SetStarFormingRegions(regions)
+            }
+            SystemCommand::SetMigrationSchedule(schedule) => {
+                GuiMessage::SetMigrationSchedule(schedule)
+            }
+            SystemCommand::SetBinaryPlanet(primary, secondary, separation) => {
+                GuiMessage::SetBinaryPlanet(primary, secondary, separation)
+            }
+            SystemCommand::SeparateBinaryPlanet(id) => GuiMessage::SeparateBinaryPlanet(id),
+            SystemCommand::AddCoOrbitalBody(host, placement, name, mass, radius) => {
+                GuiMessage::AddCoOrbitalBody(host, placement, name, mass, radius)
+            }
+            SystemCommand::SetCalendar(calendar) => GuiMessage::SetCalendar(calendar),
+            SystemCommand::RandomizePlanets(planets) => {
+                self.get_system()?.replace_planets(planets.clone())?;
+                return self.log_replayed(SystemCommand::RandomizePlanets(planets));
+            }
+            SystemCommand::LoadRealPlanets => GuiMessage::LoadRealPlanets,
+            SystemCommand::RandomizeStars(generated, mode) => {
+                self.get_system()?
+                    .add_generated_stars(generated.clone(), mode);
+                return self.log_replayed(SystemCommand::RandomizeStars(generated, mode));
+            }
+            SystemCommand::LoadStars(data_type, mode, filter) => {
+                GuiMessage::LoadStars(data_type, mode, filter)
+            }
+            SystemCommand::LoadGaiaExport(path, release, extra_columns, mode, filter) => {
+                self.load_gaia_export(&path, release, extra_columns, mode, &filter)?;
+                return self.log_replayed(SystemCommand::LoadGaiaExport(
+                    path,
+                    release,
+                    extra_columns,
+                    mode,
+                    filter,
+                ));
+            }
+            SystemCommand::RemoveDuplicateStars => GuiMessage::RemoveDuplicateStars,
+            SystemCommand::TrimFaintStars(magnitude) => GuiMessage::TrimFaintStars(magnitude),
+            SystemCommand::NameStars(seed) => {
+                self.get_system()?.name_unnamed_stars_with_seed(seed);
+                return self.log_replayed(SystemCommand::NameStars(seed));
+            }
+            SystemCommand::JitterStars(jitter) => GuiMessage::JitterStars(jitter),
+        };
+        self.handle_message(message)
+    }
+
+    fn log_replayed(&mut self, command: SystemCommand) -> Result<(), ElenathError> {
+        self.get_system()?.log_command(command);
+        self.unsaved_changes = true;
+        Ok(())
+    }
+
+    /*
+     * Applies the commands in order to a copy of the system, which only replaces the system once every command succeeded.
+     * Returns the number of replayed commands.
+     */
+    pub(super) fn replay_command_log(
+        &mut self,
+        commands: Vec<LoggedCommand>,
+    ) -> Result<usize, ElenathError> {
+        let copy = self.celestial_system.clone();
+        let original = std::mem::replace(&mut self.celestial_system, copy);
+        let unsaved_changes = self.unsaved_changes;
+        let count = commands.len();
+        for (i, logged) in commands.into_iter().enumerate() {
+            self.dialog = None;
+            if let Err(e) = self.replay_command(logged.command) {
+                self.celestial_system = original;
+                self.unsaved_changes = unsaved_changes;
+                return Err(ElenathError::Generic(format!(
+                    "Replaying command {} of {} failed: {}",
+                    i + 1,
+                    count,
+                    e
+                )));
+            }
+        }
+        Ok(count)
+    }
+}
@@ -0,0 +1,195 @@
+use super::{Dialog, DialogUpdate};
+use crate::{
+    error::ElenathError,
+    gui::{gui_widget::PADDING, message::GuiMessage, shared_widgets::edit},
+    model::body_id::BodyId,
+};
+use iced::{
+    widget::{text::Shaping, Button, Column, PickList, Row, Text},
+    Alignment, Element, Length as IcedLength,
+};
+use std::fmt::Display;
+use uom::si::{f64::Length, length::kilometer};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PlanetChoice {
+    pub(crate) id: BodyId,
+    pub(crate) name: String,
+}
+
+impl Display for PlanetChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct BinaryPlanetPair {
+    pub(crate) primary: PlanetChoice,
+    pub(crate) secondary: PlanetChoice,
+    pub(crate) separation: Length,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct BinaryPlanetsDialog {
+    planets: Vec<PlanetChoice>,
+    pairs: Vec<BinaryPlanetPair>,
+    primary: Option<PlanetChoice>,
+    secondary: Option<PlanetChoice>,
+    separation: Option<Length>,
+    separation_string: String,
+}
+
+impl BinaryPlanetsDialog {
+    pub(crate) fn new(planets: Vec<PlanetChoice>, pairs: Vec<BinaryPlanetPair>) -> Self {
+        BinaryPlanetsDialog {
+            primary: planets.first().cloned(),
+            secondary: planets.get(1).cloned(),
+            planets,
+            pairs,
+            separation: Some(Length::new::<kilometer>(400_000.)),
+            separation_string: "400000".to_string(),
+        }
+    }
+
+    fn pair_row(&self, pair: &BinaryPlanetPair) -> Row<'_, GuiMessage> {
+        let description = format!(
+            "{} and {}, {:.0} km apart",
+            pair.primary,
+            pair.secondary,
+            pair.separation.get::<kilometer>()
+        );
+        let separate_button = Button::new(Text::new("Separate"))
+            .on_press(GuiMessage::SeparateBinaryPlanet(pair.primary.id));
+        Row::new()
+            .push(Text::new(description).shaping(Shaping::Advanced))
+            .push(separate_button)
+            .spacing(PADDING)
+            .align_y(Alignment::Center)
+    }
+
+    fn planet_row<'a, F>(
+        &'a self,
+        label: &'static str,
+        selected: &Option<PlanetChoice>,
+        on_select: F,
+    ) -> Row<'a, GuiMessage>
+    where
+        F: 'a + Fn(PlanetChoice) -> BinaryPlanetsDialogEvent,
+    {
+        let picker = PickList::new(self.planets.clone(), selected.clone(), move |planet| {
+            GuiMessage::DialogUpdate(DialogUpdate::BinaryPlanetsUpdated(on_select(planet)))
+        });
+        Row::new()
+            .push(Text::new(label))
+            .push(picker)
+            .spacing(PADDING)
+            .align_y(Alignment::Center)
+    }
+
+    fn pairing(&self) -> Option<(BodyId, BodyId, Length)> {
+        let primary = self.primary.as_ref()?;
+        let secondary = self.secondary.as_ref()?;
+        if primary.id == secondary.id {
+            return None;
+        }
+        Some((primary.id, secondary.id, self.separation?))
+    }
+}
+
+impl Dialog for BinaryPlanetsDialog {
+    fn header(&self) -> String {
+        "Binary Planets".to_string()
+    }
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let explanation = Text::new(
+            "The members of a binary planet circle their common barycenter, which follows the orbit of the primary. The secondary takes over that orbit.",
+        );
+        let mut pairs = Column::new().spacing(PADDING);
+        if self.pairs.is_empty() {
+            pairs = pairs.push(Text::new("No binary planets."));
+        }
+        for pair in self.pairs.iter() {
+            pairs = pairs.push(self.pair_row(pair));
+        }
+
+        let primary = self.planet_row("Primary:", &self.primary, |planet| {
+            BinaryPlanetsDialogEvent::PrimarySelected(planet)
+        });
+        let secondary = self.planet_row("Secondary:", &self.secondary, |planet| {
+            BinaryPlanetsDialogEvent::SecondarySelected(planet)
+        });
+        let separation = edit(
+            "Separation",
+            &self.separation_string,
+            "km",
+            |string| {
+                GuiMessage::DialogUpdate(DialogUpdate::BinaryPlanetsUpdated(
+                    BinaryPlanetsDialogEvent::SeparationChanged(string),
+                ))
+            },
+            &self.separation,
+        );
+        let mut pair_button = Button::new(Text::new("Pair"));
+        if self.pairing().is_some() {
+            pair_button = pair_button.on_press(GuiMessage::DialogSubmit);
+        }
+        let close_button = Button::new(Text::new("Close")).on_press(GuiMessage::DialogClosed);
+
+        Column::new()
+            .push(explanation)
+            .push(pairs)
+            .push(primary)
+            .push(secondary)
+            .push(separation)
+            .push(pair_button)
+            .push(close_button)
+            .padding(PADDING)
+            .spacing(PADDING)
+            .width(IcedLength::Fill)
+            .align_x(Alignment::Center)
+            .into()
+    }
+
+    fn update(&mut self, event: DialogUpdate) {
+        if let DialogUpdate::BinaryPlanetsUpdated(event) = event {
+            match event {
+                BinaryPlanetsDialogEvent::PrimarySelected(planet) => {
+                    self.primary = Some(planet);
+                }
+                BinaryPlanetsDialogEvent::SecondarySelected(planet) => {
+                    self.secondary = Some(planet);
+                }
+                BinaryPlanetsDialogEvent::SeparationChanged(separation_string) => {
+                    self.separation = separation_string
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|km| *km > 0.)
+                        .map(Length::new::<kilometer>);
+                    self.separation_string = separation_string;
+                }
+            }
+        }
+    }
+
+    fn on_submit(&self) -> GuiMessage {
+        match self.pairing() {
+            Some((primary, secondary, separation)) => {
+                GuiMessage::SetBinaryPlanet(primary, secondary, separation)
+            }
+            None => GuiMessage::DialogClosed,
+        }
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum BinaryPlanetsDialogEvent {
+    PrimarySelected(PlanetChoice),
+    SecondarySelected(PlanetChoice),
+    SeparationChanged(String),
+}
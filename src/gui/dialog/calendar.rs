@@ -0,0 +1,385 @@
+use super::{Dialog, DialogUpdate};
+use crate::{
+    error::ElenathError,
+    gui::{gui_widget::PADDING, message::GuiMessage, shared_widgets::edit},
+    model::calendar::{Calendar, Intercalation, Month},
+};
+use iced::{
+    widget::{text::Shaping, Button, Column, Row, Text},
+    Alignment, Element, Length,
+};
+use uom::si::{f64::Time, time::hour};
+
+/*
+ * The day and year of the selected planet, which a calendar is most likely based on.
+ */
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PlanetDays {
+    pub(crate) day: Time,
+    pub(crate) year: Time,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct CalendarDialog {
+    calendar: Calendar,
+    day_length: Option<Time>,
+    day_length_string: String,
+    year_at_epoch: Option<i64>,
+    year_at_epoch_string: String,
+    weekdays_string: String,
+    new_month_name: String,
+    new_month_days: Option<u32>,
+    new_month_days_string: String,
+    every_years: Option<u32>,
+    every_years_string: String,
+    intercalated_month: Option<usize>,
+    intercalated_month_string: String,
+    extra_days: Option<i32>,
+    extra_days_string: String,
+    planet_days: Option<PlanetDays>,
+}
+
+impl CalendarDialog {
+    pub(crate) fn new(calendar: Option<&Calendar>, planet_days: Option<PlanetDays>) -> Self {
+        let calendar = match (calendar, planet_days) {
+            (Some(calendar), _) => calendar.clone(),
+            (None, Some(days)) => Calendar::simple(days.day, days.year),
+            (None, None) => {
+                Calendar::simple(Time::new::<hour>(24.), Time::new::<hour>(24. * 365.25))
+            }
+        };
+        let mut dialog = CalendarDialog {
+            calendar,
+            day_length: None,
+            day_length_string: String::new(),
+            year_at_epoch: None,
+            year_at_epoch_string: String::new(),
+            weekdays_string: String::new(),
+            new_month_name: String::new(),
+            new_month_days: None,
+            new_month_days_string: String::new(),
+            every_years: None,
+            every_years_string: String::new(),
+            intercalated_month: None,
+            intercalated_month_string: String::new(),
+            extra_days: None,
+            extra_days_string: String::new(),
+            planet_days,
+        };
+        dialog.fill_string_members();
+        dialog
+    }
+
+    fn fill_string_members(&mut self) {
+        self.day_length = Some(self.calendar.get_day_length());
+        self.day_length_string = format!("{:.4}", self.calendar.get_day_length().get::<hour>());
+        self.year_at_epoch = Some(self.calendar.get_year_at_epoch());
+        self.year_at_epoch_string = self.calendar.get_year_at_epoch().to_string();
+        self.weekdays_string = self.calendar.get_weekdays().join(", ");
+    }
+
+    fn month_row(&self, index: usize, month: &Month) -> Row<'_, GuiMessage> {
+        let description = format!("{}. {}: {} days", index + 1, month.name, month.days);
+        let remove_button = Button::new(Text::new("Remove")).on_press(GuiMessage::DialogUpdate(
+            DialogUpdate::CalendarUpdated(CalendarDialogEvent::RemoveMonth(index)),
+        ));
+        Row::new()
+            .push(Text::new(description).shaping(Shaping::Advanced))
+            .push(remove_button)
+            .spacing(PADDING)
+            .align_y(Alignment::Center)
+    }
+
+    fn intercalation_row(
+        &self,
+        index: usize,
+        intercalation: &Intercalation,
+    ) -> Row<'_, GuiMessage> {
+        let month_name = self
+            .calendar
+            .get_months()
+            .get(intercalation.month)
+            .map(|month| month.name.clone())
+            .unwrap_or_default();
+        let description = format!(
+            "Every {} years, {:+} days in {}",
+            intercalation.every_years, intercalation.extra_days, month_name
+        );
+        let remove_button = Button::new(Text::new("Remove")).on_press(GuiMessage::DialogUpdate(
+            DialogUpdate::CalendarUpdated(CalendarDialogEvent::RemoveIntercalation(index)),
+        ));
+        Row::new()
+            .push(Text::new(description).shaping(Shaping::Advanced))
+            .push(remove_button)
+            .spacing(PADDING)
+            .align_y(Alignment::Center)
+    }
+
+    fn update_event(&mut self, event: CalendarDialogEvent) {
+        match event {
+            CalendarDialogEvent::DayLengthChanged(day_length_string) => {
+                self.day_length = day_length_string
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|hours| *hours > 0.)
+                    .map(Time::new::<hour>);
+                if let Some(day_length) = self.day_length {
+                    self.calendar.set_day_length(day_length);
+                }
+                self.day_length_string = day_length_string;
+            }
+            CalendarDialogEvent::YearAtEpochChanged(year_at_epoch_string) => {
+                self.year_at_epoch = year_at_epoch_string.trim().parse::<i64>().ok();
+                if let Some(year_at_epoch) = self.year_at_epoch {
+                    self.calendar.set_year_at_epoch(year_at_epoch);
+                }
+                self.year_at_epoch_string = year_at_epoch_string;
+            }
+            CalendarDialogEvent::WeekdaysChanged(weekdays_string) => {
+                self.calendar.set_weekdays(
+                    weekdays_string
+                        .split(',')
+                        .map(|weekday| weekday.trim().to_string())
+                        .filter(|weekday| !weekday.is_empty())
+                        .collect(),
+                );
+                self.weekdays_string = weekdays_string;
+            }
+            CalendarDialogEvent::NewMonthNameChanged(name) => {
+                self.new_month_name = name;
+            }
+            CalendarDialogEvent::NewMonthDaysChanged(days_string) => {
+                self.new_month_days = days_string.trim().parse::<u32>().ok();
+                self.new_month_days_string = days_string;
+            }
+            CalendarDialogEvent::AddMonth => {
+                if let Some(days) = self.new_month_days {
+                    if !self.new_month_name.is_empty() {
+                        self.calendar.add_month(Month {
+                            name: self.new_month_name.clone(),
+                            days,
+                        });
+                    }
+                }
+            }
+            CalendarDialogEvent::RemoveMonth(index) => {
+                self.calendar.remove_month(index);
+            }
+            CalendarDialogEvent::EveryYearsChanged(every_years_string) => {
+                self.every_years = every_years_string
+                    .trim()
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|years| *years > 0);
+                self.every_years_string = every_years_string;
+            }
+            CalendarDialogEvent::IntercalatedMonthChanged(month_string) => {
+                self.intercalated_month = month_string
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|month| *month >= 1 && *month <= self.calendar.get_months().len())
+                    .map(|month| month - 1);
+                self.intercalated_month_string = month_string;
+            }
+            CalendarDialogEvent::ExtraDaysChanged(extra_days_string) => {
+                self.extra_days = extra_days_string.trim().parse::<i32>().ok();
+                self.extra_days_string = extra_days_string;
+            }
+            CalendarDialogEvent::AddIntercalation => {
+                if let (Some(every_years), Some(month), Some(extra_days)) =
+                    (self.every_years, self.intercalated_month, self.extra_days)
+                {
+                    if month < self.calendar.get_months().len() {
+                        self.calendar.add_intercalation(Intercalation {
+                            every_years,
+                            month,
+                            extra_days,
+                        });
+                    }
+                }
+            }
+            CalendarDialogEvent::RemoveIntercalation(index) => {
+                self.calendar.remove_intercalation(index);
+            }
+            CalendarDialogEvent::UsePlanetDays => {
+                if let Some(days) = self.planet_days {
+                    let offset = self.calendar.offset;
+                    self.calendar = Calendar::simple(days.day, days.year);
+                    self.calendar.offset = offset;
+                    self.fill_string_members();
+                }
+            }
+        }
+    }
+}
+
+fn message<F: Fn(String) -> CalendarDialogEvent>(event: F) -> impl Fn(String) -> GuiMessage {
+    move |m| GuiMessage::DialogUpdate(DialogUpdate::CalendarUpdated(event(m)))
+}
+
+impl Dialog for CalendarDialog {
+    fn header(&self) -> String {
+        "Calendar".to_string()
+    }
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let day_length = edit(
+            "Day Length",
+            &self.day_length_string,
+            "h",
+            message(CalendarDialogEvent::DayLengthChanged),
+            &self.day_length,
+        );
+        let year_at_epoch = edit(
+            "Year at Epoch",
+            &self.year_at_epoch_string,
+            "",
+            message(CalendarDialogEvent::YearAtEpochChanged),
+            &self.year_at_epoch.map(|year| year as f64),
+        );
+        let weekdays = edit(
+            "Weekdays",
+            &self.weekdays_string,
+            "comma separated, empty for none",
+            message(CalendarDialogEvent::WeekdaysChanged),
+            &Some(self.calendar.get_weekdays().len() as f64),
+        );
+
+        let mut months = Column::new().spacing(PADDING);
+        for (index, month) in self.calendar.get_months().iter().enumerate() {
+            months = months.push(self.month_row(index, month));
+        }
+        let new_month_name = edit(
+            "Month Name",
+            &self.new_month_name,
+            "",
+            message(CalendarDialogEvent::NewMonthNameChanged),
+            &Some(&self.new_month_name),
+        );
+        let new_month_days = edit(
+            "Days in Month",
+            &self.new_month_days_string,
+            "",
+            message(CalendarDialogEvent::NewMonthDaysChanged),
+            &self.new_month_days.map(|days| days as f64),
+        );
+        let mut add_month_button = Button::new(Text::new("Add Month"));
+        if self.new_month_days.is_some() && !self.new_month_name.is_empty() {
+            add_month_button = add_month_button.on_press(GuiMessage::DialogUpdate(
+                DialogUpdate::CalendarUpdated(CalendarDialogEvent::AddMonth),
+            ));
+        }
+
+        let mut intercalations = Column::new().spacing(PADDING);
+        for (index, intercalation) in self.calendar.get_intercalations().iter().enumerate() {
+            intercalations = intercalations.push(self.intercalation_row(index, intercalation));
+        }
+        let every_years = edit(
+            "Every",
+            &self.every_years_string,
+            "years",
+            message(CalendarDialogEvent::EveryYearsChanged),
+            &self.every_years.map(|years| years as f64),
+        );
+        let intercalated_month = edit(
+            "In Month",
+            &self.intercalated_month_string,
+            "number, starting at 1",
+            message(CalendarDialogEvent::IntercalatedMonthChanged),
+            &self.intercalated_month.map(|month| (month + 1) as f64),
+        );
+        let extra_days = edit(
+            "Extra Days",
+            &self.extra_days_string,
+            "negative to remove days",
+            message(CalendarDialogEvent::ExtraDaysChanged),
+            &self.extra_days.map(|days| days as f64),
+        );
+        let mut add_intercalation_button = Button::new(Text::new("Add Intercalation"));
+        if self.every_years.is_some()
+            && self.intercalated_month.is_some()
+            && self.extra_days.is_some()
+        {
+            add_intercalation_button = add_intercalation_button.on_press(GuiMessage::DialogUpdate(
+                DialogUpdate::CalendarUpdated(CalendarDialogEvent::AddIntercalation),
+            ));
+        }
+
+        let mut use_planet_days_button = Button::new(Text::new("Derive from Selected Planet"));
+        if self.planet_days.is_some() {
+            use_planet_days_button = use_planet_days_button.on_press(GuiMessage::DialogUpdate(
+                DialogUpdate::CalendarUpdated(CalendarDialogEvent::UsePlanetDays),
+            ));
+        }
+        let validation_error = self.calendar.validate().err();
+        let validation = match &validation_error {
+            None => Text::new(""),
+            Some(e) => Text::new(e.to_string()),
+        };
+        let remove_button =
+            Button::new(Text::new("Remove Calendar")).on_press(GuiMessage::SetCalendar(None));
+        let mut submit_button = Button::new(Text::new("Submit"));
+        if validation_error.is_none() {
+            submit_button = submit_button.on_press(GuiMessage::DialogSubmit);
+        }
+
+        Column::new()
+            .push(use_planet_days_button)
+            .push(day_length)
+            .push(year_at_epoch)
+            .push(weekdays)
+            .push(months)
+            .push(new_month_name)
+            .push(new_month_days)
+            .push(add_month_button)
+            .push(intercalations)
+            .push(every_years)
+            .push(intercalated_month)
+            .push(extra_days)
+            .push(add_intercalation_button)
+            .push(validation)
+            .push(
+                Row::new()
+                    .push(remove_button)
+                    .push(submit_button)
+                    .spacing(PADDING),
+            )
+            .padding(PADDING)
+            .spacing(PADDING)
+            .width(Length::Fill)
+            .align_x(Alignment::Center)
+            .into()
+    }
+
+    fn update(&mut self, message: DialogUpdate) {
+        if let DialogUpdate::CalendarUpdated(event) = message {
+            self.update_event(event);
+        }
+    }
+
+    fn on_submit(&self) -> GuiMessage {
+        GuiMessage::SetCalendar(Some(self.calendar.clone()))
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum CalendarDialogEvent {
+    DayLengthChanged(String),
+    YearAtEpochChanged(String),
+    WeekdaysChanged(String),
+    NewMonthNameChanged(String),
+    NewMonthDaysChanged(String),
+    AddMonth,
+    RemoveMonth(usize),
+    EveryYearsChanged(String),
+    IntercalatedMonthChanged(String),
+    ExtraDaysChanged(String),
+    AddIntercalation,
+    RemoveIntercalation(usize),
+    UsePlanetDays,
+}
@@ -0,0 +1,179 @@
+use super::{binary_planets::PlanetChoice, Dialog, DialogUpdate};
+use crate::{
+    error::ElenathError,
+    gui::{gui_widget::PADDING, message::GuiMessage, shared_widgets::edit},
+    model::celestial_system::co_orbitals::CoOrbitalPlacement,
+};
+use iced::{
+    widget::{Button, Column, PickList, Row, Text},
+    Alignment, Element, Length as IcedLength,
+};
+use uom::si::{
+    f64::{Length, Mass},
+    length::kilometer,
+    mass::kilogram,
+};
+
+#[derive(Debug, Clone)]
+pub(crate) struct CoOrbitalDialog {
+    planets: Vec<PlanetChoice>,
+    host: Option<PlanetChoice>,
+    placement: CoOrbitalPlacement,
+    name: String,
+    mass: Option<Mass>,
+    mass_string: String,
+    radius: Option<Length>,
+    radius_string: String,
+}
+
+impl CoOrbitalDialog {
+    pub(crate) fn new(planets: Vec<PlanetChoice>, host: Option<PlanetChoice>) -> Self {
+        CoOrbitalDialog {
+            host: host.or_else(|| planets.first().cloned()),
+            planets,
+            placement: CoOrbitalPlacement::LeadingTrojan,
+            name: String::new(),
+            mass: Some(Mass::new::<kilogram>(1e18)),
+            mass_string: "1e18".to_string(),
+            radius: Some(Length::new::<kilometer>(50.)),
+            radius_string: "50".to_string(),
+        }
+    }
+}
+
+fn message<F: Fn(String) -> CoOrbitalDialogEvent>(event: F) -> impl Fn(String) -> GuiMessage {
+    move |m| GuiMessage::DialogUpdate(DialogUpdate::CoOrbitalUpdated(event(m)))
+}
+
+impl Dialog for CoOrbitalDialog {
+    fn header(&self) -> String {
+        "Co-orbital Body".to_string()
+    }
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let explanation = Text::new(
+            "Places a small body 60° ahead of (L4) or behind (L5) the host, or opposite of it on a slightly wider horseshoe orbit.",
+        );
+        let host_picker = PickList::new(self.planets.clone(), self.host.clone(), |host| {
+            GuiMessage::DialogUpdate(DialogUpdate::CoOrbitalUpdated(
+                CoOrbitalDialogEvent::HostSelected(host),
+            ))
+        });
+        let host_row = Row::new()
+            .push(Text::new("Host:"))
+            .push(host_picker)
+            .spacing(PADDING)
+            .align_y(Alignment::Center);
+        let placement_picker = PickList::new(
+            CoOrbitalPlacement::ALL.to_vec(),
+            Some(self.placement),
+            |placement| {
+                GuiMessage::DialogUpdate(DialogUpdate::CoOrbitalUpdated(
+                    CoOrbitalDialogEvent::PlacementSelected(placement),
+                ))
+            },
+        );
+        let placement_row = Row::new()
+            .push(Text::new("Placement:"))
+            .push(placement_picker)
+            .spacing(PADDING)
+            .align_y(Alignment::Center);
+        let name = edit(
+            "Name",
+            &self.name,
+            "",
+            message(CoOrbitalDialogEvent::NameChanged),
+            &Some(&self.name),
+        );
+        let mass = edit(
+            "Mass",
+            &self.mass_string,
+            "kg",
+            message(CoOrbitalDialogEvent::MassChanged),
+            &self.mass,
+        );
+        let radius = edit(
+            "Radius",
+            &self.radius_string,
+            "km",
+            message(CoOrbitalDialogEvent::RadiusChanged),
+            &self.radius,
+        );
+        let mut submit_button = Button::new(Text::new("Submit"));
+        let named = !self.name.trim().is_empty();
+        if named && self.host.is_some() && self.mass.is_some() && self.radius.is_some() {
+            submit_button = submit_button.on_press(GuiMessage::DialogSubmit);
+        }
+        Column::new()
+            .push(explanation)
+            .push(host_row)
+            .push(placement_row)
+            .push(name)
+            .push(mass)
+            .push(radius)
+            .push(submit_button)
+            .padding(PADDING)
+            .spacing(PADDING)
+            .width(IcedLength::Fill)
+            .align_x(Alignment::Center)
+            .into()
+    }
+
+    fn update(&mut self, event: DialogUpdate) {
+        if let DialogUpdate::CoOrbitalUpdated(event) = event {
+            match event {
+                CoOrbitalDialogEvent::HostSelected(host) => {
+                    self.host = Some(host);
+                }
+                CoOrbitalDialogEvent::PlacementSelected(placement) => {
+                    self.placement = placement;
+                }
+                CoOrbitalDialogEvent::NameChanged(name) => {
+                    self.name = name;
+                }
+                CoOrbitalDialogEvent::MassChanged(mass_string) => {
+                    self.mass = mass_string
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|kg| *kg > 0.)
+                        .map(Mass::new::<kilogram>);
+                    self.mass_string = mass_string;
+                }
+                CoOrbitalDialogEvent::RadiusChanged(radius_string) => {
+                    self.radius = radius_string
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|km| *km > 0.)
+                        .map(Length::new::<kilometer>);
+                    self.radius_string = radius_string;
+                }
+            }
+        }
+    }
+
+    fn on_submit(&self) -> GuiMessage {
+        match (&self.host, self.mass, self.radius) {
+            (Some(host), Some(mass), Some(radius)) => GuiMessage::AddCoOrbitalBody(
+                host.id,
+                self.placement,
+                self.name.clone(),
+                mass,
+                radius,
+            ),
+            _ => GuiMessage::DialogClosed,
+        }
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum CoOrbitalDialogEvent {
+    HostSelected(PlanetChoice),
+    PlacementSelected(CoOrbitalPlacement),
+    NameChanged(String),
+    MassChanged(String),
+    RadiusChanged(String),
+}
@@ -0,0 +1,105 @@
+use super::{Dialog, DialogUpdate};
+use crate::error::ElenathError;
+use crate::gui::{gui_widget::PADDING, message::GuiMessage};
+use iced::{
+    widget::{Button, Column, Row, Text},
+    Element,
+};
+
+const MAX_LISTED_DUPLICATES: usize = 50;
+
+/*
+ * Opens while the duplicates are still searched for in the background.
+ */
+#[derive(Debug, Clone)]
+pub(crate) struct DuplicateStarsDialog {
+    duplicates: Option<Vec<String>>,
+    stars_changed: bool,
+}
+
+impl DuplicateStarsDialog {
+    pub(crate) fn new() -> Self {
+        DuplicateStarsDialog {
+            duplicates: None,
+            stars_changed: false,
+        }
+    }
+}
+
+impl Dialog for DuplicateStarsDialog {
+    fn header(&self) -> String {
+        "Duplicate Stars".to_string()
+    }
+
+    fn update(&mut self, event: DialogUpdate) {
+        if let DialogUpdate::DuplicateStarsFound(duplicates) = event {
+            self.stars_changed = duplicates.is_none();
+            self.duplicates = duplicates;
+        }
+    }
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let Some(duplicates) = &self.duplicates else {
+            let text = if self.stars_changed {
+                "The stars changed during the search. Please search again."
+            } else {
+                "Searching for duplicate stars..."
+            };
+            let button = Button::new(Text::new("Cancel")).on_press(GuiMessage::DialogClosed);
+            return Column::new()
+                .push(Text::new(text))
+                .push(button)
+                .spacing(PADDING)
+                .into();
+        };
+        if duplicates.is_empty() {
+            let button = Button::new(Text::new("Ok")).on_press(GuiMessage::DialogClosed);
+            return Column::new()
+                .push(Text::new("No duplicate stars were found."))
+                .push(button)
+                .spacing(PADDING)
+                .into();
+        }
+
+        let mut col = Column::new().spacing(PADDING).push(Text::new(format!(
+            "Found {} stars that are apparently the same as another star:",
+            duplicates.len()
+        )));
+        for duplicate in duplicates.iter().take(MAX_LISTED_DUPLICATES) {
+            col = col.push(Text::new(duplicate.clone()));
+        }
+        if duplicates.len() > MAX_LISTED_DUPLICATES {
+            col = col.push(Text::new(format!(
+                "... and {} more.",
+                duplicates.len() - MAX_LISTED_DUPLICATES
+            )));
+        }
+        let remove_button =
+            Button::new(Text::new(format!("Remove {} Duplicates", duplicates.len())))
+                .on_press(GuiMessage::DialogSubmit);
+        let cancel_button = Button::new(Text::new("Cancel")).on_press(GuiMessage::DialogClosed);
+        col.push(
+            Row::new()
+                .push(remove_button)
+                .push(cancel_button)
+                .spacing(PADDING),
+        )
+        .into()
+    }
+
+    fn on_submit(&self) -> GuiMessage {
+        if self
+            .duplicates
+            .as_ref()
+            .is_none_or(|duplicates| duplicates.is_empty())
+        {
+            GuiMessage::DialogClosed
+        } else {
+            GuiMessage::RemoveDuplicateStars
+        }
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
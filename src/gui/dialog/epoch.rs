@@ -0,0 +1,101 @@
+use super::{Dialog, DialogUpdate};
+use crate::{
+    error::ElenathError,
+    gui::{gui_widget::PADDING, message::GuiMessage, shared_widgets::edit},
+};
+use astro_utils::astro_display::AstroDisplay;
+use iced::{
+    widget::{text::Shaping, Button, Column, Row, Text},
+    Alignment, Element, Length as IcedLength,
+};
+use uom::si::f64::Time;
+
+#[derive(Debug, Clone)]
+pub(crate) struct EpochDialog {
+    time_since_epoch: Time,
+    epoch_description: String,
+}
+
+impl EpochDialog {
+    pub(crate) fn new(time_since_epoch: Time, old_epoch_description: &str) -> Self {
+        let epoch_description = if old_epoch_description.is_empty() {
+            String::new()
+        } else {
+            format!(
+                "{} + {}",
+                old_epoch_description,
+                time_since_epoch.astro_display()
+            )
+        };
+        EpochDialog {
+            time_since_epoch,
+            epoch_description,
+        }
+    }
+}
+
+impl Dialog for EpochDialog {
+    fn header(&self) -> String {
+        "Redefine Epoch".to_string()
+    }
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let explanation = Text::new(format!(
+            "The current time, {} after the old epoch, becomes the new epoch. Star positions, ages and everything else that is defined at the epoch is rewritten, so the system stays as it is.",
+            self.time_since_epoch.astro_display()
+        ))
+        .shaping(Shaping::Advanced);
+        let epoch_description = edit(
+            "New Epoch Description",
+            &self.epoch_description,
+            "",
+            |description| {
+                GuiMessage::DialogUpdate(DialogUpdate::EpochUpdated(
+                    EpochDialogEvent::EpochDescriptionChanged(description),
+                ))
+            },
+            &Some(&self.epoch_description),
+        );
+        let submit_button =
+            Button::new(Text::new("Redefine Epoch")).on_press(GuiMessage::DialogSubmit);
+        let cancel_button = Button::new(Text::new("Cancel")).on_press(GuiMessage::DialogClosed);
+
+        Column::new()
+            .push(explanation)
+            .push(epoch_description)
+            .push(
+                Row::new()
+                    .push(submit_button)
+                    .push(cancel_button)
+                    .spacing(PADDING),
+            )
+            .padding(PADDING)
+            .spacing(PADDING)
+            .width(IcedLength::Fill)
+            .align_x(Alignment::Center)
+            .into()
+    }
+
+    fn update(&mut self, message: DialogUpdate) {
+        if let DialogUpdate::EpochUpdated(event) = message {
+            match event {
+                EpochDialogEvent::EpochDescriptionChanged(epoch_description) => {
+                    self.epoch_description = epoch_description;
+                }
+            }
+        }
+    }
+
+    fn on_submit(&self) -> GuiMessage {
+        GuiMessage::RedefineEpoch(self.epoch_description.clone())
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum EpochDialogEvent {
+    EpochDescriptionChanged(String),
+}
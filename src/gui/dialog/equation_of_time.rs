@@ -0,0 +1,94 @@
+use super::{
+    line_chart::{draw_line_chart, CHART_HEIGHT, CHART_WIDTH},
+    Dialog, DialogUpdate, ElenathError,
+};
+use crate::{
+    gui::{
+        canvas_background::CanvasBackground, gui_widget::PADDING, message::GuiMessage,
+        shared_canvas_functionality::draw_background,
+    },
+    model::celestial_system::equation_of_time::EquationOfTime,
+};
+use iced::{
+    mouse::Cursor,
+    widget::{canvas, Button, Canvas, Column, Text},
+    Alignment, Element, Length, Rectangle, Renderer, Theme,
+};
+use uom::si::time::{day, minute};
+
+#[derive(Debug, Clone)]
+pub(crate) struct EquationOfTimeDialog {
+    planet_name: String,
+    equation_of_time: EquationOfTime,
+}
+
+impl EquationOfTimeDialog {
+    pub(crate) fn new(planet_name: String, equation_of_time: EquationOfTime) -> Self {
+        EquationOfTimeDialog {
+            planet_name,
+            equation_of_time,
+        }
+    }
+}
+
+impl Dialog for EquationOfTimeDialog {
+    fn header(&self) -> String {
+        format!("Equation of Time on {}", self.planet_name)
+    }
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let summary = Text::new(format!(
+            "Amplitude: {:.2} min",
+            self.equation_of_time.amplitude.get::<minute>()
+        ));
+        let explanation = Text::new(
+            "Apparent minus mean solar time over one orbit, caused by the eccentricity of the orbit and the axial tilt. Positive values mean that a sundial is ahead of a clock.",
+        );
+        let chart = Canvas::new(self)
+            .width(Length::Fixed(CHART_WIDTH))
+            .height(Length::Fixed(CHART_HEIGHT));
+        let button = Button::new(Text::new("Ok")).on_press(GuiMessage::DialogClosed);
+        Column::new()
+            .push(summary)
+            .push(explanation)
+            .push(chart)
+            .push(button)
+            .spacing(PADDING)
+            .align_x(Alignment::Center)
+            .into()
+    }
+
+    fn update(&mut self, _event: DialogUpdate) {}
+
+    fn on_submit(&self) -> GuiMessage {
+        GuiMessage::DialogClosed
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
+
+impl<Message> canvas::Program<Message> for EquationOfTimeDialog {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        draw_background(bounds, &mut frame, &CanvasBackground::BLACK);
+        let points: Vec<(f64, f64)> = self
+            .equation_of_time
+            .samples
+            .iter()
+            .map(|(time, difference)| (time.get::<day>(), difference.get::<minute>()))
+            .collect();
+        draw_line_chart(&mut frame, bounds, &points, "d", "min");
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,89 @@
+use super::{Dialog, DialogUpdate, ElenathError};
+use crate::gui::{
+    gui_widget::{PADDING, SMALL_COLUMN_WIDTH},
+    message::GuiMessage,
+    screenshot::{Corner, ExportCaption},
+};
+use iced::{
+    widget::{Button, Column, PickList, Row, Text, Toggler},
+    Alignment, Element, Length,
+};
+
+#[derive(Debug, Clone)]
+pub(crate) struct ExportScreenshotDialog {
+    caption: ExportCaption,
+}
+
+impl ExportScreenshotDialog {
+    pub(crate) fn new(caption: ExportCaption) -> Self {
+        ExportScreenshotDialog { caption }
+    }
+}
+
+fn message(caption: ExportCaption) -> GuiMessage {
+    GuiMessage::DialogUpdate(DialogUpdate::ExportCaptionUpdated(caption))
+}
+
+impl Dialog for ExportScreenshotDialog {
+    fn header(&self) -> String {
+        "Export Screenshot".to_string()
+    }
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let caption = self.caption;
+        let explanation =
+            Text::new("Saves the canvas as it is currently shown, as a PNG or PPM image.");
+        let caption_toggle = Toggler::new(caption.caption)
+            .label("Caption with system, observer and date")
+            .on_toggle(move |b| {
+                message(ExportCaption {
+                    caption: b,
+                    ..caption
+                })
+            });
+        let scale_toggle = Toggler::new(caption.scale_indicator)
+            .label("Field of view or scale")
+            .on_toggle(move |b| {
+                message(ExportCaption {
+                    scale_indicator: b,
+                    ..caption
+                })
+            });
+        let corner_picker = PickList::new(Corner::ALL, Some(caption.corner), move |corner| {
+            message(ExportCaption { corner, ..caption })
+        })
+        .width(Length::Fixed(SMALL_COLUMN_WIDTH));
+        let corner_row = Row::new()
+            .push(Text::new("Corner"))
+            .push(corner_picker)
+            .spacing(PADDING)
+            .align_y(Alignment::Center);
+        let submit_button = Button::new(Text::new("Export")).on_press(GuiMessage::DialogSubmit);
+
+        Column::new()
+            .push(explanation)
+            .push(caption_toggle)
+            .push(scale_toggle)
+            .push(corner_row)
+            .push(submit_button)
+            .padding(PADDING)
+            .spacing(PADDING)
+            .width(Length::Fill)
+            .align_x(Alignment::Center)
+            .into()
+    }
+
+    fn update(&mut self, event: DialogUpdate) {
+        if let DialogUpdate::ExportCaptionUpdated(caption) = event {
+            self.caption = caption;
+        }
+    }
+
+    fn on_submit(&self) -> GuiMessage {
+        GuiMessage::ExportScreenshot(self.caption)
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
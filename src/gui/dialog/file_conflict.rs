@@ -0,0 +1,58 @@
+use super::{CardStyle, Dialog, DialogUpdate};
+use crate::error::ElenathError;
+use crate::gui::{gui_widget::PADDING, message::GuiMessage};
+use iced::{
+    widget::{Button, Column, Row, Text},
+    Element,
+};
+
+/*
+ * Asked before saving over a file that another program has written to since it was opened here.
+ */
+#[derive(Debug, Clone)]
+pub(crate) struct FileConflictDialog {
+    unsaved_changes: bool,
+}
+
+impl FileConflictDialog {
+    pub(crate) fn new(unsaved_changes: bool) -> Self {
+        FileConflictDialog { unsaved_changes }
+    }
+}
+
+impl Dialog for FileConflictDialog {
+    fn card_style(&self) -> CardStyle {
+        CardStyle::Warning
+    }
+
+    fn header(&self) -> String {
+        "The file was changed by another program".to_string()
+    }
+
+    fn update(&mut self, _event: DialogUpdate) {}
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let mut col = Column::new().spacing(PADDING).push(Text::new(
+            "Saving now would discard the changes made by the other program.",
+        ));
+        if self.unsaved_changes {
+            col = col.push(Text::new(
+                "Reloading the file discards the changes made here instead.",
+            ));
+        }
+        let buttons = Row::new()
+            .push(Button::new(Text::new("Reload")).on_press(GuiMessage::ReloadFile))
+            .push(Button::new(Text::new("Overwrite")).on_press(GuiMessage::OverwriteFile))
+            .push(Button::new(Text::new("Cancel")).on_press(GuiMessage::DialogClosed))
+            .spacing(PADDING);
+        col.push(buttons).into()
+    }
+
+    fn on_submit(&self) -> GuiMessage {
+        GuiMessage::DialogClosed
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
@@ -0,0 +1,333 @@
+use super::{
+    line_chart::{draw_line_chart, CHART_HEIGHT, CHART_WIDTH},
+    Dialog, DialogUpdate,
+};
+use crate::{
+    error::ElenathError,
+    gui::{
+        canvas_background::CanvasBackground, gui_widget::PADDING, message::GuiMessage,
+        shared_canvas_functionality::draw_background, shared_widgets::edit,
+    },
+    model::celestial_system::flyby::{FlybySample, FlybyTrack, RogueFlyby},
+};
+use astro_units::{luminous_intensity::absolute_magnitude_to_luminous_intensity, mass::solar_mass};
+use astro_utils::astro_display::AstroDisplay;
+use iced::{
+    mouse::Cursor,
+    widget::{canvas, text::Shaping, Button, Canvas, Column, Text},
+    Alignment, Element, Length as IcedLength, Rectangle, Renderer, Theme,
+};
+use uom::si::{
+    angle::degree,
+    f64::{Angle, Length, Mass, Time, Velocity},
+    length::astronomical_unit,
+    time::year,
+    velocity::kilometer_per_second,
+};
+
+const LISTED_SAMPLES: usize = 11;
+
+#[derive(Debug, Clone)]
+pub(crate) struct FlybyDialog {
+    observer_name: String,
+    time_since_epoch: Time,
+    mass_string: String,
+    absolute_magnitude_string: String,
+    periapsis_string: String,
+    excess_speed_string: String,
+    inclination_string: String,
+    argument_of_periapsis_string: String,
+    periapsis_delay_string: String,
+    time_span_string: String,
+    track: Option<FlybyTrack>,
+}
+
+impl FlybyDialog {
+    pub(crate) fn new(observer_name: String, time_since_epoch: Time) -> Self {
+        FlybyDialog {
+            observer_name,
+            time_since_epoch,
+            mass_string: "0.5".to_string(),
+            absolute_magnitude_string: "8".to_string(),
+            periapsis_string: "1000".to_string(),
+            excess_speed_string: "20".to_string(),
+            inclination_string: "30".to_string(),
+            argument_of_periapsis_string: "0".to_string(),
+            periapsis_delay_string: "500".to_string(),
+            time_span_string: "1000".to_string(),
+            track: None,
+        }
+    }
+
+    fn mass(&self) -> Option<Mass> {
+        parse_positive(&self.mass_string).map(Mass::new::<solar_mass>)
+    }
+
+    fn absolute_magnitude(&self) -> Option<f64> {
+        self.absolute_magnitude_string.trim().parse::<f64>().ok()
+    }
+
+    fn periapsis(&self) -> Option<Length> {
+        parse_positive(&self.periapsis_string).map(Length::new::<astronomical_unit>)
+    }
+
+    fn excess_speed(&self) -> Option<Velocity> {
+        parse_positive(&self.excess_speed_string).map(Velocity::new::<kilometer_per_second>)
+    }
+
+    fn inclination(&self) -> Option<Angle> {
+        parse(&self.inclination_string).map(Angle::new::<degree>)
+    }
+
+    fn argument_of_periapsis(&self) -> Option<Angle> {
+        parse(&self.argument_of_periapsis_string).map(Angle::new::<degree>)
+    }
+
+    fn periapsis_delay(&self) -> Option<Time> {
+        parse(&self.periapsis_delay_string).map(Time::new::<year>)
+    }
+
+    fn time_span(&self) -> Option<Time> {
+        parse_positive(&self.time_span_string).map(Time::new::<year>)
+    }
+
+    /*
+     * Leaving the absolute magnitude empty makes the object a black hole.
+     */
+    fn flyby(&self) -> Option<RogueFlyby> {
+        Some(RogueFlyby {
+            mass: self.mass()?,
+            luminous_intensity: self
+                .absolute_magnitude()
+                .map(absolute_magnitude_to_luminous_intensity),
+            periapsis: self.periapsis()?,
+            excess_speed: self.excess_speed()?,
+            inclination: self.inclination()?,
+            argument_of_periapsis: self.argument_of_periapsis()?,
+            time_of_periapsis: self.time_since_epoch + self.periapsis_delay()?,
+        })
+    }
+
+    fn results_column(&self) -> Column<'_, GuiMessage> {
+        let Some(track) = &self.track else {
+            return Column::new();
+        };
+        let closest = format!(
+            "Closest approach to {}: {}",
+            self.observer_name,
+            describe_sample(&track.closest_approach)
+        );
+        let chart = Canvas::new(self)
+            .width(IcedLength::Fixed(CHART_WIDTH))
+            .height(IcedLength::Fixed(CHART_HEIGHT));
+        let mut col = Column::new()
+            .push(Text::new(closest).shaping(Shaping::Advanced))
+            .push(Text::new(
+                "Apparent path in ecliptic longitude and latitude:",
+            ))
+            .push(chart)
+            .spacing(PADDING)
+            .align_x(Alignment::Center);
+        let step = (track.samples.len() / (LISTED_SAMPLES - 1)).max(1);
+        for sample in track.samples.iter().step_by(step) {
+            col = col.push(Text::new(describe_sample(sample)).shaping(Shaping::Advanced));
+        }
+        col
+    }
+}
+
+fn parse(string: &str) -> Option<f64> {
+    string.trim().parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+fn parse_positive(string: &str) -> Option<f64> {
+    parse(string).filter(|v| *v > 0.)
+}
+
+fn describe_sample(sample: &FlybySample) -> String {
+    let brightness = match sample.apparent_magnitude {
+        Some(magnitude) => format!("{:.1} mag", magnitude),
+        None => "dark".to_string(),
+    };
+    format!(
+        "{}: lon {}, lat {}, {} away, {}",
+        sample.time.astro_display(),
+        sample.longitude.astro_display(),
+        sample.latitude.astro_display(),
+        sample.distance.astro_display(),
+        brightness
+    )
+}
+
+fn message<F: Fn(String) -> FlybyDialogEvent>(event: F) -> impl Fn(String) -> GuiMessage {
+    move |m| GuiMessage::DialogUpdate(DialogUpdate::FlybyUpdated(event(m)))
+}
+
+impl Dialog for FlybyDialog {
+    fn header(&self) -> String {
+        format!("Rogue Flyby seen from {}", self.observer_name)
+    }
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let explanation = Text::new(
+            "A massive object passes the central body on a hyperbolic trajectory. Leave the absolute magnitude empty for a black hole. The planets are not perturbed.",
+        );
+        let mass = edit(
+            "Mass",
+            &self.mass_string,
+            "Solar Masses",
+            message(FlybyDialogEvent::MassChanged),
+            &self.mass(),
+        );
+        let absolute_magnitude = edit(
+            "Absolute Magnitude",
+            &self.absolute_magnitude_string,
+            "mag",
+            message(FlybyDialogEvent::AbsoluteMagnitudeChanged),
+            &self
+                .absolute_magnitude()
+                .map(absolute_magnitude_to_luminous_intensity),
+        );
+        let periapsis = edit(
+            "Periapsis",
+            &self.periapsis_string,
+            "AU",
+            message(FlybyDialogEvent::PeriapsisChanged),
+            &self.periapsis(),
+        );
+        let excess_speed = edit(
+            "Speed at Infinity",
+            &self.excess_speed_string,
+            "km/s",
+            message(FlybyDialogEvent::ExcessSpeedChanged),
+            &self.excess_speed(),
+        );
+        let inclination = edit(
+            "Inclination",
+            &self.inclination_string,
+            "°",
+            message(FlybyDialogEvent::InclinationChanged),
+            &self.inclination(),
+        );
+        let argument_of_periapsis = edit(
+            "Argument of Periapsis",
+            &self.argument_of_periapsis_string,
+            "°",
+            message(FlybyDialogEvent::ArgumentOfPeriapsisChanged),
+            &self.argument_of_periapsis(),
+        );
+        let periapsis_delay = edit(
+            "Periapsis in",
+            &self.periapsis_delay_string,
+            "years from now",
+            message(FlybyDialogEvent::PeriapsisDelayChanged),
+            &self.periapsis_delay(),
+        );
+        let time_span = edit(
+            "Time Span",
+            &self.time_span_string,
+            "years",
+            message(FlybyDialogEvent::TimeSpanChanged),
+            &self.time_span(),
+        );
+        let mut simulate_button = Button::new(Text::new("Simulate"));
+        if self.flyby().is_some() && self.time_span().is_some() {
+            simulate_button = simulate_button.on_press(GuiMessage::DialogSubmit);
+        }
+        let close_button = Button::new(Text::new("Close")).on_press(GuiMessage::DialogClosed);
+        Column::new()
+            .push(explanation)
+            .push(mass)
+            .push(absolute_magnitude)
+            .push(periapsis)
+            .push(excess_speed)
+            .push(inclination)
+            .push(argument_of_periapsis)
+            .push(periapsis_delay)
+            .push(time_span)
+            .push(simulate_button)
+            .push(self.results_column())
+            .push(close_button)
+            .padding(PADDING)
+            .spacing(PADDING)
+            .width(IcedLength::Fill)
+            .align_x(Alignment::Center)
+            .into()
+    }
+
+    fn update(&mut self, event: DialogUpdate) {
+        if let DialogUpdate::FlybyUpdated(event) = event {
+            match event {
+                FlybyDialogEvent::MassChanged(string) => self.mass_string = string,
+                FlybyDialogEvent::AbsoluteMagnitudeChanged(string) => {
+                    self.absolute_magnitude_string = string
+                }
+                FlybyDialogEvent::PeriapsisChanged(string) => self.periapsis_string = string,
+                FlybyDialogEvent::ExcessSpeedChanged(string) => self.excess_speed_string = string,
+                FlybyDialogEvent::InclinationChanged(string) => self.inclination_string = string,
+                FlybyDialogEvent::ArgumentOfPeriapsisChanged(string) => {
+                    self.argument_of_periapsis_string = string
+                }
+                FlybyDialogEvent::PeriapsisDelayChanged(string) => {
+                    self.periapsis_delay_string = string
+                }
+                FlybyDialogEvent::TimeSpanChanged(string) => self.time_span_string = string,
+                FlybyDialogEvent::TrackComputed(track) => self.track = Some(track),
+            }
+        }
+    }
+
+    fn on_submit(&self) -> GuiMessage {
+        match (self.flyby(), self.time_span()) {
+            (Some(flyby), Some(time_span)) => GuiMessage::SimulateFlyby(flyby, time_span),
+            _ => GuiMessage::DialogClosed,
+        }
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
+
+impl<Message> canvas::Program<Message> for FlybyDialog {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        draw_background(bounds, &mut frame, &CanvasBackground::BLACK);
+        if let Some(track) = &self.track {
+            let points: Vec<(f64, f64)> = track
+                .samples
+                .iter()
+                .map(|sample| {
+                    (
+                        sample.longitude.get::<degree>(),
+                        sample.latitude.get::<degree>(),
+                    )
+                })
+                .collect();
+            draw_line_chart(&mut frame, bounds, &points, "°", "°");
+        }
+        vec![frame.into_geometry()]
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum FlybyDialogEvent {
+    MassChanged(String),
+    AbsoluteMagnitudeChanged(String),
+    PeriapsisChanged(String),
+    ExcessSpeedChanged(String),
+    InclinationChanged(String),
+    ArgumentOfPeriapsisChanged(String),
+    PeriapsisDelayChanged(String),
+    TimeSpanChanged(String),
+    TrackComputed(FlybyTrack),
+}
@@ -0,0 +1,69 @@
+use super::{Dialog, DialogUpdate};
+use crate::error::ElenathError;
+use crate::gui::{gui_widget::PADDING, message::GuiMessage};
+use crate::model::celestial_system::habitability::HabitabilityReport;
+use iced::{
+    widget::{text::Shaping, Button, Column, Text},
+    Element,
+};
+
+#[derive(Debug, Clone)]
+pub(crate) struct HabitabilityDialog {
+    reports: Vec<HabitabilityReport>,
+}
+
+impl HabitabilityDialog {
+    pub(crate) fn new(reports: Vec<HabitabilityReport>) -> Self {
+        HabitabilityDialog { reports }
+    }
+
+    fn summary(&self) -> String {
+        let candidates: Vec<&str> = self
+            .reports
+            .iter()
+            .filter(|report| report.could_host_life())
+            .map(|report| report.planet.as_str())
+            .collect();
+        if candidates.is_empty() {
+            "No world in this system passes every indicator.".to_string()
+        } else {
+            format!("Worlds that could host life: {}", candidates.join(", "))
+        }
+    }
+}
+
+impl Dialog for HabitabilityDialog {
+    fn header(&self) -> String {
+        "Habitability".to_string()
+    }
+
+    fn update(&mut self, _event: DialogUpdate) {}
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let mut col = Column::new()
+            .spacing(PADDING)
+            .push(Text::new(self.summary()));
+        for report in self.reports.iter() {
+            col = col.push(Text::new(format!(
+                "{}: {:.0}%",
+                report.planet,
+                report.score() * 100.
+            )));
+            for indicator in report.indicators.iter() {
+                let mark = if indicator.passed { "✓" } else { "✗" };
+                let line = format!("    {} {}: {}", mark, indicator.name, indicator.detail);
+                col = col.push(Text::new(line).shaping(Shaping::Advanced));
+            }
+        }
+        let button = Button::new(Text::new("Ok")).on_press(GuiMessage::DialogClosed);
+        col.push(button).into()
+    }
+
+    fn on_submit(&self) -> GuiMessage {
+        GuiMessage::DialogClosed
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
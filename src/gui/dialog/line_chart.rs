@@ -0,0 +1,112 @@
+use iced::{
+    widget::canvas::{self, Path, Stroke, Style},
+    Color, Point, Rectangle, Vector,
+};
+
+use crate::gui::gui_widget::PADDING;
+
+pub(super) const CHART_WIDTH: f32 = 800.;
+pub(super) const CHART_HEIGHT: f32 = 400.;
+const MARGIN: f32 = 60.;
+const LINE_COLOR: Color = Color {
+    r: 1.,
+    g: 0.8,
+    b: 0.3,
+    a: 1.,
+};
+
+/*
+ * Draws the points as a connected line, scaled to fill the bounds, with the axis ranges written at the edges.
+ */
+pub(super) fn draw_line_chart(
+    frame: &mut canvas::Frame,
+    bounds: Rectangle,
+    points: &[(f64, f64)],
+    x_unit: &str,
+    y_unit: &str,
+) {
+    if points.len() < 2 {
+        return;
+    }
+    let (x_min, x_max) = range(points.iter().map(|(x, _)| *x));
+    let (y_min, y_max) = range(points.iter().map(|(_, y)| *y));
+    let chart_width = bounds.width - 2. * MARGIN;
+    let chart_height = bounds.height - 2. * MARGIN;
+    let baseline = bounds.height - MARGIN;
+    let to_canvas = |x: f64, y: f64| {
+        Point::new(
+            MARGIN + ((x - x_min) / (x_max - x_min)) as f32 * chart_width,
+            baseline - ((y - y_min) / (y_max - y_min)) as f32 * chart_height,
+        )
+    };
+
+    let axes = Path::new(|path_builder| {
+        path_builder.move_to(Point::new(MARGIN, MARGIN));
+        path_builder.line_to(Point::new(MARGIN, baseline));
+        path_builder.line_to(Point::new(MARGIN + chart_width, baseline));
+    });
+    frame.stroke(
+        &axes,
+        Stroke {
+            style: Style::Solid(Color::WHITE),
+            ..Default::default()
+        },
+    );
+
+    let line = Path::new(|path_builder| {
+        let (x, y) = points[0];
+        path_builder.move_to(to_canvas(x, y));
+        for (x, y) in &points[1..] {
+            path_builder.line_to(to_canvas(*x, *y));
+        }
+    });
+    frame.stroke(
+        &line,
+        Stroke {
+            style: Style::Solid(LINE_COLOR),
+            width: 2.,
+            ..Default::default()
+        },
+    );
+
+    let labels = [
+        (
+            format!("{:.4} {}", y_max, y_unit),
+            Point::new(PADDING, MARGIN),
+        ),
+        (
+            format!("{:.4} {}", y_min, y_unit),
+            Point::new(PADDING, baseline),
+        ),
+        (
+            format!("{:.2} {}", x_min, x_unit),
+            Point::new(MARGIN, baseline) + Vector::new(0., PADDING),
+        ),
+        (
+            format!("{:.2} {}", x_max, x_unit),
+            Point::new(MARGIN + chart_width, baseline) + Vector::new(-MARGIN, PADDING),
+        ),
+    ];
+    for (content, position) in labels {
+        frame.fill_text(canvas::Text {
+            content,
+            position,
+            color: Color::WHITE,
+            ..Default::default()
+        });
+    }
+}
+
+/*
+ * A flat line still gets a range of non-zero width, so that it ends up in the middle of the chart.
+ */
+fn range(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), v| {
+        (min.min(v), max.max(v))
+    });
+    if max > min {
+        (min, max)
+    } else {
+        (min - 1., max + 1.)
+    }
+}
@@ -3,25 +3,196 @@ use crate::{
     gui::{
         gui_widget::{PADDING, SMALL_COLUMN_WIDTH},
         message::GuiMessage,
+        shared_widgets::{edit, star_merge_mode_control},
+    },
+    model::{
+        gaia_export::GaiaRelease,
+        star::{StarDataType, StarMergeMode},
+        star_catalog_filter::{SkyCone, StarCatalogFilter},
     },
-    model::star::StarDataType,
 };
+use astro_utils::real_data::stars::all::get_many_stars;
 use iced::{
-    widget::{Button, Column, Radio, Row, Text},
+    alignment::Horizontal,
+    widget::{Button, Column, PickList, Radio, Row, Slider, Text, Toggler},
     Alignment, Element, Length,
 };
+use uom::si::{
+    angle::degree,
+    f64::{Angle, Length as UomLength},
+    length::light_year,
+};
+
+// At the upper end of the slider, stars are not filtered by brightness at all.
+const MAGNITUDE_LIMIT_RANGE: (f32, f32) = (0., 12.);
+const MAGNITUDE_LIMIT_STEP: f32 = 0.1;
+
+/*
+ * Besides the bundled and fetched catalogs, stars can be read from a file exported from the Gaia archive.
+ * The fetched catalogs are tied to the release their source uses.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StarSource {
+    Catalog(StarDataType),
+    GaiaExport,
+}
 
 #[derive(Debug, Clone)]
 pub(crate) struct LoadRealStarsDialog {
-    data_type: StarDataType,
+    source: StarSource,
+    gaia_release: GaiaRelease,
+    extra_columns: bool,
+    merge_mode: StarMergeMode,
+    existing_star_count: usize,
+    magnitude_limit: f32,
+    max_distance_string: String,
+    region_longitude_string: String,
+    region_latitude_string: String,
+    region_radius_string: String,
 }
 
 impl LoadRealStarsDialog {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(existing_star_count: usize) -> Self {
         LoadRealStarsDialog {
-            data_type: StarDataType::GaiaMeasurementSmall,
+            source: StarSource::Catalog(StarDataType::GaiaMeasurementSmall),
+            gaia_release: GaiaRelease::Dr3,
+            extra_columns: true,
+            merge_mode: StarMergeMode::Replace,
+            existing_star_count,
+            magnitude_limit: MAGNITUDE_LIMIT_RANGE.1,
+            max_distance_string: String::new(),
+            region_longitude_string: String::new(),
+            region_latitude_string: String::new(),
+            region_radius_string: String::new(),
+        }
+    }
+
+    /*
+     * Empty or unparsable fields leave the respective restriction out.
+     */
+    fn filter(&self) -> StarCatalogFilter {
+        let magnitude_limit = if self.magnitude_limit >= MAGNITUDE_LIMIT_RANGE.1 {
+            f64::INFINITY
+        } else {
+            self.magnitude_limit as f64
+        };
+        let max_distance = self
+            .max_distance_string
+            .parse::<f64>()
+            .ok()
+            .filter(|distance| *distance > 0.)
+            .map(UomLength::new::<light_year>);
+        let degrees = |string: &str| string.parse::<f64>().ok().map(Angle::new::<degree>);
+        let region = match (
+            degrees(&self.region_longitude_string),
+            degrees(&self.region_latitude_string),
+            degrees(&self.region_radius_string),
+        ) {
+            (Some(longitude), Some(latitude), Some(radius)) if radius.value > 0. => Some(SkyCone {
+                longitude,
+                latitude,
+                radius,
+            }),
+            _ => None,
+        };
+        StarCatalogFilter {
+            magnitude_limit,
+            max_distance,
+            region,
         }
     }
+
+    fn filter_controls(&self) -> Column<'_, GuiMessage> {
+        let filter = self.filter();
+        let limit_text = if filter.magnitude_limit.is_finite() {
+            format!("{:.1} mag", self.magnitude_limit)
+        } else {
+            "No limit".to_string()
+        };
+        let magnitude_row = Row::new()
+            .push(
+                Text::new("Magnitude limit:")
+                    .width(SMALL_COLUMN_WIDTH)
+                    .align_x(Horizontal::Right),
+            )
+            .push(
+                Slider::new(
+                    MAGNITUDE_LIMIT_RANGE.0..=MAGNITUDE_LIMIT_RANGE.1,
+                    self.magnitude_limit,
+                    |limit| message(RealStarsEvent::MagnitudeLimitChanged(limit)),
+                )
+                .step(MAGNITUDE_LIMIT_STEP)
+                .width(2. * SMALL_COLUMN_WIDTH),
+            )
+            .push(Text::new(limit_text))
+            .spacing(PADDING)
+            .align_y(Alignment::Center);
+        let max_distance = edit(
+            "Maximum distance",
+            &self.max_distance_string,
+            "ly",
+            |s| message(RealStarsEvent::MaxDistanceChanged(s)),
+            &filter.max_distance,
+        );
+        let region = filter.region;
+        let region_longitude = edit(
+            "Region longitude",
+            &self.region_longitude_string,
+            "°",
+            |s| message(RealStarsEvent::RegionLongitudeChanged(s)),
+            &region.map(|region| region.longitude),
+        );
+        let region_latitude = edit(
+            "Region latitude",
+            &self.region_latitude_string,
+            "°",
+            |s| message(RealStarsEvent::RegionLatitudeChanged(s)),
+            &region.map(|region| region.latitude),
+        );
+        let region_radius = edit(
+            "Region radius",
+            &self.region_radius_string,
+            "°",
+            |s| message(RealStarsEvent::RegionRadiusChanged(s)),
+            &region.map(|region| region.radius),
+        );
+        Column::new()
+            .push(Text::new(
+                "Only stars that pass all filters are imported. Stars without a known distance are never dropped for it, and the region is given in ecliptic coordinates.",
+            ))
+            .push(magnitude_row)
+            .push(max_distance)
+            .push(region_longitude)
+            .push(region_latitude)
+            .push(region_radius)
+            .spacing(PADDING)
+            .align_x(Alignment::Center)
+    }
+
+    fn incoming_star_count(&self) -> Option<usize> {
+        match self.source {
+            StarSource::Catalog(StarDataType::Hardcoded) => Some(get_many_stars().len()),
+            _ => None,
+        }
+    }
+
+    fn gaia_export_controls(&self) -> Row<'_, GuiMessage> {
+        let release_picker = PickList::new(GaiaRelease::ALL, Some(self.gaia_release), |release| {
+            message(RealStarsEvent::GaiaReleaseSelected(release))
+        });
+        let extra_columns_toggle = Toggler::new(self.extra_columns)
+            .label("Keep parallax error and BP-RP colour")
+            .on_toggle(|b| message(RealStarsEvent::ExtraColumnsToggled(b)));
+        Row::new()
+            .push(release_picker)
+            .push(extra_columns_toggle)
+            .spacing(PADDING)
+            .align_y(Alignment::Center)
+    }
+}
+
+fn message(event: RealStarsEvent) -> GuiMessage {
+    GuiMessage::DialogUpdate(DialogUpdate::LoadRealStarsUpdated(event))
 }
 
 impl Dialog for LoadRealStarsDialog {
@@ -30,54 +201,52 @@ impl Dialog for LoadRealStarsDialog {
     }
 
     fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
-        let warning = Text::new("This will overwrite all stars in the current system.");
+        let merge_mode_control = star_merge_mode_control(
+            self.merge_mode,
+            self.existing_star_count,
+            self.incoming_star_count(),
+            |mode| {
+                GuiMessage::DialogUpdate(DialogUpdate::LoadRealStarsUpdated(
+                    RealStarsEvent::MergeModeSelected(mode),
+                ))
+            },
+        );
 
-        let on_change = |data_type| {
-            GuiMessage::DialogUpdate(DialogUpdate::LoadRealStarsUpdated(
-                RealStarsEvent::DataTypeSelected(data_type),
-            ))
+        let on_change = |source| message(RealStarsEvent::SourceSelected(source));
+        let radio = |label: &str, source: StarSource| {
+            Radio::new(label, source, Some(self.source), on_change).width(SMALL_COLUMN_WIDTH)
         };
-        let hardcoded_radio = Radio::new(
-            "Hardcoded",
-            StarDataType::Hardcoded,
-            Some(self.data_type),
-            on_change,
-        )
-        .width(SMALL_COLUMN_WIDTH);
-        let measurement_small_radio = Radio::new(
+        let hardcoded_radio = radio("Hardcoded", StarSource::Catalog(StarDataType::Hardcoded));
+        let measurement_small_radio = radio(
             "Gaia Measurement (only brightest)",
-            StarDataType::GaiaMeasurementSmall,
-            Some(self.data_type),
-            on_change,
-        )
-        .width(SMALL_COLUMN_WIDTH);
-        let measurement_large_radio = Radio::new(
+            StarSource::Catalog(StarDataType::GaiaMeasurementSmall),
+        );
+        let measurement_large_radio = radio(
             "Gaia Measurement (loads)",
-            StarDataType::GaiaMeasurementLarge,
-            Some(self.data_type),
-            on_change,
-        )
-        .width(SMALL_COLUMN_WIDTH);
-        let simulation_radio = Radio::new(
+            StarSource::Catalog(StarDataType::GaiaMeasurementLarge),
+        );
+        let simulation_radio = radio(
             "GaiaSimulation",
-            StarDataType::GaiaSimulation,
-            Some(self.data_type),
-            on_change,
-        )
-        .width(SMALL_COLUMN_WIDTH);
+            StarSource::Catalog(StarDataType::GaiaSimulation),
+        );
+        let export_radio = radio("Gaia Archive Export (.csv)", StarSource::GaiaExport);
         let type_row = Row::new()
             .push(hardcoded_radio)
             .push(measurement_small_radio)
             .push(measurement_large_radio)
             .push(simulation_radio)
+            .push(export_radio)
             .padding(PADDING)
             .spacing(PADDING);
 
         let submit_button = Button::new(Text::new("Submit")).on_press(GuiMessage::DialogSubmit);
 
-        Column::new()
-            .push(warning)
-            .push(type_row)
+        let mut col = Column::new().push(type_row);
+        if self.source == StarSource::GaiaExport {
+            col = col.push(self.gaia_export_controls());
+        }
+        col.push(self.filter_controls())
+            .push(merge_mode_control)
             .push(submit_button)
             .padding(PADDING)
             .spacing(PADDING)
@@ -89,15 +258,49 @@ impl Dialog for LoadRealStarsDialog {
     fn update(&mut self, event: DialogUpdate) {
         if let DialogUpdate::LoadRealStarsUpdated(event) = event {
             match event {
-                RealStarsEvent::DataTypeSelected(data_type) => {
-                    self.data_type = data_type;
+                RealStarsEvent::SourceSelected(source) => {
+                    self.source = source;
+                }
+                RealStarsEvent::GaiaReleaseSelected(release) => {
+                    self.gaia_release = release;
+                }
+                RealStarsEvent::ExtraColumnsToggled(extra_columns) => {
+                    self.extra_columns = extra_columns;
+                }
+                RealStarsEvent::MergeModeSelected(mode) => {
+                    self.merge_mode = mode;
+                }
+                RealStarsEvent::MagnitudeLimitChanged(limit) => {
+                    self.magnitude_limit = limit;
+                }
+                RealStarsEvent::MaxDistanceChanged(string) => {
+                    self.max_distance_string = string;
+                }
+                RealStarsEvent::RegionLongitudeChanged(string) => {
+                    self.region_longitude_string = string;
+                }
+                RealStarsEvent::RegionLatitudeChanged(string) => {
+                    self.region_latitude_string = string;
+                }
+                RealStarsEvent::RegionRadiusChanged(string) => {
+                    self.region_radius_string = string;
                 }
             }
         }
     }
 
     fn on_submit(&self) -> GuiMessage {
-        GuiMessage::LoadStars(self.data_type)
+        match self.source {
+            StarSource::Catalog(data_type) => {
+                GuiMessage::LoadStars(data_type, self.merge_mode, self.filter())
+            }
+            StarSource::GaiaExport => GuiMessage::LoadGaiaExport(
+                self.gaia_release,
+                self.extra_columns,
+                self.merge_mode,
+                self.filter(),
+            ),
+        }
     }
 
     fn get_error(&self) -> Option<ElenathError> {
@@ -107,5 +310,13 @@ impl Dialog for LoadRealStarsDialog {
 
 #[derive(Debug, Clone)]
 pub(crate) enum RealStarsEvent {
-    DataTypeSelected(StarDataType),
+    SourceSelected(StarSource),
+    GaiaReleaseSelected(GaiaRelease),
+    ExtraColumnsToggled(bool),
+    MergeModeSelected(StarMergeMode),
+    MagnitudeLimitChanged(f32),
+    MaxDistanceChanged(String),
+    RegionLongitudeChanged(String),
+    RegionLatitudeChanged(String),
+    RegionRadiusChanged(String),
 }
@@ -0,0 +1,110 @@
+use super::{Dialog, DialogUpdate, ElenathError};
+use crate::{
+    gui::{gui_widget::PADDING, message::GuiMessage, shared_widgets::edit},
+    model::{celestial_system::metadata::SystemMetadata, star_naming::NamingStyle},
+};
+use iced::{
+    widget::{Button, Column, PickList, Row, Text},
+    Alignment, Element, Length,
+};
+
+#[derive(Debug, Clone)]
+pub(crate) struct MetadataDialog {
+    metadata: SystemMetadata,
+}
+
+impl MetadataDialog {
+    pub(crate) fn new(metadata: SystemMetadata) -> Self {
+        MetadataDialog { metadata }
+    }
+}
+
+impl Dialog for MetadataDialog {
+    fn header(&self) -> String {
+        "System Info".to_string()
+    }
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let name = edit(
+            "Name",
+            &self.metadata.name,
+            "",
+            message(MetadataDialogEvent::NameChanged),
+            &Some(&self.metadata.name),
+        );
+        let author = edit(
+            "Author",
+            &self.metadata.author,
+            "",
+            message(MetadataDialogEvent::AuthorChanged),
+            &Some(&self.metadata.author),
+        );
+        let notes = edit(
+            "Notes",
+            &self.metadata.notes,
+            "",
+            message(MetadataDialogEvent::NotesChanged),
+            &Some(&self.metadata.notes),
+        );
+        let naming_style = Row::new()
+            .push(Text::new("Names for unnamed stars:"))
+            .push(PickList::new(
+                NamingStyle::ALL,
+                Some(self.metadata.naming_style),
+                |style| {
+                    GuiMessage::DialogUpdate(DialogUpdate::MetadataUpdated(
+                        MetadataDialogEvent::NamingStyleChanged(style),
+                    ))
+                },
+            ))
+            .spacing(PADDING)
+            .align_y(Alignment::Center);
+        let creation_date = Text::new(format!("Created: {}", self.metadata.creation_date));
+        let submit_button = Button::new(Text::new("Submit")).on_press(GuiMessage::DialogSubmit);
+        Column::new()
+            .push(name)
+            .push(author)
+            .push(notes)
+            .push(naming_style)
+            .push(creation_date)
+            .push(submit_button)
+            .padding(PADDING)
+            .spacing(PADDING)
+            .width(Length::Fill)
+            .align_x(Alignment::Center)
+            .into()
+    }
+
+    fn update(&mut self, message: DialogUpdate) {
+        if let DialogUpdate::MetadataUpdated(event) = message {
+            match event {
+                MetadataDialogEvent::NameChanged(name) => self.metadata.name = name,
+                MetadataDialogEvent::AuthorChanged(author) => self.metadata.author = author,
+                MetadataDialogEvent::NotesChanged(notes) => self.metadata.notes = notes,
+                MetadataDialogEvent::NamingStyleChanged(style) => {
+                    self.metadata.naming_style = style
+                }
+            }
+        }
+    }
+
+    fn on_submit(&self) -> GuiMessage {
+        GuiMessage::MetadataEdited(self.metadata.clone())
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum MetadataDialogEvent {
+    NameChanged(String),
+    AuthorChanged(String),
+    NotesChanged(String),
+    NamingStyleChanged(NamingStyle),
+}
+
+fn message<F: Fn(String) -> MetadataDialogEvent>(event: F) -> impl Fn(String) -> GuiMessage {
+    move |m| GuiMessage::DialogUpdate(DialogUpdate::MetadataUpdated(event(m)))
+}
@@ -0,0 +1,183 @@
+use super::{Dialog, DialogUpdate};
+use crate::{
+    error::ElenathError,
+    gui::{gui_widget::PADDING, message::GuiMessage, shared_widgets::edit},
+    model::{
+        body_id::BodyId,
+        migration::{MigrationKeyframe, MigrationSchedule},
+    },
+};
+use astro_utils::astro_display::AstroDisplay;
+use iced::{
+    widget::{text::Shaping, Button, Column, Row, Text},
+    Alignment, Element, Length as IcedLength,
+};
+use std::cmp::Ordering;
+use uom::si::{
+    f64::{Length, Time},
+    length::astronomical_unit,
+    time::year,
+};
+
+#[derive(Debug, Clone)]
+pub(crate) struct MigrationDialog {
+    planet_name: String,
+    planet_id: BodyId,
+    keyframes: Vec<MigrationKeyframe>,
+    time: Option<Time>,
+    time_string: String,
+    semi_major_axis: Option<Length>,
+    semi_major_axis_string: String,
+}
+
+impl MigrationDialog {
+    pub(crate) fn new(
+        planet_name: String,
+        planet_id: BodyId,
+        schedule: Option<&MigrationSchedule>,
+        current_semi_major_axis: Length,
+    ) -> Self {
+        MigrationDialog {
+            planet_name,
+            planet_id,
+            keyframes: schedule
+                .map(|schedule| schedule.get_keyframes().to_vec())
+                .unwrap_or_default(),
+            time: None,
+            time_string: String::new(),
+            semi_major_axis: Some(current_semi_major_axis),
+            semi_major_axis_string: format!(
+                "{:.3}",
+                current_semi_major_axis.get::<astronomical_unit>()
+            ),
+        }
+    }
+
+    fn keyframe_row(&self, index: usize, keyframe: &MigrationKeyframe) -> Row<'_, GuiMessage> {
+        let description = format!(
+            "At {}: {}",
+            keyframe.time.astro_display(),
+            keyframe.semi_major_axis.astro_display()
+        );
+        let remove_button = Button::new(Text::new("Remove")).on_press(GuiMessage::DialogUpdate(
+            DialogUpdate::MigrationUpdated(MigrationDialogEvent::RemoveKeyframe(index)),
+        ));
+        Row::new()
+            .push(Text::new(description).shaping(Shaping::Advanced))
+            .push(remove_button)
+            .spacing(PADDING)
+            .align_y(Alignment::Center)
+    }
+}
+
+fn message<F: Fn(String) -> MigrationDialogEvent>(event: F) -> impl Fn(String) -> GuiMessage {
+    move |m| GuiMessage::DialogUpdate(DialogUpdate::MigrationUpdated(event(m)))
+}
+
+impl Dialog for MigrationDialog {
+    fn header(&self) -> String {
+        format!("Migration of {}", self.planet_name)
+    }
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let explanation = Text::new(
+            "The semi-major axis is interpolated between the keyframes, and held before the first and after the last one.",
+        );
+        let mut keyframes = Column::new().spacing(PADDING);
+        if self.keyframes.is_empty() {
+            keyframes = keyframes.push(Text::new("No keyframes, the planet does not migrate."));
+        }
+        for (index, keyframe) in self.keyframes.iter().enumerate() {
+            keyframes = keyframes.push(self.keyframe_row(index, keyframe));
+        }
+
+        let time = edit(
+            "Time",
+            &self.time_string,
+            "years since epoch",
+            message(MigrationDialogEvent::TimeChanged),
+            &self.time,
+        );
+        let semi_major_axis = edit(
+            "Semi-major Axis",
+            &self.semi_major_axis_string,
+            "AU",
+            message(MigrationDialogEvent::SemiMajorAxisChanged),
+            &self.semi_major_axis,
+        );
+        let mut add_button = Button::new(Text::new("Add Keyframe"));
+        if self.time.is_some() && self.semi_major_axis.is_some() {
+            add_button = add_button.on_press(GuiMessage::DialogUpdate(
+                DialogUpdate::MigrationUpdated(MigrationDialogEvent::AddKeyframe),
+            ));
+        }
+        let submit_button = Button::new(Text::new("Submit")).on_press(GuiMessage::DialogSubmit);
+
+        Column::new()
+            .push(explanation)
+            .push(keyframes)
+            .push(time)
+            .push(semi_major_axis)
+            .push(add_button)
+            .push(submit_button)
+            .padding(PADDING)
+            .spacing(PADDING)
+            .width(IcedLength::Fill)
+            .align_x(Alignment::Center)
+            .into()
+    }
+
+    fn update(&mut self, message: DialogUpdate) {
+        if let DialogUpdate::MigrationUpdated(event) = message {
+            match event {
+                MigrationDialogEvent::TimeChanged(time_string) => {
+                    self.time = time_string.parse::<f64>().ok().map(Time::new::<year>);
+                    self.time_string = time_string;
+                }
+                MigrationDialogEvent::SemiMajorAxisChanged(semi_major_axis_string) => {
+                    self.semi_major_axis = semi_major_axis_string
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|au| *au > 0.)
+                        .map(Length::new::<astronomical_unit>);
+                    self.semi_major_axis_string = semi_major_axis_string;
+                }
+                MigrationDialogEvent::AddKeyframe => {
+                    if let (Some(time), Some(semi_major_axis)) = (self.time, self.semi_major_axis) {
+                        self.keyframes.retain(|keyframe| keyframe.time != time);
+                        self.keyframes.push(MigrationKeyframe {
+                            time,
+                            semi_major_axis,
+                        });
+                        self.keyframes
+                            .sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap_or(Ordering::Equal));
+                    }
+                }
+                MigrationDialogEvent::RemoveKeyframe(index) => {
+                    if index < self.keyframes.len() {
+                        self.keyframes.remove(index);
+                    }
+                }
+            }
+        }
+    }
+
+    fn on_submit(&self) -> GuiMessage {
+        GuiMessage::SetMigrationSchedule(MigrationSchedule::new(
+            self.planet_id,
+            self.keyframes.clone(),
+        ))
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum MigrationDialogEvent {
+    TimeChanged(String),
+    SemiMajorAxisChanged(String),
+    AddKeyframe,
+    RemoveKeyframe(usize),
+}
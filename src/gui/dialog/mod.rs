@@ -1,37 +1,114 @@
-use crate::error::ElenathError;
+use crate::{error::ElenathError, model::body_id::BodyId};
 
-use super::message::GuiMessage;
+use super::{message::GuiMessage, screenshot::ExportCaption};
+use binary_planets::BinaryPlanetsDialogEvent;
+use calendar::CalendarDialogEvent;
+use co_orbital::CoOrbitalDialogEvent;
+use epoch::EpochDialogEvent;
+use flyby::FlybyDialogEvent;
 use iced::{
     widget::{Container, Scrollable, Text},
     Element,
 };
 use iced_aw::{style, Card};
 use load_real_stars::RealStarsEvent;
+use metadata::MetadataDialogEvent;
+use migration::MigrationDialogEvent;
 use new_system::NewSystemDialogEvent;
 use planet::PlanetDialogEvent;
 use randomize_stars::RandomizeStarsDialogEvent;
 use star::StarDialogEvent;
+use star_forming_regions::StarFormingRegionsDialogEvent;
+use star_jitter::StarJitterDialogEvent;
+use star_rendering::StarRenderingDialogEvent;
+use star_statistics::StarStatisticsDialogEvent;
+use travel_time::TravelTimeDialogEvent;
 
+pub(crate) mod binary_planets;
+pub(crate) mod calendar;
+pub(crate) mod co_orbital;
+pub(crate) mod duplicate_stars;
+pub(crate) mod epoch;
+pub(crate) mod equation_of_time;
 pub(crate) mod error;
+pub(crate) mod export_screenshot;
+pub(crate) mod file_conflict;
+pub(crate) mod flyby;
+pub(crate) mod habitability;
+mod line_chart;
 pub(crate) mod load_real_planets;
 pub(crate) mod load_real_stars;
+pub(crate) mod metadata;
+pub(crate) mod migration;
 pub(crate) mod new_system;
 pub(crate) mod planet;
+pub(crate) mod radial_velocity;
 pub(crate) mod randomize_planets;
 pub(crate) mod randomize_stars;
+pub(crate) mod sky_region;
+pub(crate) mod space_engine_import;
+pub(crate) mod stability;
 pub(crate) mod star;
+pub(crate) mod star_forming_regions;
+pub(crate) mod star_jitter;
+pub(crate) mod star_rendering;
+pub(crate) mod star_statistics;
+pub(crate) mod transit;
+pub(crate) mod travel_time;
+pub(crate) mod zodiac;
 
 #[derive(Debug, Clone)]
 pub(crate) enum DialogType {
     NewSystem,
     NewPlanet,
-    EditPlanet(usize),
+    EditPlanet(BodyId),
     NewStar,
-    EditStar(Option<usize>),
+    EditStar(Option<BodyId>),
+    EditCentralBody,
+    EditMetadata,
     LoadRealPlanets,
     RandomizePlanets,
     LoadGaiaData,
     RandomizeStars,
+    StarStatistics,
+    DuplicateStars,
+    StarJitter,
+    StarRendering,
+    ExportScreenshot,
+    TravelTime,
+    TransitLightCurve(BodyId),
+    RadialVelocity,
+    EquationOfTime,
+    Habitability,
+    Zodiac,
+    Flyby,
+    BinaryPlanets,
+    CoOrbital,
+    StarFormingRegions,
+    Migration,
+    RedefineEpoch,
+    Calendar,
+}
+
+impl DialogType {
+    /*
+     * Whether submitting the dialog changes the system. The others only compute or display something.
+     */
+    pub(crate) fn edits_system(&self) -> bool {
+        !matches!(
+            self,
+            DialogType::StarStatistics
+                | DialogType::StarRendering
+                | DialogType::ExportScreenshot
+                | DialogType::TravelTime
+                | DialogType::TransitLightCurve(..)
+                | DialogType::RadialVelocity
+                | DialogType::EquationOfTime
+                | DialogType::Habitability
+                | DialogType::Zodiac
+                | DialogType::Flyby
+        )
+    }
 }
 
 pub(crate) enum CardStyle {
@@ -73,11 +150,26 @@ pub(crate) trait Dialog {
 
 #[derive(Debug, Clone)]
 pub(crate) enum DialogUpdate {
+    BinaryPlanetsUpdated(BinaryPlanetsDialogEvent),
+    CalendarUpdated(CalendarDialogEvent),
+    CoOrbitalUpdated(CoOrbitalDialogEvent),
+    EpochUpdated(EpochDialogEvent),
+    FlybyUpdated(FlybyDialogEvent),
     LoadRealStarsUpdated(RealStarsEvent),
+    MetadataUpdated(MetadataDialogEvent),
+    MigrationUpdated(MigrationDialogEvent),
     NewSystemUpdated(NewSystemDialogEvent),
     PlanetUpdated(PlanetDialogEvent),
     RandmoizeStarsUpdated(RandomizeStarsDialogEvent),
     StarUpdated(StarDialogEvent),
+    StarFormingRegionsUpdated(StarFormingRegionsDialogEvent),
+    StarJitterUpdated(StarJitterDialogEvent),
+    StarRenderingUpdated(StarRenderingDialogEvent),
+    StarStatisticsUpdated(StarStatisticsDialogEvent),
+    // No descriptions means that the stars changed while the duplicates were searched for.
+    DuplicateStarsFound(Option<Vec<String>>),
+    ExportCaptionUpdated(ExportCaption),
+    TravelTimeUpdated(TravelTimeDialogEvent),
     Submit,
     Close,
 }
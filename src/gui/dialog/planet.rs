@@ -11,19 +11,29 @@ use astro_utils::{
     stars::data::StarData,
 };
 use iced::{
-    widget::{text::Shaping, Button, Column, Row, Text},
+    alignment::Horizontal,
+    widget::{text::Shaping, Button, Column, PickList, Row, Text, TextInput},
     Alignment, Element, Length as IcedLength,
 };
 use uom::si::{
     angle::degree,
-    f64::{Angle, Length, Mass, Time},
+    f64::{Angle, Length, Mass, MassDensity, Time},
     length::astronomical_unit,
-    time::day,
+    mass_density::gram_per_cubic_centimeter,
+    time::{day, year},
 };
 
 use crate::{
     error::ElenathError,
-    gui::{gui_widget::PADDING, message::GuiMessage, shared_widgets::edit},
+    gui::{
+        gui_widget::{PADDING, SMALL_COLUMN_WIDTH},
+        message::GuiMessage,
+        shared_widgets::edit,
+    },
+    model::{
+        body_id::BodyId, body_notes::BodyNotes, celestial_system::naming::check_body_name,
+        resonance::Resonance,
+    },
 };
 
 use super::{Dialog, DialogUpdate};
@@ -31,7 +41,8 @@ use super::{Dialog, DialogUpdate};
 #[derive(Debug, Clone)]
 pub(crate) struct PlanetDialog {
     planet: PlanetData,
-    planet_index: Option<usize>,
+    planet_id: Option<BodyId>,
+    original_name: Option<String>,
     previous_planet: Option<DerivedPlanetData>,
     central_body: StarData,
     mass_string: String,
@@ -45,6 +56,17 @@ pub(crate) struct PlanetDialog {
     argument_of_periapsis_string: String,
     siderial_rotation_period_string: String,
     rotation_axis_string: String,
+    precession_period: Option<Time>,
+    precession_period_string: String,
+    rotation_angle: Angle,
+    rotation_angle_string: String,
+    resonance_partners: Vec<PlanetData>,
+    resonance_partner_name: Option<String>,
+    resonance_string: String,
+    resonance_error: Option<ElenathError>,
+    reported_resonance: Option<String>,
+    notes: BodyNotes,
+    tags_string: String,
     error: Option<ElenathError>,
 }
 
@@ -55,13 +77,17 @@ fn message<F: Fn(String) -> PlanetDialogEvent>(event: F) -> impl Fn(String) -> G
 impl PlanetDialog {
     pub(crate) fn edit(
         planet: PlanetData,
-        planet_index: usize,
+        planet_id: BodyId,
         previous_planet: Option<DerivedPlanetData>,
         central_body: StarData,
+        resonance_partners: Vec<PlanetData>,
+        notes: BodyNotes,
+        precession_period: Option<Time>,
     ) -> Result<Self, ElenathError> {
         let mut dialog = PlanetDialog {
+            original_name: Some(planet.get_name().clone()),
             planet: planet.clone(),
-            planet_index: Some(planet_index),
+            planet_id: Some(planet_id),
             previous_planet,
             central_body,
             mass_string: String::new(),
@@ -75,13 +101,36 @@ impl PlanetDialog {
             argument_of_periapsis_string: String::new(),
             siderial_rotation_period_string: String::new(),
             rotation_axis_string: String::new(),
+            precession_period,
+            precession_period_string: String::new(),
+            rotation_angle: Angle::new::<degree>(0.),
+            rotation_angle_string: String::new(),
+            resonance_partners,
+            resonance_partner_name: None,
+            resonance_string: String::new(),
+            resonance_error: None,
+            reported_resonance: None,
+            tags_string: notes.tags_string(),
+            notes,
             error: None,
         };
         dialog.fill_string_members()?;
         Ok(dialog)
     }
 
-    pub(crate) fn new(central_body: StarData) -> Result<Self, ElenathError> {
+    /*
+     * How far the planet has turned around its axis at epoch, which the planet data itself does not hold.
+     */
+    pub(crate) fn with_rotation_angle(mut self, rotation_angle: Angle) -> Self {
+        self.rotation_angle = rotation_angle;
+        self.rotation_angle_string = format!("{:.2}", rotation_angle.get::<degree>());
+        self
+    }
+
+    pub(crate) fn new(
+        central_body: StarData,
+        resonance_partners: Vec<PlanetData>,
+    ) -> Result<Self, ElenathError> {
         let physical_parameters = PlanetPhysicalParameters::new(
             Mass::new::<earth_mass>(0.),
             Length::new::<earth_radius>(0.),
@@ -100,7 +149,8 @@ impl PlanetDialog {
         let planet = PlanetData::new(String::new(), physical_parameters, orbital_parameters);
         let mut dialog = PlanetDialog {
             planet,
-            planet_index: None,
+            planet_id: None,
+            original_name: None,
             previous_planet: None,
             central_body,
             mass_string: String::new(),
@@ -114,6 +164,17 @@ impl PlanetDialog {
             argument_of_periapsis_string: String::new(),
             siderial_rotation_period_string: String::new(),
             rotation_axis_string: String::new(),
+            precession_period: None,
+            precession_period_string: String::new(),
+            rotation_angle: Angle::new::<degree>(0.),
+            rotation_angle_string: String::new(),
+            resonance_partners,
+            resonance_partner_name: None,
+            resonance_string: String::new(),
+            resonance_error: None,
+            reported_resonance: None,
+            tags_string: String::new(),
+            notes: BodyNotes::default(),
             error: None,
         };
         dialog.fill_string_members()?;
@@ -170,9 +231,95 @@ impl PlanetDialog {
                     e
                 ))
             })?;
+        self.precession_period_string = self
+            .precession_period
+            .map(|period| format!("{:.0}", period.get::<year>()))
+            .unwrap_or_default();
+        self.rotation_angle_string = format!("{:.2}", self.rotation_angle.get::<degree>());
         Ok(())
     }
 
+    fn apply_resonance(&mut self) -> Result<(), ElenathError> {
+        let partner_name = self
+            .resonance_partner_name
+            .as_ref()
+            .ok_or(ElenathError::Generic(
+                "Select a planet to be in resonance with.".to_string(),
+            ))?;
+        let partner = self
+            .resonance_partners
+            .iter()
+            .find(|p| p.get_name() == partner_name)
+            .ok_or(ElenathError::BodyNotFound)?;
+        let resonance = self.resonance_string.parse::<Resonance>()?;
+        let semi_major_axis =
+            resonance.semi_major_axis(partner, &self.planet, &self.central_body)?;
+        self.planet.set_semi_major_axis(semi_major_axis);
+        let partner_data = DerivedPlanetData::new(partner, &self.central_body, None)?;
+        let reported =
+            DerivedPlanetData::new(&self.planet, &self.central_body, Some(&partner_data))?
+                .get_orbital_resonance()
+                .astro_display();
+        self.reported_resonance = Some(format!("Resonance with {}: {}", partner_name, reported));
+        self.fill_string_members()
+    }
+
+    fn resonance_field(&self) -> Element<'_, GuiMessage> {
+        let partner_names: Vec<String> = self
+            .resonance_partners
+            .iter()
+            .map(|p| p.get_name().clone())
+            .collect();
+        let partner_picker = PickList::new(
+            partner_names,
+            self.resonance_partner_name.clone(),
+            message(PlanetDialogEvent::ResonancePartnerSelected),
+        )
+        .placeholder("Planet")
+        .width(SMALL_COLUMN_WIDTH);
+        let resonance_input = TextInput::new("3:2", &self.resonance_string)
+            .on_input(message(PlanetDialogEvent::ResonanceChanged))
+            .width(SMALL_COLUMN_WIDTH);
+        let apply_message = GuiMessage::DialogUpdate(DialogUpdate::PlanetUpdated(
+            PlanetDialogEvent::ApplyResonance,
+        ));
+        let apply_button = Button::new(Text::new("Apply Resonance")).on_press(apply_message);
+        let row = Row::new()
+            .push(
+                Text::new("Resonance with:")
+                    .width(SMALL_COLUMN_WIDTH)
+                    .align_x(Horizontal::Right),
+            )
+            .push(partner_picker)
+            .push(resonance_input)
+            .push(apply_button)
+            .spacing(PADDING)
+            .align_y(Alignment::Center);
+        let mut col = Column::new().push(row).spacing(PADDING);
+        if let Some(e) = &self.resonance_error {
+            col = col.push(Text::new(e.to_string()));
+        } else if let Some(reported) = &self.reported_resonance {
+            col = col.push(Text::new(reported).shaping(Shaping::Advanced));
+        }
+        col.into()
+    }
+
+    /*
+     * The other planets are exactly the resonance partners. An edited planet may keep its name, even if it is not unique.
+     */
+    fn name_error(&self) -> Option<ElenathError> {
+        let name = self.planet.get_name();
+        if self.original_name.as_ref() == Some(name) {
+            return None;
+        }
+        let taken = self
+            .resonance_partners
+            .iter()
+            .map(|partner| partner.get_name().clone())
+            .collect();
+        check_body_name(name, &taken).err()
+    }
+
     fn edit_column(&self) -> Element<'_, GuiMessage> {
         let randomize_message =
             GuiMessage::DialogUpdate(DialogUpdate::PlanetUpdated(PlanetDialogEvent::Randomize));
@@ -270,8 +417,42 @@ impl PlanetDialog {
             message(PlanetDialogEvent::RotationAxisChanged),
             &Some(self.planet.get_rotation_axis()),
         );
+        let precession_period = edit(
+            "Precession Period",
+            &self.precession_period_string,
+            "Years",
+            message(PlanetDialogEvent::PrecessionPeriodChanged),
+            &self.precession_period,
+        );
+        let rotation_angle = edit(
+            "Prime Meridian at Epoch",
+            &self.rotation_angle_string,
+            "°",
+            message(PlanetDialogEvent::RotationAngleChanged),
+            &Some(self.rotation_angle),
+        );
 
-        let submit_button = Button::new(Text::new("Submit")).on_press(GuiMessage::DialogSubmit);
+        let notes = edit(
+            "Notes",
+            &self.notes.notes,
+            "",
+            message(PlanetDialogEvent::NotesChanged),
+            &Some(&self.notes.notes),
+        );
+        let tags = edit(
+            "Tags",
+            &self.tags_string,
+            "comma separated",
+            message(PlanetDialogEvent::TagsChanged),
+            &Some(&self.tags_string),
+        );
+
+        let mut name = Column::new().push(name).spacing(PADDING);
+        let mut submit_button = Button::new(Text::new("Submit"));
+        match self.name_error() {
+            Some(e) => name = name.push(Text::new(e.to_string())),
+            None => submit_button = submit_button.on_press(GuiMessage::DialogSubmit),
+        }
 
         Column::new()
             .push(randomize_button)
@@ -281,12 +462,17 @@ impl PlanetDialog {
             .push(color)
             .push(geometric_albedo)
             .push(semi_major_axis)
+            .push(self.resonance_field())
             .push(eccentricity)
             .push(inclination)
             .push(longitude_of_ascending_node)
             .push(argument_of_periapsis)
             .push(siderial_rotation_period)
             .push(rotation_axis)
+            .push(precession_period)
+            .push(rotation_angle)
+            .push(notes)
+            .push(tags)
             .push(submit_button)
             .spacing(PADDING)
             .width(IcedLength::Fill)
@@ -349,8 +535,11 @@ impl PlanetDialog {
                         + &derived_data.get_black_body_temperature().astro_display(),
                 )
                 .shaping(Shaping::Advanced);
+                col = col.push(density_text);
+                if let Some(warning) = density_warning(derived_data.get_density()) {
+                    col = col.push(Text::new(warning).shaping(Shaping::Advanced));
+                }
                 col = col
-                    .push(density_text)
                     .push(surface_gravity_text)
                     .push(escape_velocity_text)
                     .push(orbital_period_text)
@@ -372,10 +561,31 @@ impl PlanetDialog {
     }
 }
 
+/*
+ * Solid hydrogen is the lightest material a planet could plausibly be made of, iron the densest one in abundance.
+ * Compression inside massive planets can push the bulk density a bit above that of iron, which is why the warning is non-blocking.
+ */
+fn density_warning(density: MassDensity) -> Option<String> {
+    const MIN_DENSITY: f64 = 0.09;
+    const MAX_DENSITY: f64 = 7.9;
+    let density = density.get::<gram_per_cubic_centimeter>();
+    let problem = if density < MIN_DENSITY {
+        "lighter than hydrogen"
+    } else if density > MAX_DENSITY {
+        "denser than iron"
+    } else {
+        return None;
+    };
+    Some(format!(
+        "Warning: This planet is {}.\nPlausible densities range from {} to {} g/cm³.",
+        problem, MIN_DENSITY, MAX_DENSITY
+    ))
+}
+
 impl Dialog for PlanetDialog {
     fn header(&self) -> String {
-        match self.planet_index {
-            Some(index) => format!("Edit Planet {}", index),
+        match self.planet_id {
+            Some(id) => format!("Edit Planet {}", id),
             None => "Create Planet".to_string(),
         }
     }
@@ -478,6 +688,37 @@ impl Dialog for PlanetDialog {
                     }
                     self.rotation_axis_string = rotation_axis_string;
                 }
+                PlanetDialogEvent::PrecessionPeriodChanged(precession_period_string) => {
+                    if precession_period_string.trim().is_empty() {
+                        self.precession_period = None;
+                    } else if let Ok(period) = precession_period_string.parse::<f64>() {
+                        self.precession_period = Some(Time::new::<year>(period));
+                    }
+                    self.precession_period_string = precession_period_string;
+                }
+                PlanetDialogEvent::RotationAngleChanged(rotation_angle_string) => {
+                    if let Ok(angle) = rotation_angle_string.parse::<f64>() {
+                        self.rotation_angle = Angle::new::<degree>(angle);
+                    }
+                    self.rotation_angle_string = rotation_angle_string;
+                }
+                PlanetDialogEvent::ResonancePartnerSelected(name) => {
+                    self.resonance_partner_name = Some(name);
+                }
+                PlanetDialogEvent::ResonanceChanged(resonance_string) => {
+                    self.resonance_string = resonance_string;
+                }
+                PlanetDialogEvent::ApplyResonance => {
+                    self.reported_resonance = None;
+                    self.resonance_error = self.apply_resonance().err();
+                }
+                PlanetDialogEvent::NotesChanged(notes) => {
+                    self.notes.notes = notes;
+                }
+                PlanetDialogEvent::TagsChanged(tags_string) => {
+                    self.notes.set_tags_from_string(&tags_string);
+                    self.tags_string = tags_string;
+                }
                 PlanetDialogEvent::Randomize => {
                     let name = self.planet.get_name().clone();
                     self.planet = generate_random_planet();
@@ -491,9 +732,20 @@ impl Dialog for PlanetDialog {
     }
 
     fn on_submit(&self) -> GuiMessage {
-        match self.planet_index {
-            Some(index) => GuiMessage::PlanetEdited(index, self.planet.clone()),
-            None => GuiMessage::NewPlanet(self.planet.clone()),
+        match self.planet_id {
+            Some(id) => GuiMessage::PlanetEdited(
+                id,
+                self.planet.clone(),
+                self.notes.clone(),
+                self.precession_period,
+                self.rotation_angle,
+            ),
+            None => GuiMessage::NewPlanet(
+                self.planet.clone(),
+                self.notes.clone(),
+                self.precession_period,
+                self.rotation_angle,
+            ),
         }
     }
 
@@ -516,6 +768,13 @@ pub(crate) enum PlanetDialogEvent {
     ArgumentOfPeriapsisChanged(String),
     SiderialRotationPeriodChanged(String),
     RotationAxisChanged(String),
+    PrecessionPeriodChanged(String),
+    RotationAngleChanged(String),
+    ResonancePartnerSelected(String),
+    ResonanceChanged(String),
+    ApplyResonance,
+    NotesChanged(String),
+    TagsChanged(String),
     Randomize,
 }
 
@@ -524,3 +783,30 @@ impl From<PlanetDialogEvent> for GuiMessage {
         GuiMessage::DialogUpdate(DialogUpdate::PlanetUpdated(event))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn density(grams_per_cubic_centimeter: f64) -> MassDensity {
+        MassDensity::new::<gram_per_cubic_centimeter>(grams_per_cubic_centimeter)
+    }
+
+    #[test]
+    fn densities_below_hydrogen_are_warned_about() {
+        assert!(density_warning(density(0.08))
+            .unwrap()
+            .contains("lighter than hydrogen"));
+        assert!(density_warning(density(0.09)).is_none());
+        assert!(density_warning(density(0.1)).is_none());
+    }
+
+    #[test]
+    fn densities_above_iron_are_warned_about() {
+        assert!(density_warning(density(7.8)).is_none());
+        assert!(density_warning(density(7.9)).is_none());
+        assert!(density_warning(density(8.))
+            .unwrap()
+            .contains("denser than iron"));
+    }
+}
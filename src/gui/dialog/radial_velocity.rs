@@ -0,0 +1,90 @@
+use super::{
+    line_chart::{draw_line_chart, CHART_HEIGHT, CHART_WIDTH},
+    Dialog, DialogUpdate, ElenathError,
+};
+use crate::{
+    gui::{
+        canvas_background::CanvasBackground, gui_widget::PADDING, message::GuiMessage,
+        shared_canvas_functionality::draw_background,
+    },
+    model::celestial_system::radial_velocity::RadialVelocityCurve,
+};
+use iced::{
+    mouse::Cursor,
+    widget::{canvas, Button, Canvas, Column, Text},
+    Alignment, Element, Length, Rectangle, Renderer, Theme,
+};
+use uom::si::{time::year, velocity::meter_per_second};
+
+#[derive(Debug, Clone)]
+pub(crate) struct RadialVelocityDialog {
+    curve: RadialVelocityCurve,
+}
+
+impl RadialVelocityDialog {
+    pub(crate) fn new(curve: RadialVelocityCurve) -> Self {
+        RadialVelocityDialog { curve }
+    }
+}
+
+impl Dialog for RadialVelocityDialog {
+    fn header(&self) -> String {
+        "Radial Velocity of the Central Body".to_string()
+    }
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let summary = Text::new(format!(
+            "Semi-amplitude: {:.2} m/s",
+            self.curve.semi_amplitude.get::<meter_per_second>()
+        ));
+        let explanation = Text::new(
+            "Velocity along the line of sight of an observer in the direction of the vernal equinox, over one period of the outermost planet.",
+        );
+        let chart = Canvas::new(self)
+            .width(Length::Fixed(CHART_WIDTH))
+            .height(Length::Fixed(CHART_HEIGHT));
+        let button = Button::new(Text::new("Ok")).on_press(GuiMessage::DialogClosed);
+        Column::new()
+            .push(summary)
+            .push(explanation)
+            .push(chart)
+            .push(button)
+            .spacing(PADDING)
+            .align_x(Alignment::Center)
+            .into()
+    }
+
+    fn update(&mut self, _event: DialogUpdate) {}
+
+    fn on_submit(&self) -> GuiMessage {
+        GuiMessage::DialogClosed
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
+
+impl<Message> canvas::Program<Message> for RadialVelocityDialog {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        draw_background(bounds, &mut frame, &CanvasBackground::BLACK);
+        let points: Vec<(f64, f64)> = self
+            .curve
+            .samples
+            .iter()
+            .map(|(time, velocity)| (time.get::<year>(), velocity.get::<meter_per_second>()))
+            .collect();
+        draw_line_chart(&mut frame, bounds, &points, "yr", "m/s");
+        vec![frame.into_geometry()]
+    }
+}
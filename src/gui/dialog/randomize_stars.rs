@@ -1,20 +1,40 @@
 use super::{Dialog, DialogUpdate};
-use crate::gui::{
-    gui_widget::{PADDING, SMALL_COLUMN_WIDTH},
-    message::GuiMessage,
+use crate::{
+    error::ElenathError,
+    gui::{
+        gui_widget::{PADDING, SMALL_COLUMN_WIDTH},
+        message::GuiMessage,
+        shared_widgets::{edit, star_merge_mode_control},
+    },
+    model::{
+        star::StarMergeMode,
+        star_generation::{StarGenerationParameters, SALPETER_IMF_SLOPE},
+    },
 };
+use astro_units::time::gigayear;
 use astro_utils::astro_display::AstroDisplay;
 use iced::{
     self,
     widget::{Button, Column, Radio, Row, Text, Toggler},
     Alignment, Element,
 };
-use uom::si::{f64::Length, length::light_year};
+use uom::si::{
+    f64::{Length, Time},
+    length::light_year,
+};
 
 #[derive(Debug, Clone)]
 pub(crate) struct RandomizeStarsDialog {
     keep_central_body: bool,
     generation_distance: GenerationDistance,
+    merge_mode: StarMergeMode,
+    existing_star_count: usize,
+    parameters: StarGenerationParameters,
+    max_star_count_string: String,
+    imf_slope_string: String,
+    max_age_string: String,
+    temperature_shift_string: String,
+    parse_error: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
@@ -25,12 +45,73 @@ pub(crate) enum GenerationDistance {
 }
 
 impl RandomizeStarsDialog {
-    pub(crate) fn new() -> Self {
+    pub(crate) fn new(existing_star_count: usize) -> Self {
         RandomizeStarsDialog {
             keep_central_body: true,
             generation_distance: GenerationDistance::Decent,
+            merge_mode: StarMergeMode::Replace,
+            existing_star_count,
+            parameters: StarGenerationParameters::default(),
+            max_star_count_string: String::new(),
+            imf_slope_string: format!("{:.2}", SALPETER_IMF_SLOPE),
+            max_age_string: String::new(),
+            temperature_shift_string: "0".to_string(),
+            parse_error: None,
+        }
+    }
+
+    fn parameters(&self) -> StarGenerationParameters {
+        StarGenerationParameters {
+            max_distance: max_generation_distance(self.generation_distance),
+            ..self.parameters
+        }
+    }
+
+    /*
+     * Reparses all fields, so that a field that was fixed does not hide a field that is still broken.
+     */
+    fn parse_parameters(&mut self) {
+        let mut invalid = Vec::new();
+        match parse_optional::<usize>(&self.max_star_count_string) {
+            Ok(max_star_count) => self.parameters.max_star_count = max_star_count,
+            Err(()) => invalid.push("maximum star count"),
+        }
+        match self.imf_slope_string.trim().parse::<f64>() {
+            Ok(imf_slope) => self.parameters.imf_slope = imf_slope,
+            Err(_) => invalid.push("IMF slope"),
         }
+        match parse_optional::<f64>(&self.max_age_string) {
+            Ok(max_age) => self.parameters.max_age = max_age.map(Time::new::<gigayear>),
+            Err(()) => invalid.push("maximum age"),
+        }
+        match self.temperature_shift_string.trim().parse::<f64>() {
+            Ok(temperature_shift) => self.parameters.temperature_shift = temperature_shift / 100.,
+            Err(_) => invalid.push("temperature shift"),
+        }
+        self.parse_error = if invalid.is_empty() {
+            None
+        } else {
+            Some(format!("Cannot parse the {}", invalid.join(", ")))
+        };
+    }
+
+    fn parameter_error(&self) -> Option<ElenathError> {
+        if let Some(e) = &self.parse_error {
+            return Some(ElenathError::Generic(e.clone()));
+        }
+        self.parameters().validate().err()
+    }
+}
+
+/*
+ * An empty field means that there is no limit, anything else has to parse.
+ */
+fn parse_optional<T: std::str::FromStr>(string: &str) -> Result<Option<T>, ()> {
+    let string = string.trim();
+    if string.is_empty() {
+        return Ok(None);
     }
+    string.parse::<T>().map(Some).map_err(|_| ())
 }
 
 fn max_generation_distance(distance: GenerationDistance) -> Length {
@@ -47,14 +128,18 @@ fn message<F: Fn(GenerationDistance) -> RandomizeStarsDialogEvent>(
     move |m| GuiMessage::DialogUpdate(DialogUpdate::RandmoizeStarsUpdated(event(m)))
 }
 
+fn parameter_message<F: Fn(String) -> RandomizeStarsDialogEvent>(
+    event: F,
+) -> impl Fn(String) -> GuiMessage {
+    move |m| GuiMessage::DialogUpdate(DialogUpdate::RandmoizeStarsUpdated(event(m)))
+}
+
 impl Dialog for RandomizeStarsDialog {
     fn header(&self) -> String {
         "Generate Random Stars".to_string()
     }
 
     fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
-        let warning = Text::new("This will overwrite all stars in the current system.");
-
         let keep_central_body_toggler = Toggler::new(self.keep_central_body)
             .label("Keep Central Body")
             .on_toggle(|b| {
@@ -100,14 +185,63 @@ impl Dialog for RandomizeStarsDialog {
             .push(very_far_distance_radio)
             .padding(PADDING)
             .spacing(PADDING);
-        let submit_button = Button::new(Text::new("Submit")).on_press(GuiMessage::DialogSubmit);
+        let max_star_count = edit(
+            "Maximum Star Count",
+            &self.max_star_count_string,
+            "empty for no limit",
+            parameter_message(RandomizeStarsDialogEvent::MaxStarCountChanged),
+            &self.parameters.max_star_count.map(|count| count as f64),
+        );
+        let imf_slope = edit(
+            "IMF Slope",
+            &self.imf_slope_string,
+            "Salpeter: 2.35",
+            parameter_message(RandomizeStarsDialogEvent::ImfSlopeChanged),
+            &Some(self.parameters.imf_slope),
+        );
+        let max_age = edit(
+            "Maximum Age",
+            &self.max_age_string,
+            "Gyr, empty to keep",
+            parameter_message(RandomizeStarsDialogEvent::MaxAgeChanged),
+            &self.parameters.max_age,
+        );
+        let temperature_shift = edit(
+            "Temperature Shift",
+            &self.temperature_shift_string,
+            "%, > 0 for metal-poor",
+            parameter_message(RandomizeStarsDialogEvent::TemperatureShiftChanged),
+            &Some(self.parameters.temperature_shift * 100.),
+        );
+        let merge_mode_control = star_merge_mode_control(
+            self.merge_mode,
+            self.existing_star_count,
+            self.parameters.max_star_count,
+            |mode| {
+                GuiMessage::DialogUpdate(DialogUpdate::RandmoizeStarsUpdated(
+                    RandomizeStarsDialogEvent::MergeModeSelected(mode),
+                ))
+            },
+        );
+        let mut submit_button = Button::new(Text::new("Submit"));
+        let error = self.parameter_error();
+        if error.is_none() {
+            submit_button = submit_button.on_press(GuiMessage::DialogSubmit);
+        }
 
-        Column::new()
-            .push(warning)
+        let mut col = Column::new()
+            .push(merge_mode_control)
             .push(keep_central_body_toggler)
             .push(Text::new("Maximum Generation Distance"))
             .push(generation_distance_row)
-            .push(submit_button)
+            .push(max_star_count)
+            .push(imf_slope)
+            .push(max_age)
+            .push(temperature_shift);
+        if let Some(e) = error {
+            col = col.push(Text::new(e.to_string()));
+        }
+        col.push(submit_button)
             .padding(PADDING)
             .spacing(PADDING)
             .width(iced::Length::Fill)
@@ -124,13 +258,28 @@ impl Dialog for RandomizeStarsDialog {
                 RandomizeStarsDialogEvent::MaxGenerationDistanceChanged(generation_distance) => {
                     self.generation_distance = generation_distance;
                 }
+                RandomizeStarsDialogEvent::MergeModeSelected(mode) => {
+                    self.merge_mode = mode;
+                }
+                RandomizeStarsDialogEvent::MaxStarCountChanged(max_star_count_string) => {
+                    self.max_star_count_string = max_star_count_string;
+                }
+                RandomizeStarsDialogEvent::ImfSlopeChanged(imf_slope_string) => {
+                    self.imf_slope_string = imf_slope_string;
+                }
+                RandomizeStarsDialogEvent::MaxAgeChanged(max_age_string) => {
+                    self.max_age_string = max_age_string;
+                }
+                RandomizeStarsDialogEvent::TemperatureShiftChanged(temperature_shift_string) => {
+                    self.temperature_shift_string = temperature_shift_string;
+                }
             }
+            self.parse_parameters();
         }
     }
 
     fn on_submit(&self) -> GuiMessage {
-        let max_distance = max_generation_distance(self.generation_distance);
-        GuiMessage::RandomizeStars(self.keep_central_body, max_distance)
+        GuiMessage::RandomizeStars(self.keep_central_body, self.parameters(), self.merge_mode)
     }
 
     fn get_error(&self) -> Option<super::ElenathError> {
@@ -142,4 +291,9 @@ impl Dialog for RandomizeStarsDialog {
 pub(crate) enum RandomizeStarsDialogEvent {
     KeepCentralBodySelected(bool),
     MaxGenerationDistanceChanged(GenerationDistance),
+    MergeModeSelected(StarMergeMode),
+    MaxStarCountChanged(String),
+    ImfSlopeChanged(String),
+    MaxAgeChanged(String),
+    TemperatureShiftChanged(String),
 }
@@ -0,0 +1,77 @@
+use super::{Dialog, DialogUpdate, ElenathError};
+use crate::gui::{
+    gui_widget::{group_digits, PADDING},
+    message::GuiMessage,
+    shared_widgets::std_button,
+    surface_view::sky_region::SkyRegionStatistics,
+};
+use iced::{
+    widget::{Column, Row, Text},
+    Alignment, Element, Length,
+};
+
+#[derive(Debug, Clone)]
+pub(crate) struct SkyRegionDialog {
+    statistics: SkyRegionStatistics,
+}
+
+impl SkyRegionDialog {
+    pub(crate) fn new(statistics: SkyRegionStatistics) -> Self {
+        SkyRegionDialog { statistics }
+    }
+}
+
+impl Dialog for SkyRegionDialog {
+    fn header(&self) -> String {
+        "Sky Region".to_string()
+    }
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let mut col = Column::new().push(Text::new(format!(
+            "{} stars in the selected region.",
+            group_digits(self.statistics.stars.len())
+        )));
+        if !self.statistics.brightest.is_empty() {
+            col = col.push(Text::new("Brightest members:"));
+            for star in &self.statistics.brightest {
+                col = col.push(Text::new(format!(
+                    "{}: {:.2} mag",
+                    star.name, star.apparent_magnitude
+                )));
+            }
+        }
+        let constellations = if self.statistics.constellations.is_empty() {
+            "No constellations intersected.".to_string()
+        } else {
+            format!(
+                "Constellations: {}",
+                self.statistics.constellations.join(", ")
+            )
+        };
+        col = col.push(Text::new(constellations));
+        let buttons = Row::new()
+            .push(std_button(
+                "Export Stars",
+                GuiMessage::DialogSubmit,
+                !self.statistics.stars.is_empty(),
+            ))
+            .push(std_button("Close", GuiMessage::DialogClosed, true))
+            .spacing(PADDING);
+        col.push(buttons)
+            .padding(PADDING)
+            .spacing(PADDING)
+            .width(Length::Fill)
+            .align_x(Alignment::Center)
+            .into()
+    }
+
+    fn update(&mut self, _event: DialogUpdate) {}
+
+    fn on_submit(&self) -> GuiMessage {
+        GuiMessage::ExportStars(self.statistics.stars.clone())
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
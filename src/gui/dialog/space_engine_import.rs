@@ -0,0 +1,79 @@
+use super::{CardStyle, Dialog, DialogUpdate};
+use crate::error::ElenathError;
+use crate::gui::{gui_widget::PADDING, message::GuiMessage};
+use crate::model::space_engine::SpaceEngineSystem;
+use iced::{
+    widget::{text::Shaping, Button, Column, Row, Text},
+    Element,
+};
+
+/*
+ * Asked before a SpaceEngine script that describes a star replaces the central body of the system.
+ */
+#[derive(Debug, Clone)]
+pub(crate) struct SpaceEngineImportDialog {
+    imported: SpaceEngineSystem,
+    central_body_name: String,
+}
+
+impl SpaceEngineImportDialog {
+    pub(crate) fn new(imported: SpaceEngineSystem, central_body_name: String) -> Self {
+        SpaceEngineImportDialog {
+            imported,
+            central_body_name,
+        }
+    }
+
+    fn apply(&self, replace_central_body: bool) -> GuiMessage {
+        GuiMessage::ApplySpaceEngineImport(self.imported.clone(), replace_central_body)
+    }
+}
+
+impl Dialog for SpaceEngineImportDialog {
+    fn card_style(&self) -> CardStyle {
+        CardStyle::Warning
+    }
+
+    fn header(&self) -> String {
+        "The script describes a star".to_string()
+    }
+
+    fn update(&mut self, _event: DialogUpdate) {}
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let star_name = self
+            .imported
+            .central_body
+            .as_ref()
+            .map(|star| star.get_name().clone())
+            .unwrap_or_default();
+        let question = Text::new(format!(
+            "Should {} replace {} as the central body? Either way, {} planets are imported.",
+            star_name,
+            self.central_body_name,
+            self.imported.planets.len()
+        ))
+        .shaping(Shaping::Advanced);
+        let buttons = Row::new()
+            .push(Button::new(Text::new("Replace")).on_press(self.apply(true)))
+            .push(
+                Button::new(Text::new(format!("Keep {}", self.central_body_name)))
+                    .on_press(self.apply(false)),
+            )
+            .push(Button::new(Text::new("Cancel")).on_press(GuiMessage::DialogClosed))
+            .spacing(PADDING);
+        Column::new()
+            .spacing(PADDING)
+            .push(question)
+            .push(buttons)
+            .into()
+    }
+
+    fn on_submit(&self) -> GuiMessage {
+        self.apply(false)
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
@@ -0,0 +1,47 @@
+use super::{CardStyle, Dialog, DialogUpdate};
+use crate::error::ElenathError;
+use crate::gui::{gui_widget::PADDING, message::GuiMessage};
+use iced::{
+    widget::{Button, Column, Text},
+    Element,
+};
+
+#[derive(Debug, Clone)]
+pub(crate) struct StabilityDialog {
+    warnings: Vec<String>,
+}
+
+impl StabilityDialog {
+    pub(crate) fn new(warnings: Vec<String>) -> Self {
+        StabilityDialog { warnings }
+    }
+}
+
+impl Dialog for StabilityDialog {
+    fn card_style(&self) -> CardStyle {
+        CardStyle::Warning
+    }
+
+    fn header(&self) -> String {
+        "The system might be unstable or implausible".to_string()
+    }
+
+    fn update(&mut self, _event: DialogUpdate) {}
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let mut col = Column::new().spacing(PADDING);
+        for warning in self.warnings.iter() {
+            col = col.push(Text::new(warning.clone()));
+        }
+        let button = Button::new(Text::new("Ok")).on_press(GuiMessage::DialogClosed);
+        col.push(button).into()
+    }
+
+    fn on_submit(&self) -> GuiMessage {
+        GuiMessage::DialogClosed
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
@@ -1,5 +1,6 @@
 use astro_coords::{cartesian::Cartesian, ecliptic::Ecliptic, spherical::Spherical};
 use astro_units::{
+    illuminance::apparent_magnitude_to_illuminance,
     length::solar_radius,
     luminous_intensity::{
         absolute_magnitude_to_luminous_intensity, luminous_intensity_to_absolute_magnitude,
@@ -9,24 +10,37 @@ use astro_units::{
 };
 use astro_utils::{
     astro_display::AstroDisplay,
+    color::srgb::sRGBColor,
     stars::{
         data::StarData, evolution::StarDataEvolution, physical_parameters::StarPhysicalParameters,
         random::random_stars::generate_random_star,
     },
 };
 use iced::{
-    widget::{text::Shaping, Button, Column, Row, Text},
+    widget::{text::Shaping, Button, Column, PickList, Row, Text},
     Alignment, Element, Length as IcedLength,
 };
 use uom::si::{
     angle::degree,
-    f64::{Angle, Length, LuminousIntensity, Mass, ThermodynamicTemperature, Time},
+    f64::{Angle, Length, LuminousIntensity, Mass, ThermodynamicTemperature, Time, Velocity},
     length::light_year,
     luminous_intensity::candela,
     thermodynamic_temperature::kelvin,
+    velocity::kilometer_per_second,
 };
 
-use crate::gui::{gui_widget::PADDING, message::GuiMessage, shared_widgets::edit};
+use crate::{
+    gui::{gui_widget::PADDING, message::GuiMessage, shared_widgets::edit},
+    model::{
+        appearance_override::AppearanceOverride,
+        body_id::BodyId,
+        body_notes::BodyNotes,
+        celestial_system::{metadata::SystemMetadata, naming::check_body_name},
+        star_provenance::StarProvenance,
+        universe::SystemReference,
+    },
+};
+use std::collections::HashSet;
 
 use super::{Dialog, DialogUpdate, ElenathError};
 
@@ -34,7 +48,9 @@ use super::{Dialog, DialogUpdate, ElenathError};
 pub(crate) struct StarDialog {
     star_dialog_type: StarDialogType,
     star: StarData,
-    star_index: Option<usize>,
+    star_id: Option<BodyId>,
+    original_name: Option<String>,
+    taken_names: HashSet<String>,
     time_since_epoch: Time,
     mass_string: String,
     radius_string: String,
@@ -44,6 +60,17 @@ pub(crate) struct StarDialog {
     distance_string: String,
     longitude_string: String,
     latitude_string: String,
+    system_metadata: SystemMetadata,
+    notes: BodyNotes,
+    tags_string: String,
+    radial_velocity: Option<Velocity>,
+    radial_velocity_string: String,
+    appearance_override: AppearanceOverride,
+    override_color_string: String,
+    override_magnitude_string: String,
+    linked_system: Option<usize>,
+    other_systems: Vec<SystemReference>,
+    provenance: Option<StarProvenance>,
     error: Option<ElenathError>,
 }
 
@@ -51,6 +78,7 @@ pub(crate) struct StarDialog {
 enum StarDialogType {
     New,
     Edit,
+    CentralBody,
 }
 
 #[inline(always)]
@@ -79,7 +107,9 @@ impl StarDialog {
         let mut dialog = StarDialog {
             star_dialog_type: StarDialogType::New,
             star,
-            star_index: None,
+            star_id: None,
+            original_name: None,
+            taken_names: HashSet::new(),
             time_since_epoch,
             mass_string: String::new(),
             radius_string: String::new(),
@@ -89,17 +119,35 @@ impl StarDialog {
             distance_string: String::new(),
             longitude_string: String::new(),
             latitude_string: String::new(),
+            system_metadata: SystemMetadata::default(),
+            tags_string: String::new(),
+            notes: BodyNotes::default(),
+            radial_velocity: None,
+            radial_velocity_string: String::new(),
+            appearance_override: AppearanceOverride::default(),
+            override_color_string: String::new(),
+            override_magnitude_string: String::new(),
+            linked_system: None,
+            other_systems: vec![],
+            provenance: None,
             error: None,
         };
         dialog.fill_string_members();
         dialog
     }
 
-    pub(crate) fn edit(star: StarData, star_index: Option<usize>, time_since_epoch: Time) -> Self {
+    pub(crate) fn edit(
+        star: StarData,
+        star_id: Option<BodyId>,
+        time_since_epoch: Time,
+        notes: BodyNotes,
+    ) -> Self {
         let mut dialog = StarDialog {
             star_dialog_type: StarDialogType::Edit,
+            original_name: Some(star.get_name().clone()),
             star,
-            star_index,
+            star_id,
+            taken_names: HashSet::new(),
             time_since_epoch,
             mass_string: String::new(),
             radius_string: String::new(),
@@ -109,12 +157,101 @@ impl StarDialog {
             distance_string: String::new(),
             longitude_string: String::new(),
             latitude_string: String::new(),
+            system_metadata: SystemMetadata::default(),
+            tags_string: notes.tags_string(),
+            notes,
+            radial_velocity: None,
+            radial_velocity_string: String::new(),
+            appearance_override: AppearanceOverride::default(),
+            override_color_string: String::new(),
+            override_magnitude_string: String::new(),
+            linked_system: None,
+            other_systems: vec![],
+            provenance: None,
             error: None,
         };
         dialog.fill_string_members();
         dialog
     }
 
+    pub(crate) fn central_body(
+        star: StarData,
+        time_since_epoch: Time,
+        notes: BodyNotes,
+        system_metadata: SystemMetadata,
+    ) -> Self {
+        let mut dialog = StarDialog::edit(star, None, time_since_epoch, notes);
+        dialog.star_dialog_type = StarDialogType::CentralBody;
+        dialog.system_metadata = system_metadata;
+        dialog
+    }
+
+    pub(crate) fn with_radial_velocity(mut self, radial_velocity: Option<Velocity>) -> Self {
+        self.radial_velocity = radial_velocity;
+        self.radial_velocity_string = radial_velocity
+            .map(|v| format!("{:.2}", v.get::<kilometer_per_second>()))
+            .unwrap_or_default();
+        self
+    }
+
+    /*
+     * Empty fields keep the derived color and brightness of the star.
+     */
+    pub(crate) fn with_appearance_override(
+        mut self,
+        appearance_override: Option<AppearanceOverride>,
+    ) -> Self {
+        let appearance_override = appearance_override.unwrap_or_default();
+        self.override_color_string = appearance_override
+            .color
+            .as_ref()
+            .and_then(|color| serde_json::to_string(color).ok())
+            .unwrap_or_default();
+        self.override_magnitude_string = appearance_override
+            .apparent_magnitude
+            .map(|magnitude| format!("{:.2}", magnitude))
+            .unwrap_or_default();
+        self.appearance_override = appearance_override;
+        self
+    }
+
+    /*
+     * Lets the star link to one of the other systems in the universe, which describes it in more detail.
+     */
+    pub(crate) fn with_system_links(
+        mut self,
+        linked_system: Option<usize>,
+        other_systems: Vec<SystemReference>,
+    ) -> Self {
+        self.linked_system = linked_system;
+        self.other_systems = other_systems;
+        self
+    }
+
+    /*
+     * Only distant stars have a provenance, which is shown but cannot be edited.
+     */
+    pub(crate) fn with_provenance(mut self, provenance: Option<StarProvenance>) -> Self {
+        self.provenance = provenance;
+        self
+    }
+
+    /*
+     * The names of the other stars, which the star may not take unless it already has it.
+     */
+    pub(crate) fn with_taken_names(mut self, taken_names: HashSet<String>) -> Self {
+        self.taken_names = taken_names;
+        self
+    }
+
+    fn name_error(&self) -> Option<ElenathError> {
+        let name = self.star.get_name();
+        if self.original_name.as_ref() == Some(name) {
+            return None;
+        }
+        check_body_name(name, &self.taken_names).err()
+    }
+
     fn fill_string_members(&mut self) {
         self.mass_string = self
             .star
@@ -255,7 +392,53 @@ impl StarDialog {
             self.star.get_constellation(),
         );
 
-        let submit_button = Button::new(Text::new("Submit")).on_press(GuiMessage::DialogSubmit);
+        let notes = edit(
+            "Notes",
+            &self.notes.notes,
+            "",
+            message(StarDialogEvent::NotesChanged),
+            &Some(&self.notes.notes),
+        );
+        let tags = edit(
+            "Tags",
+            &self.tags_string,
+            "comma separated",
+            message(StarDialogEvent::TagsChanged),
+            &Some(&self.tags_string),
+        );
+
+        let radial_velocity = edit(
+            "Radial Velocity",
+            &self.radial_velocity_string,
+            "km/s, > 0 receding",
+            message(StarDialogEvent::RadialVelocityChanged),
+            &self.radial_velocity,
+        );
+
+        let override_color = edit(
+            "Color Override",
+            &self.override_color_string,
+            "",
+            message(StarDialogEvent::OverrideColorChanged),
+            &self.appearance_override.color,
+        );
+        let override_magnitude = edit(
+            "Brightness Override",
+            &self.override_magnitude_string,
+            "mag",
+            message(StarDialogEvent::OverrideMagnitudeChanged),
+            &self
+                .appearance_override
+                .apparent_magnitude
+                .map(apparent_magnitude_to_illuminance),
+        );
+
+        let mut name = Column::new().push(name).spacing(PADDING);
+        let mut submit_button = Button::new(Text::new("Submit"));
+        match self.name_error() {
+            Some(e) => name = name.push(Text::new(e.to_string())),
+            None => submit_button = submit_button.on_press(GuiMessage::DialogSubmit),
+        }
 
         let mut col = Column::new()
             .push(randomize_button)
@@ -270,9 +453,71 @@ impl StarDialog {
                 .push(distance)
                 .push(longitude)
                 .push(latitude)
-                .push(constellation);
+                .push(radial_velocity)
+                .push(constellation)
+                .push(override_color)
+                .push(override_magnitude);
+        }
+        if self.star_id.is_some() && !self.other_systems.is_empty() {
+            col = col.push(self.system_link_row());
         }
-        col.push(submit_button)
+        col.push(notes)
+            .push(tags)
+            .push(submit_button)
+            .spacing(PADDING)
+            .width(IcedLength::Fill)
+            .align_x(Alignment::Center)
+            .into()
+    }
+
+    fn system_link_row(&self) -> Element<'_, GuiMessage> {
+        let linked_system = self
+            .other_systems
+            .iter()
+            .find(|system| Some(system.index) == self.linked_system)
+            .cloned();
+        let picker = PickList::new(self.other_systems.clone(), linked_system, |system| {
+            GuiMessage::DialogUpdate(DialogUpdate::StarUpdated(
+                StarDialogEvent::LinkedSystemChanged(Some(system.index)),
+            ))
+        });
+        let mut unlink_button = Button::new(Text::new("Unlink"));
+        let mut open_button = Button::new(Text::new("Open"));
+        if let Some(index) = self.linked_system {
+            unlink_button = unlink_button.on_press(GuiMessage::DialogUpdate(
+                DialogUpdate::StarUpdated(StarDialogEvent::LinkedSystemChanged(None)),
+            ));
+            open_button = open_button.on_press(GuiMessage::SystemSelected(index));
+        }
+        Row::new()
+            .push(Text::new("Linked system:"))
+            .push(picker)
+            .push(unlink_button)
+            .push(open_button)
+            .spacing(PADDING)
+            .align_y(Alignment::Center)
+            .into()
+    }
+
+    fn system_settings_column(&self) -> Element<'_, GuiMessage> {
+        let system_name = edit(
+            "System Name",
+            &self.system_metadata.name,
+            "",
+            message(StarDialogEvent::SystemNameChanged),
+            &Some(&self.system_metadata.name),
+        );
+        let epoch_description = edit(
+            "Epoch",
+            &self.system_metadata.epoch_description,
+            "",
+            message(StarDialogEvent::EpochDescriptionChanged),
+            &Some(&self.system_metadata.epoch_description),
+        );
+        Column::new()
+            .push(Text::new("System Settings"))
+            .push(system_name)
+            .push(epoch_description)
             .spacing(PADDING)
             .width(IcedLength::Fill)
             .align_x(Alignment::Center)
@@ -395,8 +640,14 @@ impl StarDialog {
         )
         .shaping(Shaping::Advanced);
 
-        Column::new()
-            .push(illuminance)
+        let mut col = Column::new();
+        if let Some(provenance) = &self.provenance {
+            let last_edited = provenance.last_edited.as_deref().unwrap_or("never");
+            col = col
+                .push(Text::new(format!("Origin: {}", provenance.origin)))
+                .push(Text::new(format!("Last Edited: {}", last_edited)));
+        }
+        col.push(illuminance)
             .push(color)
             .push(mass_per_year)
             .push(radius_per_year)
@@ -417,23 +668,38 @@ impl StarDialog {
     }
 
     fn is_central_body(&self) -> bool {
-        self.star_dialog_type == StarDialogType::Edit && self.star_index.is_none()
+        match self.star_dialog_type {
+            StarDialogType::CentralBody => true,
+            StarDialogType::Edit => self.star_id.is_none(),
+            StarDialogType::New => false,
+        }
     }
 }
 
 impl Dialog for StarDialog {
     fn header(&self) -> String {
-        match self.star_index {
-            Some(index) => format!("Edit Star {}", index),
+        if self.star_dialog_type == StarDialogType::CentralBody {
+            return "Edit Central Body".to_string();
+        }
+        match self.star_id {
+            Some(id) => format!("Edit Star {}", id),
             None => "Create Star".to_string(),
         }
     }
 
     fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
-        Row::new()
+        let row = Row::new()
             .push(self.edit_column())
-            .push(self.additional_info_column())
-            .into()
+            .push(self.additional_info_column());
+        if self.star_dialog_type == StarDialogType::CentralBody {
+            Column::new()
+                .push(self.system_settings_column())
+                .push(row)
+                .spacing(PADDING)
+                .into()
+        } else {
+            row.into()
+        }
     }
 
     fn update(&mut self, message: super::DialogUpdate) {
@@ -522,6 +788,39 @@ impl Dialog for StarDialog {
                         self.star.set_constellation(Some(constellation));
                     }
                 }
+                StarDialogEvent::SystemNameChanged(name) => {
+                    self.system_metadata.name = name;
+                }
+                StarDialogEvent::EpochDescriptionChanged(epoch_description) => {
+                    self.system_metadata.epoch_description = epoch_description;
+                }
+                StarDialogEvent::NotesChanged(notes) => {
+                    self.notes.notes = notes;
+                }
+                StarDialogEvent::TagsChanged(tags_string) => {
+                    self.notes.set_tags_from_string(&tags_string);
+                    self.tags_string = tags_string;
+                }
+                StarDialogEvent::RadialVelocityChanged(radial_velocity_string) => {
+                    self.radial_velocity = radial_velocity_string
+                        .parse::<f64>()
+                        .ok()
+                        .map(Velocity::new::<kilometer_per_second>);
+                    self.radial_velocity_string = radial_velocity_string;
+                }
+                StarDialogEvent::OverrideColorChanged(color_string) => {
+                    self.appearance_override.color =
+                        serde_json::from_str::<sRGBColor>(&color_string).ok();
+                    self.override_color_string = color_string;
+                }
+                StarDialogEvent::OverrideMagnitudeChanged(magnitude_string) => {
+                    self.appearance_override.apparent_magnitude =
+                        magnitude_string.parse::<f64>().ok();
+                    self.override_magnitude_string = magnitude_string;
+                }
+                StarDialogEvent::LinkedSystemChanged(linked_system) => {
+                    self.linked_system = linked_system;
+                }
                 StarDialogEvent::Randomize => {
                     let max_distance = Length::new::<light_year>(2000.);
                     let name = self.star.get_name().clone();
@@ -546,14 +845,37 @@ impl Dialog for StarDialog {
                 if self.is_central_body() {
                     star.set_distance_at_epoch(Length::new::<light_year>(0.));
                 }
-                return GuiMessage::StarEdited(self.star_index, star);
+                return GuiMessage::StarEdited(
+                    self.star_id,
+                    star,
+                    self.notes.clone(),
+                    self.radial_velocity,
+                    Some(self.appearance_override.clone()),
+                    self.linked_system,
+                );
+            }
+            StarDialogType::New => {
+                return GuiMessage::NewStar(
+                    self.star.clone(),
+                    self.notes.clone(),
+                    self.radial_velocity,
+                    Some(self.appearance_override.clone()),
+                )
+            }
+            StarDialogType::CentralBody => {
+                let mut star = self.star.clone();
+                star.set_distance_at_epoch(Length::new::<light_year>(0.));
+                return GuiMessage::CentralBodyEdited(
+                    star,
+                    self.notes.clone(),
+                    self.system_metadata.clone(),
+                );
             }
-            StarDialogType::New => return GuiMessage::NewStar(self.star.clone()),
         }
     }
 
     fn get_error(&self) -> Option<super::ElenathError> {
-        todo!()
+        self.error.clone()
     }
 }
 
@@ -569,6 +891,14 @@ pub(crate) enum StarDialogEvent {
     LongitudeChanged(String),
     LatitudeChanged(String),
     ConstellationChanged(String),
+    SystemNameChanged(String),
+    EpochDescriptionChanged(String),
+    NotesChanged(String),
+    TagsChanged(String),
+    RadialVelocityChanged(String),
+    OverrideColorChanged(String),
+    OverrideMagnitudeChanged(String),
+    LinkedSystemChanged(Option<usize>),
     Randomize,
 }
 
@@ -0,0 +1,276 @@
+use super::{Dialog, DialogUpdate};
+use crate::{
+    error::ElenathError,
+    gui::{gui_widget::PADDING, message::GuiMessage, shared_widgets::edit},
+    model::star_formation::StarFormingRegion,
+};
+use astro_utils::astro_display::AstroDisplay;
+use iced::{
+    widget::{text::Shaping, Button, Column, Row, Text},
+    Alignment, Element, Length as IcedLength,
+};
+use uom::si::{
+    angle::degree,
+    f64::{Angle, Length, Time, Velocity},
+    length::light_year,
+    time::year,
+    velocity::kilometer_per_second,
+};
+
+#[derive(Debug, Clone)]
+pub(crate) struct StarFormingRegionsDialog {
+    regions: Vec<StarFormingRegion>,
+    new_region: StarFormingRegion,
+    longitude_string: String,
+    latitude_string: String,
+    distance_string: String,
+    radius_string: String,
+    birth_rate_string: String,
+    onset_string: String,
+    velocity_dispersion_string: String,
+}
+
+impl StarFormingRegionsDialog {
+    pub(crate) fn new(regions: Vec<StarFormingRegion>, time_since_epoch: Time) -> Self {
+        let new_region = StarFormingRegion {
+            onset: time_since_epoch,
+            ..Default::default()
+        };
+        StarFormingRegionsDialog {
+            regions,
+            longitude_string: format!("{:.2}", new_region.longitude.get::<degree>()),
+            latitude_string: format!("{:.2}", new_region.latitude.get::<degree>()),
+            distance_string: format!("{:.2}", new_region.distance.get::<light_year>()),
+            radius_string: format!("{:.2}", new_region.radius.get::<light_year>()),
+            birth_rate_string: format!("{:.2}", new_region.births_per_megayear),
+            onset_string: format!("{:.2}", new_region.onset.get::<year>() / 1e6),
+            velocity_dispersion_string: format!(
+                "{:.2}",
+                new_region.velocity_dispersion.get::<kilometer_per_second>()
+            ),
+            new_region,
+        }
+    }
+
+    fn region_row(&self, index: usize, region: &StarFormingRegion) -> Row<'_, GuiMessage> {
+        let description = format!(
+            "{}: {} away, {} births per Myr, starting at {}",
+            region.name,
+            region.distance.astro_display(),
+            region.births_per_megayear,
+            region.onset.astro_display()
+        );
+        let remove_button = Button::new(Text::new("Remove")).on_press(GuiMessage::DialogUpdate(
+            DialogUpdate::StarFormingRegionsUpdated(StarFormingRegionsDialogEvent::RemoveRegion(
+                index,
+            )),
+        ));
+        Row::new()
+            .push(Text::new(description).shaping(Shaping::Advanced))
+            .push(remove_button)
+            .spacing(PADDING)
+            .align_y(Alignment::Center)
+    }
+
+    /*
+     * Births are told apart by region name, so names need to be unique.
+     */
+    fn can_add_new_region(&self) -> bool {
+        !self.new_region.name.is_empty()
+            && !self
+                .regions
+                .iter()
+                .any(|region| region.name == self.new_region.name)
+    }
+}
+
+fn message<F: Fn(String) -> StarFormingRegionsDialogEvent>(
+    event: F,
+) -> impl Fn(String) -> GuiMessage {
+    move |m| GuiMessage::DialogUpdate(DialogUpdate::StarFormingRegionsUpdated(event(m)))
+}
+
+impl Dialog for StarFormingRegionsDialog {
+    fn header(&self) -> String {
+        "Star-Forming Regions".to_string()
+    }
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let mut regions = Column::new().spacing(PADDING);
+        if self.regions.is_empty() {
+            regions = regions.push(Text::new("No star-forming regions yet."));
+        }
+        for (index, region) in self.regions.iter().enumerate() {
+            regions = regions.push(self.region_row(index, region));
+        }
+
+        let name = edit(
+            "Name",
+            &self.new_region.name,
+            "",
+            message(StarFormingRegionsDialogEvent::NameChanged),
+            &Some(&self.new_region.name),
+        );
+        let longitude = edit(
+            "Ecliptic Longitude",
+            &self.longitude_string,
+            "°",
+            message(StarFormingRegionsDialogEvent::LongitudeChanged),
+            &Some(self.new_region.longitude),
+        );
+        let latitude = edit(
+            "Ecliptic Latitude",
+            &self.latitude_string,
+            "°",
+            message(StarFormingRegionsDialogEvent::LatitudeChanged),
+            &Some(self.new_region.latitude),
+        );
+        let distance = edit(
+            "Distance",
+            &self.distance_string,
+            "ly",
+            message(StarFormingRegionsDialogEvent::DistanceChanged),
+            &Some(self.new_region.distance),
+        );
+        let radius = edit(
+            "Radius",
+            &self.radius_string,
+            "ly",
+            message(StarFormingRegionsDialogEvent::RadiusChanged),
+            &Some(self.new_region.radius),
+        );
+        let birth_rate = edit(
+            "Birth Rate",
+            &self.birth_rate_string,
+            "stars per Myr",
+            message(StarFormingRegionsDialogEvent::BirthRateChanged),
+            &Some(self.new_region.births_per_megayear),
+        );
+        let onset = edit(
+            "Onset",
+            &self.onset_string,
+            "Myr since epoch",
+            message(StarFormingRegionsDialogEvent::OnsetChanged),
+            &Some(self.new_region.onset),
+        );
+        let velocity_dispersion = edit(
+            "Velocity Dispersion",
+            &self.velocity_dispersion_string,
+            "km/s",
+            message(StarFormingRegionsDialogEvent::VelocityDispersionChanged),
+            &Some(self.new_region.velocity_dispersion),
+        );
+        let mut add_button = Button::new(Text::new("Add Region"));
+        if self.can_add_new_region() {
+            add_button = add_button.on_press(GuiMessage::DialogUpdate(
+                DialogUpdate::StarFormingRegionsUpdated(StarFormingRegionsDialogEvent::AddRegion),
+            ));
+        }
+        let submit_button = Button::new(Text::new("Submit")).on_press(GuiMessage::DialogSubmit);
+
+        Column::new()
+            .push(regions)
+            .push(name)
+            .push(longitude)
+            .push(latitude)
+            .push(distance)
+            .push(radius)
+            .push(birth_rate)
+            .push(onset)
+            .push(velocity_dispersion)
+            .push(add_button)
+            .push(submit_button)
+            .padding(PADDING)
+            .spacing(PADDING)
+            .width(IcedLength::Fill)
+            .align_x(Alignment::Center)
+            .into()
+    }
+
+    fn update(&mut self, message: DialogUpdate) {
+        if let DialogUpdate::StarFormingRegionsUpdated(event) = message {
+            match event {
+                StarFormingRegionsDialogEvent::NameChanged(name) => {
+                    self.new_region.name = name;
+                }
+                StarFormingRegionsDialogEvent::LongitudeChanged(longitude_string) => {
+                    if let Ok(longitude) = longitude_string.parse::<f64>() {
+                        self.new_region.longitude = Angle::new::<degree>(longitude);
+                    }
+                    self.longitude_string = longitude_string;
+                }
+                StarFormingRegionsDialogEvent::LatitudeChanged(latitude_string) => {
+                    if let Ok(latitude) = latitude_string.parse::<f64>() {
+                        self.new_region.latitude = Angle::new::<degree>(latitude);
+                    }
+                    self.latitude_string = latitude_string;
+                }
+                StarFormingRegionsDialogEvent::DistanceChanged(distance_string) => {
+                    if let Ok(distance) = distance_string.parse::<f64>() {
+                        self.new_region.distance = Length::new::<light_year>(distance);
+                    }
+                    self.distance_string = distance_string;
+                }
+                StarFormingRegionsDialogEvent::RadiusChanged(radius_string) => {
+                    if let Ok(radius) = radius_string.parse::<f64>() {
+                        self.new_region.radius = Length::new::<light_year>(radius);
+                    }
+                    self.radius_string = radius_string;
+                }
+                StarFormingRegionsDialogEvent::BirthRateChanged(birth_rate_string) => {
+                    if let Ok(birth_rate) = birth_rate_string.parse::<f64>() {
+                        self.new_region.births_per_megayear = birth_rate;
+                    }
+                    self.birth_rate_string = birth_rate_string;
+                }
+                StarFormingRegionsDialogEvent::OnsetChanged(onset_string) => {
+                    if let Ok(onset) = onset_string.parse::<f64>() {
+                        self.new_region.onset = Time::new::<year>(onset * 1e6);
+                    }
+                    self.onset_string = onset_string;
+                }
+                StarFormingRegionsDialogEvent::VelocityDispersionChanged(
+                    velocity_dispersion_string,
+                ) => {
+                    if let Ok(velocity_dispersion) = velocity_dispersion_string.parse::<f64>() {
+                        self.new_region.velocity_dispersion =
+                            Velocity::new::<kilometer_per_second>(velocity_dispersion);
+                    }
+                    self.velocity_dispersion_string = velocity_dispersion_string;
+                }
+                StarFormingRegionsDialogEvent::AddRegion => {
+                    if self.can_add_new_region() {
+                        self.regions.push(self.new_region.clone());
+                    }
+                }
+                StarFormingRegionsDialogEvent::RemoveRegion(index) => {
+                    if index < self.regions.len() {
+                        self.regions.remove(index);
+                    }
+                }
+            }
+        }
+    }
+
+    fn on_submit(&self) -> GuiMessage {
+        GuiMessage::SetStarFormingRegions(self.regions.clone())
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum StarFormingRegionsDialogEvent {
+    NameChanged(String),
+    LongitudeChanged(String),
+    LatitudeChanged(String),
+    DistanceChanged(String),
+    RadiusChanged(String),
+    BirthRateChanged(String),
+    OnsetChanged(String),
+    VelocityDispersionChanged(String),
+    AddRegion,
+    RemoveRegion(usize),
+}
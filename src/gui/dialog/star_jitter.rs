@@ -0,0 +1,147 @@
+use super::{Dialog, DialogUpdate};
+use crate::{
+    error::ElenathError,
+    gui::{gui_widget::PADDING, message::GuiMessage, shared_widgets::edit},
+    model::star_jitter::StarJitter,
+};
+use iced::{
+    widget::{Button, Column, Text},
+    Alignment, Element, Length as IcedLength,
+};
+use uom::si::{angle::degree, f64::Angle};
+
+#[derive(Debug, Clone)]
+pub(crate) struct StarJitterDialog {
+    jitter: StarJitter,
+    seed_string: String,
+    position_angle_string: String,
+    distance_fraction_string: String,
+    magnitude_string: String,
+}
+
+impl StarJitterDialog {
+    pub(crate) fn new() -> Self {
+        let jitter = StarJitter {
+            seed: 1,
+            position_angle: Angle::new::<degree>(0.5),
+            distance_fraction: 0.05,
+            magnitude: 0.2,
+        };
+        StarJitterDialog {
+            jitter,
+            seed_string: "1".to_string(),
+            position_angle_string: "0.5".to_string(),
+            distance_fraction_string: "5".to_string(),
+            magnitude_string: "0.2".to_string(),
+        }
+    }
+}
+
+fn message<F: Fn(String) -> StarJitterDialogEvent>(event: F) -> impl Fn(String) -> GuiMessage {
+    move |m| GuiMessage::DialogUpdate(DialogUpdate::StarJitterUpdated(event(m)))
+}
+
+fn parse_non_negative(string: &str) -> Option<f64> {
+    string.parse::<f64>().ok().filter(|v| *v >= 0.)
+}
+
+impl Dialog for StarJitterDialog {
+    fn header(&self) -> String {
+        "Jitter Stars".to_string()
+    }
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let explanation = Text::new(
+            "Randomly perturbs the positions and brightnesses of all stars with data. The amplitudes are standard deviations, and the same seed always yields the same variant.",
+        );
+        let seed = edit(
+            "Seed",
+            &self.seed_string,
+            "",
+            message(StarJitterDialogEvent::SeedChanged),
+            &Some(self.jitter.seed as f64),
+        );
+        let position_angle = edit(
+            "Direction",
+            &self.position_angle_string,
+            "°",
+            message(StarJitterDialogEvent::PositionAngleChanged),
+            &Some(self.jitter.position_angle),
+        );
+        let distance_fraction = edit(
+            "Distance",
+            &self.distance_fraction_string,
+            "%",
+            message(StarJitterDialogEvent::DistanceFractionChanged),
+            &Some(self.jitter.distance_fraction * 100.),
+        );
+        let magnitude = edit(
+            "Brightness",
+            &self.magnitude_string,
+            "mag",
+            message(StarJitterDialogEvent::MagnitudeChanged),
+            &Some(self.jitter.magnitude),
+        );
+        let submit_button = Button::new(Text::new("Submit")).on_press(GuiMessage::DialogSubmit);
+
+        Column::new()
+            .push(explanation)
+            .push(seed)
+            .push(position_angle)
+            .push(distance_fraction)
+            .push(magnitude)
+            .push(submit_button)
+            .padding(PADDING)
+            .spacing(PADDING)
+            .width(IcedLength::Fill)
+            .align_x(Alignment::Center)
+            .into()
+    }
+
+    fn update(&mut self, event: DialogUpdate) {
+        if let DialogUpdate::StarJitterUpdated(event) = event {
+            match event {
+                StarJitterDialogEvent::SeedChanged(seed_string) => {
+                    if let Ok(seed) = seed_string.parse::<u64>() {
+                        self.jitter.seed = seed;
+                    }
+                    self.seed_string = seed_string;
+                }
+                StarJitterDialogEvent::PositionAngleChanged(position_angle_string) => {
+                    if let Some(degrees) = parse_non_negative(&position_angle_string) {
+                        self.jitter.position_angle = Angle::new::<degree>(degrees);
+                    }
+                    self.position_angle_string = position_angle_string;
+                }
+                StarJitterDialogEvent::DistanceFractionChanged(distance_fraction_string) => {
+                    if let Some(percent) = parse_non_negative(&distance_fraction_string) {
+                        self.jitter.distance_fraction = percent / 100.;
+                    }
+                    self.distance_fraction_string = distance_fraction_string;
+                }
+                StarJitterDialogEvent::MagnitudeChanged(magnitude_string) => {
+                    if let Some(magnitude) = parse_non_negative(&magnitude_string) {
+                        self.jitter.magnitude = magnitude;
+                    }
+                    self.magnitude_string = magnitude_string;
+                }
+            }
+        }
+    }
+
+    fn on_submit(&self) -> GuiMessage {
+        GuiMessage::JitterStars(self.jitter)
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum StarJitterDialogEvent {
+    SeedChanged(String),
+    PositionAngleChanged(String),
+    DistanceFractionChanged(String),
+    MagnitudeChanged(String),
+}
@@ -0,0 +1,298 @@
+use super::{Dialog, DialogUpdate, ElenathError};
+use crate::gui::{
+    canvas_background::{BackgroundImage, CanvasBackground},
+    gui_widget::{PADDING, SMALL_COLUMN_WIDTH},
+    message::GuiMessage,
+    shared_canvas_functionality::draw_background,
+    shared_widgets::std_button,
+    star_rendering::{reference_field, FieldStar, StarRendering, REFERENCE_PICTURE},
+};
+use astro_units::illuminance::apparent_magnitude_to_illuminance;
+use iced::{
+    alignment::Horizontal,
+    mouse::Cursor,
+    widget::{canvas, Canvas, Column, Row, Slider, Text},
+    Alignment, Color, Element, Length, Point, Rectangle, Renderer, Theme,
+};
+use std::sync::Arc;
+
+const ROW_HEIGHT: f32 = 30.;
+const NAME_WIDTH: f32 = 160.;
+const DISK_COLUMN_WIDTH: f32 = 80.;
+const CHART_WIDTH: f32 = NAME_WIDTH + 2. * DISK_COLUMN_WIDTH;
+const SLIDER_STEP: f32 = 0.01;
+// The part of the field canvas that the stars of the field span, leaving a margin for their disks.
+const FIELD_FILL: f64 = 0.9;
+
+/*
+ * Shows the reference picture next to the rendering of the same stars, while the rendering is adjusted.
+ * The bundled stars of Orion and Taurus are rendered as a field, over which a photograph of them can be laid.
+ * Every change applies to the canvas right away, and is remembered across sessions once a slider is released.
+ */
+#[derive(Debug, Clone)]
+pub(crate) struct StarRenderingDialog {
+    rendering: StarRendering,
+    field: Arc<Vec<FieldStar>>,
+    reference_image: Option<Arc<BackgroundImage>>,
+    overlay_opacity: f32,
+}
+
+impl StarRenderingDialog {
+    pub(crate) fn new(rendering: StarRendering) -> Self {
+        StarRenderingDialog {
+            rendering,
+            field: Arc::new(reference_field()),
+            reference_image: None,
+            overlay_opacity: 0.5,
+        }
+    }
+
+    fn slider<'a>(
+        &self,
+        label: &'a str,
+        value: f32,
+        (min, max): (f32, f32),
+        set: fn(StarRendering, f32) -> StarRendering,
+    ) -> Row<'a, GuiMessage> {
+        let rendering = self.rendering;
+        let slider = Slider::new(min..=max, value, move |value| {
+            GuiMessage::SetStarRendering(set(rendering, value))
+        })
+        .on_release(GuiMessage::SaveStarRendering(rendering))
+        .step(SLIDER_STEP)
+        .width(2. * SMALL_COLUMN_WIDTH);
+        labelled(label, slider, value)
+    }
+
+    fn overlay_row(&self) -> Row<'_, GuiMessage> {
+        let pick_button = std_button(
+            "Reference Image...",
+            GuiMessage::PickStarRenderingReference,
+            true,
+        );
+        let opacity_slider = Slider::new(0.0..=1.0, self.overlay_opacity, |opacity| {
+            GuiMessage::DialogUpdate(DialogUpdate::StarRenderingUpdated(
+                StarRenderingDialogEvent::OverlayOpacity(opacity),
+            ))
+        })
+        .step(SLIDER_STEP)
+        .width(2. * SMALL_COLUMN_WIDTH);
+        let mut row = Row::new().push(pick_button);
+        if self.reference_image.is_some() {
+            row = row.push(labelled("Overlay", opacity_slider, self.overlay_opacity));
+        }
+        row.spacing(PADDING).align_y(Alignment::Center)
+    }
+}
+
+fn labelled<'a>(
+    label: &'a str,
+    slider: Slider<'a, f32, GuiMessage>,
+    value: f32,
+) -> Row<'a, GuiMessage> {
+    Row::new()
+        .push(
+            Text::new(label)
+                .width(SMALL_COLUMN_WIDTH)
+                .align_x(Horizontal::Right),
+        )
+        .push(slider)
+        .push(Text::new(format!("{:.2}", value)))
+        .spacing(PADDING)
+        .align_y(Alignment::Center)
+}
+
+impl Dialog for StarRenderingDialog {
+    fn header(&self) -> String {
+        "Star Rendering Calibration".to_string()
+    }
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let explanation = Text::new(
+            "Left: the stars as they are rendered, next to the same stars in a photograph of Orion and Taurus. Right: the bundled stars of Orion and Taurus, with a reference image of your own laid over them.",
+        );
+        let radius_slider = self.slider(
+            "Radius Exponent",
+            self.rendering.radius_exponent,
+            StarRendering::EXPONENT_RANGE,
+            |rendering, radius_exponent| StarRendering {
+                radius_exponent,
+                ..rendering
+            },
+        );
+        let alpha_slider = self.slider(
+            "Alpha Exponent",
+            self.rendering.alpha_exponent,
+            StarRendering::EXPONENT_RANGE,
+            |rendering, alpha_exponent| StarRendering {
+                alpha_exponent,
+                ..rendering
+            },
+        );
+        let min_radius_slider = self.slider(
+            "Minimum Radius",
+            self.rendering.min_radius,
+            StarRendering::MIN_RADIUS_RANGE,
+            |rendering, min_radius| StarRendering {
+                min_radius,
+                ..rendering
+            },
+        );
+        let magnitude_slider = self.slider(
+            "Magnitude at Min. Radius",
+            self.rendering.magnitude_at_min_radius() as f32,
+            StarRendering::MAGNITUDE_AT_MIN_RADIUS_RANGE,
+            |rendering, magnitude| rendering.with_magnitude_at_min_radius(magnitude as f64),
+        );
+        let chart_height = ROW_HEIGHT * REFERENCE_PICTURE.len() as f32;
+        let chart = Canvas::new(self)
+            .width(Length::Fixed(CHART_WIDTH))
+            .height(Length::Fixed(chart_height));
+        let field = Canvas::new(ReferenceField { dialog: self })
+            .width(Length::Fixed(chart_height))
+            .height(Length::Fixed(chart_height));
+        let canvases = Row::new().push(chart).push(field).spacing(PADDING);
+        let buttons = Row::new()
+            .push(std_button(
+                "Reset to Defaults",
+                GuiMessage::SaveStarRendering(StarRendering::default()),
+                self.rendering != StarRendering::default(),
+            ))
+            .push(std_button("Ok", GuiMessage::DialogClosed, true))
+            .spacing(PADDING);
+        Column::new()
+            .push(explanation)
+            .push(radius_slider)
+            .push(alpha_slider)
+            .push(min_radius_slider)
+            .push(magnitude_slider)
+            .push(canvases)
+            .push(self.overlay_row())
+            .push(buttons)
+            .padding(PADDING)
+            .spacing(PADDING)
+            .width(Length::Fill)
+            .align_x(Alignment::Center)
+            .into()
+    }
+
+    fn update(&mut self, event: DialogUpdate) {
+        if let DialogUpdate::StarRenderingUpdated(event) = event {
+            match event {
+                StarRenderingDialogEvent::Rendering(rendering) => {
+                    self.rendering = rendering;
+                }
+                StarRenderingDialogEvent::ReferenceImage(image) => {
+                    self.reference_image = Some(image);
+                }
+                StarRenderingDialogEvent::OverlayOpacity(opacity) => {
+                    self.overlay_opacity = opacity;
+                }
+            }
+        }
+    }
+
+    fn on_submit(&self) -> GuiMessage {
+        GuiMessage::DialogClosed
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum StarRenderingDialogEvent {
+    Rendering(StarRendering),
+    ReferenceImage(Arc<BackgroundImage>),
+    OverlayOpacity(f32),
+}
+
+impl<Message> canvas::Program<Message> for StarRenderingDialog {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        draw_background(bounds, &mut frame, &CanvasBackground::BLACK);
+        let mut stars: Vec<_> = REFERENCE_PICTURE.iter().collect();
+        stars.sort_by(|a, b| a.magnitude.total_cmp(&b.magnitude));
+        for (i, star) in stars.iter().enumerate() {
+            let y = (i as f32 + 0.5) * ROW_HEIGHT;
+            let label = canvas::Text {
+                content: format!("{} ({:.2} mag)", star.name, star.magnitude),
+                position: Point::new(0., y - PADDING),
+                color: Color::WHITE,
+                ..Default::default()
+            };
+            frame.fill_text(label);
+
+            let illuminance = apparent_magnitude_to_illuminance(star.magnitude);
+            let (radius, alpha) = self.rendering.radius_and_alpha(illuminance);
+            let radius = radius.min(DISK_COLUMN_WIDTH / 2.);
+            let rendered =
+                canvas::Path::circle(Point::new(NAME_WIDTH + DISK_COLUMN_WIDTH / 2., y), radius);
+            frame.fill(&rendered, Color::from_rgba(1., 1., 1., alpha));
+
+            let reference = canvas::Path::circle(
+                Point::new(NAME_WIDTH + 1.5 * DISK_COLUMN_WIDTH, y),
+                star.radius(self.rendering.min_radius),
+            );
+            frame.fill(&reference, Color::from_rgba(1., 1., 1., star.alpha));
+        }
+        vec![frame.into_geometry()]
+    }
+}
+
+/*
+ * The bundled stars, scaled so that the whole field fits the canvas, with the reference image laid over them.
+ */
+struct ReferenceField<'a> {
+    dialog: &'a StarRenderingDialog,
+}
+
+impl<Message> canvas::Program<Message> for ReferenceField<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        draw_background(bounds, &mut frame, &CanvasBackground::BLACK);
+        let extent = self
+            .dialog
+            .field
+            .iter()
+            .map(|star| star.offset.x.abs().max(star.offset.y.abs()))
+            .fold(0., f64::max);
+        if extent > 0. {
+            let half_size = bounds.width.min(bounds.height) as f64 / 2.;
+            let scale = FIELD_FILL * half_size / extent;
+            let center = frame.center();
+            for star in self.dialog.field.iter() {
+                let (radius, alpha) = self.dialog.rendering.radius_and_alpha(star.illuminance);
+                let position = Point::new(
+                    center.x + (star.offset.x * scale) as f32,
+                    center.y + (star.offset.y * scale) as f32,
+                );
+                let disk = canvas::Path::circle(position, radius);
+                frame.fill(&disk, Color::from_rgba(1., 1., 1., alpha));
+            }
+        }
+        if let Some(image) = &self.dialog.reference_image {
+            image.draw(bounds, &mut frame, self.dialog.overlay_opacity);
+        }
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,225 @@
+use super::{Dialog, DialogUpdate, ElenathError};
+use crate::{
+    gui::{
+        canvas_background::CanvasBackground,
+        gui_widget::{group_digits, PADDING},
+        message::GuiMessage,
+        shared_canvas_functionality::draw_background,
+        shared_widgets::edit,
+    },
+    model::celestial_system::statistics::MagnitudeHistogram,
+};
+use iced::{
+    mouse::Cursor,
+    widget::{
+        canvas::{self, Path, Stroke, Style},
+        Button, Canvas, Column, Row, Text,
+    },
+    Alignment, Color, Element, Length, Point, Rectangle, Renderer, Theme, Vector,
+};
+
+const CHART_WIDTH: f32 = 800.;
+const CHART_HEIGHT: f32 = 400.;
+const MARGIN: f32 = 40.;
+const BAR_COLOR: Color = Color {
+    r: 0.4,
+    g: 0.6,
+    b: 1.,
+    a: 1.,
+};
+const CUMULATIVE_COLOR: Color = Color {
+    r: 1.,
+    g: 0.8,
+    b: 0.3,
+    a: 1.,
+};
+const DEFAULT_TRIM_MAGNITUDE: f64 = 10.;
+
+#[derive(Debug, Clone)]
+pub(crate) struct StarStatisticsDialog {
+    histogram: MagnitudeHistogram,
+    memory_in_bytes: usize,
+    trim_magnitude: f64,
+    trim_magnitude_string: String,
+}
+
+impl StarStatisticsDialog {
+    pub(crate) fn new(histogram: MagnitudeHistogram, memory_in_bytes: usize) -> Self {
+        StarStatisticsDialog {
+            histogram,
+            memory_in_bytes,
+            trim_magnitude: DEFAULT_TRIM_MAGNITUDE,
+            trim_magnitude_string: DEFAULT_TRIM_MAGNITUDE.to_string(),
+        }
+    }
+
+    fn trim_controls<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let explanation = Text::new(
+            "To free memory, stars fainter than an absolute magnitude can be dropped. Stars known only by their appearance are kept.",
+        );
+        let magnitude = edit(
+            "Faintest kept",
+            &self.trim_magnitude_string,
+            "mag",
+            |m| {
+                GuiMessage::DialogUpdate(DialogUpdate::StarStatisticsUpdated(
+                    StarStatisticsDialogEvent::TrimMagnitudeChanged(m),
+                ))
+            },
+            &Some(self.trim_magnitude),
+        );
+        let trim_button = Button::new(Text::new("Trim Faint Stars"))
+            .on_press(GuiMessage::TrimFaintStars(self.trim_magnitude));
+        Column::new()
+            .push(explanation)
+            .push(
+                Row::new()
+                    .push(magnitude)
+                    .push(trim_button)
+                    .spacing(PADDING)
+                    .align_y(Alignment::Center),
+            )
+            .spacing(PADDING)
+            .align_x(Alignment::Center)
+            .into()
+    }
+}
+
+impl Dialog for StarStatisticsDialog {
+    fn header(&self) -> String {
+        "Star Brightness Distribution".to_string()
+    }
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let total: usize = self.histogram.counts.iter().sum();
+        let summary = Text::new(format!(
+            "{} stars between {:.1} mag and {:.1} mag. Bars: stars per bin, line: cumulative count.",
+            total,
+            self.histogram.min_magnitude,
+            self.histogram.max_magnitude()
+        ));
+        let memory = Text::new(format!(
+            "The star catalog takes up about {} MB of memory.",
+            group_digits(self.memory_in_bytes.div_ceil(1_000_000))
+        ));
+        let chart = Canvas::new(self)
+            .width(Length::Fixed(CHART_WIDTH))
+            .height(Length::Fixed(CHART_HEIGHT));
+        let button = Button::new(Text::new("Ok")).on_press(GuiMessage::DialogClosed);
+        let export_button =
+            Button::new(Text::new("Export Catalog")).on_press(GuiMessage::ExportStarCatalog);
+        Column::new()
+            .push(summary)
+            .push(chart)
+            .push(memory)
+            .push(self.trim_controls())
+            .push(Row::new().push(export_button).push(button).spacing(PADDING))
+            .spacing(PADDING)
+            .align_x(Alignment::Center)
+            .into()
+    }
+
+    fn update(&mut self, event: DialogUpdate) {
+        if let DialogUpdate::StarStatisticsUpdated(event) = event {
+            match event {
+                StarStatisticsDialogEvent::TrimMagnitudeChanged(magnitude_string) => {
+                    let magnitude = magnitude_string.parse::<f64>().ok();
+                    if let Some(magnitude) = magnitude.filter(|m| m.is_finite()) {
+                        self.trim_magnitude = magnitude;
+                    }
+                    self.trim_magnitude_string = magnitude_string;
+                }
+            }
+        }
+    }
+
+    fn on_submit(&self) -> GuiMessage {
+        GuiMessage::DialogClosed
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
+
+impl<Message> canvas::Program<Message> for StarStatisticsDialog {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        draw_background(bounds, &mut frame, &CanvasBackground::BLACK);
+        self.draw_histogram(&mut frame, bounds);
+        vec![frame.into_geometry()]
+    }
+}
+
+impl StarStatisticsDialog {
+    fn draw_histogram(&self, frame: &mut canvas::Frame, bounds: Rectangle) {
+        let counts = &self.histogram.counts;
+        let cumulative = self.histogram.cumulative_counts();
+        let total = match cumulative.last() {
+            Some(&total) if total > 0 => total as f32,
+            _ => return,
+        };
+        let max_count = counts.iter().cloned().max().unwrap_or(1).max(1) as f32;
+        let chart_width = bounds.width - 2. * MARGIN;
+        let chart_height = bounds.height - 2. * MARGIN;
+        let bar_width = chart_width / counts.len() as f32;
+        let baseline = bounds.height - MARGIN;
+
+        for (i, count) in counts.iter().enumerate() {
+            let height = *count as f32 / max_count * chart_height;
+            let top_left = Point::new(MARGIN + i as f32 * bar_width, baseline - height);
+            let bar = Path::rectangle(top_left, iced::Size::new(bar_width * 0.9, height));
+            frame.fill(&bar, BAR_COLOR);
+        }
+
+        let cumulative_line = Path::new(|path_builder| {
+            path_builder.move_to(Point::new(MARGIN, baseline));
+            for (i, count) in cumulative.iter().enumerate() {
+                let x = MARGIN + (i + 1) as f32 * bar_width;
+                let y = baseline - *count as f32 / total * chart_height;
+                path_builder.line_to(Point::new(x, y));
+            }
+        });
+        let stroke = Stroke {
+            style: Style::Solid(CUMULATIVE_COLOR),
+            width: 2.,
+            ..Default::default()
+        };
+        frame.stroke(&cumulative_line, stroke);
+
+        let label_count = counts.len().min(10);
+        let label_step = counts.len().div_ceil(label_count.max(1));
+        for i in (0..=counts.len()).step_by(label_step.max(1)) {
+            let magnitude = self.histogram.min_magnitude + i as f64 * self.histogram.bin_width;
+            let label = canvas::Text {
+                content: format!("{:.1}", magnitude),
+                position: Point::new(MARGIN + i as f32 * bar_width, baseline)
+                    + Vector::new(0., PADDING),
+                color: Color::WHITE,
+                ..Default::default()
+            };
+            frame.fill_text(label);
+        }
+        let max_label = canvas::Text {
+            content: format!("{} per bin / {} total", max_count, total),
+            position: Point::new(MARGIN, MARGIN / 2.),
+            color: Color::WHITE,
+            ..Default::default()
+        };
+        frame.fill_text(max_label);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum StarStatisticsDialogEvent {
+    TrimMagnitudeChanged(String),
+}
@@ -0,0 +1,98 @@
+use super::{
+    line_chart::{draw_line_chart, CHART_HEIGHT, CHART_WIDTH},
+    Dialog, DialogUpdate, ElenathError,
+};
+use crate::{
+    gui::{
+        canvas_background::CanvasBackground, gui_widget::PADDING, message::GuiMessage,
+        shared_canvas_functionality::draw_background,
+    },
+    model::celestial_system::transit::TransitLightCurve,
+};
+use astro_utils::astro_display::AstroDisplay;
+use iced::{
+    mouse::Cursor,
+    widget::{canvas, text::Shaping, Button, Canvas, Column, Text},
+    Alignment, Element, Length, Rectangle, Renderer, Theme,
+};
+use uom::si::time::hour;
+
+#[derive(Debug, Clone)]
+pub(crate) struct TransitDialog {
+    planet_name: String,
+    light_curve: TransitLightCurve,
+}
+
+impl TransitDialog {
+    pub(crate) fn new(planet_name: String, light_curve: TransitLightCurve) -> Self {
+        TransitDialog {
+            planet_name,
+            light_curve,
+        }
+    }
+}
+
+impl Dialog for TransitDialog {
+    fn header(&self) -> String {
+        format!("Transit of {}", self.planet_name)
+    }
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let summary = Text::new(format!(
+            "Depth: {:.0} ppm ({:.3} %), Duration: {}",
+            self.light_curve.depth * 1e6,
+            self.light_curve.depth * 100.,
+            self.light_curve.duration.astro_display()
+        ))
+        .shaping(Shaping::Advanced);
+        let explanation = Text::new(
+            "Relative brightness of the central body over time since mid-transit, seen edge-on.",
+        );
+        let chart = Canvas::new(self)
+            .width(Length::Fixed(CHART_WIDTH))
+            .height(Length::Fixed(CHART_HEIGHT));
+        let button = Button::new(Text::new("Ok")).on_press(GuiMessage::DialogClosed);
+        Column::new()
+            .push(summary)
+            .push(explanation)
+            .push(chart)
+            .push(button)
+            .spacing(PADDING)
+            .align_x(Alignment::Center)
+            .into()
+    }
+
+    fn update(&mut self, _event: DialogUpdate) {}
+
+    fn on_submit(&self) -> GuiMessage {
+        GuiMessage::DialogClosed
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
+
+impl<Message> canvas::Program<Message> for TransitDialog {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        draw_background(bounds, &mut frame, &CanvasBackground::BLACK);
+        let points: Vec<(f64, f64)> = self
+            .light_curve
+            .samples
+            .iter()
+            .map(|(time, flux)| (time.get::<hour>(), *flux))
+            .collect();
+        draw_line_chart(&mut frame, bounds, &points, "h", "");
+        vec![frame.into_geometry()]
+    }
+}
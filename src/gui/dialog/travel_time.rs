@@ -0,0 +1,189 @@
+use super::{Dialog, DialogUpdate};
+use crate::{
+    error::ElenathError,
+    gui::{gui_widget::PADDING, message::GuiMessage, shared_widgets::edit},
+    model::travel::{
+        signal_time, travel_times_at_fraction_of_c, travel_times_with_delta_v, TravelEndpoint,
+        TravelTimes,
+    },
+};
+use astro_utils::astro_display::AstroDisplay;
+use iced::{
+    widget::{text::Shaping, Button, Column, PickList, Row, Text},
+    Alignment, Element, Length as IcedLength,
+};
+use uom::si::{f64::Velocity, velocity::kilometer_per_second};
+
+#[derive(Debug, Clone)]
+pub(crate) struct TravelTimeDialog {
+    endpoints: Vec<TravelEndpoint>,
+    origin: Option<TravelEndpoint>,
+    destination: Option<TravelEndpoint>,
+    fraction_of_c: f64,
+    fraction_of_c_string: String,
+    delta_v: Option<Velocity>,
+    delta_v_string: String,
+}
+
+impl TravelTimeDialog {
+    pub(crate) fn new(endpoints: Vec<TravelEndpoint>) -> Self {
+        TravelTimeDialog {
+            origin: endpoints.first().cloned(),
+            destination: endpoints.get(1).cloned(),
+            endpoints,
+            fraction_of_c: 0.1,
+            fraction_of_c_string: "0.1".to_string(),
+            delta_v: Some(Velocity::new::<kilometer_per_second>(30.)),
+            delta_v_string: "30".to_string(),
+        }
+    }
+
+    fn endpoint_row<'a, F>(
+        &'a self,
+        label: &'static str,
+        selected: &Option<TravelEndpoint>,
+        on_select: F,
+    ) -> Row<'a, GuiMessage>
+    where
+        F: 'a + Fn(TravelEndpoint) -> TravelTimeDialogEvent,
+    {
+        let picker = PickList::new(self.endpoints.clone(), selected.clone(), move |endpoint| {
+            GuiMessage::DialogUpdate(DialogUpdate::TravelTimeUpdated(on_select(endpoint)))
+        });
+        Row::new()
+            .push(Text::new(label))
+            .push(picker)
+            .spacing(PADDING)
+            .align_y(Alignment::Center)
+    }
+
+    fn results_column(&self) -> Column<'_, GuiMessage> {
+        let (origin, destination) = match (&self.origin, &self.destination) {
+            (Some(origin), Some(destination)) => (origin, destination),
+            _ => return Column::new().push(Text::new("Please select two places.")),
+        };
+        let distance = origin.distance_to(destination);
+        let at_fraction = travel_times_at_fraction_of_c(distance, self.fraction_of_c);
+        let with_delta_v = self
+            .delta_v
+            .and_then(|delta_v| travel_times_with_delta_v(distance, delta_v));
+        Column::new()
+            .push(
+                Text::new("Distance: ".to_string() + &distance.astro_display())
+                    .shaping(Shaping::Advanced),
+            )
+            .push(
+                Text::new("Signal Time: ".to_string() + &signal_time(distance).astro_display())
+                    .shaping(Shaping::Advanced),
+            )
+            .push(travel_times_text(
+                &format!("At {}c", self.fraction_of_c),
+                at_fraction,
+            ))
+            .push(travel_times_text("With Delta-v", with_delta_v))
+            .spacing(PADDING)
+    }
+}
+
+fn travel_times_text(label: &str, times: Option<TravelTimes>) -> Text<'static> {
+    let content = match times {
+        Some(times) => format!(
+            "{}: {} (on board: {})",
+            label,
+            times.coordinate_time.astro_display(),
+            times.proper_time.astro_display()
+        ),
+        None => format!("{}: unreachable", label),
+    };
+    Text::new(content).shaping(Shaping::Advanced)
+}
+
+fn message<F: Fn(String) -> TravelTimeDialogEvent>(event: F) -> impl Fn(String) -> GuiMessage {
+    move |m| GuiMessage::DialogUpdate(DialogUpdate::TravelTimeUpdated(event(m)))
+}
+
+impl Dialog for TravelTimeDialog {
+    fn header(&self) -> String {
+        "Travel Times".to_string()
+    }
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let origin =
+            self.endpoint_row("From:", &self.origin, TravelTimeDialogEvent::OriginSelected);
+        let destination = self.endpoint_row(
+            "To:",
+            &self.destination,
+            TravelTimeDialogEvent::DestinationSelected,
+        );
+        let fraction_of_c = edit(
+            "Speed",
+            &self.fraction_of_c_string,
+            "fraction of c",
+            message(TravelTimeDialogEvent::FractionOfCChanged),
+            &Some(self.fraction_of_c),
+        );
+        let delta_v = edit(
+            "Delta-v",
+            &self.delta_v_string,
+            "km/s",
+            message(TravelTimeDialogEvent::DeltaVChanged),
+            &self.delta_v,
+        );
+        let close_button = Button::new(Text::new("Close")).on_press(GuiMessage::DialogClosed);
+
+        Column::new()
+            .push(origin)
+            .push(destination)
+            .push(fraction_of_c)
+            .push(delta_v)
+            .push(self.results_column())
+            .push(close_button)
+            .padding(PADDING)
+            .spacing(PADDING)
+            .width(IcedLength::Fill)
+            .align_x(Alignment::Center)
+            .into()
+    }
+
+    fn update(&mut self, message: DialogUpdate) {
+        if let DialogUpdate::TravelTimeUpdated(event) = message {
+            match event {
+                TravelTimeDialogEvent::OriginSelected(origin) => {
+                    self.origin = Some(origin);
+                }
+                TravelTimeDialogEvent::DestinationSelected(destination) => {
+                    self.destination = Some(destination);
+                }
+                TravelTimeDialogEvent::FractionOfCChanged(fraction_of_c_string) => {
+                    if let Ok(fraction_of_c) = fraction_of_c_string.parse::<f64>() {
+                        self.fraction_of_c = fraction_of_c;
+                    }
+                    self.fraction_of_c_string = fraction_of_c_string;
+                }
+                TravelTimeDialogEvent::DeltaVChanged(delta_v_string) => {
+                    self.delta_v = delta_v_string
+                        .parse::<f64>()
+                        .ok()
+                        .map(Velocity::new::<kilometer_per_second>);
+                    self.delta_v_string = delta_v_string;
+                }
+            }
+        }
+    }
+
+    fn on_submit(&self) -> GuiMessage {
+        GuiMessage::DialogClosed
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) enum TravelTimeDialogEvent {
+    OriginSelected(TravelEndpoint),
+    DestinationSelected(TravelEndpoint),
+    FractionOfCChanged(String),
+    DeltaVChanged(String),
+}
@@ -0,0 +1,62 @@
+use super::{Dialog, DialogUpdate};
+use crate::error::ElenathError;
+use crate::gui::{gui_widget::PADDING, message::GuiMessage};
+use crate::model::celestial_system::zodiac::ZodiacPassage;
+use iced::{
+    widget::{text::Shaping, Button, Column, Text},
+    Element,
+};
+
+#[derive(Debug, Clone)]
+pub(crate) struct ZodiacDialog {
+    planet_name: String,
+    central_body_name: String,
+    passages: Vec<ZodiacPassage>,
+}
+
+impl ZodiacDialog {
+    pub(crate) fn new(
+        planet_name: String,
+        central_body_name: String,
+        passages: Vec<ZodiacPassage>,
+    ) -> Self {
+        ZodiacDialog {
+            planet_name,
+            central_body_name,
+            passages,
+        }
+    }
+}
+
+impl Dialog for ZodiacDialog {
+    fn header(&self) -> String {
+        format!("Zodiac of {}", self.planet_name)
+    }
+
+    fn update(&mut self, _event: DialogUpdate) {}
+
+    fn body<'a>(&'a self) -> Element<'a, GuiMessage> {
+        let explanation = format!(
+            "The constellations {} passes through over one orbit, as seen from {}.",
+            self.central_body_name, self.planet_name
+        );
+        let mut col = Column::new().spacing(PADDING).push(Text::new(explanation));
+        for passage in self.passages.iter() {
+            let line = format!(
+                "{}: {} to {}",
+                passage.constellation, passage.entry_date, passage.exit_date
+            );
+            col = col.push(Text::new(line).shaping(Shaping::Advanced));
+        }
+        let button = Button::new(Text::new("Ok")).on_press(GuiMessage::DialogClosed);
+        col.push(button).into()
+    }
+
+    fn on_submit(&self) -> GuiMessage {
+        GuiMessage::DialogClosed
+    }
+
+    fn get_error(&self) -> Option<ElenathError> {
+        None
+    }
+}
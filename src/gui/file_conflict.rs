@@ -0,0 +1,69 @@
+use std::{path::Path, time::SystemTime};
+
+use super::Gui;
+use crate::{error::ElenathError, model::universe::Universe};
+
+fn modification_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).ok()?.modified().ok()
+}
+
+/*
+ * A file that has vanished in the meantime is no conflict, saving merely creates it again.
+ */
+fn has_changed(known: Option<SystemTime>, current: Option<SystemTime>) -> bool {
+    current.is_some() && current != known
+}
+
+impl Gui {
+    /*
+     * Whether another program wrote to the opened file since it was last read or written here.
+     */
+    pub(super) fn opened_file_changed_on_disk(&self) -> bool {
+        match &self.opened_file {
+            Some(path) => has_changed(self.opened_file_modified, modification_time(path)),
+            None => false,
+        }
+    }
+
+    pub(super) fn write_opened_file(&mut self) -> Result<(), ElenathError> {
+        let Some(path) = self.opened_file.clone() else {
+            return Ok(());
+        };
+        self.universe
+            .write_to_file(self.get_system_const()?, path.clone())?;
+        self.opened_file_modified = modification_time(&path);
+        self.unsaved_changes = false;
+        self.status_message = Some("Saved".to_string());
+        Ok(())
+    }
+
+    pub(super) fn read_opened_file(&mut self) -> Result<(), ElenathError> {
+        let Some(path) = self.opened_file.clone() else {
+            return Ok(());
+        };
+        let (universe, system) = Universe::read_from_file(path.clone())?;
+        self.universe = universe;
+        self.celestial_system = Some(system);
+        self.view_settings.clear();
+        self.opened_file_modified = modification_time(&path);
+        self.unsaved_changes = false;
+        self.status_message = Some("Loaded".to_string());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn only_a_newer_or_older_file_on_disk_is_a_conflict() {
+        let known = SystemTime::UNIX_EPOCH + Duration::from_secs(1000);
+        let later = known + Duration::from_secs(1);
+        assert!(!has_changed(Some(known), Some(known)));
+        assert!(has_changed(Some(known), Some(later)));
+        assert!(has_changed(None, Some(known)));
+        assert!(!has_changed(Some(known), None));
+    }
+}
@@ -1,20 +1,62 @@
 use super::{
-    dialog::error::ErrorDialog, message::GuiMessage,
-    shared_widgets::surface_and_top_view_shared_control, surface_view::widget::SurfaceViewState,
-    table_view::widget::TableViewState, top_view::widget::TopViewState, Gui,
+    camera_input::{CameraCommand, CameraInput},
+    canvas_background::CanvasBackground,
+    dialog::{error::ErrorDialog, DialogType},
+    message::GuiMessage,
+    screenshot::ExportCaption,
+    shared_canvas_functionality::LabelStyle,
+    shared_widgets::{
+        canvas_background_control, control_field, label_style_control, std_button,
+        surface_and_top_view_shared_control,
+    },
+    surface_view::{
+        sky_region::RegionDrag,
+        widget::{SurfaceViewState, SurfaceViewUpdate},
+    },
+    table_view::widget::TableViewState,
+    toast::Toasts,
+    top_view::widget::TopViewState,
+    touch::TouchTracker,
+    view_settings::ViewSettingsMemory,
+    Gui,
 };
+use crate::{
+    model::universe::Universe,
+    window_layout::{PlanetariumMonitor, WindowLayout},
+};
+use astro_utils::astro_display::AstroDisplay;
 use iced::{
-    mouse::Cursor,
-    widget::{canvas, opaque, stack, Column, Container, Row, Text},
-    Element, Length, Rectangle, Renderer, Theme,
+    mouse::{self, Cursor},
+    widget::{canvas, combo_box, opaque, stack, Column, Container, PickList, Row, Text, Toggler},
+    window, Alignment, Element, Length, Point, Rectangle, Renderer, Size, Subscription, Task,
+    Theme,
 };
+use std::cell::Cell;
 use uom::si::{f64::Time, time::day};
 
 pub(super) const PADDING: f32 = 10.0;
 pub(super) const SMALL_COLUMN_WIDTH: f32 = 150.0;
 pub(super) const BIG_COLUMN_WIDTH: f32 = 3.5 * SMALL_COLUMN_WIDTH;
+const UI_SCALE_STEP: f32 = 0.1;
+pub(super) const MIN_UI_SCALE: f32 = 0.5;
+pub(super) const MAX_UI_SCALE: f32 = 3.0;
 
-#[derive(Debug, Clone)]
+/*
+ * Separates groups of three digits with commas, like 11,324.
+ */
+pub(super) fn group_digits(number: usize) -> String {
+    let digits = number.to_string();
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub(crate) enum GuiViewMode {
     Surface,
     Top,
@@ -25,54 +67,320 @@ impl Default for Gui {
     fn default() -> Self {
         Gui {
             opened_file: None,
+            opened_file_modified: None,
+            unsaved_changes: false,
+            locked: false,
+            status_message: None,
+            toasts: Toasts::default(),
             mode: GuiViewMode::Surface,
+            linked_views: true,
+            view_settings: ViewSettingsMemory::default(),
             surface_view_state: SurfaceViewState::new(),
             top_view_state: TopViewState::new(),
             table_view_state: TableViewState::new(),
             time_step: Time::new::<day>(1.0),
             celestial_system: None,
-            selected_planet_name: String::new(),
+            universe: Universe::new(),
+            selected_planet: None,
+            planet_picker: combo_box::State::new(vec![]),
+            planet_choices: vec![],
+            target_planet: None,
+            target_picker: combo_box::State::new(vec![]),
+            target_choices: vec![],
             display_names: true,
             display_constellations: false,
+            label_style: LabelStyle::default(),
+            canvas_background: CanvasBackground::load(),
+            ui_scale: 1.,
+            window_id: None,
+            table_window_id: None,
+            window_layout: WindowLayout::load(),
+            planetarium: false,
+            export_caption: ExportCaption::default(),
+            pending_constellations: None,
+            duplicate_stars: None,
+            canvas_bounds: Cell::new(Rectangle::default()),
             dialog: None,
         }
     }
 }
 
 impl Gui {
-    fn title(&self) -> String {
-        String::from("Elenath - Imaginary Skies")
+    pub(crate) fn title(&self, window_id: window::Id) -> String {
+        if Some(window_id) == self.table_window_id {
+            return String::from("Elenath - Table");
+        }
+        let system_name = self
+            .celestial_system
+            .as_ref()
+            .map(|s| s.get_metadata().name.as_str())
+            .unwrap_or_default();
+        if system_name.is_empty() {
+            String::from("Elenath - Imaginary Skies")
+        } else {
+            format!("Elenath - {}", system_name)
+        }
+    }
+
+    /*
+     * The main window is opened here rather than by the runtime, because the table can be detached into a second window.
+     * Once it is open, the monitor it landed on is checked against the one its position was saved on.
+     */
+    pub(crate) fn new() -> (Self, Task<GuiMessage>) {
+        let gui = Gui::default();
+        let (id, open) = window::open(gui.window_layout.to_settings());
+        let gui = Gui {
+            window_id: Some(id),
+            ..gui
+        };
+        let check = open
+            .then(window::monitor_size)
+            .map(GuiMessage::CheckWindowMonitor);
+        (gui, check)
+    }
+
+    /*
+     * Opening, closing and changing windows are tasks for the runtime, which are issued here.
+     */
+    fn window_task(&mut self, message: &GuiMessage) -> Option<Task<GuiMessage>> {
+        match message {
+            GuiMessage::SetPlanetarium(true) => {
+                let id = self.window_id?;
+                let choice = self.window_layout.planetarium_monitor;
+                let to_monitor = window::position(id).then(move |position| {
+                    window::monitor_size(id).then(move |monitor| match (position, monitor) {
+                        (Some(position), Some(monitor))
+                            if choice != PlanetariumMonitor::Current =>
+                        {
+                            window::move_to(id, choice.window_position(position, monitor))
+                        }
+                        _ => Task::none(),
+                    })
+                });
+                Some(to_monitor.chain(window::set_mode(id, window::Mode::Fullscreen)))
+            }
+            GuiMessage::SetPlanetarium(false) => {
+                let id = self.window_id?;
+                // Window movements are not remembered during the planetarium, so this is where the window was before.
+                let back = match self.window_layout.position {
+                    Some((x, y))
+                        if self.window_layout.planetarium_monitor
+                            != PlanetariumMonitor::Current =>
+                    {
+                        window::move_to(id, Point::new(x, y))
+                    }
+                    _ => Task::none(),
+                };
+                Some(window::set_mode(id, window::Mode::Windowed).chain(back))
+            }
+            GuiMessage::CheckWindowMonitor(monitor)
+                if !self.window_layout.is_on_saved_monitor(*monitor) =>
+            {
+                Some(window::move_to(self.window_id?, Point::ORIGIN))
+            }
+            GuiMessage::DetachTable => {
+                if self.table_window_id.is_some() {
+                    return None;
+                }
+                let settings = window::Settings {
+                    size: Size::new(self.window_layout.width, self.window_layout.height),
+                    ..Default::default()
+                };
+                let (id, open) = window::open(settings);
+                self.table_window_id = Some(id);
+                Some(open.discard())
+            }
+            GuiMessage::AttachTable => Some(window::close(self.table_window_id.take()?)),
+            GuiMessage::WindowEvent(id, window::Event::Closed) if Some(*id) == self.window_id => {
+                Some(iced::exit())
+            }
+            _ => None,
+        }
     }
 
-    pub(crate) fn update(&mut self, message: GuiMessage) {
-        if let Err(e) = self.handle_message(message) {
+    pub(crate) fn update(&mut self, message: GuiMessage) -> Task<GuiMessage> {
+        if let GuiMessage::WindowEvent(id, window::Event::Resized(size)) = message {
+            if Some(id) == self.window_id {
+                return window::is_maximized(id)
+                    .map(move |maximized| GuiMessage::WindowResized(size, maximized));
+            }
+        }
+        if let GuiMessage::WindowEvent(id, window::Event::Moved(position)) = message {
+            if Some(id) == self.window_id {
+                return window::monitor_size(id)
+                    .map(move |monitor| GuiMessage::WindowMoved(position, monitor));
+            }
+        }
+        let window_task = self.window_task(&message);
+        let selected_planet = match &message {
+            GuiMessage::PlanetSelected(id) => *id,
+            _ => None,
+        };
+        // Switching to an unlinked view may bring back another time.
+        let is_time_update = matches!(
+            message,
+            GuiMessage::UpdateTime(_)
+                | GuiMessage::AdvanceLocalDay
+                | GuiMessage::AdvanceToLocalMidnight
+        ) || (matches!(message, GuiMessage::ModeSelected(_))
+            && !self.linked_views);
+        let time_before = self
+            .celestial_system
+            .as_ref()
+            .map(|system| system.get_time_since_epoch());
+        let searches_duplicates =
+            matches!(message, GuiMessage::OpenDialog(DialogType::DuplicateStars));
+        let result = self.handle_message(message);
+        self.refresh_planet_picker();
+        if let Err(e) = result {
             self.dialog = Some(Box::new(ErrorDialog::new(e)));
+            return Task::none();
         }
+        if is_time_update {
+            self.announce_supernovae(time_before);
+        }
+        // Any message may have changed the time, the epoch or the stars.
+        let constellations = self.recompute_outdated_constellations();
+        self.table_view_state
+            .update_next_star_birth(self.celestial_system.as_ref());
+        let duplicates = if searches_duplicates {
+            self.search_duplicate_stars()
+        } else {
+            Task::none()
+        };
+        let task = match (window_task, selected_planet) {
+            (Some(task), _) => task,
+            (None, Some(id)) => self.scroll_table_to_planet(id),
+            (None, None) => Task::none(),
+        };
+        Task::batch([constellations, duplicates, task])
     }
 
-    pub(crate) fn view(&self) -> Element<'_, GuiMessage> {
+    pub(crate) fn view(&self, window_id: window::Id) -> Element<'_, GuiMessage> {
+        if Some(window_id) == self.table_window_id {
+            return self.table_window_view();
+        }
         if let Some(dialog) = self.dialog.as_ref() {
             stack!(self.main_view(), opaque(dialog.to_element())).into()
+        } else if !self.toasts.is_empty() {
+            stack!(self.main_view(), self.toasts.view()).into()
         } else {
             self.main_view()
         }
     }
 
+    fn announce_supernovae(&mut self, time_before: Option<Time>) {
+        let (Some(from), Some(system)) = (time_before, self.celestial_system.as_ref()) else {
+            return;
+        };
+        let to = system.get_time_since_epoch();
+        if to <= from {
+            return;
+        }
+        for name in system.get_supernovae_between(from, to) {
+            self.toasts.push(format!("{} went supernova", name));
+        }
+    }
+
+    pub(crate) fn subscription(&self) -> Subscription<GuiMessage> {
+        let window_events = window::events().map(|(id, event)| GuiMessage::WindowEvent(id, event));
+        let mut subscriptions = vec![window_events];
+        #[cfg(feature = "remote_control")]
+        subscriptions.push(super::remote_control::subscription());
+        let is_animating =
+            self.surface_view_state.is_animating() || self.top_view_state.is_animating();
+        if is_animating || !self.toasts.is_empty() {
+            subscriptions.push(window::frames().map(GuiMessage::AnimationTick));
+        }
+        Subscription::batch(subscriptions)
+    }
+
     fn theme(&self) -> Theme {
         Theme::Dark
     }
 }
 
-impl<GuiMessage> canvas::Program<GuiMessage> for Gui {
-    type State = ();
+/*
+ * Whatever the canvas needs to remember between events, such as the fingers on it or a region being dragged open.
+ */
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CanvasState {
+    touch: TouchTracker,
+    camera: CameraInput,
+    region_drag: RegionDrag,
+}
+
+impl canvas::Program<GuiMessage> for Gui {
+    type State = CanvasState;
+
+    fn update(
+        &self,
+        state: &mut Self::State,
+        event: &canvas::Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> Option<canvas::Action<GuiMessage>> {
+        if let canvas::Event::Touch(touch_event) = event {
+            let gesture = state.touch.handle(touch_event)?;
+            let message = GuiMessage::TouchGesture(gesture, bounds.size());
+            return Some(canvas::Action::publish(message).and_capture());
+        }
+        if let Some(command) = state.camera.handle(event, bounds, cursor) {
+            let message = match command {
+                CameraCommand::Gesture(gesture) => GuiMessage::TouchGesture(gesture, bounds.size()),
+                CameraCommand::Walk(east, north) => match self.mode {
+                    GuiViewMode::Surface => SurfaceViewUpdate::MoveObserver(east, north).into(),
+                    _ => return None,
+                },
+            };
+            return Some(canvas::Action::publish(message).and_capture());
+        }
+        match self.mode {
+            GuiViewMode::Surface => Self::update_region_drag(state, event, bounds, cursor),
+            GuiViewMode::Top => {
+                let canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event
+                else {
+                    return None;
+                };
+                let position = cursor.position_in(bounds)?;
+                let celestial_system = self.celestial_system.as_ref()?;
+                let id = self.top_view_state.planet_at(
+                    position,
+                    bounds.size(),
+                    &self.get_selected_planet(),
+                    celestial_system,
+                )?;
+                Some(canvas::Action::publish(GuiMessage::PlanetSelected(id)).and_capture())
+            }
+            GuiViewMode::Table => None,
+        }
+    }
 
     fn draw(
         &self,
-        _state: &Self::State,
+        state: &Self::State,
         renderer: &Renderer,
         _theme: &Theme,
         bounds: Rectangle,
         _cursor: Cursor,
+    ) -> Vec<canvas::Geometry> {
+        self.canvas_bounds.set(bounds);
+        let mut geometries = self.draw_view(renderer, bounds);
+        if let GuiViewMode::Surface = self.mode {
+            geometries.extend(state.region_drag.draw(renderer, bounds));
+        }
+        geometries
+    }
+}
+
+impl Gui {
+    /*
+     * What the canvas shows of the system, without anything that belongs to an ongoing interaction.
+     */
+    pub(super) fn draw_view(
+        &self,
+        renderer: &Renderer,
+        bounds: Rectangle,
     ) -> Vec<canvas::Geometry> {
         match self.mode {
             GuiViewMode::Surface => self.surface_view_state.canvas(
@@ -82,13 +390,18 @@ impl<GuiMessage> canvas::Program<GuiMessage> for Gui {
                 &self.celestial_system,
                 self.display_names,
                 self.display_constellations,
+                &self.label_style,
+                &self.canvas_background,
             ),
             GuiViewMode::Top => self.top_view_state.canvas(
                 renderer,
                 bounds,
                 &self.get_selected_planet(),
+                self.target_planet,
                 &self.celestial_system,
                 self.display_names,
+                &self.label_style,
+                &self.canvas_background,
             ),
             _ => {
                 println!("Invalid Gui state: Canvas Program is called from a Gui mode that does not have a canvas.");
@@ -96,13 +409,194 @@ impl<GuiMessage> canvas::Program<GuiMessage> for Gui {
             }
         }
     }
-}
 
-impl Gui {
+    /*
+     * Dragging a rectangle open on the sky selects a region to compute statistics for.
+     */
+    fn update_region_drag(
+        state: &mut CanvasState,
+        event: &canvas::Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> Option<canvas::Action<GuiMessage>> {
+        let canvas::Event::Mouse(mouse_event) = event else {
+            return None;
+        };
+        match mouse_event {
+            mouse::Event::ButtonPressed(mouse::Button::Left) => {
+                state.region_drag.press(cursor.position_in(bounds)?);
+                Some(canvas::Action::capture())
+            }
+            mouse::Event::CursorMoved { .. } => {
+                let position = cursor.position_in(bounds)?;
+                state
+                    .region_drag
+                    .move_to(position)
+                    .then(canvas::Action::request_redraw)
+            }
+            mouse::Event::ButtonReleased(mouse::Button::Left) => {
+                let region = state.region_drag.release()?;
+                let message = GuiMessage::SkyRegionSelected(region, bounds.size());
+                Some(canvas::Action::publish(message).and_capture())
+            }
+            _ => None,
+        }
+    }
+
+    fn system_switcher(&self) -> Element<'_, GuiMessage> {
+        let mut row = Row::new();
+        if let Some(system) = self.celestial_system.as_ref() {
+            let systems = self.universe.get_system_references(system);
+            if systems.len() > 1 {
+                let active_system = systems
+                    .get(self.universe.get_active_system_index())
+                    .cloned();
+                let picker = PickList::new(systems, active_system, |system| {
+                    GuiMessage::SystemSelected(system.index)
+                });
+                row = row.push(picker);
+            }
+        }
+        row.push(std_button(
+            "Add System",
+            GuiMessage::AddSystem,
+            self.celestial_system.is_some() && !self.locked,
+        ))
+        .spacing(PADDING)
+        .align_y(Alignment::Center)
+        .into()
+    }
+
+    /*
+     * Scales every widget, padding and font at once, for high resolution or small displays.
+     */
+    pub(crate) fn scale_factor(&self, _window_id: window::Id) -> f32 {
+        self.ui_scale
+    }
+
+    /*
+     * The planetarium shows nothing but the sky, in fullscreen on the chosen monitor.
+     */
+    fn planetarium_view(&self) -> Element<'_, GuiMessage> {
+        let exit_button = Container::new(std_button(
+            "Exit Planetarium",
+            GuiMessage::SetPlanetarium(false),
+            true,
+        ))
+        .padding(PADDING);
+        stack!(
+            canvas(self).width(Length::Fill).height(Length::Fill),
+            exit_button
+        )
+        .into()
+    }
+
+    /*
+     * The selection is shared with the main window, because both windows are views of the same Gui.
+     */
+    fn table_window_view(&self) -> Element<'_, GuiMessage> {
+        let toprow = Row::new()
+            .push(Container::new(Text::new("")).width(Length::Fill))
+            .push(std_button("Attach Table", GuiMessage::AttachTable, true))
+            .padding(PADDING);
+        Column::new()
+            .push(toprow)
+            .push(self.table_view_state.table_view(
+                &self.celestial_system,
+                &self.get_selected_planet(),
+                self.locked,
+            ))
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .spacing(PADDING)
+            .into()
+    }
+
+    /*
+     * The last status message stays until it is replaced, or until the data is changed again.
+     */
+    fn status_bar(&self) -> Element<'_, GuiMessage> {
+        let file_name = self
+            .opened_file
+            .as_ref()
+            .and_then(|path| path.file_name())
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "Unsaved file".to_string());
+        let mut row = Row::new().push(Text::new(file_name));
+        if self.unsaved_changes {
+            row = row.push(Text::new("(modified)"));
+        }
+        if self.locked {
+            row = row.push(Text::new("(locked)"));
+        }
+        if let Some(system) = self.celestial_system.as_ref() {
+            row = row
+                .push(Text::new(format!(
+                    "Time since epoch: {}",
+                    system.get_time_since_epoch().astro_display()
+                )))
+                .push(Text::new(format!(
+                    "{} planets, {} stars",
+                    group_digits(system.get_planets_data().len()),
+                    group_digits(system.get_distant_stars().len() + 1)
+                )));
+        }
+        row.push(Container::new(Text::new("")).width(Length::Fill))
+            .push(Text::new(self.status_message.clone().unwrap_or_default()))
+            .padding(PADDING)
+            .spacing(2. * PADDING)
+            .into()
+    }
+
     fn main_view(&self) -> Element<'_, GuiMessage> {
+        let has_canvas = !matches!(self.mode, GuiViewMode::Table);
+        if self.planetarium && has_canvas && self.celestial_system.is_some() {
+            return self.planetarium_view();
+        }
         let toprow = Row::new()
             .push(Gui::gui_mode_tabs())
+            .push(self.view_mode_switcher())
             .push(Container::new(Text::new("")).width(Length::Fill))
+            .push(control_field(
+                "UI scale:",
+                format!("{:.0}%", self.ui_scale * 100.),
+                GuiMessage::SetUiScale(self.ui_scale - UI_SCALE_STEP),
+                GuiMessage::SetUiScale(self.ui_scale + UI_SCALE_STEP),
+            ))
+            .push(std_button(
+                "Planetarium",
+                GuiMessage::SetPlanetarium(true),
+                has_canvas && self.celestial_system.is_some(),
+            ))
+            .push(
+                PickList::new(
+                    PlanetariumMonitor::ALL,
+                    Some(self.window_layout.planetarium_monitor),
+                    GuiMessage::SetPlanetariumMonitor,
+                )
+                .width(Length::Fixed(SMALL_COLUMN_WIDTH)),
+            )
+            .push(std_button(
+                "Export Screenshot",
+                GuiMessage::OpenDialog(DialogType::ExportScreenshot),
+                has_canvas && self.celestial_system.is_some(),
+            ))
+            .push(self.system_switcher())
+            .push(std_button(
+                "System Info",
+                GuiMessage::OpenDialog(DialogType::EditMetadata),
+                self.celestial_system.is_some() && !self.locked,
+            ))
+            .push(std_button(
+                "Edit Central Body",
+                GuiMessage::OpenDialog(DialogType::EditCentralBody),
+                self.celestial_system.is_some() && !self.locked,
+            ))
+            .push(
+                Toggler::new(self.locked)
+                    .label("Lock")
+                    .on_toggle(GuiMessage::SetLocked),
+            )
             .push(Gui::file_buttons(self.celestial_system.is_some()))
             .padding(PADDING)
             .spacing(PADDING);
@@ -115,40 +609,105 @@ impl Gui {
                         .push(surface_and_top_view_shared_control(
                             system.get_time_since_epoch(),
                             self.time_step,
-                            self.get_planet_data(),
-                            self.get_selected_planet_data(),
+                            &self.planet_picker,
+                            &self.planet_choices,
+                            self.selected_planet,
                             self.display_names,
                             self.display_constellations,
+                            system.get_galactic_motion(),
+                            self.get_local_solar_time(),
+                            self.get_day_lengths(),
+                            system.format_calendar_date(system.get_time_since_epoch()),
                         ))
-                        .push(self.surface_view_state.control_field());
+                        .push(self.surface_view_state.control_field(
+                            self.get_observer_locations(),
+                            self.get_trackable_bodies(),
+                            self.get_selected_planet().map(|planet| {
+                                self.surface_view_state
+                                    .naked_eye_star_counts(&planet, system)
+                            }),
+                            self.get_selected_planet().map(|planet| {
+                                self.surface_view_state
+                                    .pole_stars(planet.get_data(), system)
+                            }),
+                            self.get_subsolar_point(),
+                        ))
+                        .push(self.target_control())
+                        .push(label_style_control(self.label_style))
+                        .push(canvas_background_control(
+                            self.canvas_background.get_style(),
+                        ));
+                    let summary = format!(
+                        "{} planets orbiting {}",
+                        system.get_planets_data().len(),
+                        system.get_central_body_data().get_name()
+                    );
                     col = col
                         .push(control_row)
                         .push(canvas(self).width(Length::Fill).height(Length::Fill))
+                        .push(Text::new(summary))
+                        .push(Text::new(self.surface_view_state.canvas_summary()))
                 }
                 GuiViewMode::Top => {
-                    let control_row = Row::new()
-                        .push(surface_and_top_view_shared_control(
-                            system.get_time_since_epoch(),
-                            self.time_step,
-                            self.get_planet_data(),
-                            self.get_selected_planet_data(),
-                            self.display_names,
-                            self.display_constellations,
-                        ))
-                        .push(self.top_view_state.control_field());
+                    let control_row =
+                        Row::new()
+                            .push(surface_and_top_view_shared_control(
+                                system.get_time_since_epoch(),
+                                self.time_step,
+                                &self.planet_picker,
+                                &self.planet_choices,
+                                self.selected_planet,
+                                self.display_names,
+                                self.display_constellations,
+                                system.get_galactic_motion(),
+                                None,
+                                self.get_day_lengths(),
+                                system.format_calendar_date(system.get_time_since_epoch()),
+                            ))
+                            .push(self.top_view_state.control_field(
+                                self.get_selected_planet_data(),
+                                self.selected_planet,
+                            ))
+                            .push(self.target_control())
+                            .push(label_style_control(self.label_style))
+                            .push(canvas_background_control(
+                                self.canvas_background.get_style(),
+                            ));
                     col = col
                         .push(control_row)
                         .push(canvas(self).width(Length::Fill).height(Length::Fill))
                 }
                 GuiViewMode::Table => {
-                    col = col.push(self.table_view_state.table_view(&self.celestial_system));
+                    let detach_row = Row::new()
+                        .push(Container::new(Text::new("")).width(Length::Fill))
+                        .push(std_button("Detach Table", GuiMessage::DetachTable, true))
+                        .padding(PADDING);
+                    col = col.push(detach_row).push(self.table_view_state.table_view(
+                        &self.celestial_system,
+                        &self.get_selected_planet(),
+                        self.locked,
+                    ));
                 }
             }
         }
 
-        col.width(Length::Fill)
+        col.push(self.status_bar())
+            .width(Length::Fill)
             .height(Length::Fill)
             .spacing(PADDING)
             .into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digits_are_grouped_in_threes() {
+        assert_eq!(group_digits(0), "0");
+        assert_eq!(group_digits(999), "999");
+        assert_eq!(group_digits(11324), "11,324");
+        assert_eq!(group_digits(1234567), "1,234,567");
+    }
+}
@@ -1,48 +1,201 @@
+use super::canvas_background::{BackgroundImage, BackgroundStyle};
+use super::dialog::binary_planets::{BinaryPlanetPair, BinaryPlanetsDialog, PlanetChoice};
+use super::dialog::calendar::{CalendarDialog, PlanetDays};
+use super::dialog::co_orbital::CoOrbitalDialog;
+use super::dialog::duplicate_stars::DuplicateStarsDialog;
+use super::dialog::epoch::EpochDialog;
+use super::dialog::equation_of_time::EquationOfTimeDialog;
+use super::dialog::export_screenshot::ExportScreenshotDialog;
+use super::dialog::file_conflict::FileConflictDialog;
+use super::dialog::flyby::{FlybyDialog, FlybyDialogEvent};
+use super::dialog::habitability::HabitabilityDialog;
 use super::dialog::load_real_planets::LoadRealPlanetsDialog;
 use super::dialog::load_real_stars::LoadRealStarsDialog;
+use super::dialog::metadata::MetadataDialog;
+use super::dialog::migration::MigrationDialog;
 use super::dialog::planet::PlanetDialog;
+use super::dialog::radial_velocity::RadialVelocityDialog;
 use super::dialog::randomize_planets::RandomizePlanetsDialog;
 use super::dialog::randomize_stars::RandomizeStarsDialog;
+use super::dialog::sky_region::SkyRegionDialog;
+use super::dialog::space_engine_import::SpaceEngineImportDialog;
+use super::dialog::stability::StabilityDialog;
 use super::dialog::star::StarDialog;
+use super::dialog::star_forming_regions::StarFormingRegionsDialog;
+use super::dialog::star_jitter::StarJitterDialog;
+use super::dialog::star_rendering::{StarRenderingDialog, StarRenderingDialogEvent};
+use super::dialog::star_statistics::StarStatisticsDialog;
+use super::dialog::transit::TransitDialog;
+use super::dialog::travel_time::TravelTimeDialog;
+use super::dialog::zodiac::ZodiacDialog;
 use super::dialog::{DialogType, DialogUpdate};
-use super::gui_widget::GuiViewMode;
+use super::gui_widget::{group_digits, GuiViewMode, MAX_UI_SCALE, MIN_UI_SCALE};
+use super::screenshot::ExportCaption;
+use super::shared_canvas_functionality::LabelStyle;
+use super::star_rendering::StarRendering;
 use super::table_view::col_data::TableDataType;
+use super::table_view::widget::{OriginFilter, SortDirection};
+use super::touch::TouchGesture;
+use super::view_configuration::ViewConfiguration;
 use super::Gui;
 use super::{
     dialog::new_system::NewSystemDialog, surface_view::widget::SurfaceViewUpdate,
     top_view::widget::TopViewUpdate,
 };
 use crate::error::ElenathError;
-use crate::model::star::StarDataType;
+use crate::model::appearance_override::AppearanceOverride;
+use crate::model::body_id::BodyId;
+use crate::model::body_notes::BodyNotes;
+use crate::model::calendar::Calendar;
+use crate::model::celestial_system::co_orbitals::CoOrbitalPlacement;
+use crate::model::celestial_system::command_log::{
+    read_command_log, write_command_log, SystemCommand,
+};
+use crate::model::celestial_system::constellations::ConstellationKey;
+use crate::model::celestial_system::epoch::{
+    clamp_time_since_epoch, MAX_TIME_SINCE_EPOCH_IN_YEARS,
+};
+use crate::model::celestial_system::flyby::RogueFlyby;
+use crate::model::celestial_system::metadata::SystemMetadata;
+use crate::model::celestial_system::naming::check_body_name;
+use crate::model::celestial_system::part::PartOfCelestialSystem;
+use crate::model::celestial_system::planets::SystemBody;
+use crate::model::gaia_export::{read_gaia_export, GaiaRelease};
+use crate::model::galactic_motion::GalacticMotion;
+use crate::model::migration::MigrationSchedule;
+use crate::model::observer_location::ObserverLocation;
+use crate::model::space_engine::{read_space_engine_script, SpaceEngineSystem};
+use crate::model::star::{StarDataType, StarMergeMode};
+use crate::model::star_catalog_filter::StarCatalogFilter;
+use crate::model::star_formation::StarFormingRegion;
+use crate::model::star_generation::{time_seed, StarGenerationParameters};
+use crate::model::star_jitter::StarJitter;
+use crate::model::star_naming::NamingStyle;
+use crate::model::travel::TravelEndpoint;
+use crate::model::universe::Universe;
+use crate::window_layout::PlanetariumMonitor;
 use crate::{file_dialog, model::celestial_system::CelestialSystem};
 use astro_utils::planets::derived_data::DerivedPlanetData;
 use astro_utils::planets::planet_data::PlanetData;
+use astro_utils::stars::constellation::Constellation;
 use astro_utils::stars::data::StarData;
-use uom::si::f64::{Length, Time};
+use iced::time::Instant;
+use iced::widget::scrollable::Viewport;
+use iced::{window, Point, Rectangle, Size};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use uom::si::{
+    angle::degree,
+    f64::{Angle, Length, Mass, Time, Velocity},
+};
 
 #[derive(Debug, Clone)]
 pub(crate) enum GuiMessage {
     UpdateSurfaceView(SurfaceViewUpdate),
     UpdateTopView(TopViewUpdate),
+    TouchGesture(TouchGesture, Size),
+    SkyRegionSelected(Rectangle, Size),
+    AnimationTick(Instant),
+    DismissToast(u64),
     NewSystem,
+    AddSystem,
+    SystemSelected(usize),
     SaveToFile,
     SaveToNewFile,
     OpenFile,
+    #[cfg(feature = "remote_control")]
+    OpenFileAt(PathBuf),
+    ReloadFile,
+    OverwriteFile,
     ModeSelected(GuiViewMode),
-    NewPlanet(PlanetData),
-    PlanetEdited(usize, PlanetData),
-    NewStar(StarData),
-    StarEdited(Option<usize>, StarData),
+    SetViewsLinked(bool),
+    SetLocked(bool),
+    SetStarRendering(StarRendering),
+    SaveStarRendering(StarRendering),
+    PickStarRenderingReference,
+    ExportViewConfiguration,
+    ImportViewConfiguration,
+    ExportCommandLog,
+    ReplayCommandLog,
+    NewPlanet(PlanetData, BodyNotes, Option<Time>, Angle),
+    PlanetEdited(BodyId, PlanetData, BodyNotes, Option<Time>, Angle),
+    NewStar(
+        StarData,
+        BodyNotes,
+        Option<Velocity>,
+        Option<AppearanceOverride>,
+    ),
+    StarEdited(
+        Option<BodyId>,
+        StarData,
+        BodyNotes,
+        Option<Velocity>,
+        Option<AppearanceOverride>,
+        Option<usize>,
+    ),
+    CentralBodyEdited(StarData, BodyNotes, SystemMetadata),
+    MetadataEdited(SystemMetadata),
     UpdateTime(Time),
+    AdvanceLocalDay,
+    AdvanceToLocalMidnight,
+    RedefineEpoch(String),
+    ConstellationsComputed(ConstellationKey, Vec<Constellation>),
+    DuplicateStarsFound(ConstellationKey, Vec<(usize, usize)>),
     UpdateTimeStep(Time),
-    PlanetSelected(String),
+    PlanetSelected(Option<BodyId>),
+    TargetSelected(Option<BodyId>),
+    GoToSurface(Option<BodyId>),
+    ZoomToFit,
+    ObserverLocationSelected(ObserverLocation),
+    SaveObserverLocation,
+    ExportSkyCalendar,
     SetDisplayNames(bool),
     SetDisplayConstellations(bool),
+    SetGalacticMotion(GalacticMotion),
+    SetStarFormingRegions(Vec<StarFormingRegion>),
+    SetMigrationSchedule(MigrationSchedule),
+    SimulateFlyby(RogueFlyby, Time),
+    SetBinaryPlanet(BodyId, BodyId, Length),
+    SeparateBinaryPlanet(BodyId),
+    AddCoOrbitalBody(BodyId, CoOrbitalPlacement, String, Mass, Length),
+    SetCalendar(Option<Calendar>),
+    SetLabelStyle(LabelStyle),
+    SetCanvasBackground(BackgroundStyle),
+    ExportScreenshot(ExportCaption),
+    StartParallaxWiggle,
+    #[cfg(feature = "remote_control")]
+    ExportScreenshotTo(PathBuf),
+    PickBackgroundImage,
+    SetUiScale(f32),
+    WindowEvent(window::Id, window::Event),
+    WindowResized(Size, bool),
+    WindowMoved(Point, Option<Size>),
+    CheckWindowMonitor(Option<Size>),
+    SetPlanetarium(bool),
+    SetPlanetariumMonitor(PlanetariumMonitor),
+    DetachTable,
+    AttachTable,
     TableDataTypeSelected(TableDataType),
+    TablePageSelected(usize),
+    TableScrolled(Viewport),
+    SetCatalogColumns(bool),
+    SetOriginFilter(OriginFilter),
+    TableFilterChanged(String),
+    TableSortChanged(usize, SortDirection),
     RandomizePlanets,
     LoadRealPlanets,
-    RandomizeStars(bool, Length),
-    LoadStars(StarDataType),
+    RandomizeStars(bool, StarGenerationParameters, StarMergeMode),
+    LoadStars(StarDataType, StarMergeMode, StarCatalogFilter),
+    LoadGaiaExport(GaiaRelease, bool, StarMergeMode, StarCatalogFilter),
+    ImportSpaceEngineScript,
+    ApplySpaceEngineImport(SpaceEngineSystem, bool),
+    RemoveDuplicateStars,
+    TrimFaintStars(f64),
+    NameStars,
+    JitterStars(StarJitter),
+    ExportStars(Vec<BodyId>),
+    ExportStarCatalog,
+    ExportTable,
     OpenDialog(DialogType),
     DialogUpdate(DialogUpdate),
     DialogSubmit,
@@ -50,6 +203,60 @@ pub(crate) enum GuiMessage {
     ErrorEncountered(ElenathError),
 }
 
+impl GuiMessage {
+    /*
+     * Whether handling the message changes data that is written to file.
+     */
+    fn modifies_system(&self) -> bool {
+        matches!(
+            self,
+            GuiMessage::AddSystem
+                | GuiMessage::NewPlanet(..)
+                | GuiMessage::PlanetEdited(..)
+                | GuiMessage::NewStar(..)
+                | GuiMessage::StarEdited(..)
+                | GuiMessage::CentralBodyEdited(..)
+                | GuiMessage::MetadataEdited(..)
+                | GuiMessage::RedefineEpoch(..)
+                | GuiMessage::SaveObserverLocation
+                | GuiMessage::SetGalacticMotion(..)
+                | GuiMessage::SetStarFormingRegions(..)
+                | GuiMessage::SetMigrationSchedule(..)
+                | GuiMessage::SetBinaryPlanet(..)
+                | GuiMessage::SeparateBinaryPlanet(..)
+                | GuiMessage::AddCoOrbitalBody(..)
+                | GuiMessage::SetCalendar(..)
+                | GuiMessage::RandomizePlanets
+                | GuiMessage::LoadRealPlanets
+                | GuiMessage::RandomizeStars(..)
+                | GuiMessage::LoadStars(..)
+                | GuiMessage::LoadGaiaExport(..)
+                | GuiMessage::ApplySpaceEngineImport(..)
+                | GuiMessage::RemoveDuplicateStars
+                | GuiMessage::TrimFaintStars(..)
+                | GuiMessage::NameStars
+                | GuiMessage::JitterStars(..)
+        )
+    }
+
+    /*
+     * Whether the message is refused while the system is locked for presentation.
+     * Besides the changes themselves, this covers everything that leads up to them, or discards the system altogether.
+     */
+    fn is_editing(&self) -> bool {
+        match self {
+            GuiMessage::NewSystem
+            | GuiMessage::ImportViewConfiguration
+            | GuiMessage::ImportSpaceEngineScript
+            | GuiMessage::ReplayCommandLog => true,
+            #[cfg(feature = "remote_control")]
+            GuiMessage::OpenFileAt(_) => true,
+            GuiMessage::OpenDialog(dialog_type) => dialog_type.edits_system(),
+            message => message.modifies_system(),
+        }
+    }
+}
+
 impl Gui {
     fn open_dialog(&mut self, dialog_type: DialogType) -> Result<(), ElenathError> {
         match dialog_type {
@@ -59,40 +266,112 @@ impl Gui {
             DialogType::NewPlanet => {
                 let celestial_system = &self.get_system()?;
                 let central_body = celestial_system.get_central_body_data().clone();
-                self.dialog = Some(Box::new(PlanetDialog::new(central_body)?));
+                let resonance_partners = celestial_system
+                    .get_planets_data()
+                    .into_iter()
+                    .cloned()
+                    .collect();
+                self.dialog = Some(Box::new(PlanetDialog::new(
+                    central_body,
+                    resonance_partners,
+                )?));
             }
-            DialogType::EditPlanet(index) => {
+            DialogType::EditPlanet(id) => {
                 let celestial_system = &self.get_system()?;
                 let central_body = celestial_system.get_central_body_data();
+                let index = celestial_system
+                    .get_planet_index(id)
+                    .ok_or(ElenathError::BodyNotFound)?;
                 let planet = celestial_system
                     .get_planet_data(index)
                     .ok_or(ElenathError::BodyNotFound)?;
-                let previous_planet = celestial_system.get_planet_data(index - 1);
+                let resonance_partners = celestial_system
+                    .get_planets_data()
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i != index)
+                    .map(|(_, p)| p.clone())
+                    .collect();
+                let previous_planet = index
+                    .checked_sub(1)
+                    .and_then(|i| celestial_system.get_planet_data(i));
                 let previous_planet = match previous_planet {
                     Some(p) => Some(DerivedPlanetData::new(p, central_body, None)?),
                     None => None,
                 };
-                self.dialog = Some(Box::new(PlanetDialog::edit(
-                    planet.clone(),
-                    index,
-                    previous_planet,
-                    central_body.clone(),
-                )?));
+                let notes = celestial_system.get_body_notes(Some(id));
+                let precession_period = celestial_system.get_precession_period(id);
+                let rotation_angle = celestial_system.get_rotation_angle_at_epoch(Some(id));
+                self.dialog = Some(Box::new(
+                    PlanetDialog::edit(
+                        planet.clone(),
+                        id,
+                        previous_planet,
+                        central_body.clone(),
+                        resonance_partners,
+                        notes,
+                        precession_period,
+                    )?
+                    .with_rotation_angle(rotation_angle),
+                ));
             }
             DialogType::NewStar => {
                 let system = self.get_system()?;
-                self.dialog = Some(Box::new(StarDialog::new(system.get_time_since_epoch())));
+                self.dialog = Some(Box::new(
+                    StarDialog::new(system.get_time_since_epoch())
+                        .with_taken_names(system.star_names_except(None)),
+                ));
             }
-            DialogType::EditStar(index) => {
+            DialogType::EditStar(None) | DialogType::EditCentralBody => {
                 let system = &self.get_system()?;
+                let central_body = system.get_central_body_data();
+                self.dialog = Some(Box::new(
+                    StarDialog::central_body(
+                        central_body.clone(),
+                        system.get_time_since_epoch(),
+                        system.get_body_notes(None),
+                        system.get_metadata().clone(),
+                    )
+                    .with_taken_names(system.star_names_except(None)),
+                ));
+            }
+            DialogType::EditMetadata => {
+                let metadata = self.get_system()?.get_metadata().clone();
+                self.dialog = Some(Box::new(MetadataDialog::new(metadata)));
+            }
+            DialogType::EditStar(id) => {
+                let system = self.get_system_const()?;
                 let star = system
-                    .get_star_data(index)
+                    .get_star_data_by_id(id)
                     .ok_or(ElenathError::BodyNotFound)?;
-                self.dialog = Some(Box::new(StarDialog::edit(
-                    star.clone(),
-                    index,
-                    system.get_time_since_epoch(),
-                )));
+                let active_system = self.universe.get_active_system_index();
+                let other_systems = self
+                    .universe
+                    .get_system_references(system)
+                    .into_iter()
+                    .filter(|system| system.index != active_system)
+                    .collect();
+                let linked_system = id.and_then(|id| self.universe.get_linked_system(id));
+                let distant_star = id
+                    .and_then(|id| system.get_star_index(id))
+                    .and_then(|index| system.get_distant_stars().get(index));
+                let radial_velocity = distant_star.and_then(|star| star.get_radial_velocity());
+                let appearance_override =
+                    distant_star.and_then(|star| star.get_appearance_override().cloned());
+                let provenance = distant_star.map(|star| star.get_provenance().clone());
+                self.dialog = Some(Box::new(
+                    StarDialog::edit(
+                        star.clone(),
+                        id,
+                        system.get_time_since_epoch(),
+                        system.get_body_notes(id),
+                    )
+                    .with_radial_velocity(radial_velocity)
+                    .with_appearance_override(appearance_override)
+                    .with_provenance(provenance)
+                    .with_taken_names(system.star_names_except(id))
+                    .with_system_links(linked_system, other_systems),
+                ));
             }
             DialogType::RandomizePlanets => {
                 self.dialog = Some(Box::new(RandomizePlanetsDialog::new()));
@@ -101,10 +380,222 @@ impl Gui {
                 self.dialog = Some(Box::new(LoadRealPlanetsDialog::new()));
             }
             DialogType::RandomizeStars => {
-                self.dialog = Some(Box::new(RandomizeStarsDialog::new()));
+                let existing_star_count = self.get_system()?.get_distant_star_count();
+                self.dialog = Some(Box::new(RandomizeStarsDialog::new(existing_star_count)));
+            }
+            DialogType::StarStatistics => {
+                const BIN_WIDTH: f64 = 0.5;
+                let system = self.get_system_const()?;
+                let histogram = system.get_magnitude_histogram(BIN_WIDTH);
+                let memory = system.approximate_star_memory_in_bytes();
+                self.dialog = Some(Box::new(StarStatisticsDialog::new(histogram, memory)));
+            }
+            DialogType::DuplicateStars => {
+                // The search itself is started by the update, since it runs in the background.
+                self.duplicate_stars = None;
+                self.dialog = Some(Box::new(DuplicateStarsDialog::new()));
+            }
+            DialogType::ExportScreenshot => {
+                self.dialog = Some(Box::new(ExportScreenshotDialog::new(self.export_caption)));
+            }
+            DialogType::StarRendering => {
+                self.dialog = Some(Box::new(StarRenderingDialog::new(
+                    self.surface_view_state.star_rendering,
+                )));
+            }
+            DialogType::StarJitter => {
+                self.dialog = Some(Box::new(StarJitterDialog::new()));
+            }
+            DialogType::TransitLightCurve(id) => {
+                const SAMPLE_COUNT: usize = 400;
+                let system = self.get_system_const()?;
+                let planet_name = system
+                    .get_planet(id)
+                    .ok_or(ElenathError::BodyNotFound)?
+                    .get_data()
+                    .get_name()
+                    .clone();
+                let light_curve = system
+                    .get_transit_light_curve(id, SAMPLE_COUNT)
+                    .ok_or(ElenathError::Generic(format!(
+                        "Cannot compute a transit of {}: the central body needs a radius, and the planet needs to orbit outside of it.",
+                        planet_name
+                    )))?;
+                self.dialog = Some(Box::new(TransitDialog::new(planet_name, light_curve)));
+            }
+            DialogType::RadialVelocity => {
+                const SAMPLE_COUNT: usize = 400;
+                let curve = self
+                    .get_system_const()?
+                    .get_radial_velocity_curve(SAMPLE_COUNT)
+                    .ok_or(ElenathError::Generic(
+                        "Cannot compute a radial velocity curve: the central body needs a mass, and the system needs planets.".to_string(),
+                    ))?;
+                self.dialog = Some(Box::new(RadialVelocityDialog::new(curve)));
+            }
+            DialogType::EquationOfTime => {
+                const SAMPLE_COUNT: usize = 400;
+                let id = self
+                    .selected_planet
+                    .ok_or(ElenathError::Generic("Please select a planet.".to_string()))?;
+                let system = self.get_system_const()?;
+                let planet = system.get_planet(id).ok_or(ElenathError::BodyNotFound)?;
+                let equation_of_time = system
+                    .get_equation_of_time(&planet, SAMPLE_COUNT)
+                    .ok_or(ElenathError::Generic(format!(
+                        "Cannot compute the equation of time of {}: the planet needs an orbit and a finite rotation period.",
+                        planet.get_data().get_name()
+                    )))?;
+                self.dialog = Some(Box::new(EquationOfTimeDialog::new(
+                    planet.get_data().get_name().clone(),
+                    equation_of_time,
+                )));
+            }
+            DialogType::Habitability => {
+                let reports = self.get_system_const()?.get_habitability_reports();
+                self.dialog = Some(Box::new(HabitabilityDialog::new(reports)));
+            }
+            DialogType::Zodiac => {
+                const SAMPLE_COUNT: usize = 720;
+                let planet = self
+                    .get_selected_planet()
+                    .ok_or(ElenathError::Generic("Please select a planet.".to_string()))?;
+                let system = self.get_system_const()?;
+                let planet_name = planet.get_data().get_name().clone();
+                let passages = system.get_zodiac(&planet, SAMPLE_COUNT).ok_or(
+                    ElenathError::Generic(format!(
+                        "Cannot compute the zodiac of {}: the planet needs an orbit, and the sky needs constellations.",
+                        planet_name
+                    )),
+                )?;
+                self.dialog = Some(Box::new(ZodiacDialog::new(
+                    planet_name,
+                    system.get_central_body_data().get_name().clone(),
+                    passages,
+                )));
+            }
+            DialogType::Flyby => {
+                let planet = self
+                    .get_selected_planet()
+                    .ok_or(ElenathError::Generic("Please select a planet.".to_string()))?;
+                let time_since_epoch = self.get_system_const()?.get_time_since_epoch();
+                self.dialog = Some(Box::new(FlybyDialog::new(
+                    planet.get_data().get_name().clone(),
+                    time_since_epoch,
+                )));
+            }
+            DialogType::BinaryPlanets => {
+                let system = self.get_system_const()?;
+                let choice = |id: BodyId| {
+                    Some(PlanetChoice {
+                        id,
+                        name: system.get_planet_data_by_id(id)?.get_name().clone(),
+                    })
+                };
+                let planets = (0..system.get_planets_data().len())
+                    .filter_map(|index| choice(system.get_planet_id(index)?))
+                    .collect();
+                let pairs = system
+                    .get_binary_planets()
+                    .iter()
+                    .filter_map(|pair| {
+                        Some(BinaryPlanetPair {
+                            primary: choice(pair.primary)?,
+                            secondary: choice(pair.secondary)?,
+                            separation: pair.separation,
+                        })
+                    })
+                    .collect();
+                self.dialog = Some(Box::new(BinaryPlanetsDialog::new(planets, pairs)));
+            }
+            DialogType::CoOrbital => {
+                let system = self.get_system_const()?;
+                let planets: Vec<PlanetChoice> = (0..system.get_planets_data().len())
+                    .filter_map(|index| {
+                        Some(PlanetChoice {
+                            id: system.get_planet_id(index)?,
+                            name: system.get_planet_data(index)?.get_name().clone(),
+                        })
+                    })
+                    .collect();
+                let selected = self
+                    .get_selected_planet()
+                    .and_then(|planet| planet.get_id());
+                let host = planets
+                    .iter()
+                    .find(|planet| Some(planet.id) == selected)
+                    .cloned();
+                self.dialog = Some(Box::new(CoOrbitalDialog::new(planets, host)));
+            }
+            DialogType::TravelTime => {
+                let system = self.get_system_const()?;
+                let mut endpoints = system.get_travel_endpoints();
+                let systems = self.universe.get_system_references(system);
+                for star in system.get_stars() {
+                    let (Some(id), Some(data)) = (star.get_id(), star.get_data()) else {
+                        continue;
+                    };
+                    let linked_system = self
+                        .universe
+                        .get_linked_system(id)
+                        .and_then(|index| systems.get(index));
+                    if let Some(linked_system) = linked_system {
+                        endpoints.push(TravelEndpoint {
+                            name: format!("{} (system)", linked_system),
+                            position: data.get_pos(system.get_time_since_epoch()),
+                        });
+                    }
+                }
+                self.dialog = Some(Box::new(TravelTimeDialog::new(endpoints)));
+            }
+            DialogType::StarFormingRegions => {
+                let system = self.get_system_const()?;
+                self.dialog = Some(Box::new(StarFormingRegionsDialog::new(
+                    system.get_star_forming_regions().to_vec(),
+                    system.get_time_since_epoch(),
+                )));
+            }
+            DialogType::Migration => {
+                let planet = self.get_selected_planet().ok_or(ElenathError::Generic(
+                    "Please select the planet that migrates.".to_string(),
+                ))?;
+                let system = self.get_system_const()?;
+                let id = planet.get_id().ok_or(ElenathError::BodyNotFound)?;
+                let semi_major_axis = planet
+                    .get_data()
+                    .get_orbital_parameters()
+                    .get_semi_major_axis();
+                self.dialog = Some(Box::new(MigrationDialog::new(
+                    planet.get_data().get_name().clone(),
+                    id,
+                    system.get_migration_schedule(id),
+                    semi_major_axis,
+                )));
+            }
+            DialogType::RedefineEpoch => {
+                let system = self.get_system_const()?;
+                self.dialog = Some(Box::new(EpochDialog::new(
+                    system.get_time_since_epoch(),
+                    &system.get_metadata().epoch_description,
+                )));
+            }
+            DialogType::Calendar => {
+                let planet_days = self.get_selected_planet().and_then(|planet| {
+                    let derived = planet.get_derived_data()?;
+                    Some(PlanetDays {
+                        day: self.get_local_day()?,
+                        year: derived.get_orbital_period(),
+                    })
+                });
+                let system = self.get_system_const()?;
+                self.dialog = Some(Box::new(CalendarDialog::new(
+                    system.get_calendar(),
+                    planet_days,
+                )));
             }
             DialogType::LoadGaiaData => {
-                self.dialog = Some(Box::new(LoadRealStarsDialog::new()));
+                let existing_star_count = self.get_system()?.get_distant_star_count();
+                self.dialog = Some(Box::new(LoadRealStarsDialog::new(existing_star_count)));
             }
         }
         Ok(())
@@ -116,64 +607,426 @@ impl Gui {
                 return Err(e);
             }
         }
+        if self.locked && message.is_editing() {
+            self.status_message = Some("Locked for presentation".to_string());
+            self.dialog = None;
+            return Ok(());
+        }
+        if message.modifies_system() {
+            self.unsaved_changes = true;
+            self.status_message = None;
+        }
+        let mut command = self.command_for(&message);
         match message {
             GuiMessage::UpdateSurfaceView(message) => {
+                let is_track_body_message = matches!(message, SurfaceViewUpdate::TrackBody(_));
+                if let SurfaceViewUpdate::TrackBody(body) = &message {
+                    // Tracking another body replaces the target.
+                    if *body != self.target_planet.map(SystemBody::Planet) {
+                        self.target_planet = None;
+                    }
+                }
                 self.surface_view_state.update(message);
+                if is_track_body_message {
+                    self.update_tracked_body(true)?;
+                }
             }
             GuiMessage::UpdateTopView(message) => {
                 self.top_view_state.update(message);
             }
-            GuiMessage::NewPlanet(planet) => {
-                self.get_system()?.add_planet_data(planet);
+            GuiMessage::TouchGesture(gesture, canvas_size) => match self.mode {
+                GuiViewMode::Surface => self
+                    .surface_view_state
+                    .apply_touch_gesture(gesture, canvas_size),
+                GuiViewMode::Top => self.top_view_state.apply_touch_gesture(gesture),
+                GuiViewMode::Table => {}
+            },
+            GuiMessage::SkyRegionSelected(region, canvas_size) => {
+                let planet = self
+                    .get_selected_planet()
+                    .ok_or(ElenathError::BodyNotFound)?;
+                let statistics = self.surface_view_state.sky_region_statistics(
+                    region,
+                    canvas_size,
+                    &planet,
+                    self.get_system_const()?,
+                );
+                self.dialog = Some(Box::new(SkyRegionDialog::new(statistics)));
+            }
+            GuiMessage::StartParallaxWiggle => {
+                let system = self
+                    .celestial_system
+                    .as_ref()
+                    .ok_or(ElenathError::NoCelestialSystem)?;
+                self.surface_view_state.start_parallax_wiggle(system);
+            }
+            GuiMessage::AnimationTick(now) => {
+                self.surface_view_state.animate(now);
+                self.top_view_state.animate(now);
+                self.toasts.expire(now);
+            }
+            GuiMessage::DismissToast(id) => {
+                self.toasts.dismiss(id);
+            }
+            GuiMessage::NewPlanet(planet, notes, precession_period, rotation_angle) => {
+                let system = self.get_system()?;
+                let id = system.add_planet_data(planet)?;
+                system.set_body_notes(Some(id), notes);
+                system.set_precession_period(id, precession_period);
+                system.set_rotation_angle_at_epoch(id, rotation_angle);
                 self.dialog = None;
+                self.show_stability_warnings()?;
             }
-            GuiMessage::PlanetEdited(index, planet_data) => {
-                self.get_system()?.overwrite_planet_data(index, planet_data);
+            GuiMessage::PlanetEdited(id, planet_data, notes, precession_period, rotation_angle) => {
+                let system = self.get_system()?;
+                let index = system
+                    .get_planet_index(id)
+                    .ok_or(ElenathError::BodyNotFound)?;
+                let old_name = system
+                    .get_planet_data(index)
+                    .ok_or(ElenathError::BodyNotFound)?
+                    .get_name()
+                    .clone();
+                if planet_data.get_name() != &old_name {
+                    check_body_name(
+                        planet_data.get_name(),
+                        &system.planet_names_except(Some(id)),
+                    )?;
+                }
+                system.set_body_notes(Some(id), notes);
+                system.overwrite_planet_data(index, planet_data);
+                system.set_precession_period(id, precession_period);
+                system.set_rotation_angle_at_epoch(id, rotation_angle);
                 self.dialog = None;
+                self.show_stability_warnings()?;
             }
-            GuiMessage::NewStar(star) => {
-                self.get_system()?.add_stars_from_data(vec![star]);
+            GuiMessage::NewStar(star, notes, radial_velocity, appearance_override) => {
+                let system = self.get_system()?;
+                let index = system.add_star_from_data(star)?;
+                let id = system.get_distant_stars()[index].get_id();
+                system.set_body_notes(id, notes);
+                system.set_star_radial_velocity(index, radial_velocity);
+                system.set_star_appearance_override(index, appearance_override);
                 self.dialog = None;
             }
-            GuiMessage::StarEdited(index, star_data) => {
-                self.get_system()?.overwrite_star_data(index, star_data);
+            GuiMessage::StarEdited(
+                id,
+                star_data,
+                notes,
+                radial_velocity,
+                appearance_override,
+                linked_system,
+            ) => {
+                if let Some(id) = id {
+                    self.universe.set_linked_system(id, linked_system);
+                }
+                let system = self.get_system()?;
+                let index = match id {
+                    Some(id) => Some(
+                        system
+                            .get_star_index(id)
+                            .ok_or(ElenathError::BodyNotFound)?,
+                    ),
+                    None => None,
+                };
+                let old_name = system
+                    .get_star_data(index)
+                    .ok_or(ElenathError::BodyNotFound)?
+                    .get_name()
+                    .clone();
+                if star_data.get_name() != &old_name {
+                    check_body_name(star_data.get_name(), &system.star_names_except(id))?;
+                }
+                system.set_body_notes(id, notes);
+                if let Some(index) = system.overwrite_star_data(index, star_data) {
+                    system.set_star_radial_velocity(index, radial_velocity);
+                    system.set_star_appearance_override(index, appearance_override);
+                }
+                self.dialog = None;
+            }
+            GuiMessage::CentralBodyEdited(star_data, notes, metadata) => {
+                let system = self.get_system()?;
+                let old_name = system.get_central_body_data().get_name().clone();
+                if star_data.get_name() != &old_name {
+                    check_body_name(star_data.get_name(), &system.star_names_except(None))?;
+                }
+                system.set_body_notes(None, notes);
+                system.overwrite_star_data(None, star_data);
+                system.set_metadata(metadata);
+                self.dialog = None;
+            }
+            GuiMessage::MetadataEdited(metadata) => {
+                self.get_system()?.set_metadata(metadata);
                 self.dialog = None;
             }
             GuiMessage::NewSystem => {
+                self.universe = Universe::new();
                 self.celestial_system = Some(CelestialSystem::empty());
+                self.view_settings.clear();
+                self.unsaved_changes = false;
+                self.status_message = None;
+                self.dialog = None;
+            }
+            GuiMessage::AddSystem => {
+                let index = self.universe.add_system(CelestialSystem::empty());
+                self.handle_message(GuiMessage::SystemSelected(index))?;
+            }
+            GuiMessage::SystemSelected(index) => {
+                let active = self
+                    .celestial_system
+                    .take()
+                    .ok_or(ElenathError::NoCelestialSystem)?;
+                self.celestial_system = Some(self.universe.switch_system(index, active));
+                self.selected_planet = None;
+                self.target_planet = None;
+                self.view_settings.clear();
                 self.dialog = None;
             }
             GuiMessage::SaveToFile => {
                 if self.opened_file.is_none() {
-                    self.opened_file = file_dialog::new();
-                }
-                if let Some(path) = &self.opened_file {
-                    self.get_system_const()?.write_to_file(path.clone())?;
+                    self.opened_file = file_dialog::new(self.project_folder());
+                    self.write_opened_file()?;
+                } else if self.opened_file_changed_on_disk() {
+                    self.dialog = Some(Box::new(FileConflictDialog::new(self.unsaved_changes)));
+                } else {
+                    self.write_opened_file()?;
                 }
             }
             GuiMessage::SaveToNewFile => {
-                self.opened_file = file_dialog::new();
-                if let Some(path) = &self.opened_file {
-                    self.get_system_const()?.write_to_file(path.clone())?;
-                }
+                self.opened_file = file_dialog::new(self.project_folder());
+                self.write_opened_file()?;
             }
             GuiMessage::OpenFile => {
-                self.opened_file = file_dialog::open();
-                if let Some(path) = &self.opened_file {
-                    self.celestial_system = Some(CelestialSystem::read_from_file(path.clone())?);
+                self.opened_file = file_dialog::open(self.project_folder());
+                self.read_opened_file()?;
+            }
+            #[cfg(feature = "remote_control")]
+            GuiMessage::OpenFileAt(path) => {
+                if self.unsaved_changes {
+                    return Err(ElenathError::Generic(
+                        "A remote request to open a file was refused, because there are unsaved changes."
+                            .to_string(),
+                    ));
                 }
+                let previous = self.opened_file.replace(path);
+                if let Err(e) = self.read_opened_file() {
+                    self.opened_file = previous;
+                    return Err(e);
+                }
+            }
+            GuiMessage::ReloadFile => {
+                self.read_opened_file()?;
+                self.dialog = None;
+            }
+            GuiMessage::OverwriteFile => {
+                self.write_opened_file()?;
+                self.dialog = None;
             }
             GuiMessage::ModeSelected(mode) => {
-                self.mode = mode;
+                self.switch_mode(mode)?;
+            }
+            GuiMessage::SetViewsLinked(linked) => {
+                self.set_views_linked(linked);
+            }
+            GuiMessage::SetLocked(locked) => {
+                self.locked = locked;
+                self.status_message = None;
+            }
+            GuiMessage::SetStarRendering(rendering) => {
+                self.set_star_rendering(rendering);
+            }
+            GuiMessage::SaveStarRendering(rendering) => {
+                self.save_star_rendering(rendering);
+            }
+            GuiMessage::PickStarRenderingReference => {
+                if let Some(path) = file_dialog::open_background_image(self.project_folder()) {
+                    let image = BackgroundImage::read(&path)?;
+                    if let Some(dialog) = &mut self.dialog {
+                        dialog.update(DialogUpdate::StarRenderingUpdated(
+                            StarRenderingDialogEvent::ReferenceImage(Arc::new(image)),
+                        ));
+                    }
+                }
+            }
+            GuiMessage::ExportViewConfiguration => {
+                if let Some(path) = file_dialog::save_view_configuration(self.project_folder()) {
+                    self.view_configuration().write_to_file(path)?;
+                    self.status_message = Some("Saved view configuration".to_string());
+                }
+            }
+            GuiMessage::ImportViewConfiguration => {
+                if let Some(path) = file_dialog::open_view_configuration(self.project_folder()) {
+                    let configuration = ViewConfiguration::read_from_file(path)?;
+                    let added_bookmarks = self.apply_view_configuration(configuration)?;
+                    self.status_message = Some(format!(
+                        "Applied view configuration with {} bookmarks",
+                        group_digits(added_bookmarks)
+                    ));
+                }
+            }
+            GuiMessage::ExportCommandLog => {
+                if let Some(path) = file_dialog::save_command_log(self.project_folder()) {
+                    let commands = self.get_system_const()?.get_command_log();
+                    write_command_log(commands, path)?;
+                    self.status_message = Some(format!(
+                        "Exported {} commands",
+                        group_digits(commands.len())
+                    ));
+                }
+            }
+            GuiMessage::ReplayCommandLog => {
+                if let Some(path) = file_dialog::open_command_log(self.project_folder()) {
+                    let commands = read_command_log(path)?;
+                    let replayed = self.replay_command_log(commands)?;
+                    self.status_message =
+                        Some(format!("Replayed {} commands", group_digits(replayed)));
+                }
             }
             GuiMessage::UpdateTime(time) => {
-                self.get_system()?.set_time_since_epoch(time);
+                self.set_time(time)?;
+            }
+            GuiMessage::AdvanceLocalDay => {
+                if let Some(local_day) = self.get_local_day() {
+                    let time = self.get_system_const()?.get_time_since_epoch() + local_day;
+                    self.set_time(time)?;
+                }
+            }
+            GuiMessage::AdvanceToLocalMidnight => {
+                if let Some(time) = self.get_next_local_midnight() {
+                    self.set_time(time)?;
+                }
+            }
+            GuiMessage::RedefineEpoch(epoch_description) => {
+                let system = self.get_system()?;
+                system.redefine_epoch();
+                let metadata = SystemMetadata {
+                    epoch_description,
+                    ..system.get_metadata().clone()
+                };
+                system.set_metadata(metadata);
+                self.dialog = None;
+            }
+            GuiMessage::DuplicateStarsFound(key, duplicates) => {
+                let system = self.get_system_const()?;
+                let descriptions = if key == system.constellation_key() {
+                    let stars = system.get_distant_star_appearances();
+                    let star_name = |index: usize| {
+                        let name = stars[index].get_name();
+                        if name.is_empty() {
+                            format!("Star #{}", index)
+                        } else {
+                            name.to_string()
+                        }
+                    };
+                    let descriptions = duplicates
+                        .iter()
+                        .map(|&(redundant, original)| {
+                            format!(
+                                "{} duplicates {}",
+                                star_name(redundant),
+                                star_name(original)
+                            )
+                        })
+                        .collect();
+                    self.duplicate_stars = Some((key, duplicates));
+                    Some(descriptions)
+                } else {
+                    None
+                };
+                if let Some(dialog) = &mut self.dialog {
+                    dialog.update(DialogUpdate::DuplicateStarsFound(descriptions));
+                }
+            }
+            GuiMessage::ConstellationsComputed(key, constellations) => {
+                if self.pending_constellations == Some(key) {
+                    self.pending_constellations = None;
+                }
+                self.get_system()?.apply_constellations(constellations, key);
             }
             GuiMessage::UpdateTimeStep(time_step) => {
-                self.time_step = time_step;
+                self.time_step = clamp_time_since_epoch(time_step);
+                if self.time_step != time_step {
+                    self.status_message = Some(format!(
+                        "The time step is limited to {} years",
+                        MAX_TIME_SINCE_EPOCH_IN_YEARS
+                    ));
+                }
             }
-            GuiMessage::PlanetSelected(name) => {
-                self.selected_planet_name = name;
+            GuiMessage::PlanetSelected(id) => {
+                self.selected_planet = id;
+                if id.is_some() && id == self.target_planet {
+                    self.handle_message(GuiMessage::TargetSelected(None))?;
+                }
+                self.update_tracked_body(true)?;
+            }
+            GuiMessage::TargetSelected(id) => {
+                self.target_planet = id.filter(|id| Some(*id) != self.selected_planet);
+                self.surface_view_state.update(SurfaceViewUpdate::TrackBody(
+                    self.target_planet.map(SystemBody::Planet),
+                ));
+                self.update_tracked_body(true)?;
+            }
+            GuiMessage::GoToSurface(id) => {
+                self.handle_message(GuiMessage::PlanetSelected(id))?;
+                self.handle_message(GuiMessage::ModeSelected(GuiViewMode::Surface))?;
+                self.point_surface_view_at_central_body()?;
+            }
+            GuiMessage::ZoomToFit => {
+                let outermost_extent = self.get_system_const()?.get_outermost_orbit_extent();
+                let length_per_pixel = self
+                    .top_view_state
+                    .length_per_pixel_to_fit(outermost_extent);
+                self.top_view_state
+                    .update(TopViewUpdate::LengthScale(length_per_pixel));
+            }
+            GuiMessage::ObserverLocationSelected(location) => {
+                self.surface_view_state
+                    .update(SurfaceViewUpdate::SurfaceLongitude(
+                        location.get_longitude(),
+                    ));
+                self.surface_view_state
+                    .update(SurfaceViewUpdate::SurfaceLatitude(location.get_latitude()));
+                self.surface_view_state
+                    .update(SurfaceViewUpdate::ObserverLocationName(
+                        location.get_name().clone(),
+                    ));
+            }
+            GuiMessage::SaveObserverLocation => {
+                let planet = self.selected_planet.ok_or(ElenathError::BodyNotFound)?;
+                let location = self.surface_view_state.current_observer_location();
+                self.get_system()?.add_observer_location(planet, location);
+            }
+            GuiMessage::ExportSkyCalendar => {
+                let planet = self.selected_planet.ok_or(ElenathError::BodyNotFound)?;
+                let planet_name = self.selected_planet_name();
+                let mut location = self.surface_view_state.current_observer_location();
+                if location.get_name().is_empty() {
+                    location = ObserverLocation::new(
+                        format!(
+                            "{:.2}° longitude, {:.2}° latitude",
+                            location.get_longitude().get::<degree>(),
+                            location.get_latitude().get::<degree>()
+                        ),
+                        location.get_longitude(),
+                        location.get_latitude(),
+                    );
+                }
+                let sky_calendar = self
+                    .get_system_const()?
+                    .get_sky_calendar(planet, &location)
+                    .ok_or(ElenathError::Generic(format!(
+                        "Cannot compute a sky calendar for {}: the planet needs an orbit and a solar day.",
+                        planet_name
+                    )))?;
+                if let Some(path) = file_dialog::save_sky_calendar(self.project_folder()) {
+                    let is_markdown = path.extension().is_some_and(|extension| extension == "md");
+                    let content = if is_markdown {
+                        sky_calendar.to_markdown()
+                    } else {
+                        sky_calendar.to_html()
+                    };
+                    std::fs::write(path, content)?;
+                }
             }
             GuiMessage::SetDisplayNames(display_names) => {
                 self.display_names = display_names;
@@ -181,24 +1034,287 @@ impl Gui {
             GuiMessage::SetDisplayConstellations(display_constellations) => {
                 self.display_constellations = display_constellations;
             }
+            GuiMessage::SetGalacticMotion(galactic_motion) => {
+                self.get_system()?.set_galactic_motion(galactic_motion);
+            }
+            GuiMessage::SetMigrationSchedule(schedule) => {
+                self.get_system()?.set_migration_schedule(schedule);
+                self.dialog = None;
+            }
+            GuiMessage::SimulateFlyby(flyby, time_span) => {
+                const SAMPLE_COUNT: usize = 400;
+                let planet = self
+                    .get_selected_planet()
+                    .ok_or(ElenathError::Generic("Please select a planet.".to_string()))?;
+                let track = self
+                    .get_system_const()?
+                    .get_flyby_track(&flyby, &planet, time_span, SAMPLE_COUNT)
+                    .ok_or(ElenathError::Generic(
+                        "Cannot simulate the flyby: the central body needs a mass.".to_string(),
+                    ))?;
+                if let Some(dialog) = &mut self.dialog {
+                    dialog.update(DialogUpdate::FlybyUpdated(FlybyDialogEvent::TrackComputed(
+                        track,
+                    )));
+                }
+            }
+            GuiMessage::SetBinaryPlanet(primary, secondary, separation) => {
+                self.get_system()?
+                    .set_binary_planet(primary, secondary, separation);
+                self.open_dialog(DialogType::BinaryPlanets)?;
+            }
+            GuiMessage::SeparateBinaryPlanet(planet) => {
+                self.get_system()?.remove_binary_planet(planet);
+                self.open_dialog(DialogType::BinaryPlanets)?;
+            }
+            GuiMessage::AddCoOrbitalBody(host, placement, name, mass, radius) => {
+                self.get_system()?
+                    .add_co_orbital_body(host, placement, name, mass, radius)?;
+                self.dialog = None;
+            }
+            GuiMessage::SetCalendar(calendar) => {
+                self.get_system()?.set_calendar(calendar)?;
+                self.dialog = None;
+            }
+            GuiMessage::SetStarFormingRegions(regions) => {
+                self.get_system()?.set_star_forming_regions(regions);
+                self.dialog = None;
+            }
+            GuiMessage::SetLabelStyle(label_style) => {
+                self.label_style = label_style;
+            }
+            GuiMessage::SetCanvasBackground(style) => {
+                self.set_canvas_background(style)?;
+            }
+            GuiMessage::ExportScreenshot(caption) => {
+                self.export_caption = caption;
+                self.dialog = None;
+                if let Some(path) = file_dialog::save_screenshot(self.project_folder()) {
+                    self.export_screenshot(&path)?;
+                    self.status_message = Some("Exported screenshot".to_string());
+                }
+            }
+            #[cfg(feature = "remote_control")]
+            GuiMessage::ExportScreenshotTo(path) => {
+                let folder = self.project_folder().ok_or(ElenathError::Generic(
+                    "Remote screenshots are written to the project folder, which needs a saved system."
+                        .to_string(),
+                ))?;
+                self.export_screenshot(&folder.join(path))?;
+            }
+            GuiMessage::PickBackgroundImage => {
+                if let Some(path) = file_dialog::open_background_image(self.project_folder()) {
+                    self.set_canvas_background(BackgroundStyle::Image(path))?;
+                }
+            }
+            GuiMessage::SetUiScale(ui_scale) => {
+                self.ui_scale = ui_scale.clamp(MIN_UI_SCALE, MAX_UI_SCALE);
+                self.redraw();
+            }
+            GuiMessage::WindowEvent(id, event) => {
+                if Some(id) == self.table_window_id {
+                    if let window::Event::Closed = event {
+                        self.table_window_id = None;
+                    }
+                } else if let window::Event::Closed = event {
+                    self.save_window_layout();
+                }
+            }
+            GuiMessage::WindowResized(size, maximized) => {
+                if !self.planetarium {
+                    self.window_layout.maximized = maximized;
+                    if !maximized {
+                        self.window_layout.width = size.width;
+                        self.window_layout.height = size.height;
+                    }
+                }
+                self.redraw();
+            }
+            GuiMessage::WindowMoved(position, monitor) => {
+                if !self.window_layout.maximized && !self.planetarium {
+                    self.window_layout.position = Some((position.x, position.y));
+                    self.window_layout.monitor = monitor.map(|size| (size.width, size.height));
+                }
+            }
+            GuiMessage::CheckWindowMonitor(monitor) => {
+                // The window is moved back onto a connected monitor, which reports its new position.
+                if !self.window_layout.is_on_saved_monitor(monitor) {
+                    self.window_layout.position = None;
+                    self.window_layout.monitor = None;
+                }
+            }
+            GuiMessage::SetPlanetarium(planetarium) => {
+                self.planetarium = planetarium;
+                self.redraw();
+            }
+            GuiMessage::SetPlanetariumMonitor(monitor) => {
+                self.window_layout.planetarium_monitor = monitor;
+                self.save_window_layout();
+            }
+            GuiMessage::DetachTable => {
+                if let GuiViewMode::Table = self.mode {
+                    self.mode = GuiViewMode::Surface;
+                }
+                self.table_view_state.invalidate_cache();
+            }
+            GuiMessage::AttachTable => {
+                self.mode = GuiViewMode::Table;
+                self.table_view_state.invalidate_cache();
+            }
             GuiMessage::TableDataTypeSelected(body_type) => {
                 self.table_view_state.displayed_body_type = body_type;
+                self.table_view_state.set_sort(None);
+            }
+            GuiMessage::TablePageSelected(page) => {
+                self.table_view_state.page = page;
+            }
+            GuiMessage::TableScrolled(viewport) => {
+                self.table_view_state.scrolled(viewport);
+                // Scrolling does not change any data, so the cached table cells stay valid.
+                return Ok(());
+            }
+            GuiMessage::SetCatalogColumns(shown) => {
+                self.table_view_state.set_catalog_columns(shown);
+            }
+            GuiMessage::SetOriginFilter(origin_filter) => {
+                self.table_view_state.set_origin_filter(origin_filter);
+            }
+            GuiMessage::TableFilterChanged(search) => {
+                self.table_view_state.set_search(search);
+            }
+            GuiMessage::TableSortChanged(column, direction) => {
+                self.table_view_state.set_sort(Some((column, direction)));
             }
             GuiMessage::RandomizePlanets => {
-                self.get_system()?.randomize_planets();
+                let system = self.get_system()?;
+                let planets = system.generate_random_planets();
+                system.replace_planets(planets.clone())?;
+                command = Some(SystemCommand::RandomizePlanets(planets));
                 self.dialog = None;
+                self.show_stability_warnings()?;
             }
             GuiMessage::LoadRealPlanets => {
                 self.get_system()?.load_real_planets();
                 self.dialog = None;
+                self.show_stability_warnings()?;
             }
-            GuiMessage::RandomizeStars(keep_central_body, max_distance) => {
-                self.get_system()?
-                    .randomize_stars(keep_central_body, max_distance)?;
+            GuiMessage::RandomizeStars(keep_central_body, parameters, mode) => {
+                let generated = parameters.generate_stars(keep_central_body)?;
+                command = Some(SystemCommand::RandomizeStars(generated.clone(), mode));
+                let system = self.get_system()?;
+                system.add_generated_stars(generated, mode);
+                let count = system.get_distant_stars().len();
+                self.status_message = Some(format!("Generated {} stars", group_digits(count)));
+                self.dialog = None;
+            }
+            GuiMessage::LoadStars(data_type, mode, filter) => {
+                let system = self.get_system()?;
+                let count_before = system.get_distant_stars().len();
+                let merged = system.load_real_stars(data_type, mode, &filter)?;
+                let loaded = system
+                    .get_distant_stars()
+                    .len()
+                    .saturating_sub(count_before);
+                self.status_message = Some(load_status(loaded, merged));
+                self.dialog = None;
+            }
+            GuiMessage::LoadGaiaExport(release, extra_columns, mode, filter) => {
+                if let Some(path) = file_dialog::open_gaia_export(self.project_folder()) {
+                    self.load_gaia_export(&path, release, extra_columns, mode, &filter)?;
+                    command = Some(SystemCommand::LoadGaiaExport(
+                        path,
+                        release,
+                        extra_columns,
+                        mode,
+                        filter,
+                    ));
+                    self.dialog = None;
+                }
+            }
+            GuiMessage::ImportSpaceEngineScript => {
+                if let Some(path) = file_dialog::open_space_engine_script(self.project_folder()) {
+                    self.import_space_engine_script(path)?;
+                }
+            }
+            GuiMessage::ApplySpaceEngineImport(imported, replace_central_body) => {
+                self.apply_space_engine_import(imported, replace_central_body)?;
+            }
+            GuiMessage::NameStars => {
+                let system = self.get_system()?;
+                if system.get_metadata().naming_style == NamingStyle::CatalogNumbers {
+                    self.status_message =
+                        Some("Choose a naming style in the system info first".to_string());
+                } else {
+                    let seed = time_seed();
+                    let renamed = system.name_unnamed_stars_with_seed(seed);
+                    command = Some(SystemCommand::NameStars(seed));
+                    self.status_message = Some(format!("Named {} stars", group_digits(renamed)));
+                }
+            }
+            GuiMessage::RemoveDuplicateStars => {
+                // Duplicates found in the background are only reused as long as the stars are unchanged.
+                let found = self.duplicate_stars.take();
+                let system = self.get_system()?;
+                let removed = match found {
+                    Some((key, duplicates)) if key == system.constellation_key() => {
+                        system.remove_redundant_stars(&duplicates)
+                    }
+                    _ => system.remove_duplicate_stars(),
+                };
+                self.status_message =
+                    Some(format!("Removed {} duplicate stars", group_digits(removed)));
+                self.dialog = None;
+            }
+            GuiMessage::TrimFaintStars(absolute_magnitude) => {
+                if !absolute_magnitude.is_finite() {
+                    return Err(ElenathError::Generic(
+                        "The faintest kept magnitude needs to be a finite number.".to_string(),
+                    ));
+                }
+                let removed = self
+                    .get_system()?
+                    .remove_stars_fainter_than(absolute_magnitude);
+                self.status_message = Some(format!(
+                    "Removed {} stars fainter than {:.1} mag",
+                    group_digits(removed),
+                    absolute_magnitude
+                ));
                 self.dialog = None;
             }
-            GuiMessage::LoadStars(data_type) => {
-                self.get_system()?.load_real_stars(data_type)?;
+            GuiMessage::ExportStars(ids) => {
+                if let Some(path) = file_dialog::save_stars(self.project_folder()) {
+                    self.get_system_const()?.write_stars_to_file(&ids, path)?;
+                    self.status_message =
+                        Some(format!("Exported {} stars", group_digits(ids.len())));
+                }
+                self.dialog = None;
+            }
+            GuiMessage::ExportStarCatalog => {
+                let ids = self
+                    .get_system_const()?
+                    .get_distant_stars()
+                    .iter()
+                    .filter_map(|star| star.get_id())
+                    .collect();
+                self.handle_message(GuiMessage::ExportStars(ids))?;
+            }
+            GuiMessage::ExportTable => {
+                if let Some(path) = file_dialog::save_table(self.project_folder()) {
+                    let is_tsv = path.extension().is_some_and(|extension| extension == "tsv");
+                    let separator = if is_tsv { '\t' } else { ',' };
+                    let selected_planet = self.get_selected_planet();
+                    let content = self.table_view_state.to_delimited_text(
+                        self.get_system_const()?,
+                        &selected_planet,
+                        separator,
+                    );
+                    std::fs::write(path, content)?;
+                    self.status_message = Some("Exported table".to_string());
+                }
+            }
+            GuiMessage::JitterStars(jitter) => {
+                let jittered = self.get_system()?.jitter_stars(&jitter);
+                self.status_message = Some(format!("Perturbed {} stars", group_digits(jittered)));
                 self.dialog = None;
             }
             GuiMessage::OpenDialog(dialog_type) => {
@@ -221,19 +1337,143 @@ impl Gui {
                 }
             }
         }
+        if let (Some(command), Some(system)) = (command, self.celestial_system.as_mut()) {
+            system.log_command(command);
+        }
         self.redraw();
         Ok(())
     }
 
-    fn get_system(&mut self) -> Result<&mut CelestialSystem, ElenathError> {
+    pub(super) fn load_gaia_export(
+        &mut self,
+        path: &Path,
+        release: GaiaRelease,
+        extra_columns: bool,
+        mode: StarMergeMode,
+        filter: &StarCatalogFilter,
+    ) -> Result<(), ElenathError> {
+        let content = std::fs::read_to_string(path)?;
+        let stars = read_gaia_export(&content, release, extra_columns)?;
+        let system = self.get_system()?;
+        let count_before = system.get_distant_stars().len();
+        let merged = system.add_catalog_stars(stars, mode, filter);
+        let loaded = system
+            .get_distant_stars()
+            .len()
+            .saturating_sub(count_before);
+        self.status_message = Some(load_status(loaded, merged));
+        Ok(())
+    }
+
+    fn show_stability_warnings(&mut self) -> Result<(), ElenathError> {
+        let system = self.get_system_const()?;
+        let mut warnings = system.get_stability_warnings();
+        warnings.extend(system.get_tidal_locking_warnings());
+        if !warnings.is_empty() {
+            self.dialog = Some(Box::new(StabilityDialog::new(warnings)));
+        }
+        Ok(())
+    }
+
+    /*
+     * A script that describes a star would replace the central body, which the user is asked about first.
+     */
+    fn import_space_engine_script(&mut self, path: PathBuf) -> Result<(), ElenathError> {
+        let content = std::fs::read_to_string(path)?;
+        let imported = read_space_engine_script(&content)?;
+        if imported.central_body.is_some() {
+            let central_body_name = self
+                .get_system_const()?
+                .get_central_body_data()
+                .get_name()
+                .clone();
+            self.dialog = Some(Box::new(SpaceEngineImportDialog::new(
+                imported,
+                central_body_name,
+            )));
+            Ok(())
+        } else {
+            self.handle_message(GuiMessage::ApplySpaceEngineImport(imported, false))
+        }
+    }
+
+    /*
+     * The import is built on a copy of the system, which only replaces the system once every body was added.
+     * Planets whose names are already taken are skipped rather than failing the whole import.
+     * The bodies are logged as if they had been added by hand, so that replaying the log adds them alike.
+     */
+    fn apply_space_engine_import(
+        &mut self,
+        imported: SpaceEngineSystem,
+        replace_central_body: bool,
+    ) -> Result<(), ElenathError> {
+        let mut system = self.get_system_const()?.clone();
+        let mut skipped = imported.skipped;
+        let mut commands = Vec::new();
+        if let (Some(star), true) = (imported.central_body, replace_central_body) {
+            if star.get_name() != system.get_central_body_data().get_name() {
+                check_body_name(star.get_name(), &system.star_names_except(None))?;
+            }
+            commands.push(SystemCommand::EditCentralBody(
+                star.clone(),
+                system.get_body_notes(None),
+                system.get_metadata().clone(),
+            ));
+            system.overwrite_star_data(None, star);
+        }
+        let mut imported_planets = 0;
+        for planet in imported.planets {
+            let name = planet.get_name().clone();
+            if system.add_planet_data(planet.clone()).is_ok() {
+                commands.push(SystemCommand::NewPlanet(
+                    planet,
+                    BodyNotes::default(),
+                    None,
+                    Angle::new::<degree>(0.),
+                ));
+                imported_planets += 1;
+            } else {
+                skipped.push(name);
+            }
+        }
+        for command in commands {
+            system.log_command(command);
+        }
+        *self.get_system()? = system;
+        self.dialog = None;
+        self.status_message = Some(if skipped.is_empty() {
+            format!("Imported {} planets", imported_planets)
+        } else {
+            format!(
+                "Imported {} planets, skipped {}",
+                imported_planets,
+                skipped.join(", ")
+            )
+        });
+        self.show_stability_warnings()
+    }
+
+    pub(super) fn get_system(&mut self) -> Result<&mut CelestialSystem, ElenathError> {
         self.celestial_system
             .as_mut()
             .ok_or(ElenathError::NoCelestialSystem)
     }
 
-    fn get_system_const(&self) -> Result<&CelestialSystem, ElenathError> {
+    pub(super) fn get_system_const(&self) -> Result<&CelestialSystem, ElenathError> {
         self.celestial_system
             .as_ref()
             .ok_or(ElenathError::NoCelestialSystem)
     }
 }
+
+fn load_status(loaded: usize, merged: usize) -> String {
+    if merged == 0 {
+        format!("Loaded {} stars", group_digits(loaded))
+    } else {
+        format!(
+            "Loaded {} stars, merged {} duplicates",
+            group_digits(loaded),
+            group_digits(merged)
+        )
+    }
+}
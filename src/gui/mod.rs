@@ -1,38 +1,146 @@
-use self::dialog::Dialog;
+use self::canvas_background::{BackgroundStyle, CanvasBackground};
+use self::dialog::star_rendering::StarRenderingDialogEvent;
+use self::dialog::{Dialog, DialogUpdate};
 use self::gui_widget::GuiViewMode;
-use self::surface_view::widget::SurfaceViewState;
+use self::message::GuiMessage;
+use self::screenshot::ExportCaption;
+use self::shared_canvas_functionality::LabelStyle;
+use self::shared_widgets::{FocusChoice, FocusGroup};
+use self::star_rendering::StarRendering;
+use self::surface_view::widget::{BodyChoice, SurfaceViewState};
 use self::table_view::widget::TableViewState;
+use self::toast::Toasts;
 use self::top_view::widget::TopViewState;
-use crate::model::celestial_system::CelestialSystem;
+use self::view_settings::ViewSettingsMemory;
+use crate::error::ElenathError;
+use crate::model::body_id::BodyId;
+use crate::model::celestial_system::{
+    constellations::{derive_constellations, ConstellationKey},
+    epoch::MAX_TIME_SINCE_EPOCH_IN_YEARS,
+    part::PartOfCelestialSystem,
+    planets::SystemBody,
+    stars::find_duplicate_stars,
+    CelestialSystem,
+};
+use crate::model::local_time::DayLengths;
+use crate::model::observer_location::ObserverLocation;
 use crate::model::planet::Planet;
+use crate::model::universe::Universe;
+use crate::project_folder;
+use crate::window_layout::WindowLayout;
+use astro_coords::spherical::Spherical;
+use astro_units::mass::earth_mass;
 use astro_utils::planets::planet_data::PlanetData;
-use std::path::PathBuf;
-use uom::si::f64::Time;
+use iced::widget::combo_box;
+use iced::{window, Rectangle, Task};
+use std::cell::Cell;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use uom::si::f64::{Angle, Length, Mass, Time};
 
+pub(crate) mod benchmark;
+mod camera_input;
+mod canvas_background;
+mod command_log;
 mod dialog;
+mod file_conflict;
 mod gui_widget;
 mod message;
+#[cfg(feature = "remote_control")]
+mod remote_control;
+mod screenshot;
 mod shared_canvas_functionality;
 mod shared_widgets;
+mod star_rendering;
 mod surface_view;
 mod table_view;
+mod toast;
 mod top_view;
+mod touch;
+mod transition;
+mod view_configuration;
+mod view_settings;
+
+// Below this mass, bodies are listed apart from the planets, which keeps dwarf planets and trojans out of the way.
+const SMALL_BODY_MASS_IN_EARTH_MASSES: f64 = 0.01;
 
 pub(crate) struct Gui {
     opened_file: Option<PathBuf>,
+    opened_file_modified: Option<SystemTime>,
+    unsaved_changes: bool,
+    locked: bool,
+    status_message: Option<String>,
+    toasts: Toasts,
     mode: GuiViewMode,
+    linked_views: bool,
+    view_settings: ViewSettingsMemory,
     surface_view_state: SurfaceViewState,
     top_view_state: TopViewState,
     table_view_state: TableViewState,
     time_step: Time,
     celestial_system: Option<CelestialSystem>,
-    selected_planet_name: String,
+    universe: Universe,
+    selected_planet: Option<BodyId>,
+    planet_picker: combo_box::State<FocusChoice>,
+    planet_choices: Vec<FocusChoice>,
+    target_planet: Option<BodyId>,
+    target_picker: combo_box::State<FocusChoice>,
+    target_choices: Vec<FocusChoice>,
     display_names: bool,
     display_constellations: bool,
+    label_style: LabelStyle,
+    canvas_background: CanvasBackground,
+    ui_scale: f32,
+    window_id: Option<window::Id>,
+    table_window_id: Option<window::Id>,
+    window_layout: WindowLayout,
+    planetarium: bool,
+    export_caption: ExportCaption,
+    // What the constellations in the background task are derived for.
+    pending_constellations: Option<ConstellationKey>,
+    // The duplicate stars found in the background, together with the stars they were found among.
+    duplicate_stars: Option<(ConstellationKey, Vec<(usize, usize)>)>,
+    canvas_bounds: Cell<Rectangle>,
     pub(crate) dialog: Option<Box<dyn Dialog>>,
 }
 
 impl Gui {
+    /*
+     * Moving or resizing the window sends a stream of events, so the layout is only written once the window closes.
+     */
+    pub(super) fn save_window_layout(&self) {
+        keep_for_next_session("window layout", self.window_layout.save());
+    }
+
+    /*
+     * Dragging a slider sends a stream of renderings, so they are only written once the slider is released.
+     */
+    fn set_star_rendering(&mut self, rendering: StarRendering) {
+        self.surface_view_state.star_rendering = rendering;
+        if let Some(dialog) = &mut self.dialog {
+            dialog.update(DialogUpdate::StarRenderingUpdated(
+                StarRenderingDialogEvent::Rendering(rendering),
+            ));
+        }
+    }
+
+    fn save_star_rendering(&mut self, rendering: StarRendering) {
+        self.set_star_rendering(rendering);
+        keep_for_next_session("star rendering", rendering.save());
+    }
+
+    fn set_canvas_background(&mut self, style: BackgroundStyle) -> Result<(), ElenathError> {
+        self.canvas_background.set_style(style)?;
+        keep_for_next_session("canvas background", self.canvas_background.save());
+        self.surface_view_state.redraw_background();
+        self.top_view_state.redraw_background();
+        Ok(())
+    }
+
+    fn project_folder(&self) -> Option<&Path> {
+        project_folder::of_file(self.opened_file.as_deref()?)
+    }
+
     pub(super) fn redraw(&mut self) {
         match self.mode {
             GuiViewMode::Surface => {
@@ -41,32 +149,303 @@ impl Gui {
             GuiViewMode::Top => {
                 self.top_view_state.redraw();
             }
-            _ => (),
+            GuiViewMode::Table => {
+                self.table_view_state.invalidate_cache();
+            }
         };
+        if self.table_window_id.is_some() {
+            self.table_view_state.invalidate_cache();
+        }
     }
 
     pub(super) fn get_selected_planet(&self) -> Option<Planet> {
+        let time_since_epoch = self.celestial_system.as_ref()?.get_time_since_epoch();
+        self.get_selected_planet_at(time_since_epoch)
+    }
+
+    fn get_selected_planet_at(&self, time_since_epoch: Time) -> Option<Planet> {
         let system = self.celestial_system.as_ref()?;
         let data = self.get_selected_planet_data()?;
-        let planet = Planet::new(
-            (*data).clone(),
+        let id = self.selected_planet;
+        let mut planet = Planet::new(
+            system.get_planet_data_at(id, data, time_since_epoch),
             system.get_central_body_data(),
             None,
-            system.get_time_since_epoch(),
+            system.get_planet_time(id, time_since_epoch),
             None,
         );
+        planet.set_id(id);
+        planet.set_rotation_angle_at_epoch(system.get_rotation_angle_at_epoch(id));
         Some(planet)
     }
 
+    /*
+     * The local solar time at the surface location of the surface view, as a fraction of a solar day.
+     */
+    pub(super) fn get_local_solar_time(&self) -> Option<f64> {
+        let time_since_epoch = self.celestial_system.as_ref()?.get_time_since_epoch();
+        self.local_solar_time_at(time_since_epoch)
+    }
+
+    fn local_solar_time_at(&self, time_since_epoch: Time) -> Option<f64> {
+        let planet = self.get_selected_planet_at(time_since_epoch)?;
+        self.surface_view_state
+            .local_solar_time(&planet, time_since_epoch)
+    }
+
+    pub(super) fn get_subsolar_point(&self) -> Option<Spherical> {
+        let time_since_epoch = self.celestial_system.as_ref()?.get_time_since_epoch();
+        let planet = self.get_selected_planet_at(time_since_epoch)?;
+        self.surface_view_state
+            .subsolar_point(&planet, time_since_epoch)
+    }
+
+    pub(super) fn get_day_lengths(&self) -> Option<DayLengths> {
+        DayLengths::of(&self.get_selected_planet()?)
+    }
+
+    pub(super) fn get_local_day(&self) -> Option<Time> {
+        self.get_day_lengths()?.mean_solar
+    }
+
+    /*
+     * The next time at which it is local midnight at the surface location.
+     * The mean solar day only gives a first guess, because the true solar day varies over the orbit.
+     */
+    pub(super) fn get_next_local_midnight(&self) -> Option<Time> {
+        const ITERATIONS: usize = 3;
+
+        let local_day = self.get_local_day()?;
+        let now = self.celestial_system.as_ref()?.get_time_since_epoch();
+        let mut time = now + local_day * (1. - self.local_solar_time_at(now)?);
+        for _ in 0..ITERATIONS {
+            let local_time = self.local_solar_time_at(time)?;
+            let deviation = if local_time > 0.5 {
+                local_time - 1.
+            } else {
+                local_time
+            };
+            time -= local_day * deviation;
+        }
+        if time <= now {
+            time += local_day;
+        }
+        Some(time)
+    }
+
+    /*
+     * The planet is selected by its ID, so that renaming it or giving another planet the same name does not change the selection.
+     */
     pub(super) fn get_selected_planet_data(&self) -> Option<&PlanetData> {
-        let planet_data = self
+        let system = self.celestial_system.as_ref()?;
+        let index = system.get_planet_index(self.selected_planet?)?;
+        system.get_planets_data().get(index).copied()
+    }
+
+    pub(super) fn selected_planet_name(&self) -> String {
+        self.get_selected_planet_data()
+            .map(|planet| planet.get_name().clone())
+            .unwrap_or_default()
+    }
+
+    /*
+     * The entries of the planet picker, starting with the empty selection and sorted by group.
+     * Planets that share a name are labelled with their ID to keep them apart.
+     */
+    fn focus_choices(&self) -> Vec<FocusChoice> {
+        let mut choices = vec![FocusChoice {
+            id: None,
+            label: "No focus".to_string(),
+            group: FocusGroup::Nothing,
+        }];
+        let Some(system) = self.celestial_system.as_ref() else {
+            return choices;
+        };
+        let planets = system.get_planets_data();
+        for (index, planet) in planets.iter().enumerate() {
+            let id = system.get_planet_id(index);
+            let label = planet_label(&planets, index, id);
+            let small_body_mass = Mass::new::<earth_mass>(SMALL_BODY_MASS_IN_EARTH_MASSES);
+            let group = if id.is_some_and(|id| system.get_binary_planet(id).is_some()) {
+                FocusGroup::BinaryPlanet
+            } else if planet.get_mass() < small_body_mass {
+                FocusGroup::SmallBody
+            } else {
+                FocusGroup::Planet
+            };
+            choices.push(FocusChoice { id, label, group });
+        }
+        choices.sort_by_key(|choice| choice.group);
+        choices
+    }
+
+    /*
+     * The search state of the picker is only rebuilt when the bodies change, so that typing is not interrupted.
+     */
+    pub(super) fn refresh_planet_picker(&mut self) {
+        let choices = self.focus_choices();
+        if choices != self.planet_choices {
+            self.planet_picker = combo_box::State::new(choices.clone());
+            self.planet_choices = choices;
+        }
+        let mut target_choices = self.focus_choices();
+        target_choices.retain(|choice| choice.id.is_none() || choice.id != self.selected_planet);
+        if let Some(no_target) = target_choices.first_mut() {
+            no_target.label = "No target".to_string();
+        }
+        if target_choices != self.target_choices {
+            self.target_picker = combo_box::State::new(target_choices.clone());
+            self.target_choices = target_choices;
+        }
+    }
+
+    /*
+     * The distance between the focused body and the target, and the angle between the target and the central body in the sky of the focused body.
+     * Both are taken from the same positions as the top view, so that they match what is drawn.
+     */
+    pub(super) fn get_target_separation(&self) -> Option<(Length, Option<Angle>)> {
+        let system = self.celestial_system.as_ref()?;
+        let planets = system.get_planets();
+        let find = |id: BodyId| planets.iter().find(|planet| planet.get_id() == Some(id));
+        let observer = find(self.selected_planet?)?;
+        let target = find(self.target_planet?)?;
+        let distance = (target.get_position().clone() + -observer.get_position()).length();
+        Some((distance, target.get_elongation(observer.get_position())))
+    }
+
+    pub(super) fn point_surface_view_at_central_body(&mut self) -> Result<(), ElenathError> {
+        let planet = self
+            .get_selected_planet()
+            .ok_or(ElenathError::BodyNotFound)?;
+        let time_since_epoch = self
             .celestial_system
-            .as_ref()?
-            .get_planets_data()
-            .iter()
-            .find(|p| p.get_name().eq(&self.selected_planet_name))
-            .copied();
-        planet_data
+            .as_ref()
+            .ok_or(ElenathError::NoCelestialSystem)?
+            .get_time_since_epoch();
+        self.surface_view_state
+            .point_at_central_body(&planet, time_since_epoch);
+        Ok(())
+    }
+
+    pub(super) fn scroll_table_to_planet(&mut self, id: BodyId) -> Task<GuiMessage> {
+        let selected_planet = self.get_selected_planet();
+        match &self.celestial_system {
+            Some(system) => self
+                .table_view_state
+                .scroll_to_planet(system, &selected_planet, id),
+            None => Task::none(),
+        }
+    }
+
+    /*
+     * Derives the constellations on the executor's thread pool, unless they are current or already being derived.
+     * Meanwhile, the last constellations stay on display.
+     */
+    pub(super) fn recompute_outdated_constellations(&mut self) -> Task<GuiMessage> {
+        let system = match self.celestial_system.as_ref() {
+            Some(system) => system,
+            None => return Task::none(),
+        };
+        let key = system.constellation_key();
+        if system.constellations_are_current() || self.pending_constellations == Some(key) {
+            return Task::none();
+        }
+        self.pending_constellations = Some(key);
+        let (stars, key) = system.constellation_input();
+        Task::perform(
+            async move { derive_constellations(&stars, key.time_since_epoch) },
+            move |constellations| GuiMessage::ConstellationsComputed(key, constellations),
+        )
+    }
+
+    /*
+     * Comparing every star with every other takes a while for large catalogs, so it happens in the background, on a copy of the stars.
+     */
+    pub(super) fn search_duplicate_stars(&self) -> Task<GuiMessage> {
+        let Some(system) = self.celestial_system.as_ref() else {
+            return Task::none();
+        };
+        let key = system.constellation_key();
+        let stars = system.get_distant_stars().to_vec();
+        Task::perform(
+            async move { find_duplicate_stars(&stars) },
+            move |duplicates| GuiMessage::DuplicateStarsFound(key, duplicates),
+        )
+    }
+
+    /*
+     * The system refuses times too far from the epoch, which the user is told about.
+     */
+    pub(super) fn set_time(&mut self, time: Time) -> Result<(), ElenathError> {
+        let system = self.get_system()?;
+        system.set_time_since_epoch(time);
+        if system.get_time_since_epoch() != time {
+            self.status_message = Some(format!(
+                "The time is limited to {} years before or after the epoch",
+                MAX_TIME_SINCE_EPOCH_IN_YEARS
+            ));
+        }
+        self.update_tracked_body(false)
+    }
+
+    /*
+     * Following the tracked body while the time runs snaps to it, while pointing at a newly tracked body or from another planet is animated.
+     */
+    pub(super) fn update_tracked_body(&mut self, animated: bool) -> Result<(), ElenathError> {
+        let Some(tracked_body) = self.surface_view_state.get_tracked_body() else {
+            return Ok(());
+        };
+        let observer = match self.get_selected_planet() {
+            Some(planet) => planet,
+            None => return Ok(()),
+        };
+        let system = self
+            .celestial_system
+            .as_ref()
+            .ok_or(ElenathError::NoCelestialSystem)?;
+        let time_since_epoch = system.get_time_since_epoch();
+        let target = system.get_body_position_at(tracked_body, time_since_epoch);
+        if let Some(target) = target {
+            self.surface_view_state.point_at_position(
+                &observer,
+                &target,
+                time_since_epoch,
+                animated,
+            );
+        }
+        Ok(())
+    }
+
+    /*
+     * Every body but the selected planet, which cannot be seen from its own surface.
+     */
+    pub(super) fn get_trackable_bodies(&self) -> Vec<BodyChoice> {
+        let Some(system) = &self.celestial_system else {
+            return Vec::new();
+        };
+        let mut choices = vec![BodyChoice {
+            body: Some(SystemBody::CentralBody),
+            label: system.get_central_body_data().get_name().clone(),
+        }];
+        let planets = system.get_planets_data();
+        for index in 0..planets.len() {
+            let id = system.get_planet_id(index);
+            if id.is_none() || id == self.selected_planet {
+                continue;
+            }
+            choices.push(BodyChoice {
+                body: id.map(SystemBody::Planet),
+                label: planet_label(&planets, index, id),
+            });
+        }
+        choices
+    }
+
+    pub(super) fn get_observer_locations(&self) -> &[ObserverLocation] {
+        match (self.celestial_system.as_ref(), self.selected_planet) {
+            (Some(system), Some(planet)) => system.get_observer_locations(planet),
+            _ => &[],
+        }
     }
 
     pub(super) fn get_planet_data(&self) -> Vec<&PlanetData> {
@@ -76,3 +455,24 @@ impl Gui {
             .unwrap_or_default()
     }
 }
+
+/*
+ * Settings of the installation that cannot be stored are not worth interrupting the user for, they are merely lost for the next session.
+ */
+fn keep_for_next_session(setting: &str, saved: Result<(), std::io::Error>) {
+    if let Err(e) = saved {
+        println!("Failed to save the {}: {}", setting, e);
+    }
+}
+
+/*
+ * Planets that share a name are labelled with their ID to keep them apart.
+ */
+fn planet_label(planets: &[&PlanetData], index: usize, id: Option<BodyId>) -> String {
+    let name = planets[index].get_name();
+    let is_shared = planets.iter().filter(|p| p.get_name() == name).count() > 1;
+    match id {
+        Some(id) if is_shared => format!("{} ({})", name, id),
+        _ => name.clone(),
+    }
+}
@@ -0,0 +1,303 @@
+use iced::{
+    futures::{channel::mpsc, Stream},
+    Subscription,
+};
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use uom::si::{f64::Time, time::year};
+
+use crate::project_folder;
+
+use super::{gui_widget::GuiViewMode, message::GuiMessage};
+
+const DEFAULT_PORT: u16 = 7870;
+const PORT_VARIABLE: &str = "ELENATH_REMOTE_PORT";
+const TOKEN_VARIABLE: &str = "ELENATH_REMOTE_TOKEN";
+const QUEUE_SIZE: usize = 64;
+const MAX_LINE_LENGTH: u64 = 8 * 1024;
+const MAX_HEADER_COUNT: usize = 64;
+const MAX_BODY_SIZE: usize = 64 * 1024;
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+/*
+ * Lets a notebook or a companion tool drive Elenath over HTTP while it is running.
+ * The server only starts if ELENATH_REMOTE_TOKEN is set, and every request has to send it as "Authorization: Bearer <token>".
+ * It only listens on the local machine, on the port given by ELENATH_REMOTE_PORT or 7870.
+ * Requests with an Origin header come from a web page, and are refused whatever their token.
+ *
+ * POST /system   body: path of a system file to open, refused while locked or with unsaved changes
+ * POST /time     body: years since epoch
+ * POST /view     body: surface, top or table
+ * POST /frame    body: path of a PNG or PPM image inside the project folder, which the current view is rendered to
+ *
+ * Requests are answered with 202 once they are queued, because the GUI carries them out on its own thread.
+ * A failing command shows up in the GUI like any other error.
+ */
+pub(super) fn subscription() -> Subscription<GuiMessage> {
+    Subscription::run(remote_messages)
+}
+
+fn remote_messages() -> impl Stream<Item = GuiMessage> {
+    iced::stream::channel(QUEUE_SIZE, async move |output: mpsc::Sender<GuiMessage>| {
+        let token = match std::env::var(TOKEN_VARIABLE) {
+            Ok(token) if !token.trim().is_empty() => token.trim().to_string(),
+            _ => {
+                eprintln!(
+                    "The remote control is off, because {} is not set.",
+                    TOKEN_VARIABLE
+                );
+                return;
+            }
+        };
+        let port = std::env::var(PORT_VARIABLE)
+            .ok()
+            .and_then(|port| port.parse().ok())
+            .unwrap_or(DEFAULT_PORT);
+        match TcpListener::bind(("127.0.0.1", port)) {
+            Ok(listener) => {
+                std::thread::spawn(move || serve(listener, token, output));
+            }
+            Err(e) => eprintln!(
+                "The remote control could not listen on port {}: {}",
+                port, e
+            ),
+        }
+    })
+}
+
+struct Request {
+    method: String,
+    path: String,
+    authorization: Option<String>,
+    has_origin: bool,
+    body: String,
+}
+
+fn serve(listener: TcpListener, token: String, mut output: mpsc::Sender<GuiMessage>) {
+    for stream in listener.incoming().flatten() {
+        // A client that stops sending would otherwise block every other client.
+        if stream.set_read_timeout(Some(READ_TIMEOUT)).is_err() {
+            continue;
+        }
+        let (status, text) = match read_request(&stream) {
+            Ok(request) if request.has_origin => {
+                (403, "Requests from web pages are refused".to_string())
+            }
+            Ok(request) if !is_authorized(request.authorization.as_deref(), &token) => {
+                (401, "Missing or wrong token".to_string())
+            }
+            Ok(request) => match parse_request(&request.method, &request.path, &request.body) {
+                Ok(message) => match output.try_send(message) {
+                    Ok(()) => (202, "Queued".to_string()),
+                    Err(e) if e.is_disconnected() => return,
+                    Err(_) => (503, "Too many queued requests".to_string()),
+                },
+                Err(error) => error,
+            },
+            Err(e) => (400, e.to_string()),
+        };
+        let _ = respond(stream, status, &text);
+    }
+}
+
+fn invalid(message: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message)
+}
+
+/*
+ * Lines longer than the limit are refused rather than read to their end.
+ */
+fn read_limited_line(reader: &mut impl BufRead) -> Result<String, std::io::Error> {
+    let mut line = String::new();
+    reader.take(MAX_LINE_LENGTH).read_line(&mut line)?;
+    if !line.ends_with('\n') {
+        return Err(invalid("Line too long or cut off"));
+    }
+    Ok(line)
+}
+
+fn read_request(stream: &TcpStream) -> Result<Request, std::io::Error> {
+    let mut reader = BufReader::new(stream);
+    let request_line = read_limited_line(&mut reader)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().ok_or(invalid("Empty request"))?.to_string();
+    let path = parts.next().ok_or(invalid("Missing path"))?.to_string();
+
+    let mut content_length = 0;
+    let mut authorization = None;
+    let mut has_origin = false;
+    for _ in 0..=MAX_HEADER_COUNT {
+        let header = read_limited_line(&mut reader)?;
+        let header = header.trim();
+        if header.is_empty() {
+            if content_length > MAX_BODY_SIZE {
+                return Err(invalid("Body too large"));
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body)?;
+            let body = String::from_utf8(body).map_err(|_| invalid("Body is not UTF-8"))?;
+            return Ok(Request {
+                method,
+                path,
+                authorization,
+                has_origin,
+                body,
+            });
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            continue;
+        };
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("content-length") {
+            content_length = value.trim().parse().map_err(|_| invalid("Bad length"))?;
+        } else if name.eq_ignore_ascii_case("authorization") {
+            authorization = Some(value.trim().to_string());
+        } else if name.eq_ignore_ascii_case("origin") {
+            has_origin = true;
+        }
+    }
+    Err(invalid("Too many headers"))
+}
+
+/*
+ * Compares every byte, so that the time taken does not tell how much of the token was right.
+ */
+fn is_authorized(authorization: Option<&str>, token: &str) -> bool {
+    let Some(given) = authorization.and_then(|value| value.strip_prefix("Bearer ")) else {
+        return false;
+    };
+    let (given, token) = (given.trim().as_bytes(), token.as_bytes());
+    given.len() == token.len()
+        && given
+            .iter()
+            .zip(token)
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+fn respond(mut stream: TcpStream, status: u16, text: &str) -> Result<(), std::io::Error> {
+    let reason = match status {
+        202 => "Accepted",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        503 => "Service Unavailable",
+        _ => "Bad Request",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        text.len(),
+        text
+    )
+}
+
+/*
+ * Turns a request into the message the GUI would send for the same action, or into a status code and a reason.
+ */
+fn parse_request(method: &str, path: &str, body: &str) -> Result<GuiMessage, (u16, String)> {
+    if method != "POST" {
+        return Err((405, "Only POST is supported".to_string()));
+    }
+    let body = body.trim();
+    let bad_request = |reason: String| Err((400, reason));
+    match path {
+        "/system" if body.is_empty() => bad_request("Missing system file path".to_string()),
+        "/system" => Ok(GuiMessage::OpenFileAt(PathBuf::from(body))),
+        "/time" => match body.parse::<f64>() {
+            Ok(years) if years.is_finite() => Ok(GuiMessage::UpdateTime(Time::new::<year>(years))),
+            _ => bad_request(format!("Not a number of years: {}", body)),
+        },
+        "/view" => match body.to_lowercase().as_str() {
+            "surface" => Ok(GuiMessage::ModeSelected(GuiViewMode::Surface)),
+            "top" => Ok(GuiMessage::ModeSelected(GuiViewMode::Top)),
+            "table" => Ok(GuiMessage::ModeSelected(GuiViewMode::Table)),
+            _ => bad_request(format!("Unknown view: {}", body)),
+        },
+        "/frame" => {
+            let path = Path::new(body);
+            let is_image = path
+                .extension()
+                .is_some_and(|extension| extension == "png" || extension == "ppm");
+            if !is_image || !project_folder::stays_inside(path) {
+                return bad_request(format!(
+                    "Not a PNG or PPM file inside the project folder: {}",
+                    body
+                ));
+            }
+            Ok(GuiMessage::ExportScreenshotTo(path.to_path_buf()))
+        }
+        _ => Err((404, format!("Unknown endpoint: {}", path))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_become_gui_messages() {
+        assert!(matches!(
+            parse_request("POST", "/time", " 2.5\n"),
+            Ok(GuiMessage::UpdateTime(time)) if (time.get::<year>() - 2.5).abs() < 1e-12
+        ));
+        assert!(matches!(
+            parse_request("POST", "/view", "Top"),
+            Ok(GuiMessage::ModeSelected(GuiViewMode::Top))
+        ));
+        assert!(matches!(
+            parse_request("POST", "/frame", "frames/frame.png"),
+            Ok(GuiMessage::ExportScreenshotTo(path)) if path == PathBuf::from("frames/frame.png")
+        ));
+    }
+
+    #[test]
+    fn frames_stay_inside_the_project_folder() {
+        for path in ["/home/user/.bashrc", "../frame.png", "frame.sh", ""] {
+            assert!(matches!(
+                parse_request("POST", "/frame", path),
+                Err((400, _))
+            ));
+        }
+    }
+
+    #[test]
+    fn only_the_exact_token_is_accepted() {
+        assert!(is_authorized(Some("Bearer secret"), "secret"));
+        assert!(!is_authorized(Some("Bearer secre"), "secret"));
+        assert!(!is_authorized(Some("Bearer secret2"), "secret"));
+        assert!(!is_authorized(Some("secret"), "secret"));
+        assert!(!is_authorized(None, "secret"));
+    }
+
+    #[test]
+    fn overlong_lines_are_refused() {
+        let long_line = "a".repeat(MAX_LINE_LENGTH as usize + 1) + "\n";
+        assert!(read_limited_line(&mut long_line.as_bytes()).is_err());
+        assert_eq!(
+            read_limited_line(&mut "POST /time HTTP/1.1\r\n".as_bytes()).unwrap(),
+            "POST /time HTTP/1.1\r\n"
+        );
+    }
+
+    #[test]
+    fn bad_requests_are_rejected() {
+        assert!(matches!(parse_request("GET", "/time", "1"), Err((405, _))));
+        assert!(matches!(
+            parse_request("POST", "/time", "soon"),
+            Err((400, _))
+        ));
+        assert!(matches!(
+            parse_request("POST", "/system", ""),
+            Err((400, _))
+        ));
+        assert!(matches!(parse_request("POST", "/quit", ""), Err((404, _))));
+    }
+}
@@ -0,0 +1,264 @@
+use astro_utils::astro_display::AstroDisplay;
+use iced::{
+    advanced::{graphics::geometry::Renderer as _, renderer::Headless},
+    futures::executor::block_on,
+    widget::canvas::{self, Path},
+    Color, Font, Pixels, Point, Rectangle, Renderer, Size, Vector,
+};
+use image::{ImageFormat, RgbaImage};
+use std::{fmt::Display, io::Cursor, path::Path as FilePath};
+
+use super::{gui_widget::GuiViewMode, Gui};
+use crate::error::ElenathError;
+
+const LINE_HEIGHT: f32 = 18.;
+const MARGIN: f32 = 12.;
+const CAPTION_BOX_COLOR: Color = Color::from_rgba(0., 0., 0., 0.6);
+const CAPTION_TEXT_COLOR: Color = Color::from_rgba(1., 1., 1., 0.9);
+// The text of the canvas has no layout, so the box width is estimated from the number of characters.
+const CHARACTER_WIDTH: f32 = 8.;
+// The size iced uses for text unless told otherwise.
+const TEXT_SIZE: f32 = 16.;
+const SOFTWARE_RENDERER: &str = "tiny-skia";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Corner {
+    pub(crate) const ALL: [Corner; 4] = [
+        Corner::TopLeft,
+        Corner::TopRight,
+        Corner::BottomLeft,
+        Corner::BottomRight,
+    ];
+}
+
+impl Display for Corner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Corner::TopLeft => write!(f, "Top Left"),
+            Corner::TopRight => write!(f, "Top Right"),
+            Corner::BottomLeft => write!(f, "Bottom Left"),
+            Corner::BottomRight => write!(f, "Bottom Right"),
+        }
+    }
+}
+
+/*
+ * What is stamped onto an exported screenshot of the canvas.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ExportCaption {
+    pub(crate) caption: bool,
+    pub(crate) scale_indicator: bool,
+    pub(crate) corner: Corner,
+}
+
+impl Default for ExportCaption {
+    fn default() -> Self {
+        ExportCaption {
+            caption: true,
+            scale_indicator: true,
+            corner: Corner::BottomRight,
+        }
+    }
+}
+
+impl ExportCaption {
+    /*
+     * The lines are drawn in a translucent box, which is anchored in the chosen corner of the canvas.
+     */
+    pub(crate) fn draw(
+        &self,
+        renderer: &Renderer,
+        bounds: Rectangle,
+        lines: &[String],
+    ) -> Option<canvas::Geometry> {
+        if lines.is_empty() {
+            return None;
+        }
+        let longest = lines.iter().map(|line| line.chars().count()).max()?;
+        let box_size = Size::new(
+            longest as f32 * CHARACTER_WIDTH + MARGIN,
+            lines.len() as f32 * LINE_HEIGHT + MARGIN,
+        );
+        let x = match self.corner {
+            Corner::TopLeft | Corner::BottomLeft => MARGIN,
+            Corner::TopRight | Corner::BottomRight => bounds.width - box_size.width - MARGIN,
+        };
+        let y = match self.corner {
+            Corner::TopLeft | Corner::TopRight => MARGIN,
+            Corner::BottomLeft | Corner::BottomRight => bounds.height - box_size.height - MARGIN,
+        };
+        let top_left = Point::new(x, y);
+
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        frame.fill(&Path::rectangle(top_left, box_size), CAPTION_BOX_COLOR);
+        for (i, line) in lines.iter().enumerate() {
+            let text = canvas::Text {
+                content: line.clone(),
+                position: top_left + Vector::new(MARGIN / 2., MARGIN / 2. + i as f32 * LINE_HEIGHT),
+                color: CAPTION_TEXT_COLOR,
+                ..Default::default()
+            };
+            frame.fill_text(text);
+        }
+        Some(frame.into_geometry())
+    }
+}
+
+impl Gui {
+    /*
+     * The system, the observer and the date, followed by the field of view or scale of the current view.
+     */
+    pub(super) fn export_caption_lines(&self, canvas_size: Size) -> Vec<String> {
+        let Some(system) = self.celestial_system.as_ref() else {
+            return vec![];
+        };
+        let options = self.export_caption;
+        let mut lines = Vec::new();
+        if options.caption {
+            let system_name = &system.get_metadata().name;
+            if !system_name.is_empty() {
+                lines.push(system_name.clone());
+            }
+            if let GuiViewMode::Surface = self.mode {
+                let location = self.surface_view_state.current_observer_location();
+                let mut observer = format!(
+                    "{}, {} / {}",
+                    self.selected_planet_name(),
+                    location.get_latitude().astro_display(),
+                    location.get_longitude().astro_display()
+                );
+                if !location.get_name().is_empty() {
+                    observer = format!("{} ({})", location.get_name(), observer);
+                }
+                lines.push(observer);
+            }
+            let time_since_epoch = system.get_time_since_epoch();
+            let date = match system.format_calendar_date(time_since_epoch) {
+                Some(date) => format!("{} ({})", date, time_since_epoch.astro_display()),
+                None => format!("{} since epoch", time_since_epoch.astro_display()),
+            };
+            lines.push(date);
+        }
+        if options.scale_indicator {
+            match self.mode {
+                GuiViewMode::Surface => {
+                    let (horizontal, vertical) = self.surface_view_state.field_of_view(canvas_size);
+                    lines.push(format!(
+                        "Field of view: {} x {}",
+                        horizontal.astro_display(),
+                        vertical.astro_display()
+                    ));
+                }
+                GuiViewMode::Top => lines.push(self.top_view_state.scale_description()),
+                GuiViewMode::Table => {}
+            }
+        }
+        lines
+    }
+
+    /*
+     * The canvas is drawn once more by the software renderer, at the size it was last shown.
+     * This way the export depends neither on the window nor on the graphics card, and the caption never shows up on screen.
+     */
+    fn render_canvas(&self) -> Result<RgbaImage, ElenathError> {
+        let size = self.canvas_bounds.get().size();
+        let pixel_size = Size::new(size.width.round() as u32, size.height.round() as u32);
+        if pixel_size.width == 0 || pixel_size.height == 0 {
+            return Err(ElenathError::Generic(
+                "The canvas has not been drawn yet.".to_string(),
+            ));
+        }
+        let renderer = <Renderer as Headless>::new(
+            Font::default(),
+            Pixels(TEXT_SIZE),
+            Some(SOFTWARE_RENDERER),
+        );
+        let mut renderer = block_on(renderer).ok_or(ElenathError::Generic(
+            "The software renderer is not available.".to_string(),
+        ))?;
+
+        let bounds = Rectangle::with_size(size);
+        let mut geometries = self.draw_view(&renderer, bounds);
+        let lines = self.export_caption_lines(size);
+        geometries.extend(self.export_caption.draw(&renderer, bounds, &lines));
+        for geometry in geometries {
+            renderer.draw_geometry(geometry);
+        }
+        let pixels = renderer.screenshot(pixel_size, 1., Color::BLACK);
+        RgbaImage::from_raw(pixel_size.width, pixel_size.height, pixels).ok_or(
+            ElenathError::Generic("The rendered canvas has the wrong size.".to_string()),
+        )
+    }
+
+    pub(super) fn export_screenshot(&self, path: &FilePath) -> Result<(), ElenathError> {
+        let image = self.render_canvas()?;
+        let is_ppm = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("ppm"));
+        let bytes = if is_ppm {
+            to_ppm(&image)
+        } else {
+            to_png(&image)?
+        };
+        std::fs::write(path, bytes)?;
+        Ok(())
+    }
+}
+
+fn to_png(image: &RgbaImage) -> Result<Vec<u8>, ElenathError> {
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|e| ElenathError::Generic(format!("Cannot encode the screenshot: {}", e)))?;
+    Ok(png)
+}
+
+/*
+ * Netpbm images remain available for tools that prefer them.
+ */
+fn to_ppm(image: &RgbaImage) -> Vec<u8> {
+    let mut ppm = format!("P6\n{} {}\n255\n", image.width(), image.height()).into_bytes();
+    for pixel in image.as_raw().chunks_exact(4) {
+        ppm.extend_from_slice(&pixel[..3]);
+    }
+    ppm
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_pixels() -> RgbaImage {
+        RgbaImage::from_raw(2, 1, vec![1, 2, 3, 255, 4, 5, 6, 128]).unwrap()
+    }
+
+    #[test]
+    fn ppm_drops_the_alpha_channel() {
+        let ppm = to_ppm(&two_pixels());
+        let header = b"P6\n2 1\n255\n";
+        assert_eq!(&ppm[..header.len()], header);
+        assert_eq!(&ppm[header.len()..], &[1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn png_keeps_the_pixels() {
+        let png = to_png(&two_pixels()).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        let decoded = image::load_from_memory(&png).unwrap().to_rgba8();
+        assert_eq!(decoded, two_pixels());
+    }
+
+    #[test]
+    fn png_compresses_uniform_images() {
+        let image = RgbaImage::from_pixel(256, 256, image::Rgba([0, 0, 0, 255]));
+        assert!(to_png(&image).unwrap().len() < image.as_raw().len() / 100);
+    }
+}
@@ -1,26 +1,110 @@
 use iced::{
     alignment::Vertical,
     widget::{
-        canvas::{self, Path},
+        canvas,
         text::{Alignment, Shaping},
     },
     Color, Pixels, Point, Rectangle, Vector,
 };
 
-pub(super) fn draw_background(bounds: Rectangle, frame: &mut canvas::Frame) {
-    let background = Path::rectangle(Point::ORIGIN, bounds.size());
-    frame.fill(&background, Color::BLACK);
+use super::canvas_background::CanvasBackground;
+use crate::model::star_naming::is_catalog_name;
+
+pub(super) fn draw_background(
+    bounds: Rectangle,
+    frame: &mut canvas::Frame,
+    background: &CanvasBackground,
+) {
+    background.draw(bounds, frame);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct LabelStyle {
+    pub(crate) size: f32,
+    pub(crate) follow_body_color: bool,
+    pub(crate) fixed_color: Color,
+    pub(crate) offset: f32,
+    pub(crate) show_catalog_names: bool,
+}
+
+impl LabelStyle {
+    pub(crate) const SIZE_STEP: f32 = 2.;
+    pub(crate) const OFFSET_STEP: f32 = 5.;
+    const MIN_SIZE: f32 = 6.;
+    const CONSTELLATION_SIZE_FACTOR: f32 = 1.25;
+
+    pub(crate) fn with_size(mut self, size: f32) -> Self {
+        self.size = size.max(Self::MIN_SIZE);
+        self
+    }
+
+    pub(crate) fn with_offset(mut self, offset: f32) -> Self {
+        self.offset = offset.max(0.);
+        self
+    }
+
+    pub(crate) fn with_follow_body_color(mut self, follow_body_color: bool) -> Self {
+        self.follow_body_color = follow_body_color;
+        self
+    }
+
+    pub(crate) fn with_fixed_color(mut self, fixed_color: Color) -> Self {
+        self.fixed_color = fixed_color;
+        self
+    }
+
+    pub(crate) fn with_show_catalog_names(mut self, show_catalog_names: bool) -> Self {
+        self.show_catalog_names = show_catalog_names;
+        self
+    }
+
+    /*
+     * Catalog numbers clutter the sky when thousands of Gaia stars are loaded, so they are only drawn on request.
+     */
+    fn shows(&self, name: &str) -> bool {
+        !name.is_empty() && (self.show_catalog_names || !is_catalog_name(name))
+    }
+
+    pub(crate) fn color(&self, body_color: Color) -> Color {
+        if self.follow_body_color {
+            body_color
+        } else {
+            self.fixed_color
+        }
+    }
+
+    pub(crate) fn constellation_size(&self) -> f32 {
+        self.size * Self::CONSTELLATION_SIZE_FACTOR
+    }
+}
+
+impl Default for LabelStyle {
+    fn default() -> Self {
+        LabelStyle {
+            size: 16.,
+            follow_body_color: true,
+            fixed_color: Color::WHITE,
+            offset: 10.,
+            show_catalog_names: false,
+        }
+    }
 }
 
-pub(super) fn draw_name(name: &str, color: Color, body_center: Point, frame: &mut canvas::Frame) {
-    const ORDINATE_OFFSET: f32 = 10.;
-    if name.is_empty() || name.starts_with("Gaia") || name.chars().all(char::is_numeric) {
+pub(super) fn draw_name(
+    name: &str,
+    color: Color,
+    body_center: Point,
+    style: &LabelStyle,
+    frame: &mut canvas::Frame,
+) {
+    if !style.shows(name) {
         return;
     }
     let name_widget = canvas::Text {
-        color,
+        color: style.color(color),
+        size: Pixels(style.size),
         content: name.to_string(),
-        position: body_center + Vector::new(ORDINATE_OFFSET, ORDINATE_OFFSET),
+        position: body_center + Vector::new(style.offset, style.offset),
         shaping: Shaping::Advanced,
         ..Default::default()
     };
@@ -1,18 +1,103 @@
 use super::{
+    canvas_background::{BackgroundChoice, BackgroundStyle},
     dialog::DialogType,
     gui_widget::{BIG_COLUMN_WIDTH, PADDING, SMALL_COLUMN_WIDTH},
     message::GuiMessage,
+    shared_canvas_functionality::LabelStyle,
     Gui, GuiViewMode,
 };
-use astro_utils::{astro_display::AstroDisplay, planets::planet_data::PlanetData};
+use crate::model::{
+    body_id::BodyId,
+    galactic_motion::GalacticMotion,
+    local_time::{format_local_time, DayLengths},
+    star::StarMergeMode,
+};
+use astro_utils::astro_display::AstroDisplay;
 use iced::{
     alignment::{Horizontal, Vertical},
-    widget::{text::Shaping, Button, Column, Container, PickList, Row, Text, TextInput, Toggler},
+    widget::{
+        combo_box, container, text::Shaping, tooltip, Button, Column, ComboBox, Container,
+        PickList, Radio, Row, Text, TextInput, Toggler,
+    },
     Alignment, Element, Length,
 };
+use std::fmt::Display;
 use uom::si::f64::Time;
 
+/*
+ * The groups in which the planet picker lists the bodies, in this order.
+ * The model knows neither moons nor stations, so bodies are told apart by what the system does know about them.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) enum FocusGroup {
+    Nothing,
+    Planet,
+    BinaryPlanet,
+    SmallBody,
+}
+
+impl FocusGroup {
+    fn icon(&self) -> &'static str {
+        match self {
+            FocusGroup::Nothing => "○",
+            FocusGroup::Planet => "●",
+            FocusGroup::BinaryPlanet => "◐",
+            FocusGroup::SmallBody => "•",
+        }
+    }
+}
+
+/*
+ * An entry of the planet picker. Planets are told apart by their ID, so that a renamed planet stays focused.
+ * Planets that share a name are labelled with their ID as well, so that they can be told apart in the list.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct FocusChoice {
+    pub(super) id: Option<BodyId>,
+    pub(super) label: String,
+    pub(super) group: FocusGroup,
+}
+
+impl Display for FocusChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.group.icon(), self.label)
+    }
+}
+
 impl Gui {
+    /*
+     * A second body next to the focused one, which the surface view points at and the top view connects to the focused body.
+     */
+    pub(super) fn target_control(&self) -> Element<'_, GuiMessage> {
+        let picker = planet_picker(
+            "Target body:",
+            &self.target_picker,
+            &self.target_choices,
+            self.target_planet,
+            GuiMessage::TargetSelected,
+        );
+        let separation = match self.get_target_separation() {
+            Some((distance, elongation)) => {
+                let elongation = match elongation {
+                    Some(elongation) => elongation.astro_display(),
+                    None => "N/A".to_string(),
+                };
+                format!(
+                    "Distance: {}, elongation: {}",
+                    distance.astro_display(),
+                    elongation
+                )
+            }
+            None => "Distance: N/A".to_string(),
+        };
+        Column::new()
+            .push(picker)
+            .push(Text::new(separation))
+            .align_x(Alignment::Center)
+            .spacing(PADDING)
+            .into()
+    }
+
     pub(super) fn gui_mode_tabs() -> Element<'static, GuiMessage> {
         let local_view_button = std_button(
             "Local View",
@@ -45,12 +130,30 @@ impl Gui {
         let save_to_new_file_button =
             std_button("Save to new file", GuiMessage::SaveToNewFile, has_system);
         let open_file_button = std_button("Open file", GuiMessage::OpenFile, true);
+        let save_view_button =
+            std_button("Save view setup", GuiMessage::ExportViewConfiguration, true);
+        let load_view_button =
+            std_button("Load view setup", GuiMessage::ImportViewConfiguration, true);
+        let export_log_button = std_button(
+            "Export command log",
+            GuiMessage::ExportCommandLog,
+            has_system,
+        );
+        let replay_log_button = std_button(
+            "Replay command log",
+            GuiMessage::ReplayCommandLog,
+            has_system,
+        );
 
         Row::new()
             .push(new_button)
             .push(save_to_file_button)
             .push(save_to_new_file_button)
             .push(open_file_button)
+            .push(save_view_button)
+            .push(load_view_button)
+            .push(export_log_button)
+            .push(replay_log_button)
             .align_y(Alignment::Center)
             .spacing(PADDING)
             .into()
@@ -73,31 +176,28 @@ pub(crate) fn std_button(
     button.width(SMALL_COLUMN_WIDTH)
 }
 
+/*
+ * Typing into the picker filters the bodies by name, which keeps systems with dozens of bodies navigable.
+ */
 pub(super) fn planet_picker<'a>(
-    planets: Vec<&PlanetData>,
-    selected_planet: Option<&PlanetData>,
+    label: &'a str,
+    state: &'a combo_box::State<FocusChoice>,
+    choices: &'a [FocusChoice],
+    selected_planet: Option<BodyId>,
+    on_select: fn(Option<BodyId>) -> GuiMessage,
 ) -> Element<'a, GuiMessage> {
-    let text = Text::new("Focused body:")
+    let text = Text::new(label)
         .width(SMALL_COLUMN_WIDTH)
         .align_x(Horizontal::Right)
         .align_y(Vertical::Center);
-    let mut planet_names = vec![String::new()];
-    for name in planets.iter().map(|p| p.get_name()) {
-        planet_names.push(name.clone());
-    }
-    let selected_planet_name = match selected_planet {
-        Some(planet) => planet.get_name().clone(),
-        None => String::new(),
-    };
-    let pick_list = PickList::new(
-        planet_names,
-        Some(selected_planet_name),
-        GuiMessage::PlanetSelected,
-    )
+    let selected = choices.iter().find(|choice| choice.id == selected_planet);
+    let combo_box = ComboBox::new(state, "Search bodies...", selected, move |choice| {
+        on_select(choice.id)
+    })
     .width(1.25 * SMALL_COLUMN_WIDTH + PADDING);
     Row::new()
         .push(text)
-        .push(pick_list)
+        .push(combo_box)
         .spacing(PADDING)
         .align_y(Alignment::Center)
         .into()
@@ -106,10 +206,15 @@ pub(super) fn planet_picker<'a>(
 pub(super) fn surface_and_top_view_shared_control<'a>(
     time_since_epoch: Time,
     time_step: Time,
-    planets: Vec<&PlanetData>,
-    selected_planet: Option<&PlanetData>,
+    planet_picker_state: &'a combo_box::State<FocusChoice>,
+    planet_choices: &'a [FocusChoice],
+    selected_planet: Option<BodyId>,
     display_names: bool,
     display_constellations: bool,
+    galactic_motion: GalacticMotion,
+    local_solar_time: Option<f64>,
+    day_lengths: Option<DayLengths>,
+    calendar_date: Option<String>,
 ) -> Element<'a, GuiMessage> {
     let time_control_field = control_field(
         "Time since Epoch:",
@@ -118,6 +223,12 @@ pub(super) fn surface_and_top_view_shared_control<'a>(
         GuiMessage::UpdateTime(time_since_epoch + time_step),
     );
 
+    let redefine_epoch_button = std_button(
+        "Make Current Time the Epoch",
+        GuiMessage::OpenDialog(DialogType::RedefineEpoch),
+        time_since_epoch.value != 0.,
+    );
+
     let time_step_control_field = control_field(
         "Time step:",
         time_step.astro_display(),
@@ -125,7 +236,13 @@ pub(super) fn surface_and_top_view_shared_control<'a>(
         GuiMessage::UpdateTimeStep(time_step * 2.),
     );
 
-    let planet_picker = planet_picker(planets, selected_planet);
+    let planet_picker = planet_picker(
+        "Focused body:",
+        planet_picker_state,
+        planet_choices,
+        selected_planet,
+        GuiMessage::PlanetSelected,
+    );
 
     let display_names_toggle = Container::new(
         Toggler::new(display_names)
@@ -141,18 +258,263 @@ pub(super) fn surface_and_top_view_shared_control<'a>(
     )
     .width(Length::Fixed(1.5 * SMALL_COLUMN_WIDTH));
 
-    Column::new()
-        .push(time_control_field)
-        .push(time_step_control_field)
+    let galactic_motion_toggle = Container::new(
+        Toggler::new(galactic_motion.enabled)
+            .label("Galactic Motion")
+            .on_toggle(move |enabled| {
+                GuiMessage::SetGalacticMotion(GalacticMotion {
+                    enabled,
+                    ..galactic_motion
+                })
+            }),
+    )
+    .width(Length::Fixed(1.5 * SMALL_COLUMN_WIDTH));
+
+    let dispersion = galactic_motion.velocity_dispersion;
+    let velocity_dispersion_control_field = control_field(
+        "Velocity dispersion:",
+        dispersion.astro_display(),
+        GuiMessage::SetGalacticMotion(GalacticMotion {
+            velocity_dispersion: dispersion / 2.,
+            ..galactic_motion
+        }),
+        GuiMessage::SetGalacticMotion(GalacticMotion {
+            velocity_dispersion: dispersion * 2.,
+            ..galactic_motion
+        }),
+    );
+
+    let calendar_button = std_button(
+        "Calendar",
+        GuiMessage::OpenDialog(DialogType::Calendar),
+        true,
+    );
+
+    let mut col = Column::new().push(time_control_field);
+    if let Some(calendar_date) = calendar_date {
+        col = col.push(Text::new(calendar_date).shaping(Shaping::Advanced));
+    }
+    if let Some(local_solar_time) = local_solar_time {
+        col = col.push(local_solar_time_control(local_solar_time));
+    }
+    col = col
+        .push(
+            Row::new()
+                .push(redefine_epoch_button)
+                .push(calendar_button)
+                .spacing(PADDING),
+        )
+        .push(time_step_control_field);
+    if let Some(day_lengths) = day_lengths {
+        col = col.push(day_length_presets(time_since_epoch, day_lengths));
+    }
+    col = col
         .push(planet_picker)
         .push(display_names_toggle)
         .push(diplay_constellations_toggle)
+        .push(galactic_motion_toggle);
+    if galactic_motion.enabled {
+        col = col.push(velocity_dispersion_control_field);
+    }
+    col.width(Length::Fixed(BIG_COLUMN_WIDTH))
+        .align_x(Alignment::Center)
+        .spacing(PADDING)
+        .into()
+}
+
+/*
+ * Shows the day lengths of the selected planet, and allows to use them as time step.
+ */
+fn day_length_presets<'a>(
+    time_since_epoch: Time,
+    day_lengths: DayLengths,
+) -> Column<'a, GuiMessage> {
+    let mut col = Column::new().push(
+        Text::new(format!(
+            "Sidereal day: {}",
+            day_lengths.sidereal.astro_display()
+        ))
+        .shaping(Shaping::Advanced),
+    );
+    if let Some(mean_solar) = day_lengths.mean_solar {
+        col = col.push(
+            Text::new(format!("Mean solar day: {}", mean_solar.astro_display()))
+                .shaping(Shaping::Advanced),
+        );
+    }
+    let mut step_buttons = Row::new()
+        .push(std_button(
+            "Step: Sidereal Day",
+            GuiMessage::UpdateTimeStep(day_lengths.sidereal),
+            true,
+        ))
+        .spacing(PADDING);
+    if let Some(mean_solar) = day_lengths.mean_solar {
+        step_buttons = step_buttons.push(std_button(
+            "Step: Solar Day",
+            GuiMessage::UpdateTimeStep(mean_solar),
+            true,
+        ));
+    }
+    let advance_button = std_button(
+        "+1 Sidereal Day",
+        GuiMessage::UpdateTime(time_since_epoch + day_lengths.sidereal),
+        true,
+    );
+    col.push(step_buttons)
+        .push(advance_button)
+        .spacing(PADDING)
+        .align_x(Alignment::Center)
+}
+
+fn local_solar_time_control<'a>(local_solar_time: f64) -> Column<'a, GuiMessage> {
+    let label = Text::new("Local solar time:")
+        .align_x(Horizontal::Right)
+        .width(Length::Fixed(SMALL_COLUMN_WIDTH));
+    let value = Text::new(format_local_time(local_solar_time))
+        .align_x(Horizontal::Center)
+        .width(Length::Fixed(1.25 * SMALL_COLUMN_WIDTH));
+    let buttons = Row::new()
+        .push(std_button(
+            "+1 Local Day",
+            GuiMessage::AdvanceLocalDay,
+            true,
+        ))
+        .push(std_button(
+            "To Local Midnight",
+            GuiMessage::AdvanceToLocalMidnight,
+            true,
+        ))
+        .spacing(PADDING);
+    Column::new()
+        .push(
+            Row::new()
+                .push(label)
+                .push(value)
+                .spacing(PADDING)
+                .align_y(Alignment::Center),
+        )
+        .push(buttons)
+        .spacing(PADDING)
+        .align_x(Alignment::Center)
+}
+
+pub(super) fn label_style_control<'a>(label_style: LabelStyle) -> Element<'a, GuiMessage> {
+    let size_control_field = control_field(
+        "Label size:",
+        format!("{:.0} px", label_style.size),
+        GuiMessage::SetLabelStyle(label_style.with_size(label_style.size - LabelStyle::SIZE_STEP)),
+        GuiMessage::SetLabelStyle(label_style.with_size(label_style.size + LabelStyle::SIZE_STEP)),
+    );
+
+    let offset_control_field = control_field(
+        "Label offset:",
+        format!("{:.0} px", label_style.offset),
+        GuiMessage::SetLabelStyle(
+            label_style.with_offset(label_style.offset - LabelStyle::OFFSET_STEP),
+        ),
+        GuiMessage::SetLabelStyle(
+            label_style.with_offset(label_style.offset + LabelStyle::OFFSET_STEP),
+        ),
+    );
+
+    let follow_body_color_toggle = Container::new(
+        Toggler::new(label_style.follow_body_color)
+            .label("Label in Body Color")
+            .on_toggle(move |b| GuiMessage::SetLabelStyle(label_style.with_follow_body_color(b))),
+    )
+    .width(Length::Fixed(1.5 * SMALL_COLUMN_WIDTH));
+
+    let catalog_names_toggle = Container::new(
+        Toggler::new(label_style.show_catalog_names)
+            .label("Label Catalog Stars")
+            .on_toggle(move |b| GuiMessage::SetLabelStyle(label_style.with_show_catalog_names(b))),
+    )
+    .width(Length::Fixed(1.5 * SMALL_COLUMN_WIDTH));
+
+    Column::new()
+        .push(size_control_field)
+        .push(offset_control_field)
+        .push(follow_body_color_toggle)
+        .push(catalog_names_toggle)
         .width(Length::Fixed(BIG_COLUMN_WIDTH))
         .align_x(Alignment::Center)
         .spacing(PADDING)
         .into()
 }
 
+pub(super) fn canvas_background_control<'a>(style: &BackgroundStyle) -> Element<'a, GuiMessage> {
+    let picker = PickList::new(
+        BackgroundChoice::ALL,
+        Some(style.choice()),
+        |choice| match choice {
+            BackgroundChoice::Black => GuiMessage::SetCanvasBackground(BackgroundStyle::Black),
+            BackgroundChoice::DeepBlue => {
+                GuiMessage::SetCanvasBackground(BackgroundStyle::DeepBlue)
+            }
+            BackgroundChoice::Noise => GuiMessage::SetCanvasBackground(BackgroundStyle::Noise),
+            BackgroundChoice::Image => GuiMessage::PickBackgroundImage,
+        },
+    )
+    .width(Length::Fixed(SMALL_COLUMN_WIDTH));
+    Column::new()
+        .push(Text::new("Background"))
+        .push(picker)
+        .width(Length::Fixed(SMALL_COLUMN_WIDTH))
+        .align_x(Alignment::Center)
+        .spacing(PADDING)
+        .into()
+}
+
+pub(crate) fn star_merge_mode_control<'a, F>(
+    mode: StarMergeMode,
+    existing_star_count: usize,
+    incoming_star_count: Option<usize>,
+    on_select: F,
+) -> Element<'a, GuiMessage>
+where
+    F: 'a + Copy + Fn(StarMergeMode) -> GuiMessage,
+{
+    let mut radio_row = Row::new().padding(PADDING).spacing(PADDING);
+    for option in StarMergeMode::ALL {
+        radio_row = radio_row.push(
+            Radio::new(option.to_string(), option, Some(mode), on_select).width(SMALL_COLUMN_WIDTH),
+        );
+    }
+    let preview = Text::new(mode.preview(existing_star_count, incoming_star_count));
+    Column::new()
+        .push(Text::new("Existing Stars"))
+        .push(radio_row)
+        .push(preview)
+        .align_x(Alignment::Center)
+        .into()
+}
+
+/*
+ * The label of a control field, as it reads inside of a sentence.
+ */
+fn describe(label: &str) -> String {
+    label.trim_end_matches(':').to_lowercase()
+}
+
+/*
+ * Buttons that only show a symbol get a tooltip, so that their purpose is clear without the surrounding layout.
+ */
+pub(crate) fn described_symbol_button<'a>(
+    symbol: &'a str,
+    description: String,
+    message: GuiMessage,
+) -> Element<'a, GuiMessage> {
+    tooltip(
+        Button::new(Text::new(symbol)).on_press(message),
+        Container::new(Text::new(description).shaping(Shaping::Advanced))
+            .padding(PADDING)
+            .style(container::rounded_box),
+        tooltip::Position::Bottom,
+    )
+    .into()
+}
+
 pub(crate) fn control_field<M>(
     label: &str,
     value: String,
@@ -162,20 +524,74 @@ pub(crate) fn control_field<M>(
 where
     M: Into<GuiMessage>,
 {
+    let description = describe(label);
     let label = Text::new(label)
         .align_y(Vertical::Center)
         .align_x(Horizontal::Right)
         .width(Length::Fixed(SMALL_COLUMN_WIDTH));
-    let decrease_button = Container::new(Button::new(Text::new("<<")).on_press(decrease.into()))
-        .align_x(Horizontal::Center)
-        .width(Length::Fixed(0.25 * SMALL_COLUMN_WIDTH));
+    let decrease_button = Container::new(described_symbol_button(
+        "<<",
+        format!("Decrease {}", description),
+        decrease.into(),
+    ))
+    .align_x(Horizontal::Center)
+    .width(Length::Fixed(0.25 * SMALL_COLUMN_WIDTH));
     let value = Text::new(value)
         .shaping(Shaping::Advanced)
         .width(Length::Fixed(0.75 * SMALL_COLUMN_WIDTH))
         .align_x(Horizontal::Center);
-    let increase_button = Container::new(Button::new(Text::new(">>")).on_press(increase.into()))
-        .align_x(Horizontal::Center)
-        .width(Length::Fixed(0.25 * SMALL_COLUMN_WIDTH));
+    let increase_button = Container::new(described_symbol_button(
+        ">>",
+        format!("Increase {}", description),
+        increase.into(),
+    ))
+    .align_x(Horizontal::Center)
+    .width(Length::Fixed(0.25 * SMALL_COLUMN_WIDTH));
+    Row::new()
+        .push(label)
+        .push(decrease_button)
+        .push(value)
+        .push(increase_button)
+        .spacing(PADDING)
+        .align_y(Alignment::Center)
+}
+
+pub(crate) fn editable_control_field<'a, M, F>(
+    label: &'a str,
+    value: &'a str,
+    on_input: F,
+    on_submit: M,
+    decrease: M,
+    increase: M,
+) -> Row<'a, GuiMessage>
+where
+    M: Into<GuiMessage>,
+    F: 'a + Fn(String) -> M,
+{
+    let description = describe(label);
+    let label = Text::new(label)
+        .align_y(Vertical::Center)
+        .align_x(Horizontal::Right)
+        .width(Length::Fixed(SMALL_COLUMN_WIDTH));
+    let decrease_button = Container::new(described_symbol_button(
+        "<<",
+        format!("Decrease {}", description),
+        decrease.into(),
+    ))
+    .align_x(Horizontal::Center)
+    .width(Length::Fixed(0.25 * SMALL_COLUMN_WIDTH));
+    let value = TextInput::new("", value)
+        .on_input(move |text| on_input(text).into())
+        .on_submit(on_submit.into())
+        .width(Length::Fixed(0.75 * SMALL_COLUMN_WIDTH))
+        .align_x(Horizontal::Center);
+    let increase_button = Container::new(described_symbol_button(
+        ">>",
+        format!("Increase {}", description),
+        increase.into(),
+    ))
+    .align_x(Horizontal::Center)
+    .width(Length::Fixed(0.25 * SMALL_COLUMN_WIDTH));
     Row::new()
         .push(label)
         .push(decrease_button)
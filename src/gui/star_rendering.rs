@@ -0,0 +1,333 @@
+use astro_coords::{
+    cartesian::Cartesian, direction::Direction, traits::*,
+    transformations::rotations::get_rotation_parameters,
+};
+use astro_units::illuminance::{
+    apparent_magnitude_to_illuminance, illuminance_to_apparent_magnitude, lux, Illuminance,
+};
+use astro_utils::real_data::stars::all::get_many_stars;
+use iced::Vector;
+use serde::{Deserialize, Serialize};
+use uom::si::{
+    f64::{Length, Time},
+    length::light_year,
+    time::year,
+};
+
+use crate::config;
+
+const REFERENCE_FIELD_CONSTELLATIONS: [&str; 2] = ["Orion", "Taurus"];
+
+const FILE_NAME: &str = "star_rendering.json";
+
+/*
+ * How the illuminance of a body translates into the size and opacity of the disk it is drawn as.
+ * Bodies brighter than the illuminance at the minimum radius grow with the radius exponent, fainter ones fade with the alpha exponent.
+ * The defaults were fitted to the reference picture, and are remembered across sessions once calibrated.
+ * Settings that are missing from an older configuration file take their default.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(default)]
+pub(crate) struct StarRendering {
+    pub(crate) radius_exponent: f32,
+    pub(crate) alpha_exponent: f32,
+    pub(crate) min_radius: f32,
+    lux_at_min_radius: f64,
+}
+
+impl Default for StarRendering {
+    fn default() -> Self {
+        StarRendering {
+            radius_exponent: 0.23,
+            alpha_exponent: 0.75,
+            min_radius: 1.5,
+            lux_at_min_radius: 8e-8,
+        }
+    }
+}
+
+impl StarRendering {
+    pub(crate) const EXPONENT_RANGE: (f32, f32) = (0.05, 1.5);
+    pub(crate) const MIN_RADIUS_RANGE: (f32, f32) = (0.5, 5.);
+    pub(crate) const MAGNITUDE_AT_MIN_RADIUS_RANGE: (f32, f32) = (-2., 10.);
+
+    fn illuminance_at_min_radius(&self) -> Illuminance {
+        Illuminance::new::<lux>(self.lux_at_min_radius)
+    }
+
+    /*
+     * The illuminance at which bodies stop fading and start growing, expressed as an apparent magnitude for the user.
+     */
+    pub(crate) fn magnitude_at_min_radius(&self) -> f64 {
+        illuminance_to_apparent_magnitude(self.illuminance_at_min_radius())
+    }
+
+    pub(crate) fn with_magnitude_at_min_radius(self, magnitude: f64) -> Self {
+        StarRendering {
+            lux_at_min_radius: apparent_magnitude_to_illuminance(magnitude).get::<lux>(),
+            ..self
+        }
+    }
+
+    pub(crate) fn load() -> StarRendering {
+        config::load(FILE_NAME)
+    }
+
+    pub(crate) fn save(&self) -> Result<(), std::io::Error> {
+        config::save(FILE_NAME, self)
+    }
+
+    /*
+     * The radius in pixels and the opacity of a body with the given illuminance.
+     */
+    pub(crate) fn radius_and_alpha(&self, illuminance: Illuminance) -> (f32, f32) {
+        let ratio = (illuminance / self.illuminance_at_min_radius()).value as f32;
+        if ratio < 1. {
+            (self.min_radius, ratio.powf(self.alpha_exponent))
+        } else {
+            (ratio.powf(self.radius_exponent) * self.min_radius, 1.)
+        }
+    }
+}
+
+/*
+ * Stars as they appear in a photograph of Orion and Taurus, in which the faintest stars are 1.5 pixels in radius.
+ */
+pub(crate) struct ReferenceStar {
+    pub(crate) name: &'static str,
+    pub(crate) magnitude: f64,
+    pub(crate) diameter: i32,
+    pub(crate) alpha: f32,
+}
+
+pub(crate) const REFERENCE_PICTURE_MIN_RADIUS: f32 = 1.5;
+
+pub(crate) const REFERENCE_PICTURE: [ReferenceStar; 20] = [
+    ReferenceStar {
+        name: "Elnath",
+        magnitude: 1.65,
+        diameter: 5,
+        alpha: 1.,
+    },
+    ReferenceStar {
+        name: "Zeta Tauri",
+        magnitude: 3.01,
+        diameter: 4,
+        alpha: 1.,
+    },
+    ReferenceStar {
+        name: "Epsilon Tauri",
+        magnitude: 3.53,
+        diameter: 3,
+        alpha: 0.87,
+    },
+    ReferenceStar {
+        name: "Aldebaran",
+        magnitude: 0.87,
+        diameter: 6,
+        alpha: 1.,
+    },
+    ReferenceStar {
+        name: "Gamma Tauri",
+        magnitude: 3.65,
+        diameter: 3,
+        alpha: 0.67,
+    },
+    ReferenceStar {
+        name: "Meissa",
+        magnitude: 3.47,
+        diameter: 3,
+        alpha: 0.92,
+    },
+    ReferenceStar {
+        name: "Betelgeuse",
+        magnitude: 0.42,
+        diameter: 6,
+        alpha: 1.,
+    },
+    ReferenceStar {
+        name: "Bellatrix",
+        magnitude: 1.64,
+        diameter: 5,
+        alpha: 1.,
+    },
+    ReferenceStar {
+        name: "Pi1 Ori",
+        magnitude: 4.74,
+        diameter: 3,
+        alpha: 0.10,
+    },
+    ReferenceStar {
+        name: "Pi2 Ori",
+        magnitude: 4.35,
+        diameter: 3,
+        alpha: 0.20,
+    },
+    ReferenceStar {
+        name: "Pi3 Ori",
+        magnitude: 3.16,
+        diameter: 3,
+        alpha: 0.90,
+    },
+    ReferenceStar {
+        name: "Pi4 Ori",
+        magnitude: 3.69,
+        diameter: 3,
+        alpha: 0.75,
+    },
+    ReferenceStar {
+        name: "Pi5 Ori",
+        magnitude: 3.69,
+        diameter: 3,
+        alpha: 0.70,
+    },
+    ReferenceStar {
+        name: "Pi6 Ori",
+        magnitude: 4.47,
+        diameter: 3,
+        alpha: 0.30,
+    },
+    ReferenceStar {
+        name: "Alnitak",
+        magnitude: 1.88,
+        diameter: 5,
+        alpha: 1.,
+    },
+    ReferenceStar {
+        name: "Alnilam",
+        magnitude: 1.69,
+        diameter: 5,
+        alpha: 1.,
+    },
+    ReferenceStar {
+        name: "Mintaka",
+        magnitude: 2.20,
+        diameter: 4,
+        alpha: 1.,
+    },
+    ReferenceStar {
+        name: "Saiph",
+        magnitude: 2.07,
+        diameter: 5,
+        alpha: 1.,
+    },
+    ReferenceStar {
+        name: "Riegel",
+        magnitude: 0.18,
+        diameter: 7,
+        alpha: 1.,
+    },
+    ReferenceStar {
+        name: "Sirius",
+        magnitude: -1.46,
+        diameter: 10,
+        alpha: 1.,
+    },
+];
+
+/*
+ * A bundled star of the reference field, at its offset from the centre of the field on a plane tangent to the sky.
+ * The offset is in radians near the centre, with east to the left and north up as on a photograph of the sky.
+ */
+#[derive(Debug)]
+pub(crate) struct FieldStar {
+    pub(crate) offset: Vector<f64>,
+    pub(crate) illuminance: Illuminance,
+}
+
+/*
+ * The bundled stars of the constellations in the reference picture, so that a photograph of them can be laid over.
+ */
+pub(crate) fn reference_field() -> Vec<FieldStar> {
+    let appearances: Vec<_> = get_many_stars()
+        .iter()
+        .map(|star| star.to_star_data())
+        .filter(|data| {
+            data.get_constellation()
+                .as_ref()
+                .is_some_and(|constellation| {
+                    REFERENCE_FIELD_CONSTELLATIONS.contains(&constellation.as_str())
+                })
+        })
+        .map(|data| data.to_star_appearance(Time::new::<year>(0.)))
+        .collect();
+    let sum = appearances
+        .iter()
+        .map(|appearance| {
+            appearance
+                .get_pos()
+                .to_direction()
+                .to_cartesian(Length::new::<light_year>(1.))
+        })
+        .fold(Cartesian::origin(), |sum, position| sum + position);
+    let Ok(center) = sum.to_direction() else {
+        return vec![];
+    };
+    let (angle, rotation_axis) = get_rotation_parameters(&Direction::Z, &center);
+    appearances
+        .iter()
+        .filter_map(|appearance| {
+            let local = appearance
+                .get_pos()
+                .to_direction()
+                .rotated(-angle, &rotation_axis);
+            if local.z() <= 0. {
+                return None;
+            }
+            Some(FieldStar {
+                offset: Vector::new(-local.x() / local.z(), -local.y() / local.z()),
+                illuminance: appearance.get_illuminance(),
+            })
+        })
+        .collect()
+}
+
+impl ReferenceStar {
+    /*
+     * The radius the star would have in the picture, if its faintest stars had the given minimum radius.
+     */
+    pub(crate) fn radius(&self, min_radius: f32) -> f32 {
+        self.diameter as f32 / 2. * min_radius / REFERENCE_PICTURE_MIN_RADIUS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_saved_before_the_constants_were_tunable_still_load() {
+        let rendering: StarRendering =
+            serde_json::from_str(r#"{"radius_exponent": 0.3, "alpha_exponent": 0.5}"#).unwrap();
+        assert_eq!(rendering.radius_exponent, 0.3);
+        assert_eq!(rendering.alpha_exponent, 0.5);
+        assert_eq!(rendering.min_radius, StarRendering::default().min_radius);
+        assert_eq!(
+            rendering.lux_at_min_radius,
+            StarRendering::default().lux_at_min_radius
+        );
+    }
+
+    #[test]
+    fn the_reference_field_is_centred() {
+        let field = reference_field();
+        assert!(field.len() > 5);
+        let mean_x = field.iter().map(|star| star.offset.x).sum::<f64>() / field.len() as f64;
+        let mean_y = field.iter().map(|star| star.offset.y).sum::<f64>() / field.len() as f64;
+        assert!(mean_x.abs() < 0.1);
+        assert!(mean_y.abs() < 0.1);
+    }
+
+    #[test]
+    fn a_body_at_the_threshold_is_drawn_opaque_with_the_minimum_radius() {
+        let rendering = StarRendering {
+            min_radius: 3.,
+            ..StarRendering::default()
+        }
+        .with_magnitude_at_min_radius(2.);
+        let (radius, alpha) = rendering.radius_and_alpha(apparent_magnitude_to_illuminance(2.));
+        assert!((radius - 3.).abs() < 1e-3);
+        assert!((alpha - 1.).abs() < 1e-3);
+        assert!((rendering.magnitude_at_min_radius() - 2.).abs() < 1e-6);
+    }
+}
@@ -1,12 +1,17 @@
 use astro_coords::{cartesian::Cartesian, direction::Direction, spherical::Spherical};
-use iced::{widget::canvas, Rectangle, Renderer};
+use iced::{widget::canvas, Rectangle, Renderer, Size};
+use uom::si::f64::Angle;
 
 use crate::{
-    gui::shared_canvas_functionality::{display_info_text, draw_background},
+    gui::{
+        canvas_background::CanvasBackground,
+        shared_canvas_functionality::{display_info_text, draw_background, LabelStyle},
+    },
     model::{celestial_system::CelestialSystem, planet::Planet},
 };
 
 use super::{
+    canvas_appearance::CanvasAppearance,
     viewport::{observer_normal, Viewport},
     widget::SurfaceViewState,
 };
@@ -21,6 +26,78 @@ impl SurfaceViewState {
         selected_planet.get_position().clone() + observer_normal.to_cartesian(body_radius)
     }
 
+    fn observer_normal(
+        &self,
+        selected_planet: &Planet,
+        celestial_system: &CelestialSystem,
+    ) -> Direction {
+        let surface_position = Spherical::new(self.surface_longitude, self.surface_latitude);
+        observer_normal(
+            selected_planet.get_data(),
+            selected_planet.get_rotation_angle_at_epoch(),
+            surface_position,
+            celestial_system.get_time_since_epoch(),
+        )
+    }
+
+    pub(super) fn viewport(
+        &self,
+        selected_planet: &Planet,
+        observer_normal: &Direction,
+        bounds: Rectangle,
+    ) -> Viewport {
+        let observer_view_direction = Spherical::new(self.view_longitude, self.view_latitude);
+        Viewport::calculate(
+            observer_normal,
+            &observer_view_direction,
+            self.viewport_opening_angle,
+            selected_planet.get_data().get_rotation_axis(),
+            bounds,
+            self.star_rendering,
+        )
+    }
+
+    pub(crate) fn field_of_view(&self, canvas_size: Size) -> (Angle, Angle) {
+        Viewport::field_of_view(self.viewport_opening_angle, canvas_size)
+    }
+
+    /*
+     * The viewport as it is drawn for the given canvas size, for relating canvas points to the sky.
+     */
+    pub(super) fn viewport_for_canvas(
+        &self,
+        selected_planet: &Planet,
+        celestial_system: &CelestialSystem,
+        canvas_size: Size,
+    ) -> Viewport {
+        let observer_normal = self.observer_normal(selected_planet, celestial_system);
+        self.viewport(
+            selected_planet,
+            &observer_normal,
+            Rectangle::with_size(canvas_size),
+        )
+    }
+
+    /*
+     * Projects the distant stars onto the canvas like drawing does, and returns how many of them land in the sky.
+     * Only the rasterisation is left out, so that the cost of a frame can be measured without a window.
+     */
+    pub(crate) fn project_stars(
+        &self,
+        selected_planet: &Planet,
+        celestial_system: &CelestialSystem,
+        canvas_size: Size,
+    ) -> usize {
+        let viewport = self.viewport_for_canvas(selected_planet, celestial_system, canvas_size);
+        celestial_system
+            .get_distant_stars()
+            .iter()
+            .filter_map(|star| {
+                CanvasAppearance::from_star_appearance(&star.get_displayed_appearance(), &viewport)
+            })
+            .count()
+    }
+
     pub(crate) fn canvas(
         &self,
         renderer: &Renderer,
@@ -29,11 +106,13 @@ impl SurfaceViewState {
         celestial_system: &Option<CelestialSystem>,
         display_names: bool,
         display_constellations: bool,
+        label_style: &LabelStyle,
+        background: &CanvasBackground,
     ) -> Vec<canvas::Geometry> {
         let background = self
             .background_cache
             .draw(renderer, bounds.size(), |frame| {
-                draw_background(bounds, frame);
+                draw_background(bounds, frame, background);
             });
 
         let bodies = self.bodies_cache.draw(renderer, bounds.size(), |frame| {
@@ -46,6 +125,7 @@ impl SurfaceViewState {
                         celestial_system,
                         display_names,
                         display_constellations,
+                        label_style,
                     );
                 } else {
                     display_info_text(frame, "Please select a planet.");
@@ -66,22 +146,15 @@ impl SurfaceViewState {
         celestial_system: &CelestialSystem,
         display_names: bool,
         display_constellations: bool,
+        label_style: &LabelStyle,
     ) {
-        let surface_position = Spherical::new(self.surface_longitude, self.surface_latitude);
-        let observer_normal = observer_normal(
-            selected_planet.get_data(),
-            surface_position,
-            celestial_system.get_time_since_epoch(),
-        );
+        let observer_normal = self.observer_normal(selected_planet, celestial_system);
         let observer_position = self.observer_position(selected_planet, &observer_normal);
-        let observer_view_direction = Spherical::new(self.view_longitude, self.view_latitude);
-        let viewport = Viewport::calculate(
-            &observer_normal,
-            &observer_view_direction,
-            self.viewport_opening_angle,
-            selected_planet.get_data().get_rotation_axis(),
-            bounds,
-        );
+        let viewport = self.viewport(selected_planet, &observer_normal, bounds);
+
+        if self.display_density {
+            self.draw_density(frame, celestial_system, &viewport);
+        }
 
         self.draw_bodies(
             frame,
@@ -89,12 +162,17 @@ impl SurfaceViewState {
             selected_planet,
             celestial_system,
             display_names,
+            label_style,
             &viewport,
             &observer_position,
         );
 
+        if self.path_body.is_some() {
+            self.draw_path(frame, bounds, selected_planet, celestial_system, &viewport);
+        }
+
         if display_constellations {
-            self.draw_constellations(frame, bounds, celestial_system, &viewport);
+            self.draw_constellations(frame, bounds, celestial_system, &viewport, label_style);
         }
     }
 }
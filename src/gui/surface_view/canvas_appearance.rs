@@ -1,11 +1,14 @@
 use astro_coords::{
-    cartesian::Cartesian, transformations::relative_direction::direction_relative_to_normal,
+    cartesian::Cartesian, direction::Direction,
+    transformations::relative_direction::direction_relative_to_normal,
 };
-use astro_units::illuminance::{lux, Illuminance};
 use astro_utils::{color::srgb::sRGBColor, stars::appearance::StarAppearance};
 use iced::{Color, Vector};
 
-use crate::model::{celestial_system::CelestialSystem, planet::Planet};
+use crate::{
+    gui::star_rendering::StarRendering,
+    model::{celestial_system::CelestialSystem, planet::Planet},
+};
 
 use super::viewport::Viewport;
 
@@ -17,21 +20,19 @@ pub(super) struct CanvasAppearance {
 }
 
 impl CanvasAppearance {
-    pub(super) const MIN_RADIUS: f32 = 1.5;
     const MAX_RADIUS: f32 = 1e5;
-    const RADIUS_EXPONENT: f32 = 0.23;
-    const ALPHA_EXPONENT: f32 = 0.75;
-
-    #[inline(always)]
-    fn illuminance_at_min_radius() -> Illuminance {
-        Illuminance::new::<lux>(8e-8)
-    }
 
+    /*
+     * An appearance with an undefined brightness is not drawn, instead of as a huge blot.
+     */
     pub(super) fn from_star_appearance(
         appearance: &StarAppearance,
         viewport: &Viewport,
     ) -> Option<CanvasAppearance> {
-        let (color, radius) = Self::color_and_radius(appearance);
+        if !appearance.get_illuminance().value.is_finite() {
+            return None;
+        }
+        let (color, radius) = Self::color_and_radius(appearance, &viewport.star_rendering);
         Some(Self {
             name: appearance.get_name().to_string(),
             center_offset: offset(appearance, viewport)?,
@@ -72,35 +73,44 @@ impl CanvasAppearance {
         CanvasAppearance::from_star_appearance(&planet_appearance, viewport)
     }
 
-    fn color_and_radius(body: &StarAppearance) -> (Color, f32) {
+    /*
+     * Tints the color towards red for positive shifts and towards blue for negative ones.
+     * The shift is a velocity in units of the speed of light, usually exaggerated to be visible at all.
+     */
+    pub(super) fn doppler_tinted(mut self, shift: f64) -> Self {
+        const RED: Color = Color::from_rgb(1., 0.2, 0.1);
+        const BLUE: Color = Color::from_rgb(0.3, 0.5, 1.);
+        let target = if shift > 0. { RED } else { BLUE };
+        let t = shift.abs().min(1.) as f32;
+        self.color = Color::from_rgba(
+            self.color.r + (target.r - self.color.r) * t,
+            self.color.g + (target.g - self.color.g) * t,
+            self.color.b + (target.b - self.color.b) * t,
+            self.color.a,
+        );
+        self
+    }
+
+    fn color_and_radius(body: &StarAppearance, rendering: &StarRendering) -> (Color, f32) {
         const WHITE: sRGBColor = sRGBColor::from_sRGB(1., 1., 1.);
         let color = body.get_color();
         let (r, g, b) = color.maximized_sRGB_tuple();
         let color = &sRGBColor::from_sRGB(r, g, b) + &WHITE;
         let (r, g, b) = color.maximized_sRGB_tuple();
 
-        let illuminance = body.get_illuminance();
-        let ratio = (illuminance / Self::illuminance_at_min_radius()).value as f32;
-        if ratio < 1. {
-            let radius = Self::MIN_RADIUS;
-            let alpha = ratio.powf(Self::ALPHA_EXPONENT);
-            let color = Color::from_rgba(r as f32, g as f32, b as f32, alpha);
-            (color, radius)
-        } else {
-            let radius = ratio.powf(Self::RADIUS_EXPONENT) * Self::MIN_RADIUS;
-            let color = Color::from_rgb(r as f32, g as f32, b as f32);
-            if radius > Self::MAX_RADIUS {
-                (color, Self::MAX_RADIUS)
-            } else {
-                (color, radius)
-            }
-        }
+        let (radius, alpha) = rendering.radius_and_alpha(body.get_illuminance());
+        let color = Color::from_rgba(r as f32, g as f32, b as f32, alpha);
+        (color, radius.min(Self::MAX_RADIUS))
     }
 }
 
 fn offset(appearance: &StarAppearance, viewport: &Viewport) -> Option<Vector> {
+    canvas_offset(&appearance.get_pos().to_direction(), viewport)
+}
+
+pub(super) fn canvas_offset(direction: &Direction, viewport: &Viewport) -> Option<Vector> {
     let direction = direction_relative_to_normal(
-        &appearance.get_pos().to_direction(),
+        direction,
         &viewport.center_direction,
         &viewport.top_direction,
     );
@@ -117,7 +127,9 @@ fn offset(appearance: &StarAppearance, viewport: &Viewport) -> Option<Vector> {
 mod tests {
     use astro_coords::{direction::Direction, ecliptic::Ecliptic, traits::*};
     use astro_units::{
-        illuminance::apparent_magnitude_to_illuminance, length::earth_radius, mass::earth_mass,
+        illuminance::{apparent_magnitude_to_illuminance, lux, Illuminance},
+        length::earth_radius,
+        mass::earth_mass,
     };
     use astro_utils::{
         astro_display::AstroDisplay,
@@ -136,6 +148,7 @@ mod tests {
     };
 
     use super::*;
+    use crate::gui::star_rendering::REFERENCE_PICTURE;
 
     fn some_illuminance() -> Illuminance {
         Illuminance::new::<lux>(100.)
@@ -163,6 +176,7 @@ mod tests {
                         center_direction: center_direction.clone(),
                         top_direction,
                         px_per_distance: SOME_FLOAT,
+                        star_rendering: StarRendering::default(),
                     };
                     let star_appearance = StarAppearance::new(
                         String::new(),
@@ -216,6 +230,7 @@ mod tests {
                                     center_direction: center.clone(),
                                     top_direction: top.clone(),
                                     px_per_distance: SOME_FLOAT,
+                                    star_rendering: StarRendering::default(),
                                 };
                                 let half_opening_angle = center.angle_to(&top);
                                 if half_opening_angle.get::<degree>().abs() > 89. {
@@ -321,6 +336,7 @@ mod tests {
             center_direction: Direction::Z,
             top_direction: Direction::Y,
             px_per_distance: SOME_FLOAT,
+            star_rendering: StarRendering::default(),
         };
         for x in [-0.1, 0.1] {
             for y in [-0.1, 0.1] {
@@ -356,6 +372,7 @@ mod tests {
             center_direction: Direction::X,
             top_direction: Direction::Z,
             px_per_distance: SOME_FLOAT,
+            star_rendering: StarRendering::default(),
         };
         for y in [-0.1, 0.1] {
             for z in [-0.1, 0.1] {
@@ -385,6 +402,23 @@ mod tests {
         }
     }
 
+    #[test]
+    fn receding_stars_are_tinted_red() {
+        let appearance = CanvasAppearance {
+            name: String::new(),
+            center_offset: Vector::new(0., 0.),
+            radius: 1.,
+            color: Color::from_rgba(1., 1., 1., 0.5),
+        };
+        let unchanged = appearance.doppler_tinted(0.);
+        assert!((unchanged.color.b - 1.).abs() < 1e-6);
+        let receding = unchanged.doppler_tinted(0.5);
+        assert!(receding.color.r > receding.color.b);
+        assert!((receding.color.a - 0.5).abs() < 1e-6);
+        let approaching = receding.doppler_tinted(-10.);
+        assert!(approaching.color.b > approaching.color.r);
+    }
+
     #[test]
     fn apparent_magnitude_6p5_star_is_dim() {
         let star_appearance = StarAppearance::new(
@@ -398,6 +432,7 @@ mod tests {
             center_direction: Direction::X,
             top_direction: Direction::Y,
             px_per_distance: SOME_FLOAT,
+            star_rendering: StarRendering::default(),
         };
         let canvas_appearance =
             CanvasAppearance::from_star_appearance(&star_appearance, &viewport).unwrap();
@@ -420,6 +455,7 @@ mod tests {
             center_direction: Direction::X,
             top_direction: Direction::Y,
             px_per_distance: SOME_FLOAT,
+            star_rendering: StarRendering::default(),
         };
         let canvas_appearance =
             CanvasAppearance::from_star_appearance(&star_appearance, &viewport).unwrap();
@@ -441,6 +477,7 @@ mod tests {
             center_direction: Direction::X,
             top_direction: Direction::Y,
             px_per_distance: SOME_FLOAT,
+            star_rendering: StarRendering::default(),
         };
         let canvas_appearance =
             CanvasAppearance::from_star_appearance(&star_appearance, &viewport).unwrap();
@@ -462,6 +499,7 @@ mod tests {
             center_direction: Direction::X,
             top_direction: Direction::Y,
             px_per_distance: SOME_FLOAT,
+            star_rendering: StarRendering::default(),
         };
         let canvas_appearance =
             CanvasAppearance::from_star_appearance(&star_appearance, &viewport).unwrap();
@@ -471,139 +509,10 @@ mod tests {
 
     #[test]
     fn recreating_picture_appearance() {
-        const PICTURE_MIN_RADIUS: f32 = 1.5;
-        struct PictureStar {
-            name: &'static str,
-            magnitude: f64,
-            diameter: i32,
-            alpha: f32,
-        }
-        let picture_stars = vec![
-            PictureStar {
-                name: "Elnath",
-                magnitude: 1.65,
-                diameter: 5,
-                alpha: 1.,
-            },
-            PictureStar {
-                name: "Zeta Tauri",
-                magnitude: 3.01,
-                diameter: 4,
-                alpha: 1.,
-            },
-            PictureStar {
-                name: "Epsilon Tauri",
-                magnitude: 3.53,
-                diameter: 3,
-                alpha: 0.87,
-            },
-            PictureStar {
-                name: "Aldebaran",
-                magnitude: 0.87,
-                diameter: 6,
-                alpha: 1.,
-            },
-            PictureStar {
-                name: "Gamma Tauri",
-                magnitude: 3.65,
-                diameter: 3,
-                alpha: 0.67,
-            },
-            PictureStar {
-                name: "Meissa",
-                magnitude: 3.47,
-                diameter: 3,
-                alpha: 0.92,
-            },
-            PictureStar {
-                name: "Betelgeuse",
-                magnitude: 0.42,
-                diameter: 6,
-                alpha: 1.,
-            },
-            PictureStar {
-                name: "Bellatrix",
-                magnitude: 1.64,
-                diameter: 5,
-                alpha: 1.,
-            },
-            PictureStar {
-                name: "Pi1 Ori",
-                magnitude: 4.74,
-                diameter: 3,
-                alpha: 0.10,
-            },
-            PictureStar {
-                name: "Pi2 Ori",
-                magnitude: 4.35,
-                diameter: 3,
-                alpha: 0.20,
-            },
-            PictureStar {
-                name: "Pi3 Ori",
-                magnitude: 3.16,
-                diameter: 3,
-                alpha: 0.90,
-            },
-            PictureStar {
-                name: "Pi4 Ori",
-                magnitude: 3.69,
-                diameter: 3,
-                alpha: 0.75,
-            },
-            PictureStar {
-                name: "Pi5 Ori",
-                magnitude: 3.69,
-                diameter: 3,
-                alpha: 0.70,
-            },
-            PictureStar {
-                name: "Pi6 Ori",
-                magnitude: 4.47,
-                diameter: 3,
-                alpha: 0.30,
-            },
-            PictureStar {
-                name: "Alnitak",
-                magnitude: 1.88,
-                diameter: 5,
-                alpha: 1.,
-            },
-            PictureStar {
-                name: "Alnilam",
-                magnitude: 1.69,
-                diameter: 5,
-                alpha: 1.,
-            },
-            PictureStar {
-                name: "Mintaka",
-                magnitude: 2.20,
-                diameter: 4,
-                alpha: 1.,
-            },
-            PictureStar {
-                name: "Saiph",
-                magnitude: 2.07,
-                diameter: 5,
-                alpha: 1.,
-            },
-            PictureStar {
-                name: "Riegel",
-                magnitude: 0.18,
-                diameter: 7,
-                alpha: 1.,
-            },
-            PictureStar {
-                name: "Sirius",
-                magnitude: -1.46,
-                diameter: 10,
-                alpha: 1.,
-            },
-        ];
         let accuracy = 0.5;
 
         let mut failures = 0;
-        for picture_star in picture_stars.iter() {
+        for picture_star in REFERENCE_PICTURE.iter() {
             let illuminance = apparent_magnitude_to_illuminance(picture_star.magnitude);
             let star_appearance = StarAppearance::new(
                 picture_star.name.to_string(),
@@ -612,9 +521,9 @@ mod tests {
                 Ecliptic::x_direction(),
                 Time::new::<year>(0.),
             );
-            let (color, radius) = CanvasAppearance::color_and_radius(&star_appearance);
-            let expected_radius = picture_star.diameter as f32 / 2. * CanvasAppearance::MIN_RADIUS
-                / PICTURE_MIN_RADIUS;
+            let (color, radius) =
+                CanvasAppearance::color_and_radius(&star_appearance, &StarRendering::default());
+            let expected_radius = picture_star.radius(StarRendering::default().min_radius);
             let expected_alpha = picture_star.alpha;
             if (radius / expected_radius - 1.).abs() > accuracy
                 || (color.a - expected_alpha).abs() > accuracy
@@ -633,7 +542,7 @@ mod tests {
                 );
             }
         }
-        println!("failures: {} / {}", failures, picture_stars.len());
+        println!("failures: {} / {}", failures, REFERENCE_PICTURE.len());
         assert!(failures == 0);
     }
 
@@ -658,7 +567,7 @@ mod tests {
             Direction::Z,
         );
         let planet_data = PlanetData::new("Inner".to_string(), planet_physical_params, orbit);
-        celestial_system.add_planet_data(planet_data);
+        celestial_system.add_planet_data(planet_data).unwrap();
         let planets = celestial_system.get_planets();
         let planet = planets.first().unwrap();
         let planet_position = planet.get_position();
@@ -669,11 +578,13 @@ mod tests {
             center_direction: away_from_sun,
             top_direction: Direction::Z,
             px_per_distance: SOME_FLOAT,
+            star_rendering: StarRendering::default(),
         };
         let viewport_to_sun = Viewport {
             center_direction: to_sun,
             top_direction: Direction::Z,
             px_per_distance: SOME_FLOAT,
+            star_rendering: StarRendering::default(),
         };
 
         let inner_observer = planet_position * 0.5;
@@ -717,4 +628,75 @@ mod tests {
         let planet_appearance = planet_appearance.unwrap();
         assert!(vecs_equal(planet_appearance.center_offset, CENTER));
     }
+
+    #[test]
+    fn planet_brightness_depends_on_phase_angle() {
+        let mut celestial_system = CelestialSystem::new(sun().to_star_data());
+        let orbit = OrbitParameters::new(
+            Length::new::<astronomical_unit>(0.72),
+            0.,
+            Angle::new::<degree>(0.),
+            Angle::new::<degree>(0.),
+            Angle::new::<degree>(0.),
+        );
+        let planet_physical_params = PlanetPhysicalParameters::new(
+            Mass::new::<earth_mass>(0.815),
+            Length::new::<earth_radius>(0.95),
+            0.69,
+            sRGBColor::from_sRGB(1., 1., 1.),
+            Time::new::<year>(0.),
+            Direction::Z,
+        );
+        let planet_data = PlanetData::new("Venus".to_string(), planet_physical_params, orbit);
+        celestial_system.add_planet_data(planet_data).unwrap();
+        let planets = celestial_system.get_planets();
+        let planet = planets.first().unwrap();
+        let planet_position = planet.get_position();
+
+        let away_from_sun = planet_position.to_direction().unwrap();
+        let to_sun = -&away_from_sun;
+        let viewport_away_from_sun = Viewport {
+            center_direction: away_from_sun,
+            top_direction: Direction::Z,
+            px_per_distance: SOME_FLOAT,
+            star_rendering: StarRendering::default(),
+        };
+        let viewport_to_sun = Viewport {
+            center_direction: to_sun,
+            top_direction: Direction::Z,
+            px_per_distance: SOME_FLOAT,
+            star_rendering: StarRendering::default(),
+        };
+
+        // Both observers are at the same distance from the planet, but on opposite sides.
+        let full_phase_observer = planet_position * 0.5;
+        let new_phase_observer = planet_position * 1.5;
+
+        let full_phase_angle = planet.get_phase_angle(&full_phase_observer).unwrap();
+        let new_phase_angle = planet.get_phase_angle(&new_phase_observer).unwrap();
+        assert!(full_phase_angle.get::<degree>().abs() < 1e-5);
+        assert!((new_phase_angle.get::<degree>() - 180.).abs() < 1e-5);
+
+        let full_phase = CanvasAppearance::from_planet(
+            &celestial_system,
+            planet,
+            &viewport_away_from_sun,
+            &full_phase_observer,
+        )
+        .unwrap();
+        let new_phase = CanvasAppearance::from_planet(
+            &celestial_system,
+            planet,
+            &viewport_to_sun,
+            &new_phase_observer,
+        )
+        .unwrap();
+        let brightness = |a: &CanvasAppearance| a.radius.powi(2) * a.color.a;
+        println!(
+            "full phase brightness: {}, new phase brightness: {}",
+            brightness(&full_phase),
+            brightness(&new_phase)
+        );
+        assert!(brightness(&full_phase) > brightness(&new_phase));
+    }
 }
@@ -0,0 +1,116 @@
+use iced::{
+    widget::{canvas, Button, Column, Text},
+    Color, Element, Point,
+};
+
+use crate::gui::{gui_widget::PADDING, message::GuiMessage};
+
+use super::widget::{SurfaceViewState, SurfaceViewUpdate};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(super) enum CullingResult {
+    Drawn,
+    OffScreen,
+    BehindObserver,
+}
+
+const DEBUG_TEXT_MARGIN: f32 = 10.;
+const DEBUG_TEXT_LINE_HEIGHT: f32 = 20.;
+
+/*
+ * Counts of distant stars during the last redraw of the surface view.
+ * Stars that are off screen still have their hue drawn, because it may reach into the canvas.
+ */
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct CullingStatistics {
+    pub(crate) considered: usize,
+    pub(crate) drawn: usize,
+    pub(crate) off_screen: usize,
+    pub(crate) behind_observer: usize,
+}
+
+impl CullingStatistics {
+    pub(super) fn record(&mut self, result: CullingResult) {
+        self.considered += 1;
+        match result {
+            CullingResult::Drawn => self.drawn += 1,
+            CullingResult::OffScreen => self.off_screen += 1,
+            CullingResult::BehindObserver => self.behind_observer += 1,
+        }
+    }
+
+    fn lines(&self) -> [String; 4] {
+        [
+            format!("Stars considered: {}", self.considered),
+            format!("Stars drawn: {}", self.drawn),
+            format!("Stars off screen: {}", self.off_screen),
+            format!("Stars behind observer: {}", self.behind_observer),
+        ]
+    }
+}
+
+impl SurfaceViewState {
+    /*
+     * A textual description of what the canvas shows, for those who cannot see it.
+     * It is built with the view, before the canvas is drawn, and hence describes the previously drawn frame.
+     */
+    pub(crate) fn canvas_summary(&self) -> String {
+        let drawn = self.culling_statistics.get().drawn;
+        match self.brightest_visible_star.borrow().as_ref() {
+            Some(name) => format!("{} stars visible, brightest: {}", drawn, name),
+            None => format!("{} stars visible", drawn),
+        }
+    }
+
+    pub(super) fn debug_panel(&self) -> Element<'_, GuiMessage> {
+        let label = if self.show_debug_panel {
+            "Hide Debug Info"
+        } else {
+            "Show Debug Info"
+        };
+        let toggle_button =
+            Button::new(Text::new(label)).on_press(SurfaceViewUpdate::ToggleDebugPanel.into());
+        Column::new().push(toggle_button).spacing(PADDING).into()
+    }
+
+    /*
+     * The statistics are drawn onto the canvas in the same pass that counts them, so that they always belong to the frame they are shown with.
+     */
+    pub(super) fn draw_culling_statistics(
+        &self,
+        frame: &mut canvas::Frame,
+        statistics: &CullingStatistics,
+    ) {
+        for (i, line) in statistics.lines().into_iter().enumerate() {
+            let text = canvas::Text {
+                color: Color::WHITE,
+                content: line,
+                position: Point::new(
+                    DEBUG_TEXT_MARGIN,
+                    DEBUG_TEXT_MARGIN + i as f32 * DEBUG_TEXT_LINE_HEIGHT,
+                ),
+                ..Default::default()
+            };
+            frame.fill_text(text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_recorded_star_is_considered() {
+        let mut statistics = CullingStatistics::default();
+        statistics.record(CullingResult::Drawn);
+        statistics.record(CullingResult::OffScreen);
+        statistics.record(CullingResult::BehindObserver);
+        statistics.record(CullingResult::Drawn);
+        assert_eq!(statistics.considered, 4);
+        assert_eq!(
+            statistics.drawn + statistics.off_screen + statistics.behind_observer,
+            statistics.considered
+        );
+    }
+}
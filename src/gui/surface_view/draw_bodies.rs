@@ -7,11 +7,17 @@ use iced::{
 use uom::si::{f64::Length, length::astronomical_unit};
 
 use crate::{
-    gui::shared_canvas_functionality::{canvas_contains, draw_name},
-    model::{celestial_system::CelestialSystem, planet::Planet},
+    gui::shared_canvas_functionality::{canvas_contains, draw_name, LabelStyle},
+    model::{celestial_system::CelestialSystem, planet::Planet, travel::speed_of_light},
 };
 
-use super::{canvas_appearance::CanvasAppearance, viewport::Viewport, widget::SurfaceViewState};
+use super::{
+    canvas_appearance::CanvasAppearance,
+    culling::{CullingResult, CullingStatistics},
+    parallax::shifted_appearance,
+    viewport::Viewport,
+    widget::SurfaceViewState,
+};
 
 impl SurfaceViewState {
     pub(super) fn draw_bodies(
@@ -21,22 +27,60 @@ impl SurfaceViewState {
         selected_planet: &Planet,
         celestial_system: &CelestialSystem,
         display_names: bool,
+        label_style: &LabelStyle,
         viewport: &Viewport,
         observer_position: &Cartesian,
     ) {
-        let smallest_circle = Path::circle(frame.center(), CanvasAppearance::MIN_RADIUS);
-        for distant_star in celestial_system.get_distant_star_appearances() {
-            self.draw_star(
+        let smallest_circle = Path::circle(frame.center(), self.star_rendering.min_radius);
+        let mut culling_statistics = CullingStatistics::default();
+        let mut brightest_visible_star: Option<&StarAppearance> = None;
+        let parallax = self.parallax_wiggle.as_ref().and_then(|wiggle| {
+            let sideways = viewport
+                .center_direction
+                .cross_product(&viewport.top_direction)
+                .ok()?;
+            Some((
+                wiggle,
+                wiggle.observer_offset(&sideways, self.parallax_baseline),
+            ))
+        });
+        for (index, distant_star) in celestial_system.get_distant_stars().iter().enumerate() {
+            let doppler_shift = match (self.display_doppler, distant_star.get_radial_velocity()) {
+                (true, Some(radial_velocity)) => {
+                    Some((radial_velocity / speed_of_light()).value * self.doppler_exaggeration)
+                }
+                _ => None,
+            };
+            let displayed = distant_star.get_displayed_appearance();
+            let shifted = parallax.as_ref().and_then(|(wiggle, offset)| {
+                shifted_appearance(&displayed, wiggle.distance(index)?, offset)
+            });
+            let result = self.draw_star(
                 frame,
                 bounds,
-                distant_star,
+                shifted.as_ref().unwrap_or(displayed.as_ref()),
+                doppler_shift,
                 viewport,
                 observer_position,
                 viewport.px_per_distance,
                 smallest_circle.clone(),
                 display_names,
+                label_style,
             );
+            culling_statistics.record(result);
+            let appearance = distant_star.get_appearance();
+            let is_brighter = brightest_visible_star
+                .is_none_or(|brightest| appearance.get_illuminance() > brightest.get_illuminance());
+            if result == CullingResult::Drawn && is_brighter {
+                brightest_visible_star = Some(appearance);
+            }
+        }
+        if self.show_debug_panel {
+            self.draw_culling_statistics(frame, &culling_statistics);
         }
+        self.culling_statistics.set(culling_statistics);
+        self.brightest_visible_star
+            .replace(brightest_visible_star.map(|star| star.get_name().clone()));
 
         self.draw_central_body(
             frame,
@@ -47,9 +91,11 @@ impl SurfaceViewState {
             viewport.px_per_distance,
             smallest_circle.clone(),
             display_names,
+            label_style,
         );
 
-        for planet in celestial_system.get_planets() {
+        let planets = celestial_system.get_planets();
+        for planet in planets.iter() {
             if planet.get_data() == selected_planet.get_data() {
                 continue;
             }
@@ -57,12 +103,14 @@ impl SurfaceViewState {
                 frame,
                 bounds,
                 celestial_system,
-                &planet,
+                planet,
+                &planets,
                 viewport,
                 observer_position,
                 viewport.px_per_distance,
                 smallest_circle.clone(),
                 display_names,
+                label_style,
             );
         }
     }
@@ -72,13 +120,21 @@ impl SurfaceViewState {
         frame: &mut canvas::Frame,
         bounds: Rectangle,
         star: &StarAppearance,
+        doppler_shift: Option<f64>,
         viewport: &Viewport,
         observer_position: &Cartesian,
         pixel_per_viewport_width: f32,
         smallest_circle: Path,
         display_names: bool,
-    ) {
-        let canvas_appearance = CanvasAppearance::from_star_appearance(star, viewport);
+        label_style: &LabelStyle,
+    ) -> CullingResult {
+        let canvas_appearance =
+            CanvasAppearance::from_star_appearance(star, viewport).map(|appearance| {
+                match doppler_shift {
+                    Some(shift) => appearance.doppler_tinted(shift),
+                    None => appearance,
+                }
+            });
         self.draw_body(
             frame,
             bounds,
@@ -87,8 +143,9 @@ impl SurfaceViewState {
             pixel_per_viewport_width,
             smallest_circle,
             display_names,
+            label_style,
             observer_position,
-        );
+        )
     }
 
     fn draw_central_body(
@@ -101,6 +158,7 @@ impl SurfaceViewState {
         pixel_per_viewport_width: f32,
         smallest_circle: Path,
         display_names: bool,
+        label_style: &LabelStyle,
     ) {
         let canvas_appearance =
             CanvasAppearance::from_central_body(celestial_system, viewport, observer_position);
@@ -115,6 +173,7 @@ impl SurfaceViewState {
             pixel_per_viewport_width,
             smallest_circle,
             display_names,
+            label_style,
             observer_position,
         );
     }
@@ -125,11 +184,13 @@ impl SurfaceViewState {
         bounds: Rectangle,
         celestial_system: &CelestialSystem,
         planet: &Planet,
+        planets: &[Planet],
         viewport: &Viewport,
         observer_position: &Cartesian,
         pixel_per_viewport_width: f32,
         smallest_circle: Path,
         display_names: bool,
+        label_style: &LabelStyle,
     ) {
         let canvas_appearance =
             CanvasAppearance::from_planet(celestial_system, planet, viewport, observer_position);
@@ -141,8 +202,25 @@ impl SurfaceViewState {
             pixel_per_viewport_width,
             smallest_circle,
             display_names,
+            label_style,
             observer_position,
         );
+        if let Some(canvas_appearance) = &canvas_appearance {
+            let disk_center = frame.center() + canvas_appearance.center_offset;
+            let disk_radius = canvas_apparent_radius(
+                &planet.get_data().get_radius(),
+                &-observer_position,
+                pixel_per_viewport_width,
+            );
+            self.draw_shadows(
+                frame,
+                planet,
+                planets,
+                viewport,
+                observer_position,
+                (disk_center, disk_radius),
+            );
+        }
     }
 
     fn draw_body(
@@ -154,8 +232,9 @@ impl SurfaceViewState {
         pixel_per_viewport_width: f32,
         smallest_circle: Path,
         display_names: bool,
+        label_style: &LabelStyle,
         observer_position: &Cartesian,
-    ) {
+    ) -> CullingResult {
         if let Some(canvas_appearance) = canvas_appearance {
             let pos = frame.center() + canvas_appearance.center_offset;
             let color = canvas_appearance.color;
@@ -163,7 +242,7 @@ impl SurfaceViewState {
             self.draw_hue(frame, canvas_appearance, smallest_circle);
 
             if !canvas_contains(&bounds, pos) {
-                return;
+                return CullingResult::OffScreen;
             }
 
             if let Some(radius) = radius {
@@ -179,8 +258,11 @@ impl SurfaceViewState {
             }
 
             if display_names {
-                draw_name(&canvas_appearance.name, color, pos, frame);
+                draw_name(&canvas_appearance.name, color, pos, label_style, frame);
             }
+            CullingResult::Drawn
+        } else {
+            CullingResult::BehindObserver
         }
     }
 
@@ -191,7 +273,7 @@ impl SurfaceViewState {
         smallest_circle: Path,
     ) {
         // Radial gradients are not yet impelemented in iced.
-        let mut step_width = CanvasAppearance::MIN_RADIUS;
+        let mut step_width = self.star_rendering.min_radius;
 
         const MAX_STEPS: i32 = 100;
         let mut steps = (0.99 * canvas_appearance.radius / step_width).ceil() as i32;
@@ -207,7 +289,7 @@ impl SurfaceViewState {
             if radius > canvas_appearance.radius {
                 radius = canvas_appearance.radius;
             }
-            let circle = if radius > CanvasAppearance::MIN_RADIUS {
+            let circle = if radius > self.star_rendering.min_radius {
                 Path::circle(pos, radius)
             } else {
                 let x = canvas_appearance.center_offset.x;
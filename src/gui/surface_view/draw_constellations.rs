@@ -1,7 +1,7 @@
 use super::{viewport::Viewport, widget::SurfaceViewState};
 use crate::{
     gui::{
-        shared_canvas_functionality::canvas_contains,
+        shared_canvas_functionality::{canvas_contains, LabelStyle},
         surface_view::canvas_appearance::CanvasAppearance,
     },
     model::celestial_system::CelestialSystem,
@@ -23,9 +23,10 @@ impl SurfaceViewState {
         bounds: Rectangle,
         celestial_system: &CelestialSystem,
         viewport: &Viewport,
+        label_style: &LabelStyle,
     ) {
         for constellation in celestial_system.get_constellations() {
-            self.draw_constellation(frame, bounds, constellation, viewport);
+            self.draw_constellation(frame, bounds, constellation, viewport, label_style);
         }
     }
 
@@ -35,6 +36,7 @@ impl SurfaceViewState {
         bounds: Rectangle,
         constellation: &Constellation,
         viewport: &Viewport,
+        label_style: &LabelStyle,
     ) {
         let appearances = constellation
             .get_stars()
@@ -68,8 +70,8 @@ impl SurfaceViewState {
             let name_widget = Text {
                 content: constellation.get_name().to_string(),
                 position,
-                color,
-                size: Pixels(20.),
+                color: label_style.color(color),
+                size: Pixels(label_style.constellation_size()),
                 align_x: Alignment::Center,
                 align_y: alignment::Vertical::Center,
                 ..Default::default()
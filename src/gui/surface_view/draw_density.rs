@@ -0,0 +1,67 @@
+use astro_coords::traits::*;
+use iced::{
+    widget::canvas::{Frame, Path},
+    Color,
+};
+
+use crate::model::celestial_system::CelestialSystem;
+
+use super::{canvas_appearance::canvas_offset, viewport::Viewport, widget::SurfaceViewState};
+
+impl SurfaceViewState {
+    /*
+     * Shades the sky by the local density of distant stars.
+     * Cells are coarse on purpose, so that clumps and seams in generated catalogs stand out instead of individual stars.
+     */
+    pub(super) fn draw_density(
+        &self,
+        frame: &mut Frame,
+        celestial_system: &CelestialSystem,
+        viewport: &Viewport,
+    ) {
+        const LONGITUDE_BINS: usize = 72;
+        const LATITUDE_BINS: usize = 36;
+        const MAX_ALPHA: f32 = 0.5;
+
+        let density_map = celestial_system.get_star_density_map(LONGITUDE_BINS, LATITUDE_BINS);
+        let max_count = density_map.max_count();
+        if max_count == 0 {
+            return;
+        }
+
+        for i in 0..LONGITUDE_BINS {
+            for j in 0..LATITUDE_BINS {
+                let count = density_map.count(i, j);
+                if count == 0 {
+                    continue;
+                }
+                let corners: Option<Vec<_>> = density_map
+                    .cell_corners(i, j)
+                    .iter()
+                    .map(|corner| canvas_offset(&corner.to_direction(), viewport))
+                    .collect();
+                let corners = match corners {
+                    Some(corners) => corners,
+                    None => continue,
+                };
+                let cell = Path::new(|path_builder| {
+                    path_builder.move_to(frame.center() + corners[0]);
+                    for corner in corners.iter().skip(1) {
+                        path_builder.line_to(frame.center() + *corner);
+                    }
+                    path_builder.close();
+                });
+                let relative_density = count as f32 / max_count as f32;
+                frame.fill(&cell, heat_color(relative_density, MAX_ALPHA));
+            }
+        }
+    }
+}
+
+/*
+ * Maps a relative density between 0 and 1 onto a blue to red ramp.
+ */
+fn heat_color(relative_density: f32, max_alpha: f32) -> Color {
+    let t = relative_density.clamp(0., 1.);
+    Color::from_rgba(t, 0.2 * (1. - t), 1. - t, max_alpha * t.sqrt())
+}
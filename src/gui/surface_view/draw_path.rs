@@ -0,0 +1,110 @@
+use astro_utils::astro_display::AstroDisplay;
+use iced::{
+    widget::canvas::{Frame, Path, Stroke, Style, Text},
+    Color, Point, Rectangle, Vector,
+};
+use uom::si::f64::Time;
+
+use crate::{
+    gui::shared_canvas_functionality::canvas_contains,
+    model::{
+        celestial_system::{planets::SystemBody, CelestialSystem},
+        planet::Planet,
+    },
+};
+
+use super::{canvas_appearance::canvas_offset, viewport::Viewport, widget::SurfaceViewState};
+
+impl SurfaceViewState {
+    /*
+     * Plots the apparent path of a body against the stars over the chosen time range.
+     * The observer is placed at the center of the selected planet, so that its daily rotation does not smear out the path.
+     */
+    pub(super) fn draw_path(
+        &self,
+        frame: &mut Frame,
+        bounds: Rectangle,
+        selected_planet: &Planet,
+        celestial_system: &CelestialSystem,
+        viewport: &Viewport,
+    ) {
+        const SAMPLES: usize = 200;
+        const TICKS: usize = 10;
+        const TICK_RADIUS: f32 = 2.5;
+        const PATH_COLOR: Color = Color {
+            r: 1.,
+            g: 0.8,
+            b: 0.3,
+            a: 0.8,
+        };
+
+        let (Some(observer), Some(path_body)) = (selected_planet.get_id(), self.path_body) else {
+            return;
+        };
+        let observer = SystemBody::Planet(observer);
+        let start = celestial_system.get_time_since_epoch();
+        let sample_time = |i: usize| start + self.path_time_range * (i as f64 / SAMPLES as f64);
+        let points: Vec<Option<Point>> = (0..=SAMPLES)
+            .map(|i| {
+                let offset = path_offset(
+                    celestial_system,
+                    observer,
+                    path_body,
+                    sample_time(i),
+                    viewport,
+                )?;
+                Some(frame.center() + offset)
+            })
+            .collect();
+
+        let path = Path::new(|path_builder| {
+            let mut is_drawing = false;
+            for point in points.iter() {
+                match point {
+                    Some(point) if is_drawing => path_builder.line_to(*point),
+                    Some(point) => {
+                        path_builder.move_to(*point);
+                        is_drawing = true;
+                    }
+                    None => is_drawing = false,
+                }
+            }
+        });
+        let stroke = Stroke {
+            style: Style::Solid(PATH_COLOR),
+            width: 1.5,
+            ..Default::default()
+        };
+        frame.stroke(&path, stroke);
+
+        for i in (0..=SAMPLES).step_by(SAMPLES / TICKS) {
+            if let Some(point) = points[i] {
+                if !canvas_contains(&bounds, point) {
+                    continue;
+                }
+                frame.fill(&Path::circle(point, TICK_RADIUS), PATH_COLOR);
+                let elapsed = sample_time(i) - start;
+                let tick_label = Text {
+                    content: format!("+{}", elapsed.astro_display()),
+                    position: point + Vector::new(TICK_RADIUS * 2., -TICK_RADIUS * 4.),
+                    color: PATH_COLOR,
+                    ..Default::default()
+                };
+                frame.fill_text(tick_label);
+            }
+        }
+    }
+}
+
+fn path_offset(
+    celestial_system: &CelestialSystem,
+    observer: SystemBody,
+    path_body: SystemBody,
+    time: Time,
+    viewport: &Viewport,
+) -> Option<Vector> {
+    let observer = celestial_system.get_body_position_at(observer, time)?;
+    let target = celestial_system.get_body_position_at(path_body, time)?;
+    let direction = (target + -&observer).to_direction().ok()?;
+    canvas_offset(&direction, viewport)
+}
@@ -0,0 +1,237 @@
+use astro_coords::{cartesian::Cartesian, direction::Direction};
+use iced::{
+    widget::canvas::{path::Arc, Frame, Path},
+    Color, Point, Radians,
+};
+use std::f32::consts::PI;
+use uom::si::length::astronomical_unit;
+
+use crate::model::planet::Planet;
+
+use super::{canvas_appearance::canvas_offset, viewport::Viewport, widget::SurfaceViewState};
+
+impl SurfaceViewState {
+    /*
+     * Draws the shadows that other bodies cast onto the resolved disk of a planet.
+     * The model does not know about moons or rings yet, so every other planet is a potential occluder.
+     * Shadows near the limb are cut off where the disk ends, instead of spilling onto the sky around it.
+     */
+    pub(super) fn draw_shadows(
+        &self,
+        frame: &mut Frame,
+        planet: &Planet,
+        planets: &[Planet],
+        viewport: &Viewport,
+        observer_position: &Cartesian,
+        (disk_center, disk_radius): (Point, f32),
+    ) {
+        const SHADOW_COLOR: Color = Color {
+            r: 0.,
+            g: 0.,
+            b: 0.,
+            a: 0.8,
+        };
+
+        let target_pos = to_au(planet.get_position());
+        let target_radius = planet.get_data().get_radius().get::<astronomical_unit>();
+        let observer = to_au(observer_position);
+        for occluder in planets {
+            if occluder.get_data() == planet.get_data() {
+                continue;
+            }
+            let occluder_pos = to_au(occluder.get_position());
+            let occluder_radius = occluder.get_data().get_radius().get::<astronomical_unit>();
+            let spot = match shadow_center(target_pos, target_radius, occluder_pos, occluder_radius)
+            {
+                Some(spot) => spot,
+                None => continue,
+            };
+            if dot(sub(spot, target_pos), sub(observer, target_pos)) <= 0. {
+                continue; // The shadow falls on the side facing away from the observer.
+            }
+            let to_spot = sub(spot, observer);
+            let distance = dot(to_spot, to_spot).sqrt();
+            let direction = match Direction::new(to_spot[0], to_spot[1], to_spot[2]) {
+                Ok(direction) => direction,
+                Err(_) => continue,
+            };
+            if let Some(offset) = canvas_offset(&direction, viewport) {
+                let center = frame.center() + offset;
+                let radius = (occluder_radius / distance) as f32 * viewport.px_per_distance;
+                let shadow = match shadow_on_disk(center, radius, disk_center, disk_radius) {
+                    Some(ShadowOnDisk::Whole) => Path::circle(center, radius),
+                    Some(ShadowOnDisk::CoversDisk) => Path::circle(disk_center, disk_radius),
+                    Some(ShadowOnDisk::Lens {
+                        direction,
+                        shadow_half_angle,
+                        disk_half_angle,
+                    }) => Path::new(|builder| {
+                        // The lens consists of the two circular segments on either side of the common chord.
+                        builder.arc(Arc {
+                            center,
+                            radius,
+                            start_angle: Radians(direction - shadow_half_angle),
+                            end_angle: Radians(direction + shadow_half_angle),
+                        });
+                        builder.arc(Arc {
+                            center: disk_center,
+                            radius: disk_radius,
+                            start_angle: Radians(direction + PI - disk_half_angle),
+                            end_angle: Radians(direction + PI + disk_half_angle),
+                        });
+                        builder.close();
+                    }),
+                    None => continue,
+                };
+                frame.fill(&shadow, SHADOW_COLOR);
+            }
+        }
+    }
+}
+
+/*
+ * The central body sits at the origin, so shadows point radially outwards.
+ * Returns the point where the shadow axis of the occluder hits the lit side of the target, if it does so at all.
+ */
+fn shadow_center(
+    target_pos: [f64; 3],
+    target_radius: f64,
+    occluder_pos: [f64; 3],
+    occluder_radius: f64,
+) -> Option<[f64; 3]> {
+    let occluder_distance = dot(occluder_pos, occluder_pos).sqrt();
+    if occluder_distance <= 0. {
+        return None;
+    }
+    let axis = scale(occluder_pos, 1. / occluder_distance);
+    let along_axis = dot(sub(target_pos, occluder_pos), axis);
+    if along_axis <= 0. {
+        return None;
+    }
+    let closest = add(occluder_pos, scale(axis, along_axis));
+    let perpendicular = sub(target_pos, closest);
+    let perpendicular_distance = dot(perpendicular, perpendicular).sqrt();
+    if perpendicular_distance > target_radius + occluder_radius {
+        return None;
+    }
+    let depth = (target_radius.powi(2) - perpendicular_distance.powi(2))
+        .max(0.)
+        .sqrt();
+    Some(sub(closest, scale(axis, depth)))
+}
+
+/*
+ * How the circle of a shadow overlaps the disk it falls onto, on the canvas.
+ * For a lens, the direction points from the shadow towards the disk, and the half angles span the common chord as seen from either center.
+ */
+#[derive(Debug, PartialEq)]
+enum ShadowOnDisk {
+    Whole,
+    CoversDisk,
+    Lens {
+        direction: f32,
+        shadow_half_angle: f32,
+        disk_half_angle: f32,
+    },
+}
+
+fn shadow_on_disk(
+    shadow_center: Point,
+    shadow_radius: f32,
+    disk_center: Point,
+    disk_radius: f32,
+) -> Option<ShadowOnDisk> {
+    let distance = shadow_center.distance(disk_center);
+    if distance >= shadow_radius + disk_radius {
+        None
+    } else if distance + shadow_radius <= disk_radius {
+        Some(ShadowOnDisk::Whole)
+    } else if distance + disk_radius <= shadow_radius {
+        Some(ShadowOnDisk::CoversDisk)
+    } else {
+        let half_angle = |own: f32, other: f32| {
+            ((distance.powi(2) + own.powi(2) - other.powi(2)) / (2. * distance * own))
+                .clamp(-1., 1.)
+                .acos()
+        };
+        Some(ShadowOnDisk::Lens {
+            direction: (disk_center.y - shadow_center.y).atan2(disk_center.x - shadow_center.x),
+            shadow_half_angle: half_angle(shadow_radius, disk_radius),
+            disk_half_angle: half_angle(disk_radius, shadow_radius),
+        })
+    }
+}
+
+fn to_au(pos: &Cartesian) -> [f64; 3] {
+    [
+        pos.x.get::<astronomical_unit>(),
+        pos.y.get::<astronomical_unit>(),
+        pos.z.get::<astronomical_unit>(),
+    ]
+}
+
+fn add(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn scale(a: [f64; 3], factor: f64) -> [f64; 3] {
+    [a[0] * factor, a[1] * factor, a[2] * factor]
+}
+
+fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_ACCURACY: f64 = 1e-10;
+
+    #[test]
+    fn aligned_occluder_casts_shadow_on_lit_side() {
+        let spot = shadow_center([2., 0., 0.], 0.1, [1., 0., 0.], 0.05).unwrap();
+        assert!((spot[0] - 1.9).abs() < TEST_ACCURACY);
+        assert!(spot[1].abs() < TEST_ACCURACY);
+        assert!(spot[2].abs() < TEST_ACCURACY);
+    }
+
+    #[test]
+    fn occluder_behind_target_casts_no_shadow() {
+        assert!(shadow_center([1., 0., 0.], 0.1, [2., 0., 0.], 0.05).is_none());
+    }
+
+    #[test]
+    fn misaligned_occluder_casts_no_shadow() {
+        assert!(shadow_center([2., 1., 0.], 0.1, [1., 0., 0.], 0.05).is_none());
+    }
+
+    #[test]
+    fn shadows_are_cut_off_at_the_limb() {
+        let disk = Point::new(0., 0.);
+        assert_eq!(
+            shadow_on_disk(Point::new(1., 0.), 1., disk, 10.),
+            Some(ShadowOnDisk::Whole)
+        );
+        assert_eq!(
+            shadow_on_disk(Point::new(1., 0.), 20., disk, 10.),
+            Some(ShadowOnDisk::CoversDisk)
+        );
+        assert_eq!(shadow_on_disk(Point::new(12., 0.), 1., disk, 10.), None);
+        let Some(ShadowOnDisk::Lens {
+            direction,
+            shadow_half_angle,
+            disk_half_angle,
+        }) = shadow_on_disk(Point::new(0., 10.), 10., disk, 10.)
+        else {
+            panic!("Expected the shadow to straddle the limb");
+        };
+        assert!((direction + PI / 2.).abs() < 1e-6);
+        assert!((shadow_half_angle - PI / 3.).abs() < 1e-6);
+        assert!((disk_half_angle - PI / 3.).abs() < 1e-6);
+    }
+}
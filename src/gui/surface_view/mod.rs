@@ -1,6 +1,15 @@
 mod canvas;
 mod canvas_appearance;
+mod culling;
 mod draw_bodies;
 mod draw_constellations;
+mod draw_density;
+mod draw_path;
+mod draw_shadows;
+mod parallax;
+mod planet_map;
+mod pole_stars;
+pub(super) mod sky_region;
+mod star_counts;
 mod viewport;
 pub(super) mod widget;
@@ -0,0 +1,105 @@
+use astro_coords::{cartesian::Cartesian, direction::Direction, traits::*};
+use astro_utils::stars::appearance::StarAppearance;
+use iced::time::{Duration, Instant};
+use std::f64::consts::PI;
+use uom::si::f64::Length;
+
+use crate::model::celestial_system::CelestialSystem;
+
+const WIGGLE_PERIOD: Duration = Duration::from_millis(1200);
+const WIGGLE_CYCLES: u32 = 3;
+
+/*
+ * A short sideways oscillation of the observer, which lets nearby stars swing against the distant ones.
+ * The distances are taken when the wiggle starts, so that no star data has to be looked up on every frame.
+ * Stars without data have no known distance, and stay where they are.
+ */
+pub(crate) struct ParallaxWiggle {
+    start: Instant,
+    distances: Vec<Option<Length>>,
+    displacement: f64,
+}
+
+impl ParallaxWiggle {
+    pub(super) fn new(celestial_system: &CelestialSystem) -> Self {
+        let distances = celestial_system
+            .get_distant_stars()
+            .iter()
+            .map(|star| star.get_data().map(|data| data.get_pos_at_epoch().length()))
+            .collect();
+        ParallaxWiggle {
+            start: Instant::now(),
+            distances,
+            displacement: 0.,
+        }
+    }
+
+    pub(super) fn animate(&mut self, now: Instant) {
+        let periods =
+            now.saturating_duration_since(self.start).as_secs_f64() / WIGGLE_PERIOD.as_secs_f64();
+        self.displacement = (2. * PI * periods).sin();
+    }
+
+    pub(super) fn is_finished(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.start) >= WIGGLE_PERIOD * WIGGLE_CYCLES
+    }
+
+    /*
+     * The current position of the observer relative to its rest position, perpendicular to the line of sight.
+     */
+    pub(super) fn observer_offset(&self, sideways: &Direction, baseline: Length) -> Cartesian {
+        sideways.to_cartesian(baseline * self.displacement)
+    }
+
+    pub(super) fn distance(&self, star_index: usize) -> Option<Length> {
+        self.distances.get(star_index).copied().flatten()
+    }
+}
+
+/*
+ * The appearance of a star at the given distance, as seen by an observer that moved by the offset.
+ */
+pub(super) fn shifted_appearance(
+    appearance: &StarAppearance,
+    distance: Length,
+    observer_offset: &Cartesian,
+) -> Option<StarAppearance> {
+    let position = appearance.get_pos().to_direction().to_cartesian(distance);
+    let relative_position = position + -observer_offset;
+    let new_distance = relative_position.length();
+    let direction = relative_position.to_direction().ok()?;
+    let dimming = (distance / new_distance).value.powi(2);
+    Some(StarAppearance::new(
+        appearance.get_name().clone(),
+        appearance.get_illuminance() * dimming,
+        appearance.get_color().clone(),
+        direction.to_ecliptic(),
+        appearance.get_time_since_epoch(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astro_units::illuminance::{lux, Illuminance};
+    use astro_utils::color::srgb::sRGBColor;
+    use uom::si::{f64::Time, length::light_year, time::year};
+
+    #[test]
+    fn a_sideways_step_as_long_as_the_distance_turns_the_star_by_45_degrees() {
+        let distance = Length::new::<light_year>(10.);
+        let appearance = StarAppearance::new(
+            String::new(),
+            Illuminance::new::<lux>(1.),
+            sRGBColor::from_sRGB(1., 1., 1.),
+            Direction::Z.to_ecliptic(),
+            Time::new::<year>(0.),
+        );
+        let offset = Direction::X.to_cartesian(distance);
+        let shifted = shifted_appearance(&appearance, distance, &offset).unwrap();
+        let direction = shifted.get_pos().to_direction();
+        assert!((direction.x() + 0.5f64.sqrt()).abs() < 1e-6);
+        assert!((direction.z() - 0.5f64.sqrt()).abs() < 1e-6);
+        assert!((shifted.get_illuminance().get::<lux>() - 0.5).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,175 @@
+use astro_coords::spherical::Spherical;
+use iced::{
+    mouse::{self, Cursor},
+    widget::{
+        canvas::{self, Path, Stroke, Style},
+        Canvas,
+    },
+    Color, Element, Length, Point, Rectangle, Renderer, Size, Theme,
+};
+use uom::si::{angle::degree, f64::Angle};
+
+use crate::gui::message::GuiMessage;
+
+use super::widget::{SurfaceViewState, SurfaceViewUpdate};
+
+const MAP_WIDTH: f32 = 240.;
+const MAP_HEIGHT: f32 = MAP_WIDTH / 2.;
+const GRID_STEP_IN_DEGREES: usize = 30;
+const SURFACE_COLOR: Color = Color {
+    r: 0.1,
+    g: 0.15,
+    b: 0.25,
+    a: 1.,
+};
+const GRID_COLOR: Color = Color {
+    r: 0.4,
+    g: 0.4,
+    b: 0.5,
+    a: 1.,
+};
+const SUBSOLAR_COLOR: Color = Color {
+    r: 1.,
+    g: 0.85,
+    b: 0.3,
+    a: 1.,
+};
+const OBSERVER_COLOR: Color = Color {
+    r: 1.,
+    g: 0.3,
+    b: 0.3,
+    a: 1.,
+};
+
+/*
+ * An equirectangular map of the planet surface, with longitude 0° in the middle and north at the top.
+ * Clicking on the map moves the observer there.
+ */
+struct PlanetMap {
+    observer: (Angle, Angle),
+    subsolar_point: Option<Spherical>,
+}
+
+impl SurfaceViewState {
+    pub(super) fn planet_map(&self, subsolar_point: Option<Spherical>) -> Element<'_, GuiMessage> {
+        let map = PlanetMap {
+            observer: (self.surface_longitude, self.surface_latitude),
+            subsolar_point,
+        };
+        Canvas::new(map)
+            .width(Length::Fixed(MAP_WIDTH))
+            .height(Length::Fixed(MAP_HEIGHT))
+            .into()
+    }
+}
+
+fn to_map(longitude: Angle, latitude: Angle, size: Size) -> Point {
+    let longitude = (longitude.get::<degree>() + 180.).rem_euclid(360.);
+    let x = longitude / 360. * size.width as f64;
+    let y = (90. - latitude.get::<degree>()) / 180. * size.height as f64;
+    Point::new(x as f32, y as f32)
+}
+
+fn from_map(point: Point, size: Size) -> (Angle, Angle) {
+    let longitude = point.x as f64 / size.width as f64 * 360. - 180.;
+    let latitude = 90. - point.y as f64 / size.height as f64 * 180.;
+    (
+        Angle::new::<degree>(longitude),
+        Angle::new::<degree>(latitude.clamp(-90., 90.)),
+    )
+}
+
+impl canvas::Program<GuiMessage> for PlanetMap {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut Self::State,
+        event: &canvas::Event,
+        bounds: Rectangle,
+        cursor: Cursor,
+    ) -> Option<canvas::Action<GuiMessage>> {
+        let canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event else {
+            return None;
+        };
+        let position = cursor.position_in(bounds)?;
+        let (longitude, latitude) = from_map(position, bounds.size());
+        let message = SurfaceViewUpdate::SurfacePosition(longitude, latitude).into();
+        Some(canvas::Action::publish(message).and_capture())
+    }
+
+    fn draw(
+        &self,
+        _state: &Self::State,
+        renderer: &Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let size = bounds.size();
+        let mut frame = canvas::Frame::new(renderer, size);
+        frame.fill(&Path::rectangle(Point::ORIGIN, size), SURFACE_COLOR);
+
+        let grid = Path::new(|path_builder| {
+            for longitude in (0..=360).step_by(GRID_STEP_IN_DEGREES) {
+                let x = longitude as f32 / 360. * size.width;
+                path_builder.move_to(Point::new(x, 0.));
+                path_builder.line_to(Point::new(x, size.height));
+            }
+            for latitude in (0..=180).step_by(GRID_STEP_IN_DEGREES) {
+                let y = latitude as f32 / 180. * size.height;
+                path_builder.move_to(Point::new(0., y));
+                path_builder.line_to(Point::new(size.width, y));
+            }
+        });
+        let stroke = Stroke {
+            style: Style::Solid(GRID_COLOR),
+            width: 1.,
+            ..Default::default()
+        };
+        frame.stroke(&grid, stroke);
+
+        if let Some(subsolar_point) = &self.subsolar_point {
+            let position = to_map(subsolar_point.longitude, subsolar_point.latitude, size);
+            frame.fill(&Path::circle(position, 5.), SUBSOLAR_COLOR);
+        }
+
+        let (longitude, latitude) = self.observer;
+        let position = to_map(longitude, latitude, size);
+        let cross = Path::new(|path_builder| {
+            const ARM: f32 = 5.;
+            path_builder.move_to(Point::new(position.x - ARM, position.y));
+            path_builder.line_to(Point::new(position.x + ARM, position.y));
+            path_builder.move_to(Point::new(position.x, position.y - ARM));
+            path_builder.line_to(Point::new(position.x, position.y + ARM));
+        });
+        let stroke = Stroke {
+            style: Style::Solid(OBSERVER_COLOR),
+            width: 2.,
+            ..Default::default()
+        };
+        frame.stroke(&cross, stroke);
+
+        vec![frame.into_geometry()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_positions_round_trip() {
+        let size = Size::new(MAP_WIDTH, MAP_HEIGHT);
+        for (longitude, latitude) in [(0., 0.), (-120., 45.), (170., -80.)] {
+            let point = to_map(
+                Angle::new::<degree>(longitude),
+                Angle::new::<degree>(latitude),
+                size,
+            );
+            let (longitude_back, latitude_back) = from_map(point, size);
+            assert!((longitude_back.get::<degree>() - longitude).abs() < 1e-3);
+            assert!((latitude_back.get::<degree>() - latitude).abs() < 1e-3);
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use astro_coords::{direction::Direction, traits::*};
+use astro_utils::planets::planet_data::PlanetData;
+use uom::si::angle::degree;
+
+use crate::model::celestial_system::{pole_stars::PoleStar, CelestialSystem};
+
+use super::widget::SurfaceViewState;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PoleStars {
+    pub(crate) north: Option<PoleStar>,
+    pub(crate) south: Option<PoleStar>,
+}
+
+impl PoleStars {
+    pub(crate) fn describe(&self) -> String {
+        let describe = |star: &Option<PoleStar>| match star {
+            Some(star) => format!("{} ({:.1}°)", star.name, star.distance.get::<degree>()),
+            None => "none".to_string(),
+        };
+        format!(
+            "Pole stars: north {}, south {}",
+            describe(&self.north),
+            describe(&self.south)
+        )
+    }
+}
+
+impl SurfaceViewState {
+    /*
+     * The rotation axis of the selected planet points to its north celestial pole.
+     * Like the star counts, the result is cached until the next redraw, so it follows time and edits of the axis.
+     */
+    pub(crate) fn pole_stars(
+        &self,
+        selected_planet: &PlanetData,
+        celestial_system: &CelestialSystem,
+    ) -> PoleStars {
+        if let Some(pole_stars) = self.pole_stars.borrow().as_ref() {
+            return pole_stars.clone();
+        }
+        let north_pole = selected_planet.get_rotation_axis();
+        let south_pole = Direction::new(-north_pole.x(), -north_pole.y(), -north_pole.z()).ok();
+        let pole_stars = PoleStars {
+            north: celestial_system.get_pole_star(north_pole),
+            south: south_pole.and_then(|pole| celestial_system.get_pole_star(&pole)),
+        };
+        self.pole_stars.replace(Some(pole_stars.clone()));
+        pole_stars
+    }
+}
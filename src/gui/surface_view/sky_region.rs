@@ -0,0 +1,193 @@
+use astro_coords::{direction::Direction, traits::*};
+use astro_units::illuminance::illuminance_to_apparent_magnitude;
+use iced::{
+    widget::canvas::{self, Path, Stroke},
+    Color, Point, Rectangle, Renderer, Size,
+};
+
+use crate::model::{
+    body_id::BodyId,
+    celestial_system::{part::PartOfCelestialSystem, CelestialSystem},
+    planet::Planet,
+};
+
+use super::{canvas_appearance::canvas_offset, widget::SurfaceViewState};
+
+const BRIGHTEST_MEMBER_COUNT: usize = 5;
+const SELECTION_COLOR: Color = Color {
+    r: 1.,
+    g: 0.8,
+    b: 0.3,
+    a: 0.8,
+};
+
+/*
+ * Keeps track of a rectangle that is being dragged open on the canvas.
+ * Points are relative to the top left corner of the canvas.
+ */
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct RegionDrag {
+    start: Option<Point>,
+    current: Option<Point>,
+}
+
+impl RegionDrag {
+    // Anything smaller is a click rather than a drag.
+    const MIN_SIZE: f32 = 4.;
+
+    pub(crate) fn press(&mut self, position: Point) {
+        self.start = Some(position);
+        self.current = None;
+    }
+
+    /*
+     * Returns whether a drag is in progress, and therefore whether the selection needs to be redrawn.
+     */
+    pub(crate) fn move_to(&mut self, position: Point) -> bool {
+        if self.start.is_none() {
+            return false;
+        }
+        self.current = Some(position);
+        true
+    }
+
+    /*
+     * Ends the drag, and returns the selected rectangle if it is large enough.
+     */
+    pub(crate) fn release(&mut self) -> Option<Rectangle> {
+        let rectangle = self.rectangle();
+        self.start = None;
+        self.current = None;
+        rectangle
+    }
+
+    pub(crate) fn rectangle(&self) -> Option<Rectangle> {
+        let (start, current) = (self.start?, self.current?);
+        let rectangle = Rectangle::new(
+            Point::new(start.x.min(current.x), start.y.min(current.y)),
+            Size::new((start.x - current.x).abs(), (start.y - current.y).abs()),
+        );
+        if rectangle.width < Self::MIN_SIZE || rectangle.height < Self::MIN_SIZE {
+            return None;
+        }
+        Some(rectangle)
+    }
+
+    pub(crate) fn draw(&self, renderer: &Renderer, bounds: Rectangle) -> Option<canvas::Geometry> {
+        let rectangle = self.rectangle()?;
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        let path = Path::rectangle(rectangle.position(), rectangle.size());
+        frame.stroke(
+            &path,
+            Stroke::default()
+                .with_color(SELECTION_COLOR)
+                .with_width(1.5),
+        );
+        Some(frame.into_geometry())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct BrightStar {
+    pub(crate) name: String,
+    pub(crate) apparent_magnitude: f64,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SkyRegionStatistics {
+    pub(crate) stars: Vec<BodyId>,
+    pub(crate) brightest: Vec<BrightStar>,
+    pub(crate) constellations: Vec<String>,
+}
+
+impl SurfaceViewState {
+    /*
+     * The distant stars and constellations within a rectangle of the canvas, as it is currently drawn.
+     * A constellation is intersected if at least one of its stars lies within the rectangle.
+     */
+    pub(crate) fn sky_region_statistics(
+        &self,
+        region: Rectangle,
+        canvas_size: Size,
+        selected_planet: &Planet,
+        celestial_system: &CelestialSystem,
+    ) -> SkyRegionStatistics {
+        let viewport = self.viewport_for_canvas(selected_planet, celestial_system, canvas_size);
+        let center = Point::new(canvas_size.width / 2., canvas_size.height / 2.);
+        let is_inside = |direction: Direction| {
+            canvas_offset(&direction, &viewport)
+                .is_some_and(|offset| region.contains(center + offset))
+        };
+
+        let mut stars = Vec::new();
+        let mut brightest = Vec::new();
+        // The distant stars are sorted by brightness, so the first members found are the brightest.
+        for (index, star) in celestial_system.get_distant_stars().iter().enumerate() {
+            let appearance = star.get_appearance();
+            if !is_inside(appearance.get_pos().to_direction()) {
+                continue;
+            }
+            if let Some(id) = star.get_id() {
+                stars.push(id);
+            }
+            if brightest.len() < BRIGHTEST_MEMBER_COUNT {
+                let name = if appearance.get_name().is_empty() {
+                    format!("Star #{}", index)
+                } else {
+                    appearance.get_name().clone()
+                };
+                brightest.push(BrightStar {
+                    name,
+                    apparent_magnitude: illuminance_to_apparent_magnitude(
+                        appearance.get_illuminance(),
+                    ),
+                });
+            }
+        }
+
+        let constellations = celestial_system
+            .get_constellations()
+            .iter()
+            .filter(|constellation| {
+                constellation
+                    .get_stars()
+                    .iter()
+                    .any(|star| is_inside(star.get_pos().to_direction()))
+            })
+            .map(|constellation| constellation.get_name().to_string())
+            .collect();
+
+        SkyRegionStatistics {
+            stars,
+            brightest,
+            constellations,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dragging_opens_a_normalised_rectangle() {
+        let mut drag = RegionDrag::default();
+        assert!(!drag.move_to(Point::new(5., 5.)));
+        drag.press(Point::new(50., 40.));
+        assert!(drag.move_to(Point::new(10., 80.)));
+        let rectangle = drag.release().unwrap();
+        assert_eq!(rectangle.position(), Point::new(10., 40.));
+        assert_eq!(rectangle.size(), Size::new(40., 40.));
+        assert!(drag.rectangle().is_none());
+    }
+
+    #[test]
+    fn a_click_selects_nothing() {
+        let mut drag = RegionDrag::default();
+        drag.press(Point::new(50., 40.));
+        assert!(drag.release().is_none());
+        drag.press(Point::new(50., 40.));
+        drag.move_to(Point::new(51., 41.));
+        assert!(drag.release().is_none());
+    }
+}
@@ -0,0 +1,46 @@
+use astro_coords::{spherical::Spherical, traits::*};
+use uom::si::{angle::degree, f64::Angle};
+
+use crate::model::{celestial_system::CelestialSystem, planet::Planet};
+
+use super::{viewport::observer_normal, widget::SurfaceViewState};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct NakedEyeStarCounts {
+    pub(crate) total: usize,
+    pub(crate) above_horizon: usize,
+}
+
+impl SurfaceViewState {
+    /*
+     * The counts are cached until the next redraw, which happens whenever time or the observer changes.
+     */
+    pub(crate) fn naked_eye_star_counts(
+        &self,
+        selected_planet: &Planet,
+        celestial_system: &CelestialSystem,
+    ) -> NakedEyeStarCounts {
+        if let Some(counts) = self.naked_eye_star_counts.get() {
+            return counts;
+        }
+        let surface_position = Spherical::new(self.surface_longitude, self.surface_latitude);
+        let zenith = observer_normal(
+            selected_planet.get_data(),
+            selected_planet.get_rotation_angle_at_epoch(),
+            surface_position,
+            celestial_system.get_time_since_epoch(),
+        );
+        let right_angle = Angle::new::<degree>(90.);
+        let stars = celestial_system.get_naked_eye_stars();
+        let above_horizon = stars
+            .iter()
+            .filter(|s| s.get_pos().to_direction().angle_to(&zenith) < right_angle)
+            .count();
+        let counts = NakedEyeStarCounts {
+            total: stars.len(),
+            above_horizon,
+        };
+        self.naked_eye_star_counts.set(Some(counts));
+        counts
+    }
+}
@@ -1,9 +1,11 @@
-use astro_coords::{direction::Direction, equatorial::Equatorial, spherical::Spherical, traits::*};
-use astro_utils::planets::{planet_data::PlanetData, surface_normal::surface_normal_at_time};
-use iced::Rectangle;
+pub(super) use crate::model::local_time::observer_normal;
+use astro_coords::{direction::Direction, spherical::Spherical, traits::*};
+use iced::{Rectangle, Size};
+
+use crate::gui::star_rendering::StarRendering;
 use uom::si::{
-    angle::{degree, radian},
-    f64::{Angle, SolidAngle, Time},
+    angle::radian,
+    f64::{Angle, SolidAngle},
     solid_angle::steradian,
 };
 
@@ -11,6 +13,7 @@ pub(super) struct Viewport {
     pub(super) center_direction: Direction,
     pub(super) top_direction: Direction,
     pub(super) px_per_distance: f32,
+    pub(super) star_rendering: StarRendering,
 }
 
 impl Viewport {
@@ -20,6 +23,7 @@ impl Viewport {
         opening_angle: SolidAngle,
         rotation_axis: &Direction,
         bounds: Rectangle,
+        star_rendering: StarRendering,
     ) -> Self {
         let view_direction = local_view_direction.to_direction();
         let center_direction = view_direction.active_rotation_to_new_z_axis(observer_normal);
@@ -30,11 +34,7 @@ impl Viewport {
                 Err(_) => center_direction.some_orthogonal_vector(),
             },
         };
-        let aspect_ration = bounds.width / bounds.height;
-        // A = a * b = a^2 * aspect_ratio
-        // a = sqrt(A / aspect_ratio)
-        let vertical_angle = (opening_angle.get::<steradian>() / aspect_ration as f64).sqrt();
-        let vertical_angle = Angle::new::<radian>(vertical_angle);
+        let (_, vertical_angle) = Self::field_of_view(opening_angle, bounds.size());
         let top_direction = center_direction.rotated(vertical_angle / 2., &ortho);
         let viewport_height = (vertical_angle / 2.).get::<radian>().sin() * 2.; //Viewport is at unit distance
         let px_per_distance = bounds.height / viewport_height as f32;
@@ -42,30 +42,27 @@ impl Viewport {
             center_direction,
             top_direction,
             px_per_distance,
+            star_rendering,
         }
     }
-}
 
-pub(super) fn observer_normal(
-    planet: &PlanetData,
-    surface_position: Spherical,
-    time_since_epoch: Time,
-) -> Direction {
-    let observer_equatorial_position =
-        Equatorial::new(surface_position, planet.get_rotation_axis().clone());
-    //TODO: Define Angle at Epoch
-    let planet_angle_at_epoch = Angle::new::<degree>(0.0);
-    surface_normal_at_time(
-        observer_equatorial_position,
-        planet_angle_at_epoch,
-        time_since_epoch,
-        planet.get_sideral_rotation_period(),
-    )
+    /*
+     * The horizontal and vertical angle that the canvas spans.
+     */
+    pub(super) fn field_of_view(opening_angle: SolidAngle, canvas_size: Size) -> (Angle, Angle) {
+        let aspect_ration = canvas_size.width / canvas_size.height;
+        // A = a * b = a^2 * aspect_ratio
+        // a = sqrt(A / aspect_ratio)
+        let vertical_angle = (opening_angle.get::<steradian>() / aspect_ration as f64).sqrt();
+        let vertical_angle = Angle::new::<radian>(vertical_angle);
+        (vertical_angle * aspect_ration as f64, vertical_angle)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use uom::si::angle::degree;
 
     const TEST_ACCURACY: f64 = 1e-5;
     const SOME_SQUARE: Rectangle = Rectangle {
@@ -106,6 +103,7 @@ mod tests {
                     some_solid_angle(),
                     &rotation_axis,
                     SOME_SQUARE,
+                    StarRendering::default(),
                 );
                 assert!(viewport
                     .center_direction
@@ -128,6 +126,7 @@ mod tests {
             some_solid_angle(),
             &rotation_axis,
             SOME_SQUARE,
+            StarRendering::default(),
         );
         let southward_viewport = Viewport::calculate(
             &observer_normal,
@@ -135,6 +134,7 @@ mod tests {
             some_solid_angle(),
             &rotation_axis,
             SOME_SQUARE,
+            StarRendering::default(),
         );
         let eastward_viewport = Viewport::calculate(
             &observer_normal,
@@ -142,6 +142,7 @@ mod tests {
             some_solid_angle(),
             &rotation_axis,
             SOME_SQUARE,
+            StarRendering::default(),
         );
         let northward_viewport = Viewport::calculate(
             &observer_normal,
@@ -149,6 +150,7 @@ mod tests {
             some_solid_angle(),
             &rotation_axis,
             SOME_SQUARE,
+            StarRendering::default(),
         );
         assert!(westward_viewport
             .center_direction
@@ -176,6 +178,7 @@ mod tests {
                         some_solid_angle(),
                         &rotation_axis,
                         SOME_SQUARE,
+                        StarRendering::default(),
                     );
 
                     let ortho = rotation_axis.cross_product(&viewport.center_direction);
@@ -209,6 +212,7 @@ mod tests {
             SolidAngle::new::<steradian>(0.),
             &rotation_axis,
             SOME_SQUARE,
+            StarRendering::default(),
         );
         let expected_top_direction = viewport.center_direction;
         assert!(viewport
@@ -229,6 +233,7 @@ mod tests {
             opening_solid_angle.into(),
             &rotation_axis,
             SOME_SQUARE,
+            StarRendering::default(),
         );
 
         let expected_top_direction = Direction::new(1., 0., 1.).unwrap();
@@ -250,6 +255,7 @@ mod tests {
             opening_solid_angle.into(),
             &rotation_axis,
             SOME_SQUARE,
+            StarRendering::default(),
         );
 
         let expected_top_direction = rotation_axis;
@@ -1,19 +1,47 @@
-use crate::gui::{
-    gui_widget::{BIG_COLUMN_WIDTH, PADDING},
-    message::GuiMessage,
-    shared_widgets::control_field,
+use super::{
+    culling::CullingStatistics, parallax::ParallaxWiggle, pole_stars::PoleStars,
+    star_counts::NakedEyeStarCounts, viewport::observer_normal,
+};
+use crate::{
+    gui::{
+        dialog::DialogType,
+        gui_widget::{BIG_COLUMN_WIDTH, PADDING},
+        message::GuiMessage,
+        shared_widgets::{control_field, editable_control_field, std_button},
+        star_rendering::StarRendering,
+        touch::TouchGesture,
+        transition::{interpolate_angle, interpolate_longitude, Interpolate, Transition},
+    },
+    model::{
+        celestial_system::{planets::SystemBody, CelestialSystem},
+        local_time::{local_solar_time, subsolar_point},
+        observer_location::ObserverLocation,
+        planet::Planet,
+    },
+};
+use astro_coords::{
+    cartesian::Cartesian, direction::Direction, spherical::Spherical, traits::*,
+    transformations::rotations::get_rotation_parameters,
 };
 use astro_units::angle::normalized_angle;
 use astro_utils::astro_display::AstroDisplay;
 use iced::{
-    widget::{canvas, Column},
-    Alignment, Element, Length,
+    time::Instant,
+    widget::{canvas, Column, PickList, Row, Text, TextInput, Toggler},
+    Alignment, Element, Length, Size,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    cell::{Cell, RefCell},
+    f64::consts::PI,
+    fmt::Display,
 };
-use std::f64::consts::PI;
 use uom::si::{
-    angle::degree,
-    f64::{Angle, SolidAngle},
+    angle::{degree, radian},
+    f64::{Angle, Length as UomLength, SolidAngle, Time},
+    length::light_year,
     solid_angle::steradian,
+    time::year,
 };
 
 #[inline(always)]
@@ -39,15 +67,163 @@ pub(crate) struct SurfaceViewState {
     pub(super) view_longitude: Angle,
     pub(super) view_latitude: Angle,
     pub(super) viewport_opening_angle: SolidAngle,
+    pub(super) observer_location_name: String,
+    tracked_body: Option<SystemBody>,
+    pub(super) path_body: Option<SystemBody>,
+    pub(super) path_time_range: Time,
+    pub(super) display_density: bool,
+    pub(super) display_doppler: bool,
+    pub(super) doppler_exaggeration: f64,
+    pub(crate) star_rendering: StarRendering,
+    pub(super) parallax_baseline: UomLength,
+    pub(super) parallax_wiggle: Option<ParallaxWiggle>,
+    surface_longitude_string: String,
+    surface_latitude_string: String,
+    view_longitude_string: String,
+    view_latitude_string: String,
+    viewport_opening_angle_string: String,
+    view_transition: Option<Transition<ViewDirection>>,
+    pub(super) naked_eye_star_counts: Cell<Option<NakedEyeStarCounts>>,
+    pub(super) pole_stars: RefCell<Option<PoleStars>>,
+    pub(super) show_debug_panel: bool,
+    pub(super) culling_statistics: Cell<CullingStatistics>,
+    pub(super) brightest_visible_star: RefCell<Option<String>>,
+}
+
+/*
+ * The part of the surface view that is exported with a view configuration.
+ * The observer's position on the surface belongs to the system, and is exported as a bookmark instead.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct SurfaceViewConfiguration {
+    view_longitude: Angle,
+    view_latitude: Angle,
+    opening_angle: SolidAngle,
+    display_density: bool,
+    display_doppler: bool,
+    doppler_exaggeration: f64,
+    parallax_baseline: UomLength,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ViewDirection {
+    longitude: Angle,
+    latitude: Angle,
+    opening_angle: SolidAngle,
+}
+
+impl ViewDirection {
+    fn default_view() -> Self {
+        ViewDirection {
+            longitude: Angle::new::<degree>(0.),
+            latitude: Angle::new::<degree>(90.),
+            opening_angle: human_eye_opening_angle(),
+        }
+    }
+
+    fn with_longitude(mut self, longitude: Angle) -> Self {
+        self.longitude = normalized_angle(longitude);
+        self
+    }
+
+    fn with_latitude(mut self, mut latitude: Angle) -> Self {
+        if latitude < angle_step() {
+            latitude = angle_step();
+        } else if latitude.get::<degree>() > 90. {
+            latitude = Angle::new::<degree>(90.);
+        }
+        self.latitude = latitude;
+        self
+    }
+
+    fn with_opening_angle(mut self, mut angle: SolidAngle) -> Self {
+        if angle < srad_step() {
+            angle = srad_step();
+        } else if angle.get::<steradian>() > 2. * PI {
+            angle = SolidAngle::new::<steradian>(2. * PI);
+        }
+        self.opening_angle = angle;
+        self
+    }
+}
+
+impl Interpolate for ViewDirection {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        ViewDirection {
+            longitude: interpolate_longitude(self.longitude, other.longitude, t),
+            latitude: interpolate_angle(self.latitude, other.latitude, t),
+            opening_angle: self.opening_angle + (other.opening_angle - self.opening_angle) * t,
+        }
+    }
+}
+
+/*
+ * An entry of the pickers for the tracked body and the plotted path.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct BodyChoice {
+    pub(crate) body: Option<SystemBody>,
+    pub(crate) label: String,
+}
+
+impl Display for BodyChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub(crate) enum SurfaceViewUpdate {
     SurfaceLongitude(Angle),
     SurfaceLatitude(Angle),
+    SurfacePosition(Angle, Angle),
+    MoveObserver(Angle, Angle),
     ViewLongitude(Angle),
     ViewLatitude(Angle),
     ViewportOpeningAngle(SolidAngle),
+    ObserverLocationName(String),
+    TrackBody(Option<SystemBody>),
+    PathBody(Option<SystemBody>),
+    PathTimeRange(Time),
+    DisplayDensity(bool),
+    DisplayDoppler(bool),
+    DopplerExaggeration(f64),
+    ParallaxBaseline(UomLength),
+    ToggleDebugPanel,
+    ResetView,
+    FieldEdited(SurfaceViewField, String),
+    FieldSubmitted(SurfaceViewField),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum SurfaceViewField {
+    SurfaceLongitude,
+    SurfaceLatitude,
+    ViewLongitude,
+    ViewLatitude,
+    ViewportOpeningAngle,
+}
+
+impl SurfaceViewField {
+    fn to_update(self, value: f64) -> SurfaceViewUpdate {
+        match self {
+            SurfaceViewField::SurfaceLongitude => {
+                SurfaceViewUpdate::SurfaceLongitude(Angle::new::<degree>(value))
+            }
+            SurfaceViewField::SurfaceLatitude => {
+                SurfaceViewUpdate::SurfaceLatitude(Angle::new::<degree>(value))
+            }
+            SurfaceViewField::ViewLongitude => {
+                SurfaceViewUpdate::ViewLongitude(Angle::new::<degree>(value))
+            }
+            SurfaceViewField::ViewLatitude => {
+                SurfaceViewUpdate::ViewLatitude(Angle::new::<degree>(value))
+            }
+            SurfaceViewField::ViewportOpeningAngle => {
+                SurfaceViewUpdate::ViewportOpeningAngle(SolidAngle::new::<steradian>(value))
+            }
+        }
+    }
 }
 
 impl From<SurfaceViewUpdate> for GuiMessage {
@@ -58,14 +234,70 @@ impl From<SurfaceViewUpdate> for GuiMessage {
 
 impl SurfaceViewState {
     pub(crate) fn new() -> Self {
-        SurfaceViewState {
+        let mut state = SurfaceViewState {
             background_cache: canvas::Cache::default(),
             bodies_cache: canvas::Cache::default(),
             surface_longitude: Angle::new::<degree>(0.),
             surface_latitude: Angle::new::<degree>(0.),
-            view_longitude: Angle::new::<degree>(0.),
-            view_latitude: Angle::new::<degree>(90.),
-            viewport_opening_angle: human_eye_opening_angle(),
+            view_longitude: ViewDirection::default_view().longitude,
+            view_latitude: ViewDirection::default_view().latitude,
+            viewport_opening_angle: ViewDirection::default_view().opening_angle,
+            observer_location_name: String::new(),
+            tracked_body: None,
+            path_body: None,
+            path_time_range: Time::new::<year>(1.),
+            display_density: false,
+            display_doppler: false,
+            doppler_exaggeration: 1000.,
+            star_rendering: StarRendering::load(),
+            parallax_baseline: UomLength::new::<light_year>(1.),
+            parallax_wiggle: None,
+            surface_longitude_string: String::new(),
+            surface_latitude_string: String::new(),
+            view_longitude_string: String::new(),
+            view_latitude_string: String::new(),
+            viewport_opening_angle_string: String::new(),
+            view_transition: None,
+            naked_eye_star_counts: Cell::new(None),
+            pole_stars: RefCell::new(None),
+            show_debug_panel: false,
+            culling_statistics: Cell::new(CullingStatistics::default()),
+            brightest_visible_star: RefCell::new(None),
+        };
+        state.fill_string_members();
+        state
+    }
+
+    /*
+     * During a transition, the fields already show where the view is heading.
+     */
+    fn fill_string_members(&mut self) {
+        let view_direction = self.view_direction_target();
+        self.surface_longitude_string = format!("{:.2}", self.surface_longitude.get::<degree>());
+        self.surface_latitude_string = format!("{:.2}", self.surface_latitude.get::<degree>());
+        self.view_longitude_string = format!("{:.2}", view_direction.longitude.get::<degree>());
+        self.view_latitude_string = format!("{:.2}", view_direction.latitude.get::<degree>());
+        self.viewport_opening_angle_string =
+            format!("{:.2}", view_direction.opening_angle.get::<steradian>());
+    }
+
+    fn field_string(&self, field: SurfaceViewField) -> &String {
+        match field {
+            SurfaceViewField::SurfaceLongitude => &self.surface_longitude_string,
+            SurfaceViewField::SurfaceLatitude => &self.surface_latitude_string,
+            SurfaceViewField::ViewLongitude => &self.view_longitude_string,
+            SurfaceViewField::ViewLatitude => &self.view_latitude_string,
+            SurfaceViewField::ViewportOpeningAngle => &self.viewport_opening_angle_string,
+        }
+    }
+
+    fn field_string_mut(&mut self, field: SurfaceViewField) -> &mut String {
+        match field {
+            SurfaceViewField::SurfaceLongitude => &mut self.surface_longitude_string,
+            SurfaceViewField::SurfaceLatitude => &mut self.surface_latitude_string,
+            SurfaceViewField::ViewLongitude => &mut self.view_longitude_string,
+            SurfaceViewField::ViewLatitude => &mut self.view_latitude_string,
+            SurfaceViewField::ViewportOpeningAngle => &mut self.viewport_opening_angle_string,
         }
     }
 
@@ -83,82 +315,586 @@ impl SurfaceViewState {
                 }
                 self.surface_latitude = latitude;
             }
-            SurfaceViewUpdate::ViewLongitude(mut longitude) => {
-                longitude = normalized_angle(longitude);
-                self.view_longitude = longitude;
+            SurfaceViewUpdate::SurfacePosition(longitude, latitude) => {
+                self.update(SurfaceViewUpdate::SurfaceLongitude(longitude));
+                self.update(SurfaceViewUpdate::SurfaceLatitude(latitude));
             }
-            SurfaceViewUpdate::ViewLatitude(mut latitude) => {
-                if latitude < angle_step() {
-                    latitude = angle_step();
-                } else if latitude.get::<degree>() > 90. {
-                    latitude = Angle::new::<degree>(90.);
-                }
-                self.view_latitude = latitude;
+            SurfaceViewUpdate::MoveObserver(east, north) => {
+                self.update(SurfaceViewUpdate::SurfacePosition(
+                    self.surface_longitude + east,
+                    self.surface_latitude + north,
+                ));
+            }
+            SurfaceViewUpdate::ViewLongitude(longitude) => {
+                let target = self.view_direction_target().with_longitude(longitude);
+                self.start_view_transition(target);
+            }
+            SurfaceViewUpdate::ViewLatitude(latitude) => {
+                let target = self.view_direction_target().with_latitude(latitude);
+                self.start_view_transition(target);
+            }
+            SurfaceViewUpdate::ViewportOpeningAngle(angle) => {
+                let target = self.view_direction_target().with_opening_angle(angle);
+                self.start_view_transition(target);
+            }
+            SurfaceViewUpdate::ObserverLocationName(name) => {
+                self.observer_location_name = name;
+            }
+            SurfaceViewUpdate::TrackBody(body) => {
+                self.tracked_body = body;
             }
-            SurfaceViewUpdate::ViewportOpeningAngle(mut angle) => {
-                if angle < srad_step() {
-                    angle = srad_step();
-                } else if angle.get::<steradian>() > 2. * PI {
-                    angle = SolidAngle::new::<steradian>(2. * PI);
+            SurfaceViewUpdate::PathBody(body) => {
+                self.path_body = body;
+            }
+            SurfaceViewUpdate::PathTimeRange(time_range) => {
+                self.path_time_range = time_range;
+            }
+            SurfaceViewUpdate::DisplayDensity(display_density) => {
+                self.display_density = display_density;
+            }
+            SurfaceViewUpdate::DisplayDoppler(display_doppler) => {
+                self.display_doppler = display_doppler;
+            }
+            SurfaceViewUpdate::DopplerExaggeration(exaggeration) => {
+                self.doppler_exaggeration = exaggeration;
+            }
+            SurfaceViewUpdate::ParallaxBaseline(baseline) => {
+                self.parallax_baseline = baseline;
+            }
+            SurfaceViewUpdate::ToggleDebugPanel => {
+                self.show_debug_panel = !self.show_debug_panel;
+            }
+            SurfaceViewUpdate::ResetView => {
+                self.start_view_transition(ViewDirection::default_view());
+            }
+            SurfaceViewUpdate::FieldEdited(field, text) => {
+                *self.field_string_mut(field) = text;
+                return;
+            }
+            SurfaceViewUpdate::FieldSubmitted(field) => {
+                let value = self.field_string(field).trim().parse::<f64>();
+                // Rust parses "nan" and "inf" as numbers, which no angle can be.
+                if let Some(value) = value.ok().filter(|value| value.is_finite()) {
+                    self.update(field.to_update(value));
                 }
-                self.viewport_opening_angle = angle;
             }
         }
+        self.fill_string_members();
+    }
+
+    pub(crate) fn redraw_background(&mut self) {
+        self.background_cache.clear();
     }
 
     pub(crate) fn redraw(&mut self) {
         self.bodies_cache.clear();
+        self.naked_eye_star_counts.set(None);
+        self.pole_stars.replace(None);
+    }
+
+    fn current_view_direction(&self) -> ViewDirection {
+        ViewDirection {
+            longitude: self.view_longitude,
+            latitude: self.view_latitude,
+            opening_angle: self.viewport_opening_angle,
+        }
+    }
+
+    fn view_direction_target(&self) -> ViewDirection {
+        match &self.view_transition {
+            Some(transition) => *transition.get_target(),
+            None => self.current_view_direction(),
+        }
+    }
+
+    /*
+     * Only discrete jumps are animated, such as the buttons, the entered values or pointing at another body.
+     */
+    fn start_view_transition(&mut self, target: ViewDirection) {
+        self.view_transition = Some(Transition::new(self.current_view_direction(), target));
+    }
+
+    /*
+     * Continuous input and tracking change the view on every event, which restarting the easing would only delay.
+     */
+    fn snap_view_direction(&mut self, target: ViewDirection) {
+        self.view_transition = None;
+        self.set_view_direction(target);
+        self.fill_string_members();
+    }
+
+    fn set_view_direction(&mut self, view_direction: ViewDirection) {
+        self.view_longitude = view_direction.longitude;
+        self.view_latitude = view_direction.latitude;
+        self.viewport_opening_angle = view_direction.opening_angle;
+    }
+
+    pub(crate) fn configuration(&self) -> SurfaceViewConfiguration {
+        let view_direction = self.view_direction_target();
+        SurfaceViewConfiguration {
+            view_longitude: view_direction.longitude,
+            view_latitude: view_direction.latitude,
+            opening_angle: view_direction.opening_angle,
+            display_density: self.display_density,
+            display_doppler: self.display_doppler,
+            doppler_exaggeration: self.doppler_exaggeration,
+            parallax_baseline: self.parallax_baseline,
+        }
+    }
+
+    pub(crate) fn apply_configuration(&mut self, configuration: SurfaceViewConfiguration) {
+        self.display_density = configuration.display_density;
+        self.display_doppler = configuration.display_doppler;
+        self.doppler_exaggeration = configuration.doppler_exaggeration;
+        self.parallax_baseline = configuration.parallax_baseline;
+        self.start_view_transition(ViewDirection {
+            longitude: configuration.view_longitude,
+            latitude: configuration.view_latitude,
+            opening_angle: configuration.opening_angle,
+        });
+        self.fill_string_members();
+    }
+
+    pub(crate) fn is_animating(&self) -> bool {
+        self.view_transition.is_some() || self.parallax_wiggle.is_some()
+    }
+
+    pub(crate) fn start_parallax_wiggle(&mut self, celestial_system: &CelestialSystem) {
+        self.parallax_wiggle = Some(ParallaxWiggle::new(celestial_system));
+    }
+
+    pub(crate) fn animate(&mut self, now: Instant) {
+        if let Some(wiggle) = &mut self.parallax_wiggle {
+            wiggle.animate(now);
+            if wiggle.is_finished(now) {
+                self.parallax_wiggle = None;
+            }
+        }
+        if let Some(transition) = &self.view_transition {
+            let view_direction = transition.value(now);
+            let is_finished = transition.is_finished(now);
+            self.set_view_direction(view_direction);
+            if is_finished {
+                self.view_transition = None;
+            }
+        }
+    }
+
+    pub(crate) fn point_at_central_body(
+        &mut self,
+        observer_planet: &Planet,
+        time_since_epoch: Time,
+    ) {
+        self.point_at_position(
+            observer_planet,
+            &Cartesian::origin(),
+            time_since_epoch,
+            true,
+        );
+    }
+
+    /*
+     * Pointing at another body is animated, while following a tracked body snaps to it on every time step.
+     */
+    pub(crate) fn point_at_position(
+        &mut self,
+        observer_planet: &Planet,
+        target: &Cartesian,
+        time_since_epoch: Time,
+        animated: bool,
+    ) {
+        let surface_position = Spherical::new(self.surface_longitude, self.surface_latitude);
+        let observer_normal = observer_normal(
+            observer_planet.get_data(),
+            observer_planet.get_rotation_angle_at_epoch(),
+            surface_position,
+            time_since_epoch,
+        );
+        let observer_position = self.observer_position(observer_planet, &observer_normal);
+        let direction_to_target = match (target.clone() + -&observer_position).to_direction() {
+            Ok(direction) => direction,
+            Err(_) => return,
+        };
+        // Undo the rotation that the viewport applies to the local view direction.
+        let (angle, rotation_axis) = get_rotation_parameters(&Direction::Z, &observer_normal);
+        let local_view_direction = direction_to_target
+            .rotated(-angle, &rotation_axis)
+            .to_spherical();
+        let target = self
+            .view_direction_target()
+            .with_longitude(local_view_direction.longitude)
+            .with_latitude(local_view_direction.latitude);
+        if animated {
+            self.start_view_transition(target);
+            self.fill_string_members();
+        } else {
+            self.snap_view_direction(target);
+        }
+    }
+
+    pub(crate) fn local_solar_time(
+        &self,
+        observer_planet: &Planet,
+        time_since_epoch: Time,
+    ) -> Option<f64> {
+        let surface_position = Spherical::new(self.surface_longitude, self.surface_latitude);
+        let observer_normal = observer_normal(
+            observer_planet.get_data(),
+            observer_planet.get_rotation_angle_at_epoch(),
+            surface_position,
+            time_since_epoch,
+        );
+        let to_central_body = (-observer_planet.get_position()).to_direction().ok()?;
+        local_solar_time(
+            observer_planet.get_data().get_rotation_axis(),
+            &observer_normal,
+            &to_central_body,
+            observer_planet.get_data().get_sideral_rotation_period(),
+        )
+    }
+
+    /*
+     * Where on the surface of the observer planet the central body is at the zenith.
+     */
+    pub(crate) fn subsolar_point(
+        &self,
+        observer_planet: &Planet,
+        time_since_epoch: Time,
+    ) -> Option<Spherical> {
+        let to_central_body = (-observer_planet.get_position()).to_direction().ok()?;
+        Some(subsolar_point(
+            observer_planet.get_data(),
+            observer_planet.get_rotation_angle_at_epoch(),
+            &to_central_body,
+            time_since_epoch,
+        ))
+    }
+
+    /*
+     * Pinching changes the opening angle, moving two fingers turns the view along with them.
+     */
+    pub(crate) fn apply_touch_gesture(&mut self, gesture: TouchGesture, canvas_size: Size) {
+        if gesture.zoom <= 0. || canvas_size.height <= 0. || canvas_size.width <= 0. {
+            return;
+        }
+        let target = self.view_direction_target();
+        let aspect_ratio = (canvas_size.width / canvas_size.height) as f64;
+        let vertical_angle = (target.opening_angle.get::<steradian>() / aspect_ratio).sqrt();
+        let angle_per_pixel = Angle::new::<radian>(vertical_angle / canvas_size.height as f64);
+        let gestured = target
+            .with_opening_angle(target.opening_angle / (gesture.zoom as f64).powi(2))
+            .with_longitude(target.longitude - angle_per_pixel * gesture.pan.x as f64)
+            .with_latitude(target.latitude + angle_per_pixel * gesture.pan.y as f64);
+        self.snap_view_direction(gestured);
+    }
+
+    pub(crate) fn get_tracked_body(&self) -> Option<SystemBody> {
+        self.tracked_body
     }
 
-    pub(crate) fn control_field(&self) -> Element<'_, GuiMessage> {
+    pub(crate) fn current_observer_location(&self) -> ObserverLocation {
+        ObserverLocation::new(
+            self.observer_location_name.clone(),
+            self.surface_longitude,
+            self.surface_latitude,
+        )
+    }
+
+    pub(crate) fn control_field(
+        &self,
+        observer_locations: &[ObserverLocation],
+        trackable_bodies: Vec<BodyChoice>,
+        naked_eye_star_counts: Option<NakedEyeStarCounts>,
+        pole_stars: Option<PoleStars>,
+        subsolar_point: Option<Spherical>,
+    ) -> Element<'_, GuiMessage> {
         let surface_long = self.surface_longitude;
-        let surface_longitude_control_field = control_field(
-            "Surface Longitude:",
-            surface_long.astro_display(),
+        let surface_longitude_control_field = self.angle_control_field(
+            "Surface Longitude (°):",
+            SurfaceViewField::SurfaceLongitude,
             SurfaceViewUpdate::SurfaceLongitude(surface_long - angle_step()),
             SurfaceViewUpdate::SurfaceLongitude(surface_long + angle_step()),
         );
 
         let surface_lat = self.surface_latitude;
-        let surface_latitude_control_field = control_field(
-            "Surface Latitude:",
-            surface_lat.astro_display(),
+        let surface_latitude_control_field = self.angle_control_field(
+            "Surface Latitude (°):",
+            SurfaceViewField::SurfaceLatitude,
             SurfaceViewUpdate::SurfaceLatitude(surface_lat - angle_step()),
             SurfaceViewUpdate::SurfaceLatitude(surface_lat + angle_step()),
         );
 
-        let view_long = self.view_longitude;
-        let view_longitude_control_field = control_field(
-            "Observer Longitude:",
-            view_long.astro_display(),
+        let view_direction = self.view_direction_target();
+        let view_long = view_direction.longitude;
+        let view_longitude_control_field = self.angle_control_field(
+            "Observer Longitude (°):",
+            SurfaceViewField::ViewLongitude,
             SurfaceViewUpdate::ViewLongitude(view_long - angle_step()),
             SurfaceViewUpdate::ViewLongitude(view_long + angle_step()),
         );
 
-        let view_lat = self.view_latitude;
-        let view_latitude_control_field = control_field(
-            "Observer Latitude:",
-            view_lat.astro_display(),
+        let view_lat = view_direction.latitude;
+        let view_latitude_control_field = self.angle_control_field(
+            "Observer Latitude (°):",
+            SurfaceViewField::ViewLatitude,
             SurfaceViewUpdate::ViewLatitude(view_lat - angle_step()),
             SurfaceViewUpdate::ViewLatitude(view_lat + angle_step()),
         );
 
-        let viewport_angle = self.viewport_opening_angle;
-        let viewport_angle_control_field = control_field(
-            "Viewport Opening Angle:",
-            viewport_angle.astro_display(),
+        let viewport_angle = view_direction.opening_angle;
+        let viewport_angle_control_field = self.angle_control_field(
+            "Viewport Opening Angle (sr):",
+            SurfaceViewField::ViewportOpeningAngle,
             SurfaceViewUpdate::ViewportOpeningAngle(viewport_angle - srad_step()),
             SurfaceViewUpdate::ViewportOpeningAngle(viewport_angle + srad_step()),
         );
+
+        let selected_location = observer_locations
+            .iter()
+            .find(|l| {
+                l.get_longitude() == self.surface_longitude
+                    && l.get_latitude() == self.surface_latitude
+            })
+            .cloned();
+        let location_picker = PickList::new(
+            observer_locations.to_vec(),
+            selected_location,
+            GuiMessage::ObserverLocationSelected,
+        )
+        .placeholder("Saved locations")
+        .width(Length::Fill);
+        let location_name_input = TextInput::new("Location name", &self.observer_location_name)
+            .on_input(|name| SurfaceViewUpdate::ObserverLocationName(name).into())
+            .width(Length::Fill);
+        let save_location_button = std_button(
+            "Save Location",
+            GuiMessage::SaveObserverLocation,
+            !self.observer_location_name.is_empty(),
+        );
+        let observer_location_row = Row::new()
+            .push(location_picker)
+            .push(location_name_input)
+            .push(save_location_button)
+            .spacing(PADDING)
+            .align_y(Alignment::Center);
+
+        let reset_view_button = std_button("Reset View", SurfaceViewUpdate::ResetView.into(), true);
+        let sky_calendar_button =
+            std_button("Export Sky Calendar", GuiMessage::ExportSkyCalendar, true);
+        let mut body_choices = vec![BodyChoice {
+            body: None,
+            label: String::new(),
+        }];
+        body_choices.extend(trackable_bodies);
+        let chosen = |body: Option<SystemBody>| {
+            body_choices
+                .iter()
+                .find(|choice| choice.body == body)
+                .cloned()
+        };
+        let path_body_picker =
+            PickList::new(body_choices.clone(), chosen(self.path_body), |choice| {
+                SurfaceViewUpdate::PathBody(choice.body).into()
+            })
+            .width(Length::Fill);
+        let path_body_row = Row::new()
+            .push(Text::new("Plot path of:"))
+            .push(path_body_picker)
+            .spacing(PADDING)
+            .align_y(Alignment::Center);
+        let path_time_range_control_field = control_field(
+            "Path time range:",
+            self.path_time_range.astro_display(),
+            SurfaceViewUpdate::PathTimeRange(self.path_time_range / 2.),
+            SurfaceViewUpdate::PathTimeRange(self.path_time_range * 2.),
+        );
+        let density_toggler = Toggler::new(self.display_density)
+            .label("Shade by star density")
+            .on_toggle(|b| SurfaceViewUpdate::DisplayDensity(b).into());
+        let doppler_toggler = Toggler::new(self.display_doppler)
+            .label("Tint by radial velocity")
+            .on_toggle(|b| SurfaceViewUpdate::DisplayDoppler(b).into());
+        let doppler_exaggeration_control_field = control_field(
+            "Doppler exaggeration:",
+            format!("{}x", self.doppler_exaggeration),
+            SurfaceViewUpdate::DopplerExaggeration(self.doppler_exaggeration / 10.),
+            SurfaceViewUpdate::DopplerExaggeration(self.doppler_exaggeration * 10.),
+        );
+        let parallax_baseline_control_field = control_field(
+            "Parallax baseline:",
+            self.parallax_baseline.astro_display(),
+            SurfaceViewUpdate::ParallaxBaseline(self.parallax_baseline / 2.),
+            SurfaceViewUpdate::ParallaxBaseline(self.parallax_baseline * 2.),
+        );
+        let wiggle_button = std_button(
+            "Wiggle",
+            GuiMessage::StartParallaxWiggle,
+            self.parallax_wiggle.is_none(),
+        );
+        let parallax_row = Row::new()
+            .push(parallax_baseline_control_field)
+            .push(wiggle_button)
+            .spacing(PADDING)
+            .align_y(Alignment::Center);
+        let star_rendering_button = std_button(
+            "Calibrate Star Rendering",
+            GuiMessage::OpenDialog(DialogType::StarRendering),
+            true,
+        );
+        let track_body_picker =
+            PickList::new(body_choices.clone(), chosen(self.tracked_body), |choice| {
+                SurfaceViewUpdate::TrackBody(choice.body).into()
+            })
+            .width(Length::Fill);
+        let track_body_row = Row::new()
+            .push(Text::new("Track body:"))
+            .push(track_body_picker)
+            .push(reset_view_button)
+            .push(sky_calendar_button)
+            .spacing(PADDING)
+            .align_y(Alignment::Center);
+
+        let surface_position_row = Row::new()
+            .push(
+                Column::new()
+                    .push(surface_longitude_control_field)
+                    .push(surface_latitude_control_field)
+                    .push(subsolar_point_text(&subsolar_point))
+                    .spacing(PADDING),
+            )
+            .push(self.planet_map(subsolar_point))
+            .spacing(PADDING)
+            .align_y(Alignment::Center);
+
         Column::new()
-            .push(surface_longitude_control_field)
-            .push(surface_latitude_control_field)
+            .push(observer_location_row)
+            .push(surface_position_row)
             .push(view_longitude_control_field)
             .push(view_latitude_control_field)
             .push(viewport_angle_control_field)
+            .push(track_body_row)
+            .push(path_body_row)
+            .push(path_time_range_control_field)
+            .push(density_toggler)
+            .push(doppler_toggler)
+            .push(doppler_exaggeration_control_field)
+            .push(parallax_row)
+            .push(star_rendering_button)
+            .push(naked_eye_star_count_text(naked_eye_star_counts))
+            .push(pole_star_text(pole_stars))
+            .push(self.debug_panel())
             .width(Length::Fixed(BIG_COLUMN_WIDTH))
             .align_x(Alignment::Center)
             .spacing(PADDING)
             .into()
     }
+
+    fn angle_control_field<'a>(
+        &'a self,
+        label: &'a str,
+        field: SurfaceViewField,
+        decrease: SurfaceViewUpdate,
+        increase: SurfaceViewUpdate,
+    ) -> Row<'a, GuiMessage> {
+        editable_control_field(
+            label,
+            self.field_string(field),
+            move |text| SurfaceViewUpdate::FieldEdited(field, text),
+            SurfaceViewUpdate::FieldSubmitted(field),
+            decrease,
+            increase,
+        )
+    }
+}
+
+fn naked_eye_star_count_text(counts: Option<NakedEyeStarCounts>) -> Text<'static> {
+    let text = match counts {
+        Some(counts) => format!(
+            "Naked-eye stars: {} ({} above the horizon)",
+            counts.total, counts.above_horizon
+        ),
+        None => "Naked-eye stars: N/A".to_string(),
+    };
+    Text::new(text)
+}
+
+fn subsolar_point_text(subsolar_point: &Option<Spherical>) -> Text<'static> {
+    let text = match subsolar_point {
+        Some(point) => format!(
+            "Subsolar point: {:.1}° longitude, {:.1}° latitude",
+            point.longitude.get::<degree>(),
+            point.latitude.get::<degree>()
+        ),
+        None => "Subsolar point: N/A".to_string(),
+    };
+    Text::new(text)
+}
+
+fn pole_star_text(pole_stars: Option<PoleStars>) -> Text<'static> {
+    let text = match pole_stars {
+        Some(pole_stars) => pole_stars.describe(),
+        None => "Pole stars: N/A".to_string(),
+    };
+    Text::new(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn typed_surface_latitude_is_applied_on_submit() {
+        let mut state = SurfaceViewState::new();
+        state.update(SurfaceViewUpdate::FieldEdited(
+            SurfaceViewField::SurfaceLatitude,
+            "51.48".to_string(),
+        ));
+        state.update(SurfaceViewUpdate::FieldSubmitted(
+            SurfaceViewField::SurfaceLatitude,
+        ));
+        assert!((state.surface_latitude.get::<degree>() - 51.48).abs() < 1e-10);
+    }
+
+    #[test]
+    fn unparsable_input_is_reset_on_submit() {
+        let mut state = SurfaceViewState::new();
+        state.update(SurfaceViewUpdate::FieldEdited(
+            SurfaceViewField::ViewLongitude,
+            "north".to_string(),
+        ));
+        state.update(SurfaceViewUpdate::FieldSubmitted(
+            SurfaceViewField::ViewLongitude,
+        ));
+        assert_eq!(state.view_longitude, Angle::new::<degree>(0.));
+        assert_eq!(state.view_longitude_string, "0.00");
+    }
+
+    #[test]
+    fn non_finite_input_is_reset_on_submit() {
+        let mut state = SurfaceViewState::new();
+        state.update(SurfaceViewUpdate::FieldEdited(
+            SurfaceViewField::SurfaceLatitude,
+            "nan".to_string(),
+        ));
+        state.update(SurfaceViewUpdate::FieldSubmitted(
+            SurfaceViewField::SurfaceLatitude,
+        ));
+        assert!(state.surface_latitude.get::<degree>().is_finite());
+        assert_eq!(state.surface_latitude_string, "0.00");
+    }
+
+    #[test]
+    fn gestures_snap_and_buttons_animate() {
+        let mut state = SurfaceViewState::new();
+        let drag = TouchGesture {
+            zoom: 1.,
+            pan: iced::Vector::new(10., 0.),
+        };
+        state.apply_touch_gesture(drag, Size::new(100., 100.));
+        assert!(!state.is_animating());
+        assert_ne!(state.view_longitude, Angle::new::<degree>(0.));
+
+        state.update(SurfaceViewUpdate::ViewLatitude(Angle::new::<degree>(45.)));
+        assert!(state.is_animating());
+    }
 }
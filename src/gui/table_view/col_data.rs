@@ -1,10 +1,24 @@
-use crate::model::{planet::Planet, star::Star};
+use crate::model::{
+    body_id::BodyId,
+    celestial_system::{part::PartOfCelestialSystem, tidal_locking::TidalLocking, CelestialSystem},
+    planet::Planet,
+    star::Star,
+};
+use astro_coords::cartesian::Cartesian;
+use astro_units::illuminance::illuminance_to_apparent_magnitude;
 use astro_utils::astro_display::AstroDisplay;
+use std::collections::HashMap;
 use uom::si::{f64::Time, time::year};
 
-pub(super) struct TableColData<T> {
+/*
+ * Columns without a sort key are sorted by their text.
+ * The unit is the one of the sort key, which holds the value in SI base units, and is empty for dimensionless keys.
+ */
+pub(super) struct TableColData<'a, T> {
     pub(super) header: &'static str,
-    pub(super) content_closure: Box<dyn Fn(&T) -> Option<String>>,
+    pub(super) unit: &'static str,
+    pub(super) content_closure: Box<dyn Fn(&T) -> Option<String> + 'a>,
+    pub(super) sort_key: Option<Box<dyn Fn(&T) -> Option<f64> + 'a>>,
 }
 
 #[derive(Debug, Clone)]
@@ -12,76 +26,135 @@ pub(crate) enum TableDataType {
     Planet,
     Star,
     Supernova,
+    Birth,
 }
 
-impl TableColData<Planet> {
-    pub(super) fn default_planet_col_data() -> Vec<TableColData<Planet>> {
+impl<'a, T: PartOfCelestialSystem + 'a> TableColData<'a, T> {
+    fn notes_col_data(system: &'a CelestialSystem) -> Vec<TableColData<'a, T>> {
         vec![
+            TableColData {
+                header: "Tags",
+                unit: "",
+                content_closure: Box::new(move |body| {
+                    let notes = system.find_body_notes(body.get_id());
+                    Some(notes.map(|n| n.tags_string()).unwrap_or_default())
+                }),
+                sort_key: None,
+            },
+            TableColData {
+                header: "Notes",
+                unit: "",
+                content_closure: Box::new(move |body| {
+                    let notes = system.find_body_notes(body.get_id());
+                    Some(notes.map(|n| n.notes.clone()).unwrap_or_default())
+                }),
+                sort_key: None,
+            },
+        ]
+    }
+}
+
+impl<'a> TableColData<'a, Planet> {
+    pub(super) fn default_planet_col_data(
+        observer_position: Option<Cartesian>,
+        tidal_locking: HashMap<BodyId, TidalLocking>,
+        system: &'a CelestialSystem,
+    ) -> Vec<TableColData<'a, Planet>> {
+        let mut col_data = vec![
             TableColData {
                 header: "Planet Name",
+                unit: "",
                 content_closure: Box::new(|body| {
                     let name = body.get_data().get_name();
                     Some(name.to_string())
                 }),
+                sort_key: None,
             },
             TableColData {
                 header: "Mass",
+                unit: "kg",
                 content_closure: Box::new(|body| {
                     let mass = body.get_data().get_mass();
                     Some(mass.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| Some(body.get_data().get_mass().value))),
             },
             TableColData {
                 header: "Radius",
+                unit: "m",
                 content_closure: Box::new(|body| {
                     let radius = body.get_data().get_radius();
                     Some(radius.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| Some(body.get_data().get_radius().value))),
             },
             TableColData {
                 header: "Density",
+                unit: "kg/m³",
                 content_closure: Box::new(|body| {
                     let density = body.get_derived_data()?.get_density();
                     Some(density.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_derived_data()?.get_density().value)
+                })),
             },
             TableColData {
                 header: "Surface Gravity",
+                unit: "m/s²",
                 content_closure: Box::new(|body| {
                     let surface_gravity = body.get_derived_data()?.get_surface_gravity();
                     Some(surface_gravity.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_derived_data()?.get_surface_gravity().value)
+                })),
             },
             TableColData {
                 header: "Escape Velocity",
+                unit: "m/s",
                 content_closure: Box::new(|body| {
                     let escape_velocity = body.get_derived_data()?.get_escape_velocity();
                     Some(escape_velocity.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_derived_data()?.get_escape_velocity().value)
+                })),
             },
             TableColData {
                 header: "Color",
+                unit: "",
                 content_closure: Box::new(|body| {
                     let color = body.get_data().get_color();
                     Some(color.astro_display())
                 }),
+                sort_key: None,
             },
             TableColData {
                 header: "Geometric Albedo",
+                unit: "",
                 content_closure: Box::new(|body| {
                     let albedo = body.get_data().get_geometric_albedo();
                     Some(format!("{:.2}", albedo))
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_data().get_geometric_albedo())
+                })),
             },
             TableColData {
                 header: "Black Body Temp.",
+                unit: "K",
                 content_closure: Box::new(|body| {
                     let temperature = body.get_derived_data()?.get_black_body_temperature();
                     Some(temperature.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_derived_data()?.get_black_body_temperature().value)
+                })),
             },
             TableColData {
                 header: "Semi-major Axis",
+                unit: "m",
                 content_closure: Box::new(|body| {
                     let semi_major_axis = body
                         .get_data()
@@ -89,23 +162,45 @@ impl TableColData<Planet> {
                         .get_semi_major_axis();
                     Some(semi_major_axis.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(
+                        body.get_data()
+                            .get_orbital_parameters()
+                            .get_semi_major_axis()
+                            .value,
+                    )
+                })),
             },
             TableColData {
                 header: "Eccentricity",
+                unit: "",
                 content_closure: Box::new(|body| {
                     let eccentricity = body.get_data().get_orbital_parameters().get_eccentricity();
                     Some(format!("{:.2}", eccentricity))
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_data().get_orbital_parameters().get_eccentricity())
+                })),
             },
             TableColData {
                 header: "Inclination",
+                unit: "rad",
                 content_closure: Box::new(|body| {
                     let inclination = body.get_data().get_orbital_parameters().get_inclination();
                     Some(inclination.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(
+                        body.get_data()
+                            .get_orbital_parameters()
+                            .get_inclination()
+                            .value,
+                    )
+                })),
             },
             TableColData {
                 header: "Ascending Node",
+                unit: "",
                 content_closure: Box::new(|body| {
                     let ascending_node = body
                         .get_data()
@@ -113,9 +208,11 @@ impl TableColData<Planet> {
                         .get_longitude_of_ascending_node();
                     Some(ascending_node.astro_display())
                 }),
+                sort_key: None,
             },
             TableColData {
                 header: "Arg. of Periapsis",
+                unit: "",
                 content_closure: Box::new(|body| {
                     let arg_of_periapsis = body
                         .get_data()
@@ -123,192 +220,447 @@ impl TableColData<Planet> {
                         .get_argument_of_periapsis();
                     Some(arg_of_periapsis.astro_display())
                 }),
+                sort_key: None,
             },
             TableColData {
                 header: "Orbital Period",
+                unit: "s",
                 content_closure: Box::new(|body| {
                     let orbital_period = body.get_derived_data()?.get_orbital_period();
                     Some(orbital_period.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_derived_data()?.get_orbital_period().value)
+                })),
             },
             TableColData {
                 header: "Orbital Resonance",
+                unit: "",
                 content_closure: Box::new(|body| {
                     let orbital_resonance = body.get_derived_data()?.get_orbital_resonance()?;
                     Some(orbital_resonance.astro_display())
                 }),
+                sort_key: None,
+            },
+            TableColData {
+                header: "Phase Angle",
+                unit: "",
+                content_closure: Box::new(move |body| {
+                    let phase_angle = body.get_phase_angle(observer_position.as_ref()?)?;
+                    Some(phase_angle.astro_display())
+                }),
+                sort_key: None,
             },
             TableColData {
                 header: "Sideral Day",
+                unit: "s",
                 content_closure: Box::new(|body| {
                     let siderial_day = body.get_data().get_sideral_rotation_period();
                     Some(siderial_day.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_data().get_sideral_rotation_period().value)
+                })),
             },
             TableColData {
                 header: "Synodic Day",
+                unit: "s",
                 content_closure: Box::new(|body| {
                     let synodic_day = body.get_derived_data()?.get_mean_synodic_day();
                     Some(synodic_day.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_derived_data()?.get_mean_synodic_day().value)
+                })),
+            },
+            TableColData {
+                header: "Tidal Locking",
+                unit: "",
+                content_closure: Box::new(move |body| {
+                    let locking = tidal_locking.get(&body.get_id()?)?;
+                    Some(locking.describe())
+                }),
+                sort_key: None,
             },
             TableColData {
                 header: "Rotation Axis",
+                unit: "",
                 content_closure: Box::new(|body| {
                     let rotation_axis = body.get_data().get_rotation_axis();
                     Some(format!("{}", rotation_axis))
                 }),
+                sort_key: None,
             },
             TableColData {
                 header: "Axial Tilt",
+                unit: "rad",
                 content_closure: Box::new(|body| {
                     let axial_tilt = body.get_derived_data()?.get_axial_tilt();
                     Some(axial_tilt.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_derived_data()?.get_axial_tilt().value)
+                })),
             },
-        ]
+        ];
+        col_data.extend(Self::notes_col_data(system));
+        col_data
     }
 }
 
-impl TableColData<Star> {
-    pub(super) fn default_star_col_data() -> Vec<TableColData<Star>> {
-        vec![
+impl<'a> TableColData<'a, Star> {
+    /*
+     * The catalog columns are only of interest for judging imported measurements, so they are optional.
+     */
+    pub(super) fn default_star_col_data(
+        system: &'a CelestialSystem,
+        catalog_columns: bool,
+    ) -> Vec<TableColData<'a, Star>> {
+        let mut col_data = vec![
             TableColData {
                 header: "Star Name",
+                unit: "",
                 content_closure: Box::new(|body| {
                     let name = body.get_appearance().get_name();
                     Some(name.to_string())
                 }),
+                sort_key: None,
             },
             TableColData {
                 header: "Mass",
+                unit: "kg",
                 content_closure: Box::new(|body| {
                     let mass = body.get_data()?.get_mass_at_epoch()?;
                     Some(mass.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_data()?.get_mass_at_epoch()?.value)
+                })),
             },
             TableColData {
                 header: "Radius",
+                unit: "m",
                 content_closure: Box::new(|body| {
                     let radius = body.get_data()?.get_radius_at_epoch()?;
                     Some(radius.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_data()?.get_radius_at_epoch()?.value)
+                })),
             },
             TableColData {
                 header: "Luminous Intensity",
+                unit: "cd",
                 content_closure: Box::new(|body| {
                     let luminous_intensity = body.get_data()?.get_luminous_intensity_at_epoch();
                     Some(luminous_intensity.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_data()?.get_luminous_intensity_at_epoch().value)
+                })),
             },
             TableColData {
                 header: "Temperature",
+                unit: "K",
                 content_closure: Box::new(|body| {
                     Some(body.get_data()?.get_temperature_at_epoch().astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_data()?.get_temperature_at_epoch().value)
+                })),
             },
             TableColData {
                 header: "Color",
+                unit: "",
                 content_closure: Box::new(|body| {
                     let color = body.get_appearance().get_color();
                     Some(color.astro_display())
                 }),
+                sort_key: None,
+            },
+            TableColData {
+                header: "Override",
+                unit: "",
+                content_closure: Box::new(|body| {
+                    body.get_appearance_override()
+                        .map(|_| "Overridden".to_string())
+                }),
+                sort_key: None,
             },
             TableColData {
                 header: "Age",
+                unit: "s",
                 content_closure: Box::new(|body| {
                     let age = body.get_data()?.get_age_at_epoch()?;
                     Some(age.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_data()?.get_age_at_epoch()?.value)
+                })),
             },
             TableColData {
                 header: "Distance",
+                unit: "m",
                 content_closure: Box::new(|body| {
                     Some(body.get_data()?.get_distance_at_epoch().astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_data()?.get_distance_at_epoch().value)
+                })),
             },
             TableColData {
                 header: "Vis. Mag.",
+                unit: "mag",
                 content_closure: Box::new(|body| {
                     let illuminance = body.get_appearance().get_illuminance();
                     Some(illuminance.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(illuminance_to_apparent_magnitude(
+                        body.get_appearance().get_illuminance(),
+                    ))
+                })),
             },
             TableColData {
                 header: "Ecl. Lon.",
+                unit: "",
                 content_closure: Box::new(|body| {
                     let longitude = body.get_appearance().get_pos().spherical.longitude;
                     Some(longitude.astro_display())
                 }),
+                sort_key: None,
             },
             TableColData {
                 header: "Ecl. Lat.",
+                unit: "",
                 content_closure: Box::new(|body| {
                     let latitude = body.get_appearance().get_pos().spherical.latitude;
                     Some(latitude.astro_display())
                 }),
+                sort_key: None,
             },
             TableColData {
                 header: "Const.",
+                unit: "",
                 content_closure: Box::new(|body| {
                     let constellation = body.get_data()?.get_constellation().clone()?;
                     Some(constellation.astro_display())
                 }),
+                sort_key: None,
             },
             TableColData {
                 header: "Lifetime",
+                unit: "s",
                 content_closure: Box::new(|body| {
                     let lifetime = body.get_data()?.get_lifetime();
                     Some(lifetime.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| Some(body.get_data()?.get_lifetime().value))),
             },
             TableColData {
                 header: "Fate",
+                unit: "",
                 content_closure: Box::new(|body| {
                     let fate = body.get_data()?.get_fate();
                     Some(fate.astro_display())
                 }),
+                sort_key: None,
+            },
+            TableColData {
+                header: "Origin",
+                unit: "",
+                content_closure: Box::new(|body| Some(body.get_provenance().origin.to_string())),
+                sort_key: None,
+            },
+            TableColData {
+                header: "Last Edited",
+                unit: "",
+                content_closure: Box::new(|body| body.get_provenance().last_edited.clone()),
+                sort_key: None,
+            },
+        ];
+        if catalog_columns {
+            col_data.extend(Self::catalog_col_data());
+        }
+        col_data.extend(Self::notes_col_data(system));
+        col_data
+    }
+
+    fn catalog_col_data() -> Vec<TableColData<'a, Star>> {
+        vec![
+            TableColData {
+                header: "Catalog ID",
+                unit: "",
+                content_closure: Box::new(|body| Some(body.get_catalog_entry()?.designation())),
+                sort_key: None,
+            },
+            TableColData {
+                header: "Parallax",
+                unit: "mas",
+                content_closure: Box::new(|body| {
+                    let parallax = body.get_catalog_entry()?.parallax?;
+                    Some(format!("{:.3} mas", parallax))
+                }),
+                sort_key: Some(Box::new(|body| body.get_catalog_entry()?.parallax)),
+            },
+            TableColData {
+                header: "Parallax Error",
+                unit: "mas",
+                content_closure: Box::new(|body| {
+                    let parallax_error = body.get_catalog_entry()?.parallax_error?;
+                    Some(format!("{:.3} mas", parallax_error))
+                }),
+                sort_key: Some(Box::new(|body| body.get_catalog_entry()?.parallax_error)),
+            },
+            TableColData {
+                header: "Rel. Par. Error",
+                unit: "",
+                content_closure: Box::new(|body| {
+                    let entry = body.get_catalog_entry()?;
+                    let relative_error = entry.parallax_error? / entry.parallax?.abs();
+                    Some(format!("{:.1}%", relative_error * 100.))
+                }),
+                sort_key: Some(Box::new(|body| {
+                    let entry = body.get_catalog_entry()?;
+                    Some(entry.parallax_error? / entry.parallax?.abs())
+                })),
+            },
+            TableColData {
+                header: "BP-RP",
+                unit: "mag",
+                content_closure: Box::new(|body| {
+                    let bp_rp = body.get_catalog_entry()?.bp_rp?;
+                    Some(format!("{:.3}", bp_rp))
+                }),
+                sort_key: Some(Box::new(|body| body.get_catalog_entry()?.bp_rp)),
             },
         ]
     }
 
-    pub(super) fn default_supernova_col_data() -> Vec<TableColData<Star>> {
+    pub(super) fn default_supernova_col_data() -> Vec<TableColData<'a, Star>> {
         vec![
             TableColData {
                 header: "Star Name",
+                unit: "",
                 content_closure: Box::new(|body| {
                     let name = body.get_appearance().get_name();
                     Some(name.to_string())
                 }),
+                sort_key: None,
             },
             TableColData {
                 header: "Time Until Death",
+                unit: "s",
                 content_closure: Box::new(|body| {
                     let time_until_death =
                         body.get_data()?
                             .get_time_until_death(Time::new::<year>(0.))?;
                     Some(time_until_death.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(
+                        body.get_data()?
+                            .get_time_until_death(Time::new::<year>(0.))?
+                            .value,
+                    )
+                })),
             },
             TableColData {
                 header: "Mass",
+                unit: "kg",
                 content_closure: Box::new(|body| {
                     let mass = body.get_data()?.get_mass_at_epoch()?;
                     Some(mass.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_data()?.get_mass_at_epoch()?.value)
+                })),
             },
             TableColData {
                 header: "Distance",
+                unit: "m",
                 content_closure: Box::new(|body| {
                     Some(body.get_data()?.get_distance_at_epoch().astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_data()?.get_distance_at_epoch().value)
+                })),
             },
             TableColData {
                 header: "Vis. Mag.",
+                unit: "mag",
                 content_closure: Box::new(|body| {
                     let illuminance = body.get_appearance().get_illuminance();
                     Some(illuminance.astro_display())
                 }),
+                sort_key: Some(Box::new(|body| {
+                    Some(illuminance_to_apparent_magnitude(
+                        body.get_appearance().get_illuminance(),
+                    ))
+                })),
+            },
+        ]
+    }
+
+    pub(super) fn default_birth_col_data(time_since_epoch: Time) -> Vec<TableColData<'a, Star>> {
+        vec![
+            TableColData {
+                header: "Star Name",
+                unit: "",
+                content_closure: Box::new(|body| {
+                    let name = body.get_appearance().get_name();
+                    Some(name.to_string())
+                }),
+                sort_key: None,
+            },
+            TableColData {
+                header: "Region",
+                unit: "",
+                content_closure: Box::new(|body| Some(body.get_birth()?.region.clone())),
+                sort_key: None,
+            },
+            TableColData {
+                header: "Time Since Birth",
+                unit: "s",
+                content_closure: Box::new(move |body| {
+                    let time_since_birth = time_since_epoch - body.get_birth()?.time;
+                    Some(time_since_birth.astro_display())
+                }),
+                sort_key: Some(Box::new(move |body| {
+                    Some((time_since_epoch - body.get_birth()?.time).value)
+                })),
+            },
+            TableColData {
+                header: "Mass",
+                unit: "kg",
+                content_closure: Box::new(|body| {
+                    let mass = body.get_data()?.get_mass_at_epoch()?;
+                    Some(mass.astro_display())
+                }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_data()?.get_mass_at_epoch()?.value)
+                })),
+            },
+            TableColData {
+                header: "Temperature",
+                unit: "K",
+                content_closure: Box::new(|body| {
+                    let temperature = body.get_data()?.get_temperature_at_epoch();
+                    Some(temperature.astro_display())
+                }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_data()?.get_temperature_at_epoch().value)
+                })),
+            },
+            TableColData {
+                header: "Distance",
+                unit: "m",
+                content_closure: Box::new(|body| {
+                    Some(body.get_data()?.get_distance_at_epoch().astro_display())
+                }),
+                sort_key: Some(Box::new(|body| {
+                    Some(body.get_data()?.get_distance_at_epoch().value)
+                })),
             },
         ]
     }
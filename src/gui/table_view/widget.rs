@@ -3,73 +3,345 @@ use crate::{
     gui::{
         dialog::DialogType, gui_widget::PADDING, message::GuiMessage, shared_widgets::std_button,
     },
-    model::celestial_system::{
-        part::{BodyType, PartOfCelestialSystem},
-        CelestialSystem,
+    model::{
+        body_id::BodyId,
+        celestial_system::{
+            part::{BodyType, PartOfCelestialSystem},
+            CelestialSystem,
+        },
+        planet::Planet,
+        star::Star,
+        star_formation::{StarBirth, StarFormingRegion},
+        star_provenance::StarOrigin,
     },
 };
+use astro_utils::astro_display::AstroDisplay;
 use iced::{
     widget::{
-        rule,
-        scrollable::{Direction, Scrollbar},
+        container, operation, rule,
+        scrollable::{AbsoluteOffset, Direction, Scrollbar, Viewport},
         text::Shaping,
-        Button, Column, Container, Row, Scrollable, Text,
+        Button, Column, Container, MouseArea, PickList, Row, Scrollable, Text, TextInput, Toggler,
     },
-    Alignment, Element, Length,
+    Alignment, Element, Length, Task, Theme,
 };
+use std::{
+    cell::{Ref, RefCell},
+    cmp::Ordering,
+    collections::HashMap,
+    fmt::Display,
+    ops::Range,
+};
+use uom::si::f64::Time;
 
 const CELL_WIDTH: f32 = 150.;
 const BUTTON_CELL_WIDTH: f32 = 50.;
-const MAX_ROWS: usize = 250;
+const ROW_HEIGHT: f32 = 30.;
+const ROWS_PER_PAGE: usize = 250;
+const SEARCH_WIDTH: f32 = 250.;
+const SEARCHED_HEADERS: [&str; 4] = ["Planet Name", "Star Name", "Const.", "Fate"];
+
+type CellCache = RefCell<HashMap<usize, Vec<String>>>;
+
+const TABLE_SCROLLABLE_ID: &str = "table_contents";
+
+struct RowSelection<T> {
+    selected_row: Option<usize>,
+    on_press: fn(&T) -> Option<GuiMessage>,
+}
+
+impl<T> RowSelection<T> {
+    fn none() -> Self {
+        RowSelection {
+            selected_row: None,
+            on_press: |_| None,
+        }
+    }
+}
+
+/*
+ * Narrows the star table down to the stars of one origin.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OriginFilter {
+    All,
+    Only(StarOrigin),
+}
+
+impl OriginFilter {
+    fn options() -> Vec<OriginFilter> {
+        let mut options = vec![OriginFilter::All];
+        options.extend(StarOrigin::ALL.into_iter().map(OriginFilter::Only));
+        options
+    }
+
+    fn accepts(&self, origin: StarOrigin) -> bool {
+        match self {
+            OriginFilter::All => true,
+            OriginFilter::Only(only) => *only == origin,
+        }
+    }
+}
+
+impl Display for OriginFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OriginFilter::All => write!(f, "All Origins"),
+            OriginFilter::Only(origin) => write!(f, "{}", origin),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SortDirection {
+    Ascending,
+    Descending,
+}
+
+impl SortDirection {
+    fn apply(&self, ordering: Ordering) -> Ordering {
+        match self {
+            SortDirection::Ascending => ordering,
+            SortDirection::Descending => ordering.reverse(),
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            SortDirection::Ascending => "▲",
+            SortDirection::Descending => "▼",
+        }
+    }
+}
 
 pub(crate) struct TableViewState {
     pub(crate) displayed_body_type: TableDataType,
+    pub(crate) page: usize,
+    scroll_offset: f32,
+    viewport_height: f32,
+    catalog_columns: bool,
+    origin_filter: OriginFilter,
+    search: String,
+    sort: Option<(usize, SortDirection)>,
+    cell_cache: CellCache,
+    // Searching and sorting go through every row, so the resulting order is kept until the search, the sorting or the data change.
+    row_order_cache: RefCell<Option<(usize, Vec<usize>)>>,
+    // Looking up the next birth regenerates the births of every region, so it is not done on every frame.
+    next_star_birth: Option<StarBirth>,
+    next_star_birth_input: Option<(Time, Vec<StarFormingRegion>)>,
 }
 
 impl TableViewState {
     pub(crate) fn new() -> TableViewState {
         TableViewState {
             displayed_body_type: TableDataType::Planet,
+            page: 0,
+            scroll_offset: 0.,
+            viewport_height: 1000.,
+            catalog_columns: false,
+            origin_filter: OriginFilter::All,
+            search: String::new(),
+            sort: None,
+            cell_cache: RefCell::new(HashMap::new()),
+            row_order_cache: RefCell::new(None),
+            next_star_birth: None,
+            next_star_birth_input: None,
         }
     }
 
-    pub(crate) fn table_view(&self, system: &Option<CelestialSystem>) -> Element<'_, GuiMessage> {
+    /*
+     * Looks up the next star birth again if the birth table is shown and the time or the star-forming regions have changed.
+     */
+    pub(crate) fn update_next_star_birth(&mut self, system: Option<&CelestialSystem>) {
+        if !matches!(self.displayed_body_type, TableDataType::Birth) {
+            return;
+        }
+        let is_current = match (system, &self.next_star_birth_input) {
+            (Some(system), Some((time, regions))) => {
+                *time == system.get_time_since_epoch()
+                    && regions.as_slice() == system.get_star_forming_regions()
+            }
+            (None, None) => true,
+            _ => false,
+        };
+        if is_current {
+            return;
+        }
+        self.next_star_birth = system.and_then(|system| system.get_next_star_birth());
+        self.next_star_birth_input = system.map(|system| {
+            (
+                system.get_time_since_epoch(),
+                system.get_star_forming_regions().to_vec(),
+            )
+        });
+    }
+
+    pub(crate) fn invalidate_cache(&self) {
+        self.cell_cache.borrow_mut().clear();
+        self.row_order_cache.borrow_mut().take();
+    }
+
+    /*
+     * An order for a different number of bodies is outdated even if nobody said so, and would point past the end of the table.
+     */
+    fn row_order<T>(&self, col_data: &[TableColData<'_, T>], bodies: &[T]) -> Ref<'_, [usize]> {
+        let is_current = self
+            .row_order_cache
+            .borrow()
+            .as_ref()
+            .is_some_and(|(body_count, _)| *body_count == bodies.len());
+        if !is_current {
+            let order = row_order(col_data, bodies, &self.search, self.sort);
+            *self.row_order_cache.borrow_mut() = Some((bodies.len(), order));
+        }
+        Ref::map(self.row_order_cache.borrow(), |cache| match cache {
+            Some((_, order)) => order.as_slice(),
+            None => &[],
+        })
+    }
+
+    pub(crate) fn set_catalog_columns(&mut self, shown: bool) {
+        self.catalog_columns = shown;
+        self.invalidate_cache();
+    }
+
+    pub(crate) fn set_origin_filter(&mut self, origin_filter: OriginFilter) {
+        self.origin_filter = origin_filter;
+        self.page = 0;
+        self.invalidate_cache();
+    }
+
+    pub(crate) fn set_search(&mut self, search: String) {
+        self.search = search;
+        self.page = 0;
+        self.invalidate_cache();
+    }
+
+    /*
+     * The column index refers to the columns of the displayed body type, so switching the type drops the sorting.
+     */
+    pub(crate) fn set_sort(&mut self, sort: Option<(usize, SortDirection)>) {
+        self.sort = sort;
+        self.page = 0;
+        self.invalidate_cache();
+    }
+
+    pub(crate) fn scrolled(&mut self, viewport: Viewport) {
+        self.scroll_offset = viewport.absolute_offset().y;
+        self.viewport_height = viewport.bounds().height;
+    }
+
+    /*
+     * Switches to the page containing the planet and scrolls it to the top, unless it is already in view.
+     * Planets hidden by the search are not scrolled to.
+     */
+    pub(crate) fn scroll_to_planet(
+        &mut self,
+        system: &CelestialSystem,
+        selected_planet: &Option<Planet>,
+        id: BodyId,
+    ) -> Task<GuiMessage> {
+        if !matches!(self.displayed_body_type, TableDataType::Planet) {
+            return Task::none();
+        }
+        let (planet_col_data, planets) = planet_table(system, selected_planet);
+        let Some(index) = planets.iter().position(|p| p.get_id() == Some(id)) else {
+            return Task::none();
+        };
+        let order = self.row_order(&planet_col_data, &planets);
+        let Some(row) = order.iter().position(|&i| i == index) else {
+            return Task::none();
+        };
+        let page = row / ROWS_PER_PAGE;
+        let row_on_page = row % ROWS_PER_PAGE;
+        let visible_rows = self.visible_rows();
+        let fully_visible = visible_rows.start..visible_rows.end.saturating_sub(2);
+        if page == self.page && fully_visible.contains(&row_on_page) {
+            return Task::none();
+        }
+        self.page = page;
+        self.scroll_offset = row_on_page as f32 * ROW_HEIGHT;
+        let offset = AbsoluteOffset {
+            x: 0.,
+            y: self.scroll_offset,
+        };
+        operation::scroll_to(TABLE_SCROLLABLE_ID, offset)
+    }
+
+    /*
+     * The rows of the current page that are at least partially visible, plus one row of margin.
+     */
+    fn visible_rows(&self) -> Range<usize> {
+        let first = (self.scroll_offset / ROW_HEIGHT).floor() as usize;
+        let count = (self.viewport_height / ROW_HEIGHT).ceil() as usize + 1;
+        first..first + count
+    }
+
+    pub(crate) fn table_view(
+        &self,
+        system: &Option<CelestialSystem>,
+        selected_planet: &Option<Planet>,
+        locked: bool,
+    ) -> Element<'_, GuiMessage> {
+        let search = TextInput::new("Search name, constellation or fate", &self.search)
+            .on_input(GuiMessage::TableFilterChanged)
+            .width(Length::Fixed(SEARCH_WIDTH));
         let buttons = Row::new()
             .push(data_type_selection_tabs())
+            .push(search)
             .push(Container::new(Text::new("")).width(Length::Fill))
-            .push(self.generation_buttons());
+            .push(self.generation_buttons(system, locked));
 
         let mut col = Column::new().push(buttons);
 
         if let Some(system) = system {
             let table = match self.displayed_body_type {
                 TableDataType::Planet => {
-                    let planet_col_data = TableColData::default_planet_col_data();
-                    let planets = system.get_planets();
+                    let (planet_col_data, planets) = planet_table(system, selected_planet);
+                    let selection = RowSelection {
+                        selected_row: selected_planet.as_ref().and_then(|selected| {
+                            planets.iter().position(|p| p.get_id() == selected.get_id())
+                        }),
+                        on_press: |planet| Some(GuiMessage::PlanetSelected(planet.get_id())),
+                    };
                     table(
                         planet_col_data,
                         planets,
+                        selection,
+                        self,
                         GuiMessage::OpenDialog(DialogType::NewPlanet),
                     )
                 }
                 TableDataType::Star => {
-                    let star_col_data = TableColData::default_star_col_data();
-                    let stars = system.get_stars();
+                    let (star_col_data, stars) = self.star_table(system);
                     table(
                         star_col_data,
                         stars,
+                        RowSelection::none(),
+                        self,
                         GuiMessage::OpenDialog(DialogType::NewStar),
                     )
                 }
                 TableDataType::Supernova => {
-                    let supernova_col_data = TableColData::default_supernova_col_data();
-                    let supernovae = system.get_supernovae();
+                    let (supernova_col_data, supernovae) = supernova_table(system);
                     table(
                         supernova_col_data,
                         supernovae,
+                        RowSelection::none(),
+                        self,
                         GuiMessage::OpenDialog(DialogType::NewStar),
                     )
                 }
+                TableDataType::Birth => {
+                    let (birth_col_data, births) = birth_table(system);
+                    table(
+                        birth_col_data,
+                        births,
+                        RowSelection::none(),
+                        self,
+                        GuiMessage::OpenDialog(DialogType::StarFormingRegions),
+                    )
+                }
             };
             col = col.push(table);
         }
@@ -77,67 +349,373 @@ impl TableViewState {
         col.width(Length::Fill).height(Length::Fill).into()
     }
 
-    fn generation_buttons(&self) -> Element<'static, GuiMessage> {
+    fn star_table<'a>(
+        &self,
+        system: &'a CelestialSystem,
+    ) -> (Vec<TableColData<'a, Star>>, Vec<Star>) {
+        let star_col_data = TableColData::default_star_col_data(system, self.catalog_columns);
+        let stars = system
+            .get_stars()
+            .into_iter()
+            .filter(|star| self.origin_filter.accepts(star.get_provenance().origin))
+            .collect();
+        (star_col_data, stars)
+    }
+
+    /*
+     * The displayed table as text, with the rows that the search lets through in the displayed order.
+     * Columns with a sort key are written as plain numbers in the unit named in their header, so that other programs can read them.
+     * The other cells are written as displayed, and missing values are left empty.
+     */
+    pub(crate) fn to_delimited_text(
+        &self,
+        system: &CelestialSystem,
+        selected_planet: &Option<Planet>,
+        separator: char,
+    ) -> String {
+        match self.displayed_body_type {
+            TableDataType::Planet => {
+                let (col_data, planets) = planet_table(system, selected_planet);
+                self.delimited_text(&col_data, &planets, separator)
+            }
+            TableDataType::Star => {
+                let (col_data, stars) = self.star_table(system);
+                self.delimited_text(&col_data, &stars, separator)
+            }
+            TableDataType::Supernova => {
+                let (col_data, supernovae) = supernova_table(system);
+                self.delimited_text(&col_data, &supernovae, separator)
+            }
+            TableDataType::Birth => {
+                let (col_data, births) = birth_table(system);
+                self.delimited_text(&col_data, &births, separator)
+            }
+        }
+    }
+
+    fn delimited_text<T>(
+        &self,
+        col_data: &[TableColData<'_, T>],
+        bodies: &[T],
+        separator: char,
+    ) -> String {
+        let header = col_data.iter().map(|col| match col.unit {
+            "" => col.header.to_string(),
+            unit => format!("{} [{}]", col.header, unit),
+        });
+        let mut lines = vec![delimited_line(header, separator)];
+        for &i in self.row_order(col_data, bodies).iter() {
+            let cells = col_data.iter().map(|col| match &col.sort_key {
+                Some(sort_key) => sort_key(&bodies[i])
+                    .map(|value| value.to_string())
+                    .unwrap_or_default(),
+                None => (col.content_closure)(&bodies[i]).unwrap_or_default(),
+            });
+            lines.push(delimited_line(cells, separator));
+        }
+        lines.join("\n") + "\n"
+    }
+
+    /*
+     * While locked, only the buttons that merely compute or display something stay enabled.
+     */
+    fn generation_buttons(
+        &self,
+        system: &Option<CelestialSystem>,
+        locked: bool,
+    ) -> Element<'static, GuiMessage> {
         let mut row = Row::new();
         match self.displayed_body_type {
             TableDataType::Planet => {
                 let randomize_planets = std_button(
                     "Randomize Planets",
                     GuiMessage::OpenDialog(DialogType::RandomizePlanets),
-                    true,
+                    !locked,
                 );
                 let load_real_planets = std_button(
                     "Load Real Planets",
                     GuiMessage::OpenDialog(DialogType::LoadRealPlanets),
+                    !locked,
+                );
+                let import_space_engine = std_button(
+                    "Import SpaceEngine",
+                    GuiMessage::ImportSpaceEngineScript,
+                    !locked,
+                );
+                let radial_velocity = std_button(
+                    "Radial Velocity Curve",
+                    GuiMessage::OpenDialog(DialogType::RadialVelocity),
+                    true,
+                );
+                let equation_of_time = std_button(
+                    "Equation of Time",
+                    GuiMessage::OpenDialog(DialogType::EquationOfTime),
+                    true,
+                );
+                let habitability = std_button(
+                    "Habitability",
+                    GuiMessage::OpenDialog(DialogType::Habitability),
                     true,
                 );
-                row = row.push(randomize_planets).push(load_real_planets);
+                let zodiac = std_button("Zodiac", GuiMessage::OpenDialog(DialogType::Zodiac), true);
+                let binary_planets = std_button(
+                    "Binary Planets",
+                    GuiMessage::OpenDialog(DialogType::BinaryPlanets),
+                    !locked,
+                );
+                let co_orbital = std_button(
+                    "Add Trojan",
+                    GuiMessage::OpenDialog(DialogType::CoOrbital),
+                    !locked,
+                );
+                let migration = std_button(
+                    "Migration Schedule",
+                    GuiMessage::OpenDialog(DialogType::Migration),
+                    !locked,
+                );
+                row = row
+                    .push(randomize_planets)
+                    .push(load_real_planets)
+                    .push(import_space_engine)
+                    .push(radial_velocity)
+                    .push(equation_of_time)
+                    .push(habitability)
+                    .push(zodiac)
+                    .push(binary_planets)
+                    .push(co_orbital)
+                    .push(migration);
             }
             TableDataType::Star => {
                 let randomize_stars = std_button(
                     "Randomize Stars",
                     GuiMessage::OpenDialog(DialogType::RandomizeStars),
-                    true,
+                    !locked,
                 );
                 let load_real_stars = std_button(
                     "Load Real Stars",
                     GuiMessage::OpenDialog(DialogType::LoadGaiaData),
+                    !locked,
+                );
+                let statistics = std_button(
+                    "Brightness Statistics",
+                    GuiMessage::OpenDialog(DialogType::StarStatistics),
                     true,
                 );
-                row = row.push(randomize_stars).push(load_real_stars);
+                let duplicates = std_button(
+                    "Find Duplicates",
+                    GuiMessage::OpenDialog(DialogType::DuplicateStars),
+                    !locked,
+                );
+                let jitter = std_button(
+                    "Jitter Stars",
+                    GuiMessage::OpenDialog(DialogType::StarJitter),
+                    !locked,
+                );
+                let name_stars = std_button("Name Stars", GuiMessage::NameStars, !locked);
+                let catalog_columns = Toggler::new(self.catalog_columns)
+                    .label("Catalog Columns")
+                    .on_toggle(GuiMessage::SetCatalogColumns);
+                let origin_filter = PickList::new(
+                    OriginFilter::options(),
+                    Some(self.origin_filter),
+                    GuiMessage::SetOriginFilter,
+                );
+                row = row
+                    .push(origin_filter)
+                    .push(catalog_columns)
+                    .push(randomize_stars)
+                    .push(load_real_stars)
+                    .push(statistics)
+                    .push(duplicates)
+                    .push(jitter)
+                    .push(name_stars);
             }
             TableDataType::Supernova => {}
+            TableDataType::Birth => {
+                let next_birth = system.as_ref().and_then(|system| {
+                    let birth = self.next_star_birth.as_ref()?;
+                    let time_until_birth = birth.time - system.get_time_since_epoch();
+                    Some(format!(
+                        "Next birth: {} {} in {}",
+                        birth.region,
+                        birth.number,
+                        time_until_birth.astro_display()
+                    ))
+                });
+                if let Some(next_birth) = next_birth {
+                    row = row.push(Text::new(next_birth));
+                }
+                let regions = std_button(
+                    "Star-Forming Regions",
+                    GuiMessage::OpenDialog(DialogType::StarFormingRegions),
+                    !locked,
+                );
+                row = row.push(regions);
+            }
         }
 
-        row.align_y(Alignment::Center)
+        let export = std_button("Export CSV", GuiMessage::ExportTable, system.is_some());
+        row.push(export)
+            .align_y(Alignment::Center)
             .spacing(PADDING)
             .padding(PADDING)
             .into()
     }
 }
 
+fn planet_table<'a>(
+    system: &'a CelestialSystem,
+    selected_planet: &Option<Planet>,
+) -> (Vec<TableColData<'a, Planet>>, Vec<Planet>) {
+    let observer_position = selected_planet.as_ref().map(|p| p.get_position().clone());
+    let tidal_locking = system
+        .get_planets_data()
+        .into_iter()
+        .enumerate()
+        .filter_map(|(i, planet)| {
+            let locking = system.get_tidal_locking(planet)?;
+            Some((system.get_planet_id(i)?, locking))
+        })
+        .collect();
+    let planet_col_data =
+        TableColData::default_planet_col_data(observer_position, tidal_locking, system);
+    (planet_col_data, system.get_planets())
+}
+
+fn supernova_table(system: &CelestialSystem) -> (Vec<TableColData<'_, Star>>, Vec<Star>) {
+    (
+        TableColData::default_supernova_col_data(),
+        system.get_supernovae(),
+    )
+}
+
+fn birth_table(system: &CelestialSystem) -> (Vec<TableColData<'_, Star>>, Vec<Star>) {
+    (
+        TableColData::default_birth_col_data(system.get_time_since_epoch()),
+        system.get_star_births(),
+    )
+}
+
+/*
+ * Cells containing the separator, quotes or line breaks are quoted, with quotes doubled inside.
+ */
+fn delimited_line(cells: impl Iterator<Item = String>, separator: char) -> String {
+    cells
+        .map(|cell| {
+            if cell.contains([separator, '"', '\n', '\r']) {
+                format!("\"{}\"", cell.replace('"', "\"\""))
+            } else {
+                cell
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(&separator.to_string())
+}
+
+/*
+ * The indices of the bodies that match the search, in the order of the sorted column.
+ * Bodies without a value in the sorted column come last in either direction.
+ */
+fn row_order<T>(
+    col_data: &[TableColData<'_, T>],
+    bodies: &[T],
+    search: &str,
+    sort: Option<(usize, SortDirection)>,
+) -> Vec<usize> {
+    let search = search.trim().to_lowercase();
+    let searched_cols: Vec<&TableColData<'_, T>> = col_data
+        .iter()
+        .filter(|col| SEARCHED_HEADERS.contains(&col.header))
+        .collect();
+    let mut order: Vec<usize> = (0..bodies.len())
+        .filter(|&i| {
+            search.is_empty()
+                || searched_cols.iter().any(|col| {
+                    (col.content_closure)(&bodies[i])
+                        .is_some_and(|content| content.to_lowercase().contains(&search))
+                })
+        })
+        .collect();
+    let Some((column, direction)) = sort else {
+        return order;
+    };
+    let Some(col) = col_data.get(column) else {
+        return order;
+    };
+    match &col.sort_key {
+        Some(sort_key) => {
+            let keys: Vec<Option<f64>> = bodies
+                .iter()
+                .map(|body| sort_key(body).filter(|key| !key.is_nan()))
+                .collect();
+            sort_missing_last(&mut order, &keys, direction, f64::total_cmp);
+        }
+        None => {
+            let keys: Vec<Option<String>> = bodies
+                .iter()
+                .map(|body| (col.content_closure)(body).map(|content| content.to_lowercase()))
+                .collect();
+            sort_missing_last(&mut order, &keys, direction, Ord::cmp);
+        }
+    }
+    order
+}
+
+fn sort_missing_last<K>(
+    order: &mut [usize],
+    keys: &[Option<K>],
+    direction: SortDirection,
+    compare: impl Fn(&K, &K) -> Ordering,
+) {
+    order.sort_by(|&a, &b| match (&keys[a], &keys[b]) {
+        (Some(a), Some(b)) => direction.apply(compare(a, b)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+}
+
 fn table<T>(
-    col_data: Vec<TableColData<T>>,
+    col_data: Vec<TableColData<'_, T>>,
     bodies: Vec<T>,
+    selection: RowSelection<T>,
+    state: &TableViewState,
     new_message: GuiMessage,
 ) -> Scrollable<'static, GuiMessage>
 where
     T: PartOfCelestialSystem,
 {
     let width = table_width(&col_data);
+    let order = state.row_order(&col_data, &bodies);
+    let page_count = order.len().div_ceil(ROWS_PER_PAGE).max(1);
+    let page = state.page.min(page_count - 1);
+    let mut bodies: Vec<Option<T>> = bodies.into_iter().map(Some).collect();
+    let page_rows = order
+        .iter()
+        .skip(page * ROWS_PER_PAGE)
+        .take(ROWS_PER_PAGE)
+        .filter_map(|&i| Some((i, bodies[i].take()?)))
+        .collect();
     let scrollbar = Scrollbar::new();
     Scrollable::new(
         Column::new()
-            .push(table_header(new_message, &col_data))
+            .push(pagination_controls(page, page_count))
+            .push(table_header(new_message, &col_data, state.sort))
             .push(Container::new(rule::horizontal(10)).width(width))
-            .push(table_contents(bodies, col_data)),
+            .push(table_contents(
+                page_rows,
+                selection,
+                state.visible_rows(),
+                &state.cell_cache,
+                col_data,
+            )),
     )
     .direction(Direction::Horizontal(scrollbar))
     .width(Length::Fill)
     .height(Length::Fill)
 }
 
-fn table_width<T>(table_col_data: &[TableColData<T>]) -> Length {
+fn table_width<T>(table_col_data: &[TableColData<'_, T>]) -> Length {
     Length::Fixed(table_col_data.len() as f32 * CELL_WIDTH + 2. * BUTTON_CELL_WIDTH)
 }
 
@@ -157,74 +735,146 @@ fn data_type_selection_tabs() -> Element<'static, GuiMessage> {
         GuiMessage::TableDataTypeSelected(TableDataType::Supernova),
         true,
     );
+    let birth_button = std_button(
+        "Births",
+        GuiMessage::TableDataTypeSelected(TableDataType::Birth),
+        true,
+    );
     Row::new()
         .push(planet_button)
         .push(star_button)
         .push(supernova_button)
+        .push(birth_button)
         .align_y(Alignment::Center)
         .spacing(PADDING)
         .padding(PADDING)
         .into()
 }
 
+fn pagination_controls(page: usize, page_count: usize) -> Row<'static, GuiMessage> {
+    let mut previous_button = Button::new(Text::new("<<"));
+    if page > 0 {
+        previous_button = previous_button.on_press(GuiMessage::TablePageSelected(page - 1));
+    }
+    let mut next_button = Button::new(Text::new(">>"));
+    if page + 1 < page_count {
+        next_button = next_button.on_press(GuiMessage::TablePageSelected(page + 1));
+    }
+    Row::new()
+        .push(previous_button)
+        .push(Text::new(format!("Page {} of {}", page + 1, page_count)))
+        .push(next_button)
+        .align_y(Alignment::Center)
+        .spacing(PADDING)
+        .padding(PADDING)
+}
+
+/*
+ * The rows are paired with their index among all bodies, which keys the cell cache and stays the same under sorting.
+ */
 fn table_contents<T>(
-    bodies: Vec<T>,
-    table_col_data: Vec<TableColData<T>>,
+    page_rows: Vec<(usize, T)>,
+    selection: RowSelection<T>,
+    visible_rows: Range<usize>,
+    cell_cache: &CellCache,
+    table_col_data: Vec<TableColData<'_, T>>,
 ) -> Element<'static, GuiMessage>
 where
     T: PartOfCelestialSystem,
 {
-    let mut col = Column::new();
-    let length = bodies.len();
-    for (sorting_index, body) in bodies.into_iter().enumerate().take(MAX_ROWS) {
-        col = col.push(table_row(sorting_index, body, &table_col_data));
-    }
-    if length > MAX_ROWS {
-        col = col.push(Text::new(format!("... and {} more", length - MAX_ROWS)));
+    let row_count = page_rows.len();
+    let start = visible_rows.start.min(row_count);
+    let end = visible_rows.end.min(row_count);
+
+    // Rows outside of the viewport are replaced by empty space of the same height, so the scrollbar stays accurate.
+    let mut col = Column::new().push(vertical_spacer(start));
+    for (sorting_index, body) in page_rows.into_iter().skip(start).take(end - start) {
+        let is_selected = selection.selected_row == Some(sorting_index);
+        let row = table_row(sorting_index, &body, &table_col_data, cell_cache);
+        let mut row = Container::new(row);
+        if is_selected {
+            row = row.style(selected_row_style);
+        }
+        let mut row = MouseArea::new(row);
+        if let Some(message) = (selection.on_press)(&body) {
+            row = row.on_press(message);
+        }
+        col = col.push(row);
     }
+    col = col.push(vertical_spacer(row_count - end));
     let scrollbar = Scrollbar::new();
     Scrollable::new(col)
         .direction(Direction::Vertical(scrollbar))
+        .id(TABLE_SCROLLABLE_ID)
+        .on_scroll(GuiMessage::TableScrolled)
         .height(Length::Fill)
         .into()
 }
 
+fn selected_row_style(theme: &Theme) -> container::Style {
+    container::Style::default().background(theme.extended_palette().primary.weak.color)
+}
+
+fn vertical_spacer(rows: usize) -> Container<'static, GuiMessage> {
+    Container::new(Text::new("")).height(Length::Fixed(rows as f32 * ROW_HEIGHT))
+}
+
+/*
+ * Clicking a header sorts by its column, and clicking it again reverses the order.
+ */
 fn table_header<T>(
     new_dialog_message: GuiMessage,
-    table_col_data: &Vec<TableColData<T>>,
+    table_col_data: &Vec<TableColData<'_, T>>,
+    sort: Option<(usize, SortDirection)>,
 ) -> Row<'static, GuiMessage> {
     let new_button = Button::new("New").on_press(new_dialog_message);
 
     let mut row = Row::new()
         .push(Container::new(new_button).width(Length::Fixed(BUTTON_CELL_WIDTH)))
         .push(Container::new(Text::new("")).width(Length::Fixed(BUTTON_CELL_WIDTH)));
-    for col in table_col_data {
-        row = row.push(table_cell(Text::new(col.header).into()));
+    for (i, col) in table_col_data.iter().enumerate() {
+        let (label, direction) = match sort {
+            Some((column, direction)) if column == i => (
+                format!("{} {}", col.header, direction.symbol()),
+                match direction {
+                    SortDirection::Ascending => SortDirection::Descending,
+                    SortDirection::Descending => SortDirection::Ascending,
+                },
+            ),
+            _ => (col.header.to_string(), SortDirection::Ascending),
+        };
+        let header = Button::new(Text::new(label).shaping(Shaping::Advanced))
+            .on_press(GuiMessage::TableSortChanged(i, direction));
+        row = row.push(table_cell(header.into()));
     }
     row.align_y(Alignment::Center)
 }
 
 fn table_row<T>(
     sorting_index: usize,
-    data: T,
-    table_col_data: &[TableColData<T>],
+    data: &T,
+    table_col_data: &[TableColData<'_, T>],
+    cell_cache: &CellCache,
 ) -> Row<'static, GuiMessage>
 where
     T: PartOfCelestialSystem,
 {
     let mut edit_button = Button::new(Text::new("Edit"));
-    let index = data.get_index();
+    let id = data.get_id();
     match data.get_body_type() {
         BodyType::Planet => {
-            if let Some(index) = index {
+            if let Some(id) = id {
                 edit_button =
-                    edit_button.on_press(GuiMessage::OpenDialog(DialogType::EditPlanet(index)));
+                    edit_button.on_press(GuiMessage::OpenDialog(DialogType::EditPlanet(id)));
             }
         }
         BodyType::Star => {
-            edit_button = edit_button.on_press(GuiMessage::OpenDialog(DialogType::EditStar(
-                data.get_index(),
-            )));
+            // The central body is the only star without an index, and it has no ID either.
+            let is_central_body = data.get_index().is_none();
+            if id.is_some() || is_central_body {
+                edit_button =
+                    edit_button.on_press(GuiMessage::OpenDialog(DialogType::EditStar(id)));
+            }
         }
     }
     let mut row = Row::new()
@@ -233,14 +883,108 @@ where
             Container::new(Text::new(format!("{}", sorting_index + 1)))
                 .width(Length::Fixed(BUTTON_CELL_WIDTH)),
         );
-    for col in table_col_data.iter() {
-        let content = (col.content_closure)(&data).unwrap_or("N/A".to_string());
+    let cells = cell_cache
+        .borrow_mut()
+        .entry(sorting_index)
+        .or_insert_with(|| {
+            table_col_data
+                .iter()
+                .map(|col| (col.content_closure)(data).unwrap_or("N/A".to_string()))
+                .collect()
+        })
+        .clone();
+    for content in cells {
         let text = Text::new(content).shaping(Shaping::Advanced);
         row = row.push(table_cell(text.into()));
     }
-    row.align_y(Alignment::Center)
+    row.height(Length::Fixed(ROW_HEIGHT))
+        .align_y(Alignment::Center)
 }
 
 fn table_cell(content: Element<'_, GuiMessage>) -> Container<'_, GuiMessage> {
     Container::new(content).width(Length::Fixed(CELL_WIDTH))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn col(header: &'static str) -> TableColData<'static, (&'static str, Option<f64>)> {
+        TableColData {
+            header,
+            unit: "",
+            content_closure: Box::new(|body| Some(body.0.to_string())),
+            sort_key: Some(Box::new(|body| body.1)),
+        }
+    }
+
+    #[test]
+    fn search_filters_and_sorting_keeps_missing_values_last() {
+        let col_data = vec![col("Star Name")];
+        let bodies = [
+            ("Vega", Some(3.)),
+            ("Deneb", None),
+            ("Altair", Some(1.)),
+            ("Denebola", Some(2.)),
+        ];
+        assert_eq!(row_order(&col_data, &bodies, "", None), vec![0, 1, 2, 3]);
+        assert_eq!(row_order(&col_data, &bodies, " deneb", None), vec![1, 3]);
+        let ascending = Some((0, SortDirection::Ascending));
+        assert_eq!(
+            row_order(&col_data, &bodies, "", ascending),
+            vec![2, 3, 0, 1]
+        );
+        let descending = Some((0, SortDirection::Descending));
+        assert_eq!(
+            row_order(&col_data, &bodies, "", descending),
+            vec![0, 3, 2, 1]
+        );
+    }
+
+    #[test]
+    fn cells_with_separators_are_quoted() {
+        let cells = ["Vega", "1,5 AU", "\"Wega\""].map(String::from).into_iter();
+        assert_eq!(
+            delimited_line(cells, ','),
+            "Vega,\"1,5 AU\",\"\"\"Wega\"\"\""
+        );
+        let cells = ["Vega", "1,5 AU"].map(String::from).into_iter();
+        assert_eq!(delimited_line(cells, '\t'), "Vega\t1,5 AU");
+    }
+
+    #[test]
+    fn sortable_columns_are_exported_as_numbers_with_their_unit() {
+        let col_data = vec![
+            TableColData {
+                header: "Star Name",
+                unit: "",
+                content_closure: Box::new(|body: &(&str, Option<f64>)| Some(body.0.to_string())),
+                sort_key: None,
+            },
+            TableColData {
+                header: "Distance",
+                unit: "m",
+                content_closure: Box::new(|_| Some("1 ly".to_string())),
+                sort_key: Some(Box::new(|body| body.1)),
+            },
+        ];
+        let bodies = [("Vega", Some(2.5e17)), ("Deneb", None)];
+        let text = TableViewState::new().delimited_text(&col_data, &bodies, ',');
+        assert_eq!(
+            text,
+            "Star Name,Distance [m]\nVega,250000000000000000\nDeneb,\n"
+        );
+    }
+
+    #[test]
+    fn row_order_is_kept_until_the_search_changes() {
+        let col_data = vec![col("Star Name")];
+        let bodies = [("Vega", None), ("Deneb", None)];
+        let mut state = TableViewState::new();
+        assert_eq!(&*state.row_order(&col_data, &bodies), &[0, 1]);
+        state.set_search("deneb".to_string());
+        assert_eq!(&*state.row_order(&col_data, &bodies), &[1]);
+        let more_bodies = [("Vega", None), ("Altair", None), ("Deneb", None)];
+        assert_eq!(&*state.row_order(&col_data, &more_bodies), &[2]);
+    }
+}
@@ -0,0 +1,102 @@
+use super::{
+    gui_widget::{BIG_COLUMN_WIDTH, PADDING},
+    message::GuiMessage,
+    shared_widgets::described_symbol_button,
+};
+use iced::{
+    alignment::{Horizontal, Vertical},
+    time::Instant,
+    widget::{container, Column, Container, Row, Text},
+    Alignment, Element, Length,
+};
+use std::time::Duration;
+
+const TOAST_DURATION: Duration = Duration::from_secs(6);
+
+#[derive(Debug, Clone)]
+struct Toast {
+    id: u64,
+    message: String,
+    shown_since: Instant,
+}
+
+/*
+ * Short notifications in the corner of the window, which disappear on their own and never block the user.
+ */
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Toasts {
+    toasts: Vec<Toast>,
+    next_id: u64,
+}
+
+impl Toasts {
+    pub(crate) fn push(&mut self, message: String) {
+        self.toasts.push(Toast {
+            id: self.next_id,
+            message,
+            shown_since: Instant::now(),
+        });
+        self.next_id += 1;
+    }
+
+    pub(crate) fn dismiss(&mut self, id: u64) {
+        self.toasts.retain(|toast| toast.id != id);
+    }
+
+    pub(crate) fn expire(&mut self, now: Instant) {
+        self.toasts
+            .retain(|toast| now.saturating_duration_since(toast.shown_since) < TOAST_DURATION);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+
+    pub(crate) fn view(&self) -> Element<'_, GuiMessage> {
+        let mut col = Column::new().spacing(PADDING).align_x(Alignment::End);
+        for toast in &self.toasts {
+            let row = Row::new()
+                .push(Text::new(&toast.message).width(Length::Fill))
+                .push(described_symbol_button(
+                    "×",
+                    "Dismiss".to_string(),
+                    GuiMessage::DismissToast(toast.id),
+                ))
+                .spacing(PADDING)
+                .align_y(Alignment::Center);
+            col = col.push(
+                Container::new(row)
+                    .width(BIG_COLUMN_WIDTH)
+                    .padding(PADDING)
+                    .style(container::rounded_box),
+            );
+        }
+        Container::new(col)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .align_x(Horizontal::Right)
+            .align_y(Vertical::Bottom)
+            .padding(4. * PADDING)
+            .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toasts_expire_and_can_be_dismissed() {
+        let mut toasts = Toasts::default();
+        toasts.push("First".to_string());
+        toasts.push("Second".to_string());
+        toasts.dismiss(0);
+        assert_eq!(toasts.toasts.len(), 1);
+        assert_eq!(toasts.toasts[0].message, "Second");
+
+        toasts.expire(Instant::now());
+        assert!(!toasts.is_empty());
+        toasts.expire(Instant::now() + TOAST_DURATION);
+        assert!(toasts.is_empty());
+    }
+}
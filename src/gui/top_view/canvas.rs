@@ -1,9 +1,6 @@
-use astro_coords::{
-    cartesian::Cartesian, direction::Direction, traits::*,
-    transformations::rotations::get_rotation_parameters,
-};
+use astro_coords::{cartesian::Cartesian, direction::Direction, traits::*};
 use astro_units::length::solar_radius;
-use astro_utils::{astro_display::AstroDisplay, color::srgb::sRGBColor};
+use astro_utils::{color::srgb::sRGBColor, real_data::stars::sun};
 use iced::{
     widget::{
         canvas::{self, Path, Style},
@@ -17,16 +14,25 @@ use uom::si::{
 };
 
 use crate::{
-    gui::shared_canvas_functionality::{
-        canvas_contains, display_info_text, draw_background, draw_name,
+    gui::{
+        canvas_background::CanvasBackground,
+        shared_canvas_functionality::{
+            canvas_contains, display_info_text, draw_background, draw_name, LabelStyle,
+        },
+    },
+    model::{
+        body_id::BodyId,
+        celestial_system::{part::PartOfCelestialSystem, CelestialSystem},
+        planet::Planet,
     },
-    model::{celestial_system::CelestialSystem, planet::Planet},
 };
 
-use super::widget::TopViewState;
+use super::{selection::is_selected, widget::TopViewState};
+
+const SCALE_LENGTH_IN_PX: f32 = 200.0;
 
 impl TopViewState {
-    fn canvas_position(
+    pub(super) fn canvas_position(
         &self,
         pos: &Cartesian,
         view_angle: Angle,
@@ -43,23 +49,29 @@ impl TopViewState {
         renderer: &Renderer,
         bounds: Rectangle,
         selected_planet: &Option<Planet>,
+        target_planet: Option<BodyId>,
         celestial_system: &Option<CelestialSystem>,
         display_names: bool,
+        label_style: &LabelStyle,
+        background: &CanvasBackground,
     ) -> Vec<canvas::Geometry> {
+        self.canvas_size.set(bounds.size());
         let background = self
             .background_cache
             .draw(renderer, bounds.size(), |frame| {
-                draw_background(bounds, frame);
+                draw_background(bounds, frame, background);
             });
 
         let bodies = self.bodies_cache.draw(renderer, bounds.size(), |frame| {
             if let Some(celestial_system) = celestial_system {
                 self.draw_bodies(
                     selected_planet,
+                    target_planet,
                     celestial_system,
                     &bounds,
                     frame,
                     display_names,
+                    label_style,
                 );
             } else {
                 display_info_text(frame, "Please load or generate a celestial system.");
@@ -76,28 +88,62 @@ impl TopViewState {
     fn draw_bodies(
         &self,
         selected_planet: &Option<Planet>,
+        target_planet: Option<BodyId>,
         celestial_system: &CelestialSystem,
         bounds: &Rectangle,
         frame: &mut canvas::Frame,
         display_names: bool,
+        label_style: &LabelStyle,
     ) {
-        let view_direction = &self.view_ecliptic.spherical.to_direction();
-        let (angle, view_rotation_axis) = get_rotation_parameters(&Direction::Z, view_direction);
+        let (angle, view_rotation_axis, offset) = self.view_transformation(selected_planet);
 
-        let offset = match selected_planet {
-            Some(focus) => self.canvas_position(focus.get_position(), angle, &view_rotation_axis),
-            None => Vector::new(0.0, 0.0),
-        };
+        if self.display_grid {
+            self.draw_grid(frame, angle, &view_rotation_axis, offset);
+        }
 
         let central_body_view = ViewParams {
             view_angle: angle,
             rotation_axis: &view_rotation_axis,
             offset,
             display_names,
+            label_style,
         };
         self.draw_central_body(celestial_system, frame, bounds, &central_body_view);
+        if self.display_barycenter {
+            self.draw_central_body_wobble(
+                frame,
+                celestial_system,
+                angle,
+                &view_rotation_axis,
+                offset,
+            );
+        }
+
+        let planets = celestial_system.get_planets();
+        let observer = planets
+            .iter()
+            .find(|planet| is_selected(selected_planet, planet));
+        let target = planets
+            .iter()
+            .find(|planet| target_planet.is_some() && planet.get_id() == target_planet);
+        if let (Some(observer), Some(target)) = (observer, target) {
+            self.draw_target_line(
+                frame,
+                observer.get_position(),
+                target.get_position(),
+                &central_body_view,
+            );
+        }
 
-        for planet in celestial_system.get_planets().iter() {
+        for planet in planets.iter() {
+            self.draw_orbit(
+                frame,
+                celestial_system,
+                planet,
+                angle,
+                &view_rotation_axis,
+                offset,
+            );
             let data = planet.get_data();
             let body = BodyParams {
                 name: data.get_name(),
@@ -111,8 +157,12 @@ impl TopViewState {
                 rotation_axis: &view_rotation_axis,
                 offset,
                 display_names,
+                label_style,
             };
             self.draw_body(frame, bounds, &body, &view);
+            if is_selected(selected_planet, planet) {
+                self.draw_selection_ring(frame, &body, &view);
+            }
         }
     }
 
@@ -138,7 +188,23 @@ impl TopViewState {
             radius,
         };
 
+        let pos = frame.center()
+            + self.canvas_position(&pos3d, view.view_angle, view.rotation_axis)
+            - view.offset;
+        let relative_luminosity = (data.get_luminous_intensity(time)
+            / sun().to_star_data().get_luminous_intensity_at_epoch())
+        .value;
+        draw_glow(
+            frame,
+            pos,
+            &radius,
+            relative_luminosity,
+            canvas_color(&color, None),
+        );
         self.draw_body(frame, bounds, &body, view);
+        if (radius / self.length_per_pixel).value < 1. {
+            draw_sub_pixel_marker(frame, pos, &radius);
+        }
     }
 
     fn draw_body(
@@ -152,22 +218,30 @@ impl TopViewState {
         let pos = frame.center()
             + self.canvas_position(body.pos3d, view.view_angle, view.rotation_axis)
             - view.offset;
-        if canvas_contains(bounds, pos) {
+        if canvas_contains(bounds, pos) && radius.is_finite() {
             let circle = Path::circle(pos, radius);
             let color = canvas_color(body.color, body.albedo);
             frame.fill(&circle, color);
 
             if view.display_names {
-                draw_name(body.name, color, pos, frame);
+                draw_name(body.name, color, pos, view.label_style, frame);
             }
         }
     }
 
+    pub(crate) fn scale_description(&self) -> String {
+        format!(
+            "{} px = {}",
+            SCALE_LENGTH_IN_PX,
+            self.scale_unit
+                .format(SCALE_LENGTH_IN_PX as f64 * self.length_per_pixel)
+        )
+    }
+
     fn draw_scale(&self, bounds: Rectangle, frame: &mut canvas::Frame) {
-        const LENGTH_IN_PX: f32 = 200.0;
         let start_pos = Point::ORIGIN + Vector::new(50., bounds.height - 50.);
-        let middle_pos = start_pos + Vector::new(LENGTH_IN_PX / 2., 0.0);
-        let end_pos = start_pos + Vector::new(LENGTH_IN_PX, 0.0);
+        let middle_pos = start_pos + Vector::new(SCALE_LENGTH_IN_PX / 2., 0.0);
+        let end_pos = start_pos + Vector::new(SCALE_LENGTH_IN_PX, 0.0);
         let delimitor_vec = Vector::new(0.0, 5.);
 
         let scale = Path::new(|path_builder| {
@@ -186,7 +260,9 @@ impl TopViewState {
 
         let text = canvas::Text {
             color: Color::WHITE,
-            content: (LENGTH_IN_PX as f64 * self.length_per_pixel).astro_display(),
+            content: self
+                .scale_unit
+                .format(SCALE_LENGTH_IN_PX as f64 * self.length_per_pixel),
             position: middle_pos,
             align_x: Alignment::Center,
             ..Default::default()
@@ -195,11 +271,64 @@ impl TopViewState {
     }
 }
 
-fn canvas_radius(radius: &Length) -> f32 {
+pub(super) fn canvas_radius(radius: &Length) -> f32 {
     const SIZE_NUMBER: f32 = 0.3;
     (radius.get::<kilometer>() as f32).powf(SIZE_NUMBER) * SIZE_NUMBER
 }
 
+/*
+ * A halo around the central body that grows with the fourth root of its luminosity relative to the sun.
+ * Radial gradients are not yet implemented in iced, so the halo is built from translucent rings.
+ */
+fn draw_glow(
+    frame: &mut canvas::Frame,
+    pos: Point,
+    radius: &Length,
+    relative_luminosity: f64,
+    color: Color,
+) {
+    const STEPS: usize = 12;
+    const SUN_GLOW_FACTOR: f32 = 3.;
+    const MAX_GLOW_FACTOR: f32 = 20.;
+
+    let body_radius = canvas_radius(radius);
+    let glow_factor =
+        (SUN_GLOW_FACTOR * relative_luminosity.max(0.).powf(0.25) as f32).min(MAX_GLOW_FACTOR);
+    let glow_radius = body_radius * glow_factor;
+    if !glow_radius.is_finite() || glow_radius <= body_radius {
+        return;
+    }
+    let mut step_color = color;
+    step_color.a = 0.4 / STEPS as f32;
+    for step in 0..STEPS {
+        let ring_radius =
+            body_radius + (glow_radius - body_radius) * (STEPS - step) as f32 / STEPS as f32;
+        frame.fill(&Path::circle(pos, ring_radius), step_color);
+    }
+}
+
+/*
+ * Bodies are drawn larger than they are, so a cross hair marks the central body if it would be smaller than a pixel at true scale.
+ */
+fn draw_sub_pixel_marker(frame: &mut canvas::Frame, pos: Point, radius: &Length) {
+    const GAP: f32 = 3.;
+    const LENGTH: f32 = 8.;
+    let inner = canvas_radius(radius) + GAP;
+    let outer = inner + LENGTH;
+    let marker = Path::new(|path_builder| {
+        for (x, y) in [(1., 0.), (-1., 0.), (0., 1.), (0., -1.)] {
+            path_builder.move_to(pos + Vector::new(x * inner, y * inner));
+            path_builder.line_to(pos + Vector::new(x * outer, y * outer));
+        }
+    });
+    let stroke = canvas::Stroke {
+        style: Style::Solid(Color::WHITE),
+        width: 1.,
+        ..Default::default()
+    };
+    frame.stroke(&marker, stroke);
+}
+
 fn canvas_color(color: &sRGBColor, albedo: Option<f64>) -> Color {
     let (r, g, b) = color.maximized_sRGB_tuple();
     let a = albedo.unwrap_or(1.) as f32;
@@ -211,17 +340,18 @@ fn canvas_color(color: &sRGBColor, albedo: Option<f64>) -> Color {
     }
 }
 
-struct BodyParams<'a> {
+pub(super) struct BodyParams<'a> {
     name: &'a str,
-    pos3d: &'a Cartesian,
+    pub(super) pos3d: &'a Cartesian,
     color: &'a sRGBColor,
     albedo: Option<f64>,
-    radius: Length,
+    pub(super) radius: Length,
 }
 
-struct ViewParams<'a> {
-    view_angle: Angle,
-    rotation_axis: &'a Direction,
-    offset: Vector,
+pub(super) struct ViewParams<'a> {
+    pub(super) view_angle: Angle,
+    pub(super) rotation_axis: &'a Direction,
+    pub(super) offset: Vector,
     display_names: bool,
+    label_style: &'a LabelStyle,
 }
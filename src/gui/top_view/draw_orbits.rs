@@ -0,0 +1,199 @@
+use astro_coords::{cartesian::Cartesian, direction::Direction};
+use astro_utils::astro_display::AstroDisplay;
+use iced::{
+    widget::canvas::{Frame, Path, Stroke, Style, Text},
+    Color, Point, Vector,
+};
+use uom::si::f64::Angle;
+
+use crate::model::{celestial_system::CelestialSystem, planet::Planet};
+
+use super::widget::TopViewState;
+
+const ORBIT_SAMPLES: usize = 360;
+const WOBBLE_SAMPLES: usize = 720;
+const BARYCENTER_MARKER_SIZE: f32 = 4.;
+const ARROWS_PER_ORBIT: usize = 4;
+const ARROW_SIZE: f32 = 6.;
+const APSIS_MARKER_RADIUS: f32 = 3.;
+const ORBIT_COLOR: Color = Color {
+    r: 0.6,
+    g: 0.6,
+    b: 0.6,
+    a: 0.6,
+};
+const WOBBLE_COLOR: Color = Color {
+    r: 1.,
+    g: 0.85,
+    b: 0.3,
+    a: 0.8,
+};
+
+impl TopViewState {
+    /*
+     * Draws the orbit of a planet with arrows in the direction of motion and markers at periapsis and apoapsis.
+     */
+    pub(super) fn draw_orbit(
+        &self,
+        frame: &mut Frame,
+        celestial_system: &CelestialSystem,
+        planet: &Planet,
+        view_angle: Angle,
+        view_rotation_axis: &Direction,
+        offset: Vector,
+    ) {
+        let samples = match celestial_system.get_orbit_samples(planet, ORBIT_SAMPLES) {
+            Some(samples) => samples,
+            None => return,
+        };
+        let to_canvas = |pos: &Cartesian| {
+            frame.center() + self.canvas_position(pos, view_angle, view_rotation_axis) - offset
+        };
+        let points: Vec<Point> = samples.positions.iter().map(to_canvas).collect();
+        if points.iter().any(|p| !p.x.is_finite() || !p.y.is_finite()) {
+            return;
+        }
+
+        let orbit = Path::new(|path_builder| {
+            path_builder.move_to(points[0]);
+            for point in points.iter().skip(1) {
+                path_builder.line_to(*point);
+            }
+            path_builder.close();
+        });
+        let stroke = Stroke {
+            style: Style::Solid(ORBIT_COLOR),
+            width: 1.,
+            ..Default::default()
+        };
+        frame.stroke(&orbit, stroke);
+
+        let arrow_spacing = ORBIT_SAMPLES / ARROWS_PER_ORBIT;
+        for i in (arrow_spacing / 2..ORBIT_SAMPLES).step_by(arrow_spacing) {
+            draw_arrow(frame, points[i], points[(i + 1) % ORBIT_SAMPLES]);
+        }
+
+        if let Some((periapsis, apoapsis)) = samples.apsis_indices() {
+            let orbit_parameters = planet.get_data().get_orbital_parameters();
+            let semi_major_axis = orbit_parameters.get_semi_major_axis();
+            let eccentricity = orbit_parameters.get_eccentricity();
+            self.draw_apsis(
+                frame,
+                points[periapsis],
+                "Periapsis",
+                semi_major_axis * (1. - eccentricity),
+            );
+            self.draw_apsis(
+                frame,
+                points[apoapsis],
+                "Apoapsis",
+                semi_major_axis * (1. + eccentricity),
+            );
+        }
+    }
+
+    /*
+     * Draws the path of the central body around the barycenter, in the frame in which the central body currently sits at the origin.
+     * The path is far smaller than the planet orbits, so it only becomes visible when zooming in on the central body.
+     */
+    pub(super) fn draw_central_body_wobble(
+        &self,
+        frame: &mut Frame,
+        celestial_system: &CelestialSystem,
+        view_angle: Angle,
+        view_rotation_axis: &Direction,
+        offset: Vector,
+    ) {
+        let key = celestial_system.central_body_wobble_key();
+        let mut cache = self.wobble_cache.borrow_mut();
+        if cache
+            .as_ref()
+            .is_none_or(|(cached_key, _)| *cached_key != key)
+        {
+            let wobble = celestial_system.get_central_body_wobble(WOBBLE_SAMPLES);
+            *cache = Some((key, wobble));
+        }
+        let wobble = match cache.as_ref().and_then(|(_, wobble)| wobble.as_ref()) {
+            Some(wobble) => wobble,
+            None => return,
+        };
+        let now = &wobble.positions[0];
+        let to_canvas = |pos: &Cartesian| {
+            frame.center() + self.canvas_position(pos, view_angle, view_rotation_axis) - offset
+        };
+        let points: Vec<Point> = wobble
+            .positions
+            .iter()
+            .map(|position| to_canvas(&(position.clone() + -now)))
+            .collect();
+        let barycenter = to_canvas(&-now);
+        if points
+            .iter()
+            .chain(std::iter::once(&barycenter))
+            .any(|p| !p.x.is_finite() || !p.y.is_finite())
+        {
+            return;
+        }
+
+        let path = Path::new(|path_builder| {
+            path_builder.move_to(points[0]);
+            for point in points.iter().skip(1) {
+                path_builder.line_to(*point);
+            }
+            path_builder.move_to(barycenter - Vector::new(BARYCENTER_MARKER_SIZE, 0.));
+            path_builder.line_to(barycenter + Vector::new(BARYCENTER_MARKER_SIZE, 0.));
+            path_builder.move_to(barycenter - Vector::new(0., BARYCENTER_MARKER_SIZE));
+            path_builder.line_to(barycenter + Vector::new(0., BARYCENTER_MARKER_SIZE));
+        });
+        let stroke = Stroke {
+            style: Style::Solid(WOBBLE_COLOR),
+            width: 1.,
+            ..Default::default()
+        };
+        frame.stroke(&path, stroke);
+
+        let label = Text {
+            content: format!("Star wobble: {}", wobble.amplitude().astro_display()),
+            position: barycenter + Vector::new(BARYCENTER_MARKER_SIZE * 2., BARYCENTER_MARKER_SIZE),
+            color: WOBBLE_COLOR,
+            ..Default::default()
+        };
+        frame.fill_text(label);
+    }
+
+    fn draw_apsis<T: AstroDisplay>(
+        &self,
+        frame: &mut Frame,
+        point: Point,
+        name: &str,
+        distance: T,
+    ) {
+        frame.fill(&Path::circle(point, APSIS_MARKER_RADIUS), ORBIT_COLOR);
+        if self.display_apsis_distances {
+            let label = Text {
+                content: format!("{}: {}", name, distance.astro_display()),
+                position: point + Vector::new(APSIS_MARKER_RADIUS * 2., APSIS_MARKER_RADIUS),
+                color: ORBIT_COLOR,
+                ..Default::default()
+            };
+            frame.fill_text(label);
+        }
+    }
+}
+
+fn draw_arrow(frame: &mut Frame, from: Point, to: Point) {
+    let direction = to - from;
+    let length = (direction.x.powi(2) + direction.y.powi(2)).sqrt();
+    if length <= f32::EPSILON {
+        return;
+    }
+    let forward = direction * (ARROW_SIZE / length);
+    let sideways = Vector::new(-forward.y, forward.x) * 0.5;
+    let arrow = Path::new(|path_builder| {
+        path_builder.move_to(to);
+        path_builder.line_to(to - forward + sideways);
+        path_builder.line_to(to - forward - sideways);
+        path_builder.close();
+    });
+    frame.fill(&arrow, ORBIT_COLOR);
+}
@@ -1,2 +1,5 @@
 mod canvas;
+mod draw_orbits;
+mod scale;
+mod selection;
 pub(super) mod widget;
@@ -0,0 +1,138 @@
+use astro_coords::{cartesian::Cartesian, direction::Direction};
+use astro_utils::astro_display::AstroDisplay;
+use iced::{
+    widget::canvas::{Frame, Path, Stroke, Style, Text},
+    Color, Vector,
+};
+use serde::{Deserialize, Serialize};
+use std::{f64::consts::PI, fmt::Display};
+use uom::si::{
+    f64::{Angle, Length},
+    length::{astronomical_unit, gigameter, kilometer},
+};
+
+use super::widget::TopViewState;
+
+const KILOMETERS_PER_LIGHT_MINUTE: f64 = 299_792.458 * 60.;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ScaleUnit {
+    Automatic,
+    AstronomicalUnit,
+    Gigameter,
+    LightMinute,
+}
+
+impl ScaleUnit {
+    pub(crate) const ALL: [ScaleUnit; 4] = [
+        ScaleUnit::Automatic,
+        ScaleUnit::AstronomicalUnit,
+        ScaleUnit::Gigameter,
+        ScaleUnit::LightMinute,
+    ];
+
+    pub(crate) fn format(&self, length: Length) -> String {
+        match self {
+            ScaleUnit::Automatic => length.astro_display(),
+            ScaleUnit::AstronomicalUnit => {
+                format!("{:.3} AU", length.get::<astronomical_unit>())
+            }
+            ScaleUnit::Gigameter => format!("{:.3} Gm", length.get::<gigameter>()),
+            ScaleUnit::LightMinute => format!(
+                "{:.3} light-minutes",
+                length.get::<kilometer>() / KILOMETERS_PER_LIGHT_MINUTE
+            ),
+        }
+    }
+}
+
+impl Display for ScaleUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScaleUnit::Automatic => write!(f, "Automatic"),
+            ScaleUnit::AstronomicalUnit => write!(f, "AU"),
+            ScaleUnit::Gigameter => write!(f, "Gm"),
+            ScaleUnit::LightMinute => write!(f, "Light-minutes"),
+        }
+    }
+}
+
+impl TopViewState {
+    /*
+     * Concentric rings around the central body in the ecliptic plane.
+     * Only as many rings are drawn as are needed to fill the canvas.
+     */
+    pub(super) fn draw_grid(
+        &self,
+        frame: &mut Frame,
+        view_angle: Angle,
+        view_rotation_axis: &Direction,
+        offset: Vector,
+    ) {
+        const SEGMENTS: usize = 180;
+        const MAX_RINGS: usize = 200;
+        const GRID_COLOR: Color = Color {
+            r: 0.4,
+            g: 0.5,
+            b: 0.7,
+            a: 0.4,
+        };
+
+        let size = frame.size();
+        let canvas_radius = (size.width.powi(2) + size.height.powi(2)).sqrt() / 2.;
+        let visible_distance = self.length_per_pixel * (canvas_radius as f64)
+            + self.length_per_pixel * (offset.x.hypot(offset.y) as f64);
+        let ring_count =
+            ((visible_distance / self.grid_spacing).value.ceil() as usize).min(MAX_RINGS);
+
+        let stroke = Stroke {
+            style: Style::Solid(GRID_COLOR),
+            width: 1.,
+            ..Default::default()
+        };
+        for ring in 1..=ring_count {
+            let radius = self.grid_spacing * ring as f64;
+            let points: Vec<_> = (0..SEGMENTS)
+                .map(|i| {
+                    let phi = 2. * PI * i as f64 / SEGMENTS as f64;
+                    let pos = Cartesian::new(
+                        radius * phi.cos(),
+                        radius * phi.sin(),
+                        Length::new::<astronomical_unit>(0.),
+                    );
+                    frame.center() + self.canvas_position(&pos, view_angle, view_rotation_axis)
+                        - offset
+                })
+                .collect();
+            let path = Path::new(|path_builder| {
+                path_builder.move_to(points[0]);
+                for point in points.iter().skip(1) {
+                    path_builder.line_to(*point);
+                }
+                path_builder.close();
+            });
+            frame.stroke(&path, stroke);
+
+            let label = Text {
+                content: self.scale_unit.format(radius),
+                position: points[0],
+                color: GRID_COLOR,
+                ..Default::default()
+            };
+            frame.fill_text(label);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_au_in_all_units() {
+        let length = Length::new::<astronomical_unit>(1.);
+        assert_eq!(ScaleUnit::AstronomicalUnit.format(length), "1.000 AU");
+        assert_eq!(ScaleUnit::Gigameter.format(length), "149.598 Gm");
+        assert_eq!(ScaleUnit::LightMinute.format(length), "8.317 light-minutes");
+    }
+}
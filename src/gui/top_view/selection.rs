@@ -0,0 +1,124 @@
+use astro_coords::{
+    cartesian::Cartesian, direction::Direction, traits::*,
+    transformations::rotations::get_rotation_parameters,
+};
+use iced::{
+    widget::canvas::{Frame, Path, Stroke, Style},
+    Color, Point, Size, Vector,
+};
+use uom::si::f64::Angle;
+
+use crate::model::{
+    body_id::BodyId,
+    celestial_system::{part::PartOfCelestialSystem, CelestialSystem},
+    planet::Planet,
+};
+
+use super::{
+    canvas::{canvas_radius, BodyParams, ViewParams},
+    widget::TopViewState,
+};
+
+const SELECTION_RING_GAP: f32 = 4.;
+const TARGET_LINE_COLOR: Color = Color {
+    r: 1.,
+    g: 0.85,
+    b: 0.3,
+    a: 0.7,
+};
+const MIN_CLICK_RADIUS: f32 = 8.;
+
+impl TopViewState {
+    /*
+     * The rotation angle, rotation axis and canvas offset with which bodies are drawn.
+     */
+    pub(super) fn view_transformation(
+        &self,
+        selected_planet: &Option<Planet>,
+    ) -> (Angle, Direction, Vector) {
+        let view_direction = &self.view_ecliptic.spherical.to_direction();
+        let (angle, view_rotation_axis) = get_rotation_parameters(&Direction::Z, view_direction);
+        let offset = match selected_planet {
+            Some(focus) => self.canvas_position(focus.get_position(), angle, &view_rotation_axis),
+            None => Vector::new(0.0, 0.0),
+        };
+        (angle, view_rotation_axis, offset)
+    }
+
+    /*
+     * Returns the ID of the planet drawn closest to the clicked position, if it is close enough to count as a hit.
+     * The position is relative to the top left corner of the canvas.
+     */
+    pub(crate) fn planet_at(
+        &self,
+        position: Point,
+        canvas_size: Size,
+        selected_planet: &Option<Planet>,
+        celestial_system: &CelestialSystem,
+    ) -> Option<BodyId> {
+        let (angle, view_rotation_axis, offset) = self.view_transformation(selected_planet);
+        let center = Point::new(canvas_size.width / 2., canvas_size.height / 2.);
+        celestial_system
+            .get_planets()
+            .into_iter()
+            .filter_map(|planet| {
+                let pos = center
+                    + self.canvas_position(planet.get_position(), angle, &view_rotation_axis)
+                    - offset;
+                let distance = pos.distance(position);
+                let click_radius =
+                    canvas_radius(&planet.get_data().get_radius()).max(MIN_CLICK_RADIUS);
+                (distance <= click_radius).then_some((distance, planet))
+            })
+            .min_by(|(a, _), (b, _)| a.total_cmp(b))
+            .and_then(|(_, planet)| planet.get_id())
+    }
+
+    pub(super) fn draw_selection_ring(
+        &self,
+        frame: &mut Frame,
+        body: &BodyParams,
+        view: &ViewParams,
+    ) {
+        let pos = frame.center()
+            + self.canvas_position(body.pos3d, view.view_angle, view.rotation_axis)
+            - view.offset;
+        let ring = Path::circle(pos, canvas_radius(&body.radius) + SELECTION_RING_GAP);
+        let stroke = Stroke {
+            style: Style::Solid(Color::WHITE),
+            width: 1.5,
+            ..Default::default()
+        };
+        frame.stroke(&ring, stroke);
+    }
+
+    /*
+     * Connects the focused body with the target, so that the pair stands out among the other orbits.
+     */
+    pub(super) fn draw_target_line(
+        &self,
+        frame: &mut Frame,
+        observer: &Cartesian,
+        target: &Cartesian,
+        view: &ViewParams,
+    ) {
+        let to_canvas = |pos: &Cartesian| {
+            frame.center() + self.canvas_position(pos, view.view_angle, view.rotation_axis)
+                - view.offset
+        };
+        let line = Path::line(to_canvas(observer), to_canvas(target));
+        let stroke = Stroke {
+            style: Style::Solid(TARGET_LINE_COLOR),
+            width: 1.5,
+            ..Default::default()
+        };
+        frame.stroke(&line, stroke);
+    }
+}
+
+pub(super) fn is_selected(selected_planet: &Option<Planet>, planet: &Planet) -> bool {
+    match selected_planet {
+        Some(selected) => selected.get_id() == planet.get_id(),
+        None => false,
+    }
+}
@@ -1,20 +1,31 @@
 use astro_coords::ecliptic::Ecliptic;
 use astro_units::angle::normalized_angle;
-use astro_utils::astro_display::AstroDisplay;
+use astro_utils::{astro_display::AstroDisplay, planets::planet_data::PlanetData};
 use iced::{
-    widget::{canvas::Cache, Column},
-    Alignment, Element, Length as IcedLength,
+    time::Instant,
+    widget::{canvas::Cache, Column, PickList, Row, Text, Toggler},
+    Alignment, Element, Length as IcedLength, Size,
 };
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
 use uom::si::{
     angle::degree,
     f64::{Angle, Length},
     length::astronomical_unit,
 };
 
+use super::scale::ScaleUnit;
 use crate::gui::{
+    dialog::DialogType,
     gui_widget::{BIG_COLUMN_WIDTH, PADDING},
     message::GuiMessage,
-    shared_widgets::control_field,
+    shared_widgets::{control_field, std_button},
+    touch::TouchGesture,
+    transition::{interpolate_angle, interpolate_longitude, Interpolate, Transition},
+};
+use crate::model::{
+    body_id::BodyId,
+    celestial_system::barycenter::{CentralBodyWobble, WobbleKey},
 };
 
 pub(crate) struct TopViewState {
@@ -23,6 +34,49 @@ pub(crate) struct TopViewState {
     pub(super) scale_cache: Cache,
     pub(super) length_per_pixel: Length,
     pub(super) view_ecliptic: Ecliptic,
+    pub(super) canvas_size: Cell<Size>,
+    pub(super) display_apsis_distances: bool,
+    pub(super) scale_unit: ScaleUnit,
+    pub(super) display_grid: bool,
+    pub(super) grid_spacing: Length,
+    pub(super) display_barycenter: bool,
+    pub(super) wobble_cache: RefCell<Option<(WobbleKey, Option<CentralBodyWobble>)>>,
+    view_transition: Option<Transition<TopViewParams>>,
+}
+
+/*
+ * The part of the top view that is exported with a view configuration.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct TopViewConfiguration {
+    length_per_pixel: Length,
+    view_longitude: Angle,
+    view_latitude: Angle,
+    display_apsis_distances: bool,
+    scale_unit: ScaleUnit,
+    display_grid: bool,
+    grid_spacing: Length,
+    #[serde(default)]
+    display_barycenter: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TopViewParams {
+    length_per_pixel: Length,
+    longitude: Angle,
+    latitude: Angle,
+}
+
+impl Interpolate for TopViewParams {
+    fn interpolate(&self, other: &Self, t: f64) -> Self {
+        // Zooming is interpolated logarithmically, so that it feels uniform.
+        let zoom_ratio = (other.length_per_pixel / self.length_per_pixel).value;
+        TopViewParams {
+            length_per_pixel: self.length_per_pixel * zoom_ratio.powf(t),
+            longitude: interpolate_longitude(self.longitude, other.longitude, t),
+            latitude: interpolate_angle(self.latitude, other.latitude, t),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -30,6 +84,11 @@ pub(crate) enum TopViewUpdate {
     LengthScale(Length),
     ViewLongitude(Angle),
     ViewLatitude(Angle),
+    DisplayApsisDistances(bool),
+    ScaleUnit(ScaleUnit),
+    DisplayGrid(bool),
+    GridSpacing(Length),
+    DisplayBarycenter(bool),
 }
 
 impl From<TopViewUpdate> for GuiMessage {
@@ -46,17 +105,37 @@ impl TopViewState {
             scale_cache: Cache::default(),
             length_per_pixel: Length::new::<astronomical_unit>(0.01),
             view_ecliptic: Ecliptic::z_direction(),
+            canvas_size: Cell::new(Size::ZERO),
+            display_apsis_distances: false,
+            scale_unit: ScaleUnit::Automatic,
+            display_grid: false,
+            grid_spacing: Length::new::<astronomical_unit>(1.),
+            display_barycenter: false,
+            wobble_cache: RefCell::new(None),
+            view_transition: None,
         }
     }
 
     pub(crate) fn update(&mut self, message: TopViewUpdate) {
+        self.update_view(message, true);
+    }
+
+    /*
+     * Continuous input snaps to the new view instead of restarting the easing on every event.
+     */
+    fn update_view(&mut self, message: TopViewUpdate, animated: bool) {
+        let mut target = self.view_params_target();
         match message {
             TopViewUpdate::LengthScale(length_per_pixel) => {
-                self.length_per_pixel = length_per_pixel;
+                // A zero or undefined scale would make every position on the canvas undefined.
+                if !(length_per_pixel.value.is_finite() && length_per_pixel.value > 0.) {
+                    return;
+                }
+                target.length_per_pixel = length_per_pixel;
             }
             TopViewUpdate::ViewLongitude(mut longitude) => {
                 longitude = normalized_angle(longitude);
-                self.view_ecliptic.spherical.longitude = longitude;
+                target.longitude = longitude;
             }
             TopViewUpdate::ViewLatitude(mut latitude) => {
                 if latitude.get::<degree>() < -90. {
@@ -64,45 +143,261 @@ impl TopViewState {
                 } else if latitude.get::<degree>() > 90. {
                     latitude = Angle::new::<degree>(90.);
                 }
-                self.view_ecliptic.spherical.latitude = latitude;
+                target.latitude = latitude;
+            }
+            TopViewUpdate::DisplayApsisDistances(display) => {
+                self.display_apsis_distances = display;
+                return;
+            }
+            TopViewUpdate::ScaleUnit(unit) => {
+                self.scale_unit = unit;
+                return;
+            }
+            TopViewUpdate::DisplayGrid(display) => {
+                self.display_grid = display;
+                return;
+            }
+            TopViewUpdate::GridSpacing(spacing) => {
+                self.grid_spacing = spacing;
+                return;
+            }
+            TopViewUpdate::DisplayBarycenter(display) => {
+                self.display_barycenter = display;
+                return;
+            }
+        }
+        if animated {
+            self.view_transition = Some(Transition::new(self.current_view_params(), target));
+        } else {
+            self.view_transition = None;
+            self.set_view_params(target);
+        }
+    }
+
+    /*
+     * Pinching zooms, moving two fingers turns the view direction along with them.
+     */
+    pub(crate) fn apply_touch_gesture(&mut self, gesture: TouchGesture) {
+        const DEGREES_PER_PIXEL: f64 = 0.2;
+
+        if gesture.zoom <= 0. {
+            return;
+        }
+        let target = self.view_params_target();
+        self.update_view(
+            TopViewUpdate::LengthScale(target.length_per_pixel / gesture.zoom as f64),
+            false,
+        );
+        self.update_view(
+            TopViewUpdate::ViewLongitude(
+                target.longitude - Angle::new::<degree>(gesture.pan.x as f64 * DEGREES_PER_PIXEL),
+            ),
+            false,
+        );
+        self.update_view(
+            TopViewUpdate::ViewLatitude(
+                target.latitude + Angle::new::<degree>(gesture.pan.y as f64 * DEGREES_PER_PIXEL),
+            ),
+            false,
+        );
+    }
+
+    fn current_view_params(&self) -> TopViewParams {
+        TopViewParams {
+            length_per_pixel: self.length_per_pixel,
+            longitude: self.view_ecliptic.spherical.longitude,
+            latitude: self.view_ecliptic.spherical.latitude,
+        }
+    }
+
+    fn set_view_params(&mut self, params: TopViewParams) {
+        self.length_per_pixel = params.length_per_pixel;
+        self.view_ecliptic.spherical.longitude = params.longitude;
+        self.view_ecliptic.spherical.latitude = params.latitude;
+    }
+
+    fn view_params_target(&self) -> TopViewParams {
+        match &self.view_transition {
+            Some(transition) => *transition.get_target(),
+            None => self.current_view_params(),
+        }
+    }
+
+    /*
+     * Without anything to fit, e.g. because there are no planets, the zoom stays as it is.
+     */
+    pub(crate) fn length_per_pixel_to_fit(&self, radius: Length) -> Length {
+        const FILLED_FRACTION: f32 = 0.9;
+        let size = self.canvas_size.get();
+        let smaller_side = size.width.min(size.height);
+        if smaller_side <= 0. || !(radius.value.is_finite() && radius.value > 0.) {
+            return self.view_params_target().length_per_pixel;
+        }
+        radius / (FILLED_FRACTION * smaller_side / 2.) as f64
+    }
+
+    pub(crate) fn configuration(&self) -> TopViewConfiguration {
+        let target = self.view_params_target();
+        TopViewConfiguration {
+            length_per_pixel: target.length_per_pixel,
+            view_longitude: target.longitude,
+            view_latitude: target.latitude,
+            display_apsis_distances: self.display_apsis_distances,
+            scale_unit: self.scale_unit,
+            display_grid: self.display_grid,
+            grid_spacing: self.grid_spacing,
+            display_barycenter: self.display_barycenter,
+        }
+    }
+
+    pub(crate) fn apply_configuration(&mut self, configuration: TopViewConfiguration) {
+        self.display_apsis_distances = configuration.display_apsis_distances;
+        self.scale_unit = configuration.scale_unit;
+        self.display_grid = configuration.display_grid;
+        self.grid_spacing = configuration.grid_spacing;
+        self.display_barycenter = configuration.display_barycenter;
+        let target = TopViewParams {
+            length_per_pixel: configuration.length_per_pixel,
+            longitude: configuration.view_longitude,
+            latitude: configuration.view_latitude,
+        };
+        self.view_transition = Some(Transition::new(self.current_view_params(), target));
+    }
+
+    pub(crate) fn is_animating(&self) -> bool {
+        self.view_transition.is_some()
+    }
+
+    pub(crate) fn animate(&mut self, now: Instant) {
+        if let Some(transition) = &self.view_transition {
+            let params = transition.value(now);
+            let is_finished = transition.is_finished(now);
+            self.set_view_params(params);
+            if is_finished {
+                self.view_transition = None;
             }
         }
     }
 
+    pub(crate) fn redraw_background(&mut self) {
+        self.background_cache.clear();
+    }
+
     pub(crate) fn redraw(&mut self) {
         self.bodies_cache.clear();
         self.scale_cache.clear();
     }
 
-    pub(crate) fn control_field(&self) -> Element<'_, GuiMessage> {
+    pub(crate) fn control_field(
+        &self,
+        selected_planet: Option<&PlanetData>,
+        selected_planet_id: Option<BodyId>,
+    ) -> Element<'_, GuiMessage> {
+        let target = self.view_params_target();
         let length_scale_control_field = control_field(
             "Length per 100px:",
-            (self.length_per_pixel * 100.).astro_display(),
-            TopViewUpdate::LengthScale(self.length_per_pixel / 2.),
-            TopViewUpdate::LengthScale(self.length_per_pixel * 2.),
+            (target.length_per_pixel * 100.).astro_display(),
+            TopViewUpdate::LengthScale(target.length_per_pixel / 2.),
+            TopViewUpdate::LengthScale(target.length_per_pixel * 2.),
         );
         let view_angle_step: Angle = Angle::new::<degree>(10.0);
-        let view_longitude = self.view_ecliptic.spherical.longitude;
+        let view_longitude = target.longitude;
         let view_longitude_control_field = control_field(
             "View longitude:",
             view_longitude.astro_display(),
             TopViewUpdate::ViewLongitude(view_longitude - view_angle_step),
             TopViewUpdate::ViewLongitude(view_longitude + view_angle_step),
         );
-        let view_latitude = self.view_ecliptic.spherical.latitude;
+        let view_latitude = target.latitude;
         let view_latitude_control_field = control_field(
             "View latitude:",
             view_latitude.astro_display(),
             TopViewUpdate::ViewLatitude(view_latitude - view_angle_step),
             TopViewUpdate::ViewLatitude(view_latitude + view_angle_step),
         );
+        let go_to_surface_button = std_button(
+            "Go to Surface",
+            GuiMessage::GoToSurface(selected_planet_id),
+            selected_planet.is_some(),
+        );
+        let apsis_distances_toggler = Toggler::new(self.display_apsis_distances)
+            .label("Show apsis distances")
+            .on_toggle(|b| TopViewUpdate::DisplayApsisDistances(b).into());
+        let scale_unit_picker = PickList::new(ScaleUnit::ALL, Some(self.scale_unit), |unit| {
+            TopViewUpdate::ScaleUnit(unit).into()
+        });
+        let scale_unit_row = Row::new()
+            .push(Text::new("Scale unit:"))
+            .push(scale_unit_picker)
+            .spacing(PADDING)
+            .align_y(Alignment::Center);
+        let grid_toggler = Toggler::new(self.display_grid)
+            .label("Show distance grid")
+            .on_toggle(|b| TopViewUpdate::DisplayGrid(b).into());
+        let grid_spacing_control_field = control_field(
+            "Grid spacing:",
+            self.scale_unit.format(self.grid_spacing),
+            TopViewUpdate::GridSpacing(self.grid_spacing / 2.),
+            TopViewUpdate::GridSpacing(self.grid_spacing * 2.),
+        );
+        let barycenter_toggler = Toggler::new(self.display_barycenter)
+            .label("Show star wobble")
+            .on_toggle(|b| TopViewUpdate::DisplayBarycenter(b).into());
+        let transit_button = std_button(
+            "Transit",
+            GuiMessage::OpenDialog(DialogType::TransitLightCurve(
+                selected_planet_id.unwrap_or_default(),
+            )),
+            selected_planet_id.is_some(),
+        );
+        let zoom_to_fit_button = std_button("Zoom to Fit", GuiMessage::ZoomToFit, true);
+        let travel_time_button = std_button(
+            "Travel Times",
+            GuiMessage::OpenDialog(DialogType::TravelTime),
+            true,
+        );
+        let flyby_button = std_button(
+            "Rogue Flyby",
+            GuiMessage::OpenDialog(DialogType::Flyby),
+            selected_planet.is_some(),
+        );
+        let button_row = Row::new()
+            .push(zoom_to_fit_button)
+            .push(go_to_surface_button)
+            .push(transit_button)
+            .push(travel_time_button)
+            .push(flyby_button)
+            .spacing(PADDING);
         Column::new()
             .push(length_scale_control_field)
             .push(view_longitude_control_field)
             .push(view_latitude_control_field)
+            .push(scale_unit_row)
+            .push(grid_toggler)
+            .push(grid_spacing_control_field)
+            .push(apsis_distances_toggler)
+            .push(barycenter_toggler)
+            .push(button_row)
             .width(IcedLength::Fixed(BIG_COLUMN_WIDTH))
             .align_x(Alignment::Center)
             .spacing(PADDING)
             .into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zooming_to_fit_nothing_keeps_the_zoom() {
+        let mut state = TopViewState::new();
+        state.canvas_size.set(Size::new(800., 600.));
+        let nothing = Length::new::<astronomical_unit>(0.);
+        let length_per_pixel = state.length_per_pixel_to_fit(nothing);
+        assert_eq!(length_per_pixel, state.length_per_pixel);
+
+        state.update(TopViewUpdate::LengthScale(length_per_pixel * 0.));
+        assert!(!state.is_animating());
+    }
+}
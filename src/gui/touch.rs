@@ -0,0 +1,107 @@
+use iced::{
+    touch::{self, Finger},
+    Point, Vector,
+};
+
+/*
+ * The change between two consecutive positions of two fingers.
+ * A zoom above 1 means that the fingers moved apart, the pan is the movement of the point between them.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct TouchGesture {
+    pub(crate) zoom: f32,
+    pub(crate) pan: Vector,
+}
+
+/*
+ * Keeps track of the fingers on a canvas, and turns their movements into pinch and two-finger pan gestures.
+ */
+#[derive(Debug, Clone, Default)]
+pub(crate) struct TouchTracker {
+    fingers: Vec<(Finger, Point)>,
+}
+
+impl TouchTracker {
+    const MIN_FINGER_DISTANCE: f32 = 1.;
+
+    pub(crate) fn handle(&mut self, event: &touch::Event) -> Option<TouchGesture> {
+        match *event {
+            touch::Event::FingerPressed { id, position } => {
+                self.fingers.retain(|(finger, _)| *finger != id);
+                self.fingers.push((id, position));
+                None
+            }
+            touch::Event::FingerMoved { id, position } => {
+                let before = self.two_finger_state();
+                if let Some(finger) = self.fingers.iter_mut().find(|(finger, _)| *finger == id) {
+                    finger.1 = position;
+                }
+                let after = self.two_finger_state();
+                let ((center_before, distance_before), (center_after, distance_after)) =
+                    (before?, after?);
+                Some(TouchGesture {
+                    zoom: distance_after / distance_before,
+                    pan: center_after - center_before,
+                })
+            }
+            touch::Event::FingerLifted { id, .. } | touch::Event::FingerLost { id, .. } => {
+                self.fingers.retain(|(finger, _)| *finger != id);
+                None
+            }
+        }
+    }
+
+    /*
+     * The center between, and the distance of, exactly two fingers.
+     */
+    fn two_finger_state(&self) -> Option<(Point, f32)> {
+        let [(_, a), (_, b)] = self.fingers.as_slice() else {
+            return None;
+        };
+        let distance = a.distance(*b);
+        if distance < Self::MIN_FINGER_DISTANCE {
+            return None;
+        }
+        let center = Point::new((a.x + b.x) / 2., (a.y + b.y) / 2.);
+        Some((center, distance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn press(tracker: &mut TouchTracker, id: u64, x: f32, y: f32) {
+        let event = touch::Event::FingerPressed {
+            id: Finger(id),
+            position: Point::new(x, y),
+        };
+        assert!(tracker.handle(&event).is_none());
+    }
+
+    fn move_finger(tracker: &mut TouchTracker, id: u64, x: f32, y: f32) -> Option<TouchGesture> {
+        tracker.handle(&touch::Event::FingerMoved {
+            id: Finger(id),
+            position: Point::new(x, y),
+        })
+    }
+
+    #[test]
+    fn single_finger_makes_no_gesture() {
+        let mut tracker = TouchTracker::default();
+        press(&mut tracker, 1, 0., 0.);
+        assert!(move_finger(&mut tracker, 1, 10., 0.).is_none());
+    }
+
+    #[test]
+    fn spreading_fingers_zooms_in_and_moving_them_pans() {
+        let mut tracker = TouchTracker::default();
+        press(&mut tracker, 1, 0., 0.);
+        press(&mut tracker, 2, 10., 0.);
+        let gesture = move_finger(&mut tracker, 2, 20., 0.).unwrap();
+        assert!((gesture.zoom - 2.).abs() < 1e-6);
+        assert!((gesture.pan.x - 5.).abs() < 1e-6);
+        let gesture = move_finger(&mut tracker, 1, 0., 10.).unwrap();
+        assert!((gesture.pan.y - 5.).abs() < 1e-6);
+    }
+}
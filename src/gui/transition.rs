@@ -0,0 +1,80 @@
+use astro_units::angle::normalized_angle;
+use iced::time::{Duration, Instant};
+use uom::si::{angle::degree, f64::Angle};
+
+const TRANSITION_DURATION: Duration = Duration::from_millis(300);
+
+pub(super) trait Interpolate {
+    fn interpolate(&self, other: &Self, t: f64) -> Self;
+}
+
+/*
+ * Animates a set of view parameters from one value to another.
+ * The displayed value is sampled on every frame until the transition is finished.
+ */
+pub(super) struct Transition<T> {
+    from: T,
+    to: T,
+    start: Instant,
+}
+
+impl<T: Interpolate> Transition<T> {
+    pub(super) fn new(from: T, to: T) -> Self {
+        Transition {
+            from,
+            to,
+            start: Instant::now(),
+        }
+    }
+
+    pub(super) fn get_target(&self) -> &T {
+        &self.to
+    }
+
+    pub(super) fn value(&self, now: Instant) -> T {
+        let t = now.saturating_duration_since(self.start).as_secs_f64()
+            / TRANSITION_DURATION.as_secs_f64();
+        let t = t.clamp(0., 1.);
+        let smoothed = t * t * (3. - 2. * t);
+        self.from.interpolate(&self.to, smoothed)
+    }
+
+    pub(super) fn is_finished(&self, now: Instant) -> bool {
+        now.saturating_duration_since(self.start) >= TRANSITION_DURATION
+    }
+}
+
+pub(super) fn interpolate_angle(from: Angle, to: Angle, t: f64) -> Angle {
+    from + (to - from) * t
+}
+
+pub(super) fn interpolate_longitude(from: Angle, to: Angle, t: f64) -> Angle {
+    let mut delta = normalized_angle(to - from);
+    if delta > Angle::new::<degree>(180.) {
+        delta -= Angle::new::<degree>(360.);
+    }
+    normalized_angle(from + delta * t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn longitude_is_interpolated_across_zero() {
+        let from = Angle::new::<degree>(350.);
+        let to = Angle::new::<degree>(10.);
+        let halfway = interpolate_longitude(from, to, 0.5);
+        assert!(
+            halfway.get::<degree>().abs() < 1e-10 || (halfway.get::<degree>() - 360.).abs() < 1e-10
+        );
+    }
+
+    #[test]
+    fn interpolation_ends_at_target() {
+        let from = Angle::new::<degree>(10.);
+        let to = Angle::new::<degree>(80.);
+        let end = interpolate_angle(from, to, 1.);
+        assert!((end.get::<degree>() - 80.).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,179 @@
+use iced::Color;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, path::PathBuf};
+use uom::si::f64::Time;
+
+use super::{
+    canvas_background::BackgroundStyle, shared_canvas_functionality::LabelStyle,
+    star_rendering::StarRendering, surface_view::widget::SurfaceViewConfiguration,
+    top_view::widget::TopViewConfiguration, Gui,
+};
+use crate::{error::ElenathError, model::observer_location::ObserverLocation, project_folder};
+
+/*
+ * How labels are drawn, in a form that can be stored.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+struct LabelRules {
+    size: f32,
+    offset: f32,
+    follow_body_color: bool,
+    fixed_color: [f32; 4],
+    #[serde(default)]
+    show_catalog_names: bool,
+}
+
+impl From<LabelStyle> for LabelRules {
+    fn from(style: LabelStyle) -> Self {
+        LabelRules {
+            size: style.size,
+            offset: style.offset,
+            follow_body_color: style.follow_body_color,
+            fixed_color: [
+                style.fixed_color.r,
+                style.fixed_color.g,
+                style.fixed_color.b,
+                style.fixed_color.a,
+            ],
+            show_catalog_names: style.show_catalog_names,
+        }
+    }
+}
+
+impl From<LabelRules> for LabelStyle {
+    fn from(rules: LabelRules) -> Self {
+        let [r, g, b, a] = rules.fixed_color;
+        LabelStyle::default()
+            .with_size(rules.size)
+            .with_offset(rules.offset)
+            .with_follow_body_color(rules.follow_body_color)
+            .with_fixed_color(Color::from_rgba(r, g, b, a))
+            .with_show_catalog_names(rules.show_catalog_names)
+    }
+}
+
+/*
+ * Everything about how the system is looked at, but nothing about the system itself.
+ * Bookmarks are the saved observer locations, by the name of their planet. They are only applied to planets of the same name.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct ViewConfiguration {
+    display_names: bool,
+    display_constellations: bool,
+    labels: LabelRules,
+    time_step: Time,
+    star_rendering: StarRendering,
+    background: BackgroundStyle,
+    surface_view: SurfaceViewConfiguration,
+    top_view: TopViewConfiguration,
+    bookmarks: BTreeMap<String, Vec<ObserverLocation>>,
+}
+
+impl ViewConfiguration {
+    /*
+     * A background image next to the configuration is referenced relative to it, so that both can be moved together.
+     */
+    pub(crate) fn write_to_file(&self, path: PathBuf) -> Result<(), std::io::Error> {
+        let mut configuration = self.clone();
+        if let Some(folder) = project_folder::of_file(&path) {
+            configuration.background = configuration.background.relative_to(folder);
+        }
+        let content = serde_json::to_string_pretty(&configuration)?;
+        std::fs::write(path, content)
+    }
+
+    pub(crate) fn read_from_file(path: PathBuf) -> Result<Self, std::io::Error> {
+        let content = std::fs::read_to_string(&path)?;
+        let mut configuration: ViewConfiguration = serde_json::from_str(&content)?;
+        if let Some(folder) = project_folder::of_file(&path) {
+            configuration.background = configuration.background.resolved_in(folder);
+        }
+        Ok(configuration)
+    }
+}
+
+impl Gui {
+    pub(super) fn view_configuration(&self) -> ViewConfiguration {
+        let mut bookmarks: BTreeMap<String, Vec<ObserverLocation>> = BTreeMap::new();
+        if let Some(system) = self.celestial_system.as_ref() {
+            for (planet, locations) in system.get_all_observer_locations() {
+                let Some(data) = system.get_planet_data_by_id(planet) else {
+                    continue;
+                };
+                if !locations.is_empty() {
+                    bookmarks
+                        .entry(data.get_name().clone())
+                        .or_default()
+                        .extend_from_slice(locations);
+                }
+            }
+        }
+        ViewConfiguration {
+            display_names: self.display_names,
+            display_constellations: self.display_constellations,
+            labels: self.label_style.into(),
+            time_step: self.time_step,
+            star_rendering: self.surface_view_state.star_rendering,
+            background: self.canvas_background.get_style().clone(),
+            surface_view: self.surface_view_state.configuration(),
+            top_view: self.top_view_state.configuration(),
+            bookmarks,
+        }
+    }
+
+    /*
+     * Returns the number of bookmarks that were added to the loaded system.
+     * The background is applied last, because its image may have gone missing since the configuration was saved.
+     */
+    pub(super) fn apply_view_configuration(
+        &mut self,
+        configuration: ViewConfiguration,
+    ) -> Result<usize, ElenathError> {
+        self.display_names = configuration.display_names;
+        self.display_constellations = configuration.display_constellations;
+        self.label_style = configuration.labels.into();
+        self.time_step = configuration.time_step;
+        self.save_star_rendering(configuration.star_rendering);
+        self.surface_view_state
+            .apply_configuration(configuration.surface_view);
+        self.top_view_state
+            .apply_configuration(configuration.top_view);
+
+        let mut added_bookmarks = 0;
+        if let Some(system) = self.celestial_system.as_mut() {
+            for (planet_name, locations) in configuration.bookmarks {
+                for planet in system.get_planet_ids_by_name(&planet_name) {
+                    for location in locations.iter() {
+                        system.add_observer_location(planet, location.clone());
+                        added_bookmarks += 1;
+                    }
+                }
+            }
+        }
+        if added_bookmarks > 0 {
+            self.unsaved_changes = true;
+        }
+
+        self.set_canvas_background(configuration.background)?;
+        Ok(added_bookmarks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn label_rules_round_trip() {
+        let style = LabelStyle::default()
+            .with_size(20.)
+            .with_offset(3.)
+            .with_follow_body_color(false)
+            .with_fixed_color(Color::from_rgb(1., 0., 0.))
+            .with_show_catalog_names(true);
+        let rules = LabelRules::from(style);
+        let content = serde_json::to_string(&rules).unwrap();
+        let read: LabelRules = serde_json::from_str(&content).unwrap();
+        assert_eq!(LabelStyle::from(read), style);
+    }
+}
@@ -0,0 +1,194 @@
+use super::{
+    gui_widget::{GuiViewMode, PADDING, SMALL_COLUMN_WIDTH},
+    message::GuiMessage,
+    shared_canvas_functionality::LabelStyle,
+    Gui,
+};
+use crate::error::ElenathError;
+use crate::model::body_id::BodyId;
+use astro_utils::astro_display::AstroDisplay;
+use iced::{
+    widget::{Container, PickList, Row, Toggler},
+    Alignment, Element, Length,
+};
+use std::fmt::Display;
+use uom::si::f64::Time;
+
+/*
+ * The settings that all views share, as one of the views left them.
+ * The planet is remembered by its ID, and its name only serves the description.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ViewSettings {
+    selected_planet: Option<BodyId>,
+    selected_planet_name: String,
+    time_since_epoch: Option<Time>,
+    time_step: Time,
+    display_names: bool,
+    display_constellations: bool,
+    label_style: LabelStyle,
+}
+
+/*
+ * The settings each view had when it was left, so that unlinked views get them back when they are switched to.
+ */
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ViewSettingsMemory {
+    surface: Option<ViewSettings>,
+    top: Option<ViewSettings>,
+    table: Option<ViewSettings>,
+}
+
+impl ViewSettingsMemory {
+    fn slot(&mut self, mode: &GuiViewMode) -> &mut Option<ViewSettings> {
+        match mode {
+            GuiViewMode::Surface => &mut self.surface,
+            GuiViewMode::Top => &mut self.top,
+            GuiViewMode::Table => &mut self.table,
+        }
+    }
+
+    fn get(&self, mode: &GuiViewMode) -> Option<&ViewSettings> {
+        match mode {
+            GuiViewMode::Surface => self.surface.as_ref(),
+            GuiViewMode::Top => self.top.as_ref(),
+            GuiViewMode::Table => self.table.as_ref(),
+        }
+    }
+
+    fn store(&mut self, mode: &GuiViewMode, settings: ViewSettings) {
+        *self.slot(mode) = Some(settings);
+    }
+
+    pub(crate) fn clear(&mut self) {
+        *self = ViewSettingsMemory::default();
+    }
+}
+
+/*
+ * An entry of the quick switch, which tells what a view will show before switching to it.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ViewModeChoice {
+    mode: GuiViewMode,
+    remembered: Option<String>,
+}
+
+impl Display for ViewModeChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self.mode {
+            GuiViewMode::Surface => "Local View",
+            GuiViewMode::Top => "Top View",
+            GuiViewMode::Table => "Table View",
+        };
+        match &self.remembered {
+            Some(remembered) => write!(f, "{} ({})", name, remembered),
+            None => write!(f, "{}", name),
+        }
+    }
+}
+
+impl ViewSettings {
+    fn describe(&self) -> String {
+        let planet = if self.selected_planet_name.is_empty() {
+            "no planet"
+        } else {
+            &self.selected_planet_name
+        };
+        match self.time_since_epoch {
+            Some(time) => format!("{}, {}", planet, time.astro_display()),
+            None => planet.to_string(),
+        }
+    }
+}
+
+impl Gui {
+    fn current_view_settings(&self) -> ViewSettings {
+        ViewSettings {
+            selected_planet: self.selected_planet,
+            selected_planet_name: self.selected_planet_name(),
+            time_since_epoch: self
+                .celestial_system
+                .as_ref()
+                .map(|system| system.get_time_since_epoch()),
+            time_step: self.time_step,
+            display_names: self.display_names,
+            display_constellations: self.display_constellations,
+            label_style: self.label_style,
+        }
+    }
+
+    fn apply_view_settings(&mut self, settings: ViewSettings) -> Result<(), ElenathError> {
+        self.selected_planet = settings.selected_planet;
+        self.time_step = settings.time_step;
+        self.display_names = settings.display_names;
+        self.display_constellations = settings.display_constellations;
+        self.label_style = settings.label_style;
+        if let (Some(system), Some(time)) =
+            (self.celestial_system.as_mut(), settings.time_since_epoch)
+        {
+            if system.get_time_since_epoch() != time {
+                system.set_time_since_epoch(time);
+            }
+        }
+        self.update_tracked_body(true)
+    }
+
+    /*
+     * Linked views share one set of settings. Otherwise, the view that is left remembers its settings, and the view that is switched to gets its own back.
+     */
+    pub(super) fn switch_mode(&mut self, mode: GuiViewMode) -> Result<(), ElenathError> {
+        if !self.linked_views && mode != self.mode {
+            let settings = self.current_view_settings();
+            self.view_settings.store(&self.mode, settings);
+            if let Some(settings) = self.view_settings.get(&mode).cloned() {
+                self.apply_view_settings(settings)?;
+            }
+        }
+        self.mode = mode;
+        self.redraw();
+        Ok(())
+    }
+
+    /*
+     * When the views are linked again, they all continue with the settings of the current view.
+     */
+    pub(super) fn set_views_linked(&mut self, linked: bool) {
+        self.linked_views = linked;
+        self.view_settings.clear();
+    }
+
+    pub(super) fn view_mode_switcher(&self) -> Element<'_, GuiMessage> {
+        let choices: Vec<ViewModeChoice> =
+            [GuiViewMode::Surface, GuiViewMode::Top, GuiViewMode::Table]
+                .into_iter()
+                .map(|mode| {
+                    let remembered = if self.linked_views || mode == self.mode {
+                        None
+                    } else {
+                        self.view_settings.get(&mode).map(ViewSettings::describe)
+                    };
+                    ViewModeChoice { mode, remembered }
+                })
+                .collect();
+        let selected = choices
+            .iter()
+            .find(|choice| choice.mode == self.mode)
+            .cloned();
+        let picker = PickList::new(choices, selected, |choice| {
+            GuiMessage::ModeSelected(choice.mode)
+        });
+        let link_toggle = Container::new(
+            Toggler::new(self.linked_views)
+                .label("Link Views")
+                .on_toggle(GuiMessage::SetViewsLinked),
+        )
+        .width(Length::Fixed(SMALL_COLUMN_WIDTH));
+        Row::new()
+            .push(picker)
+            .push(link_toggle)
+            .align_y(Alignment::Center)
+            .spacing(PADDING)
+            .into()
+    }
+}
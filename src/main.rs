@@ -1,26 +1,25 @@
 #![warn(clippy::unwrap_used)]
 
 use gui::Gui;
-use iced::{window, Result, Size};
+use iced::Result;
 
+mod config;
 mod error;
 mod file_dialog;
 mod gui;
 mod model;
-
-static APP_TITLE: &str = "Elenath";
+mod project_folder;
+mod window_layout;
 
 fn main() -> Result {
-    let window_settings = window::Settings {
-        size: (Size {
-            width: 1820.,
-            height: 980.,
-        }),
-        ..Default::default()
-    };
-    iced::application(Gui::default, Gui::update, Gui::view)
-        .title(APP_TITLE)
+    if std::env::args().any(|arg| arg == "--benchmark") {
+        println!("{}", gui::benchmark::run());
+        return Ok(());
+    }
+    iced::daemon(Gui::new, Gui::update, Gui::view)
+        .title(Gui::title)
+        .subscription(Gui::subscription)
+        .scale_factor(Gui::scale_factor)
         .antialiasing(true)
-        .window(window_settings)
         .run()
 }
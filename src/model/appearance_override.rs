@@ -0,0 +1,71 @@
+use astro_units::illuminance::apparent_magnitude_to_illuminance;
+use astro_utils::{color::srgb::sRGBColor, stars::appearance::StarAppearance};
+use serde::{Deserialize, Serialize};
+
+/*
+ * Artistic control over how a star is drawn, without touching its physical data.
+ * Statistics, sorting and constellations keep using the derived appearance.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub(crate) struct AppearanceOverride {
+    pub(crate) color: Option<sRGBColor>,
+    pub(crate) apparent_magnitude: Option<f64>,
+}
+
+impl AppearanceOverride {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.color.is_none() && self.apparent_magnitude.is_none()
+    }
+
+    pub(crate) fn apply(&self, appearance: &StarAppearance) -> StarAppearance {
+        let illuminance = match self.apparent_magnitude {
+            Some(magnitude) => apparent_magnitude_to_illuminance(magnitude),
+            None => appearance.get_illuminance(),
+        };
+        let color = self
+            .color
+            .clone()
+            .unwrap_or_else(|| appearance.get_color().clone());
+        StarAppearance::new(
+            appearance.get_name().clone(),
+            illuminance,
+            color,
+            appearance.get_pos().clone(),
+            appearance.get_time_since_epoch(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_units::illuminance::illuminance_to_apparent_magnitude;
+    use astro_utils::real_data::stars::sun;
+    use uom::si::{f64::Time, time::year};
+
+    use super::*;
+
+    #[test]
+    fn only_overridden_properties_change() {
+        let appearance = sun()
+            .to_star_data()
+            .to_star_appearance(Time::new::<year>(0.));
+        let red = sRGBColor::from_sRGB(1., 0., 0.);
+        let color_only = AppearanceOverride {
+            color: Some(red.clone()),
+            apparent_magnitude: None,
+        };
+        let recolored = color_only.apply(&appearance);
+        assert_eq!(recolored.get_color(), &red);
+        assert_eq!(recolored.get_illuminance(), appearance.get_illuminance());
+
+        let brightness_only = AppearanceOverride {
+            color: None,
+            apparent_magnitude: Some(3.),
+        };
+        let dimmed = brightness_only.apply(&appearance);
+        assert_eq!(dimmed.get_color(), appearance.get_color());
+        let magnitude = illuminance_to_apparent_magnitude(dimmed.get_illuminance());
+        assert!((magnitude - 3.).abs() < 1e-6);
+        assert!(AppearanceOverride::default().is_empty());
+    }
+}
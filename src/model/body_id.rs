@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+/*
+ * Identifies a body independently of its position in any sorted list.
+ * IDs are handed out by the celestial system and are never reused within it.
+ * The default ID marks bodies that have not been assigned one, e.g. because they were saved before IDs existed.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub(crate) struct BodyId(u64);
+
+impl BodyId {
+    pub(crate) fn is_assigned(&self) -> bool {
+        self.0 != 0
+    }
+
+    pub(crate) fn as_u64(&self) -> u64 {
+        self.0
+    }
+
+    pub(crate) fn next(&self) -> BodyId {
+        BodyId(self.0 + 1)
+    }
+}
+
+impl Display for BodyId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+/*
+ * Free-text lore that worldbuilders attach to a body, stored alongside but independent of its physical data.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub(crate) struct BodyNotes {
+    pub(crate) notes: String,
+    pub(crate) tags: Vec<String>,
+}
+
+impl BodyNotes {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.notes.is_empty() && self.tags.is_empty()
+    }
+
+    pub(crate) fn tags_string(&self) -> String {
+        self.tags.join(", ")
+    }
+
+    pub(crate) fn set_tags_from_string(&mut self, tags: &str) {
+        self.tags = tags
+            .split(',')
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .collect();
+    }
+}
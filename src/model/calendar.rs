@@ -0,0 +1,492 @@
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use uom::si::{f64::Time, time::second};
+
+use crate::error::ElenathError;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct Month {
+    pub(crate) name: String,
+    pub(crate) days: u32,
+}
+
+/*
+ * In every year whose number is divisible by `every_years`, the month gets extra days, like February in a leap year.
+ * Negative extra days take days away, which allows for exceptions like the Gregorian century rule.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Intercalation {
+    pub(crate) every_years: u32,
+    pub(crate) month: usize,
+    pub(crate) extra_days: i32,
+}
+
+/*
+ * A fictional calendar. Its dates are counted from midnight at the start of the first month of the year at epoch,
+ * which lies the calendar offset before the epoch of the system.
+ * The fields that the cycle is derived from are only changed through setters, which forget the cached cycle.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct Calendar {
+    day_length: Time,
+    months: Vec<Month>,
+    intercalations: Vec<Intercalation>,
+    weekdays: Vec<String>,
+    year_at_epoch: i64,
+    #[serde(default = "no_offset")]
+    pub(crate) offset: Time,
+    #[serde(skip)]
+    cycle: CycleCache,
+}
+
+fn no_offset() -> Time {
+    Time::new::<second>(0.)
+}
+
+/*
+ * The pattern of year lengths repeats after this many years, which together have this many days.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct CalendarCycle {
+    years: i64,
+    days: i64,
+}
+
+/*
+ * The cycle of a validated calendar, so that dates need not go through all years of the cycle again.
+ * It is derived data and hence does not take part in comparisons.
+ * Cloning a calendar clones its cache, so every change to a clone has to reset it.
+ */
+#[derive(Debug, Clone, Default)]
+pub(crate) struct CycleCache(OnceLock<Option<CalendarCycle>>);
+
+impl PartialEq for CycleCache {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CalendarDate {
+    pub(crate) year: i64,
+    pub(crate) month: usize,
+    pub(crate) day: u32,
+    pub(crate) weekday: Option<usize>,
+    pub(crate) fraction_of_day: f64,
+}
+
+const MAX_CYCLE_YEARS: u64 = 100_000;
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl Calendar {
+    /*
+     * Twelve months of roughly equal length and a seven day week.
+     * A leap day is added to the last month often enough to make up for the fraction of a day left over each year.
+     */
+    pub(crate) fn simple(day_length: Time, year_length: Time) -> Calendar {
+        const MONTH_COUNT: u32 = 12;
+        const WEEK_LENGTH: usize = 7;
+
+        let days_per_year = (year_length / day_length).value.abs();
+        let whole_days = (days_per_year.floor() as u32).max(MONTH_COUNT);
+        let months = (0..MONTH_COUNT)
+            .map(|i| Month {
+                name: format!("Month {}", i + 1),
+                days: whole_days / MONTH_COUNT + if i < whole_days % MONTH_COUNT { 1 } else { 0 },
+            })
+            .collect();
+        let fraction = days_per_year - days_per_year.floor();
+        let intercalations = if fraction > 0.01 {
+            vec![Intercalation {
+                every_years: (1. / fraction).round() as u32,
+                month: MONTH_COUNT as usize - 1,
+                extra_days: 1,
+            }]
+        } else {
+            vec![]
+        };
+        let weekdays = (0..WEEK_LENGTH).map(|i| format!("Day {}", i + 1)).collect();
+        Calendar {
+            day_length,
+            months,
+            intercalations,
+            weekdays,
+            year_at_epoch: 1,
+            offset: no_offset(),
+            cycle: CycleCache::default(),
+        }
+    }
+
+    pub(crate) fn get_day_length(&self) -> Time {
+        self.day_length
+    }
+
+    pub(crate) fn set_day_length(&mut self, day_length: Time) {
+        self.day_length = day_length;
+        self.reset_cycle();
+    }
+
+    pub(crate) fn get_year_at_epoch(&self) -> i64 {
+        self.year_at_epoch
+    }
+
+    pub(crate) fn set_year_at_epoch(&mut self, year_at_epoch: i64) {
+        self.year_at_epoch = year_at_epoch;
+        self.reset_cycle();
+    }
+
+    pub(crate) fn get_weekdays(&self) -> &[String] {
+        &self.weekdays
+    }
+
+    pub(crate) fn set_weekdays(&mut self, weekdays: Vec<String>) {
+        self.weekdays = weekdays;
+    }
+
+    pub(crate) fn get_months(&self) -> &[Month] {
+        &self.months
+    }
+
+    pub(crate) fn add_month(&mut self, month: Month) {
+        self.months.push(month);
+        self.reset_cycle();
+    }
+
+    /*
+     * The intercalations of the removed month go with it, and those of later months keep pointing at their month.
+     */
+    pub(crate) fn remove_month(&mut self, index: usize) {
+        if index >= self.months.len() {
+            return;
+        }
+        self.months.remove(index);
+        self.intercalations.retain(|rule| rule.month != index);
+        for rule in &mut self.intercalations {
+            if rule.month > index {
+                rule.month -= 1;
+            }
+        }
+        self.reset_cycle();
+    }
+
+    pub(crate) fn get_intercalations(&self) -> &[Intercalation] {
+        &self.intercalations
+    }
+
+    pub(crate) fn add_intercalation(&mut self, intercalation: Intercalation) {
+        self.intercalations.push(intercalation);
+        self.reset_cycle();
+    }
+
+    pub(crate) fn remove_intercalation(&mut self, index: usize) {
+        if index < self.intercalations.len() {
+            self.intercalations.remove(index);
+            self.reset_cycle();
+        }
+    }
+
+    fn reset_cycle(&mut self) {
+        self.cycle = CycleCache::default();
+    }
+
+    pub(crate) fn validate(&self) -> Result<(), ElenathError> {
+        self.checked_cycle().map(|_| ())
+    }
+
+    /*
+     * Validates the calendar and keeps its cycle, which the fields of an edited calendar may no longer match.
+     */
+    pub(crate) fn validated(self) -> Result<Calendar, ElenathError> {
+        let cycle = self.checked_cycle()?;
+        Ok(Calendar {
+            cycle: CycleCache(OnceLock::from(Some(cycle))),
+            ..self
+        })
+    }
+
+    /*
+     * Calendars that were loaded from file are validated when their first date is needed.
+     */
+    fn cycle(&self) -> Option<CalendarCycle> {
+        *self.cycle.0.get_or_init(|| self.checked_cycle().ok())
+    }
+
+    fn checked_cycle(&self) -> Result<CalendarCycle, ElenathError> {
+        if self.day_length.value <= 0. || !self.day_length.value.is_finite() {
+            return Err(ElenathError::Generic(
+                "The length of a day needs to be positive.".to_string(),
+            ));
+        }
+        if self.months.is_empty() {
+            return Err(ElenathError::Generic(
+                "A calendar needs at least one month.".to_string(),
+            ));
+        }
+        for intercalation in &self.intercalations {
+            if intercalation.every_years == 0 || intercalation.month >= self.months.len() {
+                return Err(ElenathError::Generic(
+                    "Every intercalation needs a cycle of at least one year and an existing month."
+                        .to_string(),
+                ));
+            }
+        }
+        let cycle_years = self.cycle_years();
+        if cycle_years > MAX_CYCLE_YEARS {
+            return Err(ElenathError::Generic(format!(
+                "The intercalations only repeat after {} years, which is more than {}.",
+                cycle_years, MAX_CYCLE_YEARS
+            )));
+        }
+        let years = cycle_years as i64;
+        let year_lengths: Vec<i64> = (0..years)
+            .map(|year| self.days_in_year(self.year_at_epoch + year))
+            .collect();
+        if year_lengths.iter().any(|&days| days <= 0) {
+            return Err(ElenathError::Generic(
+                "Every year needs at least one day.".to_string(),
+            ));
+        }
+        Ok(CalendarCycle {
+            years,
+            days: year_lengths.iter().sum(),
+        })
+    }
+
+    fn days_in_month(&self, year: i64, month: usize) -> i64 {
+        let extra_days: i64 = self
+            .intercalations
+            .iter()
+            .filter(|rule| rule.month == month && year.rem_euclid(rule.every_years as i64) == 0)
+            .map(|rule| rule.extra_days as i64)
+            .sum();
+        (self.months[month].days as i64 + extra_days).max(0)
+    }
+
+    fn days_in_year(&self, year: i64) -> i64 {
+        (0..self.months.len())
+            .map(|month| self.days_in_month(year, month))
+            .sum()
+    }
+
+    /*
+     * After this many years, the pattern of year lengths repeats.
+     */
+    fn cycle_years(&self) -> u64 {
+        self.intercalations
+            .iter()
+            .map(|rule| rule.every_years.max(1) as u64)
+            .fold(1, |lcm: u64, every| {
+                (lcm / gcd(lcm, every)).saturating_mul(every)
+            })
+    }
+
+    pub(crate) fn date(&self, time_since_epoch: Time) -> Option<CalendarDate> {
+        let cycle = self.cycle()?;
+        let days = ((time_since_epoch + self.offset) / self.day_length).value;
+        if !days.is_finite() || days.abs() > i64::MAX as f64 / 2. {
+            return None;
+        }
+        let fraction_of_day = days - days.floor();
+        let mut remaining_days = days.floor() as i64;
+        let weekday = if self.weekdays.is_empty() {
+            None
+        } else {
+            Some(remaining_days.rem_euclid(self.weekdays.len() as i64) as usize)
+        };
+
+        let cycles = remaining_days.div_euclid(cycle.days);
+        remaining_days -= cycles * cycle.days;
+        let mut year = self.year_at_epoch + cycles * cycle.years;
+        while remaining_days >= self.days_in_year(year) {
+            remaining_days -= self.days_in_year(year);
+            year += 1;
+        }
+        let mut month = 0;
+        while remaining_days >= self.days_in_month(year, month) {
+            remaining_days -= self.days_in_month(year, month);
+            month += 1;
+        }
+        Some(CalendarDate {
+            year,
+            month,
+            day: remaining_days as u32 + 1,
+            weekday,
+            fraction_of_day,
+        })
+    }
+
+    pub(crate) fn format_day(&self, time_since_epoch: Time) -> Option<String> {
+        let date = self.date(time_since_epoch)?;
+        let month = self.months.get(date.month)?;
+        let day = format!("{} {} {}", date.day, month.name, date.year);
+        match date.weekday {
+            Some(weekday) => Some(format!("{}, {}", self.weekdays.get(weekday)?, day)),
+            None => Some(day),
+        }
+    }
+
+    pub(crate) fn format_date(&self, time_since_epoch: Time) -> Option<String> {
+        let date = self.date(time_since_epoch)?;
+        let minutes = (date.fraction_of_day * 24. * 60.).floor() as u32 % (24 * 60);
+        Some(format!(
+            "{}, {:02}:{:02}",
+            self.format_day(time_since_epoch)?,
+            minutes / 60,
+            minutes % 60
+        ))
+    }
+
+    /*
+     * The month that contains the given time, starting at midnight of its first day.
+     */
+    pub(crate) fn month_containing(&self, time_since_epoch: Time) -> Option<CalendarMonth> {
+        let date = self.date(time_since_epoch)?;
+        let month = self.months.get(date.month)?;
+        let elapsed_days = (date.day - 1) as f64 + date.fraction_of_day;
+        Some(CalendarMonth {
+            title: format!("{} {}", month.name, date.year),
+            start: time_since_epoch - self.day_length * elapsed_days,
+            days: self.days_in_month(date.year, date.month) as u32,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CalendarMonth {
+    pub(crate) title: String,
+    pub(crate) start: Time,
+    pub(crate) days: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use uom::si::time::{day, hour};
+
+    use super::*;
+
+    fn julian_calendar() -> Calendar {
+        let month = |name: &str, days: u32| Month {
+            name: name.to_string(),
+            days,
+        };
+        Calendar {
+            day_length: Time::new::<day>(1.),
+            months: vec![
+                month("January", 31),
+                month("February", 28),
+                month("March", 31),
+                month("April", 30),
+                month("May", 31),
+                month("June", 30),
+                month("July", 31),
+                month("August", 31),
+                month("September", 30),
+                month("October", 31),
+                month("November", 30),
+                month("December", 31),
+            ],
+            intercalations: vec![Intercalation {
+                every_years: 4,
+                month: 1,
+                extra_days: 1,
+            }],
+            weekdays: ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"]
+                .iter()
+                .map(|d| d.to_string())
+                .collect(),
+            year_at_epoch: 2000,
+            offset: no_offset(),
+            cycle: CycleCache::default(),
+        }
+    }
+
+    #[test]
+    fn dates_follow_months_and_leap_years() {
+        let calendar = julian_calendar();
+        let date = calendar.date(Time::new::<day>(59.5)).unwrap();
+        assert_eq!((date.year, date.month, date.day), (2000, 1, 29));
+        assert!((date.fraction_of_day - 0.5).abs() < 1e-9);
+        let date = calendar.date(Time::new::<day>(366. + 59.)).unwrap();
+        assert_eq!((date.year, date.month, date.day), (2001, 2, 1));
+        let date = calendar.date(Time::new::<day>(-1.)).unwrap();
+        assert_eq!((date.year, date.month, date.day), (1999, 11, 31));
+        let date = calendar.date(Time::new::<day>(4. * 365.25 * 100.)).unwrap();
+        assert_eq!((date.year, date.month, date.day), (2400, 0, 1));
+    }
+
+    #[test]
+    fn formatted_dates_contain_weekdays() {
+        let calendar = julian_calendar();
+        let formatted = calendar
+            .format_date(Time::new::<day>(8.) + Time::new::<hour>(6.5))
+            .unwrap();
+        assert_eq!(formatted, "Tue, 9 January 2000, 06:30");
+    }
+
+    #[test]
+    fn months_start_at_midnight_of_their_first_day() {
+        let calendar = julian_calendar();
+        let month = calendar.month_containing(Time::new::<day>(40.25)).unwrap();
+        assert_eq!(month.title, "February 2000");
+        assert_eq!(month.days, 29);
+        assert!((month.start.get::<day>() - 31.).abs() < 1e-9);
+    }
+
+    #[test]
+    fn simple_calendar_has_leap_days() {
+        let calendar = Calendar::simple(Time::new::<day>(1.), Time::new::<day>(365.25));
+        assert!(calendar.validate().is_ok());
+        let total_days: u32 = calendar.months.iter().map(|m| m.days).sum();
+        assert_eq!(total_days, 365);
+        assert_eq!(calendar.intercalations[0].every_years, 4);
+    }
+
+    #[test]
+    fn validated_calendars_keep_their_cycle() {
+        let calendar = julian_calendar().validated().unwrap();
+        let cycle = calendar.cycle.0.get().copied().flatten().unwrap();
+        assert_eq!(
+            cycle,
+            CalendarCycle {
+                years: 4,
+                days: 4 * 365 + 1,
+            }
+        );
+        assert_eq!(calendar, julian_calendar());
+    }
+
+    #[test]
+    fn edited_clones_of_validated_calendars_forget_the_cycle() {
+        let calendar = julian_calendar().validated().unwrap();
+        let mut edited = calendar.clone();
+        while edited.get_months().len() > 1 {
+            edited.remove_month(1);
+        }
+        assert!(edited.get_intercalations().is_empty());
+        assert_eq!(
+            edited.format_day(Time::new::<day>(-1.)).unwrap(),
+            "Sun, 31 January 1999"
+        );
+        assert_eq!(
+            calendar.format_day(Time::new::<day>(-1.)).unwrap(),
+            "Sun, 31 December 1999"
+        );
+    }
+
+    #[test]
+    fn invalid_calendars_are_rejected() {
+        let mut calendar = julian_calendar();
+        calendar.intercalations[0].month = 12;
+        assert!(calendar.validate().is_err());
+        let mut calendar = julian_calendar();
+        calendar.months.clear();
+        assert!(calendar.validate().is_err());
+    }
+}
@@ -0,0 +1,128 @@
+use astro_coords::cartesian::Cartesian;
+use astro_utils::planets::planet_data::PlanetData;
+use uom::si::{
+    f64::{Length, Mass, Time},
+    length::meter,
+    mass::kilogram,
+};
+
+use crate::model::body_id::BodyId;
+
+use super::{
+    binary_planets::BinaryPlanet, constellations::ConstellationKey, epoch::PlanetTimeOffset,
+    CelestialSystem,
+};
+
+/*
+ * The positions of the central body relative to the barycenter of the system, over the longest orbital period of its planets.
+ */
+pub(crate) struct CentralBodyWobble {
+    pub(crate) positions: Vec<Cartesian>,
+}
+
+/*
+ * Everything the wobble is derived from, so that a wobble can be reused for as long as its key matches.
+ * The star key covers the time and the central body, and the planets are compared by value because they can be changed in many places.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WobbleKey {
+    stars: ConstellationKey,
+    central_body_mass: Option<Mass>,
+    planets: Vec<PlanetData>,
+    planet_ids: Vec<BodyId>,
+    planet_time_offsets: Vec<PlanetTimeOffset>,
+    binary_planets: Vec<BinaryPlanet>,
+}
+
+impl CentralBodyWobble {
+    pub(crate) fn amplitude(&self) -> Length {
+        self.positions
+            .iter()
+            .map(|position| position.length())
+            .fold(Length::new::<meter>(0.), Length::max)
+    }
+}
+
+impl CelestialSystem {
+    /*
+     * The planets are treated as point masses that do not pull on each other, in keeping with the rest of the model.
+     */
+    pub(crate) fn get_central_body_offset_at(&self, time: Time) -> Option<Cartesian> {
+        let central_body_mass = self.central_body.get_mass(time)?.get::<kilogram>();
+        let mut total_mass = central_body_mass;
+        let mut weighted = [0.; 3];
+        for (index, planet) in self.planets.iter().enumerate() {
+            let mass = planet.get_mass().get::<kilogram>();
+            let position = self.get_planet_position(index, time);
+            weighted[0] += mass * position.x.get::<meter>();
+            weighted[1] += mass * position.y.get::<meter>();
+            weighted[2] += mass * position.z.get::<meter>();
+            total_mass += mass;
+        }
+        if total_mass <= 0. {
+            return None;
+        }
+        let component = |weighted: f64| Length::new::<meter>(-weighted / total_mass);
+        Some(Cartesian::new(
+            component(weighted[0]),
+            component(weighted[1]),
+            component(weighted[2]),
+        ))
+    }
+
+    pub(crate) fn central_body_wobble_key(&self) -> WobbleKey {
+        WobbleKey {
+            stars: self.constellation_key(),
+            central_body_mass: self.central_body.get_mass(self.time_since_epoch),
+            planets: self.planets.clone(),
+            planet_ids: self.planet_ids.clone(),
+            planet_time_offsets: self.planet_time_offsets.clone(),
+            binary_planets: self.binary_planets.clone(),
+        }
+    }
+
+    pub(crate) fn get_central_body_wobble(&self, count: usize) -> Option<CentralBodyWobble> {
+        let period = self
+            .get_planets()
+            .iter()
+            .filter_map(|planet| planet.get_derived_data())
+            .map(|derived| derived.get_orbital_period())
+            .filter(|period| period.value.is_finite())
+            .reduce(Time::max)?;
+        let positions = (0..count)
+            .map(|i| {
+                let time = self.time_since_epoch + period * (i as f64 / count as f64);
+                self.get_central_body_offset_at(time)
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(CentralBodyWobble { positions })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_units::length::solar_radius;
+    use astro_utils::real_data::{
+        planets::{earth, jupiter},
+        stars::sun,
+    };
+
+    use super::*;
+
+    #[test]
+    fn jupiter_moves_the_sun_by_about_its_radius() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_planet_data(jupiter().to_planet_data()).unwrap();
+        let wobble = system.get_central_body_wobble(360).unwrap();
+        let amplitude = wobble.amplitude().get::<solar_radius>();
+        assert!(amplitude > 0.95 && amplitude < 1.2, "{}", amplitude);
+    }
+
+    #[test]
+    fn earth_barely_moves_the_sun() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_planet_data(earth().to_planet_data()).unwrap();
+        let wobble = system.get_central_body_wobble(360).unwrap();
+        assert!(wobble.amplitude().get::<solar_radius>() < 0.001);
+    }
+}
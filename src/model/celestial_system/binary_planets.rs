@@ -0,0 +1,168 @@
+use astro_coords::cartesian::Cartesian;
+use serde::{Deserialize, Serialize};
+use uom::si::f64::{Length, Time};
+
+use crate::model::{
+    body_id::BodyId,
+    planet::{calc_binary_pos, calc_pos},
+};
+
+use super::CelestialSystem;
+
+/*
+ * Two planets that orbit their common barycenter, which in turn orbits the central body.
+ * Both planets share the orbital parameters of the barycenter.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct BinaryPlanet {
+    pub(crate) primary: BodyId,
+    pub(crate) secondary: BodyId,
+    pub(crate) separation: Length,
+}
+
+impl BinaryPlanet {
+    pub(crate) fn contains(&self, planet: BodyId) -> bool {
+        self.primary == planet || self.secondary == planet
+    }
+}
+
+impl CelestialSystem {
+    pub(crate) fn get_binary_planets(&self) -> &[BinaryPlanet] {
+        &self.binary_planets
+    }
+
+    pub(crate) fn get_binary_planet(&self, planet: BodyId) -> Option<&BinaryPlanet> {
+        self.binary_planets
+            .iter()
+            .find(|pair| pair.contains(planet))
+    }
+
+    /*
+     * The secondary takes over the orbit of the primary, which becomes the orbit of the barycenter.
+     * Planets can only be part of one pair, so any previous pairs of either planet are dissolved.
+     */
+    pub(crate) fn set_binary_planet(
+        &mut self,
+        primary: BodyId,
+        secondary: BodyId,
+        separation: Length,
+    ) {
+        if primary == secondary || separation.value <= 0. {
+            return;
+        }
+        let (Some(primary_index), Some(secondary_index)) = (
+            self.get_planet_index(primary),
+            self.get_planet_index(secondary),
+        ) else {
+            return;
+        };
+        self.remove_binary_planet(primary);
+        self.remove_binary_planet(secondary);
+        let orbit = self.planets[primary_index].get_orbital_parameters().clone();
+        let secondary_data = &mut self.planets[secondary_index];
+        secondary_data.set_semi_major_axis(orbit.get_semi_major_axis());
+        secondary_data.set_eccentricity(orbit.get_eccentricity());
+        secondary_data.set_inclination(orbit.get_inclination());
+        secondary_data.set_longitude_of_ascending_node(orbit.get_longitude_of_ascending_node());
+        secondary_data.set_argument_of_periapsis(orbit.get_argument_of_periapsis());
+        self.binary_planets.push(BinaryPlanet {
+            primary,
+            secondary,
+            separation,
+        });
+        self.sort_planets_by_semimajor_axis();
+    }
+
+    pub(crate) fn are_binary_partners(&self, index: usize, other_index: usize) -> bool {
+        let (Some(id), Some(other_id)) =
+            (self.get_planet_id(index), self.get_planet_id(other_index))
+        else {
+            return false;
+        };
+        self.get_binary_planet(id)
+            .is_some_and(|pair| pair.contains(other_id) && id != other_id)
+    }
+
+    pub(crate) fn remove_binary_planet(&mut self, planet: BodyId) {
+        self.binary_planets.retain(|pair| !pair.contains(planet));
+    }
+
+    /*
+     * The position of the planet at the given index and system time.
+     * Both members of a binary planet follow the clock of the primary, so that they share one barycenter.
+     */
+    pub(crate) fn get_planet_position(&self, index: usize, time: Time) -> Cartesian {
+        let id = self.get_planet_id(index);
+        let binary = id.and_then(|id| {
+            let pair = self.get_binary_planet(id)?;
+            let primary = self.get_planet_index(pair.primary)?;
+            let secondary = self.get_planet_index(pair.secondary)?;
+            Some((pair, primary, secondary))
+        });
+        match binary {
+            Some((pair, primary, secondary)) => calc_binary_pos(
+                &self.central_body,
+                self.get_planet_time(Some(pair.primary), time),
+                &self.planets[primary],
+                &self.planets[secondary],
+                pair.separation,
+                primary == index,
+            ),
+            None => calc_pos(
+                &self.central_body,
+                self.get_planet_time(id, time),
+                &self.planets[index],
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_coords::traits::*;
+    use astro_utils::real_data::{planets::*, stars::sun};
+    use uom::si::{
+        f64::Length,
+        length::{astronomical_unit, kilometer},
+        time::day,
+    };
+
+    use super::*;
+
+    #[test]
+    fn members_circle_the_barycenter_on_the_shared_orbit() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let earth_id = system.add_planet_data(earth().to_planet_data()).unwrap();
+        let mut second_earth = earth().to_planet_data();
+        second_earth.set_name("Second Earth".to_string());
+        second_earth.set_semi_major_axis(Length::new::<astronomical_unit>(3.));
+        let second_id = system.add_planet_data(second_earth).unwrap();
+        let separation = Length::new::<kilometer>(400_000.);
+        system.set_binary_planet(earth_id, second_id, separation);
+
+        let earth_index = system.get_planet_index(earth_id).unwrap();
+        let second_index = system.get_planet_index(second_id).unwrap();
+        let sma = |index: usize| {
+            system.planets[index]
+                .get_orbital_parameters()
+                .get_semi_major_axis()
+        };
+        assert_eq!(sma(earth_index), sma(second_index));
+
+        for days in [0., 3., 10.] {
+            let time = Time::new::<day>(days);
+            let first = system.get_planet_position(earth_index, time);
+            let second = system.get_planet_position(second_index, time);
+            let distance = (first.clone() + -&second).length().get::<kilometer>();
+            assert!((distance - 400_000.).abs() < 1., "{}", distance);
+            let barycenter = calc_pos(&system.central_body, time, &system.planets[earth_index]);
+            for member in [first, second] {
+                let offset = (member + -&barycenter).length().get::<kilometer>();
+                assert!((offset - 200_000.).abs() < 1., "{}", offset);
+            }
+        }
+
+        system.remove_binary_planet(second_id);
+        assert!(system.get_binary_planets().is_empty());
+    }
+}
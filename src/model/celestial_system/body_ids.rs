@@ -0,0 +1,169 @@
+use astro_utils::{planets::planet_data::PlanetData, stars::data::StarData};
+
+use std::io::ErrorKind;
+
+use crate::model::body_id::BodyId;
+
+use super::{part::PartOfCelestialSystem, CelestialSystem};
+
+impl CelestialSystem {
+    pub(super) fn new_body_id(&mut self) -> BodyId {
+        self.last_body_id = self.last_body_id.next();
+        self.last_body_id
+    }
+
+    /*
+     * Systems saved before bodies had IDs are given IDs when they are loaded.
+     * A file with more planet IDs than planets, or without enough IDs left to hand out, is refused rather than cut to fit.
+     */
+    pub(crate) fn assign_missing_body_ids(&mut self) -> Result<(), std::io::Error> {
+        let invalid = |message: &str| std::io::Error::new(ErrorKind::InvalidData, message);
+        if self.planet_ids.len() > self.planets.len() {
+            return Err(invalid("The file lists more planet IDs than planets."));
+        }
+        let missing_star_ids = self
+            .distant_stars
+            .iter()
+            .filter(|star| star.get_id().is_none())
+            .count();
+        let missing_ids = missing_star_ids + self.planets.len() - self.planet_ids.len();
+        if self
+            .last_body_id
+            .as_u64()
+            .checked_add(missing_ids as u64)
+            .is_none()
+        {
+            return Err(invalid("The file has no body IDs left to hand out."));
+        }
+        for i in 0..self.distant_stars.len() {
+            if self.distant_stars[i].get_id().is_none() {
+                let id = self.new_body_id();
+                self.distant_stars[i].set_id(id);
+            }
+        }
+        while self.planet_ids.len() < self.planets.len() {
+            let id = self.new_body_id();
+            self.planet_ids.push(id);
+        }
+        Ok(())
+    }
+
+    pub(crate) fn get_star_index(&self, id: BodyId) -> Option<usize> {
+        self.distant_stars
+            .iter()
+            .position(|s| s.get_id() == Some(id))
+    }
+
+    pub(crate) fn get_planet_index(&self, id: BodyId) -> Option<usize> {
+        self.planet_ids
+            .iter()
+            .position(|planet_id| *planet_id == id)
+    }
+
+    pub(crate) fn get_planet_id(&self, index: usize) -> Option<BodyId> {
+        self.planet_ids.get(index).copied()
+    }
+
+    /*
+     * Names need not be unique, so this may find several planets or none.
+     */
+    pub(crate) fn get_planet_ids_by_name(&self, name: &str) -> Vec<BodyId> {
+        self.planets
+            .iter()
+            .zip(self.planet_ids.iter())
+            .filter(|(planet, _)| planet.get_name() == name)
+            .map(|(_, id)| *id)
+            .collect()
+    }
+
+    /*
+     * No ID refers to the central body.
+     */
+    pub(crate) fn get_star_data_by_id(&self, id: Option<BodyId>) -> Option<&StarData> {
+        match id {
+            Some(id) => self.get_star_data(Some(self.get_star_index(id)?)),
+            None => self.get_star_data(None),
+        }
+    }
+
+    pub(crate) fn get_planet_data_by_id(&self, id: BodyId) -> Option<&PlanetData> {
+        self.get_planet_data(self.get_planet_index(id)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::{
+        planets::{earth, jupiter, mercury},
+        stars::{all::get_many_stars, sun},
+    };
+    use std::collections::HashSet;
+    use uom::si::{f64::Length, length::light_year};
+
+    use crate::model::star_provenance::StarOrigin;
+
+    use super::*;
+
+    #[test]
+    fn star_ids_survive_resorting() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let stars = get_many_stars().iter().map(|s| s.to_star_data()).collect();
+        system.add_stars_from_data(stars, StarOrigin::Hardcoded);
+        let id = system.distant_stars[17].get_id().unwrap();
+        let mut star = system.get_star_data(Some(17)).unwrap().clone();
+        star.set_distance_at_epoch(Length::new::<light_year>(1.));
+        let name = star.get_name().clone();
+        system.overwrite_star_data(Some(17), star);
+        assert_ne!(system.get_star_index(id), Some(17));
+        assert_eq!(
+            system.get_star_data_by_id(Some(id)).unwrap().get_name(),
+            &name
+        );
+    }
+
+    #[test]
+    fn planet_ids_survive_resorting() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_planet_data(jupiter().to_planet_data()).unwrap();
+        let jupiter_id = system.get_planet_id(0).unwrap();
+        system.add_planet_data(mercury().to_planet_data()).unwrap();
+        system.add_planet_data(earth().to_planet_data()).unwrap();
+        assert_eq!(system.get_planet_index(jupiter_id), Some(2));
+        assert_eq!(
+            system.get_planet_data_by_id(jupiter_id).unwrap().get_name(),
+            "Jupiter"
+        );
+    }
+
+    #[test]
+    fn inconsistent_planet_ids_are_refused() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_planet_data(earth().to_planet_data()).unwrap();
+        system.planet_ids.push(BodyId::default().next());
+        assert!(system.assign_missing_body_ids().is_err());
+        assert_eq!(system.planet_ids.len(), 2);
+    }
+
+    #[test]
+    fn running_out_of_ids_is_refused() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_planet_data(earth().to_planet_data()).unwrap();
+        system.planet_ids.clear();
+        system.last_body_id = serde_json::from_str(&u64::MAX.to_string()).unwrap();
+        assert!(system.assign_missing_body_ids().is_err());
+        assert!(system.planet_ids.is_empty());
+    }
+
+    #[test]
+    fn ids_are_unique() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let stars = get_many_stars().iter().map(|s| s.to_star_data()).collect();
+        system.add_stars_from_data(stars, StarOrigin::Hardcoded);
+        let ids: HashSet<BodyId> = system
+            .distant_stars
+            .iter()
+            .filter_map(|s| s.get_id())
+            .collect();
+        assert_eq!(ids.len(), system.distant_stars.len());
+    }
+}
@@ -0,0 +1,78 @@
+use serde::{Deserialize, Serialize};
+
+use crate::model::{body_id::BodyId, body_notes::BodyNotes};
+
+use super::CelestialSystem;
+
+/*
+ * The notes of one body. Planets and stars share the ID space, and no ID stands for the central body.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(super) struct NotesOfBody {
+    body: Option<BodyId>,
+    notes: BodyNotes,
+}
+
+impl CelestialSystem {
+    pub(crate) fn find_body_notes(&self, body: Option<BodyId>) -> Option<&BodyNotes> {
+        self.body_notes
+            .iter()
+            .find(|entry| entry.body == body)
+            .map(|entry| &entry.notes)
+    }
+
+    pub(crate) fn get_body_notes(&self, body: Option<BodyId>) -> BodyNotes {
+        self.find_body_notes(body).cloned().unwrap_or_default()
+    }
+
+    pub(crate) fn set_body_notes(&mut self, body: Option<BodyId>, notes: BodyNotes) {
+        self.body_notes.retain(|entry| entry.body != body);
+        if !notes.is_empty() {
+            self.body_notes.push(NotesOfBody { body, notes });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::{planets::earth, stars::sun};
+
+    use crate::model::{body_notes::BodyNotes, celestial_system::CelestialSystem};
+
+    fn lore() -> BodyNotes {
+        BodyNotes {
+            notes: "Cradle of the elves".to_string(),
+            tags: vec!["habitable".to_string()],
+        }
+    }
+
+    #[test]
+    fn tags_are_parsed_from_comma_separated_list() {
+        let mut notes = BodyNotes::default();
+        notes.set_tags_from_string(" homeworld,, ancient ruins ,");
+        assert_eq!(notes.tags, vec!["homeworld", "ancient ruins"]);
+        assert_eq!(notes.tags_string(), "homeworld, ancient ruins");
+    }
+
+    #[test]
+    fn empty_notes_are_removed() {
+        let mut system = CelestialSystem::empty();
+        system.set_body_notes(None, lore());
+        assert_eq!(system.get_body_notes(None), lore());
+        system.set_body_notes(None, BodyNotes::default());
+        assert!(system.find_body_notes(None).is_none());
+    }
+
+    #[test]
+    fn notes_stay_with_a_renamed_planet() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let id = system.add_planet_data(earth().to_planet_data()).unwrap();
+        system.set_body_notes(Some(id), lore());
+
+        let mut renamed = earth().to_planet_data();
+        renamed.set_name("Arda".to_string());
+        system.overwrite_planet_data(0, renamed);
+        assert_eq!(system.get_body_notes(Some(id)), lore());
+        assert!(system.find_body_notes(None).is_none());
+    }
+}
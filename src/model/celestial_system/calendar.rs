@@ -0,0 +1,23 @@
+use uom::si::f64::Time;
+
+use crate::{error::ElenathError, model::calendar::Calendar};
+
+use super::CelestialSystem;
+
+impl CelestialSystem {
+    pub(crate) fn get_calendar(&self) -> Option<&Calendar> {
+        self.calendar.as_ref()
+    }
+
+    pub(crate) fn set_calendar(&mut self, calendar: Option<Calendar>) -> Result<(), ElenathError> {
+        self.calendar = calendar.map(Calendar::validated).transpose()?;
+        Ok(())
+    }
+
+    /*
+     * The date in the fictional calendar, if the system has one.
+     */
+    pub(crate) fn format_calendar_date(&self, time_since_epoch: Time) -> Option<String> {
+        self.calendar.as_ref()?.format_date(time_since_epoch)
+    }
+}
@@ -0,0 +1,178 @@
+use astro_coords::direction::Direction;
+use astro_utils::{
+    color::srgb::sRGBColor,
+    planets::{
+        derived_data::DerivedPlanetData, orbit_parameters::OrbitParameters,
+        physical_parameters::PlanetPhysicalParameters, planet_data::PlanetData,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use uom::si::{
+    f64::{Length, Mass, Time},
+    time::hour,
+};
+
+use crate::{error::ElenathError, model::body_id::BodyId};
+
+use super::CelestialSystem;
+
+const SMALL_BODY_ALBEDO: f64 = 0.1;
+const SMALL_BODY_ROTATION_PERIOD_HOURS: f64 = 10.;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CoOrbitalPlacement {
+    LeadingTrojan,
+    TrailingTrojan,
+    Horseshoe,
+}
+
+impl CoOrbitalPlacement {
+    pub(crate) const ALL: [CoOrbitalPlacement; 3] = [
+        CoOrbitalPlacement::LeadingTrojan,
+        CoOrbitalPlacement::TrailingTrojan,
+        CoOrbitalPlacement::Horseshoe,
+    ];
+
+    /*
+     * How far along the orbit of the host the body starts, as a fraction of the orbital period.
+     */
+    fn phase(&self) -> f64 {
+        match self {
+            CoOrbitalPlacement::LeadingTrojan => 1. / 6.,
+            CoOrbitalPlacement::TrailingTrojan => -1. / 6.,
+            CoOrbitalPlacement::Horseshoe => 0.5,
+        }
+    }
+}
+
+impl Display for CoOrbitalPlacement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CoOrbitalPlacement::LeadingTrojan => write!(f, "L4 (leading)"),
+            CoOrbitalPlacement::TrailingTrojan => write!(f, "L5 (trailing)"),
+            CoOrbitalPlacement::Horseshoe => write!(f, "Horseshoe"),
+        }
+    }
+}
+
+impl CelestialSystem {
+    /*
+     * Trojans share the orbit of their host.
+     * A horseshoe orbit is slightly wider, by half the width of the horseshoe region of about sqrt(8/3 μ),
+     * with μ the mass ratio of host and central body.
+     */
+    pub(crate) fn get_co_orbital_parameters(
+        &self,
+        host: &PlanetData,
+        placement: CoOrbitalPlacement,
+    ) -> OrbitParameters {
+        let orbit = host.get_orbital_parameters();
+        let semi_major_axis = match (placement, self.central_body.get_mass_at_epoch()) {
+            (CoOrbitalPlacement::Horseshoe, Some(central_body_mass)) => {
+                let mass_ratio = (host.get_mass() / central_body_mass).value;
+                orbit.get_semi_major_axis() * (1. + 0.5 * (8. / 3. * mass_ratio).sqrt())
+            }
+            _ => orbit.get_semi_major_axis(),
+        };
+        OrbitParameters::new(
+            semi_major_axis,
+            orbit.get_eccentricity(),
+            orbit.get_inclination(),
+            orbit.get_longitude_of_ascending_node(),
+            orbit.get_argument_of_periapsis(),
+        )
+    }
+
+    /*
+     * Adds a small body on the orbit of the host, and lets it run ahead of the host by the phase of the placement.
+     * The bodies do not attract each other, so a horseshoe orbit only starts out opposite of the host and then drifts.
+     */
+    pub(crate) fn add_co_orbital_body(
+        &mut self,
+        host: BodyId,
+        placement: CoOrbitalPlacement,
+        name: String,
+        mass: Mass,
+        radius: Length,
+    ) -> Result<BodyId, ElenathError> {
+        let host_data = self
+            .get_planet_data_by_id(host)
+            .ok_or(ElenathError::BodyNotFound)?
+            .clone();
+        let physical_parameters = PlanetPhysicalParameters::new(
+            mass,
+            radius,
+            SMALL_BODY_ALBEDO,
+            sRGBColor::from_sRGB(0.5, 0.5, 0.5),
+            Time::new::<hour>(SMALL_BODY_ROTATION_PERIOD_HOURS),
+            Direction::Z,
+        );
+        let orbit = self.get_co_orbital_parameters(&host_data, placement);
+        let body = PlanetData::new(name, physical_parameters, orbit);
+        let period = DerivedPlanetData::new(&body, &self.central_body, None)?.get_orbital_period();
+        let host_offset = self.get_planet_time(Some(host), Time::new::<hour>(0.));
+        let id = self.add_planet_data(body)?;
+        self.set_planet_time_offset(id, host_offset + period * placement.phase());
+        Ok(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_coords::traits::*;
+    use astro_utils::real_data::{planets::earth, stars::sun};
+    use uom::si::{angle::degree, length::kilometer, mass::kilogram, time::day};
+
+    use super::*;
+
+    fn angle_to_host(placement: CoOrbitalPlacement, days: f64) -> f64 {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let earth_id = system.add_planet_data(earth().to_planet_data()).unwrap();
+        let body_id = system
+            .add_co_orbital_body(
+                earth_id,
+                placement,
+                "Body".to_string(),
+                Mass::new::<kilogram>(1e15),
+                Length::new::<kilometer>(1.),
+            )
+            .unwrap();
+        let time = Time::new::<day>(days);
+        let position = |id| {
+            let index = system.get_planet_index(id).unwrap();
+            system
+                .get_planet_position(index, time)
+                .to_direction()
+                .unwrap()
+        };
+        position(earth_id)
+            .angle_to(&position(body_id))
+            .get::<degree>()
+    }
+
+    #[test]
+    fn trojans_stay_sixty_degrees_from_the_host() {
+        for placement in [
+            CoOrbitalPlacement::LeadingTrojan,
+            CoOrbitalPlacement::TrailingTrojan,
+        ] {
+            for days in [0., 100., 250.] {
+                let angle = angle_to_host(placement, days);
+                assert!(
+                    (angle - 60.).abs() < 2.,
+                    "{} at {} days: {}",
+                    placement,
+                    days,
+                    angle
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn horseshoe_body_starts_opposite_of_the_host() {
+        let angle = angle_to_host(CoOrbitalPlacement::Horseshoe, 0.);
+        assert!(angle > 170., "{}", angle);
+    }
+}
@@ -0,0 +1,130 @@
+use astro_utils::{planets::planet_data::PlanetData, stars::data::StarData};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use uom::si::f64::{Angle, Length, Mass, Time, Velocity};
+
+use crate::model::{
+    appearance_override::AppearanceOverride,
+    body_id::BodyId,
+    body_notes::BodyNotes,
+    calendar::Calendar,
+    gaia_export::GaiaRelease,
+    galactic_motion::GalacticMotion,
+    migration::MigrationSchedule,
+    observer_location::ObserverLocation,
+    star::{StarDataType, StarMergeMode},
+    star_catalog_filter::StarCatalogFilter,
+    star_formation::StarFormingRegion,
+    star_generation::GeneratedStars,
+    star_jitter::StarJitter,
+};
+
+use super::{
+    co_orbitals::CoOrbitalPlacement,
+    metadata::{today, SystemMetadata},
+    CelestialSystem,
+};
+
+/*
+ * A change to the system, with everything needed to apply it again.
+ * Commands that draw random numbers keep what was drawn, and imports keep the path of the file, so that replaying them does the same.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) enum SystemCommand {
+    NewPlanet(PlanetData, BodyNotes, Option<Time>, Angle),
+    EditPlanet(BodyId, PlanetData, BodyNotes, Option<Time>, Angle),
+    NewStar(
+        StarData,
+        BodyNotes,
+        Option<Velocity>,
+        Option<AppearanceOverride>,
+    ),
+    EditStar(
+        Option<BodyId>,
+        StarData,
+        BodyNotes,
+        Option<Velocity>,
+        Option<AppearanceOverride>,
+        Option<usize>,
+    ),
+    EditCentralBody(StarData, BodyNotes, SystemMetadata),
+    EditMetadata(SystemMetadata),
+    RedefineEpoch(String),
+    AddObserverLocation(BodyId, ObserverLocation),
+    SetGalacticMotion(GalacticMotion),
+    SetStarFormingRegions(Vec<StarFormingRegion>),
+    SetMigrationSchedule(MigrationSchedule),
+    SetBinaryPlanet(BodyId, BodyId, Length),
+    SeparateBinaryPlanet(BodyId),
+    AddCoOrbitalBody(BodyId, CoOrbitalPlacement, String, Mass, Length),
+    SetCalendar(Option<Calendar>),
+    RandomizePlanets(Vec<PlanetData>),
+    LoadRealPlanets,
+    RandomizeStars(GeneratedStars, StarMergeMode),
+    LoadStars(StarDataType, StarMergeMode, StarCatalogFilter),
+    LoadGaiaExport(PathBuf, GaiaRelease, bool, StarMergeMode, StarCatalogFilter),
+    RemoveDuplicateStars,
+    TrimFaintStars(f64),
+    NameStars(u64),
+    JitterStars(StarJitter),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct LoggedCommand {
+    pub(crate) date: String,
+    pub(crate) command: SystemCommand,
+}
+
+pub(crate) fn write_command_log(
+    commands: &[LoggedCommand],
+    path: PathBuf,
+) -> Result<(), std::io::Error> {
+    let content = serde_json::to_string_pretty(commands)?;
+    std::fs::write(path, content)
+}
+
+pub(crate) fn read_command_log(path: PathBuf) -> Result<Vec<LoggedCommand>, std::io::Error> {
+    let content = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&content)?)
+}
+
+impl CelestialSystem {
+    pub(crate) fn get_command_log(&self) -> &[LoggedCommand] {
+        &self.command_log
+    }
+
+    pub(crate) fn log_command(&mut self, command: SystemCommand) {
+        self.command_log.push(LoggedCommand {
+            date: today(),
+            command,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::{planets::earth, stars::sun};
+    use uom::si::angle::degree;
+
+    use super::*;
+
+    #[test]
+    fn commands_survive_a_round_trip() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.log_command(SystemCommand::NewPlanet(
+            earth().to_planet_data(),
+            BodyNotes::default(),
+            None,
+            Angle::new::<degree>(0.),
+        ));
+        system.log_command(SystemCommand::LoadStars(
+            StarDataType::Hardcoded,
+            StarMergeMode::Append,
+            StarCatalogFilter::default(),
+        ));
+        let json = serde_json::to_string(system.get_command_log()).unwrap();
+        let commands: Vec<LoggedCommand> = serde_json::from_str(&json).unwrap();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(serde_json::to_string(&commands).unwrap(), json);
+    }
+}
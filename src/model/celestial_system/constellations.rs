@@ -2,21 +2,126 @@ use astro_utils::stars::{
     constellation::{collect_constellations, Constellation},
     data::StarData,
 };
+use uom::si::f64::Time;
 
 use super::CelestialSystem;
 
+/*
+ * What a set of constellations was derived for.
+ * The revision counts every change to the stars, which includes redefining the epoch and changing the galactic motion.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct ConstellationKey {
+    pub(crate) time_since_epoch: Time,
+    revision: u64,
+}
+
+pub(crate) fn derive_constellations(
+    stars: &[StarData],
+    time_since_epoch: Time,
+) -> Vec<Constellation> {
+    collect_constellations(stars, time_since_epoch)
+}
+
 impl CelestialSystem {
+    /*
+     * Derives the constellations right away, and makes those still being derived elsewhere outdated.
+     */
     pub(super) fn update_constellations(&mut self) {
-        let stars: Vec<StarData> = self
-            .get_stars()
-            .iter()
-            .filter_map(|s| s.get_data())
-            .cloned()
-            .collect();
-        self.constellations = collect_constellations(&stars[..], self.time_since_epoch);
+        self.star_revision += 1;
+        let (stars, key) = self.constellation_input();
+        self.constellations = derive_constellations(&stars, key.time_since_epoch);
+        self.constellations_key = Some(key);
+    }
+
+    pub(crate) fn constellation_key(&self) -> ConstellationKey {
+        ConstellationKey {
+            time_since_epoch: self.time_since_epoch,
+            revision: self.star_revision,
+        }
+    }
+
+    pub(crate) fn constellations_are_current(&self) -> bool {
+        self.constellations_key == Some(self.constellation_key())
+    }
+
+    /*
+     * A snapshot of everything needed to derive the constellations, so that they can be derived away from the system, e.g. in a background task.
+     */
+    pub(crate) fn constellation_input(&self) -> (Vec<StarData>, ConstellationKey) {
+        let mut stars = vec![self.central_body.clone()];
+        stars.extend(
+            self.distant_stars
+                .iter()
+                .filter_map(|s| s.data_at(self.time_since_epoch, &self.galactic_motion)),
+        );
+        (stars, self.constellation_key())
+    }
+
+    /*
+     * Constellations derived for another time or for stars that have changed since are outdated and get discarded.
+     * Returns whether the constellations were applied.
+     */
+    pub(crate) fn apply_constellations(
+        &mut self,
+        constellations: Vec<Constellation>,
+        key: ConstellationKey,
+    ) -> bool {
+        if key != self.constellation_key() {
+            return false;
+        }
+        self.constellations = constellations;
+        self.constellations_key = Some(key);
+        true
     }
 
     pub(crate) fn get_constellations(&self) -> &Vec<Constellation> {
         &self.constellations
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::stars::{all::get_many_stars, sun};
+    use uom::si::time::year;
+
+    use crate::model::star_provenance::StarOrigin;
+
+    use super::*;
+
+    #[test]
+    fn outdated_constellations_are_discarded() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let stars = get_many_stars().iter().map(|s| s.to_star_data()).collect();
+        system.add_stars_from_data(stars, StarOrigin::Hardcoded);
+        let (stars, old_key) = system.constellation_input();
+        let constellations = derive_constellations(&stars, old_key.time_since_epoch);
+
+        system.set_time_since_epoch(Time::new::<year>(1000.));
+        assert!(!system.constellations_are_current());
+        assert!(!system.apply_constellations(vec![], old_key));
+        assert_eq!(system.get_constellations().len(), constellations.len());
+
+        let (stars, new_key) = system.constellation_input();
+        let new_constellations = derive_constellations(&stars, new_key.time_since_epoch);
+        assert!(system.apply_constellations(new_constellations.clone(), new_key));
+        assert_eq!(system.get_constellations().len(), new_constellations.len());
+        assert!(system.constellations_are_current());
+    }
+
+    #[test]
+    fn constellations_of_the_old_epoch_are_discarded() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let stars = get_many_stars().iter().map(|s| s.to_star_data()).collect();
+        system.add_stars_from_data(stars, StarOrigin::Hardcoded);
+        system.set_time_since_epoch(Time::new::<year>(1000.));
+        system.set_time_since_epoch(Time::new::<year>(0.));
+        let (_, key_before) = system.constellation_input();
+
+        system.set_time_since_epoch(Time::new::<year>(1000.));
+        system.redefine_epoch();
+        assert_eq!(system.get_time_since_epoch(), key_before.time_since_epoch);
+        assert!(!system.apply_constellations(vec![], key_before));
+        assert!(system.constellations_are_current());
+    }
+}
@@ -0,0 +1,120 @@
+use astro_coords::{direction::Direction, spherical::Spherical, traits::*};
+use std::f64::consts::PI;
+use uom::si::{angle::radian, f64::Angle};
+
+use super::CelestialSystem;
+
+/*
+ * Star counts on a coarse grid of the sky.
+ * Latitude bins are equally spaced in the sine of the latitude, so that all cells cover the same solid angle and counts are directly proportional to densities.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StarDensityMap {
+    pub(crate) longitude_bins: usize,
+    pub(crate) latitude_bins: usize,
+    counts: Vec<usize>,
+}
+
+impl StarDensityMap {
+    pub(crate) fn new(
+        directions: &[Direction],
+        longitude_bins: usize,
+        latitude_bins: usize,
+    ) -> Self {
+        let mut counts = vec![0; longitude_bins * latitude_bins];
+        for direction in directions {
+            let longitude = direction.y().atan2(direction.x()).rem_euclid(2. * PI);
+            let sin_latitude = direction.z().clamp(-1., 1.);
+            let i =
+                ((longitude / (2. * PI) * longitude_bins as f64) as usize).min(longitude_bins - 1);
+            let j =
+                (((sin_latitude + 1.) / 2. * latitude_bins as f64) as usize).min(latitude_bins - 1);
+            counts[j * longitude_bins + i] += 1;
+        }
+        StarDensityMap {
+            longitude_bins,
+            latitude_bins,
+            counts,
+        }
+    }
+
+    pub(crate) fn count(&self, longitude_index: usize, latitude_index: usize) -> usize {
+        self.counts[latitude_index * self.longitude_bins + longitude_index]
+    }
+
+    pub(crate) fn max_count(&self) -> usize {
+        self.counts.iter().cloned().max().unwrap_or(0)
+    }
+
+    /*
+     * The corners of a cell, in counter-clockwise order starting at the lowest longitude and latitude.
+     */
+    pub(crate) fn cell_corners(
+        &self,
+        longitude_index: usize,
+        latitude_index: usize,
+    ) -> [Spherical; 4] {
+        let longitude =
+            |i: usize| Angle::new::<radian>(2. * PI * i as f64 / self.longitude_bins as f64);
+        let latitude = |j: usize| {
+            let sin_latitude = 2. * j as f64 / self.latitude_bins as f64 - 1.;
+            Angle::new::<radian>(sin_latitude.asin())
+        };
+        [
+            Spherical::new(longitude(longitude_index), latitude(latitude_index)),
+            Spherical::new(longitude(longitude_index + 1), latitude(latitude_index)),
+            Spherical::new(longitude(longitude_index + 1), latitude(latitude_index + 1)),
+            Spherical::new(longitude(longitude_index), latitude(latitude_index + 1)),
+        ]
+    }
+}
+
+impl CelestialSystem {
+    pub(crate) fn get_star_density_map(
+        &self,
+        longitude_bins: usize,
+        latitude_bins: usize,
+    ) -> StarDensityMap {
+        let directions: Vec<Direction> = self
+            .get_distant_star_appearances()
+            .iter()
+            .map(|star| star.get_pos().to_direction())
+            .collect();
+        StarDensityMap::new(&directions, longitude_bins, latitude_bins)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_direction_is_counted_once() {
+        let directions = vec![
+            Direction::X,
+            Direction::Y,
+            Direction::Z,
+            -&Direction::X,
+            -&Direction::Y,
+            -&Direction::Z,
+        ];
+        let map = StarDensityMap::new(&directions, 8, 4);
+        let total: usize = (0..8)
+            .flat_map(|i| (0..4).map(move |j| (i, j)))
+            .map(|(i, j)| map.count(i, j))
+            .sum();
+        assert_eq!(total, directions.len());
+        assert_eq!(map.count(0, 3), 1);
+        assert_eq!(map.count(0, 0), 1);
+    }
+
+    #[test]
+    fn cells_span_the_whole_sky() {
+        let map = StarDensityMap::new(&[], 8, 4);
+        let first = map.cell_corners(0, 0);
+        let last = map.cell_corners(7, 3);
+        assert!((first[0].latitude.get::<radian>() + PI / 2.).abs() < 1e-10);
+        assert!((last[2].latitude.get::<radian>() - PI / 2.).abs() < 1e-10);
+        assert!((last[2].longitude.get::<radian>() - 2. * PI).abs() < 1e-10);
+    }
+}
@@ -0,0 +1,190 @@
+use astro_utils::planets::derived_data::DerivedPlanetData;
+use serde::{Deserialize, Serialize};
+use uom::si::{f64::Time, time::year};
+
+use crate::model::body_id::BodyId;
+
+use super::CelestialSystem;
+
+/*
+ * Further away from the epoch, star evolution and orbit propagation run out of precision and yield NaNs.
+ * This is also about the age of the universe, so no meaningful time is lost.
+ */
+pub(crate) const MAX_TIME_SINCE_EPOCH_IN_YEARS: f64 = 1e10;
+
+/*
+ * Limits the time to the supported range around the epoch. An undefined time falls back to the epoch itself.
+ */
+pub(crate) fn clamp_time_since_epoch(time: Time) -> Time {
+    let max = Time::new::<year>(MAX_TIME_SINCE_EPOCH_IN_YEARS);
+    if time.value.is_nan() {
+        Time::new::<year>(0.)
+    } else if time > max {
+        max
+    } else if time < -max {
+        -max
+    } else {
+        time
+    }
+}
+
+/*
+ * Orbits do not have an anomaly at epoch, so re-epoching cannot rewrite where a planet is on its orbit.
+ * Instead the planet keeps running this far ahead of the system time, reduced to less than one orbital period.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub(super) struct PlanetTimeOffset {
+    planet: BodyId,
+    offset: Time,
+}
+
+impl CelestialSystem {
+    /*
+     * Makes the current time the new epoch. Everything that is defined at the epoch is rewritten,
+     * so that the system looks the same as before, only with a time since epoch of zero.
+     */
+    pub(crate) fn redefine_epoch(&mut self) {
+        let shift = self.time_since_epoch;
+        if shift.value == 0. {
+            return;
+        }
+
+        if let Some(age) = self.central_body.get_age_at_epoch() {
+            self.central_body.set_age_at_epoch(Some(age + shift));
+        }
+        let galactic_motion = self.galactic_motion;
+        for star in &mut self.distant_stars {
+            star.shift_epoch(shift, &galactic_motion);
+        }
+        for region in &mut self.star_forming_regions {
+            region.onset -= shift;
+        }
+        self.migration_schedules = self
+            .migration_schedules
+            .iter()
+            .map(|schedule| schedule.shifted(shift))
+            .collect();
+        self.shift_planet_time_offsets(shift);
+        self.shift_rotation_axes(shift);
+        self.shift_rotation_angles(shift);
+        if let Some(calendar) = &mut self.calendar {
+            calendar.offset += shift;
+        }
+
+        self.time_since_epoch = Time::new::<year>(0.);
+        for star in &mut self.distant_stars {
+            star.recalculate_appearance(self.time_since_epoch, &galactic_motion);
+        }
+        self.process_stars();
+    }
+
+    /*
+     * The time at which the orbit of a planet is evaluated.
+     */
+    pub(crate) fn get_planet_time(&self, planet: Option<BodyId>, time: Time) -> Time {
+        let offset = self
+            .planet_time_offsets
+            .iter()
+            .find(|offset| Some(offset.planet) == planet);
+        match offset {
+            Some(offset) => time + offset.offset,
+            None => time,
+        }
+    }
+
+    pub(super) fn set_planet_time_offset(&mut self, planet: BodyId, offset: Time) {
+        self.planet_time_offsets
+            .retain(|time_offset| time_offset.planet != planet);
+        self.planet_time_offsets
+            .push(PlanetTimeOffset { planet, offset });
+    }
+
+    fn shift_planet_time_offsets(&mut self, shift: Time) {
+        let mut offsets = Vec::new();
+        for (planet, id) in self.planets.iter().zip(self.planet_ids.iter()) {
+            let offset = self.get_planet_time(Some(*id), shift);
+            let period = DerivedPlanetData::new(planet, &self.central_body, None)
+                .ok()
+                .map(|derived| derived.get_orbital_period());
+            let offset = match period {
+                Some(period) if period.value > 0. => {
+                    period * (offset / period).value.rem_euclid(1.)
+                }
+                _ => offset,
+            };
+            offsets.push(PlanetTimeOffset {
+                planet: *id,
+                offset,
+            });
+        }
+        self.planet_time_offsets = offsets;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::{
+        planets::*,
+        stars::{all::get_many_stars, sun},
+    };
+    use uom::si::{length::astronomical_unit, time::year};
+
+    use crate::model::star_provenance::StarOrigin;
+
+    use super::*;
+
+    #[test]
+    fn absurd_times_are_clamped() {
+        let max = Time::new::<year>(MAX_TIME_SINCE_EPOCH_IN_YEARS);
+        let reasonable = Time::new::<year>(-1234.5);
+        assert_eq!(clamp_time_since_epoch(reasonable), reasonable);
+        assert_eq!(clamp_time_since_epoch(max * 1e300 * 1e300), max);
+        assert_eq!(clamp_time_since_epoch(-max * 2.), -max);
+        assert_eq!(
+            clamp_time_since_epoch(Time::new::<year>(f64::NAN)),
+            Time::new::<year>(0.)
+        );
+
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_planet_data(earth().to_planet_data()).unwrap();
+        system.set_time_since_epoch(Time::new::<year>(f64::INFINITY));
+        assert_eq!(system.get_time_since_epoch(), max);
+        for planet in system.get_planets() {
+            assert!(planet.get_position().length().value.is_finite());
+        }
+    }
+
+    #[test]
+    fn redefining_the_epoch_keeps_the_system_in_place() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_planet_data(earth().to_planet_data()).unwrap();
+        system.add_planet_data(jupiter().to_planet_data()).unwrap();
+        let stars = get_many_stars().iter().map(|s| s.to_star_data()).collect();
+        system.add_stars_from_data(stars, StarOrigin::Hardcoded);
+        system.set_time_since_epoch(Time::new::<year>(1234.5));
+
+        let planet_positions: Vec<_> = system
+            .get_planets()
+            .iter()
+            .map(|planet| planet.get_position().clone())
+            .collect();
+        let star_names: Vec<String> = system
+            .get_distant_star_appearances()
+            .iter()
+            .map(|star| star.get_name().clone())
+            .collect();
+
+        system.redefine_epoch();
+        assert_eq!(system.get_time_since_epoch(), Time::new::<year>(0.));
+        for (planet, before) in system.get_planets().iter().zip(planet_positions.iter()) {
+            let offset = (planet.get_position().clone() + -before).length();
+            assert!(offset.get::<astronomical_unit>() < 1e-6);
+        }
+        let names_after: Vec<String> = system
+            .get_distant_star_appearances()
+            .iter()
+            .map(|star| star.get_name().clone())
+            .collect();
+        assert_eq!(star_names, names_after);
+    }
+}
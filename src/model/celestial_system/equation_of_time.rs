@@ -0,0 +1,110 @@
+use astro_coords::traits::*;
+use std::{cmp::Ordering, f64::consts::PI};
+use uom::si::f64::Time;
+
+use crate::model::{
+    local_time::right_ascension,
+    planet::{calc_pos, Planet},
+};
+
+use super::{part::PartOfCelestialSystem, CelestialSystem};
+
+/*
+ * The difference between apparent and mean solar time over one orbit of a planet.
+ * Positive values mean that a sundial is ahead of a clock.
+ */
+#[derive(Debug, Clone)]
+pub(crate) struct EquationOfTime {
+    pub(crate) amplitude: Time,
+    pub(crate) samples: Vec<(Time, Time)>,
+}
+
+impl CelestialSystem {
+    /*
+     * Samples one orbit of the planet, starting at the current time.
+     * The mean sun moves uniformly around the rotation axis, while the true central body is slowed down and sped up
+     * by the eccentricity of the orbit, and projected onto the equator at an angle given by the axial tilt.
+     */
+    pub(crate) fn get_equation_of_time(
+        &self,
+        planet: &Planet,
+        sample_count: usize,
+    ) -> Option<EquationOfTime> {
+        let data = planet.get_data();
+        let rotation_period = data.get_sideral_rotation_period();
+        let orbital_period = planet.get_derived_data()?.get_orbital_period();
+        if sample_count < 2 || !rotation_period.value.is_finite() || rotation_period.value == 0. {
+            return None;
+        }
+
+        let offsets: Vec<Time> = (0..sample_count)
+            .map(|i| orbital_period * (i as f64 / (sample_count - 1) as f64))
+            .collect();
+        let mut true_angles = Vec::with_capacity(sample_count);
+        for offset in &offsets {
+            let time = self.get_planet_time(planet.get_id(), self.time_since_epoch + *offset);
+            let pos = calc_pos(&self.central_body, time, data);
+            let to_central_body = (-&pos).to_direction().ok()?;
+            let angle =
+                right_ascension(data.get_rotation_axis(), &to_central_body, rotation_period)?;
+            true_angles.push(angle);
+        }
+        unwrap_angles(&mut true_angles);
+
+        let first = true_angles[0];
+        let total = true_angles[sample_count - 1] - first;
+        let mut differences: Vec<f64> = true_angles
+            .iter()
+            .enumerate()
+            .map(|(i, angle)| first + total * (i as f64 / (sample_count - 1) as f64) - angle)
+            .collect();
+        let mean = differences.iter().sum::<f64>() / sample_count as f64;
+        for difference in &mut differences {
+            *difference -= mean;
+        }
+
+        let samples: Vec<(Time, Time)> = offsets
+            .into_iter()
+            .zip(differences)
+            .map(|(offset, difference)| (offset, rotation_period.abs() * (difference / (2. * PI))))
+            .collect();
+        let min = samples.iter().map(|(_, d)| *d).min_by(compare)?;
+        let max = samples.iter().map(|(_, d)| *d).max_by(compare)?;
+        Some(EquationOfTime {
+            amplitude: (max - min) / 2.,
+            samples,
+        })
+    }
+}
+
+/*
+ * Removes the jumps by a full turn, so that consecutive angles differ by less than half a turn.
+ */
+fn unwrap_angles(angles: &mut [f64]) {
+    for i in 1..angles.len() {
+        let step = (angles[i] - angles[i - 1] + PI).rem_euclid(2. * PI) - PI;
+        angles[i] = angles[i - 1] + step;
+    }
+}
+
+fn compare<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    a.partial_cmp(b).unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::{planets::earth, stars::sun};
+    use uom::si::time::minute;
+
+    use super::*;
+
+    #[test]
+    fn equation_of_time_of_earth() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_planet_data(earth().to_planet_data()).unwrap();
+        let planets = system.get_planets();
+        let equation_of_time = system.get_equation_of_time(&planets[0], 400).unwrap();
+        let amplitude = equation_of_time.amplitude.get::<minute>();
+        assert!(amplitude > 12. && amplitude < 18., "{}", amplitude);
+    }
+}
@@ -0,0 +1,200 @@
+use astro_coords::{cartesian::Cartesian, traits::*};
+use astro_units::illuminance::{illuminance_to_apparent_magnitude, lux, Illuminance};
+use uom::si::{
+    angle::radian,
+    f64::{Angle, Length, LuminousIntensity, Mass, Time, Velocity},
+    length::meter,
+    luminous_intensity::candela,
+    mass::kilogram,
+    time::second,
+    velocity::meter_per_second,
+};
+
+use crate::model::planet::{calc_pos, Planet};
+
+use super::{part::PartOfCelestialSystem, CelestialSystem};
+
+const GRAVITATIONAL_CONSTANT: f64 = 6.674_30e-11;
+const MAX_NEWTON_ITERATIONS: usize = 50;
+
+/*
+ * A massive object that passes the central body once, on a hyperbolic trajectory.
+ * The ascending node lies along the x axis, and the time of periapsis is measured since the epoch.
+ * Black holes have no luminous intensity.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RogueFlyby {
+    pub(crate) mass: Mass,
+    pub(crate) luminous_intensity: Option<LuminousIntensity>,
+    pub(crate) periapsis: Length,
+    pub(crate) excess_speed: Velocity,
+    pub(crate) inclination: Angle,
+    pub(crate) argument_of_periapsis: Angle,
+    pub(crate) time_of_periapsis: Time,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct FlybySample {
+    pub(crate) time: Time,
+    pub(crate) longitude: Angle,
+    pub(crate) latitude: Angle,
+    pub(crate) distance: Length,
+    pub(crate) apparent_magnitude: Option<f64>,
+}
+
+/*
+ * The flyby as seen from a planet, sampled evenly around the time of periapsis.
+ */
+#[derive(Debug, Clone)]
+pub(crate) struct FlybyTrack {
+    pub(crate) samples: Vec<FlybySample>,
+    pub(crate) closest_approach: FlybySample,
+}
+
+impl RogueFlyby {
+    /*
+     * Solves the hyperbolic Kepler equation e sinh(H) - H = M with Newton's method.
+     */
+    pub(crate) fn position(
+        &self,
+        central_body_mass: Mass,
+        time_since_epoch: Time,
+    ) -> Option<Cartesian> {
+        let mu = GRAVITATIONAL_CONSTANT * (central_body_mass + self.mass).get::<kilogram>();
+        let speed = self.excess_speed.get::<meter_per_second>();
+        let periapsis = self.periapsis.get::<meter>();
+        if mu <= 0. || speed <= 0. || periapsis <= 0. {
+            return None;
+        }
+        let semi_major_axis = mu / speed.powi(2);
+        let eccentricity = 1. + periapsis / semi_major_axis;
+        let mean_motion = (mu / semi_major_axis.powi(3)).sqrt();
+        let mean_anomaly =
+            mean_motion * (time_since_epoch - self.time_of_periapsis).get::<second>();
+
+        let mut anomaly = (mean_anomaly / eccentricity).asinh();
+        for _ in 0..MAX_NEWTON_ITERATIONS {
+            let step = (eccentricity * anomaly.sinh() - anomaly - mean_anomaly)
+                / (eccentricity * anomaly.cosh() - 1.);
+            anomaly -= step;
+            if step.abs() < 1e-12 {
+                break;
+            }
+        }
+        let x = semi_major_axis * (eccentricity - anomaly.cosh());
+        let y = semi_major_axis * (eccentricity.powi(2) - 1.).sqrt() * anomaly.sinh();
+
+        let (sin_w, cos_w) = self.argument_of_periapsis.get::<radian>().sin_cos();
+        let (sin_i, cos_i) = self.inclination.get::<radian>().sin_cos();
+        let in_plane = x * sin_w + y * cos_w;
+        let position = Cartesian::new(
+            Length::new::<meter>(x * cos_w - y * sin_w),
+            Length::new::<meter>(in_plane * cos_i),
+            Length::new::<meter>(in_plane * sin_i),
+        );
+        position.x.value.is_finite().then_some(position)
+    }
+}
+
+impl CelestialSystem {
+    /*
+     * The central body needs a mass for the trajectory. The flyby does not perturb the planets.
+     */
+    pub(crate) fn get_flyby_track(
+        &self,
+        flyby: &RogueFlyby,
+        observer: &Planet,
+        time_span: Time,
+        sample_count: usize,
+    ) -> Option<FlybyTrack> {
+        let central_body_mass = self.central_body.get_mass_at_epoch()?;
+        if sample_count < 2 {
+            return None;
+        }
+        let start = flyby.time_of_periapsis - time_span / 2.;
+        let mut samples = Vec::with_capacity(sample_count);
+        for i in 0..sample_count {
+            let time = start + time_span * (i as f64 / (sample_count - 1) as f64);
+            let planet_time = self.get_planet_time(observer.get_id(), time);
+            let observer_position = calc_pos(&self.central_body, planet_time, observer.get_data());
+            let relative = flyby.position(central_body_mass, time)? + -&observer_position;
+            let distance = relative.length();
+            let ecliptic = relative.to_ecliptic().ok()?;
+            let apparent_magnitude = flyby.luminous_intensity.map(|intensity| {
+                let illuminance = Illuminance::new::<lux>(
+                    intensity.get::<candela>() / distance.get::<meter>().powi(2),
+                );
+                illuminance_to_apparent_magnitude(illuminance)
+            });
+            samples.push(FlybySample {
+                time,
+                longitude: ecliptic.spherical.longitude,
+                latitude: ecliptic.spherical.latitude,
+                distance,
+                apparent_magnitude,
+            });
+        }
+        let closest_approach = samples
+            .iter()
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))?
+            .clone();
+        Some(FlybyTrack {
+            samples,
+            closest_approach,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::{planets::earth, stars::sun};
+    use uom::si::{
+        angle::degree, length::astronomical_unit, mass::solar_mass, time::year,
+        velocity::kilometer_per_second,
+    };
+
+    use super::*;
+
+    fn some_flyby() -> RogueFlyby {
+        RogueFlyby {
+            mass: Mass::new::<solar_mass>(0.5),
+            luminous_intensity: Some(LuminousIntensity::new::<candela>(1e26)),
+            periapsis: Length::new::<astronomical_unit>(100.),
+            excess_speed: Velocity::new::<kilometer_per_second>(20.),
+            inclination: Angle::new::<degree>(30.),
+            argument_of_periapsis: Angle::new::<degree>(0.),
+            time_of_periapsis: Time::new::<year>(10.),
+        }
+    }
+
+    #[test]
+    fn periapsis_is_reached_at_the_time_of_periapsis() {
+        let flyby = some_flyby();
+        let sun_mass = Mass::new::<solar_mass>(1.);
+        let at_periapsis = flyby.position(sun_mass, flyby.time_of_periapsis).unwrap();
+        let distance = at_periapsis.length().get::<astronomical_unit>();
+        assert!((distance - 100.).abs() < 1e-6);
+        let later = flyby
+            .position(sun_mass, flyby.time_of_periapsis + Time::new::<year>(50.))
+            .unwrap();
+        assert!(later.length().get::<astronomical_unit>() > 150.);
+    }
+
+    #[test]
+    fn flyby_is_brightest_at_closest_approach() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_planet_data(earth().to_planet_data()).unwrap();
+        let planets = system.get_planets();
+        let track = system
+            .get_flyby_track(&some_flyby(), &planets[0], Time::new::<year>(100.), 101)
+            .unwrap();
+        let brightest = track
+            .samples
+            .iter()
+            .filter_map(|sample| sample.apparent_magnitude)
+            .fold(f64::INFINITY, f64::min);
+        assert_eq!(track.closest_approach.apparent_magnitude, Some(brightest));
+        let distance = track.closest_approach.distance.get::<astronomical_unit>();
+        assert!(distance > 98. && distance < 102.);
+    }
+}
@@ -0,0 +1,160 @@
+use astro_utils::planets::{derived_data::DerivedPlanetData, planet_data::PlanetData};
+use uom::si::{
+    acceleration::standard_gravity, length::astronomical_unit, length::kilometer,
+    thermodynamic_temperature::kelvin,
+};
+
+use super::{tidal_locking::is_synchronous, CelestialSystem};
+
+const SUN_RADIUS_KM: f64 = 695_700.;
+const SUN_TEMPERATURE_KELVIN: f64 = 5_772.;
+const FLUX_RANGE: (f64, f64) = (0.36, 1.1);
+const GRAVITY_RANGE: (f64, f64) = (0.3, 3.);
+const TEMPERATURE_RANGE_KELVIN: (f64, f64) = (180., 310.);
+const FLARE_STAR_TEMPERATURE_KELVIN: f64 = 3_900.;
+
+#[derive(Debug, Clone)]
+pub(crate) struct HabitabilityIndicator {
+    pub(crate) name: &'static str,
+    pub(crate) passed: bool,
+    pub(crate) detail: String,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct HabitabilityReport {
+    pub(crate) planet: String,
+    pub(crate) indicators: Vec<HabitabilityIndicator>,
+}
+
+impl HabitabilityReport {
+    /*
+     * The fraction of indicators that favour life.
+     */
+    pub(crate) fn score(&self) -> f64 {
+        if self.indicators.is_empty() {
+            return 0.;
+        }
+        let passed = self.indicators.iter().filter(|i| i.passed).count();
+        passed as f64 / self.indicators.len() as f64
+    }
+
+    pub(crate) fn could_host_life(&self) -> bool {
+        self.indicators.iter().all(|i| i.passed)
+    }
+}
+
+fn in_range(value: f64, (min, max): (f64, f64)) -> bool {
+    value >= min && value <= max
+}
+
+impl CelestialSystem {
+    /*
+     * The flux the planet receives, relative to the flux Earth receives from the Sun.
+     * The central body is treated as a black body, so the flux scales with its surface area and the fourth power of its temperature.
+     */
+    fn relative_flux(&self, planet: &PlanetData) -> Option<f64> {
+        let radius = self.central_body.get_radius_at_epoch()?.get::<kilometer>() / SUN_RADIUS_KM;
+        let temperature =
+            self.central_body.get_temperature_at_epoch().get::<kelvin>() / SUN_TEMPERATURE_KELVIN;
+        let distance = planet
+            .get_orbital_parameters()
+            .get_semi_major_axis()
+            .get::<astronomical_unit>();
+        if distance <= 0. {
+            return None;
+        }
+        Some(radius.powi(2) * temperature.powi(4) / distance.powi(2))
+    }
+
+    /*
+     * Crude indicators, meant to point worldbuilders at problems rather than to decide about life.
+     * The habitable zone uses the runaway and maximum greenhouse fluxes, and cool stars are assumed to be flare stars.
+     */
+    pub(crate) fn get_habitability_reports(&self) -> Vec<HabitabilityReport> {
+        let star_temperature = self.central_body.get_temperature_at_epoch().get::<kelvin>();
+        self.planets
+            .iter()
+            .map(|planet| {
+                let mut indicators = Vec::new();
+                if let Some(flux) = self.relative_flux(planet) {
+                    indicators.push(HabitabilityIndicator {
+                        name: "Habitable zone",
+                        passed: in_range(flux, FLUX_RANGE),
+                        detail: format!("{:.2} times the flux on Earth", flux),
+                    });
+                }
+                let derived = DerivedPlanetData::new(planet, &self.central_body, None).ok();
+                if let Some(derived) = derived.as_ref() {
+                    let gravity = derived.get_surface_gravity().get::<standard_gravity>();
+                    indicators.push(HabitabilityIndicator {
+                        name: "Surface gravity",
+                        passed: in_range(gravity, GRAVITY_RANGE),
+                        detail: format!("{:.2} g", gravity),
+                    });
+                    let temperature = derived.get_black_body_temperature().get::<kelvin>();
+                    indicators.push(HabitabilityIndicator {
+                        name: "Temperature",
+                        passed: in_range(temperature, TEMPERATURE_RANGE_KELVIN),
+                        detail: format!("{:.0} K without greenhouse effect", temperature),
+                    });
+                    let synchronous = is_synchronous(planet, derived.get_orbital_period());
+                    let expected_locked = self
+                        .get_tidal_locking(planet)
+                        .is_some_and(|locking| locking.expected_locked);
+                    let detail = if synchronous {
+                        "Rotation matches the orbit, one side always faces the star"
+                    } else if expected_locked {
+                        "Tides should have slowed the rotation down to match the orbit"
+                    } else {
+                        "Rotation differs from the orbit"
+                    };
+                    indicators.push(HabitabilityIndicator {
+                        name: "Day and night",
+                        passed: !synchronous && !expected_locked,
+                        detail: detail.to_string(),
+                    });
+                }
+                let flare_star = star_temperature < FLARE_STAR_TEMPERATURE_KELVIN;
+                indicators.push(HabitabilityIndicator {
+                    name: "Stellar activity",
+                    passed: !flare_star,
+                    detail: if flare_star {
+                        format!("{:.0} K star, likely to flare", star_temperature)
+                    } else {
+                        format!("{:.0} K star, likely quiet", star_temperature)
+                    },
+                });
+                HabitabilityReport {
+                    planet: planet.get_name().clone(),
+                    indicators,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::{planets::*, stars::sun};
+
+    use crate::model::celestial_system::CelestialSystem;
+
+    #[test]
+    fn earth_is_the_only_habitable_inner_planet() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_planet_data(mercury().to_planet_data()).unwrap();
+        system.add_planet_data(venus().to_planet_data()).unwrap();
+        system.add_planet_data(earth().to_planet_data()).unwrap();
+        system.add_planet_data(jupiter().to_planet_data()).unwrap();
+        let reports = system.get_habitability_reports();
+        let habitable: Vec<&str> = reports
+            .iter()
+            .filter(|report| report.could_host_life())
+            .map(|report| report.planet.as_str())
+            .collect();
+        assert_eq!(habitable, vec!["Earth"]);
+        for report in &reports {
+            assert!(report.score() >= 0. && report.score() <= 1.);
+        }
+    }
+}
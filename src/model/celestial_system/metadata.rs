@@ -0,0 +1,88 @@
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::model::star_naming::NamingStyle;
+
+use super::CelestialSystem;
+
+/*
+ * System-level settings that are not tied to any particular body.
+ * The epoch description is free text, e.g. "J2000" or "Founding of the first colony", because imaginary systems rarely share our calendar.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub(crate) struct SystemMetadata {
+    pub(crate) name: String,
+    pub(crate) epoch_description: String,
+    #[serde(default)]
+    pub(crate) author: String,
+    #[serde(default)]
+    pub(crate) notes: String,
+    #[serde(default)]
+    pub(crate) creation_date: String,
+    #[serde(default)]
+    pub(crate) naming_style: NamingStyle,
+}
+
+impl SystemMetadata {
+    pub(crate) fn created_today() -> Self {
+        SystemMetadata {
+            creation_date: today(),
+            ..Default::default()
+        }
+    }
+}
+
+impl CelestialSystem {
+    pub(crate) fn get_metadata(&self) -> &SystemMetadata {
+        &self.metadata
+    }
+
+    pub(crate) fn set_metadata(&mut self, metadata: SystemMetadata) {
+        self.metadata = metadata;
+    }
+}
+
+pub(crate) fn today() -> String {
+    const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let (year, month, day) = civil_from_days((seconds / SECONDS_PER_DAY) as i64);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/*
+ * Converts days since 1970-01-01 to a proleptic Gregorian date.
+ * https://howardhinnant.github.io/date_algorithms.html#civil_from_days
+ */
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719468;
+    let era = z.div_euclid(146097);
+    let day_of_era = z.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn civil_dates_are_calculated_correctly() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(59), (1970, 3, 1));
+        assert_eq!(civil_from_days(11016), (2000, 2, 29));
+        assert_eq!(civil_from_days(19723), (2024, 1, 1));
+    }
+}
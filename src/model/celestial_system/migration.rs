@@ -0,0 +1,82 @@
+use crate::model::{body_id::BodyId, migration::MigrationSchedule};
+
+use super::CelestialSystem;
+
+impl CelestialSystem {
+    pub(crate) fn get_migration_schedule(&self, planet: BodyId) -> Option<&MigrationSchedule> {
+        self.migration_schedules
+            .iter()
+            .find(|schedule| schedule.planet == planet)
+    }
+
+    /*
+     * Replaces the schedule of the planet. A schedule without keyframes removes it, and the planet keeps its current orbit.
+     */
+    pub(crate) fn set_migration_schedule(&mut self, schedule: MigrationSchedule) {
+        self.migration_schedules
+            .retain(|existing| existing.planet != schedule.planet);
+        if !schedule.get_keyframes().is_empty() {
+            self.migration_schedules.push(schedule);
+        }
+        self.apply_migrations();
+    }
+
+    pub(super) fn apply_migrations(&mut self) {
+        if self.migration_schedules.is_empty() {
+            return;
+        }
+        for schedule in &self.migration_schedules {
+            let index = self
+                .planet_ids
+                .iter()
+                .position(|planet_id| *planet_id == schedule.planet);
+            let semi_major_axis = schedule.semi_major_axis_at(self.time_since_epoch);
+            if let (Some(index), Some(semi_major_axis)) = (index, semi_major_axis) {
+                self.planets[index].set_semi_major_axis(semi_major_axis);
+            }
+        }
+        self.sort_planets_by_semimajor_axis();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::{planets::*, stars::sun};
+    use uom::si::{
+        f64::{Length, Time},
+        length::astronomical_unit,
+        time::year,
+    };
+
+    use crate::model::{
+        celestial_system::CelestialSystem,
+        migration::{MigrationKeyframe, MigrationSchedule},
+    };
+
+    #[test]
+    fn migrating_planet_follows_schedule_and_keeps_its_id() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_planet_data(earth().to_planet_data()).unwrap();
+        system.add_planet_data(jupiter().to_planet_data()).unwrap();
+        let jupiter_id = system.get_planet_id(1).unwrap();
+        let keyframe = |years: f64, au: f64| MigrationKeyframe {
+            time: Time::new::<year>(years),
+            semi_major_axis: Length::new::<astronomical_unit>(au),
+        };
+        let schedule =
+            MigrationSchedule::new(jupiter_id, vec![keyframe(0., 5.2), keyframe(1e6, 0.1)]);
+        system.set_migration_schedule(schedule);
+
+        system.set_time_since_epoch(Time::new::<year>(1e6));
+        assert_eq!(system.get_planet_index(jupiter_id), Some(0));
+        let semi_major_axis = system
+            .get_planet_data_by_id(jupiter_id)
+            .unwrap()
+            .get_orbital_parameters()
+            .get_semi_major_axis();
+        assert!((semi_major_axis.get::<astronomical_unit>() - 0.1).abs() < 1e-9);
+
+        system.set_time_since_epoch(Time::new::<year>(0.));
+        assert_eq!(system.get_planet_index(jupiter_id), Some(1));
+    }
+}
@@ -15,12 +15,53 @@ use uom::si::{
     time::year,
 };
 
-use super::star::Star;
+use super::{
+    body_id::BodyId, calendar::Calendar, galactic_motion::GalacticMotion,
+    migration::MigrationSchedule, star::Star, star_formation::StarFormingRegion,
+};
+use binary_planets::BinaryPlanet;
+use body_notes::NotesOfBody;
+use command_log::LoggedCommand;
+use constellations::ConstellationKey;
+use epoch::PlanetTimeOffset;
+use metadata::SystemMetadata;
+use observer_locations::PlanetObserverLocations;
+use precession::AxialPrecession;
+use rotation::RotationAngleAtEpoch;
 
+pub(crate) mod barycenter;
+pub(crate) mod binary_planets;
+pub(crate) mod body_ids;
+pub(crate) mod body_notes;
+pub(crate) mod calendar;
+pub(crate) mod co_orbitals;
+pub(crate) mod command_log;
 pub(crate) mod constellations;
+pub(crate) mod density;
+pub(crate) mod epoch;
+pub(crate) mod equation_of_time;
+pub(crate) mod flyby;
+pub(crate) mod habitability;
+pub(crate) mod metadata;
+pub(crate) mod migration;
+pub(crate) mod naming;
+pub(crate) mod observer_locations;
+pub(crate) mod orbits;
 pub(crate) mod part;
 pub(crate) mod planets;
+pub(crate) mod pole_stars;
+pub(crate) mod precession;
+pub(crate) mod radial_velocity;
+pub(crate) mod rotation;
+pub(crate) mod sky_calendar;
+pub(crate) mod stability;
+pub(crate) mod star_formation;
 pub(crate) mod stars;
+pub(crate) mod statistics;
+pub(crate) mod tidal_locking;
+pub(crate) mod transit;
+pub(crate) mod travel;
+pub(crate) mod zodiac;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub(crate) struct CelestialSystem {
@@ -28,7 +69,39 @@ pub(crate) struct CelestialSystem {
     planets: Vec<PlanetData>,
     distant_stars: Vec<Star>,
     constellations: Vec<Constellation>,
+    #[serde(skip)]
+    constellations_key: Option<ConstellationKey>,
+    #[serde(skip)]
+    star_revision: u64,
     time_since_epoch: Time,
+    #[serde(default)]
+    observer_locations: Vec<PlanetObserverLocations>,
+    #[serde(default)]
+    metadata: SystemMetadata,
+    #[serde(default)]
+    body_notes: Vec<NotesOfBody>,
+    #[serde(default)]
+    planet_ids: Vec<BodyId>,
+    #[serde(default)]
+    last_body_id: BodyId,
+    #[serde(default)]
+    galactic_motion: GalacticMotion,
+    #[serde(default)]
+    star_forming_regions: Vec<StarFormingRegion>,
+    #[serde(default)]
+    migration_schedules: Vec<MigrationSchedule>,
+    #[serde(default)]
+    planet_time_offsets: Vec<PlanetTimeOffset>,
+    #[serde(default)]
+    calendar: Option<Calendar>,
+    #[serde(default)]
+    axial_precessions: Vec<AxialPrecession>,
+    #[serde(default)]
+    binary_planets: Vec<BinaryPlanet>,
+    #[serde(default)]
+    command_log: Vec<LoggedCommand>,
+    #[serde(default)]
+    rotation_angles_at_epoch: Vec<RotationAngleAtEpoch>,
 }
 
 impl CelestialSystem {
@@ -42,7 +115,23 @@ impl CelestialSystem {
             planets: vec![],
             distant_stars: vec![],
             constellations: vec![],
+            constellations_key: None,
+            star_revision: 0,
             time_since_epoch: Time::new::<year>(0.),
+            observer_locations: vec![],
+            metadata: SystemMetadata::default(),
+            body_notes: vec![],
+            planet_ids: vec![],
+            last_body_id: BodyId::default(),
+            galactic_motion: GalacticMotion::default(),
+            star_forming_regions: vec![],
+            migration_schedules: vec![],
+            planet_time_offsets: vec![],
+            calendar: None,
+            axial_precessions: vec![],
+            binary_planets: vec![],
+            command_log: vec![],
+            rotation_angles_at_epoch: vec![],
         }
     }
 
@@ -65,16 +154,56 @@ impl CelestialSystem {
             planets: vec![],
             distant_stars: vec![],
             constellations: vec![],
+            constellations_key: None,
+            star_revision: 0,
             time_since_epoch: Time::new::<year>(0.),
+            observer_locations: vec![],
+            metadata: SystemMetadata::created_today(),
+            body_notes: vec![],
+            planet_ids: vec![],
+            last_body_id: BodyId::default(),
+            galactic_motion: GalacticMotion::default(),
+            star_forming_regions: vec![],
+            migration_schedules: vec![],
+            planet_time_offsets: vec![],
+            calendar: None,
+            axial_precessions: vec![],
+            binary_planets: vec![],
+            command_log: vec![],
+            rotation_angles_at_epoch: vec![],
         }
     }
 
+    /*
+     * Constellations are kept as they are, because deriving them is slow.
+     * They are recomputed via constellation_input and apply_constellations.
+     * Times outside of the supported range are clamped, so the actual time may differ from the requested one.
+     */
     pub(crate) fn set_time_since_epoch(&mut self, time_since_epoch: Time) {
+        let time_since_epoch = epoch::clamp_time_since_epoch(time_since_epoch);
         self.time_since_epoch = time_since_epoch;
         for star in &mut self.distant_stars {
-            star.recalculate_appearance_if_necessary(time_since_epoch);
+            star.recalculate_appearance_if_necessary(time_since_epoch, &self.galactic_motion);
+        }
+        if self.update_star_births() {
+            self.sort_stars_by_brightness();
+        }
+        self.apply_migrations();
+    }
+
+    pub(crate) fn get_galactic_motion(&self) -> GalacticMotion {
+        self.galactic_motion
+    }
+
+    /*
+     * Moves all stars to where the new motion puts them, which may change their brightness order.
+     */
+    pub(crate) fn set_galactic_motion(&mut self, galactic_motion: GalacticMotion) {
+        self.galactic_motion = galactic_motion;
+        for star in &mut self.distant_stars {
+            star.recalculate_appearance(self.time_since_epoch, &galactic_motion);
         }
-        self.update_constellations();
+        self.process_stars();
     }
 
     pub(crate) fn get_time_since_epoch(&self) -> Time {
@@ -91,10 +220,20 @@ impl CelestialSystem {
     pub(crate) fn read_from_file(path: PathBuf) -> Result<Self, std::io::Error> {
         let file = std::fs::File::open(path)?;
         let reader = std::io::BufReader::new(file);
-        let celestial_system = serde_json::from_reader(reader)?;
+        let mut celestial_system: CelestialSystem = serde_json::from_reader(reader)?;
+        celestial_system.prepare_after_loading()?;
         Ok(celestial_system)
     }
 
+    /*
+     * Every way of reading a system from file has to go through this, so that loaded systems are as valid as those created in the app.
+     */
+    pub(crate) fn prepare_after_loading(&mut self) -> Result<(), std::io::Error> {
+        self.assign_missing_body_ids()?;
+        self.time_since_epoch = epoch::clamp_time_since_epoch(self.time_since_epoch);
+        Ok(())
+    }
+
     pub(crate) fn get_supernovae(&self) -> Vec<Star> {
         let mut supernovae: Vec<Star> = self
             .get_stars()
@@ -111,6 +250,26 @@ impl CelestialSystem {
         supernovae
     }
 
+    /*
+     * The names of the stars that went supernova while the time passed from one moment to a later one.
+     */
+    pub(crate) fn get_supernovae_between(&self, from: Time, to: Time) -> Vec<String> {
+        self.distant_stars
+            .iter()
+            .filter_map(|star| star.get_data())
+            .chain(std::iter::once(&self.central_body))
+            .filter(|data| data.get_fate() == &StarFate::TypeIISupernova)
+            .filter(|data| {
+                let alive_before = data
+                    .get_time_until_death(from)
+                    .is_some_and(|t| t.value > 0.);
+                let dead_after = data.get_time_until_death(to).is_some_and(|t| t.value <= 0.);
+                alive_before && dead_after
+            })
+            .map(|data| data.get_name().clone())
+            .collect()
+    }
+
     fn ord_by_time_til_death(&self, a: &Star, b: &Star) -> std::cmp::Ordering {
         let data_a = a.get_data();
         let data_b = b.get_data();
@@ -0,0 +1,172 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    error::ElenathError,
+    model::{
+        body_id::BodyId,
+        star_naming::{is_catalog_name, NameGenerator},
+    },
+};
+
+use super::{part::PartOfCelestialSystem, CelestialSystem};
+
+/*
+ * Names identify bodies in the planet picker, in the body notes and in constellations, so new names must be given and unique.
+ */
+pub(crate) fn check_body_name(name: &str, taken: &HashSet<String>) -> Result<(), ElenathError> {
+    if name.trim().is_empty() {
+        return Err(ElenathError::Generic(
+            "The name must not be empty.".to_string(),
+        ));
+    }
+    if taken.contains(name) {
+        return Err(ElenathError::Generic(format!(
+            "The name \"{}\" is already taken.",
+            name
+        )));
+    }
+    Ok(())
+}
+
+impl CelestialSystem {
+    /*
+     * The names of all planets except the one with the given ID.
+     */
+    pub(crate) fn planet_names_except(&self, id: Option<BodyId>) -> HashSet<String> {
+        let own_index = id.and_then(|id| self.get_planet_index(id));
+        self.planets
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| Some(*index) != own_index)
+            .map(|(_, planet)| planet.get_name().clone())
+            .collect()
+    }
+
+    /*
+     * The names of all distant stars except the one with the given ID.
+     */
+    pub(crate) fn star_names_except(&self, id: Option<BodyId>) -> HashSet<String> {
+        self.distant_stars
+            .iter()
+            .filter(|star| id.is_none() || star.get_id() != id)
+            .map(|star| star.get_appearance().get_name().clone())
+            .collect()
+    }
+
+    /*
+     * Names the distant stars with data that only have a catalog number or no name, in the naming style of the system.
+     * Constellations with catalog numbers are renamed alike, with one name per constellation so that their stars stay together.
+     * Mere appearances cannot be renamed. The same seed draws the same names, so that a replayed command renames alike.
+     * Returns the number of renamed stars.
+     */
+    pub(crate) fn name_unnamed_stars_with_seed(&mut self, seed: u64) -> usize {
+        let taken: HashSet<String> = self
+            .distant_stars
+            .iter()
+            .map(|star| star.get_appearance().get_name().clone())
+            .chain(
+                self.constellations
+                    .iter()
+                    .map(|constellation| constellation.get_name().to_string()),
+            )
+            .collect();
+        let mut generator = NameGenerator::new(self.metadata.naming_style, seed, taken);
+        let mut constellation_names: HashMap<String, Option<String>> = HashMap::new();
+        let mut renamed = 0;
+        let mut changed = false;
+        for star in self.distant_stars.iter_mut() {
+            let Some(mut data) = star.get_data().cloned() else {
+                continue;
+            };
+            let mut star_changed = false;
+            if is_catalog_name(data.get_name()) {
+                if let Some(name) = generator.next_name() {
+                    data.set_name(name);
+                    renamed += 1;
+                    star_changed = true;
+                }
+            }
+            let numbered_constellation = data.get_constellation().clone().filter(|constellation| {
+                !constellation.is_empty() && is_catalog_name(constellation)
+            });
+            if let Some(constellation) = numbered_constellation {
+                let name = constellation_names
+                    .entry(constellation)
+                    .or_insert_with(|| generator.next_name());
+                if let Some(name) = name {
+                    data.set_constellation(Some(name.clone()));
+                    star_changed = true;
+                }
+            }
+            if star_changed {
+                star.set_data(data, self.time_since_epoch, &self.galactic_motion);
+                changed = true;
+            }
+        }
+        if changed {
+            self.update_constellations();
+        }
+        renamed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::stars::{all::get_many_stars, sun};
+
+    use crate::model::{star_naming::NamingStyle, star_provenance::StarOrigin};
+
+    use super::*;
+
+    fn system_with_numbered_stars(naming_style: NamingStyle) -> CelestialSystem {
+        let stars = get_many_stars()
+            .iter()
+            .take(10)
+            .enumerate()
+            .map(|(i, star)| {
+                let mut data = star.to_star_data();
+                data.set_name(format!("{}", i));
+                data.set_constellation(Some("7".to_string()));
+                data
+            })
+            .collect();
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_stars_from_data(stars, StarOrigin::Random);
+        let mut metadata = system.get_metadata().clone();
+        metadata.naming_style = naming_style;
+        system.set_metadata(metadata);
+        system
+    }
+
+    #[test]
+    fn numbered_stars_and_constellations_are_named() {
+        let mut system = system_with_numbered_stars(NamingStyle::Syllables);
+        assert_eq!(system.name_unnamed_stars_with_seed(42), 10);
+        let constellations: HashSet<_> = system
+            .get_distant_stars()
+            .iter()
+            .map(|star| star.get_data().unwrap().get_constellation().clone())
+            .collect();
+        assert_eq!(constellations.len(), 1);
+        for star in system.get_distant_stars() {
+            assert!(!is_catalog_name(star.get_appearance().get_name()));
+        }
+    }
+
+    #[test]
+    fn names_of_other_bodies_are_taken() {
+        let system = system_with_numbered_stars(NamingStyle::CatalogNumbers);
+        let id = system.get_distant_stars()[0].get_id();
+        let taken = system.star_names_except(id);
+        assert!(check_body_name("0", &taken).is_ok());
+        assert!(check_body_name("1", &taken).is_err());
+        assert!(check_body_name(" ", &taken).is_err());
+        assert!(check_body_name("1", &system.star_names_except(None)).is_err());
+    }
+
+    #[test]
+    fn catalog_numbers_are_kept_by_default() {
+        let mut system = system_with_numbered_stars(NamingStyle::CatalogNumbers);
+        assert_eq!(system.name_unnamed_stars_with_seed(42), 0);
+    }
+}
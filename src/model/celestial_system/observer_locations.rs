@@ -0,0 +1,120 @@
+use crate::model::{body_id::BodyId, observer_location::ObserverLocation};
+use serde::{Deserialize, Serialize};
+
+use super::CelestialSystem;
+
+/*
+ * The observer locations saved for one planet.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(super) struct PlanetObserverLocations {
+    planet: BodyId,
+    locations: Vec<ObserverLocation>,
+}
+
+impl CelestialSystem {
+    pub(crate) fn add_observer_location(&mut self, planet: BodyId, location: ObserverLocation) {
+        let index = match self
+            .observer_locations
+            .iter()
+            .position(|entry| entry.planet == planet)
+        {
+            Some(index) => index,
+            None => {
+                self.observer_locations.push(PlanetObserverLocations {
+                    planet,
+                    locations: vec![],
+                });
+                self.observer_locations.len() - 1
+            }
+        };
+        let locations = &mut self.observer_locations[index].locations;
+        match locations
+            .iter_mut()
+            .find(|l| l.get_name() == location.get_name())
+        {
+            Some(existing) => *existing = location,
+            None => locations.push(location),
+        }
+    }
+
+    pub(crate) fn get_observer_locations(&self, planet: BodyId) -> &[ObserverLocation] {
+        self.observer_locations
+            .iter()
+            .find(|entry| entry.planet == planet)
+            .map(|entry| entry.locations.as_slice())
+            .unwrap_or_default()
+    }
+
+    /*
+     * The observer locations of all planets that still exist, together with the planet's ID.
+     */
+    pub(crate) fn get_all_observer_locations(
+        &self,
+    ) -> impl Iterator<Item = (BodyId, &[ObserverLocation])> {
+        self.observer_locations
+            .iter()
+            .filter(|entry| self.get_planet_index(entry.planet).is_some())
+            .map(|entry| (entry.planet, entry.locations.as_slice()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::{planets::earth, stars::sun};
+    use uom::si::{angle::degree, f64::Angle};
+
+    use super::*;
+
+    fn capital() -> ObserverLocation {
+        ObserverLocation::new(
+            "Capital City".to_string(),
+            Angle::new::<degree>(12.),
+            Angle::new::<degree>(51.48),
+        )
+    }
+
+    #[test]
+    fn observer_locations_are_stored_per_planet() {
+        let mut system = CelestialSystem::empty();
+        system.add_observer_location(BodyId::default().next(), capital());
+        assert_eq!(
+            system.get_observer_locations(BodyId::default().next()),
+            &[capital()]
+        );
+        assert!(system
+            .get_observer_locations(BodyId::default().next().next())
+            .is_empty());
+    }
+
+    #[test]
+    fn observer_location_with_same_name_is_overwritten() {
+        let mut system = CelestialSystem::empty();
+        let planet = BodyId::default().next();
+        let old = ObserverLocation::new(
+            "Observatory".to_string(),
+            Angle::new::<degree>(0.),
+            Angle::new::<degree>(0.),
+        );
+        let new = ObserverLocation::new(
+            "Observatory".to_string(),
+            Angle::new::<degree>(0.),
+            Angle::new::<degree>(90.),
+        );
+        system.add_observer_location(planet, old);
+        system.add_observer_location(planet, new.clone());
+        assert_eq!(system.get_observer_locations(planet), &[new]);
+    }
+
+    #[test]
+    fn observer_locations_stay_with_a_renamed_planet() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let planet = system.add_planet_data(earth().to_planet_data()).unwrap();
+        system.add_observer_location(planet, capital());
+
+        let mut renamed = earth().to_planet_data();
+        renamed.set_name("Arda".to_string());
+        system.overwrite_planet_data(0, renamed);
+        assert_eq!(system.get_observer_locations(planet), &[capital()]);
+    }
+}
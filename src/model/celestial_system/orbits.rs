@@ -0,0 +1,83 @@
+use astro_coords::cartesian::Cartesian;
+
+use crate::model::{
+    celestial_system::part::PartOfCelestialSystem,
+    planet::{calc_pos, Planet},
+};
+
+use super::CelestialSystem;
+
+/*
+ * Positions along one full orbit of the planet, starting at its current position.
+ */
+pub(crate) struct OrbitSamples {
+    pub(crate) positions: Vec<Cartesian>,
+}
+
+impl OrbitSamples {
+    /*
+     * The samples closest to and furthest from the central body.
+     * They approximate the apsides to within one sample spacing.
+     */
+    pub(crate) fn apsis_indices(&self) -> Option<(usize, usize)> {
+        let distance = |i: &usize| self.positions[*i].length().value;
+        let indices = 0..self.positions.len();
+        let periapsis = indices
+            .clone()
+            .min_by(|a, b| distance(a).total_cmp(&distance(b)))?;
+        let apoapsis = indices.max_by(|a, b| distance(a).total_cmp(&distance(b)))?;
+        Some((periapsis, apoapsis))
+    }
+}
+
+impl CelestialSystem {
+    pub(crate) fn get_orbit_samples(&self, planet: &Planet, count: usize) -> Option<OrbitSamples> {
+        let period = planet.get_derived_data()?.get_orbital_period();
+        let now = self.get_planet_time(planet.get_id(), self.time_since_epoch);
+        let positions = (0..count)
+            .map(|i| {
+                let time = now + period * (i as f64 / count as f64);
+                calc_pos(&self.central_body, time, planet.get_data())
+            })
+            .collect();
+        Some(OrbitSamples { positions })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::{planets::mars, stars::sun};
+    use uom::si::length::astronomical_unit;
+
+    use super::*;
+
+    #[test]
+    fn sampled_apsides_match_orbit_parameters() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_planet_data(mars().to_planet_data()).unwrap();
+        let planet = &system.get_planets()[0];
+        let samples = system.get_orbit_samples(planet, 720).unwrap();
+        let (periapsis, apoapsis) = samples.apsis_indices().unwrap();
+
+        let orbit = planet.get_data().get_orbital_parameters();
+        let semi_major_axis = orbit.get_semi_major_axis();
+        let eccentricity = orbit.get_eccentricity();
+        let expected_periapsis = semi_major_axis * (1. - eccentricity);
+        let expected_apoapsis = semi_major_axis * (1. + eccentricity);
+        let accuracy = 1e-3;
+        let periapsis = samples.positions[periapsis].length();
+        let apoapsis = samples.positions[apoapsis].length();
+        assert!(
+            (periapsis - expected_periapsis)
+                .get::<astronomical_unit>()
+                .abs()
+                < accuracy
+        );
+        assert!(
+            (apoapsis - expected_apoapsis)
+                .get::<astronomical_unit>()
+                .abs()
+                < accuracy
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use crate::model::body_id::BodyId;
+
 #[derive(Debug, Clone, Copy)]
 pub(crate) enum BodyType {
     Planet,
@@ -7,5 +9,7 @@ pub(crate) enum BodyType {
 pub(crate) trait PartOfCelestialSystem {
     fn get_index(&self) -> Option<usize>;
 
+    fn get_id(&self) -> Option<BodyId>;
+
     fn get_body_type(&self) -> BodyType;
 }
@@ -1,16 +1,44 @@
-use std::cmp::Ordering;
+use std::{cmp::Ordering, collections::HashSet};
 
+use astro_coords::cartesian::Cartesian;
 use astro_utils::{planets::planet_data::PlanetData, real_data::planets::*};
-use uom::si::f64::Length;
+use uom::si::{
+    f64::{Length, Time},
+    length::astronomical_unit,
+};
 
-use crate::model::planet::Planet;
+use crate::{
+    error::ElenathError,
+    model::{body_id::BodyId, planet::Planet},
+};
 
-use super::CelestialSystem;
+use super::{naming::check_body_name, CelestialSystem};
+
+/*
+ * A body of the system whose position is known at any time.
+ * Planets are referred to by their ID, so that they stay the same body when they are renamed or share a name.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum SystemBody {
+    CentralBody,
+    Planet(BodyId),
+}
 
 impl CelestialSystem {
-    pub(crate) fn add_planet_data(&mut self, planet: PlanetData) {
+    /*
+     * Rejects planets without a name or with the name of another planet, whichever dialog or import they come from.
+     */
+    pub(crate) fn add_planet_data(&mut self, planet: PlanetData) -> Result<BodyId, ElenathError> {
+        check_body_name(planet.get_name(), &self.planet_names_except(None))?;
+        Ok(self.insert_planet_data(planet))
+    }
+
+    fn insert_planet_data(&mut self, planet: PlanetData) -> BodyId {
         self.planets.push(planet);
+        let id = self.new_body_id();
+        self.planet_ids.push(id);
         self.sort_planets_by_semimajor_axis();
+        id
     }
 
     pub(crate) fn overwrite_planet_data(&mut self, index: usize, planet: PlanetData) {
@@ -18,31 +46,56 @@ impl CelestialSystem {
         self.sort_planets_by_semimajor_axis();
     }
 
-    fn sort_planets_by_semimajor_axis(&mut self) {
+    /*
+     * The planet IDs are kept in the same order as the planets.
+     */
+    pub(super) fn sort_planets_by_semimajor_axis(&mut self) {
         fn sma(a: &PlanetData) -> Length {
             a.get_orbital_parameters().get_semi_major_axis()
         }
-        self.planets
-            .sort_by(|a, b| sma(a).partial_cmp(&sma(b)).unwrap_or(Ordering::Equal));
+        let mut planets: Vec<(PlanetData, BodyId)> = self
+            .planets
+            .drain(..)
+            .zip(self.planet_ids.drain(..))
+            .collect();
+        planets.sort_by(|(a, _), (b, _)| sma(a).partial_cmp(&sma(b)).unwrap_or(Ordering::Equal));
+        (self.planets, self.planet_ids) = planets.into_iter().unzip();
     }
 
-    pub(crate) fn randomize_planets(&mut self) {
-        todo!();
-        self.sort_planets_by_semimajor_axis();
+    pub(crate) fn generate_random_planets(&self) -> Vec<PlanetData> {
+        todo!()
+    }
+
+    /*
+     * Replaces all planets, e.g. by randomly generated ones, or fails without any change if their names are not unique.
+     */
+    pub(crate) fn replace_planets(&mut self, planets: Vec<PlanetData>) -> Result<(), ElenathError> {
+        let mut taken = HashSet::new();
+        for planet in planets.iter() {
+            check_body_name(planet.get_name(), &taken)?;
+            taken.insert(planet.get_name().clone());
+        }
+        self.planets.clear();
+        self.planet_ids.clear();
+        for planet in planets {
+            self.insert_planet_data(planet);
+        }
+        Ok(())
     }
 
     pub(crate) fn load_real_planets(&mut self) {
         self.planets.clear();
-        self.add_planet_data(mercury().to_planet_data());
-        self.add_planet_data(venus().to_planet_data());
-        self.add_planet_data(earth().to_planet_data());
-        self.add_planet_data(mars().to_planet_data());
-        self.add_planet_data(ceres().to_planet_data());
-        self.add_planet_data(jupiter().to_planet_data());
-        self.add_planet_data(saturn().to_planet_data());
-        self.add_planet_data(uranus().to_planet_data());
-        self.add_planet_data(neptune().to_planet_data());
-        self.add_planet_data(pluto().to_planet_data());
+        self.planet_ids.clear();
+        self.insert_planet_data(mercury().to_planet_data());
+        self.insert_planet_data(venus().to_planet_data());
+        self.insert_planet_data(earth().to_planet_data());
+        self.insert_planet_data(mars().to_planet_data());
+        self.insert_planet_data(ceres().to_planet_data());
+        self.insert_planet_data(jupiter().to_planet_data());
+        self.insert_planet_data(saturn().to_planet_data());
+        self.insert_planet_data(uranus().to_planet_data());
+        self.insert_planet_data(neptune().to_planet_data());
+        self.insert_planet_data(pluto().to_planet_data());
     }
 
     pub(crate) fn get_planets_data(&self) -> Vec<&PlanetData> {
@@ -57,6 +110,34 @@ impl CelestialSystem {
         self.planets.get(index)
     }
 
+    pub(crate) fn get_outermost_orbit_extent(&self) -> Length {
+        let mut extent = Length::new::<astronomical_unit>(0.);
+        for planet in &self.planets {
+            let orbit = planet.get_orbital_parameters();
+            let apoapsis = orbit.get_semi_major_axis() * (1. + orbit.get_eccentricity());
+            if apoapsis > extent {
+                extent = apoapsis;
+            }
+        }
+        extent
+    }
+
+    pub(crate) fn get_body_position_at(&self, body: SystemBody, time: Time) -> Option<Cartesian> {
+        match body {
+            SystemBody::CentralBody => Some(Cartesian::origin()),
+            SystemBody::Planet(id) => {
+                let index = self.get_planet_index(id)?;
+                Some(self.get_planet_position(index, time))
+            }
+        }
+    }
+
+    pub(crate) fn get_planet(&self, id: BodyId) -> Option<Planet> {
+        self.get_planets()
+            .into_iter()
+            .find(|planet| planet.get_id() == Some(id))
+    }
+
     pub(crate) fn get_planets(&self) -> Vec<Planet> {
         let mut bodies: Vec<Planet> = Vec::new();
         for (i, planet_data) in self.planets.iter().enumerate() {
@@ -65,13 +146,17 @@ impl CelestialSystem {
             } else {
                 None
             };
-            let planet = Planet::new(
-                planet_data.clone(),
+            let id = self.get_planet_id(i);
+            let mut planet = Planet::new(
+                self.get_planet_data_at(id, planet_data, self.time_since_epoch),
                 &self.central_body,
                 previous,
-                self.time_since_epoch,
+                self.get_planet_time(id, self.time_since_epoch),
                 Some(i),
             );
+            planet.set_id(id);
+            planet.set_rotation_angle_at_epoch(self.get_rotation_angle_at_epoch(id));
+            planet.set_position(self.get_planet_position(i, self.time_since_epoch));
             bodies.push(planet);
         }
         bodies
@@ -87,10 +172,10 @@ mod tests {
     #[test]
     fn planets_are_sorted_by_semimajor_axis() {
         let mut system = CelestialSystem::empty();
-        system.add_planet_data(venus().to_planet_data());
-        system.add_planet_data(mercury().to_planet_data());
-        system.add_planet_data(mars().to_planet_data());
-        system.add_planet_data(earth().to_planet_data());
+        system.add_planet_data(venus().to_planet_data()).unwrap();
+        system.add_planet_data(mercury().to_planet_data()).unwrap();
+        system.add_planet_data(mars().to_planet_data()).unwrap();
+        system.add_planet_data(earth().to_planet_data()).unwrap();
         let planets = system.get_planets_data();
         assert_eq!(planets[0].get_name(), "Mercury");
         assert_eq!(planets[1].get_name(), "Venus");
@@ -98,14 +183,52 @@ mod tests {
         assert_eq!(planets[3].get_name(), "Mars");
     }
 
+    #[test]
+    fn outermost_orbit_extent_is_apoapsis_of_outermost_planet() {
+        let mut system = CelestialSystem::empty();
+        system.add_planet_data(earth().to_planet_data()).unwrap();
+        system.add_planet_data(mars().to_planet_data()).unwrap();
+        let mars_data = mars().to_planet_data();
+        let orbit = mars_data.get_orbital_parameters();
+        let expected = orbit.get_semi_major_axis() * (1. + orbit.get_eccentricity());
+        assert_eq!(system.get_outermost_orbit_extent(), expected);
+    }
+
     #[test]
     fn edited_planets_are_sorted_by_semimajor_axis() {
         let mut system = CelestialSystem::empty();
-        system.add_planet_data(mercury().to_planet_data());
-        system.add_planet_data(earth().to_planet_data());
+        system.add_planet_data(mercury().to_planet_data()).unwrap();
+        system.add_planet_data(earth().to_planet_data()).unwrap();
         system.overwrite_planet_data(0, jupiter().to_planet_data());
         let planets = system.get_planets_data();
         assert_eq!(planets[0].get_name(), "Earth");
         assert_eq!(planets[1].get_name(), "Jupiter");
     }
+
+    #[test]
+    fn added_planets_need_a_free_name() {
+        let mut system = CelestialSystem::empty();
+        system.add_planet_data(earth().to_planet_data()).unwrap();
+        let mut namesake = mars().to_planet_data();
+        namesake.set_name("Earth".to_string());
+        assert!(system.add_planet_data(namesake).is_err());
+        let mut unnamed = mars().to_planet_data();
+        unnamed.set_name(" ".to_string());
+        assert!(system.add_planet_data(unnamed).is_err());
+        assert_eq!(system.get_planets_data().len(), 1);
+    }
+
+    #[test]
+    fn replaced_planets_need_unique_names() {
+        let mut system = CelestialSystem::empty();
+        system.add_planet_data(earth().to_planet_data()).unwrap();
+        let planets = vec![mars().to_planet_data(), mars().to_planet_data()];
+        assert!(system.replace_planets(planets).is_err());
+        assert_eq!(system.get_planets_data()[0].get_name(), "Earth");
+        let planets = vec![mars().to_planet_data(), venus().to_planet_data()];
+        system.replace_planets(planets).unwrap();
+        let planets = system.get_planets_data();
+        assert_eq!(planets[0].get_name(), "Venus");
+        assert_eq!(planets[1].get_name(), "Mars");
+    }
 }
@@ -0,0 +1,51 @@
+use astro_coords::{direction::Direction, traits::*};
+use uom::si::f64::Angle;
+
+use super::CelestialSystem;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PoleStar {
+    pub(crate) name: String,
+    pub(crate) distance: Angle,
+}
+
+impl CelestialSystem {
+    /*
+     * Among the named stars that are visible to the naked eye, the one closest to the given celestial pole.
+     * Unnamed or faint stars are left out, because nobody would navigate by them.
+     */
+    pub(crate) fn get_pole_star(&self, pole: &Direction) -> Option<PoleStar> {
+        self.get_naked_eye_stars()
+            .into_iter()
+            .filter(|star| !star.get_name().is_empty())
+            .map(|star| PoleStar {
+                name: star.get_name().to_string(),
+                distance: star.get_pos().to_direction().angle_to(pole),
+            })
+            .min_by(|a, b| a.distance.total_cmp(&b.distance))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::{
+        planets::earth,
+        stars::{all::get_many_stars, sun},
+    };
+    use uom::si::angle::degree;
+
+    use crate::model::star_provenance::StarOrigin;
+
+    use super::*;
+
+    #[test]
+    fn polaris_is_the_pole_star_of_earth() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let stars = get_many_stars().iter().map(|s| s.to_star_data()).collect();
+        system.add_stars_from_data(stars, StarOrigin::Hardcoded);
+        let north_pole = earth().to_planet_data().get_rotation_axis().clone();
+        let pole_star = system.get_pole_star(&north_pole).unwrap();
+        assert_eq!(pole_star.name, "Polaris");
+        assert!(pole_star.distance.get::<degree>() < 2.);
+    }
+}
@@ -0,0 +1,132 @@
+use astro_coords::{direction::Direction, traits::*};
+use astro_utils::planets::planet_data::PlanetData;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use uom::si::{
+    angle::radian,
+    f64::{Angle, Time},
+};
+
+use crate::model::body_id::BodyId;
+
+use super::CelestialSystem;
+
+/*
+ * The rotation axis of a planet circles around the normal of its orbit once per precession period.
+ * A positive period turns the axis against the direction of the orbit, as the torque of the central body does for Earth.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub(super) struct AxialPrecession {
+    planet: BodyId,
+    period: Time,
+}
+
+fn orbit_normal(planet: &PlanetData) -> Direction {
+    let orbit = planet.get_orbital_parameters();
+    let inclination = orbit.get_inclination().get::<radian>();
+    let ascending_node = orbit.get_longitude_of_ascending_node().get::<radian>();
+    Direction::new(
+        inclination.sin() * ascending_node.sin(),
+        -inclination.sin() * ascending_node.cos(),
+        inclination.cos(),
+    )
+    .unwrap_or(Direction::Z)
+}
+
+impl CelestialSystem {
+    pub(crate) fn get_precession_period(&self, planet: BodyId) -> Option<Time> {
+        self.axial_precessions
+            .iter()
+            .find(|precession| precession.planet == planet)
+            .map(|precession| precession.period)
+    }
+
+    pub(crate) fn set_precession_period(&mut self, planet: BodyId, period: Option<Time>) {
+        self.axial_precessions
+            .retain(|precession| precession.planet != planet);
+        if let Some(period) = period.filter(|p| p.value.is_finite() && p.value != 0.) {
+            self.axial_precessions
+                .push(AxialPrecession { planet, period });
+        }
+    }
+
+    /*
+     * The rotation axis at the given time, whereas the planet data holds the axis at epoch.
+     */
+    pub(crate) fn get_rotation_axis_at(
+        &self,
+        planet: Option<BodyId>,
+        data: &PlanetData,
+        time_since_epoch: Time,
+    ) -> Direction {
+        let axis = data.get_rotation_axis().clone();
+        let Some(period) = planet.and_then(|id| self.get_precession_period(id)) else {
+            return axis;
+        };
+        let angle = Angle::new::<radian>(-2. * PI * (time_since_epoch / period).value);
+        axis.rotated(angle, &orbit_normal(data))
+    }
+
+    /*
+     * A copy of the planet data with the rotation axis precessed to the given time.
+     */
+    pub(crate) fn get_planet_data_at(
+        &self,
+        planet: Option<BodyId>,
+        data: &PlanetData,
+        time_since_epoch: Time,
+    ) -> PlanetData {
+        let mut data = data.clone();
+        let axis = self.get_rotation_axis_at(planet, &data, time_since_epoch);
+        data.set_rotation_axis(axis);
+        data
+    }
+
+    pub(super) fn shift_rotation_axes(&mut self, shift: Time) {
+        let axes: Vec<Direction> = self
+            .planets
+            .iter()
+            .zip(self.planet_ids.iter())
+            .map(|(planet, id)| self.get_rotation_axis_at(Some(*id), planet, shift))
+            .collect();
+        for (planet, axis) in self.planets.iter_mut().zip(axes) {
+            planet.set_rotation_axis(axis);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::{planets::earth, stars::sun};
+    use uom::si::{angle::degree, time::year};
+
+    use super::*;
+
+    #[test]
+    fn axis_returns_after_one_period_and_keeps_its_tilt() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let id = system.add_planet_data(earth().to_planet_data()).unwrap();
+        let period = Time::new::<year>(25_772.);
+        system.set_precession_period(id, Some(period));
+        let data = system.get_planet_data_by_id(id).unwrap().clone();
+        let normal = orbit_normal(&data);
+        let axis_at_epoch = data.get_rotation_axis().clone();
+
+        let half_way = system.get_rotation_axis_at(Some(id), &data, period / 2.);
+        assert!(half_way.angle_to(&axis_at_epoch).get::<degree>() > 40.);
+        let tilt = |axis: &Direction| axis.angle_to(&normal).get::<degree>();
+        assert!((tilt(&half_way) - tilt(&axis_at_epoch)).abs() < 1e-6);
+
+        let full_circle = system.get_rotation_axis_at(Some(id), &data, period);
+        assert!(full_circle.angle_to(&axis_at_epoch).get::<degree>() < 1e-4);
+    }
+
+    #[test]
+    fn removing_the_period_stops_the_precession() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let id = system.add_planet_data(earth().to_planet_data()).unwrap();
+        system.set_precession_period(id, Some(Time::new::<year>(1.)));
+        system.set_precession_period(id, None);
+        assert_eq!(system.get_precession_period(id), None);
+    }
+}
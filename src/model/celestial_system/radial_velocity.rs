@@ -0,0 +1,99 @@
+use std::cmp::Ordering;
+use uom::si::{
+    f64::{Time, Velocity},
+    velocity::meter_per_second,
+};
+
+use crate::model::planet::calc_pos;
+
+use super::CelestialSystem;
+
+/*
+ * The line-of-sight velocity of the central body, caused by the pull of its planets.
+ * The observer is far away in the direction of the x axis, and positive velocities point away from them.
+ */
+#[derive(Debug, Clone)]
+pub(crate) struct RadialVelocityCurve {
+    pub(crate) semi_amplitude: Velocity,
+    pub(crate) samples: Vec<(Time, Velocity)>,
+}
+
+impl CelestialSystem {
+    /*
+     * Samples one period of the outermost planet, starting at the current time.
+     * Planet velocities are obtained from positions shortly before and after each sample.
+     */
+    pub(crate) fn get_radial_velocity_curve(
+        &self,
+        sample_count: usize,
+    ) -> Option<RadialVelocityCurve> {
+        const STEPS_PER_SHORTEST_ORBIT: f64 = 1000.;
+
+        let star_mass = self.central_body.get_mass(self.time_since_epoch)?;
+        let periods: Vec<Time> = self
+            .get_planets()
+            .iter()
+            .filter_map(|planet| planet.get_derived_data())
+            .map(|derived| derived.get_orbital_period())
+            .collect();
+        let shortest_period = periods.iter().cloned().min_by(compare)?;
+        let longest_period = periods.iter().cloned().max_by(compare)?;
+        if sample_count < 2 {
+            return None;
+        }
+        let dt = shortest_period / STEPS_PER_SHORTEST_ORBIT;
+
+        let samples: Vec<(Time, Velocity)> = (0..sample_count)
+            .map(|i| {
+                let offset = longest_period * (i as f64 / (sample_count - 1) as f64);
+                let time = self.time_since_epoch + offset;
+                let star_velocity_x = self
+                    .planets
+                    .iter()
+                    .enumerate()
+                    .map(|(index, planet)| {
+                        let time = self.get_planet_time(self.get_planet_id(index), time);
+                        let before = calc_pos(&self.central_body, time - dt, planet);
+                        let after = calc_pos(&self.central_body, time + dt, planet);
+                        let planet_velocity_x = (after.x - before.x) / (2. * dt);
+                        -planet_velocity_x * (planet.get_mass() / star_mass).value
+                    })
+                    .fold(Velocity::new::<meter_per_second>(0.), |sum, v| sum + v);
+                (offset, -star_velocity_x)
+            })
+            .collect();
+        let min = samples.iter().map(|(_, v)| *v).min_by(compare)?;
+        let max = samples.iter().map(|(_, v)| *v).max_by(compare)?;
+        Some(RadialVelocityCurve {
+            semi_amplitude: (max - min) / 2.,
+            samples,
+        })
+    }
+}
+
+fn compare<T: PartialOrd>(a: &T, b: &T) -> Ordering {
+    a.partial_cmp(b).unwrap_or(Ordering::Equal)
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::{planets::jupiter, stars::sun};
+    use uom::si::velocity::meter_per_second;
+
+    use crate::model::celestial_system::CelestialSystem;
+
+    #[test]
+    fn jupiter_makes_the_sun_wobble_by_twelve_meters_per_second() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_planet_data(jupiter().to_planet_data()).unwrap();
+        let curve = system.get_radial_velocity_curve(500).unwrap();
+        let semi_amplitude = curve.semi_amplitude.get::<meter_per_second>();
+        assert!((semi_amplitude - 12.5).abs() < 0.5);
+    }
+
+    #[test]
+    fn no_planets_no_curve() {
+        let system = CelestialSystem::new(sun().to_star_data());
+        assert!(system.get_radial_velocity_curve(500).is_none());
+    }
+}
@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use uom::si::{
+    angle::radian,
+    f64::{Angle, Time},
+};
+
+use crate::model::body_id::BodyId;
+
+use super::CelestialSystem;
+
+/*
+ * How far a planet has turned around its rotation axis at epoch, which fixes where its prime meridian points.
+ * Planets without an entry start at zero.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub(super) struct RotationAngleAtEpoch {
+    planet: BodyId,
+    angle: Angle,
+}
+
+impl CelestialSystem {
+    pub(crate) fn get_rotation_angle_at_epoch(&self, planet: Option<BodyId>) -> Angle {
+        planet
+            .and_then(|id| {
+                self.rotation_angles_at_epoch
+                    .iter()
+                    .find(|rotation| rotation.planet == id)
+            })
+            .map(|rotation| rotation.angle)
+            .unwrap_or(Angle::new::<radian>(0.))
+    }
+
+    pub(crate) fn set_rotation_angle_at_epoch(&mut self, planet: BodyId, angle: Angle) {
+        self.rotation_angles_at_epoch
+            .retain(|rotation| rotation.planet != planet);
+        if angle.value.is_finite() && angle.value != 0. {
+            self.rotation_angles_at_epoch
+                .push(RotationAngleAtEpoch { planet, angle });
+        }
+    }
+
+    /*
+     * Each planet keeps turning at its sidereal rate, so the new angle at epoch is the angle at the time of the shift.
+     */
+    pub(super) fn shift_rotation_angles(&mut self, shift: Time) {
+        let angles: Vec<(BodyId, Angle)> = self
+            .planets
+            .iter()
+            .zip(self.planet_ids.iter())
+            .filter_map(|(planet, id)| {
+                let period = planet.get_sideral_rotation_period();
+                if !period.value.is_finite() || period.value == 0. {
+                    return None;
+                }
+                let turned = 2. * PI * (shift / period).value;
+                let angle = self.get_rotation_angle_at_epoch(Some(*id)).get::<radian>() + turned;
+                Some((*id, Angle::new::<radian>(angle.rem_euclid(2. * PI))))
+            })
+            .collect();
+        for (id, angle) in angles {
+            self.set_rotation_angle_at_epoch(id, angle);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_coords::{direction::Direction, spherical::Spherical, traits::*};
+    use astro_utils::real_data::{planets::earth, stars::sun};
+    use uom::si::{angle::degree, time::day};
+
+    use crate::model::local_time::observer_normal;
+
+    use super::*;
+
+    fn normal_on_equator(system: &CelestialSystem, id: BodyId, time: Time) -> Direction {
+        let data = system.get_planet_data_by_id(id).unwrap();
+        let surface_position = Spherical::new(Angle::new::<degree>(0.), Angle::new::<degree>(0.));
+        observer_normal(
+            data,
+            system.get_rotation_angle_at_epoch(Some(id)),
+            surface_position,
+            time,
+        )
+    }
+
+    #[test]
+    fn angle_at_epoch_turns_the_prime_meridian() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let id = system.add_planet_data(earth().to_planet_data()).unwrap();
+        let epoch = Time::new::<day>(0.);
+        let unturned = normal_on_equator(&system, id, epoch);
+        system.set_rotation_angle_at_epoch(id, Angle::new::<degree>(90.));
+        let turned = normal_on_equator(&system, id, epoch);
+        assert!((unturned.angle_to(&turned).get::<degree>() - 90.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn redefining_the_epoch_keeps_the_rotation() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let id = system.add_planet_data(earth().to_planet_data()).unwrap();
+        system.set_rotation_angle_at_epoch(id, Angle::new::<degree>(30.));
+        let time = Time::new::<day>(10.3);
+        system.set_time_since_epoch(time);
+        let before = normal_on_equator(&system, id, time);
+
+        system.redefine_epoch();
+        let after = normal_on_equator(&system, id, Time::new::<day>(0.));
+        assert!(before.angle_to(&after).get::<degree>() < 1e-6);
+    }
+}
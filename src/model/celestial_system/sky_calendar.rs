@@ -0,0 +1,388 @@
+use astro_coords::{cartesian::Cartesian, spherical::Spherical, traits::*};
+use astro_utils::{astro_display::AstroDisplay, planets::derived_data::DerivedPlanetData};
+use uom::si::{
+    angle::{degree, radian},
+    f64::Time,
+};
+
+use crate::model::{
+    body_id::BodyId,
+    local_time::{format_local_time, observer_normal},
+    observer_location::ObserverLocation,
+};
+
+use super::CelestialSystem;
+
+const SAMPLES_PER_DAY: usize = 96;
+const DAYS_WITHOUT_CALENDAR: u32 = 30;
+const CONJUNCTION_THRESHOLD_DEGREES: f64 = 5.;
+
+/*
+ * Rise and set are given as fractions of the day, and are missing if they do not happen on that day.
+ */
+#[derive(Debug, Clone)]
+pub(crate) struct RiseAndSet {
+    pub(crate) body: String,
+    pub(crate) rise: Option<f64>,
+    pub(crate) set: Option<f64>,
+    pub(crate) illuminated_fraction: Option<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct SkyCalendarDay {
+    pub(crate) label: String,
+    pub(crate) rises_and_sets: Vec<RiseAndSet>,
+    pub(crate) events: Vec<String>,
+}
+
+/*
+ * A month at a glance for one location on the surface of a planet.
+ */
+#[derive(Debug, Clone)]
+pub(crate) struct SkyCalendar {
+    pub(crate) title: String,
+    pub(crate) location: String,
+    pub(crate) days: Vec<SkyCalendarDay>,
+}
+
+struct Body {
+    name: String,
+    planet_index: Option<usize>,
+}
+
+impl CelestialSystem {
+    /*
+     * Covers the month of the fictional calendar that contains the current time.
+     * Without a calendar, it covers 30 mean solar days from now.
+     */
+    pub(crate) fn get_sky_calendar(
+        &self,
+        observer: BodyId,
+        location: &ObserverLocation,
+    ) -> Option<SkyCalendar> {
+        let observer_index = self.get_planet_index(observer)?;
+        let (title, start, day_count, day_length) = match self.get_calendar() {
+            Some(calendar) => {
+                let month = calendar.month_containing(self.time_since_epoch)?;
+                (
+                    month.title,
+                    month.start,
+                    month.days,
+                    calendar.get_day_length(),
+                )
+            }
+            None => {
+                let derived =
+                    DerivedPlanetData::new(&self.planets[observer_index], &self.central_body, None)
+                        .ok()?;
+                let day_length = derived.get_mean_synodic_day().abs();
+                if !day_length.value.is_finite() || day_length.value == 0. {
+                    return None;
+                }
+                let title = format!(
+                    "{} days from {}",
+                    DAYS_WITHOUT_CALENDAR,
+                    self.time_since_epoch.astro_display()
+                );
+                (
+                    title,
+                    self.time_since_epoch,
+                    DAYS_WITHOUT_CALENDAR,
+                    day_length,
+                )
+            }
+        };
+
+        let mut bodies = vec![Body {
+            name: self.central_body.get_name().clone(),
+            planet_index: None,
+        }];
+        for (index, planet) in self.planets.iter().enumerate() {
+            if index != observer_index {
+                bodies.push(Body {
+                    name: planet.get_name().clone(),
+                    planet_index: Some(index),
+                });
+            }
+        }
+        let position_of = |body: &Body, time: Time| match body.planet_index {
+            Some(index) => self.get_planet_position(index, time),
+            None => Cartesian::origin(),
+        };
+        let surface_position = Spherical::new(location.get_longitude(), location.get_latitude());
+        let observer_data = &self.planets[observer_index];
+        let observer_id = self.get_planet_id(observer_index);
+        let rotation_angle_at_epoch = self.get_rotation_angle_at_epoch(observer_id);
+
+        let mut days = Vec::new();
+        let mut separations: Vec<Vec<f64>> = Vec::new();
+        for day in 0..day_count {
+            let day_start = start + day_length * day as f64;
+            let altitudes: Vec<Vec<f64>> = (0..=SAMPLES_PER_DAY)
+                .map(|sample| {
+                    let time = day_start + day_length * (sample as f64 / SAMPLES_PER_DAY as f64);
+                    let observer = self.get_planet_position(observer_index, time);
+                    let observer_data = self.get_planet_data_at(observer_id, observer_data, time);
+                    let normal = observer_normal(
+                        &observer_data,
+                        rotation_angle_at_epoch,
+                        surface_position,
+                        time,
+                    );
+                    bodies
+                        .iter()
+                        .map(
+                            |body| match (position_of(body, time) + -&observer).to_direction() {
+                                Ok(direction) => normal.dot_product(&direction),
+                                Err(_) => 0.,
+                            },
+                        )
+                        .collect()
+                })
+                .collect();
+
+            let observer = self.get_planet_position(observer_index, day_start);
+            let directions: Vec<_> = bodies
+                .iter()
+                .map(|body| {
+                    (position_of(body, day_start) + -&observer)
+                        .to_direction()
+                        .ok()
+                })
+                .collect();
+            let rises_and_sets = bodies
+                .iter()
+                .enumerate()
+                .map(|(index, body)| {
+                    let heights: Vec<f64> = altitudes.iter().map(|a| a[index]).collect();
+                    let illuminated_fraction = body.planet_index.and_then(|_| {
+                        let position = position_of(body, day_start);
+                        let to_central_body = (-&position).to_direction().ok()?;
+                        let to_observer = (observer.clone() + -&position).to_direction().ok()?;
+                        let phase_angle = to_central_body.angle_to(&to_observer);
+                        Some((1. + phase_angle.get::<radian>().cos()) / 2.)
+                    });
+                    RiseAndSet {
+                        body: body.name.clone(),
+                        rise: crossing(&heights, true),
+                        set: crossing(&heights, false),
+                        illuminated_fraction,
+                    }
+                })
+                .collect();
+            let mut day_separations = Vec::new();
+            for i in 0..bodies.len() {
+                for j in (i + 1)..bodies.len() {
+                    let separation = match (&directions[i], &directions[j]) {
+                        (Some(a), Some(b)) => a.angle_to(b).get::<degree>(),
+                        _ => f64::INFINITY,
+                    };
+                    day_separations.push(separation);
+                }
+            }
+            separations.push(day_separations);
+
+            let label = match self.get_calendar() {
+                Some(calendar) => calendar
+                    .format_day(day_start)
+                    .unwrap_or_else(|| format!("Day {}", day + 1)),
+                None => format!("Day {}", day + 1),
+            };
+            days.push(SkyCalendarDay {
+                label,
+                rises_and_sets,
+                events: Vec::new(),
+            });
+        }
+
+        let pairs: Vec<(usize, usize)> = (0..bodies.len())
+            .flat_map(|i| ((i + 1)..bodies.len()).map(move |j| (i, j)))
+            .collect();
+        for (pair_index, (i, j)) in pairs.iter().enumerate() {
+            for day in 1..days.len().saturating_sub(1) {
+                let before = separations[day - 1][pair_index];
+                let separation = separations[day][pair_index];
+                let after = separations[day + 1][pair_index];
+                if separation < CONJUNCTION_THRESHOLD_DEGREES
+                    && separation <= before
+                    && separation < after
+                {
+                    days[day].events.push(format!(
+                        "Conjunction of {} and {}, {:.1}° apart",
+                        bodies[*i].name, bodies[*j].name, separation
+                    ));
+                }
+            }
+        }
+
+        Some(SkyCalendar {
+            title,
+            location: format!("{} on {}", location.get_name(), planet_name),
+            days,
+        })
+    }
+}
+
+/*
+ * The fraction of the day at which the heights first change sign in the given direction.
+ */
+fn crossing(heights: &[f64], rising: bool) -> Option<f64> {
+    let steps = heights.len().checked_sub(1)?;
+    for i in 0..steps {
+        let (a, b) = (heights[i], heights[i + 1]);
+        let crosses = if rising {
+            a < 0. && b >= 0.
+        } else {
+            a >= 0. && b < 0.
+        };
+        if crosses {
+            let fraction = a / (a - b);
+            return Some((i as f64 + fraction) / steps as f64);
+        }
+    }
+    None
+}
+
+fn time_or_dash(fraction_of_day: Option<f64>) -> String {
+    fraction_of_day
+        .map(format_local_time)
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn percent_or_dash(fraction: Option<f64>) -> String {
+    fraction
+        .map(|fraction| format!("{:.0}%", fraction * 100.))
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl SkyCalendar {
+    pub(crate) fn to_markdown(&self) -> String {
+        let mut markdown = format!("# Sky Calendar: {}\n\n{}\n", self.title, self.location);
+        for day in &self.days {
+            markdown += &format!("\n## {}\n\n", day.label);
+            markdown += "| Body | Rise | Set | Illuminated |\n|---|---|---|---|\n";
+            for entry in &day.rises_and_sets {
+                markdown += &format!(
+                    "| {} | {} | {} | {} |\n",
+                    entry.body,
+                    time_or_dash(entry.rise),
+                    time_or_dash(entry.set),
+                    percent_or_dash(entry.illuminated_fraction)
+                );
+            }
+            for event in &day.events {
+                markdown += &format!("\n- {}\n", event);
+            }
+        }
+        markdown
+    }
+
+    pub(crate) fn to_html(&self) -> String {
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Sky Calendar: {0}</title>\n<style>\nbody {{ font-family: sans-serif; }}\n.day {{ display: inline-block; vertical-align: top; width: 24em; margin: 0.5em; break-inside: avoid; }}\ntable {{ border-collapse: collapse; width: 100%; }}\nth, td {{ border: 1px solid #888; padding: 0.1em 0.3em; }}\n</style>\n</head>\n<body>\n<h1>Sky Calendar: {0}</h1>\n<p>{1}</p>\n",
+            escape_html(&self.title),
+            escape_html(&self.location)
+        );
+        for day in &self.days {
+            html += &format!(
+                "<div class=\"day\">\n<h2>{}</h2>\n<table>\n<tr><th>Body</th><th>Rise</th><th>Set</th><th>Illuminated</th></tr>\n",
+                escape_html(&day.label)
+            );
+            for entry in &day.rises_and_sets {
+                html += &format!(
+                    "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+                    escape_html(&entry.body),
+                    time_or_dash(entry.rise),
+                    time_or_dash(entry.set),
+                    percent_or_dash(entry.illuminated_fraction)
+                );
+            }
+            html += "</table>\n";
+            if !day.events.is_empty() {
+                html += "<ul>\n";
+                for event in &day.events {
+                    html += &format!("<li>{}</li>\n", escape_html(event));
+                }
+                html += "</ul>\n";
+            }
+            html += "</div>\n";
+        }
+        html += "</body>\n</html>\n";
+        html
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::{
+        planets::{earth, jupiter, venus},
+        stars::sun,
+    };
+    use uom::si::f64::Angle;
+
+    use super::*;
+
+    fn equator() -> ObserverLocation {
+        ObserverLocation::new(
+            "Equator".to_string(),
+            Angle::new::<degree>(0.),
+            Angle::new::<degree>(0.),
+        )
+    }
+
+    #[test]
+    fn the_sun_rises_and_sets_every_day_at_the_equator() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_planet_data(venus().to_planet_data()).unwrap();
+        let earth = system.add_planet_data(earth().to_planet_data()).unwrap();
+        system.add_planet_data(jupiter().to_planet_data()).unwrap();
+        let calendar = system.get_sky_calendar(earth, &equator()).unwrap();
+        assert_eq!(calendar.days.len(), DAYS_WITHOUT_CALENDAR as usize);
+        for day in &calendar.days {
+            assert_eq!(day.rises_and_sets.len(), 3);
+            let sun = &day.rises_and_sets[0];
+            assert!(sun.rise.is_some() || sun.set.is_some());
+            assert!(sun.illuminated_fraction.is_none());
+        }
+        let markdown = calendar.to_markdown();
+        assert!(markdown.contains("| Jupiter |"));
+        assert!(calendar.to_html().contains("<h2>Day 30</h2>"));
+    }
+
+    #[test]
+    fn planets_that_share_a_name_see_each_other() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let earth = system.add_planet_data(earth().to_planet_data()).unwrap();
+        let namesake = system.add_planet_data(jupiter().to_planet_data()).unwrap();
+        let index = system.get_planet_index(namesake).unwrap();
+        let mut renamed = jupiter().to_planet_data();
+        renamed.set_name("Earth".to_string());
+        system.overwrite_planet_data(index, renamed);
+        let from_earth = system.get_sky_calendar(earth, &equator()).unwrap();
+        let from_namesake = system.get_sky_calendar(namesake, &equator()).unwrap();
+        for calendar in [&from_earth, &from_namesake] {
+            let bodies: Vec<&str> = calendar.days[0]
+                .rises_and_sets
+                .iter()
+                .map(|entry| entry.body.as_str())
+                .collect();
+            assert_eq!(bodies, vec!["Sun", "Earth"]);
+        }
+        assert_ne!(from_earth.to_markdown(), from_namesake.to_markdown());
+    }
+
+    #[test]
+    fn crossings_are_interpolated() {
+        let heights = [-1., 1., 1., -3., -1.];
+        assert!((crossing(&heights, true).unwrap() - 0.125).abs() < 1e-9);
+        assert!((crossing(&heights, false).unwrap() - 0.5625).abs() < 1e-9);
+        assert!(crossing(&[1., 1.], true).is_none());
+    }
+}
@@ -0,0 +1,102 @@
+use astro_utils::planets::planet_data::PlanetData;
+use uom::si::f64::{Length, Mass};
+
+use super::CelestialSystem;
+
+impl CelestialSystem {
+    /*
+     * A cheap heuristic, not an n-body integration: Neighbouring orbits that cross or come closer than the sum of their Hill radii are flagged.
+     * Orbits are compared in terms of periapsis and apoapsis only, so inclined orbits that never actually meet (like Pluto and Neptune) are flagged as well.
+     * Moons are not yet part of the model, so their Hill stability is not checked.
+     * The members of a binary planet share one orbit by design and are not compared with each other.
+     */
+    pub(crate) fn get_stability_warnings(&self) -> Vec<String> {
+        let central_body_mass = match self.central_body.get_mass_at_epoch() {
+            Some(mass) => mass,
+            None => return vec![],
+        };
+        let mut warnings = vec![];
+        for (i, inner) in self.planets.iter().enumerate() {
+            for (j, outer) in self.planets.iter().enumerate().skip(i + 1) {
+                if self.are_binary_partners(i, j) {
+                    continue;
+                }
+                let separation = periapsis(outer) - apoapsis(inner);
+                if separation.value < 0. {
+                    warnings.push(format!(
+                        "The orbits of {} and {} cross.",
+                        inner.get_name(),
+                        outer.get_name()
+                    ));
+                } else if separation
+                    < hill_radius(inner, central_body_mass) + hill_radius(outer, central_body_mass)
+                {
+                    warnings.push(format!(
+                        "The Hill spheres of {} and {} overlap.",
+                        inner.get_name(),
+                        outer.get_name()
+                    ));
+                }
+            }
+        }
+        warnings
+    }
+}
+
+fn periapsis(planet: &PlanetData) -> Length {
+    let orbit = planet.get_orbital_parameters();
+    orbit.get_semi_major_axis() * (1. - orbit.get_eccentricity())
+}
+
+fn apoapsis(planet: &PlanetData) -> Length {
+    let orbit = planet.get_orbital_parameters();
+    orbit.get_semi_major_axis() * (1. + orbit.get_eccentricity())
+}
+
+fn hill_radius(planet: &PlanetData, central_body_mass: Mass) -> Length {
+    let mass_ratio: f64 = (planet.get_mass() / (3. * central_body_mass)).value;
+    periapsis(planet) * mass_ratio.cbrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::{planets::*, stars::sun};
+    use uom::si::{f64::Length, length::astronomical_unit};
+
+    use crate::model::celestial_system::CelestialSystem;
+
+    #[test]
+    fn inner_solar_system_is_stable() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_planet_data(mercury().to_planet_data()).unwrap();
+        system.add_planet_data(venus().to_planet_data()).unwrap();
+        system.add_planet_data(earth().to_planet_data()).unwrap();
+        system.add_planet_data(mars().to_planet_data()).unwrap();
+        assert!(system.get_stability_warnings().is_empty());
+    }
+
+    #[test]
+    fn crossing_orbits_are_flagged() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_planet_data(earth().to_planet_data()).unwrap();
+        let mut eccentric_mars = mars().to_planet_data();
+        eccentric_mars.set_eccentricity(0.5);
+        system.add_planet_data(eccentric_mars).unwrap();
+        let warnings = system.get_stability_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("cross"));
+    }
+
+    #[test]
+    fn close_neighbours_are_flagged() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_planet_data(jupiter().to_planet_data()).unwrap();
+        let mut close_neighbour = saturn().to_planet_data();
+        close_neighbour.set_eccentricity(0.);
+        close_neighbour.set_semi_major_axis(Length::new::<astronomical_unit>(5.6));
+        system.add_planet_data(close_neighbour).unwrap();
+        let warnings = system.get_stability_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].contains("Hill"));
+    }
+}
@@ -0,0 +1,187 @@
+use std::{cmp::Ordering, collections::HashSet};
+
+use crate::model::{
+    star::Star,
+    star_formation::{StarBirth, StarFormingRegion},
+    star_provenance::{StarOrigin, StarProvenance},
+};
+
+use super::CelestialSystem;
+
+impl CelestialSystem {
+    pub(crate) fn get_star_forming_regions(&self) -> &[StarFormingRegion] {
+        &self.star_forming_regions
+    }
+
+    /*
+     * Stars born in the previous regions are discarded, because their births no longer follow from the regions.
+     */
+    pub(crate) fn set_star_forming_regions(&mut self, regions: Vec<StarFormingRegion>) {
+        self.distant_stars.retain(|star| star.get_birth().is_none());
+        self.star_forming_regions = regions;
+        self.update_star_births();
+        self.process_stars();
+    }
+
+    /*
+     * Adds the stars that have been born by now and removes those that are not born yet.
+     * Stars that stay keep their IDs. Returns whether any star was added or removed.
+     */
+    pub(super) fn update_star_births(&mut self) -> bool {
+        let births: Vec<(&StarFormingRegion, StarBirth)> = self
+            .star_forming_regions
+            .iter()
+            .flat_map(|region| {
+                region
+                    .births_until(self.time_since_epoch)
+                    .into_iter()
+                    .map(move |birth| (region, birth))
+            })
+            .collect();
+        let expected: HashSet<(&str, usize)> = births
+            .iter()
+            .map(|(_, birth)| (birth.region.as_str(), birth.number))
+            .collect();
+
+        let star_count = self.distant_stars.len();
+        self.distant_stars.retain(|star| match star.get_birth() {
+            Some(birth) => expected.contains(&(birth.region.as_str(), birth.number)),
+            None => true,
+        });
+        let mut changed = self.distant_stars.len() != star_count;
+
+        let present: HashSet<(String, usize)> = self
+            .distant_stars
+            .iter()
+            .filter_map(|star| star.get_birth())
+            .map(|birth| (birth.region.clone(), birth.number))
+            .collect();
+        let galactic_motion = self.galactic_motion;
+        let mut newborns = Vec::new();
+        for (region, birth) in births {
+            if present.contains(&(birth.region.clone(), birth.number)) {
+                continue;
+            }
+            let mut star = Star::from_data(region.star_data(&birth), None, self.time_since_epoch);
+            star.set_velocity(region.star_velocity(&birth));
+            star.set_birth(birth);
+            star.set_provenance(StarProvenance::new(StarOrigin::StarFormation));
+            star.recalculate_appearance(self.time_since_epoch, &galactic_motion);
+            newborns.push(star);
+        }
+        for mut star in newborns {
+            star.set_id(self.new_body_id());
+            self.distant_stars.push(star);
+            changed = true;
+        }
+        changed
+    }
+
+    /*
+     * The stars born so far, the most recent birth first.
+     */
+    pub(crate) fn get_star_births(&self) -> Vec<Star> {
+        let mut births: Vec<Star> = self
+            .distant_stars
+            .iter()
+            .filter(|star| star.get_birth().is_some())
+            .cloned()
+            .collect();
+        births.sort_by(|a, b| {
+            let time = |star: &Star| star.get_birth().map(|birth| birth.time);
+            time(b).partial_cmp(&time(a)).unwrap_or(Ordering::Equal)
+        });
+        births
+    }
+
+    pub(crate) fn get_next_star_birth(&self) -> Option<StarBirth> {
+        self.star_forming_regions
+            .iter()
+            .filter_map(|region| region.next_birth(self.time_since_epoch))
+            .min_by(|a, b| a.time.partial_cmp(&b.time).unwrap_or(Ordering::Equal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_coords::{ecliptic::Ecliptic, spherical::Spherical};
+    use astro_utils::real_data::stars::sun;
+    use uom::si::{f64::Time, length::light_year, time::year};
+
+    use crate::model::celestial_system::part::PartOfCelestialSystem;
+
+    use super::*;
+
+    #[test]
+    fn stars_are_born_and_unborn_with_time() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.set_star_forming_regions(vec![StarFormingRegion::default()]);
+        assert_eq!(system.get_distant_star_count(), 0);
+
+        system.set_time_since_epoch(Time::new::<year>(1e6));
+        let born = system.get_distant_star_count();
+        assert!(born > 0);
+        let first_id = system.get_distant_stars()[0].get_id();
+
+        system.set_time_since_epoch(Time::new::<year>(2e6));
+        assert!(system.get_distant_star_count() >= born);
+
+        system.set_time_since_epoch(Time::new::<year>(1e6));
+        assert_eq!(system.get_distant_star_count(), born);
+        assert!(system
+            .get_distant_stars()
+            .iter()
+            .any(|star| star.get_id() == first_id));
+
+        system.set_time_since_epoch(Time::new::<year>(0.));
+        assert_eq!(system.get_distant_star_count(), 0);
+    }
+
+    #[test]
+    fn next_birth_is_announced() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.set_star_forming_regions(vec![StarFormingRegion::default()]);
+        assert!(system.get_star_births().is_empty());
+        let next_birth = system.get_next_star_birth().unwrap();
+        assert!(next_birth.time > system.get_time_since_epoch());
+
+        system.set_time_since_epoch(next_birth.time);
+        let births = system.get_star_births();
+        assert_eq!(births.len(), 1);
+        assert_eq!(births[0].get_birth(), Some(&next_birth));
+    }
+
+    #[test]
+    fn born_stars_disperse() {
+        let region = StarFormingRegion {
+            births_per_megayear: 100.,
+            ..Default::default()
+        };
+        let center = Ecliptic {
+            spherical: Spherical::new(region.longitude, region.latitude),
+        }
+        .to_direction()
+        .to_cartesian(region.distance);
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.set_star_forming_regions(vec![region.clone()]);
+        system.set_time_since_epoch(Time::new::<year>(1e6));
+
+        let spread = |system: &CelestialSystem, time: Time| {
+            let stars = system.get_distant_stars();
+            let total: f64 = stars
+                .iter()
+                .filter_map(|star| star.data_at(time, &system.get_galactic_motion()))
+                .map(|data| {
+                    (data.get_pos_at_epoch().clone() + -&center)
+                        .length()
+                        .get::<light_year>()
+                })
+                .sum();
+            total / stars.len() as f64
+        };
+        let young_spread = spread(&system, Time::new::<year>(1e6));
+        let old_spread = spread(&system, Time::new::<year>(3e8));
+        assert!(young_spread < region.radius.get::<light_year>() * 1.1);
+        assert!(old_spread > 10. * region.radius.get::<light_year>());
+    }
+}
@@ -1,5 +1,7 @@
 use astro_coords::cartesian::Cartesian;
-use astro_units::illuminance::Illuminance;
+use astro_units::{
+    illuminance::Illuminance, luminous_intensity::absolute_magnitude_to_luminous_intensity,
+};
 use astro_utils::{
     real_data::stars::{all::get_many_stars, sun},
     stars::{
@@ -9,80 +11,304 @@ use astro_utils::{
             gaia_source::fetch_brightest_stars,
             gaia_universe_simulation::fetch_brightest_stars_simulated_data,
         },
-        random::random_stars::{generate_random_star, generate_random_stars},
     },
 };
-use std::cmp::Ordering;
-use uom::si::f64::Length;
+use std::{cmp::Ordering, collections::HashSet, path::PathBuf};
+use uom::si::f64::Velocity;
 
 use crate::{
     error::ElenathError,
-    model::star::{Star, StarDataType},
+    model::{
+        appearance_override::AppearanceOverride,
+        body_id::BodyId,
+        celestial_system::part::PartOfCelestialSystem,
+        gaia_export::CatalogEntry,
+        star::{Star, StarDataType, StarMergeMode},
+        star_catalog_filter::StarCatalogFilter,
+        star_generation::GeneratedStars,
+        star_jitter::StarJitter,
+        star_provenance::{StarOrigin, StarProvenance},
+        votable::stars_to_votable,
+    },
 };
 
-use super::CelestialSystem;
+use super::{naming::check_body_name, CelestialSystem};
+
+/*
+ * Catalog cross-identifications decide where both stars have them, so that close pairs with
+ * distinct entries are kept apart, and a source is recognised even if its measurements differ.
+ * Otherwise the apparent position and brightness decide.
+ */
+fn is_same_star(
+    star: &StarAppearance,
+    entry: Option<&CatalogEntry>,
+    other: &StarAppearance,
+    other_entry: Option<&CatalogEntry>,
+) -> bool {
+    entry
+        .zip(other_entry)
+        .and_then(|(entry, other_entry)| entry.cross_match(other_entry))
+        .unwrap_or_else(|| star.apparently_the_same(other))
+}
+
+/*
+ * Finds stars that are the same as another star, regardless of how they were added. Each entry
+ * holds the index of the redundant star and the index of the star it duplicates. Stars with full
+ * data are preferred over mere appearances.
+ * Every star is compared with every other, which takes a while for large catalogs.
+ */
+pub(crate) fn find_duplicate_stars(stars: &[Star]) -> Vec<(usize, usize)> {
+    let mut duplicates = Vec::new();
+    let mut is_duplicate = vec![false; stars.len()];
+    for (i, star) in stars.iter().enumerate() {
+        if is_duplicate[i] {
+            continue;
+        }
+        for (j, other) in stars.iter().enumerate().skip(i + 1) {
+            if is_duplicate[j]
+                || !is_same_star(
+                    star.get_appearance(),
+                    star.get_catalog_entry(),
+                    other.get_appearance(),
+                    other.get_catalog_entry(),
+                )
+            {
+                continue;
+            }
+            if star.get_data().is_none() && other.get_data().is_some() {
+                is_duplicate[i] = true;
+                duplicates.push((i, j));
+                break;
+            }
+            is_duplicate[j] = true;
+            duplicates.push((j, i));
+        }
+    }
+    duplicates
+}
 
 impl CelestialSystem {
-    pub(crate) fn add_stars_from_data(&mut self, star_data: Vec<StarData>) {
+    pub(crate) fn add_stars_from_data(&mut self, star_data: Vec<StarData>, origin: StarOrigin) {
         let index = self.distant_stars.len();
         for data in star_data {
-            self.distant_stars
-                .push(Star::from_data(data, Some(index), self.time_since_epoch));
+            let mut star = Star::from_data(data, Some(index), self.time_since_epoch);
+            star.set_id(self.new_body_id());
+            star.set_provenance(StarProvenance::new(origin));
+            self.distant_stars.push(star);
         }
         self.process_stars();
     }
 
-    fn remove_known_star_from_list(
-        star_appearances: &mut Vec<StarAppearance>,
-        known_star: &StarAppearance,
+    /*
+     * Merges the given stars into the distant stars. Filling gaps only adds stars that do not
+     * coincide with a star that is already part of the system.
+     */
+    pub(crate) fn merge_stars_from_data(
+        &mut self,
+        star_data: Vec<StarData>,
+        mode: StarMergeMode,
+        origin: StarOrigin,
     ) {
-        let mut index_to_remove = None;
-
-        for (index, star_appearance) in star_appearances.iter().enumerate() {
-            if star_appearance.apparently_the_same(known_star) {
-                index_to_remove = Some(index);
-                break;
+        match mode {
+            StarMergeMode::Replace => {
+                self.distant_stars.clear();
+                self.add_stars_from_data(star_data, origin);
+            }
+            StarMergeMode::Append => self.add_stars_from_data(star_data, origin),
+            StarMergeMode::FillGaps => {
+                let known_stars = self.get_distant_star_appearances();
+                let new_stars = star_data
+                    .into_iter()
+                    .filter(|data| {
+                        let appearance = data.to_star_appearance(self.time_since_epoch);
+                        !known_stars
+                            .iter()
+                            .any(|known| known.apparently_the_same(&appearance))
+                    })
+                    .collect();
+                self.add_stars_from_data(new_stars, origin);
             }
         }
+    }
+
+    pub(crate) fn get_distant_star_count(&self) -> usize {
+        self.distant_stars.len()
+    }
 
-        if let Some(index) = index_to_remove {
-            star_appearances.remove(index);
+    fn push_appearance(
+        &mut self,
+        appearance: StarAppearance,
+        entry: Option<CatalogEntry>,
+        origin: StarOrigin,
+    ) {
+        let index = self.distant_stars.len();
+        let mut star = Star::from_appearance(appearance, Some(index));
+        star.set_id(self.new_body_id());
+        star.set_provenance(StarProvenance::new(origin));
+        if let Some(entry) = entry {
+            star.set_catalog_entry(entry);
         }
+        self.distant_stars.push(star);
     }
 
+    /*
+     * Adds the stars that are not yet part of the system. A star that is already known is merged
+     * into the known one instead, which takes over its catalog entry if it has none yet.
+     * Only the stars that were known beforehand are compared against, which keeps large imports fast.
+     * Returns the number of merged stars.
+     */
     pub(crate) fn add_star_appearances_without_duplicates(
         &mut self,
-        mut star_appearances: Vec<StarAppearance>,
-    ) {
-        for known_star in self.get_distant_star_appearances() {
-            Self::remove_known_star_from_list(&mut star_appearances, known_star);
+        star_appearances: Vec<(StarAppearance, Option<CatalogEntry>)>,
+        origin: StarOrigin,
+    ) -> usize {
+        let known_count = self.distant_stars.len();
+        let mut merged = 0;
+        for (appearance, entry) in star_appearances {
+            let known = self.distant_stars[..known_count].iter_mut().find(|known| {
+                is_same_star(
+                    known.get_appearance(),
+                    known.get_catalog_entry(),
+                    &appearance,
+                    entry.as_ref(),
+                )
+            });
+            match known {
+                Some(known) => {
+                    if let Some(entry) = entry.filter(|_| known.get_catalog_entry().is_none()) {
+                        known.set_catalog_entry(entry);
+                    }
+                    merged += 1;
+                }
+                None => self.push_appearance(appearance, entry, origin),
+            }
         }
+        self.process_stars();
+        merged
+    }
 
-        for star_appearance in star_appearances {
-            let index = self.distant_stars.len();
-            self.distant_stars
-                .push(Star::from_appearance(star_appearance, Some(index)));
+    pub(crate) fn find_duplicate_stars(&self) -> Vec<(usize, usize)> {
+        find_duplicate_stars(&self.distant_stars)
+    }
+
+    pub(crate) fn remove_duplicate_stars(&mut self) -> usize {
+        let duplicates = self.find_duplicate_stars();
+        self.remove_redundant_stars(&duplicates)
+    }
+
+    /*
+     * Removes the redundant stars of duplicates found beforehand, which must have been found for the current stars.
+     */
+    pub(crate) fn remove_redundant_stars(&mut self, duplicates: &[(usize, usize)]) -> usize {
+        let mut redundant: Vec<usize> = duplicates
+            .iter()
+            .map(|(redundant, _)| *redundant)
+            .filter(|redundant| *redundant < self.distant_stars.len())
+            .collect();
+        redundant.sort_unstable_by(|a, b| b.cmp(a));
+        for index in redundant.iter() {
+            self.distant_stars.remove(*index);
         }
         self.process_stars();
+        redundant.len()
     }
 
-    pub(crate) fn overwrite_star_data(&mut self, index: Option<usize>, star_data: StarData) {
-        match index {
+    /*
+     * Drops the stars whose current absolute magnitude is above the limit, and releases the memory they held.
+     * Stars known only by their appearance have no absolute brightness, so they are kept.
+     */
+    pub(crate) fn remove_stars_fainter_than(&mut self, absolute_magnitude: f64) -> usize {
+        let min_intensity = absolute_magnitude_to_luminous_intensity(absolute_magnitude);
+        let time = self.time_since_epoch;
+        let count = self.distant_stars.len();
+        self.distant_stars.retain(|star| {
+            !star
+                .get_data()
+                .is_some_and(|data| data.get_luminous_intensity(time) < min_intensity)
+        });
+        self.distant_stars.shrink_to_fit();
+        self.process_stars();
+        count - self.distant_stars.len()
+    }
+
+    /*
+     * Rejects stars without a name or with the name of another distant star.
+     */
+    pub(crate) fn add_star_from_data(
+        &mut self,
+        star_data: StarData,
+    ) -> Result<usize, ElenathError> {
+        check_body_name(star_data.get_name(), &self.star_names_except(None))?;
+        let mut star = Star::from_data(star_data, None, self.time_since_epoch);
+        star.set_id(self.new_body_id());
+        star.set_provenance(StarProvenance::new(StarOrigin::Manual));
+        star.mark_edited();
+        let index = self.insert_star_by_brightness(star);
+        self.reindex_stars(index, self.distant_stars.len() - 1);
+        self.update_constellations();
+        Ok(index)
+    }
+
+    /*
+     * Only the edited star is moved to its new place in the brightness order, and only the stars in between are reindexed.
+     * The edited star keeps its ID. Returns its new index.
+     */
+    pub(crate) fn overwrite_star_data(
+        &mut self,
+        index: Option<usize>,
+        star_data: StarData,
+    ) -> Option<usize> {
+        let new_index = match index {
             Some(index) => {
-                self.distant_stars[index] =
-                    Star::from_data(star_data, Some(index), self.time_since_epoch)
+                let old_star = self.distant_stars.remove(index);
+                let mut star = Star::from_data(star_data, None, self.time_since_epoch);
+                if let Some(id) = old_star.get_id() {
+                    star.set_id(id);
+                }
+                star.set_radial_velocity(old_star.get_radial_velocity());
+                star.set_appearance_override(old_star.get_appearance_override().cloned());
+                star.set_provenance(old_star.get_provenance().clone());
+                star.mark_edited();
+                let new_index = self.insert_star_by_brightness(star);
+                self.reindex_stars(index.min(new_index), index.max(new_index));
+                Some(new_index)
             }
-            None => self.central_body = star_data,
+            None => {
+                self.central_body = star_data;
+                None
+            }
+        };
+        self.update_constellations();
+        new_index
+    }
+
+    fn insert_star_by_brightness(&mut self, star: Star) -> usize {
+        let illuminance = star.get_appearance().get_illuminance();
+        let index = self
+            .distant_stars
+            .partition_point(|s| s.get_appearance().get_illuminance() >= illuminance);
+        self.distant_stars.insert(index, star);
+        index
+    }
+
+    fn reindex_stars(&mut self, first: usize, last: usize) {
+        for (i, star) in self
+            .distant_stars
+            .iter_mut()
+            .enumerate()
+            .take(last + 1)
+            .skip(first)
+        {
+            star.set_index(i);
         }
-        self.process_stars();
     }
 
-    fn process_stars(&mut self) {
+    pub(super) fn process_stars(&mut self) {
         self.sort_stars_by_brightness();
         self.update_constellations();
     }
 
-    fn sort_stars_by_brightness(&mut self) {
+    pub(super) fn sort_stars_by_brightness(&mut self) {
         fn illum(b: &Star) -> Illuminance {
             b.get_appearance().get_illuminance()
         }
@@ -94,47 +320,155 @@ impl CelestialSystem {
         }
     }
 
-    pub(crate) fn randomize_stars(
-        &mut self,
-        keep_central_body: bool,
-        max_distance: Length,
-    ) -> Result<(), ElenathError> {
-        if !keep_central_body {
-            self.central_body = generate_random_star(None)?
-        };
-        let stars = generate_random_stars(max_distance)?;
-        self.add_stars_from_data(stars);
-        Ok(())
+    pub(crate) fn add_generated_stars(&mut self, generated: GeneratedStars, mode: StarMergeMode) {
+        if let Some(central_body) = generated.central_body {
+            self.central_body = central_body;
+        }
+        self.merge_stars_from_data(generated.stars, mode, StarOrigin::Random);
+        self.name_unnamed_stars_with_seed(generated.naming_seed);
+    }
+
+    /*
+     * Perturbs all distant stars that have data. Stars that are mere appearances cannot be moved and are left as they are.
+     * Returns the number of perturbed stars.
+     */
+    pub(crate) fn jitter_stars(&mut self, jitter: &StarJitter) -> usize {
+        if jitter.is_identity() {
+            return 0;
+        }
+        let mut count = 0;
+        for star in self.distant_stars.iter_mut() {
+            let (Some(data), Some(id)) = (star.get_data(), star.get_id()) else {
+                continue;
+            };
+            let jittered = jitter.apply(data, id);
+            star.set_data(jittered, self.time_since_epoch, &self.galactic_motion);
+            star.mark_edited();
+            count += 1;
+        }
+        self.process_stars();
+        count
     }
 
-    pub(crate) fn load_real_stars(&mut self, data_type: StarDataType) -> Result<(), ElenathError> {
-        self.central_body = sun().to_star_data();
-        self.distant_stars.clear();
+    /*
+     * Only the stars that pass the filter are imported. Replacing the stars also makes the sun the central body,
+     * while appending or filling gaps keeps the central body of the system.
+     * Returns the number of fetched stars that were merged into known ones.
+     */
+    pub(crate) fn load_real_stars(
+        &mut self,
+        data_type: StarDataType,
+        mode: StarMergeMode,
+        filter: &StarCatalogFilter,
+    ) -> Result<usize, ElenathError> {
+        if mode == StarMergeMode::Replace {
+            self.central_body = sun().to_star_data();
+        }
+        let mut merged = 0;
         match data_type {
             StarDataType::Hardcoded => {
-                let stars = get_many_stars().iter().map(|s| s.to_star_data()).collect();
-                self.add_stars_from_data(stars);
+                let stars = self.filtered_hardcoded_stars(filter);
+                self.merge_stars_from_data(stars, mode, StarOrigin::Hardcoded);
             }
             StarDataType::GaiaMeasurementSmall => {
-                self.load_gaia_data(6.)?;
+                merged = self.load_gaia_data(6., mode, filter)?;
             }
             StarDataType::GaiaMeasurementLarge => {
-                self.load_gaia_data(11.0)?;
+                merged = self.load_gaia_data(11.0, mode, filter)?;
             }
             StarDataType::GaiaSimulation => {
-                let stars = fetch_brightest_stars_simulated_data()?;
-                self.add_stars_from_data(stars);
+                let stars = fetch_brightest_stars_simulated_data()?
+                    .into_iter()
+                    .filter(|data| filter.accepts_data(data, self.time_since_epoch))
+                    .collect();
+                self.merge_stars_from_data(stars, mode, StarOrigin::GaiaSimulation);
             }
         }
-        Ok(())
+        Ok(merged)
     }
 
-    fn load_gaia_data(&mut self, magnitude_threshold: f64) -> Result<(), ElenathError> {
-        let hardcoded_stars = get_many_stars().iter().map(|s| s.to_star_data()).collect();
-        self.add_stars_from_data(hardcoded_stars);
-        let gaia_stars = fetch_brightest_stars(magnitude_threshold)?;
+    fn filtered_hardcoded_stars(&self, filter: &StarCatalogFilter) -> Vec<StarData> {
+        get_many_stars()
+            .iter()
+            .map(|s| s.to_star_data())
+            .filter(|data| filter.accepts_data(data, self.time_since_epoch))
+            .collect()
+    }
+
+    /*
+     * A magnitude limit below the threshold of the preset also saves downloading the fainter stars.
+     */
+    fn load_gaia_data(
+        &mut self,
+        magnitude_threshold: f64,
+        mode: StarMergeMode,
+        filter: &StarCatalogFilter,
+    ) -> Result<usize, ElenathError> {
+        let hardcoded_stars = self.filtered_hardcoded_stars(filter);
+        self.merge_stars_from_data(hardcoded_stars, mode, StarOrigin::Hardcoded);
+        let gaia_stars: Vec<_> =
+            fetch_brightest_stars(magnitude_threshold.min(filter.magnitude_limit))?
+                .into_iter()
+                .filter(|appearance| filter.accepts_appearance(appearance))
+                .map(|appearance| (appearance, None))
+                .collect();
         println!("Fetched {} stars from Gaia", gaia_stars.len());
-        self.add_star_appearances_without_duplicates(gaia_stars);
+        Ok(self.add_star_appearances_without_duplicates(gaia_stars, StarOrigin::GaiaFetch))
+    }
+
+    /*
+     * Adds the stars of a catalog export, which are mere appearances. Returns the number of stars
+     * that were merged into known ones, which only happens when filling gaps.
+     */
+    pub(crate) fn add_catalog_stars(
+        &mut self,
+        stars: Vec<(StarAppearance, CatalogEntry)>,
+        mode: StarMergeMode,
+        filter: &StarCatalogFilter,
+    ) -> usize {
+        let stars: Vec<_> = stars
+            .into_iter()
+            .filter(|(appearance, _)| filter.accepts_appearance(appearance))
+            .map(|(appearance, entry)| (appearance, Some(entry)))
+            .collect();
+        match mode {
+            StarMergeMode::Replace => self.distant_stars.clear(),
+            StarMergeMode::Append => {}
+            StarMergeMode::FillGaps => {
+                return self.add_star_appearances_without_duplicates(stars, StarOrigin::GaiaExport)
+            }
+        }
+        for (appearance, entry) in stars {
+            self.push_appearance(appearance, entry, StarOrigin::GaiaExport);
+        }
+        self.process_stars();
+        0
+    }
+
+    /*
+     * Writes the distant stars with the given IDs, as they are stored in the system file.
+     * Files ending in .vot or .xml are written as a VOTable for other astronomy software instead.
+     */
+    pub(crate) fn write_stars_to_file(
+        &self,
+        ids: &[BodyId],
+        path: PathBuf,
+    ) -> Result<(), std::io::Error> {
+        let ids: HashSet<BodyId> = ids.iter().copied().collect();
+        let stars: Vec<&Star> = self
+            .distant_stars
+            .iter()
+            .filter(|star| star.get_id().is_some_and(|id| ids.contains(&id)))
+            .collect();
+        let is_votable = path.extension().is_some_and(|extension| {
+            extension.eq_ignore_ascii_case("vot") || extension.eq_ignore_ascii_case("xml")
+        });
+        if is_votable {
+            return std::fs::write(path, stars_to_votable(&stars));
+        }
+        let file = std::fs::File::create(path)?;
+        let writer = std::io::BufWriter::new(file);
+        serde_json::to_writer(writer, &stars)?;
         Ok(())
     }
 
@@ -162,6 +496,30 @@ impl CelestialSystem {
         bodies
     }
 
+    pub(crate) fn get_distant_stars(&self) -> &[Star] {
+        &self.distant_stars
+    }
+
+    pub(crate) fn set_star_radial_velocity(
+        &mut self,
+        index: usize,
+        radial_velocity: Option<Velocity>,
+    ) {
+        if let Some(star) = self.distant_stars.get_mut(index) {
+            star.set_radial_velocity(radial_velocity);
+        }
+    }
+
+    pub(crate) fn set_star_appearance_override(
+        &mut self,
+        index: usize,
+        appearance_override: Option<AppearanceOverride>,
+    ) {
+        if let Some(star) = self.distant_stars.get_mut(index) {
+            star.set_appearance_override(appearance_override);
+        }
+    }
+
     pub(crate) fn get_distant_star_appearances(&self) -> Vec<&StarAppearance> {
         let mut stars = Vec::new();
         for star in &self.distant_stars {
@@ -180,10 +538,17 @@ impl CelestialSystem {
 
 #[cfg(test)]
 mod tests {
-    use astro_units::luminous_intensity::absolute_magnitude_to_luminous_intensity;
-    use uom::si::length::light_year;
+    use uom::si::{
+        angle::degree,
+        f64::{Angle, Length, Time},
+        length::light_year,
+        time::year,
+    };
 
-    use crate::model::celestial_system::part::PartOfCelestialSystem;
+    use crate::model::{
+        celestial_system::part::PartOfCelestialSystem, gaia_export::GaiaRelease,
+        star_naming::NamingStyle,
+    };
 
     use super::*;
 
@@ -206,7 +571,7 @@ mod tests {
             .rev()
             .map(|s| s.to_star_data())
             .collect();
-        system.add_stars_from_data(reverse_stars);
+        system.add_stars_from_data(reverse_stars, StarOrigin::Hardcoded);
         let stars = system.get_stars();
         for i in 1..stars.len() - 1 {
             assert!(
@@ -220,7 +585,7 @@ mod tests {
     fn edited_stars_are_sorted_by_brightness() {
         let mut system = CelestialSystem::new(sun().to_star_data());
         let stars = get_many_stars().iter().map(|s| s.to_star_data()).collect();
-        system.add_stars_from_data(stars);
+        system.add_stars_from_data(stars, StarOrigin::Hardcoded);
         let mut bright_star = sun().to_star_data();
         bright_star.set_distance_at_epoch(Length::new::<light_year>(1.));
         bright_star.set_luminous_intensity_at_epoch(absolute_magnitude_to_luminous_intensity(-10.));
@@ -234,6 +599,227 @@ mod tests {
         }
     }
 
+    #[test]
+    fn merging_stars_respects_mode() {
+        let stars: Vec<StarData> = get_many_stars().iter().map(|s| s.to_star_data()).collect();
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.merge_stars_from_data(stars.clone(), StarMergeMode::Append, StarOrigin::Hardcoded);
+        assert_eq!(system.get_distant_star_count(), stars.len());
+
+        system.merge_stars_from_data(
+            stars.clone(),
+            StarMergeMode::FillGaps,
+            StarOrigin::Hardcoded,
+        );
+        assert_eq!(system.get_distant_star_count(), stars.len());
+
+        system.merge_stars_from_data(stars.clone(), StarMergeMode::Append, StarOrigin::Hardcoded);
+        assert_eq!(system.get_distant_star_count(), 2 * stars.len());
+
+        system.merge_stars_from_data(stars.clone(), StarMergeMode::Replace, StarOrigin::Hardcoded);
+        assert_eq!(system.get_distant_star_count(), stars.len());
+    }
+
+    #[test]
+    fn only_replacing_real_stars_replaces_the_central_body() {
+        let mut central_body = sun().to_star_data();
+        central_body.set_name("Not the Sun".to_string());
+        let mut system = CelestialSystem::new(central_body);
+        let filter = StarCatalogFilter::default();
+        for mode in [StarMergeMode::Append, StarMergeMode::FillGaps] {
+            system
+                .load_real_stars(StarDataType::Hardcoded, mode, &filter)
+                .unwrap();
+            assert_eq!(system.get_central_body_data().get_name(), "Not the Sun");
+        }
+        system
+            .load_real_stars(StarDataType::Hardcoded, StarMergeMode::Replace, &filter)
+            .unwrap();
+        assert_eq!(
+            system.get_central_body_data().get_name(),
+            sun().to_star_data().get_name()
+        );
+    }
+
+    #[test]
+    fn duplicate_stars_are_removed() {
+        let stars: Vec<StarData> = get_many_stars().iter().map(|s| s.to_star_data()).collect();
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_stars_from_data(stars.clone(), StarOrigin::Hardcoded);
+        assert!(system.find_duplicate_stars().is_empty());
+
+        system.add_stars_from_data(stars.clone(), StarOrigin::Hardcoded);
+        assert_eq!(system.find_duplicate_stars().len(), stars.len());
+        assert_eq!(system.remove_duplicate_stars(), stars.len());
+        assert_eq!(system.get_distant_star_count(), stars.len());
+    }
+
+    #[test]
+    fn faint_stars_are_trimmed() {
+        let mut faint_star = sun().to_star_data();
+        faint_star.set_distance_at_epoch(Length::new::<light_year>(1.));
+        faint_star.set_luminous_intensity_at_epoch(absolute_magnitude_to_luminous_intensity(15.));
+        let mut bright_star = faint_star.clone();
+        bright_star.set_luminous_intensity_at_epoch(absolute_magnitude_to_luminous_intensity(-5.));
+        let appearance = get_many_stars()[0]
+            .to_star_data()
+            .to_star_appearance(Time::new::<year>(0.));
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_stars_from_data(vec![faint_star, bright_star], StarOrigin::Hardcoded);
+        system.add_star_appearances_without_duplicates(
+            vec![(appearance, None)],
+            StarOrigin::GaiaFetch,
+        );
+
+        assert_eq!(system.remove_stars_fainter_than(10.), 1);
+        assert_eq!(system.get_distant_star_count(), 2);
+        assert!(system.get_star_data(Some(0)).is_some());
+    }
+
+    #[test]
+    fn duplicate_appearances_yield_to_star_data() {
+        let data = get_many_stars()[0].to_star_data();
+        let appearance = data.to_star_appearance(Time::new::<year>(0.));
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_star_appearances_without_duplicates(
+            vec![(appearance, None)],
+            StarOrigin::GaiaFetch,
+        );
+        system.add_stars_from_data(vec![data], StarOrigin::Hardcoded);
+        system.remove_duplicate_stars();
+        assert_eq!(system.get_distant_star_count(), 1);
+        assert!(system.get_star_data(Some(0)).is_some());
+    }
+
+    #[test]
+    fn catalog_ids_decide_over_positions() {
+        let appearance = get_many_stars()[0]
+            .to_star_data()
+            .to_star_appearance(Time::new::<year>(0.));
+        let entry = |source_id| CatalogEntry {
+            release: GaiaRelease::Dr3,
+            source_id,
+            parallax: None,
+            parallax_error: None,
+            bp_rp: None,
+            hip: None,
+        };
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_star_appearances_without_duplicates(
+            vec![(appearance.clone(), None)],
+            StarOrigin::GaiaFetch,
+        );
+
+        let merged = system.add_star_appearances_without_duplicates(
+            vec![(appearance.clone(), Some(entry(1)))],
+            StarOrigin::GaiaFetch,
+        );
+        assert_eq!(merged, 1);
+        assert_eq!(system.get_distant_star_count(), 1);
+
+        let merged = system.add_star_appearances_without_duplicates(
+            vec![(appearance, Some(entry(2)))],
+            StarOrigin::GaiaFetch,
+        );
+        assert_eq!(merged, 0);
+        assert_eq!(system.get_distant_star_count(), 2);
+        assert!(system.find_duplicate_stars().is_empty());
+    }
+
+    #[test]
+    fn edited_star_keeps_indices_consistent() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let stars = get_many_stars().iter().map(|s| s.to_star_data()).collect();
+        system.add_stars_from_data(stars, StarOrigin::Hardcoded);
+        let mut bright_star = sun().to_star_data();
+        bright_star.set_name("Bright Star".to_string());
+        bright_star.set_distance_at_epoch(Length::new::<light_year>(1.));
+        bright_star.set_luminous_intensity_at_epoch(absolute_magnitude_to_luminous_intensity(-10.));
+        let new_index = system.overwrite_star_data(Some(17), bright_star).unwrap();
+        assert_eq!(
+            system.get_star_data(Some(new_index)).unwrap().get_name(),
+            "Bright Star"
+        );
+        for (i, star) in system.get_stars().iter().skip(1).enumerate() {
+            assert_eq!(star.get_index(), Some(i));
+        }
+    }
+
+    #[test]
+    fn edited_star_keeps_its_origin() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let stars = get_many_stars().iter().map(|s| s.to_star_data()).collect();
+        system.add_stars_from_data(stars, StarOrigin::Hardcoded);
+        let star = system.get_star_data(Some(3)).unwrap().clone();
+        let new_index = system.overwrite_star_data(Some(3), star).unwrap();
+        let provenance = system.get_distant_stars()[new_index].get_provenance();
+        assert_eq!(provenance.origin, StarOrigin::Hardcoded);
+        assert!(provenance.last_edited.is_some());
+    }
+
+    #[test]
+    fn added_star_is_inserted_by_brightness() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let stars = get_many_stars().iter().map(|s| s.to_star_data()).collect();
+        system.add_stars_from_data(stars, StarOrigin::Hardcoded);
+        let mut bright_star = sun().to_star_data();
+        bright_star.set_distance_at_epoch(Length::new::<light_year>(1.));
+        bright_star.set_luminous_intensity_at_epoch(absolute_magnitude_to_luminous_intensity(-10.));
+        assert_eq!(system.add_star_from_data(bright_star).unwrap(), 0);
+        for (i, star) in system.get_stars().iter().skip(1).enumerate() {
+            assert_eq!(star.get_index(), Some(i));
+        }
+    }
+
+    #[test]
+    fn generated_stars_are_added_alike_on_replay() {
+        let stars: Vec<StarData> = get_many_stars()
+            .iter()
+            .take(10)
+            .enumerate()
+            .map(|(i, star)| {
+                let mut data = star.to_star_data();
+                data.set_name(format!("{}", i));
+                data
+            })
+            .collect();
+        let generated = GeneratedStars {
+            central_body: None,
+            stars,
+            naming_seed: 42,
+        };
+        let names = || {
+            let mut system = CelestialSystem::new(sun().to_star_data());
+            let mut metadata = system.get_metadata().clone();
+            metadata.naming_style = NamingStyle::Syllables;
+            system.set_metadata(metadata);
+            system.add_generated_stars(generated.clone(), StarMergeMode::Append);
+            system
+                .get_distant_stars()
+                .iter()
+                .map(|star| star.get_appearance().get_name().clone())
+                .collect::<Vec<_>>()
+        };
+        let first = names();
+        assert_eq!(first.len(), 10);
+        assert!(!first.contains(&"0".to_string()));
+        assert_eq!(first, names());
+    }
+
+    #[test]
+    fn added_stars_need_a_free_name() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let stars = get_many_stars().iter().map(|s| s.to_star_data()).collect();
+        system.add_stars_from_data(stars, StarOrigin::Hardcoded);
+        let count = system.get_distant_stars().len();
+        let taken = get_many_stars()[0].to_star_data();
+        assert!(system.add_star_from_data(taken).is_err());
+        let mut unnamed = sun().to_star_data();
+        unnamed.set_name(String::new());
+        assert!(system.add_star_from_data(unnamed).is_err());
+        assert_eq!(system.get_distant_stars().len(), count);
+    }
+
     #[test]
     fn star_index_is_correct_after_sorting() {
         let mut system = CelestialSystem::new(sun().to_star_data());
@@ -242,7 +828,7 @@ mod tests {
             .rev()
             .map(|s| s.to_star_data())
             .collect();
-        system.add_stars_from_data(reversed_stars);
+        system.add_stars_from_data(reversed_stars, StarOrigin::Hardcoded);
         for (i, star) in system.get_stars().iter().enumerate() {
             if i == 0 {
                 assert_eq!(star.get_index(), None);
@@ -251,4 +837,33 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn jitter_is_reproducible_with_the_same_seed() {
+        let jittered_system = |seed: u64| {
+            let mut system = CelestialSystem::new(sun().to_star_data());
+            let stars = get_many_stars().iter().map(|s| s.to_star_data()).collect();
+            system.add_stars_from_data(stars, StarOrigin::Hardcoded);
+            let jitter = StarJitter {
+                seed,
+                position_angle: Angle::new::<degree>(1.),
+                distance_fraction: 0.1,
+                magnitude: 0.5,
+            };
+            system.jitter_stars(&jitter);
+            system
+        };
+        let distances = |system: &CelestialSystem| -> Vec<f64> {
+            system
+                .get_distant_stars()
+                .iter()
+                .filter_map(|star| star.get_data())
+                .map(|data| data.get_distance_at_epoch().get::<light_year>())
+                .collect()
+        };
+        let first = jittered_system(1);
+        assert_eq!(distances(&first), distances(&jittered_system(1)));
+        assert_ne!(distances(&first), distances(&jittered_system(2)));
+        assert_eq!(first.get_distant_star_count(), get_many_stars().len());
+    }
 }
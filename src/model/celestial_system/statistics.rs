@@ -0,0 +1,128 @@
+use astro_units::illuminance::{
+    apparent_magnitude_to_illuminance, illuminance_to_apparent_magnitude,
+};
+use astro_utils::stars::appearance::StarAppearance;
+
+use super::CelestialSystem;
+use crate::model::star::Star;
+
+/*
+ * Star counts binned by apparent magnitude.
+ * The first bin starts at the largest multiple of the bin width below the brightest star.
+ */
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MagnitudeHistogram {
+    pub(crate) min_magnitude: f64,
+    pub(crate) bin_width: f64,
+    pub(crate) counts: Vec<usize>,
+}
+
+impl MagnitudeHistogram {
+    pub(crate) fn new(magnitudes: &[f64], bin_width: f64) -> Self {
+        let brightest = magnitudes.iter().cloned().fold(f64::INFINITY, f64::min);
+        let dimmest = magnitudes.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        if !brightest.is_finite() || !dimmest.is_finite() {
+            return MagnitudeHistogram {
+                min_magnitude: 0.,
+                bin_width,
+                counts: vec![],
+            };
+        }
+        let min_magnitude = (brightest / bin_width).floor() * bin_width;
+        let bin_count = ((dimmest - min_magnitude) / bin_width).floor() as usize + 1;
+        let mut counts = vec![0; bin_count];
+        for magnitude in magnitudes {
+            let bin = ((magnitude - min_magnitude) / bin_width).floor() as usize;
+            counts[bin.min(bin_count - 1)] += 1;
+        }
+        MagnitudeHistogram {
+            min_magnitude,
+            bin_width,
+            counts,
+        }
+    }
+
+    pub(crate) fn max_magnitude(&self) -> f64 {
+        self.min_magnitude + self.counts.len() as f64 * self.bin_width
+    }
+
+    /*
+     * The number of stars at least as bright as the upper edge of each bin.
+     */
+    pub(crate) fn cumulative_counts(&self) -> Vec<usize> {
+        self.counts
+            .iter()
+            .scan(0, |total, count| {
+                *total += count;
+                Some(*total)
+            })
+            .collect()
+    }
+}
+
+/*
+ * The faintest stars visible to the unaided human eye under a perfectly dark sky.
+ */
+pub(crate) const NAKED_EYE_MAGNITUDE: f64 = 6.5;
+
+impl CelestialSystem {
+    pub(crate) fn get_naked_eye_stars(&self) -> Vec<&StarAppearance> {
+        let threshold = apparent_magnitude_to_illuminance(NAKED_EYE_MAGNITUDE);
+        self.distant_stars
+            .iter()
+            .map(|s| s.get_appearance())
+            .filter(|a| a.get_illuminance() >= threshold)
+            .collect()
+    }
+
+    pub(crate) fn get_magnitude_histogram(&self, bin_width: f64) -> MagnitudeHistogram {
+        let magnitudes: Vec<f64> = self
+            .distant_stars
+            .iter()
+            .map(|s| illuminance_to_apparent_magnitude(s.get_appearance().get_illuminance()))
+            .collect();
+        MagnitudeHistogram::new(&magnitudes, bin_width)
+    }
+
+    /*
+     * A rough estimate of the memory held by the distant stars.
+     * Of the heap data, only the names and constellations are counted, which make up the bulk of it.
+     */
+    pub(crate) fn approximate_star_memory_in_bytes(&self) -> usize {
+        let names: usize = self
+            .distant_stars
+            .iter()
+            .map(|star| {
+                let data_names = star.get_data().map_or(0, |data| {
+                    data.get_name().capacity()
+                        + data
+                            .get_constellation()
+                            .as_ref()
+                            .map_or(0, String::capacity)
+                });
+                star.get_appearance().get_name().capacity() + data_names
+            })
+            .sum();
+        self.distant_stars.capacity() * std::mem::size_of::<Star>() + names
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn magnitudes_are_binned() {
+        let histogram = MagnitudeHistogram::new(&[-1.2, 0.3, 0.7, 2.9], 1.);
+        assert!((histogram.min_magnitude + 2.).abs() < 1e-10);
+        assert_eq!(histogram.counts, vec![1, 0, 2, 0, 1]);
+        assert_eq!(histogram.cumulative_counts(), vec![1, 1, 3, 3, 4]);
+        assert!((histogram.max_magnitude() - 3.).abs() < 1e-10);
+    }
+
+    #[test]
+    fn empty_catalog_yields_empty_histogram() {
+        let histogram = MagnitudeHistogram::new(&[], 0.5);
+        assert!(histogram.counts.is_empty());
+    }
+}
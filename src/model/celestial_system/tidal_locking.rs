@@ -0,0 +1,156 @@
+use astro_utils::{
+    astro_display::AstroDisplay,
+    planets::{derived_data::DerivedPlanetData, planet_data::PlanetData},
+};
+use std::f64::consts::PI;
+use uom::si::{
+    f64::Time,
+    length::meter,
+    mass::kilogram,
+    time::{hour, second},
+};
+
+use super::CelestialSystem;
+
+const GRAVITATIONAL_CONSTANT: f64 = 6.674_30e-11;
+const TIDAL_DISSIPATION_FACTOR: f64 = 100.;
+const LOVE_NUMBER: f64 = 0.3;
+const MOMENT_OF_INERTIA_FACTOR: f64 = 0.4;
+const INITIAL_ROTATION_PERIOD_HOURS: f64 = 12.;
+const ROTATION_MATCHING_ORBIT_TOLERANCE: f64 = 0.05;
+const SPIN_ORBIT_RESONANCES: [f64; 2] = [1., 1.5];
+
+/*
+ * Whether a planet would be expected to be tidally locked to the central body, and whether the entered rotation agrees.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct TidalLocking {
+    pub(crate) locking_time: Time,
+    pub(crate) expected_locked: bool,
+    pub(crate) rotation_matches_orbit: bool,
+}
+
+impl TidalLocking {
+    pub(crate) fn contradicts_rotation(&self) -> bool {
+        self.expected_locked != self.rotation_matches_orbit
+    }
+
+    pub(crate) fn describe(&self) -> String {
+        match (self.expected_locked, self.rotation_matches_orbit) {
+            (true, true) => "Locked".to_string(),
+            (true, false) => "Expected, but rotating".to_string(),
+            (false, true) => "Locked, but unexpected".to_string(),
+            (false, false) => format!("Locks in {}", self.locking_time.astro_display()),
+        }
+    }
+}
+
+fn rotations_per_orbit(planet: &PlanetData, orbital_period: Time) -> f64 {
+    (orbital_period / planet.get_sideral_rotation_period()).value
+}
+
+/*
+ * Whether the planet rotates once per orbit, so that one side always faces the central body.
+ */
+pub(crate) fn is_synchronous(planet: &PlanetData, orbital_period: Time) -> bool {
+    (rotations_per_orbit(planet, orbital_period) - 1.).abs() < ROTATION_MATCHING_ORBIT_TOLERANCE
+}
+
+/*
+ * Tides also capture planets in a 3:2 resonance, like Mercury, instead of a synchronous rotation.
+ */
+fn rotation_matches_orbit(planet: &PlanetData, orbital_period: Time) -> bool {
+    let ratio = rotations_per_orbit(planet, orbital_period);
+    SPIN_ORBIT_RESONANCES
+        .iter()
+        .any(|resonance| (ratio / resonance - 1.).abs() < ROTATION_MATCHING_ORBIT_TOLERANCE)
+}
+
+impl CelestialSystem {
+    /*
+     * The despinning time after Gladman et al. (1996), for a rocky planet that started out with a 12 hour day.
+     * The planet is expected to be locked if the central body, and therefore the planet, is older than that.
+     * Without a central body mass or age, no estimate is possible.
+     */
+    pub(crate) fn get_tidal_locking(&self, planet: &PlanetData) -> Option<TidalLocking> {
+        let star_mass = self.central_body.get_mass_at_epoch()?.get::<kilogram>();
+        let age = self.central_body.get_age_at_epoch()? + self.time_since_epoch;
+        let orbital_period = DerivedPlanetData::new(planet, &self.central_body, None)
+            .ok()?
+            .get_orbital_period();
+
+        let initial_spin =
+            2. * PI / Time::new::<hour>(INITIAL_ROTATION_PERIOD_HOURS).get::<second>();
+        let distance = planet
+            .get_orbital_parameters()
+            .get_semi_major_axis()
+            .get::<meter>();
+        let mass = planet.get_mass().get::<kilogram>();
+        let radius = planet.get_radius().get::<meter>();
+        let seconds = MOMENT_OF_INERTIA_FACTOR
+            * initial_spin
+            * distance.powi(6)
+            * mass
+            * TIDAL_DISSIPATION_FACTOR
+            / (3. * GRAVITATIONAL_CONSTANT * star_mass.powi(2) * LOVE_NUMBER * radius.powi(3));
+        if !seconds.is_finite() {
+            return None;
+        }
+        let locking_time = Time::new::<second>(seconds);
+        Some(TidalLocking {
+            locking_time,
+            expected_locked: locking_time < age,
+            rotation_matches_orbit: rotation_matches_orbit(planet, orbital_period),
+        })
+    }
+
+    pub(crate) fn get_tidal_locking_warnings(&self) -> Vec<String> {
+        self.planets
+            .iter()
+            .filter_map(|planet| {
+                let locking = self.get_tidal_locking(planet)?;
+                if !locking.contradicts_rotation() {
+                    return None;
+                }
+                let warning = if locking.expected_locked {
+                    format!(
+                        "{} should have become tidally locked after {}, but its rotation is not in resonance with its orbit.",
+                        planet.get_name(),
+                        locking.locking_time.astro_display()
+                    )
+                } else {
+                    format!(
+                        "{} rotates in resonance with its orbit, but would only become tidally locked after {}.",
+                        planet.get_name(),
+                        locking.locking_time.astro_display()
+                    )
+                };
+                Some(warning)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::{planets::*, stars::sun};
+    use uom::si::time::year;
+
+    use crate::model::celestial_system::CelestialSystem;
+
+    #[test]
+    fn earth_is_not_locked_but_mercury_would_be() {
+        let system = CelestialSystem::new(sun().to_star_data());
+        let earth = system.get_tidal_locking(&earth().to_planet_data()).unwrap();
+        assert!(!earth.expected_locked);
+        assert!(!earth.rotation_matches_orbit);
+        assert!(earth.locking_time.get::<year>() > 1e10);
+
+        let mercury = system
+            .get_tidal_locking(&mercury().to_planet_data())
+            .unwrap();
+        assert!(mercury.expected_locked);
+        assert!(mercury.rotation_matches_orbit);
+        assert!(!mercury.contradicts_rotation());
+    }
+}
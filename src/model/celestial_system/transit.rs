@@ -0,0 +1,130 @@
+use std::f64::consts::PI;
+use uom::si::{
+    f64::{Length, Time},
+    length::kilometer,
+};
+
+use crate::model::body_id::BodyId;
+
+use super::CelestialSystem;
+
+/*
+ * The brightness of the central body during a transit of a planet, relative to its brightness outside of transit.
+ * The observer is assumed to look exactly along the orbital plane, and the orbit is approximated as circular.
+ */
+#[derive(Debug, Clone)]
+pub(crate) struct TransitLightCurve {
+    pub(crate) depth: f64,
+    pub(crate) duration: Time,
+    pub(crate) samples: Vec<(Time, f64)>,
+}
+
+impl CelestialSystem {
+    /*
+     * Samples the light curve from one transit duration before to one transit duration after mid-transit.
+     */
+    pub(crate) fn get_transit_light_curve(
+        &self,
+        planet: BodyId,
+        sample_count: usize,
+    ) -> Option<TransitLightCurve> {
+        let planet = self.get_planet(planet)?;
+        let star_radius = self.central_body.get_radius(self.time_since_epoch)?;
+        let planet_radius = planet.get_data().get_radius();
+        let period = planet.get_derived_data()?.get_orbital_period();
+        let semi_major_axis = planet
+            .get_data()
+            .get_orbital_parameters()
+            .get_semi_major_axis();
+        if semi_major_axis <= star_radius + planet_radius || sample_count < 2 {
+            return None;
+        }
+
+        let contact_angle = ((star_radius + planet_radius) / semi_major_axis)
+            .value
+            .asin();
+        let duration = period * contact_angle / PI;
+        let samples = (0..sample_count)
+            .map(|i| {
+                let time = duration * (2. * i as f64 / (sample_count - 1) as f64 - 1.);
+                let angle = 2. * PI * (time / period).value;
+                let separation = semi_major_axis * angle.sin().abs();
+                let flux = relative_flux(separation, star_radius, planet_radius);
+                (time, flux)
+            })
+            .collect();
+        Some(TransitLightCurve {
+            depth: 1. - relative_flux(Length::new::<kilometer>(0.), star_radius, planet_radius),
+            duration,
+            samples,
+        })
+    }
+}
+
+/*
+ * Limb darkening is neglected, so the blocked light is proportional to the overlapping area of the two discs.
+ */
+fn relative_flux(separation: Length, star_radius: Length, planet_radius: Length) -> f64 {
+    let d = separation.get::<kilometer>();
+    let r_star = star_radius.get::<kilometer>();
+    let r_planet = planet_radius.get::<kilometer>();
+    let star_area = PI * r_star * r_star;
+    1. - circle_overlap_area(d, r_star, r_planet) / star_area
+}
+
+fn circle_overlap_area(d: f64, r1: f64, r2: f64) -> f64 {
+    if d >= r1 + r2 {
+        return 0.;
+    }
+    let r_min = r1.min(r2);
+    if d <= (r1 - r2).abs() {
+        return PI * r_min * r_min;
+    }
+    let a1 = ((d * d + r1 * r1 - r2 * r2) / (2. * d * r1))
+        .clamp(-1., 1.)
+        .acos();
+    let a2 = ((d * d + r2 * r2 - r1 * r1) / (2. * d * r2))
+        .clamp(-1., 1.)
+        .acos();
+    let triangle = 0.5 * ((-d + r1 + r2) * (d + r1 - r2) * (d - r1 + r2) * (d + r1 + r2)).sqrt();
+    r1 * r1 * a1 + r2 * r2 * a2 - triangle
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::{
+        planets::{jupiter, saturn},
+        stars::sun,
+    };
+    use uom::si::time::hour;
+
+    use super::*;
+
+    #[test]
+    fn jupiter_dims_the_sun_by_one_percent() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let planet = system.add_planet_data(jupiter().to_planet_data()).unwrap();
+        let curve = system.get_transit_light_curve(planet, 101).unwrap();
+        assert!((curve.depth - 0.0105).abs() < 0.001);
+        assert!((curve.duration.get::<hour>() - 33.).abs() < 2.);
+        let (_, flux_at_start) = curve.samples[0];
+        let (_, flux_at_middle) = curve.samples[50];
+        assert!((flux_at_start - 1.).abs() < 1e-9);
+        assert!((flux_at_middle - (1. - curve.depth)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn planets_that_share_a_name_have_their_own_transits() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let jupiter = system.add_planet_data(jupiter().to_planet_data()).unwrap();
+        let namesake = system.add_planet_data(saturn().to_planet_data()).unwrap();
+        let index = system.get_planet_index(namesake).unwrap();
+        let mut renamed = saturn().to_planet_data();
+        renamed.set_name("Jupiter".to_string());
+        system.overwrite_planet_data(index, renamed);
+        let jupiter_curve = system.get_transit_light_curve(jupiter, 101).unwrap();
+        let namesake_curve = system.get_transit_light_curve(namesake, 101).unwrap();
+        assert!((jupiter_curve.duration.get::<hour>() - 33.).abs() < 2.);
+        assert!(namesake_curve.duration > jupiter_curve.duration);
+    }
+}
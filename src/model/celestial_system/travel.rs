@@ -0,0 +1,51 @@
+use astro_coords::cartesian::Cartesian;
+
+use super::CelestialSystem;
+use crate::model::travel::TravelEndpoint;
+
+impl CelestialSystem {
+    /*
+     * The central body, all planets at their current positions and all named distant stars with known data.
+     */
+    pub(crate) fn get_travel_endpoints(&self) -> Vec<TravelEndpoint> {
+        let mut endpoints = vec![TravelEndpoint {
+            name: self.central_body.get_name().clone(),
+            position: Cartesian::origin(),
+        }];
+        for planet in self.get_planets() {
+            endpoints.push(TravelEndpoint {
+                name: planet.get_data().get_name().clone(),
+                position: planet.get_position().clone(),
+            });
+        }
+        for star in &self.distant_stars {
+            if let Some(data) = star.get_data() {
+                if !data.get_name().is_empty() {
+                    endpoints.push(TravelEndpoint {
+                        name: data.get_name().clone(),
+                        position: data.get_pos(self.time_since_epoch),
+                    });
+                }
+            }
+        }
+        endpoints
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::{planets::earth, stars::sun};
+    use uom::si::length::astronomical_unit;
+
+    use crate::model::celestial_system::CelestialSystem;
+
+    #[test]
+    fn earth_is_an_astronomical_unit_from_the_sun() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        system.add_planet_data(earth().to_planet_data()).unwrap();
+        let endpoints = system.get_travel_endpoints();
+        assert_eq!(endpoints.len(), 2);
+        let distance = endpoints[0].distance_to(&endpoints[1]);
+        assert!((distance.get::<astronomical_unit>() - 1.).abs() < 0.02);
+    }
+}
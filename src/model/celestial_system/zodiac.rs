@@ -0,0 +1,131 @@
+use astro_coords::{direction::Direction, traits::*};
+use astro_utils::astro_display::AstroDisplay;
+use uom::si::f64::Time;
+
+use crate::model::planet::{calc_pos, Planet};
+
+use super::{part::PartOfCelestialSystem, CelestialSystem};
+
+/*
+ * A stretch of the orbit during which the central body appears in front of one constellation.
+ */
+#[derive(Debug, Clone)]
+pub(crate) struct ZodiacPassage {
+    pub(crate) constellation: String,
+    pub(crate) entry: Time,
+    pub(crate) exit: Time,
+    pub(crate) entry_date: String,
+    pub(crate) exit_date: String,
+}
+
+/*
+ * The constellation of the star that is closest to the given direction.
+ * Constellations have no boundaries here, so the sky is divided between the stars that form them.
+ */
+fn closest_constellation(stars: &[(Direction, usize)], direction: &Direction) -> Option<usize> {
+    stars
+        .iter()
+        .map(|(star, constellation)| (star.angle_to(direction), *constellation))
+        .min_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, constellation)| constellation)
+}
+
+impl CelestialSystem {
+    fn format_date(&self, time_since_epoch: Time) -> String {
+        self.format_calendar_date(time_since_epoch)
+            .unwrap_or_else(|| time_since_epoch.astro_display())
+    }
+
+    /*
+     * The constellations the central body passes through over one orbit of the planet, starting at the current time.
+     * Entry and exit are accurate to one sample, and the first passage starts with the current time.
+     */
+    pub(crate) fn get_zodiac(
+        &self,
+        planet: &Planet,
+        sample_count: usize,
+    ) -> Option<Vec<ZodiacPassage>> {
+        let orbital_period = planet.get_derived_data()?.get_orbital_period();
+        if sample_count < 2 || !orbital_period.value.is_finite() || orbital_period.value <= 0. {
+            return None;
+        }
+        let central_body_name = self.central_body.get_name();
+        let stars: Vec<(Direction, usize)> = self
+            .constellations
+            .iter()
+            .enumerate()
+            .flat_map(|(index, constellation)| {
+                constellation
+                    .get_stars()
+                    .iter()
+                    .filter(|star| star.get_name() != central_body_name)
+                    .map(move |star| (star.get_pos().to_direction(), index))
+            })
+            .collect();
+
+        let start = self.time_since_epoch;
+        let mut passages: Vec<ZodiacPassage> = Vec::new();
+        for i in 0..sample_count {
+            let time = start + orbital_period * (i as f64 / sample_count as f64);
+            let planet_time = self.get_planet_time(planet.get_id(), time);
+            let pos = calc_pos(&self.central_body, planet_time, planet.get_data());
+            let to_central_body = (-&pos).to_direction().ok()?;
+            let index = closest_constellation(&stars, &to_central_body)?;
+            let name = self.constellations[index].get_name().to_string();
+            if passages
+                .last()
+                .is_some_and(|last| last.constellation == name)
+            {
+                continue;
+            }
+            if let Some(last) = passages.last_mut() {
+                last.exit = time;
+            }
+            passages.push(ZodiacPassage {
+                constellation: name,
+                entry: time,
+                exit: time,
+                entry_date: String::new(),
+                exit_date: String::new(),
+            });
+        }
+        if let Some(last) = passages.last_mut() {
+            last.exit = start + orbital_period;
+        }
+        for passage in passages.iter_mut() {
+            passage.entry_date = self.format_date(passage.entry);
+            passage.exit_date = self.format_date(passage.exit);
+        }
+        Some(passages)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::{
+        planets::earth,
+        stars::{all::get_many_stars, sun},
+    };
+
+    use crate::model::star_provenance::StarOrigin;
+
+    use super::*;
+
+    #[test]
+    fn sun_passes_through_leo_as_seen_from_earth() {
+        let mut system = CelestialSystem::new(sun().to_star_data());
+        let stars = get_many_stars().iter().map(|s| s.to_star_data()).collect();
+        system.add_stars_from_data(stars, StarOrigin::Hardcoded);
+        system.add_planet_data(earth().to_planet_data()).unwrap();
+        let planets = system.get_planets();
+        let zodiac = system.get_zodiac(&planets[0], 365).unwrap();
+        assert!(zodiac.len() > 5);
+        assert!(zodiac.iter().any(|passage| passage.constellation == "Leo"));
+        for passage in zodiac.iter() {
+            assert!(passage.entry < passage.exit);
+        }
+        for pair in zodiac.windows(2) {
+            assert_eq!(pair[0].exit, pair[1].entry);
+        }
+    }
+}
@@ -0,0 +1,288 @@
+use astro_coords::{direction::Direction, ecliptic::Ecliptic, traits::*};
+use astro_units::illuminance::apparent_magnitude_to_illuminance;
+use astro_utils::{color::srgb::sRGBColor, stars::appearance::StarAppearance};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use uom::si::{
+    angle::{degree, radian},
+    f64::{Angle, ThermodynamicTemperature, Time},
+    thermodynamic_temperature::kelvin,
+    time::year,
+};
+
+use crate::error::ElenathError;
+
+const OBLIQUITY_OF_THE_ECLIPTIC_IN_DEGREES: f64 = 23.4393;
+
+/*
+ * Source IDs are only unique within a data release, so the release is kept alongside them.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum GaiaRelease {
+    Dr2,
+    Dr3,
+}
+
+impl GaiaRelease {
+    pub(crate) const ALL: [GaiaRelease; 2] = [GaiaRelease::Dr2, GaiaRelease::Dr3];
+}
+
+impl Display for GaiaRelease {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GaiaRelease::Dr2 => write!(f, "Gaia DR2"),
+            GaiaRelease::Dr3 => write!(f, "Gaia DR3"),
+        }
+    }
+}
+
+/*
+ * What a catalog knows about a star beyond its appearance, for judging the quality of the measurement.
+ * Parallaxes are in milliarcseconds, as they are given in the catalog.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct CatalogEntry {
+    pub(crate) release: GaiaRelease,
+    pub(crate) source_id: u64,
+    #[serde(default)]
+    pub(crate) parallax: Option<f64>,
+    #[serde(default)]
+    pub(crate) parallax_error: Option<f64>,
+    #[serde(default)]
+    pub(crate) bp_rp: Option<f64>,
+    #[serde(default)]
+    pub(crate) hip: Option<u32>,
+}
+
+impl CatalogEntry {
+    pub(crate) fn designation(&self) -> String {
+        format!("{} {}", self.release, self.source_id)
+    }
+
+    /*
+     * Whether both entries name the same star, as far as the catalogs can tell.
+     * Source IDs are compared within a release, and the Hipparcos number links the releases.
+     */
+    pub(crate) fn cross_match(&self, other: &CatalogEntry) -> Option<bool> {
+        if self.release == other.release {
+            return Some(self.source_id == other.source_id);
+        }
+        Some(self.hip? == other.hip?)
+    }
+}
+
+/*
+ * The indices of the columns in a CSV export of the Gaia archive. The extra columns may be missing.
+ */
+struct Columns {
+    source_id: usize,
+    ra: usize,
+    dec: usize,
+    magnitude: usize,
+    parallax: Option<usize>,
+    parallax_error: Option<usize>,
+    bp_rp: Option<usize>,
+    hip: Option<usize>,
+}
+
+impl Columns {
+    fn from_header(header: &str) -> Result<Self, ElenathError> {
+        let names: Vec<&str> = header.split(',').map(|name| name.trim()).collect();
+        let optional = |name: &str| names.iter().position(|n| *n == name);
+        let required = |name: &str| {
+            optional(name).ok_or(ElenathError::Generic(format!(
+                "The Gaia export lacks the column \"{}\".",
+                name
+            )))
+        };
+        Ok(Columns {
+            source_id: required("source_id")?,
+            ra: required("ra")?,
+            dec: required("dec")?,
+            magnitude: required("phot_g_mean_mag")?,
+            parallax: optional("parallax"),
+            parallax_error: optional("parallax_error"),
+            bp_rp: optional("bp_rp"),
+            hip: optional("hip").or(optional("original_ext_source_id")),
+        })
+    }
+}
+
+/*
+ * Reads a CSV file as it is exported by the Gaia archive, with one star per line.
+ * Both releases name the columns alike. Without extra columns, only the source ID is kept besides the appearance.
+ * A Hipparcos cross-match is kept whenever the export contains one, because it identifies the star across releases.
+ * Stars without a position or a magnitude are skipped.
+ */
+pub(crate) fn read_gaia_export(
+    content: &str,
+    release: GaiaRelease,
+    extra_columns: bool,
+) -> Result<Vec<(StarAppearance, CatalogEntry)>, ElenathError> {
+    let mut lines = content
+        .lines()
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'));
+    let header = lines.next().ok_or(ElenathError::Generic(
+        "The Gaia export is empty.".to_string(),
+    ))?;
+    let columns = Columns::from_header(header)?;
+
+    let mut stars = Vec::new();
+    for line in lines {
+        let values: Vec<&str> = line.split(',').map(|value| value.trim()).collect();
+        let number = |index: usize| values.get(index)?.parse::<f64>().ok();
+        let optional_number = |index: Option<usize>| number(index?);
+        let extra = |value: Option<f64>| value.filter(|_| extra_columns);
+        let (Some(source_id), Some(ra), Some(dec), Some(magnitude)) = (
+            values.get(columns.source_id).and_then(|id| id.parse().ok()),
+            number(columns.ra),
+            number(columns.dec),
+            number(columns.magnitude),
+        ) else {
+            continue;
+        };
+        let bp_rp = optional_number(columns.bp_rp);
+        let entry = CatalogEntry {
+            release,
+            source_id,
+            parallax: extra(optional_number(columns.parallax)),
+            parallax_error: extra(optional_number(columns.parallax_error)),
+            bp_rp: extra(bp_rp),
+            hip: columns
+                .hip
+                .and_then(|index| values.get(index))
+                .and_then(|hip| hip.parse().ok()),
+        };
+        let appearance = StarAppearance::new(
+            entry.designation(),
+            apparent_magnitude_to_illuminance(magnitude),
+            color_from_bp_rp(bp_rp),
+            equatorial_to_ecliptic(Angle::new::<degree>(ra), Angle::new::<degree>(dec)),
+            Time::new::<year>(0.),
+        );
+        stars.push((appearance, entry));
+    }
+    Ok(stars)
+}
+
+/*
+ * The colour index is used for the colour even if it is not kept, because the appearance needs a colour in any case.
+ * The effective temperature follows the relation for dwarfs by Mucciarelli & Bellazzini (2020).
+ */
+fn color_from_bp_rp(bp_rp: Option<f64>) -> sRGBColor {
+    let Some(bp_rp) = bp_rp else {
+        return sRGBColor::from_sRGB(1., 1., 1.);
+    };
+    let theta = 0.4929 + 0.5092 * bp_rp - 0.0353 * bp_rp.powi(2);
+    let temperature = 5040. / theta.max(0.1);
+    sRGBColor::from_temperature(ThermodynamicTemperature::new::<kelvin>(temperature))
+}
+
+fn equatorial_to_ecliptic(ra: Angle, dec: Angle) -> Ecliptic {
+    let (ra, dec) = (ra.get::<radian>(), dec.get::<radian>());
+    let epsilon = OBLIQUITY_OF_THE_ECLIPTIC_IN_DEGREES.to_radians();
+    let x = dec.cos() * ra.cos();
+    let y = dec.cos() * ra.sin();
+    let z = dec.sin();
+    let (sin_e, cos_e) = (epsilon.sin(), epsilon.cos());
+    Direction::new(x, y * cos_e + z * sin_e, -y * sin_e + z * cos_e)
+        .unwrap_or(Direction::X)
+        .to_ecliptic()
+}
+
+/*
+ * The inverse of the conversion on import, so that exported stars land where the catalogs put them.
+ */
+pub(crate) fn ecliptic_to_equatorial(ecliptic: &Ecliptic) -> (Angle, Angle) {
+    let longitude = ecliptic.spherical.longitude.get::<radian>();
+    let latitude = ecliptic.spherical.latitude.get::<radian>();
+    let epsilon = OBLIQUITY_OF_THE_ECLIPTIC_IN_DEGREES.to_radians();
+    let x = latitude.cos() * longitude.cos();
+    let y = latitude.cos() * longitude.sin();
+    let z = latitude.sin();
+    let (sin_e, cos_e) = (epsilon.sin(), epsilon.cos());
+    let (y, z) = (y * cos_e - z * sin_e, y * sin_e + z * cos_e);
+    let ra = y.atan2(x).rem_euclid(2. * std::f64::consts::PI);
+    let dec = z.clamp(-1., 1.).asin();
+    (Angle::new::<radian>(ra), Angle::new::<radian>(dec))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = "\
+source_id,ra,dec,parallax,parallax_error,phot_g_mean_mag,bp_rp
+4295806720,44.99615537864534,0.005615226341865997,6.0,0.1,10.5,0.8
+34361129088,45.004316164207644,0.021045032689817285,,,11.2,
+123,45.0,0.0,1.0,0.5,,1.0
+";
+
+    #[test]
+    fn extra_columns_are_only_kept_on_request() {
+        let stars = read_gaia_export(EXPORT, GaiaRelease::Dr3, true).unwrap();
+        assert_eq!(stars.len(), 2);
+        let (appearance, entry) = &stars[0];
+        assert_eq!(appearance.get_name(), "Gaia DR3 4295806720");
+        assert_eq!(entry.parallax_error, Some(0.1));
+        assert_eq!(entry.bp_rp, Some(0.8));
+        assert_eq!(stars[1].1.parallax, None);
+
+        let stars = read_gaia_export(EXPORT, GaiaRelease::Dr2, false).unwrap();
+        assert_eq!(stars[0].1.parallax_error, None);
+        assert_eq!(stars[0].1.bp_rp, None);
+        assert_eq!(stars[0].1.release, GaiaRelease::Dr2);
+    }
+
+    #[test]
+    fn releases_are_linked_by_the_hipparcos_number() {
+        let entry = |release, source_id, hip| CatalogEntry {
+            release,
+            source_id,
+            parallax: None,
+            parallax_error: None,
+            bp_rp: None,
+            hip,
+        };
+        let dr3 = entry(GaiaRelease::Dr3, 1, Some(42));
+        assert_eq!(
+            dr3.cross_match(&entry(GaiaRelease::Dr3, 2, Some(42))),
+            Some(false)
+        );
+        assert_eq!(
+            dr3.cross_match(&entry(GaiaRelease::Dr2, 2, Some(42))),
+            Some(true)
+        );
+        assert_eq!(
+            dr3.cross_match(&entry(GaiaRelease::Dr2, 1, Some(7))),
+            Some(false)
+        );
+        assert_eq!(dr3.cross_match(&entry(GaiaRelease::Dr2, 1, None)), None);
+    }
+
+    #[test]
+    fn an_export_without_positions_is_rejected() {
+        assert!(
+            read_gaia_export("source_id,phot_g_mean_mag\n1,5.0", GaiaRelease::Dr3, true).is_err()
+        );
+        assert!(read_gaia_export("", GaiaRelease::Dr3, true).is_err());
+    }
+
+    #[test]
+    fn the_celestial_north_pole_is_tilted_by_the_obliquity() {
+        let pole = equatorial_to_ecliptic(Angle::new::<degree>(0.), Angle::new::<degree>(90.));
+        let latitude = pole.spherical.latitude.get::<degree>();
+        assert!((latitude - (90. - OBLIQUITY_OF_THE_ECLIPTIC_IN_DEGREES)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn equatorial_coordinates_survive_the_round_trip() {
+        for (ra, dec) in [(10., 20.), (200., -60.), (359., 1.)] {
+            let ecliptic =
+                equatorial_to_ecliptic(Angle::new::<degree>(ra), Angle::new::<degree>(dec));
+            let (ra_back, dec_back) = ecliptic_to_equatorial(&ecliptic);
+            assert!((ra_back.get::<degree>() - ra).abs() < 1e-6);
+            assert!((dec_back.get::<degree>() - dec).abs() < 1e-6);
+        }
+    }
+}
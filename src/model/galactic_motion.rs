@@ -0,0 +1,274 @@
+use astro_coords::cartesian::Cartesian;
+use astro_utils::stars::data::StarData;
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use uom::si::{
+    f64::{Length, Time, Velocity},
+    length::meter,
+    time::{second, year},
+    velocity::{kilometer_per_second, meter_per_second},
+};
+
+use super::{body_id::BodyId, star_generation::SplitMix64};
+
+// Oort constants in km/s/kpc.
+const OORT_A: f64 = 15.3;
+const OORT_B: f64 = -11.9;
+const METERS_PER_KILOPARSEC: f64 = 3.0857e19;
+// Ecliptic longitude and latitude in degrees (J2000).
+const GALACTIC_NORTH_POLE: (f64, f64) = (180.023, 29.811);
+const GALACTIC_CENTER: (f64, f64) = (266.840, -5.536);
+
+/*
+ * Lets distant stars drift away from their epoch positions over long timescales.
+ * Star velocities relative to the central body are composed of a differential galactic rotation,
+ * linearised around the central body via the Oort constants, and a random peculiar velocity.
+ * The peculiar velocity is derived from the star ID, so a star keeps it across sessions without storing it.
+ * A known radial velocity replaces the random line-of-sight component.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct GalacticMotion {
+    pub(crate) enabled: bool,
+    pub(crate) velocity_dispersion: Velocity,
+}
+
+impl Default for GalacticMotion {
+    fn default() -> Self {
+        GalacticMotion {
+            enabled: false,
+            velocity_dispersion: Velocity::new::<kilometer_per_second>(20.),
+        }
+    }
+}
+
+impl GalacticMotion {
+    /*
+     * Drifting stars are only recalculated after this much time has passed, because they barely move in between.
+     */
+    pub(crate) fn recalculation_interval() -> Time {
+        Time::new::<year>(1000.)
+    }
+
+    /*
+     * The velocity of a star in ecliptic coordinates, in m/s.
+     */
+    pub(crate) fn velocity(
+        &self,
+        position: &Cartesian,
+        id: BodyId,
+        radial_velocity: Option<Velocity>,
+    ) -> [f64; 3] {
+        let position = to_meters(position);
+        let (x_axis, y_axis, z_axis) = galactic_axes();
+        let rotation = rotation_velocity(&position, &x_axis, &y_axis);
+
+        let mut random = SplitMix64(id.as_u64().wrapping_mul(0x2545F4914F6CDD1D));
+        let sigma = self.velocity_dispersion.get::<meter_per_second>();
+        let mut peculiar = [0.; 3];
+        for component in peculiar.iter_mut() {
+            *component = sigma * standard_normal(&mut random);
+        }
+        let peculiar = add(
+            &add(&scale(&x_axis, peculiar[0]), &scale(&y_axis, peculiar[1])),
+            &scale(&z_axis, peculiar[2]),
+        );
+        let mut velocity = add(&rotation, &peculiar);
+
+        if let Some(radial_velocity) = radial_velocity {
+            let distance = dot(&position, &position).sqrt();
+            if distance > 0. {
+                let line_of_sight = scale(&position, 1. / distance);
+                let current = dot(&velocity, &line_of_sight);
+                let target = radial_velocity.get::<meter_per_second>();
+                velocity = add(&velocity, &scale(&line_of_sight, target - current));
+            }
+        }
+        velocity
+    }
+
+    /*
+     * A copy of the star data that has moved along its velocity since the epoch.
+     */
+    pub(crate) fn moved(
+        &self,
+        data: &StarData,
+        id: BodyId,
+        radial_velocity: Option<Velocity>,
+        time_since_epoch: Time,
+    ) -> StarData {
+        let velocity = self.velocity(data.get_pos_at_epoch(), id, radial_velocity);
+        displaced(data, &velocity, time_since_epoch)
+    }
+
+    /*
+     * A copy of the star data that has moved along a velocity that was assigned to the star, e.g. at its birth.
+     * This velocity replaces the random peculiar velocity, but the star still takes part in the galactic rotation.
+     */
+    pub(crate) fn moved_with_own_velocity(
+        &self,
+        data: &StarData,
+        own_velocity: &[Velocity; 3],
+        elapsed_time: Time,
+    ) -> StarData {
+        let mut velocity = own_velocity.map(|v| v.get::<meter_per_second>());
+        if self.enabled {
+            let (x_axis, y_axis, _) = galactic_axes();
+            let position = to_meters(data.get_pos_at_epoch());
+            velocity = add(&velocity, &rotation_velocity(&position, &x_axis, &y_axis));
+        }
+        displaced(data, &velocity, elapsed_time)
+    }
+}
+
+/*
+ * An isotropic random velocity, as for the members of a young star cluster.
+ */
+pub(crate) fn random_velocity(dispersion: Velocity, random: &mut SplitMix64) -> [Velocity; 3] {
+    [(); 3].map(|_| dispersion * standard_normal(random))
+}
+
+fn displaced(data: &StarData, velocity: &[f64; 3], elapsed_time: Time) -> StarData {
+    let position = data.get_pos_at_epoch();
+    let seconds = elapsed_time.get::<second>();
+    let displacement = |axis: usize| Length::new::<meter>(velocity[axis] * seconds);
+    let moved_position = Cartesian::new(
+        position.x + displacement(0),
+        position.y + displacement(1),
+        position.z + displacement(2),
+    );
+    let mut moved = data.clone();
+    moved.set_pos_at_epoch(moved_position);
+    moved
+}
+
+/*
+ * The differential galactic rotation relative to the central body, linearised via the Oort constants.
+ */
+fn rotation_velocity(position: &[f64; 3], x_axis: &[f64; 3], y_axis: &[f64; 3]) -> [f64; 3] {
+    let x = dot(position, x_axis);
+    let y = dot(position, y_axis);
+    let per_second = 1e3 / METERS_PER_KILOPARSEC;
+    let v_x = (OORT_A - OORT_B) * per_second * y;
+    let v_y = (OORT_A + OORT_B) * per_second * x;
+    add(&scale(x_axis, v_x), &scale(y_axis, v_y))
+}
+
+fn to_meters(position: &Cartesian) -> [f64; 3] {
+    [
+        position.x.get::<meter>(),
+        position.y.get::<meter>(),
+        position.z.get::<meter>(),
+    ]
+}
+
+fn galactic_axes() -> ([f64; 3], [f64; 3], [f64; 3]) {
+    let z_axis = ecliptic_unit_vector(GALACTIC_NORTH_POLE);
+    let center = ecliptic_unit_vector(GALACTIC_CENTER);
+    let x_axis = add(&center, &scale(&z_axis, -dot(&center, &z_axis)));
+    let x_axis = scale(&x_axis, 1. / dot(&x_axis, &x_axis).sqrt());
+    let y_axis = cross(&z_axis, &x_axis);
+    (x_axis, y_axis, z_axis)
+}
+
+fn ecliptic_unit_vector((longitude, latitude): (f64, f64)) -> [f64; 3] {
+    let (longitude, latitude) = (longitude.to_radians(), latitude.to_radians());
+    [
+        latitude.cos() * longitude.cos(),
+        latitude.cos() * longitude.sin(),
+        latitude.sin(),
+    ]
+}
+
+pub(crate) fn standard_normal(random: &mut SplitMix64) -> f64 {
+    // Box-Muller transform. 1 - u keeps the logarithm finite.
+    let u = 1. - random.next_f64();
+    let v = random.next_f64();
+    (-2. * u.ln()).sqrt() * (2. * PI * v).cos()
+}
+
+fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: &[f64; 3], b: &[f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn add(a: &[f64; 3], b: &[f64; 3]) -> [f64; 3] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn scale(a: &[f64; 3], factor: f64) -> [f64; 3] {
+    [a[0] * factor, a[1] * factor, a[2] * factor]
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_utils::real_data::stars::sun;
+    use uom::si::length::light_year;
+
+    use super::*;
+
+    #[test]
+    fn galactic_axes_are_orthonormal() {
+        let (x, y, z) = galactic_axes();
+        for axis in [&x, &y, &z] {
+            assert!((dot(axis, axis) - 1.).abs() < 1e-9);
+        }
+        assert!(dot(&x, &y).abs() < 1e-9);
+        assert!(dot(&x, &z).abs() < 1e-9);
+        assert!(dot(&y, &z).abs() < 1e-9);
+    }
+
+    #[test]
+    fn star_at_the_central_body_only_moves_by_dispersion() {
+        let motion = GalacticMotion {
+            enabled: true,
+            velocity_dispersion: Velocity::new::<kilometer_per_second>(0.),
+        };
+        let velocity = motion.velocity(&Cartesian::origin(), BodyId::default().next(), None);
+        assert!(dot(&velocity, &velocity).sqrt() < 1e-9);
+    }
+
+    #[test]
+    fn known_radial_velocity_is_kept() {
+        let motion = GalacticMotion::default();
+        let distance = Length::new::<light_year>(100.);
+        let position = Cartesian::new(distance, distance * 0., distance * 0.);
+        let radial_velocity = Velocity::new::<kilometer_per_second>(-42.);
+        let velocity = motion.velocity(&position, BodyId::default().next(), Some(radial_velocity));
+        assert!((velocity[0] - radial_velocity.get::<meter_per_second>()).abs() < 1e-6);
+    }
+
+    #[test]
+    fn peculiar_velocity_is_stable_per_star() {
+        let motion = GalacticMotion::default();
+        let position = Cartesian::origin();
+        let id = BodyId::default().next();
+        assert_eq!(
+            motion.velocity(&position, id, None),
+            motion.velocity(&position, id, None)
+        );
+        assert_ne!(
+            motion.velocity(&position, id, None),
+            motion.velocity(&position, id.next(), None)
+        );
+    }
+
+    #[test]
+    fn own_velocity_moves_star_without_galactic_motion() {
+        let motion = GalacticMotion::default();
+        let data = sun().to_star_data();
+        let speed = Velocity::new::<kilometer_per_second>(1.);
+        let own_velocity = [speed, speed * 0., speed * 0.];
+        let elapsed_time = Time::new::<year>(1e8);
+        let moved = motion.moved_with_own_velocity(&data, &own_velocity, elapsed_time);
+        let expected = speed * elapsed_time;
+        let actual = moved.get_pos_at_epoch().x - data.get_pos_at_epoch().x;
+        assert!(((actual - expected) / expected).value.abs() < 1e-9);
+    }
+}
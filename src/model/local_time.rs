@@ -0,0 +1,271 @@
+use astro_coords::{direction::Direction, equatorial::Equatorial, spherical::Spherical};
+use astro_utils::planets::{planet_data::PlanetData, surface_normal::surface_normal_at_time};
+use std::f64::consts::PI;
+use uom::si::{
+    angle::{degree, radian},
+    f64::{Angle, Time},
+};
+
+use super::planet::Planet;
+
+/*
+ * The sidereal day is one rotation with respect to the stars, the mean solar day one rotation with respect to the central body.
+ * A tidally locked planet has no solar day.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct DayLengths {
+    pub(crate) sidereal: Time,
+    pub(crate) mean_solar: Option<Time>,
+}
+
+fn finite_and_nonzero(time: Time) -> Option<Time> {
+    if time.value.is_finite() && time.value != 0. {
+        Some(time.abs())
+    } else {
+        None
+    }
+}
+
+impl DayLengths {
+    pub(crate) fn of(planet: &Planet) -> Option<DayLengths> {
+        let sidereal = finite_and_nonzero(planet.get_data().get_sideral_rotation_period())?;
+        let mean_solar = planet
+            .get_derived_data()
+            .and_then(|derived| finite_and_nonzero(derived.get_mean_synodic_day()));
+        Some(DayLengths {
+            sidereal,
+            mean_solar,
+        })
+    }
+}
+
+/*
+ * The direction straight up from a location on the surface of the planet.
+ */
+pub(crate) fn observer_normal(
+    planet: &PlanetData,
+    rotation_angle_at_epoch: Angle,
+    surface_position: Spherical,
+    time_since_epoch: Time,
+) -> Direction {
+    let observer_equatorial_position =
+        Equatorial::new(surface_position, planet.get_rotation_axis().clone());
+    surface_normal_at_time(
+        observer_equatorial_position,
+        rotation_angle_at_epoch,
+        time_since_epoch,
+        planet.get_sideral_rotation_period(),
+    )
+}
+
+/*
+ * The surface location that has the central body straight overhead.
+ * The surface normals at two points on the equator and at the north pole span the rotating frame of the planet,
+ * so the direction to the central body only needs to be expressed in them.
+ */
+pub(crate) fn subsolar_point(
+    planet: &PlanetData,
+    rotation_angle_at_epoch: Angle,
+    to_central_body: &Direction,
+    time_since_epoch: Time,
+) -> Spherical {
+    let normal_at = |longitude: f64, latitude: f64| {
+        let surface_position = Spherical::new(
+            Angle::new::<degree>(longitude),
+            Angle::new::<degree>(latitude),
+        );
+        observer_normal(
+            planet,
+            rotation_angle_at_epoch,
+            surface_position,
+            time_since_epoch,
+        )
+    };
+    let central_body = components(to_central_body);
+    let x = dot(&central_body, &components(&normal_at(0., 0.)));
+    let y = dot(&central_body, &components(&normal_at(90., 0.)));
+    let z = dot(&central_body, &components(&normal_at(0., 90.)));
+    Spherical::new(
+        Angle::new::<radian>(y.atan2(x)),
+        Angle::new::<radian>(z.clamp(-1., 1.).asin()),
+    )
+}
+
+fn components(direction: &Direction) -> [f64; 3] {
+    [direction.x(), direction.y(), direction.z()]
+}
+
+fn dot(a: &[f64; 3], b: &[f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+fn cross(a: &[f64; 3], b: &[f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+fn perpendicular_to(vector: &[f64; 3], axis: &[f64; 3]) -> [f64; 3] {
+    let along = dot(vector, axis);
+    [
+        vector[0] - along * axis[0],
+        vector[1] - along * axis[1],
+        vector[2] - along * axis[2],
+    ]
+}
+
+/*
+ * The local solar time as a fraction of a solar day, with 0 at local midnight and 0.5 at local noon.
+ * It follows from the hour angle of the central body, measured around the rotation axis of the planet.
+ * At the poles, or if the central body lies on the rotation axis, there is no local solar time.
+ */
+pub(crate) fn local_solar_time(
+    rotation_axis: &Direction,
+    observer_normal: &Direction,
+    to_central_body: &Direction,
+    sideral_rotation_period: Time,
+) -> Option<f64> {
+    const ACCURACY: f64 = 1e-9;
+
+    let axis = [rotation_axis.x(), rotation_axis.y(), rotation_axis.z()];
+    let meridian = [
+        observer_normal.x(),
+        observer_normal.y(),
+        observer_normal.z(),
+    ];
+    let central_body = [
+        to_central_body.x(),
+        to_central_body.y(),
+        to_central_body.z(),
+    ];
+    let meridian = perpendicular_to(&meridian, &axis);
+    let central_body = perpendicular_to(&central_body, &axis);
+    if dot(&meridian, &meridian) < ACCURACY || dot(&central_body, &central_body) < ACCURACY {
+        return None;
+    }
+
+    // Before noon the rotating surface still turns towards the central body, so it lies ahead of the meridian.
+    let ahead = dot(&cross(&meridian, &central_body), &axis).atan2(dot(&meridian, &central_body));
+    let hour_angle = if sideral_rotation_period.value < 0. {
+        ahead
+    } else {
+        -ahead
+    };
+    Some((0.5 + hour_angle / (2. * PI)).rem_euclid(1.))
+}
+
+/*
+ * The angle of a direction around the rotation axis, counted in the sense of rotation of the planet.
+ * The zero point is arbitrary but fixed, so only differences of right ascensions are meaningful.
+ */
+pub(crate) fn right_ascension(
+    rotation_axis: &Direction,
+    direction: &Direction,
+    sideral_rotation_period: Time,
+) -> Option<f64> {
+    const ACCURACY: f64 = 1e-9;
+
+    let reference = rotation_axis.some_orthogonal_vector();
+    let axis = [rotation_axis.x(), rotation_axis.y(), rotation_axis.z()];
+    let reference = [reference.x(), reference.y(), reference.z()];
+    let direction = perpendicular_to(&[direction.x(), direction.y(), direction.z()], &axis);
+    if dot(&direction, &direction) < ACCURACY {
+        return None;
+    }
+    let angle = dot(&cross(&reference, &direction), &axis).atan2(dot(&reference, &direction));
+    if sideral_rotation_period.value < 0. {
+        Some(-angle)
+    } else {
+        Some(angle)
+    }
+}
+
+/*
+ * Formats a fraction of a day as a 24 hour clock reading.
+ */
+pub(crate) fn format_local_time(fraction_of_day: f64) -> String {
+    let minutes = (fraction_of_day.rem_euclid(1.) * 24. * 60.).floor() as u32 % (24 * 60);
+    format!("{:02}:{:02}", minutes / 60, minutes % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_coords::traits::*;
+    use astro_utils::real_data::{planets::earth, stars::sun};
+    use uom::si::time::{day, hour};
+
+    use super::*;
+
+    const TEST_ACCURACY: f64 = 1e-9;
+
+    fn local_time_with_central_body_at(x: f64, y: f64, z: f64) -> Option<f64> {
+        local_solar_time(
+            &Direction::Z,
+            &Direction::X,
+            &Direction::new(x, y, z).unwrap(),
+            Time::new::<day>(1.),
+        )
+    }
+
+    #[test]
+    fn local_solar_time_follows_the_central_body() {
+        let noon = local_time_with_central_body_at(1., 0., 0.).unwrap();
+        assert!((noon - 0.5).abs() < TEST_ACCURACY);
+        let morning = local_time_with_central_body_at(0., 1., 0.).unwrap();
+        assert!((morning - 0.25).abs() < TEST_ACCURACY);
+        let evening = local_time_with_central_body_at(0., -1., 0.3).unwrap();
+        assert!((evening - 0.75).abs() < TEST_ACCURACY);
+        let midnight = local_time_with_central_body_at(-1., 0., 0.).unwrap();
+        assert!(midnight.abs() < TEST_ACCURACY || (midnight - 1.).abs() < TEST_ACCURACY);
+    }
+
+    #[test]
+    fn there_is_no_local_solar_time_at_the_poles() {
+        let local_time = local_solar_time(
+            &Direction::Z,
+            &Direction::Z,
+            &Direction::X,
+            Time::new::<day>(1.),
+        );
+        assert!(local_time.is_none());
+    }
+
+    #[test]
+    fn central_body_is_overhead_at_the_subsolar_point() {
+        let planet = earth().to_planet_data();
+        let rotation_angle_at_epoch = Angle::new::<degree>(20.);
+        let time = Time::new::<hour>(7.);
+        for to_central_body in [
+            Direction::new(1., 0.3, 0.2).unwrap(),
+            Direction::new(-0.5, 1., -0.4).unwrap(),
+        ] {
+            let point = subsolar_point(&planet, rotation_angle_at_epoch, &to_central_body, time);
+            let zenith = observer_normal(&planet, rotation_angle_at_epoch, point, time);
+            assert!(zenith.angle_to(&to_central_body).get::<degree>() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn formatting_local_time() {
+        assert_eq!(format_local_time(0.), "00:00");
+        assert_eq!(format_local_time(0.5), "12:00");
+        assert_eq!(format_local_time(0.75 + 1. / 24. / 2.), "18:30");
+    }
+
+    #[test]
+    fn earth_day_lengths() {
+        let earth = Planet::new(
+            earth().to_planet_data(),
+            &sun().to_star_data(),
+            None,
+            Time::new::<day>(0.),
+            None,
+        );
+        let days = DayLengths::of(&earth).unwrap();
+        assert!((days.sidereal.get::<hour>() - 23.934).abs() < 0.01);
+        let mean_solar = days.mean_solar.unwrap();
+        assert!((mean_solar.get::<hour>() - 24.).abs() < 0.01);
+    }
+}
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use uom::si::f64::{Length, Time};
+
+use super::body_id::BodyId;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct MigrationKeyframe {
+    pub(crate) time: Time,
+    pub(crate) semi_major_axis: Length,
+}
+
+/*
+ * Scripts how the semi-major axis of a planet changes over time, e.g. for a migrating gas giant.
+ * Between keyframes the semi-major axis is interpolated linearly, before the first and after the last one it is held.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct MigrationSchedule {
+    pub(crate) planet: BodyId,
+    keyframes: Vec<MigrationKeyframe>,
+}
+
+impl MigrationSchedule {
+    pub(crate) fn new(planet: BodyId, mut keyframes: Vec<MigrationKeyframe>) -> Self {
+        keyframes.sort_by(|a, b| a.time.partial_cmp(&b.time).unwrap_or(Ordering::Equal));
+        MigrationSchedule { planet, keyframes }
+    }
+
+    pub(crate) fn get_keyframes(&self) -> &[MigrationKeyframe] {
+        &self.keyframes
+    }
+
+    /*
+     * The same schedule, relative to an epoch that lies the given time later.
+     */
+    pub(crate) fn shifted(&self, shift: Time) -> MigrationSchedule {
+        let keyframes = self
+            .keyframes
+            .iter()
+            .map(|keyframe| MigrationKeyframe {
+                time: keyframe.time - shift,
+                ..*keyframe
+            })
+            .collect();
+        MigrationSchedule::new(self.planet, keyframes)
+    }
+
+    pub(crate) fn semi_major_axis_at(&self, time: Time) -> Option<Length> {
+        let first = self.keyframes.first()?;
+        let last = self.keyframes.last()?;
+        if time <= first.time {
+            return Some(first.semi_major_axis);
+        }
+        if time >= last.time {
+            return Some(last.semi_major_axis);
+        }
+        let next = self.keyframes.iter().position(|k| k.time > time)?;
+        let (before, after) = (&self.keyframes[next - 1], &self.keyframes[next]);
+        let fraction = ((time - before.time) / (after.time - before.time)).value;
+        Some(before.semi_major_axis + (after.semi_major_axis - before.semi_major_axis) * fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uom::si::{length::astronomical_unit, time::year};
+
+    use super::*;
+
+    fn keyframe(years: f64, au: f64) -> MigrationKeyframe {
+        MigrationKeyframe {
+            time: Time::new::<year>(years),
+            semi_major_axis: Length::new::<astronomical_unit>(au),
+        }
+    }
+
+    #[test]
+    fn empty_schedule_has_no_semi_major_axis() {
+        let schedule = MigrationSchedule::new(BodyId::default().next(), vec![]);
+        assert!(schedule.semi_major_axis_at(Time::new::<year>(0.)).is_none());
+    }
+
+    #[test]
+    fn semi_major_axis_is_interpolated_and_held() {
+        let schedule = MigrationSchedule::new(
+            BodyId::default().next(),
+            vec![keyframe(2e6, 1.5), keyframe(1e6, 3.5)],
+        );
+        let at = |years: f64| {
+            schedule
+                .semi_major_axis_at(Time::new::<year>(years))
+                .unwrap()
+                .get::<astronomical_unit>()
+        };
+        assert!((at(0.) - 3.5).abs() < 1e-9);
+        assert!((at(1.5e6) - 2.5).abs() < 1e-9);
+        assert!((at(3e6) - 1.5).abs() < 1e-9);
+    }
+}
@@ -1,3 +1,23 @@
+pub(super) mod appearance_override;
+pub(super) mod body_id;
+pub(super) mod body_notes;
+pub(super) mod calendar;
 pub(super) mod celestial_system;
+pub(super) mod gaia_export;
+pub(super) mod galactic_motion;
+pub(super) mod local_time;
+pub(super) mod migration;
+pub(super) mod observer_location;
 pub(super) mod planet;
+pub(super) mod resonance;
+pub(super) mod space_engine;
 pub(super) mod star;
+pub(super) mod star_catalog_filter;
+pub(super) mod star_formation;
+pub(super) mod star_generation;
+pub(super) mod star_jitter;
+pub(super) mod star_naming;
+pub(super) mod star_provenance;
+pub(super) mod travel;
+pub(super) mod universe;
+pub(super) mod votable;
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+use uom::si::f64::Angle;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct ObserverLocation {
+    name: String,
+    longitude: Angle,
+    latitude: Angle,
+}
+
+impl ObserverLocation {
+    pub(crate) fn new(name: String, longitude: Angle, latitude: Angle) -> Self {
+        ObserverLocation {
+            name,
+            longitude,
+            latitude,
+        }
+    }
+
+    pub(crate) fn get_name(&self) -> &String {
+        &self.name
+    }
+
+    pub(crate) fn get_longitude(&self) -> Angle {
+        self.longitude
+    }
+
+    pub(crate) fn get_latitude(&self) -> Angle {
+        self.latitude
+    }
+}
+
+impl Display for ObserverLocation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
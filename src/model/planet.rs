@@ -1,17 +1,29 @@
-use astro_coords::cartesian::Cartesian;
+use astro_coords::{cartesian::Cartesian, traits::*};
 use astro_utils::{
     planets::{derived_data::DerivedPlanetData, planet_data::PlanetData},
     stars::data::StarData,
 };
-use uom::si::f64::Time;
+use std::f64::consts::PI;
+use uom::si::{
+    angle::radian,
+    f64::{Angle, Length, Time},
+    length::meter,
+    mass::kilogram,
+    time::second,
+};
 
-use super::celestial_system::part::{BodyType, PartOfCelestialSystem};
+use super::{
+    body_id::BodyId,
+    celestial_system::part::{BodyType, PartOfCelestialSystem},
+};
 
 pub(crate) struct Planet {
     data: PlanetData,
     derived_data: Option<DerivedPlanetData>,
     pos: Cartesian,
     index: Option<usize>,
+    id: Option<BodyId>,
+    rotation_angle_at_epoch: Angle,
 }
 
 impl Planet {
@@ -29,9 +41,23 @@ impl Planet {
             derived_data,
             pos,
             index,
+            id: None,
+            rotation_angle_at_epoch: Angle::new::<radian>(0.),
         }
     }
 
+    pub(crate) fn set_id(&mut self, id: Option<BodyId>) {
+        self.id = id;
+    }
+
+    pub(crate) fn set_rotation_angle_at_epoch(&mut self, angle: Angle) {
+        self.rotation_angle_at_epoch = angle;
+    }
+
+    pub(crate) fn get_rotation_angle_at_epoch(&self) -> Angle {
+        self.rotation_angle_at_epoch
+    }
+
     pub(crate) fn get_data(&self) -> &PlanetData {
         &self.data
     }
@@ -43,9 +69,36 @@ impl Planet {
     pub(crate) fn get_position(&self) -> &Cartesian {
         &self.pos
     }
+
+    pub(super) fn set_position(&mut self, pos: Cartesian) {
+        self.pos = pos;
+    }
+
+    /*
+     * The angle between the direction towards the central body and the direction towards the observer, as seen from the planet.
+     * A phase angle of 0° corresponds to a fully illuminated disk, 180° to a new moon like appearance.
+     */
+    pub(crate) fn get_phase_angle(&self, observer_position: &Cartesian) -> Option<Angle> {
+        let to_central_body = (-&self.pos).to_direction().ok()?;
+        let to_observer = (observer_position.clone() + -&self.pos)
+            .to_direction()
+            .ok()?;
+        Some(to_central_body.angle_to(&to_observer))
+    }
+
+    /*
+     * The angle between the planet and the central body, as seen by an observer.
+     */
+    pub(crate) fn get_elongation(&self, observer_position: &Cartesian) -> Option<Angle> {
+        let to_central_body = (-observer_position).to_direction().ok()?;
+        let to_planet = (self.pos.clone() + -observer_position)
+            .to_direction()
+            .ok()?;
+        Some(to_central_body.angle_to(&to_planet))
+    }
 }
 
-fn calc_pos(central_body: &StarData, time: Time, data: &PlanetData) -> Cartesian {
+pub(crate) fn calc_pos(central_body: &StarData, time: Time, data: &PlanetData) -> Cartesian {
     let pos = if let Some(central_body_mass) = central_body.get_mass(time) {
         data.get_orbital_parameters()
             .calculate_position(data.get_mass(), central_body_mass, time)
@@ -55,11 +108,71 @@ fn calc_pos(central_body: &StarData, time: Time, data: &PlanetData) -> Cartesian
     pos
 }
 
+const GRAVITATIONAL_CONSTANT: f64 = 6.674_30e-11;
+
+/*
+ * The position of one member of a binary planet.
+ * The orbital parameters of the primary describe the orbit of the barycenter of the pair around the central body.
+ * Around the barycenter, the members circle each other at the given separation, in the plane of that orbit,
+ * always on opposite sides.
+ */
+pub(crate) fn calc_binary_pos(
+    central_body: &StarData,
+    time: Time,
+    primary: &PlanetData,
+    secondary: &PlanetData,
+    separation: Length,
+    of_primary: bool,
+) -> Cartesian {
+    let barycenter = calc_pos(central_body, time, primary);
+    let (data, partner) = if of_primary {
+        (primary, secondary)
+    } else {
+        (secondary, primary)
+    };
+    let total_mass = (data.get_mass() + partner.get_mass()).get::<kilogram>();
+    let separation = separation.get::<meter>();
+    if total_mass <= 0. || separation <= 0. {
+        return barycenter;
+    }
+    let mutual_period =
+        2. * PI * (separation.powi(3) / (GRAVITATIONAL_CONSTANT * total_mass)).sqrt();
+    let mut phase = 2. * PI * time.get::<second>() / mutual_period;
+    if of_primary {
+        phase += PI;
+    }
+    let distance_to_barycenter = separation * partner.get_mass().get::<kilogram>() / total_mass;
+
+    let orbit = primary.get_orbital_parameters();
+    let (sin_i, cos_i) = orbit.get_inclination().get::<radian>().sin_cos();
+    let (sin_node, cos_node) = orbit
+        .get_longitude_of_ascending_node()
+        .get::<radian>()
+        .sin_cos();
+    let (sin_phase, cos_phase) = phase.sin_cos();
+    let offset = |node_component: f64, perpendicular_component: f64| {
+        Length::new::<meter>(
+            distance_to_barycenter
+                * (cos_phase * node_component + sin_phase * perpendicular_component),
+        )
+    };
+    barycenter
+        + Cartesian::new(
+            offset(cos_node, -sin_node * cos_i),
+            offset(sin_node, cos_node * cos_i),
+            offset(0., sin_i),
+        )
+}
+
 impl PartOfCelestialSystem for Planet {
     fn get_index(&self) -> Option<usize> {
         self.index
     }
 
+    fn get_id(&self) -> Option<BodyId> {
+        self.id
+    }
+
     fn get_body_type(&self) -> BodyType {
         BodyType::Planet
     }
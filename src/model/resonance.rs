@@ -0,0 +1,97 @@
+use astro_utils::{
+    planets::{derived_data::DerivedPlanetData, planet_data::PlanetData},
+    stars::data::StarData,
+};
+use std::{fmt::Display, str::FromStr};
+use uom::si::f64::Length;
+
+use crate::error::ElenathError;
+
+/*
+ * A resonance p:q means that the planet completes q orbits in the time the reference planet completes p orbits.
+ * A resonance of 3:2 with respect to an inner planet thus places the planet further out, like Pluto and Neptune.
+ */
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Resonance {
+    numerator: u32,
+    denominator: u32,
+}
+
+impl Resonance {
+    /*
+     * The reverse of get_orbital_resonance, which compares the orbital periods that astro_utils derives.
+     * Those same periods are scaled here, so that the resonance it reports for the result is the designed one.
+     * For the planet alone, the period grows with the semi-major axis to the power of 3/2.
+     */
+    pub(crate) fn semi_major_axis(
+        &self,
+        reference: &PlanetData,
+        planet: &PlanetData,
+        central_body: &StarData,
+    ) -> Result<Length, ElenathError> {
+        let reference_axis = reference.get_orbital_parameters().get_semi_major_axis();
+        let reference_period =
+            DerivedPlanetData::new(reference, central_body, None)?.get_orbital_period();
+        let mut alongside = planet.clone();
+        alongside.set_semi_major_axis(reference_axis);
+        let period_alongside =
+            DerivedPlanetData::new(&alongside, central_body, None)?.get_orbital_period();
+        let target_period = reference_period * self.numerator as f64 / self.denominator as f64;
+        let period_ratio: f64 = (target_period / period_alongside).value;
+        Ok(reference_axis * period_ratio.powf(2. / 3.))
+    }
+}
+
+impl FromStr for Resonance {
+    type Err = ElenathError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ElenathError::Generic(format!("Cannot parse resonance \"{}\".", s));
+        let (numerator, denominator) = s.split_once(':').ok_or_else(error)?;
+        let numerator = numerator.trim().parse::<u32>().map_err(|_| error())?;
+        let denominator = denominator.trim().parse::<u32>().map_err(|_| error())?;
+        if numerator == 0 || denominator == 0 {
+            return Err(error());
+        }
+        Ok(Resonance {
+            numerator,
+            denominator,
+        })
+    }
+}
+
+impl Display for Resonance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.numerator, self.denominator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astro_utils::real_data::{planets::*, stars::sun};
+
+    #[test]
+    fn parsing_resonances() {
+        let resonance: Resonance = "3:2".parse().unwrap();
+        assert_eq!(resonance.to_string(), "3:2");
+        let resonance: Resonance = " 1 : 2 ".parse().unwrap();
+        assert_eq!(resonance.to_string(), "1:2");
+        assert!("3/2".parse::<Resonance>().is_err());
+        assert!("3:0".parse::<Resonance>().is_err());
+        assert!("a:b".parse::<Resonance>().is_err());
+    }
+
+    #[test]
+    fn pluto_is_in_three_to_two_resonance_with_neptune() {
+        let neptune = neptune().to_planet_data();
+        let pluto = pluto().to_planet_data();
+        let resonance: Resonance = "3:2".parse().unwrap();
+        let axis = resonance
+            .semi_major_axis(&neptune, &pluto, &sun().to_star_data())
+            .unwrap();
+        let pluto_axis = pluto.get_orbital_parameters().get_semi_major_axis();
+        let relative_difference = ((axis - pluto_axis) / pluto_axis).value.abs();
+        assert!(relative_difference < 0.01);
+    }
+}
@@ -0,0 +1,394 @@
+use astro_coords::{cartesian::Cartesian, direction::Direction};
+use astro_units::{
+    luminous_intensity::absolute_magnitude_to_luminous_intensity,
+    mass::{earth_mass, solar_mass},
+};
+use astro_utils::{
+    color::srgb::sRGBColor,
+    planets::{
+        orbit_parameters::OrbitParameters, physical_parameters::PlanetPhysicalParameters,
+        planet_data::PlanetData,
+    },
+    real_data::stars::sun,
+    stars::{
+        data::StarData, evolution::StarDataEvolution, physical_parameters::StarPhysicalParameters,
+    },
+};
+use uom::si::{
+    angle::degree,
+    f64::{Angle, Length, Mass, ThermodynamicTemperature, Time},
+    length::{astronomical_unit, kilometer},
+    thermodynamic_temperature::kelvin,
+    time::hour,
+};
+
+use crate::error::ElenathError;
+
+const STAR_KINDS: [&str; 2] = ["Star", "StarBarycenter"];
+const BODY_KINDS: [&str; 5] = ["Planet", "DwarfPlanet", "Moon", "Asteroid", "Comet"];
+const DEFAULT_ALBEDO: f64 = 0.3;
+const DEFAULT_ROTATION_PERIOD_IN_HOURS: f64 = 24.;
+const SUN_TEMPERATURE_IN_KELVIN: f64 = 5772.;
+// Real scripts nest a few levels deep, a deeper script is malformed and would otherwise exhaust the stack.
+const MAX_NESTING_DEPTH: usize = 32;
+
+/*
+ * What Elenath can take over from a SpaceEngine script.
+ * Elenath only knows bodies that orbit the central body, so moons and bodies around other stars are skipped by name.
+ */
+#[derive(Debug, Clone)]
+pub(crate) struct SpaceEngineSystem {
+    pub(crate) central_body: Option<StarData>,
+    pub(crate) planets: Vec<PlanetData>,
+    pub(crate) skipped: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Open,
+    Close,
+    LineBreak,
+}
+
+/*
+ * An object or a nested block like Orbit, with the properties that are given on one line each.
+ */
+#[derive(Debug, Default)]
+struct ScObject {
+    kind: String,
+    name: String,
+    properties: Vec<(String, Vec<String>)>,
+    children: Vec<ScObject>,
+}
+
+impl ScObject {
+    fn value(&self, key: &str) -> Option<&str> {
+        self.properties
+            .iter()
+            .find(|(k, _)| k.eq_ignore_ascii_case(key))
+            .and_then(|(_, values)| values.first())
+            .map(|value| value.as_str())
+    }
+
+    fn number(&self, key: &str) -> Option<f64> {
+        self.value(key)?.parse().ok()
+    }
+
+    fn child(&self, kind: &str) -> Option<&ScObject> {
+        self.children
+            .iter()
+            .find(|child| child.kind.eq_ignore_ascii_case(kind))
+    }
+
+    /*
+     * Values like "nan" or "inf" parse as numbers, but no body can be built from them.
+     */
+    fn non_finite_property(&self) -> Option<(&str, &str)> {
+        let own = self.properties.iter().find_map(|(key, values)| {
+            let value = values.first()?;
+            let number = value.parse::<f64>().ok()?;
+            (!number.is_finite()).then_some((key.as_str(), value.as_str()))
+        });
+        own.or_else(|| self.children.iter().find_map(ScObject::non_finite_property))
+    }
+}
+
+fn tokenize(content: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = content.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\n' => tokens.push(Token::LineBreak),
+            '{' => tokens.push(Token::Open),
+            '}' => tokens.push(Token::Close),
+            '"' => {
+                let text: String = chars.by_ref().take_while(|c| *c != '"').collect();
+                tokens.push(Token::Quoted(text));
+            }
+            '/' if chars.peek() == Some(&'/') => while chars.next_if(|c| *c != '\n').is_some() {},
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    if c == '\n' {
+                        tokens.push(Token::LineBreak);
+                    }
+                    previous = c;
+                }
+            }
+            c if c.is_whitespace() => {}
+            c => {
+                let mut word = c.to_string();
+                while let Some(c) =
+                    chars.next_if(|c| !c.is_whitespace() && !matches!(c, '{' | '}' | '"'))
+                {
+                    word.push(c);
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    tokens
+}
+
+/*
+ * A keyword followed by a block is an object, a keyword followed by values up to the end of the line is a property.
+ */
+fn parse_block(
+    tokens: &mut std::iter::Peekable<std::vec::IntoIter<Token>>,
+    depth: usize,
+) -> Result<ScObject, ElenathError> {
+    if depth > MAX_NESTING_DEPTH {
+        return Err(ElenathError::Generic(
+            "The SpaceEngine script nests its blocks too deeply.".to_string(),
+        ));
+    }
+    let nested = depth > 0;
+    let mut block = ScObject::default();
+    loop {
+        let keyword = match tokens.next() {
+            None if nested => {
+                return Err(ElenathError::Generic(
+                    "The SpaceEngine script has an unclosed block.".to_string(),
+                ))
+            }
+            None => return Ok(block),
+            Some(Token::Close) if nested => return Ok(block),
+            Some(Token::Close) => {
+                return Err(ElenathError::Generic(
+                    "The SpaceEngine script closes a block that was never opened.".to_string(),
+                ))
+            }
+            Some(Token::LineBreak) => continue,
+            Some(Token::Word(word)) => word,
+            Some(token) => {
+                return Err(ElenathError::Generic(format!(
+                    "Unexpected {:?} in the SpaceEngine script.",
+                    token
+                )))
+            }
+        };
+        let mut values = Vec::new();
+        while let Some(Token::Word(value) | Token::Quoted(value)) =
+            tokens.next_if(|token| matches!(token, Token::Word(_) | Token::Quoted(_)))
+        {
+            values.push(value);
+        }
+        while tokens.next_if_eq(&Token::LineBreak).is_some() {}
+        if tokens.next_if_eq(&Token::Open).is_some() {
+            let mut child = parse_block(tokens, depth + 1)?;
+            child.kind = keyword;
+            child.name = values.into_iter().next().unwrap_or_default();
+            block.children.push(child);
+        } else {
+            block.properties.push((keyword, values));
+        }
+    }
+}
+
+/*
+ * Reads the first star of the script as the central body, and the bodies that orbit it as planets.
+ * Units follow SpaceEngine: solar masses for stars, Earth masses for planets, kilometers for radii,
+ * astronomical units and years for orbits, and hours for rotation periods.
+ */
+pub(crate) fn read_space_engine_script(content: &str) -> Result<SpaceEngineSystem, ElenathError> {
+    let mut tokens = tokenize(content).into_iter().peekable();
+    let script = parse_block(&mut tokens, 0)?;
+    if let Some((key, value)) = script.non_finite_property() {
+        return Err(ElenathError::Generic(format!(
+            "The SpaceEngine script gives {} as {}, which is not a finite number.",
+            key, value
+        )));
+    }
+    let objects = script.children;
+
+    let star = objects
+        .iter()
+        .find(|object| STAR_KINDS.contains(&object.kind.as_str()));
+    let central_body = star.map(star_data);
+    let star_mass = star
+        .and_then(|star| star.number("Mass"))
+        .map(Mass::new::<solar_mass>);
+
+    let mut planets = Vec::new();
+    let mut skipped = Vec::new();
+    for object in objects
+        .iter()
+        .filter(|object| BODY_KINDS.contains(&object.kind.as_str()))
+    {
+        let orbits_central_body = match (star, object.value("ParentBody")) {
+            (Some(star), Some(parent)) => parent == star.name,
+            (_, parent) => parent.is_none_or(|parent| {
+                !objects
+                    .iter()
+                    .any(|other| BODY_KINDS.contains(&other.kind.as_str()) && other.name == parent)
+            }),
+        };
+        match planet_data(object, star_mass).filter(|_| orbits_central_body) {
+            Some(planet) => planets.push(planet),
+            None => skipped.push(object.name.clone()),
+        }
+    }
+    if central_body.is_none() && planets.is_empty() {
+        return Err(ElenathError::Generic(
+            "The SpaceEngine script contains neither a star nor a planet.".to_string(),
+        ));
+    }
+    Ok(SpaceEngineSystem {
+        central_body,
+        planets,
+        skipped,
+    })
+}
+
+fn star_data(star: &ScObject) -> StarData {
+    let sun_intensity = sun().to_star_data().get_luminous_intensity_at_epoch();
+    let luminous_intensity = match (star.number("AbsMagn"), star.number("Luminosity")) {
+        (Some(magnitude), _) => absolute_magnitude_to_luminous_intensity(magnitude),
+        (None, Some(luminosity)) => sun_intensity * luminosity,
+        (None, None) => sun_intensity,
+    };
+    let temperature = star.number("Teff").unwrap_or(SUN_TEMPERATURE_IN_KELVIN);
+    let params = StarPhysicalParameters::new(
+        None,
+        None,
+        luminous_intensity,
+        ThermodynamicTemperature::new::<kelvin>(temperature),
+    );
+    let mut data = StarData::new(
+        star.name.clone(),
+        None,
+        params,
+        Cartesian::origin(),
+        StarDataEvolution::none(),
+    );
+    data.set_mass_at_epoch(star.number("Mass").map(Mass::new::<solar_mass>));
+    data.set_radius_at_epoch(star.number("Radius").map(Length::new::<kilometer>));
+    data
+}
+
+/*
+ * Without a semi-major axis, it follows from the period by Kepler's third law, which needs the mass of the star.
+ */
+fn planet_data(body: &ScObject, star_mass: Option<Mass>) -> Option<PlanetData> {
+    let orbit = body.child("Orbit")?;
+    let semi_major_axis = match (orbit.number("SemiMajorAxis"), orbit.number("Period")) {
+        (Some(semi_major_axis), _) => semi_major_axis,
+        (None, Some(period)) => {
+            let star_mass = star_mass.unwrap_or(Mass::new::<solar_mass>(1.));
+            (star_mass.get::<solar_mass>() * period.powi(2)).cbrt()
+        }
+        (None, None) => return None,
+    };
+    let angle = |key: &str| Angle::new::<degree>(orbit.number(key).unwrap_or(0.));
+    let orbit = OrbitParameters::new(
+        Length::new::<astronomical_unit>(semi_major_axis),
+        orbit.number("Eccentricity").unwrap_or(0.),
+        angle("Inclination"),
+        angle("AscendingNode"),
+        angle("ArgOfPericenter"),
+    );
+
+    let obliquity = body.number("Obliquity").unwrap_or(0.).to_radians();
+    let rotation_axis =
+        Direction::new(obliquity.sin(), 0., obliquity.cos()).unwrap_or(Direction::Z);
+    let albedo = body
+        .number("AlbedoGeom")
+        .or(body.number("AlbedoBond"))
+        .or(body.number("Albedo"))
+        .unwrap_or(DEFAULT_ALBEDO);
+    let physical_parameters = PlanetPhysicalParameters::new(
+        Mass::new::<earth_mass>(body.number("Mass")?),
+        Length::new::<kilometer>(body.number("Radius")?),
+        albedo,
+        sRGBColor::from_sRGB(0.7, 0.7, 0.7),
+        Time::new::<hour>(
+            body.number("RotationPeriod")
+                .unwrap_or(DEFAULT_ROTATION_PERIOD_IN_HOURS),
+        ),
+        rotation_axis,
+    );
+    Some(PlanetData::new(
+        body.name.clone(),
+        physical_parameters,
+        orbit,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCRIPT: &str = r#"
+// A fictional system
+Star "Anor"
+{
+    Class "G2V"
+    Mass 1.1
+    Teff 5900
+}
+
+Planet "Arda"
+{
+    ParentBody "Anor"
+    Mass 1.0 /* Earth masses */
+    Radius 6371
+    Obliquity 23.4
+    Orbit
+    {
+        Period 1.0
+        Eccentricity 0.02
+    }
+}
+
+Moon "Ithil"
+{
+    ParentBody "Arda"
+    Mass 0.012
+    Radius 1737
+    Orbit { SemiMajorAxis 0.00257 }
+}
+"#;
+
+    #[test]
+    fn planets_of_the_star_are_read() {
+        let system = read_space_engine_script(SCRIPT).unwrap();
+        let star = system.central_body.unwrap();
+        assert_eq!(star.get_name(), "Anor");
+        assert_eq!(system.planets.len(), 1);
+        let planet = &system.planets[0];
+        assert_eq!(planet.get_name(), "Arda");
+        let semi_major_axis = planet
+            .get_orbital_parameters()
+            .get_semi_major_axis()
+            .get::<astronomical_unit>();
+        assert!((semi_major_axis - 1.1f64.cbrt()).abs() < 1e-9);
+        assert_eq!(system.skipped, vec!["Ithil".to_string()]);
+    }
+
+    #[test]
+    fn unbalanced_braces_are_rejected() {
+        assert!(read_space_engine_script("Planet \"A\" {\n Mass 1\n").is_err());
+        assert!(read_space_engine_script("}").is_err());
+        assert!(read_space_engine_script("").is_err());
+    }
+
+    #[test]
+    fn deeply_nested_blocks_are_rejected() {
+        let script = "Planet \"A\" {\n".repeat(10_000) + &"}\n".repeat(10_000);
+        assert!(read_space_engine_script(&script).is_err());
+    }
+
+    #[test]
+    fn non_finite_numbers_are_rejected() {
+        for value in ["nan", "inf", "-infinity", "1e400"] {
+            let script = SCRIPT.replace("Eccentricity 0.02", &format!("Eccentricity {}", value));
+            assert!(read_space_engine_script(&script).is_err());
+        }
+    }
+}
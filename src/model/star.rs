@@ -1,14 +1,40 @@
 use astro_utils::stars::{appearance::StarAppearance, data::StarData};
 use serde::{Deserialize, Serialize};
-use uom::si::f64::Time;
+use std::borrow::Cow;
+use uom::si::{
+    f64::{Time, Velocity},
+    time::year,
+};
 
-use super::celestial_system::part::{BodyType, PartOfCelestialSystem};
+use super::{
+    appearance_override::AppearanceOverride,
+    body_id::BodyId,
+    celestial_system::part::{BodyType, PartOfCelestialSystem},
+    gaia_export::CatalogEntry,
+    galactic_motion::GalacticMotion,
+    star_formation::StarBirth,
+    star_provenance::StarProvenance,
+};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub(crate) struct Star {
     data: Option<StarData>,
     appearance: StarAppearance,
     index: Option<usize>,
+    #[serde(default)]
+    id: BodyId,
+    #[serde(default)]
+    radial_velocity: Option<Velocity>,
+    #[serde(default)]
+    birth: Option<StarBirth>,
+    #[serde(default)]
+    velocity: Option<[Velocity; 3]>,
+    #[serde(default)]
+    appearance_override: Option<AppearanceOverride>,
+    #[serde(default)]
+    catalog_entry: Option<CatalogEntry>,
+    #[serde(default)]
+    provenance: StarProvenance,
 }
 
 impl Star {
@@ -18,6 +44,13 @@ impl Star {
             data: Some(data),
             appearance,
             index,
+            id: BodyId::default(),
+            radial_velocity: None,
+            birth: None,
+            velocity: None,
+            appearance_override: None,
+            catalog_entry: None,
+            provenance: StarProvenance::default(),
         }
     }
 
@@ -26,6 +59,13 @@ impl Star {
             data: None,
             appearance,
             index,
+            id: BodyId::default(),
+            radial_velocity: None,
+            birth: None,
+            velocity: None,
+            appearance_override: None,
+            catalog_entry: None,
+            provenance: StarProvenance::default(),
         }
     }
 
@@ -33,21 +73,176 @@ impl Star {
         self.data.as_ref()
     }
 
+    /*
+     * Replaces the data of a star that has data, keeping everything else about it.
+     */
+    pub(super) fn set_data(
+        &mut self,
+        data: StarData,
+        time_since_epoch: Time,
+        galactic_motion: &GalacticMotion,
+    ) {
+        self.data = Some(data);
+        self.recalculate_appearance(time_since_epoch, galactic_motion);
+    }
+
     pub(crate) fn get_appearance(&self) -> &StarAppearance {
         &self.appearance
     }
 
+    /*
+     * Only stars that were imported from a catalog export have an entry.
+     */
+    pub(crate) fn get_catalog_entry(&self) -> Option<&CatalogEntry> {
+        self.catalog_entry.as_ref()
+    }
+
+    pub(super) fn set_catalog_entry(&mut self, catalog_entry: CatalogEntry) {
+        self.catalog_entry = Some(catalog_entry);
+    }
+
+    pub(crate) fn get_provenance(&self) -> &StarProvenance {
+        &self.provenance
+    }
+
+    pub(super) fn set_provenance(&mut self, provenance: StarProvenance) {
+        self.provenance = provenance;
+    }
+
+    pub(super) fn mark_edited(&mut self) {
+        self.provenance.mark_edited();
+    }
+
+    pub(crate) fn get_appearance_override(&self) -> Option<&AppearanceOverride> {
+        self.appearance_override.as_ref()
+    }
+
+    pub(super) fn set_appearance_override(
+        &mut self,
+        appearance_override: Option<AppearanceOverride>,
+    ) {
+        self.appearance_override = appearance_override.filter(|o| !o.is_empty());
+    }
+
+    /*
+     * The appearance as it is drawn, with any override applied on top of the derived one.
+     */
+    pub(crate) fn get_displayed_appearance(&self) -> Cow<'_, StarAppearance> {
+        match &self.appearance_override {
+            Some(appearance_override) => Cow::Owned(appearance_override.apply(&self.appearance)),
+            None => Cow::Borrowed(&self.appearance),
+        }
+    }
+
+    /*
+     * Positive radial velocities point away from the central body.
+     */
+    pub(crate) fn get_radial_velocity(&self) -> Option<Velocity> {
+        self.radial_velocity
+    }
+
+    pub(super) fn set_radial_velocity(&mut self, radial_velocity: Option<Velocity>) {
+        self.radial_velocity = radial_velocity;
+    }
+
+    /*
+     * Only stars that were born in a star-forming region have a birth.
+     */
+    pub(crate) fn get_birth(&self) -> Option<&StarBirth> {
+        self.birth.as_ref()
+    }
+
+    pub(super) fn set_birth(&mut self, birth: StarBirth) {
+        self.birth = Some(birth);
+    }
+
+    /*
+     * A velocity that was assigned to the star when it was generated, relative to the central body.
+     * It replaces the random peculiar velocity of the galactic motion.
+     */
+    pub(super) fn set_velocity(&mut self, velocity: [Velocity; 3]) {
+        self.velocity = Some(velocity);
+    }
+
+    pub(super) fn set_id(&mut self, id: BodyId) {
+        self.id = id;
+    }
+
     pub(super) fn set_index(&mut self, index: usize) {
         self.index = Some(index);
     }
 
-    pub(super) fn recalculate_appearance_if_necessary(&mut self, time_since_epoch: Time) {
+    pub(super) fn recalculate_appearance_if_necessary(
+        &mut self,
+        time_since_epoch: Time,
+        galactic_motion: &GalacticMotion,
+    ) {
         if let Some(data) = &self.data {
             let then = self.appearance.get_time_since_epoch();
-            if data.has_changed(then, time_since_epoch) {
-                self.appearance = data.to_star_appearance(time_since_epoch);
+            let has_drifted = (galactic_motion.enabled || self.velocity.is_some())
+                && (time_since_epoch - then).value.abs()
+                    > GalacticMotion::recalculation_interval().value;
+            if has_drifted || data.has_changed(then, time_since_epoch) {
+                self.recalculate_appearance(time_since_epoch, galactic_motion);
+            }
+        }
+    }
+
+    pub(super) fn recalculate_appearance(
+        &mut self,
+        time_since_epoch: Time,
+        galactic_motion: &GalacticMotion,
+    ) {
+        if let Some(data) = self.data_at(time_since_epoch, galactic_motion) {
+            self.appearance = data.to_star_appearance(time_since_epoch);
+        }
+    }
+
+    /*
+     * Rewrites the star as it is after the given time, which becomes the new epoch.
+     * Stars that move along their own velocity since their birth stay at their birthplace, only their birth moves.
+     */
+    pub(super) fn shift_epoch(&mut self, shift: Time, galactic_motion: &GalacticMotion) {
+        let moves_since_birth = self.velocity.is_some() && self.birth.is_some();
+        if !moves_since_birth {
+            if let Some(moved) = self.data_at(shift, galactic_motion) {
+                self.data = Some(moved);
             }
         }
+        if let Some(data) = &mut self.data {
+            if !moves_since_birth {
+                data.set_pos_at_epoch(data.get_pos(shift));
+            }
+            if let Some(age) = data.get_age_at_epoch() {
+                data.set_age_at_epoch(Some(age + shift));
+            }
+        }
+        if let Some(birth) = &mut self.birth {
+            birth.time -= shift;
+        }
+    }
+
+    /*
+     * The star data, moved to where galactic motion has carried the star by the given time.
+     * Stars with their own velocity start moving at their birth, or at the epoch if they have none.
+     */
+    pub(super) fn data_at(
+        &self,
+        time_since_epoch: Time,
+        galactic_motion: &GalacticMotion,
+    ) -> Option<StarData> {
+        let data = self.data.as_ref()?;
+        if let Some(velocity) = &self.velocity {
+            let start = match &self.birth {
+                Some(birth) => birth.time,
+                None => Time::new::<year>(0.),
+            };
+            Some(galactic_motion.moved_with_own_velocity(data, velocity, time_since_epoch - start))
+        } else if galactic_motion.enabled {
+            Some(galactic_motion.moved(data, self.id, self.radial_velocity, time_since_epoch))
+        } else {
+            Some(data.clone())
+        }
     }
 }
 
@@ -56,15 +251,79 @@ impl PartOfCelestialSystem for Star {
         self.index
     }
 
+    fn get_id(&self) -> Option<BodyId> {
+        if self.id.is_assigned() {
+            Some(self.id)
+        } else {
+            None
+        }
+    }
+
     fn get_body_type(&self) -> BodyType {
         BodyType::Star
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Copy)]
 pub(crate) enum StarDataType {
     Hardcoded,
     GaiaMeasurementSmall,
     GaiaMeasurementLarge,
     GaiaSimulation,
 }
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Copy)]
+pub(crate) enum StarMergeMode {
+    Replace,
+    Append,
+    FillGaps,
+}
+
+impl StarMergeMode {
+    pub(crate) const ALL: [StarMergeMode; 3] = [
+        StarMergeMode::Replace,
+        StarMergeMode::Append,
+        StarMergeMode::FillGaps,
+    ];
+
+    /*
+     * Describes what happens to the existing distant stars, and how many stars the system will
+     * hold afterwards if the number of incoming stars is known in advance.
+     */
+    pub(crate) fn preview(&self, existing: usize, incoming: Option<usize>) -> String {
+        match (self, incoming) {
+            (StarMergeMode::Replace, Some(incoming)) => format!(
+                "{} existing stars will be removed, {} stars will be added.",
+                existing, incoming
+            ),
+            (StarMergeMode::Replace, None) => {
+                format!("{} existing stars will be removed.", existing)
+            }
+            (StarMergeMode::Append, Some(incoming)) => format!(
+                "{} existing stars are kept, {} stars will be added, {} in total.",
+                existing,
+                incoming,
+                existing + incoming
+            ),
+            (StarMergeMode::Append, None) => format!("{} existing stars are kept.", existing),
+            (StarMergeMode::FillGaps, Some(incoming)) => format!(
+                "{} existing stars are kept, up to {} stars will be added where there is no star yet.",
+                existing, incoming
+            ),
+            (StarMergeMode::FillGaps, None) => format!(
+                "{} existing stars are kept, new stars are only added where there is no star yet.",
+                existing
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for StarMergeMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StarMergeMode::Replace => write!(f, "Replace"),
+            StarMergeMode::Append => write!(f, "Append"),
+            StarMergeMode::FillGaps => write!(f, "Fill Gaps"),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use astro_coords::{ecliptic::Ecliptic, spherical::Spherical, traits::*};
+use astro_units::illuminance::illuminance_to_apparent_magnitude;
+use astro_utils::stars::{appearance::StarAppearance, data::StarData};
+use serde::{Deserialize, Deserializer, Serialize};
+use uom::si::f64::{Angle, Length, Time};
+
+/*
+ * A circular patch of the sky, around the given ecliptic coordinates.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct SkyCone {
+    pub(crate) longitude: Angle,
+    pub(crate) latitude: Angle,
+    pub(crate) radius: Angle,
+}
+
+impl SkyCone {
+    fn contains(&self, appearance: &StarAppearance) -> bool {
+        let center = Ecliptic {
+            spherical: Spherical::new(self.longitude, self.latitude),
+        }
+        .to_direction();
+        appearance.get_pos().to_direction().angle_to(&center) <= self.radius
+    }
+}
+
+/*
+ * Restricts which stars of a catalog are imported, so that slow machines only carry the stars they need.
+ * Stars that are mere appearances have no known distance, and are never dropped for it.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct StarCatalogFilter {
+    #[serde(deserialize_with = "deserialize_magnitude_limit")]
+    pub(crate) magnitude_limit: f64,
+    pub(crate) max_distance: Option<Length>,
+    pub(crate) region: Option<SkyCone>,
+}
+
+/*
+ * JSON knows no infinity, so a missing magnitude limit is written as null.
+ */
+fn deserialize_magnitude_limit<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<f64, D::Error> {
+    Ok(Option::<f64>::deserialize(deserializer)?.unwrap_or(f64::INFINITY))
+}
+
+impl Default for StarCatalogFilter {
+    fn default() -> Self {
+        StarCatalogFilter {
+            magnitude_limit: f64::INFINITY,
+            max_distance: None,
+            region: None,
+        }
+    }
+}
+
+impl StarCatalogFilter {
+    pub(crate) fn accepts_appearance(&self, appearance: &StarAppearance) -> bool {
+        let magnitude = illuminance_to_apparent_magnitude(appearance.get_illuminance());
+        magnitude <= self.magnitude_limit
+            && self.region.is_none_or(|region| region.contains(appearance))
+    }
+
+    pub(crate) fn accepts_data(&self, data: &StarData, time_since_epoch: Time) -> bool {
+        let is_close_enough = self
+            .max_distance
+            .is_none_or(|max_distance| data.get_pos_at_epoch().length() <= max_distance);
+        is_close_enough && self.accepts_appearance(&data.to_star_appearance(time_since_epoch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astro_coords::direction::Direction;
+    use astro_units::illuminance::apparent_magnitude_to_illuminance;
+    use astro_utils::color::srgb::sRGBColor;
+    use uom::si::{angle::degree, time::year};
+
+    fn appearance(magnitude: f64, direction: Direction) -> StarAppearance {
+        StarAppearance::new(
+            String::new(),
+            apparent_magnitude_to_illuminance(magnitude),
+            sRGBColor::from_sRGB(1., 1., 1.),
+            direction.to_ecliptic(),
+            Time::new::<year>(0.),
+        )
+    }
+
+    #[test]
+    fn faint_stars_are_dropped() {
+        let filter = StarCatalogFilter {
+            magnitude_limit: 6.,
+            ..Default::default()
+        };
+        assert!(filter.accepts_appearance(&appearance(5.9, Direction::X)));
+        assert!(!filter.accepts_appearance(&appearance(6.1, Direction::X)));
+    }
+
+    #[test]
+    fn stars_outside_of_the_region_are_dropped() {
+        let filter = StarCatalogFilter {
+            region: Some(SkyCone {
+                longitude: Angle::new::<degree>(0.),
+                latitude: Angle::new::<degree>(90.),
+                radius: Angle::new::<degree>(10.),
+            }),
+            ..Default::default()
+        };
+        assert!(filter.accepts_appearance(&appearance(1., Direction::Z)));
+        assert!(!filter.accepts_appearance(&appearance(1., Direction::X)));
+    }
+}
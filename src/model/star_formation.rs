@@ -0,0 +1,250 @@
+use astro_coords::{cartesian::Cartesian, ecliptic::Ecliptic, spherical::Spherical};
+use astro_units::{length::solar_radius, mass::solar_mass};
+use astro_utils::{
+    real_data::stars::sun,
+    stars::{
+        data::StarData, evolution::StarDataEvolution, physical_parameters::StarPhysicalParameters,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+use uom::si::{
+    angle::degree,
+    f64::{Angle, Length, Mass, ThermodynamicTemperature, Time, Velocity},
+    length::light_year,
+    thermodynamic_temperature::kelvin,
+    time::year,
+    velocity::kilometer_per_second,
+};
+
+use super::{
+    galactic_motion::random_velocity,
+    star_generation::{SplitMix64, SALPETER_IMF_SLOPE},
+};
+
+const MIN_BIRTH_MASS_IN_SOLAR_MASSES: f64 = 0.5;
+const MAX_BIRTH_MASS_IN_SOLAR_MASSES: f64 = 30.;
+const SUN_TEMPERATURE_IN_KELVIN: f64 = 5772.;
+// Keeps a region with an absurd birth rate from stalling the time slider.
+const MAX_BIRTHS_PER_REGION: usize = 10_000;
+
+/*
+ * A region that spawns new stars once the time since epoch has passed its onset.
+ * Births are derived from the region parameters alone, so going back and forth in time yields the same stars.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct StarFormingRegion {
+    pub(crate) name: String,
+    pub(crate) longitude: Angle,
+    pub(crate) latitude: Angle,
+    pub(crate) distance: Length,
+    pub(crate) radius: Length,
+    pub(crate) births_per_megayear: f64,
+    pub(crate) onset: Time,
+    #[serde(default = "default_velocity_dispersion")]
+    pub(crate) velocity_dispersion: Velocity,
+}
+
+/*
+ * Identifies a star that was born in a star-forming region.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub(crate) struct StarBirth {
+    pub(crate) region: String,
+    pub(crate) number: usize,
+    pub(crate) time: Time,
+}
+
+impl Default for StarFormingRegion {
+    fn default() -> Self {
+        StarFormingRegion {
+            name: "Star-Forming Region".to_string(),
+            longitude: Angle::new::<degree>(0.),
+            latitude: Angle::new::<degree>(0.),
+            distance: Length::new::<light_year>(1000.),
+            radius: Length::new::<light_year>(10.),
+            births_per_megayear: 10.,
+            onset: Time::new::<year>(0.),
+            velocity_dispersion: default_velocity_dispersion(),
+        }
+    }
+}
+
+impl StarFormingRegion {
+    /*
+     * Births happen as a Poisson process, so the waiting times between them are exponentially distributed.
+     */
+    pub(crate) fn births_until(&self, time_since_epoch: Time) -> Vec<StarBirth> {
+        let mut births = Vec::new();
+        if self.births_per_megayear <= 0. {
+            return births;
+        }
+        let mut random = SplitMix64(self.seed());
+        let mut time = self.onset;
+        while births.len() < MAX_BIRTHS_PER_REGION {
+            let waiting_time = -(1. - random.next_f64()).ln() / self.births_per_megayear;
+            time += megayears(waiting_time);
+            if time > time_since_epoch {
+                break;
+            }
+            births.push(StarBirth {
+                region: self.name.clone(),
+                number: births.len() + 1,
+                time,
+            });
+        }
+        births
+    }
+
+    /*
+     * The next birth after the given time, if the region is still forming stars.
+     */
+    pub(crate) fn next_birth(&self, time_since_epoch: Time) -> Option<StarBirth> {
+        let far_future = time_since_epoch + megayears(1e3);
+        self.births_until(far_future)
+            .into_iter()
+            .find(|birth| birth.time > time_since_epoch)
+    }
+
+    /*
+     * A young main-sequence star with a mass drawn from the Salpeter IMF, placed uniformly within the region.
+     * Stars born after the epoch have a negative age at the epoch.
+     */
+    pub(crate) fn star_data(&self, birth: &StarBirth) -> StarData {
+        let mut random =
+            SplitMix64(self.seed() ^ (birth.number as u64).wrapping_mul(0x9E3779B97F4A7C15));
+        let mass = salpeter_mass(&mut random);
+        let luminosity = mass.powf(3.5);
+        let radius = mass.powf(0.8);
+        let temperature = SUN_TEMPERATURE_IN_KELVIN * (luminosity / radius.powi(2)).powf(0.25);
+        let luminous_intensity =
+            sun().to_star_data().get_luminous_intensity_at_epoch() * luminosity;
+
+        let params = StarPhysicalParameters::new(
+            None,
+            None,
+            luminous_intensity,
+            ThermodynamicTemperature::new::<kelvin>(temperature),
+        );
+        let mut data = StarData::new(
+            format!("{} {}", self.name, birth.number),
+            None,
+            params,
+            self.random_position(&mut random),
+            StarDataEvolution::none(),
+        );
+        data.set_mass_at_epoch(Some(Mass::new::<solar_mass>(mass)));
+        data.set_radius_at_epoch(Some(Length::new::<solar_radius>(radius)));
+        data.set_age_at_epoch(Some(-birth.time));
+        data
+    }
+
+    /*
+     * The velocity a star is born with. The stars of a region disperse with it over hundreds of megayears,
+     * until the cluster they formed has dissolved.
+     */
+    pub(crate) fn star_velocity(&self, birth: &StarBirth) -> [Velocity; 3] {
+        let mut random =
+            SplitMix64(self.seed() ^ (birth.number as u64).wrapping_mul(0xD1B54A32D192ED03));
+        random_velocity(self.velocity_dispersion, &mut random)
+    }
+
+    fn random_position(&self, random: &mut SplitMix64) -> Cartesian {
+        let center = Ecliptic {
+            spherical: Spherical::new(self.longitude, self.latitude),
+        }
+        .to_direction()
+        .to_cartesian(self.distance);
+        let cos_theta = 2. * random.next_f64() - 1.;
+        let sin_theta = (1. - cos_theta * cos_theta).sqrt();
+        let phi = 2. * PI * random.next_f64();
+        let offset = self.radius * random.next_f64().cbrt();
+        Cartesian::new(
+            center.x + offset * sin_theta * phi.cos(),
+            center.y + offset * sin_theta * phi.sin(),
+            center.z + offset * cos_theta,
+        )
+    }
+
+    fn seed(&self) -> u64 {
+        // FNV-1a, because the standard library hasher is not guaranteed to be stable across releases.
+        self.name.bytes().fold(0xCBF29CE484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001B3)
+        })
+    }
+}
+
+// Typical for the internal motion of open clusters.
+fn default_velocity_dispersion() -> Velocity {
+    Velocity::new::<kilometer_per_second>(1.)
+}
+
+fn megayears(value: f64) -> Time {
+    Time::new::<year>(1e6 * value)
+}
+
+fn salpeter_mass(random: &mut SplitMix64) -> f64 {
+    let exponent = 1. - SALPETER_IMF_SLOPE;
+    let low = MIN_BIRTH_MASS_IN_SOLAR_MASSES.powf(exponent);
+    let high = MAX_BIRTH_MASS_IN_SOLAR_MASSES.powf(exponent);
+    (low + random.next_f64() * (high - low)).powf(1. / exponent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_births_before_onset() {
+        let region = StarFormingRegion {
+            onset: megayears(1.),
+            ..Default::default()
+        };
+        assert!(region.births_until(megayears(1.)).is_empty());
+    }
+
+    #[test]
+    fn births_are_reproducible() {
+        let region = StarFormingRegion::default();
+        let time = megayears(5.);
+        let births = region.births_until(time);
+        assert!(!births.is_empty());
+        assert_eq!(births, region.births_until(time));
+        for birth in births.iter() {
+            assert!(birth.time <= time);
+            let first = region.star_data(birth);
+            let second = region.star_data(birth);
+            assert_eq!(first.get_name(), second.get_name());
+            assert_eq!(
+                first.get_distance_at_epoch(),
+                second.get_distance_at_epoch()
+            );
+        }
+    }
+
+    #[test]
+    fn birth_count_follows_rate() {
+        let region = StarFormingRegion::default();
+        let births = region.births_until(megayears(100.));
+        assert!(births.len() > 800 && births.len() < 1200);
+    }
+
+    #[test]
+    fn born_stars_lie_within_region() {
+        let region = StarFormingRegion::default();
+        for birth in region.births_until(megayears(10.)) {
+            let distance = region.star_data(&birth).get_distance_at_epoch();
+            assert!(distance >= region.distance - region.radius * 1.000001);
+            assert!(distance <= region.distance + region.radius * 1.000001);
+        }
+    }
+
+    #[test]
+    fn next_birth_lies_in_the_future() {
+        let region = StarFormingRegion::default();
+        let now = megayears(2.);
+        let next = region.next_birth(now).unwrap();
+        assert!(next.time > now);
+        assert_eq!(next.number, region.births_until(now).len() + 1);
+    }
+}
@@ -0,0 +1,293 @@
+use crate::error::ElenathError;
+use astro_utils::stars::{
+    data::StarData,
+    random::random_stars::{generate_random_star, generate_random_stars},
+};
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+use uom::si::{
+    f64::{Length, Time},
+    length::light_year,
+    mass::kilogram,
+    time::year,
+};
+
+/*
+ * The slope of the Salpeter initial mass function, which the underlying generator follows.
+ */
+pub(crate) const SALPETER_IMF_SLOPE: f64 = 2.35;
+
+/*
+ * Knobs that are applied on top of the random star generator.
+ * The generator itself only takes a maximum distance, so the other parameters reshape its output:
+ * Stars are thinned out to match the IMF slope and star count, ages are rescaled, and temperatures are shifted as a crude proxy for metallicity, which mainly affects star colors.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct StarGenerationParameters {
+    pub(crate) max_distance: Length,
+    pub(crate) max_star_count: Option<usize>,
+    pub(crate) imf_slope: f64,
+    pub(crate) max_age: Option<Time>,
+    pub(crate) temperature_shift: f64,
+}
+
+impl Default for StarGenerationParameters {
+    fn default() -> Self {
+        StarGenerationParameters {
+            max_distance: Length::new::<light_year>(1000.),
+            max_star_count: None,
+            imf_slope: SALPETER_IMF_SLOPE,
+            max_age: None,
+            temperature_shift: 0.,
+        }
+    }
+}
+
+impl StarGenerationParameters {
+    /*
+     * Rejects parameters that cannot be applied, so that a replayed command fails just like the dialog does.
+     */
+    pub(crate) fn validate(&self) -> Result<(), ElenathError> {
+        if !self.max_distance.value.is_finite() || self.max_distance.value <= 0. {
+            return Err(ElenathError::Generic(
+                "The maximum generation distance must be positive".to_string(),
+            ));
+        }
+        if self.max_star_count == Some(0) {
+            return Err(ElenathError::Generic(
+                "The maximum star count must be positive".to_string(),
+            ));
+        }
+        if !self.imf_slope.is_finite() {
+            return Err(ElenathError::Generic(
+                "The IMF slope must be a finite number".to_string(),
+            ));
+        }
+        if let Some(max_age) = self.max_age {
+            if !max_age.value.is_finite() || max_age.value <= 0. {
+                return Err(ElenathError::Generic(
+                    "The maximum age must be positive".to_string(),
+                ));
+            }
+        }
+        if !self.temperature_shift.is_finite() || self.temperature_shift <= -1. {
+            return Err(ElenathError::Generic(
+                "The temperature shift must be larger than -100%".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    pub(crate) fn generate(&self) -> Result<Vec<StarData>, ElenathError> {
+        self.validate()?;
+        Ok(self.apply(generate_random_stars(self.max_distance)?))
+    }
+
+    pub(crate) fn generate_stars(
+        &self,
+        keep_central_body: bool,
+    ) -> Result<GeneratedStars, ElenathError> {
+        let central_body = match keep_central_body {
+            true => None,
+            false => Some(generate_random_star(None)?),
+        };
+        Ok(GeneratedStars {
+            central_body,
+            stars: self.generate()?,
+            naming_seed: time_seed(),
+        })
+    }
+
+    fn apply(&self, stars: Vec<StarData>) -> Vec<StarData> {
+        self.apply_with_seed(stars, time_seed())
+    }
+
+    fn apply_with_seed(&self, stars: Vec<StarData>, seed: u64) -> Vec<StarData> {
+        let mut random = SplitMix64(seed);
+        let mut stars = self.reweight_imf(stars, &mut random);
+        if let Some(max_star_count) = self.max_star_count {
+            thin_out(&mut stars, max_star_count, &mut random);
+        }
+        if let Some(max_age) = self.max_age {
+            rescale_ages(&mut stars, max_age);
+        }
+        if self.temperature_shift != 0. {
+            for star in stars.iter_mut() {
+                let temperature = star.get_temperature_at_epoch();
+                star.set_temperature_at_epoch(temperature * (1. + self.temperature_shift));
+            }
+        }
+        stars
+    }
+
+    /*
+     * Rejection sampling with weights m^(s0 - s), so that the surviving stars follow an IMF with slope s.
+     */
+    fn reweight_imf(&self, stars: Vec<StarData>, random: &mut SplitMix64) -> Vec<StarData> {
+        let exponent = SALPETER_IMF_SLOPE - self.imf_slope;
+        if exponent == 0. {
+            return stars;
+        }
+        let weight = |star: &StarData| {
+            star.get_mass_at_epoch()
+                .map(|m| m.get::<kilogram>().powf(exponent))
+        };
+        let max_weight = stars.iter().filter_map(weight).fold(0., f64::max);
+        if max_weight <= 0. {
+            return stars;
+        }
+        stars
+            .into_iter()
+            .filter(|star| match weight(star) {
+                Some(w) => random.next_f64() < w / max_weight,
+                None => true,
+            })
+            .collect()
+    }
+}
+
+fn thin_out(stars: &mut Vec<StarData>, max_star_count: usize, random: &mut SplitMix64) {
+    // Partial Fisher-Yates shuffle, so that the remaining stars are an unbiased sample.
+    let count = stars.len();
+    if count <= max_star_count {
+        return;
+    }
+    for i in 0..max_star_count {
+        let j = i + (random.next_f64() * (count - i) as f64) as usize;
+        stars.swap(i, j.min(count - 1));
+    }
+    stars.truncate(max_star_count);
+}
+
+fn rescale_ages(stars: &mut [StarData], max_age: Time) {
+    let oldest = stars.iter().filter_map(|s| s.get_age_at_epoch()).fold(
+        Time::new::<year>(0.),
+        |oldest, age| {
+            if age > oldest {
+                age
+            } else {
+                oldest
+            }
+        },
+    );
+    if oldest.value <= 0. {
+        return;
+    }
+    let factor: f64 = (max_age / oldest).value;
+    for star in stars.iter_mut() {
+        if let Some(age) = star.get_age_at_epoch() {
+            star.set_age_at_epoch(Some(age * factor));
+        }
+    }
+}
+
+/*
+ * What the random generator drew, so that replaying the command adds the same stars under the same names.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub(crate) struct GeneratedStars {
+    pub(crate) central_body: Option<StarData>,
+    pub(crate) stars: Vec<StarData>,
+    pub(crate) naming_seed: u64,
+}
+
+pub(crate) fn time_seed() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or_default()
+}
+
+pub(crate) struct SplitMix64(pub(crate) u64);
+
+impl SplitMix64 {
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^= z >> 31;
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use astro_utils::real_data::stars::all::get_many_stars;
+
+    fn stars() -> Vec<StarData> {
+        get_many_stars().iter().map(|s| s.to_star_data()).collect()
+    }
+
+    #[test]
+    fn default_parameters_do_not_change_stars() {
+        let stars = stars();
+        let generated = StarGenerationParameters::default().apply_with_seed(stars.clone(), 42);
+        assert_eq!(generated.len(), stars.len());
+        for (a, b) in stars.iter().zip(generated.iter()) {
+            assert_eq!(a.get_name(), b.get_name());
+        }
+    }
+
+    #[test]
+    fn invalid_parameters_are_rejected() {
+        assert!(StarGenerationParameters::default().validate().is_ok());
+        let invalid = [
+            StarGenerationParameters {
+                max_star_count: Some(0),
+                ..Default::default()
+            },
+            StarGenerationParameters {
+                imf_slope: f64::NAN,
+                ..Default::default()
+            },
+            StarGenerationParameters {
+                max_age: Some(Time::new::<year>(-1.)),
+                ..Default::default()
+            },
+            StarGenerationParameters {
+                temperature_shift: -1.,
+                ..Default::default()
+            },
+        ];
+        for parameters in invalid {
+            assert!(parameters.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn star_count_is_limited() {
+        let parameters = StarGenerationParameters {
+            max_star_count: Some(10),
+            ..Default::default()
+        };
+        let generated = parameters.apply_with_seed(stars(), 42);
+        assert_eq!(generated.len(), 10);
+    }
+
+    #[test]
+    fn steeper_imf_removes_stars() {
+        let parameters = StarGenerationParameters {
+            imf_slope: 4.,
+            ..Default::default()
+        };
+        let generated = parameters.apply_with_seed(stars(), 42);
+        assert!(generated.len() < stars().len());
+    }
+
+    #[test]
+    fn ages_are_rescaled() {
+        let max_age = Time::new::<year>(1e9);
+        let parameters = StarGenerationParameters {
+            max_age: Some(max_age),
+            ..Default::default()
+        };
+        let generated = parameters.apply_with_seed(stars(), 42);
+        for star in generated.iter() {
+            if let Some(age) = star.get_age_at_epoch() {
+                assert!(age <= max_age * 1.000001);
+            }
+        }
+    }
+}
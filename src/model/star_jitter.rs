@@ -0,0 +1,61 @@
+use astro_coords::cartesian::Cartesian;
+use astro_utils::stars::data::StarData;
+use serde::{Deserialize, Serialize};
+use uom::si::{angle::radian, f64::Angle};
+
+use super::{body_id::BodyId, galactic_motion::standard_normal, star_generation::SplitMix64};
+
+/*
+ * Random perturbations of the distant stars, for turning a real catalog into an alternate universe.
+ * The amplitudes are standard deviations: The direction is tilted by the given angle, the distance
+ * scaled by the given fraction and the brightness shifted by the given number of magnitudes.
+ * Each star draws from its own generator, derived from the seed and its ID, so that the same seed
+ * perturbs a star the same way regardless of the order or number of the other stars.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct StarJitter {
+    pub(crate) seed: u64,
+    pub(crate) position_angle: Angle,
+    pub(crate) distance_fraction: f64,
+    pub(crate) magnitude: f64,
+}
+
+impl Default for StarJitter {
+    fn default() -> Self {
+        StarJitter {
+            seed: 0,
+            position_angle: Angle::new::<radian>(0.),
+            distance_fraction: 0.,
+            magnitude: 0.,
+        }
+    }
+}
+
+impl StarJitter {
+    pub(crate) fn is_identity(&self) -> bool {
+        self.position_angle.value == 0. && self.distance_fraction == 0. && self.magnitude == 0.
+    }
+
+    pub(crate) fn apply(&self, data: &StarData, id: BodyId) -> StarData {
+        let mut random = SplitMix64(self.seed ^ id.as_u64().wrapping_mul(0x9E3779B97F4A7C15));
+        let position = data.get_pos_at_epoch();
+        // The isotropic offset also changes the distance, but only by a fraction of the order of the angle.
+        let angular_offset = self.position_angle.get::<radian>();
+        let offset = [(); 3].map(|_| angular_offset * standard_normal(&mut random));
+        let radial_scale = (1. + self.distance_fraction * standard_normal(&mut random)).max(0.);
+        let distance = position.length();
+        let moved = Cartesian::new(
+            (position.x + distance * offset[0]) * radial_scale,
+            (position.y + distance * offset[1]) * radial_scale,
+            (position.z + distance * offset[2]) * radial_scale,
+        );
+        let brightness_factor = 10f64.powf(-0.4 * self.magnitude * standard_normal(&mut random));
+
+        let mut jittered = data.clone();
+        jittered.set_pos_at_epoch(moved);
+        jittered.set_luminous_intensity_at_epoch(
+            data.get_luminous_intensity_at_epoch() * brightness_factor,
+        );
+        jittered
+    }
+}
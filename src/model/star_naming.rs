@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, fmt::Display};
+
+use super::star_generation::SplitMix64;
+
+const ONSETS: [&str; 16] = [
+    "", "b", "d", "f", "g", "k", "l", "m", "n", "r", "s", "t", "v", "th", "dr", "gl",
+];
+const VOWELS: [&str; 8] = ["a", "e", "i", "o", "u", "ae", "ia", "ei"];
+const CODAS: [&str; 8] = ["", "", "", "n", "r", "l", "s", "th"];
+
+const ADJECTIVES: [&str; 16] = [
+    "Silver",
+    "Broken",
+    "Hidden",
+    "Little",
+    "Great",
+    "Burning",
+    "Pale",
+    "Wandering",
+    "Sleeping",
+    "Golden",
+    "Northern",
+    "Twin",
+    "Crowned",
+    "Weeping",
+    "Hollow",
+    "Bright",
+];
+const NOUNS: [&str; 16] = [
+    "Heron", "Lantern", "Anvil", "Harp", "Serpent", "Crown", "Ship", "Hound", "Tree", "Spear",
+    "Gate", "Swan", "Wheel", "Maiden", "Bell", "Stag",
+];
+
+/*
+ * How names are made up for stars and constellations that only come with catalog numbers or no name at all.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum NamingStyle {
+    #[default]
+    CatalogNumbers,
+    Syllables,
+    Wordlist,
+}
+
+impl NamingStyle {
+    pub(crate) const ALL: [NamingStyle; 3] = [
+        NamingStyle::CatalogNumbers,
+        NamingStyle::Syllables,
+        NamingStyle::Wordlist,
+    ];
+}
+
+impl Display for NamingStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NamingStyle::CatalogNumbers => write!(f, "Catalog Numbers"),
+            NamingStyle::Syllables => write!(f, "Syllables"),
+            NamingStyle::Wordlist => write!(f, "Wordlist"),
+        }
+    }
+}
+
+/*
+ * Names that are not worth drawing, because they carry no meaning to a reader.
+ */
+pub(crate) fn is_catalog_name(name: &str) -> bool {
+    name.is_empty() || name.starts_with("Gaia") || name.chars().all(char::is_numeric)
+}
+
+pub(crate) struct NameGenerator {
+    style: NamingStyle,
+    random: SplitMix64,
+    used: HashSet<String>,
+}
+
+impl NameGenerator {
+    /*
+     * Names that are already taken are never handed out again.
+     */
+    pub(crate) fn new(style: NamingStyle, seed: u64, taken: HashSet<String>) -> Self {
+        NameGenerator {
+            style,
+            random: SplitMix64(seed),
+            used: taken,
+        }
+    }
+
+    /*
+     * Returns None for catalog numbers, where the names are kept as they are.
+     */
+    pub(crate) fn next_name(&mut self) -> Option<String> {
+        const ATTEMPTS: usize = 100;
+        let mut attempt = 0;
+        let name = loop {
+            let mut name = match self.style {
+                NamingStyle::CatalogNumbers => return None,
+                NamingStyle::Syllables => self.syllable_name(),
+                NamingStyle::Wordlist => self.wordlist_name(),
+            };
+            if attempt >= ATTEMPTS {
+                // The combinations run out eventually, so the name is made unique by counting.
+                name = format!("{} {}", name, self.used.len() + 1);
+            }
+            if !self.used.contains(&name) && !is_catalog_name(&name) {
+                break name;
+            }
+            attempt += 1;
+        };
+        self.used.insert(name.clone());
+        Some(name)
+    }
+
+    fn pick<'a>(&mut self, options: &[&'a str]) -> &'a str {
+        let index = (self.random.next_f64() * options.len() as f64) as usize;
+        options[index.min(options.len() - 1)]
+    }
+
+    fn syllable_name(&mut self) -> String {
+        let syllable_count = 2 + (self.random.next_f64() * 2.) as usize;
+        let mut name = String::new();
+        for _ in 0..syllable_count {
+            name += self.pick(&ONSETS);
+            name += self.pick(&VOWELS);
+        }
+        name += self.pick(&CODAS);
+        let mut chars = name.chars();
+        match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => name,
+        }
+    }
+
+    fn wordlist_name(&mut self) -> String {
+        format!("{} {}", self.pick(&ADJECTIVES), self.pick(&NOUNS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catalog_names_are_recognised() {
+        assert!(is_catalog_name(""));
+        assert!(is_catalog_name("Gaia DR3 4295806720"));
+        assert!(is_catalog_name("12345"));
+        assert!(!is_catalog_name("Sirius"));
+    }
+
+    #[test]
+    fn generated_names_are_unique_and_readable() {
+        for style in [NamingStyle::Syllables, NamingStyle::Wordlist] {
+            let mut generator = NameGenerator::new(style, 42, HashSet::new());
+            let names: Vec<String> = (0..500).filter_map(|_| generator.next_name()).collect();
+            assert_eq!(names.len(), 500);
+            let unique: HashSet<&String> = names.iter().collect();
+            assert_eq!(unique.len(), names.len());
+            assert!(names.iter().all(|name| !is_catalog_name(name)));
+        }
+    }
+
+    #[test]
+    fn catalog_numbers_keep_names_as_they_are() {
+        let mut generator = NameGenerator::new(NamingStyle::CatalogNumbers, 42, HashSet::new());
+        assert_eq!(generator.next_name(), None);
+    }
+}
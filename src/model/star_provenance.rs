@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use super::celestial_system::metadata::today;
+
+/*
+ * Where a star came from. Stars from files that predate the provenance have an unknown origin.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) enum StarOrigin {
+    #[default]
+    Unknown,
+    Hardcoded,
+    GaiaFetch,
+    GaiaSimulation,
+    GaiaExport,
+    Random,
+    StarFormation,
+    Manual,
+}
+
+impl StarOrigin {
+    pub(crate) const ALL: [StarOrigin; 8] = [
+        StarOrigin::Unknown,
+        StarOrigin::Hardcoded,
+        StarOrigin::GaiaFetch,
+        StarOrigin::GaiaSimulation,
+        StarOrigin::GaiaExport,
+        StarOrigin::Random,
+        StarOrigin::StarFormation,
+        StarOrigin::Manual,
+    ];
+}
+
+impl Display for StarOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StarOrigin::Unknown => write!(f, "Unknown"),
+            StarOrigin::Hardcoded => write!(f, "Hardcoded"),
+            StarOrigin::GaiaFetch => write!(f, "Gaia Fetch"),
+            StarOrigin::GaiaSimulation => write!(f, "Gaia Simulation"),
+            StarOrigin::GaiaExport => write!(f, "Gaia Export"),
+            StarOrigin::Random => write!(f, "Random"),
+            StarOrigin::StarFormation => write!(f, "Star Formation"),
+            StarOrigin::Manual => write!(f, "Manual"),
+        }
+    }
+}
+
+/*
+ * Keeps catalogs that mix stars of several origins auditable.
+ * The date of the last edit is only set by edits through the GUI, and stays empty for stars that were never touched.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub(crate) struct StarProvenance {
+    pub(crate) origin: StarOrigin,
+    #[serde(default)]
+    pub(crate) last_edited: Option<String>,
+}
+
+impl StarProvenance {
+    pub(crate) fn new(origin: StarOrigin) -> Self {
+        StarProvenance {
+            origin,
+            last_edited: None,
+        }
+    }
+
+    pub(crate) fn mark_edited(&mut self) {
+        self.last_edited = Some(today());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn editing_keeps_the_origin() {
+        let mut provenance = StarProvenance::new(StarOrigin::GaiaFetch);
+        assert_eq!(provenance.last_edited, None);
+        provenance.mark_edited();
+        assert_eq!(provenance.last_edited, Some(today()));
+        assert_eq!(provenance.origin, StarOrigin::GaiaFetch);
+    }
+}
@@ -0,0 +1,116 @@
+use astro_coords::cartesian::Cartesian;
+use std::fmt::Display;
+use uom::si::{
+    f64::{Length, Time, Velocity},
+    velocity::meter_per_second,
+};
+
+const SPEED_OF_LIGHT: f64 = 299_792_458.;
+
+/*
+ * A place that can be travelled to, e.g. a planet, the central body or another system in the universe.
+ */
+#[derive(Debug, Clone)]
+pub(crate) struct TravelEndpoint {
+    pub(crate) name: String,
+    pub(crate) position: Cartesian,
+}
+
+impl PartialEq for TravelEndpoint {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Display for TravelEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name)
+    }
+}
+
+impl TravelEndpoint {
+    pub(crate) fn distance_to(&self, other: &TravelEndpoint) -> Length {
+        (self.position.clone() + -&other.position).length()
+    }
+}
+
+/*
+ * The coordinate time is measured by someone staying behind, the proper time by the travellers themselves.
+ */
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct TravelTimes {
+    pub(crate) coordinate_time: Time,
+    pub(crate) proper_time: Time,
+}
+
+pub(crate) fn speed_of_light() -> Velocity {
+    Velocity::new::<meter_per_second>(SPEED_OF_LIGHT)
+}
+
+pub(crate) fn signal_time(distance: Length) -> Time {
+    distance / speed_of_light()
+}
+
+/*
+ * Travelling at a constant fraction of the speed of light, neglecting acceleration.
+ */
+pub(crate) fn travel_times_at_fraction_of_c(
+    distance: Length,
+    fraction: f64,
+) -> Option<TravelTimes> {
+    if fraction <= 0. || fraction >= 1. {
+        return None;
+    }
+    let coordinate_time = distance / (speed_of_light() * fraction);
+    let proper_time = coordinate_time * (1. - fraction * fraction).sqrt();
+    Some(TravelTimes {
+        coordinate_time,
+        proper_time,
+    })
+}
+
+/*
+ * Half of the delta-v is spent accelerating and the other half braking, with a coasting phase in between.
+ * The burns are assumed to be short compared to the coasting phase.
+ */
+pub(crate) fn travel_times_with_delta_v(
+    distance: Length,
+    delta_v: Velocity,
+) -> Option<TravelTimes> {
+    let fraction = (delta_v / 2. / speed_of_light()).value;
+    travel_times_at_fraction_of_c(distance, fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use uom::si::{length::light_year, time::year, velocity::kilometer_per_second};
+
+    const ACCURACY: f64 = 1e-3;
+
+    #[test]
+    fn light_needs_a_year_per_light_year() {
+        let time = signal_time(Length::new::<light_year>(4.2));
+        assert!((time.get::<year>() - 4.2).abs() < ACCURACY);
+    }
+
+    #[test]
+    fn fast_travellers_age_less() {
+        let distance = Length::new::<light_year>(10.);
+        let times = travel_times_at_fraction_of_c(distance, 0.6).unwrap();
+        assert!((times.coordinate_time.get::<year>() - 16.667).abs() < ACCURACY);
+        assert!((times.proper_time.get::<year>() - 13.333).abs() < ACCURACY);
+    }
+
+    #[test]
+    fn delta_v_is_split_between_acceleration_and_braking() {
+        let distance = Length::new::<light_year>(1.);
+        let delta_v = 0.2 * speed_of_light();
+        let times = travel_times_with_delta_v(distance, delta_v).unwrap();
+        assert!((times.coordinate_time.get::<year>() - 10.).abs() < ACCURACY);
+        assert!(
+            travel_times_with_delta_v(distance, Velocity::new::<kilometer_per_second>(0.))
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt::Display, path::PathBuf};
+
+use super::{body_id::BodyId, celestial_system::CelestialSystem};
+
+/*
+ * Several celestial systems that are saved to the same file, e.g. a star cluster in which every star has its own planets.
+ * The active system is checked out to the gui, so its slot only holds a placeholder until it is checked back in.
+ */
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct Universe {
+    systems: Vec<CelestialSystem>,
+    active_system: usize,
+    #[serde(default)]
+    star_links: Vec<StarLink>,
+}
+
+#[derive(Serialize)]
+struct UniverseRef<'a> {
+    systems: Vec<&'a CelestialSystem>,
+    active_system: usize,
+    star_links: &'a [StarLink],
+}
+
+/*
+ * A distant star in one system that is described in more detail by another system.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+struct StarLink {
+    system: usize,
+    star: BodyId,
+    target: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SystemReference {
+    pub(crate) index: usize,
+    pub(crate) name: String,
+}
+
+impl Display for SystemReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.name.is_empty() {
+            write!(f, "System {}", self.index + 1)
+        } else {
+            write!(f, "{}", self.name)
+        }
+    }
+}
+
+impl Universe {
+    pub(crate) fn new() -> Self {
+        Universe {
+            systems: vec![CelestialSystem::empty()],
+            active_system: 0,
+            star_links: vec![],
+        }
+    }
+
+    pub(crate) fn get_active_system_index(&self) -> usize {
+        self.active_system
+    }
+
+    pub(crate) fn get_system_references(&self, active: &CelestialSystem) -> Vec<SystemReference> {
+        self.systems
+            .iter()
+            .enumerate()
+            .map(|(index, system)| {
+                let system = if index == self.active_system {
+                    active
+                } else {
+                    system
+                };
+                SystemReference {
+                    index,
+                    name: system.get_metadata().name.clone(),
+                }
+            })
+            .collect()
+    }
+
+    pub(crate) fn add_system(&mut self, system: CelestialSystem) -> usize {
+        self.systems.push(system);
+        self.systems.len() - 1
+    }
+
+    /*
+     * Stores the active system and hands out the one at index instead.
+     * If there is no such system, the active one is handed back unchanged.
+     */
+    pub(crate) fn switch_system(
+        &mut self,
+        index: usize,
+        active: CelestialSystem,
+    ) -> CelestialSystem {
+        if index >= self.systems.len() || index == self.active_system {
+            return active;
+        }
+        self.systems[self.active_system] = active;
+        self.active_system = index;
+        std::mem::replace(&mut self.systems[index], CelestialSystem::empty())
+    }
+
+    pub(crate) fn get_linked_system(&self, star: BodyId) -> Option<usize> {
+        self.star_links
+            .iter()
+            .find(|link| link.system == self.active_system && link.star == star)
+            .map(|link| link.target)
+    }
+
+    pub(crate) fn set_linked_system(&mut self, star: BodyId, target: Option<usize>) {
+        let active_system = self.active_system;
+        self.star_links
+            .retain(|link| link.system != active_system || link.star != star);
+        if let Some(target) = target {
+            if target < self.systems.len() && target != active_system {
+                self.star_links.push(StarLink {
+                    system: active_system,
+                    star,
+                    target,
+                });
+            }
+        }
+    }
+
+    /*
+     * A universe with only one system is written as a plain system file, so it stays readable by older versions.
+     */
+    pub(crate) fn write_to_file(
+        &self,
+        active: &CelestialSystem,
+        path: PathBuf,
+    ) -> Result<(), std::io::Error> {
+        if self.systems.len() == 1 {
+            return active.write_to_file(path);
+        }
+        let systems = self
+            .systems
+            .iter()
+            .enumerate()
+            .map(|(index, system)| {
+                if index == self.active_system {
+                    active
+                } else {
+                    system
+                }
+            })
+            .collect();
+        let universe = UniverseRef {
+            systems,
+            active_system: self.active_system,
+            star_links: &self.star_links,
+        };
+        let file = std::fs::File::create(path)?;
+        let writer = std::io::BufWriter::new(file);
+        serde_json::to_writer(writer, &universe)?;
+        Ok(())
+    }
+
+    /*
+     * Reads both universe files and plain system files.
+     * Returns the universe together with its checked out active system.
+     */
+    pub(crate) fn read_from_file(path: PathBuf) -> Result<(Self, CelestialSystem), std::io::Error> {
+        let file = std::fs::File::open(path)?;
+        let reader = std::io::BufReader::new(file);
+        let value: serde_json::Value = serde_json::from_reader(reader)?;
+        if value.get("systems").is_none() {
+            let mut system: CelestialSystem = serde_json::from_value(value)?;
+            system.prepare_after_loading()?;
+            return Ok((Universe::new(), system));
+        }
+        let mut universe: Universe = serde_json::from_value(value)?;
+        if universe.systems.is_empty() {
+            universe.systems.push(CelestialSystem::empty());
+        }
+        for system in universe.systems.iter_mut() {
+            system.prepare_after_loading()?;
+        }
+        universe.active_system = universe.active_system.min(universe.systems.len() - 1);
+        let active = std::mem::replace(
+            &mut universe.systems[universe.active_system],
+            CelestialSystem::empty(),
+        );
+        Ok((universe, active))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn named_system(name: &str) -> CelestialSystem {
+        let mut system = CelestialSystem::empty();
+        let mut metadata = system.get_metadata().clone();
+        metadata.name = name.to_string();
+        system.set_metadata(metadata);
+        system
+    }
+
+    #[test]
+    fn switching_systems_keeps_them_intact() {
+        let mut universe = Universe::new();
+        let second = universe.add_system(named_system("Second"));
+        let active = universe.switch_system(second, named_system("First"));
+        assert_eq!(active.get_metadata().name, "Second");
+        let active = universe.switch_system(0, active);
+        assert_eq!(active.get_metadata().name, "First");
+        let names: Vec<String> = universe
+            .get_system_references(&active)
+            .iter()
+            .map(|r| r.name.clone())
+            .collect();
+        assert_eq!(names, vec!["First", "Second"]);
+    }
+
+    #[test]
+    fn loading_clamps_the_time_of_every_system() {
+        use crate::model::celestial_system::epoch::MAX_TIME_SINCE_EPOCH_IN_YEARS;
+        use uom::si::time::year;
+
+        let mut value = serde_json::to_value(named_system("Far Future")).unwrap();
+        value["time_since_epoch"] = serde_json::json!(1e300);
+        let universe = serde_json::json!({
+            "systems": [value.clone(), value.clone()],
+            "active_system": 0,
+        });
+        let max = MAX_TIME_SINCE_EPOCH_IN_YEARS;
+        for (file, content) in [("system", value), ("universe", universe)] {
+            let path = std::env::temp_dir().join(format!("elenath_clamp_test_{}.json", file));
+            std::fs::write(&path, content.to_string()).unwrap();
+            let (universe, active) = Universe::read_from_file(path.clone()).unwrap();
+            std::fs::remove_file(path).unwrap();
+            let years = active.get_time_since_epoch().get::<year>();
+            assert!((years - max).abs() < max * 1e-9, "{}", years);
+            for system in &universe.systems {
+                assert!(system.get_time_since_epoch().get::<year>() <= max * (1. + 1e-9));
+            }
+        }
+    }
+
+    #[test]
+    fn star_links_belong_to_their_system() {
+        let mut universe = Universe::new();
+        let second = universe.add_system(named_system("Second"));
+        let star = BodyId::default().next();
+        universe.set_linked_system(star, Some(second));
+        assert_eq!(universe.get_linked_system(star), Some(second));
+
+        let active = universe.switch_system(second, named_system("First"));
+        assert_eq!(universe.get_linked_system(star), None);
+
+        universe.switch_system(0, active);
+        universe.set_linked_system(star, None);
+        assert_eq!(universe.get_linked_system(star), None);
+    }
+}
@@ -0,0 +1,123 @@
+use astro_units::illuminance::illuminance_to_apparent_magnitude;
+use uom::si::{angle::degree, length::meter, thermodynamic_temperature::kelvin};
+
+use super::{gaia_export::ecliptic_to_equatorial, star::Star};
+
+const METERS_PER_PARSEC: f64 = 3.085_677_581e16;
+
+/*
+ * Writes the stars as a VOTable, which TOPCAT and Aladin read directly.
+ * Positions are converted back to equatorial coordinates, and magnitudes are those seen from the central body.
+ * Distances and temperatures are only known for stars with physical data, the other cells stay empty.
+ */
+pub(crate) fn stars_to_votable(stars: &[&Star]) -> String {
+    let mut xml = String::from(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<VOTABLE version="1.4" xmlns="http://www.ivoa.net/xml/VOTable/v1.3">
+  <RESOURCE name="Elenath">
+    <TABLE name="stars">
+      <FIELD name="name" datatype="char" arraysize="*" ucd="meta.id;meta.main"/>
+      <FIELD name="ra" datatype="double" unit="deg" ucd="pos.eq.ra;meta.main"/>
+      <FIELD name="dec" datatype="double" unit="deg" ucd="pos.eq.dec;meta.main"/>
+      <FIELD name="mag" datatype="double" unit="mag" ucd="phot.mag"/>
+      <FIELD name="distance" datatype="double" unit="pc" ucd="pos.distance"/>
+      <FIELD name="teff" datatype="double" unit="K" ucd="phys.temperature.effective"/>
+      <FIELD name="source_id" datatype="char" arraysize="*" ucd="meta.id.cross"/>
+      <DATA>
+        <TABLEDATA>
+"#,
+    );
+    for star in stars {
+        let appearance = star.get_displayed_appearance();
+        let (ra, dec) = ecliptic_to_equatorial(appearance.get_pos());
+        let magnitude = illuminance_to_apparent_magnitude(appearance.get_illuminance());
+        let distance = star
+            .get_data()
+            .map(|data| data.get_distance_at_epoch().get::<meter>() / METERS_PER_PARSEC);
+        let temperature = star
+            .get_data()
+            .map(|data| data.get_temperature_at_epoch().get::<kelvin>());
+        let source_id = star.get_catalog_entry().map(|entry| entry.designation());
+        let cells = [
+            escape(appearance.get_name()),
+            number(Some(ra.get::<degree>())),
+            number(Some(dec.get::<degree>())),
+            number(Some(magnitude)),
+            number(distance),
+            number(temperature),
+            source_id.map(|id| escape(&id)).unwrap_or_default(),
+        ];
+        xml.push_str("          <TR>");
+        for cell in cells {
+            xml.push_str(&format!("<TD>{}</TD>", cell));
+        }
+        xml.push_str("</TR>\n");
+    }
+    xml.push_str(
+        r#"        </TABLEDATA>
+      </DATA>
+    </TABLE>
+  </RESOURCE>
+</VOTABLE>
+"#,
+    );
+    xml
+}
+
+/*
+ * Empty cells are read as null values for floating point columns.
+ */
+fn number(value: Option<f64>) -> String {
+    value
+        .filter(|value| value.is_finite())
+        .map(|value| value.to_string())
+        .unwrap_or_default()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use astro_coords::{direction::Direction, traits::*};
+    use astro_units::illuminance::apparent_magnitude_to_illuminance;
+    use astro_utils::{
+        color::srgb::sRGBColor, real_data::stars::sun, stars::appearance::StarAppearance,
+    };
+    use uom::si::{
+        f64::{Length, Time},
+        length::light_year,
+        time::year,
+    };
+
+    use super::*;
+
+    #[test]
+    fn stars_become_table_rows() {
+        let mut data = sun().to_star_data();
+        data.set_name("Sun & <friends>".to_string());
+        data.set_distance_at_epoch(Length::new::<light_year>(10.));
+        let with_data = Star::from_data(data, None, Time::new::<year>(0.));
+        let appearance = StarAppearance::new(
+            "Appearance".to_string(),
+            apparent_magnitude_to_illuminance(5.),
+            sRGBColor::from_sRGB(1., 1., 1.),
+            Direction::X.to_ecliptic(),
+            Time::new::<year>(0.),
+        );
+        let without_data = Star::from_appearance(appearance, None);
+
+        let votable = stars_to_votable(&[&with_data, &without_data]);
+        assert_eq!(votable.matches("<TR>").count(), 2);
+        assert!(votable.contains("<TD>Sun &amp; &lt;friends&gt;</TD>"));
+        let last_row = votable
+            .lines()
+            .find(|line| line.contains("Appearance"))
+            .unwrap();
+        assert!(last_row.ends_with("</TD><TD></TD><TD></TD><TD></TD></TR>"));
+    }
+}
@@ -0,0 +1,84 @@
+use std::path::{Component, Path, PathBuf};
+
+/*
+ * The project folder is the folder of the opened save file.
+ * Exported charts, screenshots and backdrops are meant to live next to the save file, and files that refer to each other within the folder do so by relative paths.
+ * This way the folder can be moved, zipped or shared as a whole.
+ */
+pub(crate) fn of_file(file: &Path) -> Option<&Path> {
+    file.parent()
+        .filter(|folder| !folder.as_os_str().is_empty())
+}
+
+/*
+ * Paths outside of the folder stay as they are, because there is no telling where they end up on another machine.
+ */
+pub(crate) fn to_relative(path: &Path, folder: &Path) -> PathBuf {
+    match path.strip_prefix(folder) {
+        Ok(relative) => relative.to_path_buf(),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+/*
+ * Whether a path, taken relative to the project folder, cannot lead out of it.
+ */
+pub(crate) fn stays_inside(path: &Path) -> bool {
+    path.components().next().is_some()
+        && path
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+}
+
+pub(crate) fn to_absolute(path: &Path, folder: &Path) -> PathBuf {
+    if path.is_relative() {
+        folder.join(path)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paths_inside_the_folder_survive_a_round_trip() {
+        let folder = Path::new("/worlds/arda");
+        let image = Path::new("/worlds/arda/backdrops/milky_way.ppm");
+        let relative = to_relative(image, folder);
+        assert_eq!(relative, Path::new("backdrops/milky_way.ppm"));
+        assert_eq!(to_absolute(&relative, folder), image);
+
+        let moved = Path::new("/shared/arda");
+        assert_eq!(
+            to_absolute(&relative, moved),
+            Path::new("/shared/arda/backdrops/milky_way.ppm")
+        );
+    }
+
+    #[test]
+    fn paths_outside_the_folder_stay_absolute() {
+        let folder = Path::new("/worlds/arda");
+        let image = Path::new("/pictures/milky_way.ppm");
+        assert_eq!(to_relative(image, folder), image);
+        assert_eq!(to_absolute(image, folder), image);
+    }
+
+    #[test]
+    fn only_plain_relative_paths_stay_inside() {
+        assert!(stays_inside(Path::new("frames/frame.png")));
+        assert!(!stays_inside(Path::new("/frames/frame.png")));
+        assert!(!stays_inside(Path::new("frames/../../frame.png")));
+        assert!(!stays_inside(Path::new("")));
+    }
+
+    #[test]
+    fn a_bare_file_name_has_no_project_folder() {
+        assert_eq!(of_file(Path::new("system.json")), None);
+        assert_eq!(
+            of_file(Path::new("/worlds/arda/system.json")),
+            Some(Path::new("/worlds/arda"))
+        );
+    }
+}
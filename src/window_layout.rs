@@ -0,0 +1,166 @@
+use iced::{window, Point, Size};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use crate::config;
+
+/*
+ * The size, position and maximized state of the main window, remembered across sessions.
+ * The position also decides on which monitor the window opens.
+ * The windowing library cannot list the connected monitors, so the size of the monitor under the saved position stands in for it.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub(crate) struct WindowLayout {
+    pub(crate) width: f32,
+    pub(crate) height: f32,
+    pub(crate) position: Option<(f32, f32)>,
+    #[serde(default)]
+    pub(crate) monitor: Option<(f32, f32)>,
+    pub(crate) maximized: bool,
+    #[serde(default)]
+    pub(crate) planetarium_monitor: PlanetariumMonitor,
+}
+
+/*
+ * The planetarium goes fullscreen on the monitor the window is on, or on one of its neighbours.
+ * The window is shifted by the width of its monitor, which reaches the neighbour in the usual side by side arrangement.
+ */
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub(crate) enum PlanetariumMonitor {
+    #[default]
+    Current,
+    Left,
+    Right,
+}
+
+impl PlanetariumMonitor {
+    pub(crate) const ALL: [PlanetariumMonitor; 3] = [
+        PlanetariumMonitor::Current,
+        PlanetariumMonitor::Left,
+        PlanetariumMonitor::Right,
+    ];
+
+    pub(crate) fn window_position(&self, position: Point, monitor: Size) -> Point {
+        match self {
+            PlanetariumMonitor::Current => position,
+            PlanetariumMonitor::Left => Point::new(position.x - monitor.width, position.y),
+            PlanetariumMonitor::Right => Point::new(position.x + monitor.width, position.y),
+        }
+    }
+}
+
+impl Display for PlanetariumMonitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlanetariumMonitor::Current => write!(f, "This Monitor"),
+            PlanetariumMonitor::Left => write!(f, "Monitor to the Left"),
+            PlanetariumMonitor::Right => write!(f, "Monitor to the Right"),
+        }
+    }
+}
+
+impl Default for WindowLayout {
+    fn default() -> Self {
+        WindowLayout {
+            width: 1820.,
+            height: 980.,
+            position: None,
+            monitor: None,
+            maximized: false,
+            planetarium_monitor: PlanetariumMonitor::default(),
+        }
+    }
+}
+
+const FILE_NAME: &str = "window_layout.json";
+
+impl WindowLayout {
+    pub(crate) fn load() -> WindowLayout {
+        config::load(FILE_NAME)
+    }
+
+    pub(crate) fn save(&self) -> Result<(), std::io::Error> {
+        config::save(FILE_NAME, self)
+    }
+
+    pub(crate) fn to_settings(self) -> window::Settings {
+        let position = match self.position {
+            Some((x, y)) => window::Position::Specific(Point::new(x, y)),
+            None => window::Position::default(),
+        };
+        window::Settings {
+            size: Size::new(self.width, self.height),
+            position,
+            maximized: self.maximized,
+            ..Default::default()
+        }
+    }
+
+    /*
+     * Whether the window, opened at the saved position, is on the monitor it was saved on.
+     * Off all monitors, some platforms report no monitor at all, and others the nearest one, which differs in size unless it is a twin.
+     */
+    pub(crate) fn is_on_saved_monitor(&self, monitor: Option<Size>) -> bool {
+        const TOLERANCE: f32 = 0.5;
+        match (self.position, self.monitor, monitor) {
+            (None, _, _) => true,
+            (Some(_), _, None) => false,
+            (Some(_), None, Some(_)) => true,
+            (Some(_), Some((width, height)), Some(monitor)) => {
+                (monitor.width - width).abs() < TOLERANCE
+                    && (monitor.height - height).abs() < TOLERANCE
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout_survives_serialization() {
+        let layout = WindowLayout {
+            width: 1280.,
+            height: 720.,
+            position: Some((-1920., 0.)),
+            monitor: Some((1920., 1080.)),
+            maximized: true,
+            planetarium_monitor: PlanetariumMonitor::Left,
+        };
+        let serialized = serde_json::to_string(&layout).unwrap();
+        let deserialized: WindowLayout = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(layout, deserialized);
+    }
+
+    #[test]
+    fn windows_off_their_saved_monitor_are_detected() {
+        let layout = WindowLayout {
+            position: Some((-1920., 0.)),
+            monitor: Some((1920., 1080.)),
+            ..WindowLayout::default()
+        };
+        assert!(layout.is_on_saved_monitor(Some(Size::new(1920., 1080.))));
+        assert!(!layout.is_on_saved_monitor(Some(Size::new(2560., 1440.))));
+        assert!(!layout.is_on_saved_monitor(None));
+        assert!(WindowLayout::default().is_on_saved_monitor(None));
+    }
+
+    #[test]
+    fn planetarium_moves_by_one_monitor_width() {
+        let position = Point::new(100., 50.);
+        let monitor = Size::new(1920., 1080.);
+        assert_eq!(
+            PlanetariumMonitor::Current.window_position(position, monitor),
+            position
+        );
+        assert_eq!(
+            PlanetariumMonitor::Left.window_position(position, monitor),
+            Point::new(-1820., 50.)
+        );
+        assert_eq!(
+            PlanetariumMonitor::Right.window_position(position, monitor),
+            Point::new(2020., 50.)
+        );
+    }
+}